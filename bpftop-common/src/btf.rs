@@ -0,0 +1,330 @@
+//! Minimal reader for raw kernel BTF (/sys/kernel/btf/vmlinux): enough to
+//! resolve member paths like `rss_stat[1].count` to byte offsets. Shared by
+//! the loader, which checks the compiled-in offsets against the running
+//! kernel, and `cargo xtask gen-offsets`, which writes them.
+
+use anyhow::{bail, Context, Result};
+
+const BTF_MAGIC: u16 = 0xeb9f;
+
+const KIND_INT: u32 = 1;
+const KIND_PTR: u32 = 2;
+const KIND_ARRAY: u32 = 3;
+const KIND_STRUCT: u32 = 4;
+const KIND_UNION: u32 = 5;
+const KIND_ENUM: u32 = 6;
+const KIND_TYPEDEF: u32 = 8;
+const KIND_VOLATILE: u32 = 9;
+const KIND_CONST: u32 = 10;
+const KIND_RESTRICT: u32 = 11;
+const KIND_FLOAT: u32 = 16;
+const KIND_TYPE_TAG: u32 = 18;
+const KIND_ENUM64: u32 = 19;
+
+struct Member {
+    name_off: u32,
+    type_id: u32,
+    /// Offset in bits from the start of the enclosing struct.
+    bit_offset: u32,
+}
+
+struct Type {
+    name_off: u32,
+    kind: u32,
+    /// Byte size for ints, enums, floats, structs and unions; referenced
+    /// type for pointers, typedefs and qualifiers.
+    size_or_type: u32,
+    /// Struct/union members.
+    members: Vec<Member>,
+    /// Array element type and length.
+    array: Option<(u32, u32)>,
+}
+
+pub struct Btf {
+    /// Indexed by type ID; ID 0 is void.
+    types: Vec<Type>,
+    strings: Vec<u8>,
+}
+
+fn u16_at(data: &[u8], off: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(off..off + 2)?.try_into().ok()?))
+}
+
+fn u32_at(data: &[u8], off: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(off..off + 4)?.try_into().ok()?))
+}
+
+impl Btf {
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        // Native byte order; both supported architectures are little-endian
+        let magic = u16_at(data, 0).context("truncated header")?;
+        if magic != BTF_MAGIC {
+            bail!("bad BTF magic {magic:#x}");
+        }
+        let field = |off| u32_at(data, off).context("truncated header");
+        let hdr_len = field(4)? as usize;
+        let type_off = hdr_len + field(8)? as usize;
+        let type_len = field(12)? as usize;
+        let str_off = hdr_len + field(16)? as usize;
+        let str_len = field(20)? as usize;
+        let strings = data
+            .get(str_off..str_off + str_len)
+            .context("string section out of bounds")?
+            .to_vec();
+        let types_data = data
+            .get(type_off..type_off + type_len)
+            .context("type section out of bounds")?;
+
+        let mut types = vec![Type {
+            name_off: 0,
+            kind: 0,
+            size_or_type: 0,
+            members: Vec::new(),
+            array: None,
+        }];
+        let mut pos = 0;
+        while pos < types_data.len() {
+            let word = |at: usize| u32_at(types_data, at).context("truncated type");
+            let name_off = word(pos)?;
+            let info = word(pos + 4)?;
+            let size_or_type = word(pos + 8)?;
+            let vlen = (info & 0xffff) as usize;
+            let kind = (info >> 24) & 0x1f;
+            let kind_flag = info >> 31 == 1;
+            pos += 12;
+
+            let mut members = Vec::new();
+            let mut array = None;
+            pos += match kind {
+                KIND_STRUCT | KIND_UNION => {
+                    for m in 0..vlen {
+                        let at = pos + m * 12;
+                        let raw_offset = word(at + 8)?;
+                        members.push(Member {
+                            name_off: word(at)?,
+                            type_id: word(at + 4)?,
+                            // With kind_flag the top byte holds a bitfield size
+                            bit_offset: if kind_flag { raw_offset & 0xff_ffff } else { raw_offset },
+                        });
+                    }
+                    vlen * 12
+                }
+                KIND_ARRAY => {
+                    array = Some((word(pos)?, word(pos + 8)?));
+                    12
+                }
+                // INT, VAR and DECL_TAG carry one extra u32
+                KIND_INT | 14 | 17 => 4,
+                // ENUM and FUNC_PROTO: 8-byte entries
+                KIND_ENUM | 13 => vlen * 8,
+                // DATASEC and ENUM64: 12-byte entries
+                15 | KIND_ENUM64 => vlen * 12,
+                // PTR, FWD, TYPEDEF, qualifiers, FUNC, FLOAT, TYPE_TAG
+                KIND_PTR | 7..=12 | KIND_FLOAT | KIND_TYPE_TAG => 0,
+                other => bail!("unknown BTF kind {other}"),
+            };
+            types.push(Type {
+                name_off,
+                kind,
+                size_or_type,
+                members,
+                array,
+            });
+        }
+        Ok(Self { types, strings })
+    }
+
+    fn name(&self, off: u32) -> &[u8] {
+        let rest = self.strings.get(off as usize..).unwrap_or_default();
+        let end = rest.iter().position(|&b| b == 0).unwrap_or(rest.len());
+        &rest[..end]
+    }
+
+    /// ID of the (complete) struct called `name`.
+    pub fn struct_id(&self, name: &str) -> Option<usize> {
+        self.types.iter().position(|t| {
+            t.kind == KIND_STRUCT && !t.members.is_empty() && self.name(t.name_off) == name.as_bytes()
+        })
+    }
+
+    /// Follow typedefs and qualifiers to the underlying type's ID.
+    fn resolve(&self, mut id: usize) -> Option<usize> {
+        loop {
+            let t = self.types.get(id)?;
+            match t.kind {
+                KIND_TYPEDEF | KIND_VOLATILE | KIND_CONST | KIND_RESTRICT | KIND_TYPE_TAG => {
+                    id = t.size_or_type as usize
+                }
+                _ => return Some(id),
+            }
+        }
+    }
+
+    /// Size in bytes of type `id` (pointers are 64-bit).
+    fn size_of(&self, id: usize) -> Option<u64> {
+        let t = &self.types[self.resolve(id)?];
+        match t.kind {
+            KIND_PTR => Some(8),
+            KIND_ARRAY => {
+                let (elem, len) = t.array?;
+                Some(self.size_of(elem as usize)? * len as u64)
+            }
+            KIND_INT | KIND_STRUCT | KIND_UNION | KIND_ENUM | KIND_ENUM64 | KIND_FLOAT => {
+                Some(t.size_or_type as u64)
+            }
+            _ => None,
+        }
+    }
+
+    /// Byte offset and type of member `name` of struct/union `id`, looking
+    /// through anonymous struct/union members.
+    fn member(&self, id: usize, name: &str) -> Option<(u64, usize)> {
+        let t = self.types.get(id)?;
+        if let Some(m) = t.members.iter().find(|m| self.name(m.name_off) == name.as_bytes()) {
+            return Some((m.bit_offset as u64 / 8, m.type_id as usize));
+        }
+        t.members.iter().filter(|m| m.name_off == 0).find_map(|m| {
+            let inner = self.resolve(m.type_id as usize)?;
+            if !matches!(self.types[inner].kind, KIND_STRUCT | KIND_UNION) {
+                return None;
+            }
+            let (off, ty) = self.member(inner, name)?;
+            Some((m.bit_offset as u64 / 8 + off, ty))
+        })
+    }
+
+    /// Byte offset of a member path such as `numbers[0].nr` within struct
+    /// `id`.
+    pub fn offset_of(&self, id: usize, path: &str) -> Result<u64> {
+        let mut ty = id;
+        let mut offset = 0;
+        for segment in path.split('.') {
+            let (name, index) = match segment.split_once('[') {
+                Some((name, rest)) => {
+                    let idx: u64 = rest
+                        .strip_suffix(']')
+                        .and_then(|i| i.parse().ok())
+                        .with_context(|| format!("bad index in {segment}"))?;
+                    (name, Some(idx))
+                }
+                None => (segment, None),
+            };
+            let (member_off, member_ty) = self
+                .member(ty, name)
+                .with_context(|| format!("no member {name}"))?;
+            offset += member_off;
+            ty = self.resolve(member_ty).context("dangling type")?;
+            if let Some(idx) = index {
+                let (elem, _) = self.types[ty]
+                    .array
+                    .with_context(|| format!("{name} is not an array"))?;
+                let elem_size = self
+                    .size_of(elem as usize)
+                    .with_context(|| format!("unsized element in {name}"))?;
+                offset += idx * elem_size;
+                ty = self.resolve(elem as usize).context("dangling type")?;
+            }
+        }
+        Ok(offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Raw BTF with the given type section (u32 words) and string table.
+    fn btf_blob(types: &[u32], strings: &[u8]) -> Vec<u8> {
+        let type_len = types.len() as u32 * 4;
+        let mut data = Vec::new();
+        data.extend_from_slice(&BTF_MAGIC.to_le_bytes());
+        data.extend_from_slice(&[1, 0]); // version, flags
+        for word in [24, 0, type_len, type_len, strings.len() as u32] {
+            data.extend_from_slice(&word.to_le_bytes());
+        }
+        data.extend(types.iter().flat_map(|w| w.to_le_bytes()));
+        data.extend_from_slice(strings);
+        data
+    }
+
+    fn info(kind: u32, vlen: u32, kind_flag: bool) -> u32 {
+        (kind_flag as u32) << 31 | kind << 24 | vlen
+    }
+
+    const STRINGS: &[u8] = b"\0int\0pid_t\0task_struct\0pid\0flags\0start_time\0real_start\0rss\0";
+    const INT: u32 = 1;
+    const PID_T: u32 = 5;
+    const TASK_STRUCT: u32 = 11;
+    const PID: u32 = 23;
+    const FLAGS: u32 = 27;
+    const START_TIME: u32 = 33;
+    const REAL_START: u32 = 44;
+    const RSS: u32 = 55;
+
+    /// A task_struct declared once empty and once for real, with a
+    /// bitfield, a typedef'd member, two fields in an anonymous union and
+    /// an array.
+    fn task_btf() -> Vec<u8> {
+        btf_blob(
+            &[
+                // 1: int
+                INT, info(KIND_INT, 0, false), 4, 32,
+                // 2: typedef int pid_t
+                PID_T, info(KIND_TYPEDEF, 0, false), 1,
+                // 3: struct task_struct; (no members)
+                TASK_STRUCT, info(KIND_STRUCT, 0, false), 0,
+                // 4: union { int start_time; pid_t real_start; }
+                0, info(KIND_UNION, 2, false), 4,
+                START_TIME, 1, 0,
+                REAL_START, 2, 0,
+                // 5: const union (4), to look through
+                0, info(KIND_CONST, 0, false), 4,
+                // 6: pid_t[4]
+                0, info(KIND_ARRAY, 0, false), 0, 2, 1, 4,
+                // 7: struct task_struct {
+                //     unsigned flags:4 @4; pid_t pid @8; union @16; pid_t rss[4] @24;
+                // }
+                TASK_STRUCT, info(KIND_STRUCT, 4, true), 40,
+                FLAGS, 1, 4 << 24 | 32,
+                PID, 2, 64,
+                0, 5, 128,
+                RSS, 6, 192,
+            ],
+            STRINGS,
+        )
+    }
+
+    #[test]
+    fn resolves_member_paths() {
+        let btf = Btf::parse(&task_btf()).unwrap();
+        // The empty declaration is skipped for the definition
+        let task = btf.struct_id("task_struct").unwrap();
+        assert_eq!(task, 7);
+        assert_eq!(btf.struct_id("mm_struct"), None);
+
+        assert_eq!(btf.offset_of(task, "flags").unwrap(), 4);
+        assert_eq!(btf.offset_of(task, "pid").unwrap(), 8);
+        assert_eq!(btf.offset_of(task, "start_time").unwrap(), 16);
+        assert_eq!(btf.offset_of(task, "real_start").unwrap(), 16);
+        assert_eq!(btf.offset_of(task, "rss").unwrap(), 24);
+        assert_eq!(btf.offset_of(task, "rss[3]").unwrap(), 36);
+        assert_eq!(btf.size_of(6), Some(16));
+        assert!(btf.offset_of(task, "comm").is_err());
+        assert!(btf.offset_of(task, "pid[1]").is_err());
+        assert!(btf.offset_of(task, "rss[x]").is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_btf() {
+        let mut data = task_btf();
+        data[0] = 0;
+        assert!(Btf::parse(&data).is_err());
+
+        let unknown_kind = btf_blob(&[0, info(31, 0, false), 0], b"\0");
+        assert!(Btf::parse(&unknown_kind).is_err());
+
+        // A struct claiming more members than the section holds
+        let truncated = btf_blob(&[TASK_STRUCT, info(KIND_STRUCT, 2, false), 8, PID, 1], STRINGS);
+        assert!(Btf::parse(&truncated).is_err());
+    }
+}
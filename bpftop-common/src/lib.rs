@@ -1,4 +1,7 @@
-#![cfg_attr(not(feature = "userspace"), no_std)]
+#![cfg_attr(not(any(feature = "userspace", feature = "btf")), no_std)]
+
+#[cfg(feature = "btf")]
+pub mod btf;
 
 /// Process/task information collected by the eBPF task iterator.
 /// This struct is written by the eBPF program and read by userspace.
@@ -12,13 +15,19 @@ pub struct TaskInfo {
     pub tid: u32,
     /// Parent process ID
     pub ppid: u32,
+    /// Process group ID
+    pub pgid: u32,
+    /// Session ID
+    pub sid: u32,
     /// Effective user ID
     pub euid: u32,
     /// Real user ID
     pub ruid: u32,
+    /// Per-task flags (task->flags, PF_* bits)
+    pub flags: u32,
     /// Process state (0=running, 1=sleeping, 2=disk_sleep, 4=zombie, 8=stopped)
     pub state: u8,
-    pub _pad: [u8; 3],
+    pub _pad: [u8; 7],
     /// User CPU time in nanoseconds
     pub utime_ns: u64,
     /// System CPU time in nanoseconds
@@ -39,10 +48,72 @@ pub struct TaskInfo {
     pub shmem_pages: u64,
     /// Cgroup inode ID for container detection
     pub cgroup_id: u64,
+    /// Voluntary context switches of this thread (task->nvcsw)
+    pub nvcsw: u64,
+    /// Involuntary context switches of this thread (task->nivcsw)
+    pub nivcsw: u64,
+}
+
+/// `TaskInfo.flags` bit set on kernel threads.
+pub const PF_KTHREAD: u32 = 0x0020_0000;
+
+/// Byte offsets of the task_struct fields the eBPF programs read.
+/// Compiled in per arch/kernel as the `TASK_OFFSETS` global; the loader
+/// overwrites it with the running kernel's layout from BTF.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "userspace", derive(Debug))]
+pub struct TaskOffsets {
+    pub state: u32,
+    pub flags: u32,
+    pub prio: u32,
+    pub static_prio: u32,
+    pub mm: u32,
+    pub pid: u32,
+    pub tgid: u32,
+    pub real_parent: u32,
+    pub utime: u32,
+    pub stime: u32,
+    pub nvcsw: u32,
+    pub nivcsw: u32,
+    pub start_time: u32,
+    pub cred: u32,
+    pub comm: u32,
+    pub cgroups: u32,
+    pub signal: u32,
+}
+
+/// Byte offsets of the msghdr and iov_iter fields the DNS kprobes read.
+/// Compiled in as the `MSG_OFFSETS` global; the loader overwrites it from
+/// BTF, or leaves DNS off where iov_iter predates the 6.4 layout.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "userspace", derive(Debug))]
+pub struct MsgOffsets {
+    /// msghdr.msg_iter
+    pub msg_iter: u32,
+    /// iov_iter.iter_type
+    pub iter_type: u32,
+    /// iov_iter.ubuf / __iov (a union)
+    pub iter_ptr: u32,
+    /// iov_iter.count
+    pub iter_count: u32,
+}
+
+/// CMDLINE_MAP key. The start time tells a reused PID apart from the
+/// process that held it before, so a stale cmdline is never shown for it.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "userspace", derive(Debug))]
+pub struct CmdlineKey {
+    pub pid: u32,
+    pub _pad: u32,
+    /// task_struct.start_time of the thread group leader (boot-relative ns)
+    pub start_time_ns: u64,
 }
 
 /// Command line event captured by sched_process_exec tracepoint.
-/// Stored in CMDLINE_MAP keyed by PID.
+/// Stored in CMDLINE_MAP keyed by [`CmdlineKey`].
 #[repr(C)]
 #[derive(Clone, Copy)]
 #[cfg_attr(feature = "userspace", derive(Debug))]
@@ -52,7 +123,99 @@ pub struct CmdlineEvent {
     pub cmdline: [u8; 256],
 }
 
-/// Per-process network I/O stats collected by kprobes on tcp/udp send/recv.
+/// Exec event pushed to the EXEC_EVENTS ring buffer by the sched_process_exec
+/// tracepoint, so processes that exit between refreshes are still seen.
+#[repr(C)]
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "userspace", derive(Debug))]
+pub struct ExecEvent {
+    /// Boot-relative timestamp in nanoseconds (bpf_ktime_get_boot_ns)
+    pub timestamp_ns: u64,
+    pub pid: u32,
+    pub ppid: u32,
+    /// Real user ID
+    pub uid: u32,
+    /// Bytes of `cmdline` in use
+    pub len: u32,
+    /// Process name (comm) after exec, null-terminated
+    pub comm: [u8; 16],
+    /// NUL-separated argv, truncated
+    pub cmdline: [u8; 256],
+}
+
+/// Exit event pushed to the EXIT_EVENTS ring buffer when a thread group
+/// leader exits.
+#[repr(C)]
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "userspace", derive(Debug))]
+pub struct ExitEvent {
+    /// Boot-relative timestamp in nanoseconds (bpf_ktime_get_boot_ns)
+    pub timestamp_ns: u64,
+    /// Time from fork to exit in nanoseconds
+    pub lifetime_ns: u64,
+    pub pid: u32,
+    pub ppid: u32,
+    /// Code passed to do_exit, in wait(2) status encoding
+    pub exit_code: u32,
+    pub _pad: u32,
+    /// Process name (comm), null-terminated
+    pub comm: [u8; 16],
+}
+
+/// OOM kill event pushed to the OOM_EVENTS ring buffer by the
+/// oom:mark_victim tracepoint.
+#[repr(C)]
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "userspace", derive(Debug))]
+pub struct OomEvent {
+    /// Boot-relative timestamp in nanoseconds (bpf_ktime_get_boot_ns)
+    pub timestamp_ns: u64,
+    /// Victim's anon + file + shmem RSS in KiB, 0 if the kernel doesn't say
+    pub rss_kb: u64,
+    /// Victim PID (the thread the OOM killer picked)
+    pub pid: u32,
+    /// Thread group of the task whose allocation invoked the OOM killer
+    pub trigger_pid: u32,
+    /// Victim's comm, empty if the kernel doesn't record it
+    pub comm: [u8; 16],
+    pub trigger_comm: [u8; 16],
+}
+
+/// Slots of the OOM_FIELDS array: record offsets of oom:mark_victim fields
+/// that only newer kernels have (0 = absent).
+pub const OOM_FIELD_COMM: u32 = 0;
+pub const OOM_FIELD_ANON_RSS: u32 = 1;
+pub const OOM_FIELD_FILE_RSS: u32 = 2;
+pub const OOM_FIELD_SHMEM_RSS: u32 = 3;
+pub const OOM_FIELD_SLOTS: u32 = 4;
+
+/// Bytes of a DNS query captured per event: the 12-byte header plus a
+/// question for any name up to ~240 characters.
+pub const DNS_PAYLOAD_MAX: usize = 256;
+
+/// UDP send to port 53, pushed to the DNS_EVENTS ring buffer. The query
+/// is parsed in userspace.
+#[repr(C)]
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "userspace", derive(Debug))]
+pub struct DnsEvent {
+    /// Boot-relative timestamp in nanoseconds (bpf_ktime_get_boot_ns)
+    pub timestamp_ns: u64,
+    pub pid: u32,
+    /// Bytes of `payload` filled
+    pub len: u32,
+    /// AF_INET or AF_INET6
+    pub family: u16,
+    pub _pad: [u8; 6],
+    /// Server address (IPv4 in the first 4 bytes)
+    pub daddr: [u8; 16],
+    /// Start of the UDP payload
+    pub payload: [u8; DNS_PAYLOAD_MAX],
+}
+
+/// Per-thread network I/O stats collected by kprobes on tcp/udp send/recv.
+/// The thread group leader's entry also carries the counts of threads that
+/// already exited.
 #[repr(C)]
 #[derive(Clone, Copy)]
 #[cfg_attr(feature = "userspace", derive(Debug))]
@@ -63,6 +226,150 @@ pub struct NetStats {
     pub _pad: u32,
 }
 
+/// PEER_STATS key: one process exchanging data with one remote address.
+#[repr(C)]
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "userspace", derive(Debug))]
+pub struct PeerKey {
+    pub pid: u32,
+    /// AF_INET or AF_INET6
+    pub family: u16,
+    pub _pad: u16,
+    /// Remote address (IPv4 in first 4 bytes, or full IPv6)
+    pub addr: [u8; 16],
+}
+
+/// PEER_STATS value: bytes sent to and received from one peer.
+#[repr(C)]
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "userspace", derive(Debug))]
+pub struct PeerStats {
+    pub tx_bytes: u64,
+    pub rx_bytes: u64,
+}
+
+/// SOCK_DROPS key: packets dropped on one local port.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "userspace", derive(Debug))]
+pub struct DropKey {
+    /// Network namespace inode of the socket
+    pub netns: u32,
+    /// Local port (host byte order)
+    pub port: u16,
+    /// DROP_LISTEN_OVERFLOW or DROP_RCVBUF
+    pub kind: u16,
+}
+
+/// TCP SYN or accept queue of a listener was full.
+pub const DROP_LISTEN_OVERFLOW: u16 = 0;
+/// Socket receive buffer was full (mostly UDP).
+pub const DROP_RCVBUF: u16 = 1;
+
+/// TCP_CONNS value: one TCP socket as last seen by the
+/// sock:inet_sock_set_state tracepoint. Entries go away when the socket
+/// reaches TCP_CLOSE.
+#[repr(C)]
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "userspace", derive(Debug))]
+pub struct TcpConn {
+    /// Process that called connect() or listen() on it; 0 for sockets
+    /// created by a listener, whose transitions run in softirq context
+    pub pid: u32,
+    /// Network namespace inode of the socket
+    pub netns: u32,
+    /// AF_INET or AF_INET6
+    pub family: u8,
+    /// TCP state (skc_state numbering)
+    pub state: u8,
+    /// Local port (network byte order)
+    pub src_port: u16,
+    /// Remote port (network byte order)
+    pub dst_port: u16,
+    pub _pad: u16,
+    /// Local address (IPv4 in first 4 bytes, or full IPv6)
+    pub src_addr: [u8; 16],
+    /// Remote address
+    pub dst_addr: [u8; 16],
+}
+
+/// Per-thread run-queue latency: time spent runnable but waiting for a
+/// CPU, summed over every wakeup or preemption. The thread group leader's
+/// entry also carries the waits of threads that already exited.
+#[repr(C)]
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "userspace", derive(Debug))]
+pub struct RunqLatency {
+    pub total_ns: u64,
+    /// Number of waits in `total_ns`
+    pub count: u64,
+}
+
+/// What a thread of the off-CPU target is doing; indexes `OffCpuTime::ns`.
+pub const OFFCPU_RUNNING: u32 = 0;
+/// Preempted or woken, waiting in a run queue.
+pub const OFFCPU_RUNNABLE: u32 = 1;
+/// Interruptible sleep (S).
+pub const OFFCPU_SLEEPING: u32 = 2;
+/// Uninterruptible sleep (D), usually disk I/O.
+pub const OFFCPU_BLOCKED: u32 = 3;
+pub const OFFCPU_STATES: usize = 4;
+
+/// When a thread of the off-CPU target entered its current state.
+#[repr(C)]
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "userspace", derive(Debug))]
+pub struct OffCpuStart {
+    pub since_ns: u64,
+    /// One of the `OFFCPU_*` states
+    pub state: u32,
+    pub tgid: u32,
+}
+
+/// Per-thread time of the off-CPU target since it was set, by state.
+#[repr(C)]
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "userspace", derive(Debug))]
+pub struct OffCpuTime {
+    pub ns: [u64; OFFCPU_STATES],
+    /// Process the thread belonged to when it was counted
+    pub tgid: u32,
+    pub _pad: u32,
+}
+
+/// Per-process file I/O byte counters collected by kprobes on vfs_read/vfs_write.
+#[repr(C)]
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "userspace", derive(Debug))]
+pub struct DiskStats {
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+}
+
+/// Key of the per-filesystem write counters: bytes a process wrote to
+/// files on one superblock.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "userspace", derive(Debug))]
+pub struct DevWriteKey {
+    pub pid: u32,
+    /// Kernel dev_t of the superblock (major << 20 | minor)
+    pub dev: u32,
+}
+
+/// Number of log2 buckets in `BioLatency`.
+pub const BIO_LATENCY_BUCKETS: usize = 24;
+
+/// Block I/O completion latency histogram for one process. Bucket `i`
+/// counts requests that took [2^i, 2^(i+1)) microseconds; the last bucket
+/// also takes everything slower.
+#[repr(C)]
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "userspace", derive(Debug))]
+pub struct BioLatency {
+    pub buckets: [u32; BIO_LATENCY_BUCKETS],
+}
+
 /// File descriptor information collected by the eBPF file iterator.
 #[repr(C)]
 #[derive(Clone, Copy)]
@@ -88,6 +395,12 @@ pub struct FileInfo {
     pub src_port: u16,
     /// Destination port (network byte order)
     pub dst_port: u16,
+    /// Network namespace inode of the socket (0 for non-sockets)
+    pub netns: u32,
+    /// Smoothed TCP round-trip time in microseconds (0 if not TCP or unmeasured)
+    pub srtt_us: u32,
+    /// TCP congestion window in segments (0 if not TCP)
+    pub snd_cwnd: u32,
     /// File path or unix socket path, null-terminated
     pub path: [u8; 256],
 }
@@ -95,11 +408,62 @@ pub struct FileInfo {
 #[cfg(feature = "userspace")]
 unsafe impl aya::Pod for TaskInfo {}
 
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for TaskOffsets {}
+
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for MsgOffsets {}
+
 #[cfg(feature = "userspace")]
 unsafe impl aya::Pod for FileInfo {}
 
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for CmdlineKey {}
+
 #[cfg(feature = "userspace")]
 unsafe impl aya::Pod for CmdlineEvent {}
 
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for ExecEvent {}
+
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for ExitEvent {}
+
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for OomEvent {}
+
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for DnsEvent {}
+
 #[cfg(feature = "userspace")]
 unsafe impl aya::Pod for NetStats {}
+
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for DiskStats {}
+
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for BioLatency {}
+
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for DevWriteKey {}
+
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for PeerKey {}
+
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for DropKey {}
+
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for TcpConn {}
+
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for PeerStats {}
+
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for RunqLatency {}
+
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for OffCpuStart {}
+
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for OffCpuTime {}
@@ -0,0 +1,127 @@
+use std::io;
+use std::os::unix::net::UnixDatagram;
+
+use serde::{Deserialize, Serialize};
+
+const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+const SYSLOG_SOCKET: &str = "/dev/log";
+const SYSLOG_IDENTIFIER: &str = "bpftop";
+/// syslog facility LOG_USER, pre-shifted.
+const LOG_USER: u8 = 1 << 3;
+
+/// Alert severity, mapped onto syslog priorities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum Severity {
+    Critical,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    /// syslog(3) priority level.
+    pub fn syslog_level(&self) -> u8 {
+        match self {
+            Self::Critical => 2,
+            Self::Warning => 4,
+            Self::Info => 6,
+        }
+    }
+}
+
+/// A detection worth surfacing outside the TUI.
+#[derive(Debug, Clone)]
+pub struct Alert {
+    pub severity: Severity,
+    /// Short machine-readable kind, e.g. "priv_escalation".
+    pub kind: &'static str,
+    pub message: String,
+    /// Extra structured fields. Keys are upper-case journald field names
+    /// without the `BPFTOP_` prefix (e.g. "PID").
+    pub fields: Vec<(&'static str, String)>,
+}
+
+/// Where to forward alerts (`[alerts] forward = ...` in the config).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForwardTarget {
+    #[default]
+    None,
+    Journald,
+    Syslog,
+}
+
+/// Sends alerts to journald (native protocol) or syslog (/dev/log).
+pub struct AlertForwarder {
+    target: ForwardTarget,
+    socket: UnixDatagram,
+}
+
+impl AlertForwarder {
+    /// Returns `None` when forwarding is disabled or the socket can't be created.
+    pub fn new(target: ForwardTarget) -> Option<Self> {
+        if target == ForwardTarget::None {
+            return None;
+        }
+        match UnixDatagram::unbound() {
+            Ok(socket) => Some(Self { target, socket }),
+            Err(e) => {
+                log::warn!("Alert forwarding disabled: {e}");
+                None
+            }
+        }
+    }
+
+    pub fn forward(&self, alert: &Alert) {
+        let result = match self.target {
+            ForwardTarget::Journald => self.send_journald(alert),
+            ForwardTarget::Syslog => self.send_syslog(alert),
+            ForwardTarget::None => Ok(()),
+        };
+        if let Err(e) = result {
+            log::warn!("Alert forwarding failed: {e}");
+        }
+    }
+
+    fn send_journald(&self, alert: &Alert) -> io::Result<()> {
+        let mut payload = Vec::new();
+        journald_field(&mut payload, "MESSAGE", &alert.message);
+        journald_field(&mut payload, "PRIORITY", &alert.severity.syslog_level().to_string());
+        journald_field(&mut payload, "SYSLOG_IDENTIFIER", SYSLOG_IDENTIFIER);
+        journald_field(&mut payload, "BPFTOP_ALERT", alert.kind);
+        for (key, value) in &alert.fields {
+            journald_field(&mut payload, &format!("BPFTOP_{key}"), value);
+        }
+        self.socket.send_to(&payload, JOURNALD_SOCKET).map(|_| ())
+    }
+
+    fn send_syslog(&self, alert: &Alert) -> io::Result<()> {
+        // RFC 3164 style without timestamp/hostname; the local daemon fills those in
+        let mut line = format!(
+            "<{}>{}[{}]: {} alert={}",
+            LOG_USER | alert.severity.syslog_level(),
+            SYSLOG_IDENTIFIER,
+            std::process::id(),
+            alert.message,
+            alert.kind,
+        );
+        for (key, value) in &alert.fields {
+            line.push_str(&format!(" {}={:?}", key.to_lowercase(), value));
+        }
+        self.socket.send_to(line.as_bytes(), SYSLOG_SOCKET).map(|_| ())
+    }
+}
+
+/// Append one field in journald's native datagram format. Values containing
+/// newlines use the length-prefixed binary form.
+fn journald_field(buf: &mut Vec<u8>, key: &str, value: &str) {
+    buf.extend_from_slice(key.as_bytes());
+    if value.contains('\n') {
+        buf.push(b'\n');
+        buf.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        buf.push(b'=');
+    }
+    buf.extend_from_slice(value.as_bytes());
+    buf.push(b'\n');
+}
@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::fs;
+use std::time::Instant;
+
+use aya::maps::loaded_maps;
+use aya::programs::loaded_programs;
+
+/// Every BPF program and map loaded on the system, for the BPF objects
+/// panel.
+#[derive(Debug, Clone, Default)]
+pub struct BpfObjects {
+    /// Costliest first.
+    pub programs: Vec<BpfProgram>,
+    /// By ID, i.e. in load order.
+    pub maps: Vec<BpfMap>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BpfProgram {
+    pub id: u32,
+    pub name: String,
+    /// Program type, e.g. "KProbe", "TracePoint".
+    pub kind: String,
+    /// Runs since run-time stats were enabled.
+    pub total_runs: u64,
+    pub runs_per_sec: f64,
+    /// Mean time per run over the cycle.
+    pub avg_ns: f64,
+    /// Share of one CPU spent in the program.
+    pub cpu_percent: f64,
+    /// Memory charged for it (instructions, JIT image).
+    pub memlock_bytes: u64,
+    /// Processes holding it open, directly or through a link.
+    pub pids: Vec<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BpfMap {
+    pub id: u32,
+    pub name: String,
+    /// Map type, e.g. "Hash", "RingBuf".
+    pub kind: String,
+    pub key_size: u32,
+    pub value_size: u32,
+    pub max_entries: u32,
+    /// From the fdinfo of a holder; `None` if nothing has it open (pinned,
+    /// or only reachable from programs).
+    pub memlock_bytes: Option<u64>,
+    pub pids: Vec<u32>,
+}
+
+/// Samples the system's BPF objects, turning the kernel's cumulative
+/// run counters into per-cycle rates.
+pub struct BpfObjectSampler {
+    /// Program ID -> (run count, run time ns) at the previous sample.
+    prev: HashMap<u32, (u64, u64)>,
+    prev_time: Instant,
+}
+
+impl BpfObjectSampler {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            prev: HashMap::new(),
+            prev_time: now,
+        }
+    }
+
+    /// Forget the previous counts, e.g. after run-time stats were off.
+    pub fn reset(&mut self) {
+        self.prev.clear();
+    }
+
+    pub fn sample(&mut self, now: Instant) -> BpfObjects {
+        let elapsed = now.duration_since(self.prev_time).as_secs_f64().max(f64::EPSILON);
+        self.prev_time = now;
+        let holders = scan_holders();
+
+        let mut counts = HashMap::new();
+        let mut programs: Vec<BpfProgram> = loaded_programs()
+            .filter_map(Result::ok)
+            .map(|info| {
+                let id = info.id();
+                let run_count = info.run_count();
+                let run_time_ns = info.run_time().as_nanos() as u64;
+                counts.insert(id, (run_count, run_time_ns));
+                // The first sample of a program only primes the deltas
+                let (runs, time_ns) = match self.prev.get(&id) {
+                    Some(&(count, time)) => {
+                        (run_count.saturating_sub(count), run_time_ns.saturating_sub(time))
+                    }
+                    None => (0, 0),
+                };
+                BpfProgram {
+                    id,
+                    name: info.name_as_str().unwrap_or("").to_string(),
+                    kind: info.program_type().map_or_else(|_| "?".to_string(), |t| format!("{t:?}")),
+                    total_runs: run_count,
+                    runs_per_sec: runs as f64 / elapsed,
+                    avg_ns: if runs > 0 { time_ns as f64 / runs as f64 } else { 0.0 },
+                    cpu_percent: time_ns as f64 / (elapsed * 1e9) * 100.0,
+                    memlock_bytes: info.memory_locked().map_or(0, u64::from),
+                    pids: holders.programs.get(&id).cloned().unwrap_or_default(),
+                }
+            })
+            .collect();
+        self.prev = counts;
+        programs.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent).then(a.id.cmp(&b.id)));
+
+        let mut maps: Vec<BpfMap> = loaded_maps()
+            .filter_map(Result::ok)
+            .map(|info| {
+                let id = info.id();
+                BpfMap {
+                    id,
+                    name: info.name_as_str().unwrap_or("").to_string(),
+                    kind: info.map_type().map_or_else(|_| "?".to_string(), |t| format!("{t:?}")),
+                    key_size: info.key_size(),
+                    value_size: info.value_size(),
+                    max_entries: info.max_entries(),
+                    memlock_bytes: holders.map_memlock.get(&id).copied(),
+                    pids: holders.maps.get(&id).cloned().unwrap_or_default(),
+                }
+            })
+            .collect();
+        maps.sort_by_key(|m| m.id);
+
+        BpfObjects { programs, maps }
+    }
+}
+
+/// Who holds which BPF objects open, from /proc/*/fd and fdinfo.
+#[derive(Default)]
+struct Holders {
+    /// Program ID -> PIDs with a program or link FD for it.
+    programs: HashMap<u32, Vec<u32>>,
+    maps: HashMap<u32, Vec<u32>>,
+    map_memlock: HashMap<u32, u64>,
+}
+
+/// The bpf syscall can list objects but not who loaded them; their FDs
+/// (and the FDs of links attaching programs) can.
+fn scan_holders() -> Holders {
+    let mut holders = Holders::default();
+    let Ok(proc_dir) = fs::read_dir("/proc") else {
+        return holders;
+    };
+    for entry in proc_dir.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(format!("/proc/{pid}/fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            let Ok(target) = fs::read_link(fd.path()) else {
+                continue;
+            };
+            let is_map = match target.to_str() {
+                Some("anon_inode:bpf-map") => true,
+                Some("anon_inode:bpf-prog" | "anon_inode:bpf_link") => false,
+                _ => continue,
+            };
+            let fdinfo = format!("/proc/{pid}/fdinfo/{}", fd.file_name().to_string_lossy());
+            let Ok(info) = fs::read_to_string(fdinfo) else {
+                continue;
+            };
+            if is_map {
+                let Some(id) = fdinfo_field(&info, "map_id") else {
+                    continue;
+                };
+                if let Some(memlock) = fdinfo_field(&info, "memlock") {
+                    holders.map_memlock.insert(id as u32, memlock);
+                }
+                push_unique(holders.maps.entry(id as u32).or_default(), pid);
+            } else if let Some(id) = fdinfo_field(&info, "prog_id") {
+                push_unique(holders.programs.entry(id as u32).or_default(), pid);
+            }
+        }
+    }
+    holders
+}
+
+/// A numeric `name:\tvalue` line of an fdinfo file.
+fn fdinfo_field(info: &str, name: &str) -> Option<u64> {
+    info.lines()
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+        .and_then(|v| v.trim().parse().ok())
+}
+
+fn push_unique(pids: &mut Vec<u32>, pid: u32) {
+    if !pids.contains(&pid) {
+        pids.push(pid);
+    }
+}
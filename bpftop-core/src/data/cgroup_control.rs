@@ -0,0 +1,188 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::system::{parse_psi_some, PsiAverages};
+
+const CGROUP_BASE: &str = "/sys/fs/cgroup";
+const BPFTOP_CGROUP_DIR: &str = "bpftop.freeze";
+const SPAWN_CGROUP_DIR: &str = "bpftop.spawn";
+
+/// cpu.max period; quotas are expressed against it.
+const CPU_PERIOD_US: u64 = 100_000;
+
+const PERMS_HINT: &str =
+    "insufficient permissions: add cap_dac_override,cap_sys_admin or run as root";
+
+/// Wrap an io::Result, replacing PermissionDenied with a helpful message.
+fn check_perms<T>(result: io::Result<T>) -> io::Result<T> {
+    result.map_err(|e| {
+        if e.kind() == io::ErrorKind::PermissionDenied {
+            io::Error::new(io::ErrorKind::PermissionDenied, PERMS_HINT)
+        } else {
+            e
+        }
+    })
+}
+
+/// Read all PIDs from a cgroup's cgroup.procs file.
+pub fn read_cgroup_pids(cgroup_path: &str) -> io::Result<Vec<u32>> {
+    let path = format!("{CGROUP_BASE}{cgroup_path}/cgroup.procs");
+    let contents = fs::read_to_string(&path)?;
+    Ok(contents
+        .lines()
+        .filter_map(|line| line.trim().parse::<u32>().ok())
+        .collect())
+}
+
+/// Dirty page cache of a cgroup and how long its tasks stall on I/O. A
+/// heavy writer fills the dirty limit, then balance_dirty_pages() throttles
+/// it, which shows up as I/O pressure.
+#[derive(Debug, Clone, Default)]
+pub struct CgroupWriteback {
+    /// Bytes of page cache waiting to be written back.
+    pub dirty: u64,
+    /// Bytes being written back right now.
+    pub writeback: u64,
+    /// `io.pressure` of the cgroup; `None` without PSI.
+    pub io_pressure: Option<PsiAverages>,
+}
+
+/// Read a cgroup's writeback state from memory.stat and io.pressure.
+/// `None` if the memory controller isn't enabled for it (or on cgroup v1).
+pub fn read_writeback(cgroup_path: &str) -> Option<CgroupWriteback> {
+    let stat = fs::read_to_string(format!("{CGROUP_BASE}{cgroup_path}/memory.stat")).ok()?;
+    let mut wb = CgroupWriteback::default();
+    for line in stat.lines() {
+        match line.split_once(' ') {
+            Some(("file_dirty", v)) => wb.dirty = v.trim().parse().unwrap_or(0),
+            Some(("file_writeback", v)) => wb.writeback = v.trim().parse().unwrap_or(0),
+            _ => {}
+        }
+    }
+    wb.io_pressure = fs::read_to_string(format!("{CGROUP_BASE}{cgroup_path}/io.pressure"))
+        .ok()
+        .and_then(|content| parse_psi_some(&content));
+    Some(wb)
+}
+
+/// Check if a cgroup is currently frozen (reads cgroup.freeze).
+pub fn is_frozen(cgroup_path: &str) -> bool {
+    let path = format!("{CGROUP_BASE}{cgroup_path}/cgroup.freeze");
+    fs::read_to_string(&path)
+        .map(|s| s.trim() == "1")
+        .unwrap_or(false)
+}
+
+/// Freeze a cgroup (write "1" to cgroup.freeze).
+pub fn freeze_cgroup(cgroup_path: &str) -> io::Result<()> {
+    let path = format!("{CGROUP_BASE}{cgroup_path}/cgroup.freeze");
+    check_perms(fs::write(&path, "1"))
+}
+
+/// Unfreeze a cgroup (write "0" to cgroup.freeze).
+pub fn thaw_cgroup(cgroup_path: &str) -> io::Result<()> {
+    let path = format!("{CGROUP_BASE}{cgroup_path}/cgroup.freeze");
+    check_perms(fs::write(&path, "0"))
+}
+
+/// Kill every process in a cgroup and its descendants at once by writing
+/// "1" to cgroup.kill. Unlike signalling the PIDs one by one, nothing in
+/// it can fork a replacement in between.
+pub fn kill_cgroup(cgroup_path: &str) -> io::Result<()> {
+    let path = format!("{CGROUP_BASE}{cgroup_path}/cgroup.kill");
+    if !Path::new(&path).exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "no cgroup.kill (needs Linux 5.14+ with cgroup v2)",
+        ));
+    }
+    check_perms(fs::write(&path, "1"))
+}
+
+/// The cgroup v2 path bpftop itself runs in, e.g. "/user.slice/...".
+pub fn own_cgroup() -> Option<String> {
+    fs::read_to_string("/proc/self/cgroup")
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .map(|path| path.trim().to_string())
+}
+
+/// Create a bpftop-managed cgroup for a root-cgroup process and move it there.
+/// Returns the new cgroup relative path (e.g. "/bpftop.freeze/1234").
+pub fn create_and_move(pid: u32) -> io::Result<String> {
+    let dir = format!("{CGROUP_BASE}/{BPFTOP_CGROUP_DIR}/{pid}");
+    let rel_path = format!("/{BPFTOP_CGROUP_DIR}/{pid}");
+
+    // Create the cgroup directory (and parent if needed)
+    let parent = format!("{CGROUP_BASE}/{BPFTOP_CGROUP_DIR}");
+    if !Path::new(&parent).exists() {
+        check_perms(fs::create_dir(&parent))?;
+    }
+    if !Path::new(&dir).exists() {
+        check_perms(fs::create_dir(&dir))?;
+    }
+
+    // Move the process into the new cgroup
+    let procs_path = format!("{dir}/cgroup.procs");
+    check_perms(fs::write(&procs_path, pid.to_string()))?;
+
+    Ok(rel_path)
+}
+
+/// Create a cgroup for `:spawn` under /bpftop.spawn with the given limits,
+/// enabling the cpu/memory controllers it needs. `cpu_percent` is in
+/// percent of one CPU. Returns the relative path (e.g. "/bpftop.spawn/4-1").
+pub fn create_spawn_cgroup(
+    name: &str,
+    cpu_percent: Option<u32>,
+    memory_max: Option<u64>,
+) -> io::Result<String> {
+    let parent = format!("{CGROUP_BASE}/{SPAWN_CGROUP_DIR}");
+    if !Path::new(&parent).exists() {
+        check_perms(fs::create_dir(&parent))?;
+    }
+    let mut controllers = Vec::new();
+    if cpu_percent.is_some() {
+        controllers.push("+cpu");
+    }
+    if memory_max.is_some() {
+        controllers.push("+memory");
+    }
+    if !controllers.is_empty() {
+        // Fails if the root hasn't delegated the controller to its children
+        check_perms(fs::write(
+            format!("{parent}/cgroup.subtree_control"),
+            controllers.join(" "),
+        ))?;
+    }
+
+    let dir = format!("{parent}/{name}");
+    check_perms(fs::create_dir(&dir))?;
+    let limits = (|| -> io::Result<()> {
+        if let Some(pct) = cpu_percent {
+            let quota = u64::from(pct) * CPU_PERIOD_US / 100;
+            check_perms(fs::write(format!("{dir}/cpu.max"), format!("{quota} {CPU_PERIOD_US}")))?;
+        }
+        if let Some(bytes) = memory_max {
+            check_perms(fs::write(format!("{dir}/memory.max"), bytes.to_string()))?;
+        }
+        Ok(())
+    })();
+    if let Err(e) = limits {
+        let _ = fs::remove_dir(&dir);
+        return Err(e);
+    }
+    Ok(format!("/{SPAWN_CGROUP_DIR}/{name}"))
+}
+
+/// Absolute path of a cgroup's cgroup.procs file.
+pub fn procs_file(cgroup_path: &str) -> String {
+    format!("{CGROUP_BASE}{cgroup_path}/cgroup.procs")
+}
+
+/// Remove an empty cgroup. Fails (harmlessly) while tasks remain.
+pub fn remove_cgroup(cgroup_path: &str) -> io::Result<()> {
+    fs::remove_dir(format!("{CGROUP_BASE}{cgroup_path}"))
+}
@@ -0,0 +1,1824 @@
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use bpftop_common::{
+    DevWriteKey, DropKey, FileInfo, BIO_LATENCY_BUCKETS, DROP_LISTEN_OVERFLOW, DROP_RCVBUF, OFFCPU_BLOCKED,
+    OFFCPU_RUNNABLE, OFFCPU_RUNNING, OFFCPU_SLEEPING, OFFCPU_STATES, PF_KTHREAD,
+};
+
+use super::bpf_objects::{BpfObjectSampler, BpfObjects};
+use super::cgroup_control::{self, CgroupWriteback};
+use super::clock::{Clock, SystemClock};
+use super::container::CgroupResolver;
+use super::session::TtyResolver;
+use crate::alerts::{Alert, Severity};
+use super::execs::ExecRecord;
+use super::dns::{DnsLog, DnsQuery};
+use super::exits::ExitRecord;
+use super::oom::OomRecord;
+use super::perf::PerfCounters;
+use super::files::{
+    decode_addr, fdless_tcp_sockets, OpenFile, SOCK_DGRAM, SOCK_STREAM, TCP_LISTEN,
+};
+#[cfg(feature = "gpu")]
+use super::gpu::GpuCollector;
+use super::history::RssTracker;
+use super::process::{format_bytes, ProcessInfo, ProcessState};
+use super::system::*;
+use crate::ebpf::loader::{self, EbpfLoader, MapUsage, Subsystem};
+
+/// Everything produced by one collection cycle.
+pub struct Snapshot {
+    pub sys: SystemInfo,
+    pub processes: Vec<ProcessInfo>,
+    /// Per-PID details for the process the UI is inspecting, if any.
+    pub inspection: Option<Inspection>,
+    /// Detections raised during this cycle.
+    pub alerts: Vec<Alert>,
+    /// Execs since the previous cycle, oldest first.
+    pub execs: Vec<ExecRecord>,
+    /// Process exits since the previous cycle, oldest first.
+    pub exits: Vec<ExitRecord>,
+    /// OOM kills since the previous cycle, oldest first.
+    pub ooms: Vec<OomRecord>,
+    /// bpftop's own eBPF footprint, while requested via
+    /// `Collector::bpf_stats_handle`.
+    pub bpf_stats: Option<BpfStats>,
+    /// Every BPF program and map on the system, while requested via
+    /// `Collector::bpf_objects_handle`.
+    pub bpf_objects: Option<BpfObjects>,
+}
+
+/// Program overhead and map fill levels for the BPF stats panel.
+#[derive(Debug, Clone, Default)]
+pub struct BpfStats {
+    pub programs: Vec<ProgramLoad>,
+    pub maps: Vec<MapUsage>,
+}
+
+/// Detail data gathered only for the PID set via `Collector::inspect_handle`.
+#[derive(Debug, Clone, Default)]
+pub struct Inspection {
+    pub pid: u32,
+    pub files: Vec<OpenFile>,
+    /// TCP/UDP sockets of every process, for the all-processes and
+    /// per-netns connections views.
+    pub net_sockets: Vec<OpenFile>,
+    /// Every task in the thread group, leader included.
+    pub threads: Vec<ThreadInfo>,
+    /// Remote addresses with the most traffic, busiest first.
+    pub peers: Vec<Peer>,
+    /// Block I/O completion latency; `None` if it never hit a disk.
+    pub io_latency: Option<IoLatency>,
+    /// Mount points the process writes to, busiest first.
+    pub mount_writes: Vec<MountWrite>,
+    /// Dirty/writeback pages and I/O stalls of the process's cgroup.
+    pub cgroup_writeback: Option<CgroupWriteback>,
+    /// Running/runnable/sleeping split, while requested via
+    /// `Collector::offcpu_handle`.
+    pub off_cpu: Option<OffCpuBreakdown>,
+    /// Why hardware counters can't be read here, once an open failed.
+    pub perf_unavailable: Option<String>,
+    /// Recent DNS queries, newest first.
+    pub dns_queries: Vec<DnsQuery>,
+}
+
+/// Where the inspected process's threads spent their time since the
+/// off-CPU breakdown was switched on, summed over threads.
+#[derive(Debug, Clone, Copy)]
+pub struct OffCpuBreakdown {
+    pub running_ns: u64,
+    /// Waiting in a run queue for a CPU.
+    pub runnable_ns: u64,
+    /// Interruptible sleep (waiting on a lock, socket, timer...).
+    pub sleeping_ns: u64,
+    /// Uninterruptible sleep, usually disk I/O.
+    pub blocked_ns: u64,
+    pub elapsed: Duration,
+}
+
+impl OffCpuBreakdown {
+    fn new(ns: [u64; OFFCPU_STATES], elapsed: Duration) -> Self {
+        Self {
+            running_ns: ns[OFFCPU_RUNNING as usize],
+            runnable_ns: ns[OFFCPU_RUNNABLE as usize],
+            sleeping_ns: ns[OFFCPU_SLEEPING as usize],
+            blocked_ns: ns[OFFCPU_BLOCKED as usize],
+            elapsed,
+        }
+    }
+
+    pub fn total_ns(&self) -> u64 {
+        self.running_ns + self.runnable_ns + self.sleeping_ns + self.blocked_ns
+    }
+
+    /// Share of the counted time, in percent.
+    pub fn percent(&self, ns: u64) -> f64 {
+        ns as f64 * 100.0 / self.total_ns().max(1) as f64
+    }
+}
+
+/// Cost of one of bpftop's eBPF programs over the last cycle.
+#[derive(Debug, Clone)]
+pub struct ProgramLoad {
+    pub name: String,
+    /// Runs since run-time stats were enabled.
+    pub total_runs: u64,
+    pub runs_per_sec: f64,
+    /// Mean time per run over the cycle.
+    pub avg_ns: f64,
+    /// Share of one CPU spent in the program.
+    pub cpu_percent: f64,
+}
+
+/// Number of mounts kept in `Inspection::mount_writes`.
+pub const TOP_MOUNTS: usize = 5;
+
+/// Bytes the inspected process wrote to files under one mount point.
+#[derive(Debug, Clone)]
+pub struct MountWrite {
+    pub mount: String,
+    pub bytes: u64,
+    pub rate: f64,
+}
+
+/// Block I/O latency of the inspected process since bpftop started.
+/// Percentiles are the upper bound of the power-of-two histogram bucket
+/// they fall in, so they are accurate to a factor of two.
+#[derive(Debug, Clone, Copy)]
+pub struct IoLatency {
+    pub requests: u64,
+    pub p50_us: u64,
+    pub p99_us: u64,
+    /// Request counts; bucket `i` is [2^i, 2^(i+1)) microseconds.
+    pub buckets: [u32; BIO_LATENCY_BUCKETS],
+}
+
+impl IoLatency {
+    fn from_histogram(buckets: &[u32; BIO_LATENCY_BUCKETS]) -> Option<Self> {
+        let requests: u64 = buckets.iter().map(|&n| n as u64).sum();
+        if requests == 0 {
+            return None;
+        }
+        let percentile = |q: f64| {
+            let rank = (requests as f64 * q).ceil() as u64;
+            let mut seen = 0;
+            for (i, &n) in buckets.iter().enumerate() {
+                seen += n as u64;
+                if seen >= rank {
+                    return 1u64 << (i + 1);
+                }
+            }
+            1u64 << buckets.len()
+        };
+        Some(Self {
+            requests,
+            p50_us: percentile(0.50),
+            p99_us: percentile(0.99),
+            buckets: *buckets,
+        })
+    }
+}
+
+/// Number of peers kept in `Inspection::peers`.
+pub const TOP_PEERS: usize = 5;
+
+/// RSS window for the GROW column when `[alerts] leak_minutes` is 0.
+const DEFAULT_LEAK_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// Growth of the time spent suspended between refreshes that counts as a
+/// suspend and resume. The two clocks are read apart, so they jitter.
+const SUSPEND_GAP_NS: u64 = 1_000_000_000;
+
+/// How long without new throttle events before a throttling episode is
+/// over. Events come in bursts; a refresh without any is not relief.
+const THROTTLE_QUIET: Duration = Duration::from_secs(5);
+
+/// How often BPF map fill levels are counted while nobody is watching.
+const MAP_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Fill level at which a BPF map raises an alert.
+pub const MAP_FULL_PERCENT: f64 = 80.0;
+
+/// setuid-root helpers that become root as a matter of course, so their
+/// uid change is not an escalation alert. `[alerts] setuid_allowlist`.
+pub const ROUTINE_SETUID: &[&str] = &[
+    "sudo",
+    "su",
+    "doas",
+    "pkexec",
+    "passwd",
+    "chsh",
+    "chfn",
+    "gpasswd",
+    "newgrp",
+    "mount",
+    "umount",
+    "fusermount",
+    "fusermount3",
+    "unix_chkpwd",
+    "polkit-agent-he",
+    "dbus-daemon-lau",
+    "ssh-keysign",
+    "Xorg.wrap",
+];
+
+/// Bytes the inspected process exchanged with one remote address.
+#[derive(Debug, Clone)]
+pub struct Peer {
+    pub addr: IpAddr,
+    pub tx_bytes: u64,
+    pub rx_bytes: u64,
+}
+
+/// One task of the inspected process.
+#[derive(Debug, Clone)]
+pub struct ThreadInfo {
+    pub tid: u32,
+    pub comm: String,
+    pub state: ProcessState,
+    pub cpu_percent: f64,
+    pub cpu_time_secs: f64,
+}
+
+/// Orchestrates data collection from eBPF programs.
+///
+/// System-wide stats still come from /proc/stat, /proc/meminfo, etc.
+/// All per-process data comes from the BPF task iterator and CMDLINE_MAP.
+/// No per-PID /proc reads occur after startup.
+pub struct Collector {
+    ebpf: EbpfLoader,
+    /// Time for every rate and window; the system clock outside tests.
+    clock: Arc<dyn Clock>,
+    cgroup_resolver: CgroupResolver,
+    tty_resolver: TtyResolver,
+    prev_cpu_total: CpuStats,
+    prev_cpus: Vec<CpuStats>,
+    /// tid -> utime+stime, for processes (tid == pid) and threads alike.
+    prev_proc_times: HashMap<u32, u64>,
+    /// pid (process rows) or tid (thread rows) -> (tx, rx) bytes.
+    prev_net_bytes: HashMap<u32, (u64, u64)>,
+    prev_net_time: Instant,
+    prev_disk_bytes: HashMap<u32, (u64, u64)>,
+    /// tid -> (voluntary, involuntary) context switches.
+    prev_csw: HashMap<u32, (u64, u64)>,
+    /// tid -> (run-queue wait ns, number of waits).
+    prev_runq: HashMap<u32, (u64, u64)>,
+    prev_block_devices: Vec<BlockDevice>,
+    prev_rapl: Vec<RaplPackage>,
+    prev_thermal: Option<CpuThermal>,
+    /// Time suspended since boot at the previous refresh.
+    prev_suspended_ns: u64,
+    prev_net_ifaces: Vec<NetInterface>,
+    prev_sock_drops: HashMap<u32, u64>,
+    /// (pid, start time) -> parent when first seen or at exec, to tell
+    /// reparented processes apart.
+    orig_parents: HashMap<(u32, u64), u32>,
+    /// PIDs already alerted on for the drop episode still in progress.
+    dropping_pids: HashSet<u32>,
+    /// Drops/s on a process's sockets that raise an alert (0 = never).
+    drop_alert_per_sec: f64,
+    rss_tracker: RssTracker,
+    /// Whether a steady RSS climb over the tracker window raises an alert.
+    leak_alert: bool,
+    /// PIDs already alerted on for the growth episode still in progress.
+    leaking_pids: HashSet<u32>,
+    /// Seconds of continuous thermal throttling that raise an alert
+    /// (0 = never).
+    throttle_alert_secs: u64,
+    /// Start of the throttling episode in progress and its latest event.
+    throttle_since: Option<Instant>,
+    last_throttle: Option<Instant>,
+    /// Whether the episode in progress already alerted.
+    throttle_alerted: bool,
+    /// tid -> utime+stime, only for threads of the inspected PID.
+    prev_thread_times: HashMap<u32, u64>,
+    prev_dev_writes: HashMap<DevWriteKey, u64>,
+    /// Recent DNS queries of every process that sent any.
+    dns_log: DnsLog,
+    /// pid -> (start_time_ns, uid) for privilege escalation detection.
+    prev_uids: HashMap<u32, (u64, u32)>,
+    /// Commands whose switch to root doesn't alert.
+    setuid_allowlist: HashSet<String>,
+    /// (pid, start time) of processes that already raised an escalation
+    /// alert, so one flapping between uids alerts once.
+    escalated: HashSet<(u32, u64)>,
+    ifindex_cache: HashMap<u32, String>,
+    page_size: u64,
+    /// PID the UI wants detail data for (0 = none). Shared with the App.
+    inspect_pid: Arc<AtomicU32>,
+    /// Whether the UI wants per-program BPF overhead. Shared with the App.
+    bpf_stats_wanted: Arc<AtomicBool>,
+    /// Whether the UI wants the system's BPF programs and maps. Shared
+    /// with the App.
+    bpf_objects_wanted: Arc<AtomicBool>,
+    bpf_object_sampler: BpfObjectSampler,
+    perf: PerfCounters,
+    /// Busiest processes to keep hardware counters open for.
+    perf_top: usize,
+    /// Whether the UI wants hardware counters for the inspected PID.
+    /// Shared with the App.
+    perf_wanted: Arc<AtomicBool>,
+    /// Whether the UI wants the inspected PID's off-CPU breakdown. Shared
+    /// with the App.
+    offcpu_wanted: Arc<AtomicBool>,
+    /// Whether the UI shows per-process file I/O. Shared with the App.
+    file_io_wanted: Arc<AtomicBool>,
+    /// Whether the UI shows run-queue latency. Shared with the App.
+    runq_wanted: Arc<AtomicBool>,
+    /// Whether the inspected PID is shown in the detail view, which wants
+    /// more than the files and connections panels. Shared with the App.
+    detail_wanted: Arc<AtomicBool>,
+    /// Cgroups frozen with a timer, and when to thaw them. The UI schedules,
+    /// the collector thread thaws.
+    thaw_at: Arc<Mutex<HashMap<String, Instant>>>,
+    /// When the off-CPU programs started counting the current target.
+    offcpu_since: Instant,
+    /// Program name -> (run count, run time ns) at the previous sample.
+    prev_prog_stats: HashMap<String, (u64, u64)>,
+    prev_prog_time: Instant,
+    /// When map fill levels were last counted.
+    maps_checked: Option<Instant>,
+    /// Maps already alerted on for the episode still in progress.
+    full_maps: HashSet<&'static str>,
+    #[cfg(feature = "gpu")]
+    gpu_collector: Option<GpuCollector>,
+}
+
+impl Collector {
+    pub fn new(ebpf: EbpfLoader) -> Self {
+        Self::with_clock(ebpf, Arc::new(SystemClock))
+    }
+
+    /// A collector that reads time from `clock`.
+    pub fn with_clock(mut ebpf: EbpfLoader, clock: Arc<dyn Clock>) -> Self {
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) as u64 };
+
+        // One-shot /proc/*/cmdline scan to seed the BPF map for
+        // processes already running before tracepoints were attached.
+        loader::seed_cmdlines_from_proc(&mut ebpf);
+
+        // Seed CPU stats so the first real collect() gets a meaningful
+        // delta instead of computing against zero (which would show
+        // all CPUs at ~100%).
+        let (prev_cpu_total, prev_cpus) = read_cpu_stats().unwrap_or_default();
+        let now = clock.now();
+
+        Self {
+            ebpf,
+            clock,
+            cgroup_resolver: CgroupResolver::new(),
+            tty_resolver: TtyResolver::default(),
+            dns_log: DnsLog::default(),
+            prev_cpu_total,
+            prev_cpus,
+            prev_proc_times: HashMap::new(),
+            prev_net_bytes: HashMap::new(),
+            prev_net_time: now,
+            prev_disk_bytes: HashMap::new(),
+            prev_csw: HashMap::new(),
+            prev_runq: HashMap::new(),
+            prev_block_devices: read_block_devices().unwrap_or_default(),
+            prev_rapl: read_rapl_packages(),
+            prev_thermal: read_cpu_thermal(),
+            prev_suspended_ns: read_suspended_ns(),
+            prev_net_ifaces: read_net_interfaces().unwrap_or_default(),
+            prev_sock_drops: HashMap::new(),
+            orig_parents: HashMap::new(),
+            dropping_pids: HashSet::new(),
+            drop_alert_per_sec: 0.0,
+            rss_tracker: RssTracker::new(DEFAULT_LEAK_WINDOW),
+            leak_alert: false,
+            leaking_pids: HashSet::new(),
+            throttle_alert_secs: 0,
+            throttle_since: None,
+            last_throttle: None,
+            throttle_alerted: false,
+            prev_thread_times: HashMap::new(),
+            prev_dev_writes: HashMap::new(),
+            prev_uids: HashMap::new(),
+            setuid_allowlist: ROUTINE_SETUID.iter().map(|s| s.to_string()).collect(),
+            escalated: HashSet::new(),
+            ifindex_cache: HashMap::new(),
+            page_size,
+            inspect_pid: Arc::new(AtomicU32::new(0)),
+            bpf_stats_wanted: Arc::new(AtomicBool::new(false)),
+            bpf_objects_wanted: Arc::new(AtomicBool::new(false)),
+            bpf_object_sampler: BpfObjectSampler::new(now),
+            perf: PerfCounters::new(now),
+            perf_top: 0,
+            perf_wanted: Arc::new(AtomicBool::new(false)),
+            offcpu_wanted: Arc::new(AtomicBool::new(false)),
+            file_io_wanted: Arc::new(AtomicBool::new(false)),
+            runq_wanted: Arc::new(AtomicBool::new(false)),
+            detail_wanted: Arc::new(AtomicBool::new(false)),
+            thaw_at: Arc::new(Mutex::new(HashMap::new())),
+            offcpu_since: now,
+            prev_prog_stats: HashMap::new(),
+            prev_prog_time: now,
+            maps_checked: None,
+            full_maps: HashSet::new(),
+            #[cfg(feature = "gpu")]
+            gpu_collector: GpuCollector::try_new(),
+        }
+    }
+
+    /// Create a no-op collector that will never be called.
+    /// Used as a placeholder after extracting the real collector for the background thread.
+    pub fn noop() -> Self {
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+        let now = clock.now();
+        Self {
+            ebpf: EbpfLoader::noop(),
+            clock,
+            cgroup_resolver: CgroupResolver::new(),
+            tty_resolver: TtyResolver::default(),
+            dns_log: DnsLog::default(),
+            prev_cpu_total: CpuStats::default(),
+            prev_cpus: Vec::new(),
+            prev_proc_times: HashMap::new(),
+            prev_net_bytes: HashMap::new(),
+            prev_net_time: now,
+            prev_disk_bytes: HashMap::new(),
+            prev_csw: HashMap::new(),
+            prev_runq: HashMap::new(),
+            prev_block_devices: Vec::new(),
+            prev_rapl: Vec::new(),
+            prev_thermal: None,
+            prev_suspended_ns: 0,
+            prev_net_ifaces: Vec::new(),
+            prev_sock_drops: HashMap::new(),
+            orig_parents: HashMap::new(),
+            dropping_pids: HashSet::new(),
+            drop_alert_per_sec: 0.0,
+            rss_tracker: RssTracker::new(DEFAULT_LEAK_WINDOW),
+            leak_alert: false,
+            leaking_pids: HashSet::new(),
+            throttle_alert_secs: 0,
+            throttle_since: None,
+            last_throttle: None,
+            throttle_alerted: false,
+            prev_thread_times: HashMap::new(),
+            prev_dev_writes: HashMap::new(),
+            prev_uids: HashMap::new(),
+            setuid_allowlist: ROUTINE_SETUID.iter().map(|s| s.to_string()).collect(),
+            escalated: HashSet::new(),
+            ifindex_cache: HashMap::new(),
+            page_size: 4096,
+            inspect_pid: Arc::new(AtomicU32::new(0)),
+            bpf_stats_wanted: Arc::new(AtomicBool::new(false)),
+            bpf_objects_wanted: Arc::new(AtomicBool::new(false)),
+            bpf_object_sampler: BpfObjectSampler::new(now),
+            perf: PerfCounters::new(now),
+            perf_top: 0,
+            perf_wanted: Arc::new(AtomicBool::new(false)),
+            offcpu_wanted: Arc::new(AtomicBool::new(false)),
+            file_io_wanted: Arc::new(AtomicBool::new(false)),
+            runq_wanted: Arc::new(AtomicBool::new(false)),
+            detail_wanted: Arc::new(AtomicBool::new(false)),
+            thaw_at: Arc::new(Mutex::new(HashMap::new())),
+            offcpu_since: now,
+            prev_prog_stats: HashMap::new(),
+            prev_prog_time: now,
+            maps_checked: None,
+            full_maps: HashSet::new(),
+            #[cfg(feature = "gpu")]
+            gpu_collector: None,
+        }
+    }
+
+    /// Handle for selecting which PID gets detail data in each `Snapshot`.
+    /// Store 0 to stop inspecting.
+    pub fn inspect_handle(&self) -> Arc<AtomicU32> {
+        Arc::clone(&self.inspect_pid)
+    }
+
+    /// Flag the UI sets while it shows per-program BPF overhead.
+    pub fn bpf_stats_handle(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.bpf_stats_wanted)
+    }
+
+    /// Flag the UI sets while it lists the system's BPF programs and maps.
+    pub fn bpf_objects_handle(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.bpf_objects_wanted)
+    }
+
+    /// Flag the UI sets while it wants the off-CPU breakdown of the
+    /// inspected PID. The sched_switch programs are attached only then.
+    pub fn offcpu_handle(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.offcpu_wanted)
+    }
+
+    /// Flag the UI sets while it shows the DISK R/s or W/s column. The
+    /// vfs_read/vfs_write kprobes are attached only then, or while the
+    /// detail view is open.
+    pub fn file_io_handle(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.file_io_wanted)
+    }
+
+    /// Flag the UI sets while it shows the LAT column. The sched_wakeup/
+    /// sched_switch tracepoints are attached only then, or while the detail
+    /// view is open.
+    pub fn runq_handle(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.runq_wanted)
+    }
+
+    /// Flag the UI sets while the inspected PID is in the detail view.
+    /// Peers are only read then, since that walks the whole PEER_STATS map.
+    pub fn detail_handle(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.detail_wanted)
+    }
+
+    /// Auto-thaw schedule, cgroup path -> deadline. The UI inserts an
+    /// entry when it freezes a cgroup for a limited time.
+    pub fn thaw_schedule_handle(&self) -> Arc<Mutex<HashMap<String, Instant>>> {
+        Arc::clone(&self.thaw_at)
+    }
+
+    /// Flag the UI sets while it wants hardware counters for the
+    /// inspected PID.
+    pub fn perf_handle(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.perf_wanted)
+    }
+
+    /// Map fill levels, counted every `MAP_CHECK_INTERVAL` or every cycle
+    /// while the UI shows them. `None` on cycles that skip the count.
+    fn sample_map_usage(&mut self, wanted: bool) -> Option<Vec<MapUsage>> {
+        let now = self.clock.now();
+        let due = self
+            .maps_checked
+            .is_none_or(|t| now.duration_since(t) >= MAP_CHECK_INTERVAL);
+        if !wanted && !due {
+            return None;
+        }
+        self.maps_checked = Some(now);
+        Some(self.ebpf.map_usage())
+    }
+
+    /// Warn once per episode when a map passes `MAP_FULL_PERCENT`; past
+    /// max_entries the eBPF side silently drops new entries.
+    fn detect_full_maps(&mut self, usage: &[MapUsage]) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        let mut full = HashSet::new();
+        for map in usage.iter().filter(|m| m.max_entries > 0) {
+            let percent = map.entries as f64 * 100.0 / map.max_entries as f64;
+            if percent < MAP_FULL_PERCENT {
+                continue;
+            }
+            full.insert(map.name);
+            if self.full_maps.contains(map.name) {
+                continue;
+            }
+            alerts.push(Alert {
+                severity: Severity::Warning,
+                kind: "bpf_map_full",
+                message: format!(
+                    "BPF map {} is {:.0}% full ({}/{}); new entries will be dropped",
+                    map.name, percent, map.entries, map.max_entries
+                ),
+                fields: vec![
+                    ("MAP", map.name.to_string()),
+                    ("ENTRIES", map.entries.to_string()),
+                    ("MAX_ENTRIES", map.max_entries.to_string()),
+                ],
+            });
+        }
+        self.full_maps = full;
+        alerts
+    }
+
+    /// Drop every previous sample after a suspend, so this refresh shows
+    /// no rates instead of spikes. /proc/stat ticks, the task iterator's
+    /// ns and the monotonic clock the rates are divided by each treat the
+    /// time asleep differently, and device counters may jump on resume.
+    fn reset_baselines(&mut self, cpu_total: &CpuStats, cpus: &[CpuStats]) {
+        self.prev_cpu_total = cpu_total.clone();
+        self.prev_cpus = cpus.to_vec();
+        self.prev_proc_times.clear();
+        self.prev_net_bytes.clear();
+        self.prev_net_time = self.clock.now();
+        self.prev_disk_bytes.clear();
+        self.prev_csw.clear();
+        self.prev_runq.clear();
+        self.prev_block_devices.clear();
+        self.prev_rapl.clear();
+        self.prev_thermal = None;
+        self.prev_net_ifaces.clear();
+        self.prev_sock_drops.clear();
+        self.prev_thread_times.clear();
+        self.prev_dev_writes.clear();
+        self.prev_prog_stats.clear();
+        self.bpf_object_sampler.reset();
+    }
+
+    /// Kernel run-time accounting is switched on only while the UI shows
+    /// program costs, bpftop's own or everyone's.
+    fn update_run_stats(&mut self) {
+        let wanted = self.bpf_stats_wanted.load(Ordering::Relaxed)
+            || self.bpf_objects_wanted.load(Ordering::Relaxed);
+        if wanted != self.ebpf.run_stats_enabled() {
+            if let Err(e) = self.ebpf.set_run_stats(wanted) {
+                log::warn!("{e:#}");
+            }
+            self.prev_prog_stats.clear();
+            self.bpf_object_sampler.reset();
+        }
+    }
+
+    /// Per-program overhead while the UI asks for it.
+    fn sample_program_load(&mut self) -> Option<Vec<ProgramLoad>> {
+        if !self.bpf_stats_wanted.load(Ordering::Relaxed) || !self.ebpf.run_stats_enabled() {
+            return None;
+        }
+
+        let now = self.clock.now();
+        let elapsed = now
+            .duration_since(self.prev_prog_time)
+            .as_secs_f64()
+            .max(f64::EPSILON);
+        self.prev_prog_time = now;
+        let stats = self.ebpf.program_stats();
+        let loads = stats
+            .iter()
+            .map(|s| {
+                // The first sample after enabling only primes the deltas
+                let (runs, time_ns) = match self.prev_prog_stats.get(&s.name) {
+                    Some(&(count, time)) => (
+                        s.run_count.saturating_sub(count),
+                        s.run_time_ns.saturating_sub(time),
+                    ),
+                    None => (0, 0),
+                };
+                ProgramLoad {
+                    name: s.name.clone(),
+                    total_runs: s.run_count,
+                    runs_per_sec: runs as f64 / elapsed,
+                    avg_ns: if runs > 0 { time_ns as f64 / runs as f64 } else { 0.0 },
+                    cpu_percent: time_ns as f64 / (elapsed * 1e9) * 100.0,
+                }
+            })
+            .collect();
+        self.prev_prog_stats = stats
+            .into_iter()
+            .map(|s| (s.name, (s.run_count, s.run_time_ns)))
+            .collect();
+        Some(loads)
+    }
+
+    /// Collect all system and process data for one refresh cycle.
+    pub fn collect(&mut self) -> Result<Snapshot> {
+        // System-wide stats from /proc (unchanged — these are the kernel's
+        // official API for aggregate stats)
+        let (mut cpu_total, mut cpus) = read_cpu_stats()?;
+        let (memory, swap) = read_memory_info()?;
+        let load_avg = read_load_avg()?;
+        let uptime = read_uptime()?;
+        let pressure = read_pressure();
+
+        let suspended_ns = read_suspended_ns();
+        if suspended_ns.saturating_sub(self.prev_suspended_ns) > SUSPEND_GAP_NS {
+            log::info!(
+                "resumed after {}s suspended, starting counters afresh",
+                (suspended_ns - self.prev_suspended_ns) / 1_000_000_000
+            );
+            self.reset_baselines(&cpu_total, &cpus);
+        }
+        self.prev_suspended_ns = suspended_ns;
+
+        // Calculate CPU percentages from deltas
+        cpu_total.calc_percentages(&self.prev_cpu_total);
+        for (i, cpu) in cpus.iter_mut().enumerate() {
+            if let Some(prev) = self.prev_cpus.get(i) {
+                cpu.calc_percentages(prev);
+            }
+        }
+
+        // Refresh cgroup inode map periodically (once per cycle, not per-process)
+        self.cgroup_resolver.tick();
+
+        // Process data from BPF task iterator (replaces all /proc/{pid} reads)
+        // If BPF isn't loaded or read fails, return empty process list
+        // but still return valid system stats.
+        let bpf_tasks = match self.ebpf.read_tasks() {
+            Ok(tasks) => tasks,
+            Err(e) => {
+                log::debug!("BPF read_tasks failed: {e}");
+                Vec::new()
+            }
+        };
+
+        // Classify tasks: processes, user threads, kernel threads
+        let mut task_count = 0u32;
+        let mut user_threads = 0u32;
+        let mut kernel_threads = 0u32;
+        // A process's CPU time is that of all its threads, not just the
+        // leader's. Time of threads that already exited isn't included, so
+        // the total can dip; the saturating delta below reads that as idle.
+        let mut group_cpu_ns = HashMap::<u32, u64>::new();
+        // Context switches likewise, (voluntary, involuntary)
+        let mut group_csw = HashMap::<u32, (u64, u64)>::new();
+        for task in &bpf_tasks {
+            *group_cpu_ns.entry(task.pid).or_insert(0) += task.utime_ns + task.stime_ns;
+            let csw = group_csw.entry(task.pid).or_insert((0, 0));
+            csw.0 += task.nvcsw;
+            csw.1 += task.nivcsw;
+            if task.flags & PF_KTHREAD != 0 {
+                kernel_threads += 1;
+            } else if task.tid != task.pid {
+                user_threads += 1;
+            } else {
+                task_count += 1;
+            }
+        }
+
+        let mut processes = Vec::with_capacity(bpf_tasks.len());
+        let mut running = 0u32;
+        let mut sleeping = 0u32;
+
+        // Cache frozen state per unique cgroup to avoid redundant reads
+        let mut frozen_cache = HashMap::<String, bool>::new();
+        let thaw_in = self.run_thaw_timers();
+
+        // Total CPU ticks delta for percentage calculation
+        let total_sys_delta = cpu_total
+            .total_ticks()
+            .saturating_sub(self.prev_cpu_total.total_ticks());
+
+        // Convert ns delta to fraction of total CPU time
+        // total_sys_delta is in jiffies (clock ticks), so convert
+        // cpu_delta_ns to jiffies: ns / (1e9 / CLK_TCK) = ns * CLK_TCK / 1e9
+        // But it's simpler to keep everything relative:
+        // cpu% = (delta_ns / delta_wall_ns) * 100
+        // We approximate wall time delta from system ticks:
+        // wall_ns ≈ total_sys_delta * (1e9 / CLK_TCK) / num_cpus
+        let num_cpus = cpus.len().max(1) as f64;
+        let cpu_percent_of = |cpu_delta_ns: u64| {
+            if total_sys_delta > 0 {
+                // total_sys_delta is aggregate ticks across all cores.
+                // Per-core wall time in ns = total_sys_delta / num_cpus * (1e9 / CLK_TCK)
+                // cpu% (Irix mode, 0-100% per core) = delta_ns / wall_ns * 100
+                let wall_delta_ns =
+                    total_sys_delta as f64 * 1_000_000_000.0 / 100.0 / num_cpus;
+                (cpu_delta_ns as f64 / wall_delta_ns) * 100.0
+            } else {
+                0.0
+            }
+        };
+
+        // One map walk for every cmdline instead of a lookup per process
+        let mut cmdlines = self.ebpf.read_all_cmdlines();
+
+        // Parent at exec time, for processes whose parent exited before
+        // this refresh saw them
+        let execs: Vec<ExecRecord> = self
+            .ebpf
+            .drain_exec_events()
+            .iter()
+            .map(|ev| ExecRecord::from_raw(ev, resolve_username(ev.uid)))
+            .collect();
+        let exec_parents: HashMap<u32, u32> = execs.iter().map(|e| (e.pid, e.ppid)).collect();
+        let mut ooms: Vec<OomRecord> = self
+            .ebpf
+            .drain_oom_events()
+            .iter()
+            .map(OomRecord::from_raw)
+            .collect();
+        let mut orig_parents = HashMap::new();
+
+        for task in &bpf_tasks {
+            // Threads (tid != tgid) get their rows from the leader's below
+            if task.tid != task.pid {
+                continue;
+            }
+
+            let state = ProcessState::from_kernel_state(task.state);
+            match state {
+                ProcessState::Running => running += 1,
+                ProcessState::Sleeping | ProcessState::Idle => sleeping += 1,
+                _ => {}
+            }
+
+            // CPU time: BPF gives nanoseconds; convert to ticks equivalent for delta
+            let total_cpu_ns = group_cpu_ns
+                .get(&task.pid)
+                .copied()
+                .unwrap_or(task.utime_ns + task.stime_ns);
+            let prev_cpu_ns = self
+                .prev_proc_times
+                .get(&task.pid)
+                .copied()
+                .unwrap_or(total_cpu_ns);
+            let cpu_percent = cpu_percent_of(total_cpu_ns.saturating_sub(prev_cpu_ns));
+
+            let res_bytes = task.rss_pages * self.page_size;
+            let shr_bytes = task.shmem_pages * self.page_size;
+            let mem_percent = if memory.total > 0 {
+                res_bytes as f64 / memory.total as f64 * 100.0
+            } else {
+                0.0
+            };
+
+            let cpu_time_secs = total_cpu_ns as f64 / 1_000_000_000.0;
+            let (nvcsw, nivcsw) = group_csw
+                .get(&task.pid)
+                .copied()
+                .unwrap_or((task.nvcsw, task.nivcsw));
+
+            let nice = task.static_prio - 120;
+            let priority = task.prio - 100;
+
+            let user = resolve_username(task.ruid);
+
+            let is_kernel_thread = task.flags & PF_KTHREAD != 0;
+
+            // cmdline: prefer BPF CMDLINE_MAP, fall back to comm
+            let comm = comm_to_string(&task.comm);
+            let cmdline = cmdlines
+                .remove(&(task.pid, task.start_time_ns))
+                .unwrap_or_else(|| format!("[{}]", comm));
+
+            // Container: resolve from cgroup_id
+            let cgroup_path = self.cgroup_resolver.resolve_path(task.cgroup_id);
+            let container = self.cgroup_resolver.resolve(task.cgroup_id);
+
+            // Check frozen state (cached per cgroup)
+            let frozen = *frozen_cache
+                .entry(cgroup_path.clone())
+                .or_insert_with(|| cgroup_control::is_frozen(&cgroup_path));
+            let thaw_in_secs = if frozen { thaw_in.get(&cgroup_path).copied() } else { None };
+
+            let key = (task.pid, task.start_time_ns);
+            let orig_ppid = self
+                .orig_parents
+                .get(&key)
+                .or_else(|| exec_parents.get(&task.pid))
+                .copied()
+                .unwrap_or(task.ppid);
+            orig_parents.insert(key, orig_ppid);
+
+            processes.push(ProcessInfo {
+                pid: task.pid,
+                ppid: task.ppid,
+                orig_ppid,
+                pgid: task.pgid,
+                sid: task.sid,
+                tty: None,
+                uid: task.ruid,
+                user,
+                state,
+                priority,
+                nice,
+                virt_bytes: task.vsize_bytes,
+                res_bytes,
+                shr_bytes,
+                cpu_percent,
+                mem_percent,
+                rss_growth: 0.0,
+                gpu_percent: 0.0,
+                watts: 0.0,
+                gpu_mem_bytes: 0,
+                net_rx_bytes: 0,
+                net_tx_bytes: 0,
+                net_rate: 0.0,
+                net_tx_rate: 0.0,
+                net_rx_rate: 0.0,
+                disk_read_bytes: 0,
+                disk_write_bytes: 0,
+                disk_read_rate: 0.0,
+                disk_write_rate: 0.0,
+                sock_drops: 0,
+                sock_drop_rate: 0.0,
+                net_ifname: String::new(),
+                fd_count: 0,
+                nvcsw,
+                nivcsw,
+                csw_rate: 0.0,
+                icsw_rate: 0.0,
+                runq_wait_ns: 0,
+                runq_waits: 0,
+                runq_latency_ns: 0.0,
+                cpu_time_secs,
+                start_time_ns: task.start_time_ns,
+                comm,
+                cmdline,
+                container_image: container.as_ref().and_then(|c| c.image.clone()),
+                perf: None,
+                pod: container.as_ref().and_then(|c| Some(c.pod.as_ref()?.name.clone())),
+                pod_namespace: container.as_ref().and_then(|c| c.pod.as_ref()?.namespace.clone()),
+                container_id: container.as_ref().map(|c| c.id.clone()),
+                container: container.map(|c| c.name),
+                service: None,
+                badge: None,
+                cgroup_path,
+                children: Vec::new(),
+                prev_cpu_ns: total_cpu_ns,
+                is_kernel_thread,
+                is_thread: false,
+                tid: task.pid,
+                tagged: false,
+                frozen,
+                thaw_in_secs,
+                tree_prefix: String::new(),
+                exited: None,
+                idle: false,
+            });
+        }
+
+        self.tty_resolver.resolve(&mut processes);
+
+        // Thread rows: a copy of the leader's row, taken before the
+        // per-process GPU, network, disk and fd counters are filled in, with
+        // the thread's own name, state and CPU time (and, below, network)
+        let leaders: HashMap<u32, usize> = processes
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.pid, i))
+            .collect();
+        let mut threads: Vec<ProcessInfo> = bpf_tasks
+            .iter()
+            .filter(|task| task.tid != task.pid)
+            .filter_map(|task| {
+                let leader = &processes[*leaders.get(&task.pid)?];
+                let total_cpu_ns = task.utime_ns + task.stime_ns;
+                let prev_cpu_ns = self
+                    .prev_proc_times
+                    .get(&task.tid)
+                    .copied()
+                    .unwrap_or(total_cpu_ns);
+                Some(ProcessInfo {
+                    tid: task.tid,
+                    is_thread: true,
+                    comm: comm_to_string(&task.comm),
+                    state: ProcessState::from_kernel_state(task.state),
+                    priority: task.prio - 100,
+                    nice: task.static_prio - 120,
+                    cpu_percent: cpu_percent_of(total_cpu_ns.saturating_sub(prev_cpu_ns)),
+                    cpu_time_secs: total_cpu_ns as f64 / 1_000_000_000.0,
+                    prev_cpu_ns: total_cpu_ns,
+                    nvcsw: task.nvcsw,
+                    nivcsw: task.nivcsw,
+                    start_time_ns: task.start_time_ns,
+                    ..leader.clone()
+                })
+            })
+            .collect();
+
+        // GPU data
+        let mut gpus = Vec::new();
+        #[cfg(feature = "gpu")]
+        if let Some(ref mut gc) = self.gpu_collector {
+            let (gpu_devices, proc_gpu) = gc.collect();
+            gpus = gpu_devices;
+            for proc in &mut processes {
+                if let Some(usage) = proc_gpu.get(&proc.pid) {
+                    proc.gpu_percent = usage.gpu_percent;
+                    proc.gpu_mem_bytes = usage.gpu_mem_bytes;
+                }
+            }
+        }
+
+        // Network data from BPF kprobes. NET_STATS is per thread: thread
+        // rows get their own counters, processes the sum over their threads
+        let thread_net = self.ebpf.read_net_stats();
+        let tgids: HashMap<u32, u32> = bpf_tasks.iter().map(|t| (t.tid, t.pid)).collect();
+        let mut net_stats = HashMap::<u32, (u64, u64, u32)>::new();
+        for (tid, &(tx, rx, ifindex)) in &thread_net {
+            let Some(pid) = tgids.get(tid) else {
+                continue;
+            };
+            let total = net_stats.entry(*pid).or_insert((0, 0, 0));
+            total.0 += tx;
+            total.1 += rx;
+            if ifindex > 0 {
+                total.2 = ifindex;
+            }
+        }
+        let now = self.clock.now();
+        let wall_delta_secs = now.duration_since(self.prev_net_time).as_secs_f64().max(0.1);
+        let mut new_net_bytes = HashMap::new();
+        let rows = processes
+            .iter_mut()
+            .map(|p| (p.pid, p))
+            .chain(threads.iter_mut().map(|t| (t.tid, t)));
+        for (id, proc) in rows {
+            let stats = if proc.is_thread { thread_net.get(&id) } else { net_stats.get(&id) };
+            if let Some(&(tx, rx, ifindex)) = stats {
+                proc.net_tx_bytes = tx;
+                proc.net_rx_bytes = rx;
+                let (prev_tx, prev_rx) = self.prev_net_bytes.get(&id).copied().unwrap_or((tx, rx));
+                proc.net_tx_rate = tx.saturating_sub(prev_tx) as f64 / wall_delta_secs;
+                proc.net_rx_rate = rx.saturating_sub(prev_rx) as f64 / wall_delta_secs;
+                proc.net_rate = proc.net_tx_rate + proc.net_rx_rate;
+                new_net_bytes.insert(id, (tx, rx));
+
+                // Resolve interface name
+                if ifindex > 0 {
+                    let ifname = self.ifindex_cache.entry(ifindex).or_insert_with(|| {
+                        nix::net::if_::if_indextoname(ifindex)
+                            .map(|s| s.to_string_lossy().into_owned())
+                            .unwrap_or_else(|_| format!("if{ifindex}"))
+                    });
+                    proc.net_ifname = ifname.clone();
+                } else {
+                    proc.net_ifname = "*".to_string();
+                }
+            }
+        }
+
+        // File I/O from BPF vfs_read/vfs_write kprobes (same interval as net).
+        // They fire on every read and write, so they only run while wanted
+        let detail_wanted = self.detail_wanted.load(Ordering::Relaxed);
+        let file_io_wanted = self.file_io_wanted.load(Ordering::Relaxed) || detail_wanted;
+        if let Err(e) = self.ebpf.set_attached(Subsystem::FileIo, file_io_wanted) {
+            log::warn!("{e:#}");
+        }
+        let disk_stats = self.ebpf.read_disk_stats();
+        let mut new_disk_bytes = HashMap::new();
+        for proc in &mut processes {
+            if let Some(&(read, write)) = disk_stats.get(&proc.pid) {
+                proc.disk_read_bytes = read;
+                proc.disk_write_bytes = write;
+                let (prev_read, prev_write) = self.prev_disk_bytes.get(&proc.pid).copied().unwrap_or((read, write));
+                proc.disk_read_rate = read.saturating_sub(prev_read) as f64 / wall_delta_secs;
+                proc.disk_write_rate = write.saturating_sub(prev_write) as f64 / wall_delta_secs;
+                new_disk_bytes.insert(proc.pid, (read, write));
+            }
+        }
+
+        // Whole-disk throughput from /proc/diskstats (same interval as net)
+        let mut disks = read_block_devices().unwrap_or_default();
+        for disk in &mut disks {
+            if let Some(prev) = self.prev_block_devices.iter().find(|d| d.name == disk.name) {
+                disk.calc_rates(prev, wall_delta_secs);
+            }
+        }
+        self.prev_block_devices = disks.clone();
+
+        // Package power from the RAPL energy counters
+        let mut rapl = read_rapl_packages();
+        for package in &mut rapl {
+            if let Some(prev) = self.prev_rapl.iter().find(|p| p.zone == package.zone) {
+                package.calc_rates(prev, wall_delta_secs);
+            }
+        }
+        self.prev_rapl = rapl.clone();
+
+        // CPU frequency and thermal throttle events
+        let mut thermal = read_cpu_thermal();
+        if let (Some(thermal), Some(prev)) = (&mut thermal, &self.prev_thermal) {
+            thermal.calc_rates(prev);
+        }
+        self.prev_thermal = thermal.clone();
+
+        // Per-interface throughput from /proc/net/dev
+        let mut net_ifaces = read_net_interfaces().unwrap_or_default();
+        for iface in &mut net_ifaces {
+            if let Some(prev) = self.prev_net_ifaces.iter().find(|i| i.name == iface.name) {
+                iface.calc_rates(prev, wall_delta_secs);
+            }
+        }
+        self.prev_net_ifaces = net_ifaces.clone();
+
+        // Drained every refresh, so the detail view has history when opened
+        self.dns_log.record(&self.ebpf.drain_dns_events());
+
+        // Detail data (threads, decoded FDs) for the PID the UI is inspecting
+        let inspect_pid = self.inspect_pid.load(Ordering::Relaxed);
+        let mut inspection = (inspect_pid != 0).then(|| Inspection {
+            pid: inspect_pid,
+            ..Default::default()
+        });
+
+        // Off-CPU accounting follows the inspected PID while asked for
+        let offcpu_pid = if self.offcpu_wanted.load(Ordering::Relaxed) {
+            inspect_pid
+        } else {
+            0
+        };
+        if offcpu_pid != self.ebpf.offcpu_target() {
+            if let Err(e) = self.ebpf.set_offcpu_target(offcpu_pid) {
+                log::warn!("{e:#}");
+            }
+            self.offcpu_since = self.clock.now();
+        }
+
+        // Per-thread breakdown for the inspected PID, from the same task
+        // iterator pass (no /proc/<pid>/task reads)
+        if let Some(ref mut insp) = inspection {
+            let wall_delta_ns =
+                total_sys_delta as f64 * 1_000_000_000.0 / 100.0 / cpus.len().max(1) as f64;
+            let mut thread_times = HashMap::new();
+            for task in bpf_tasks.iter().filter(|t| t.pid == insp.pid) {
+                let total_cpu_ns = task.utime_ns + task.stime_ns;
+                let prev = self
+                    .prev_thread_times
+                    .get(&task.tid)
+                    .copied()
+                    .unwrap_or(total_cpu_ns);
+                let cpu_percent = if wall_delta_ns > 0.0 {
+                    total_cpu_ns.saturating_sub(prev) as f64 / wall_delta_ns * 100.0
+                } else {
+                    0.0
+                };
+                thread_times.insert(task.tid, total_cpu_ns);
+                insp.threads.push(ThreadInfo {
+                    tid: task.tid,
+                    comm: comm_to_string(&task.comm),
+                    state: ProcessState::from_kernel_state(task.state),
+                    cpu_percent,
+                    cpu_time_secs: total_cpu_ns as f64 / 1_000_000_000.0,
+                });
+            }
+            self.prev_thread_times = thread_times;
+
+            if detail_wanted {
+                let mut peers: Vec<Peer> = self
+                    .ebpf
+                    .read_peer_stats(insp.pid)
+                    .into_iter()
+                    .map(|(family, addr, tx_bytes, rx_bytes)| Peer {
+                        addr: decode_addr(family, &addr, 0).ip(),
+                        tx_bytes,
+                        rx_bytes,
+                    })
+                    .collect();
+                peers.sort_by_key(|p| std::cmp::Reverse(p.tx_bytes + p.rx_bytes));
+                peers.truncate(TOP_PEERS);
+                insp.peers = peers;
+            }
+            insp.dns_queries = self.dns_log.recent(insp.pid);
+
+            insp.off_cpu = self
+                .ebpf
+                .read_offcpu_time()
+                .map(|ns| OffCpuBreakdown::new(ns, self.clock.now().duration_since(self.offcpu_since)));
+
+            insp.io_latency = self
+                .ebpf
+                .read_bio_latency(insp.pid)
+                .and_then(|hist| IoLatency::from_histogram(&hist.buckets));
+
+            let mounts = read_mount_points().unwrap_or_default();
+            let mut dev_writes = HashMap::new();
+            let mut mount_writes: Vec<MountWrite> = Vec::new();
+            for (dev, bytes) in self.ebpf.read_dev_writes(insp.pid) {
+                let key = DevWriteKey { pid: insp.pid, dev };
+                let prev = self.prev_dev_writes.get(&key).copied().unwrap_or(bytes);
+                dev_writes.insert(key, bytes);
+                let mount = mounts
+                    .get(&dev)
+                    .cloned()
+                    .unwrap_or_else(|| format!("[dev {}:{}]", dev >> 20, dev & 0xfffff));
+                mount_writes.push(MountWrite {
+                    mount,
+                    bytes,
+                    rate: bytes.saturating_sub(prev) as f64 / wall_delta_secs,
+                });
+            }
+            self.prev_dev_writes = dev_writes;
+            mount_writes.sort_by(|a, b| b.rate.total_cmp(&a.rate).then(b.bytes.cmp(&a.bytes)));
+            mount_writes.truncate(TOP_MOUNTS);
+            insp.mount_writes = mount_writes;
+
+            insp.cgroup_writeback = processes
+                .iter()
+                .find(|p| p.pid == insp.pid)
+                .and_then(|p| cgroup_control::read_writeback(&p.cgroup_path));
+        } else {
+            self.prev_thread_times.clear();
+            self.prev_dev_writes.clear();
+        }
+
+        // One walk of the task_file iterator gives every process's FD count,
+        // the inspected PID's panels and the owners of dropping sockets
+        let drops = self.ebpf.read_sock_drops();
+        let mut drop_owners: HashMap<DropKey, Vec<u32>> = HashMap::new();
+        match self.ebpf.read_files() {
+            Ok(files) => {
+                let mut fd_counts = HashMap::<u32, u32>::new();
+                for file in &files {
+                    *fd_counts.entry(file.pid).or_insert(0) += 1;
+                    if !drops.is_empty() {
+                        if let Some(key) = drop_key(file) {
+                            let owners = drop_owners.entry(key).or_default();
+                            if !owners.contains(&file.pid) {
+                                owners.push(file.pid);
+                            }
+                        }
+                    }
+                    if let Some(ref mut insp) = inspection {
+                        if file.pid == insp.pid {
+                            insp.files.push(OpenFile::from_raw(file));
+                        }
+                        if OpenFile::is_inet_socket(file) {
+                            insp.net_sockets.push(OpenFile::from_raw(file));
+                        }
+                    }
+                }
+                for proc in &mut processes {
+                    proc.fd_count = fd_counts.get(&proc.pid).copied().unwrap_or(0);
+                }
+            }
+            Err(e) => log::debug!("BPF read_files failed: {e}"),
+        }
+        // TCP sockets the FD walk can't see: closed but still shutting
+        // down, or waiting in a listener's accept queue
+        if let Some(ref mut insp) = inspection {
+            let conns = self.ebpf.read_tcp_conns();
+            let fdless = fdless_tcp_sockets(&insp.net_sockets, &conns);
+            insp.net_sockets.extend(fdless);
+        }
+
+        // Listen-queue and receive-buffer drops from the kfree_skb
+        // tracepoint, credited to every process holding the dropping port
+        let mut sock_drops = HashMap::<u32, u64>::new();
+        for (key, count) in drops {
+            for pid in drop_owners.get(&key).into_iter().flatten() {
+                *sock_drops.entry(*pid).or_insert(0) += count;
+            }
+        }
+        for proc in &mut processes {
+            if let Some(&drops) = sock_drops.get(&proc.pid) {
+                let prev = self.prev_sock_drops.get(&proc.pid).copied().unwrap_or(drops);
+                proc.sock_drops = drops;
+                proc.sock_drop_rate = drops.saturating_sub(prev) as f64 / wall_delta_secs;
+            }
+        }
+        self.prev_sock_drops = sock_drops;
+        self.orig_parents = orig_parents;
+
+        // Build parent-child relationships for tree view
+        let pid_set: HashMap<u32, usize> = processes
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.pid, i))
+            .collect();
+
+        let child_map: Vec<(u32, u32)> = processes
+            .iter()
+            .filter(|p| p.ppid != 0 && p.ppid != p.pid)
+            .map(|p| (p.ppid, p.pid))
+            .collect();
+
+        for (ppid, child_pid) in child_map {
+            if let Some(&idx) = pid_set.get(&ppid) {
+                processes[idx].children.push(child_pid);
+            }
+        }
+        self.dns_log.retain(|pid| pid_set.contains_key(&pid));
+
+        self.rss_tracker.update(now, &processes);
+        for proc in &mut processes {
+            proc.rss_growth = self.rss_tracker.growth(proc);
+        }
+
+        let mut alerts = self.detect_priv_escalation(&processes);
+        alerts.extend(self.detect_sock_drops(&processes));
+        alerts.extend(self.detect_leaks(&processes));
+        alerts.extend(self.detect_throttling(thermal.as_ref(), now));
+        let bpf_stats_wanted = self.bpf_stats_wanted.load(Ordering::Relaxed);
+        let map_usage = self.sample_map_usage(bpf_stats_wanted);
+        if let Some(usage) = &map_usage {
+            alerts.extend(self.detect_full_maps(usage));
+        }
+        processes.extend(threads);
+
+        // Run-queue latency from the sched tracepoints. Per thread like
+        // NET_STATS; processes get the sum over their threads. The
+        // tracepoints fire on every context switch, so only run while wanted
+        let runq_wanted = self.runq_wanted.load(Ordering::Relaxed) || detail_wanted;
+        if let Err(e) = self.ebpf.set_attached(Subsystem::RunQueue, runq_wanted) {
+            log::warn!("{e:#}");
+        }
+        let thread_runq = self.ebpf.read_runq_latency();
+        let mut group_runq = HashMap::<u32, (u64, u64)>::new();
+        for (tid, &(wait_ns, waits)) in &thread_runq {
+            if let Some(pid) = tgids.get(tid) {
+                let total = group_runq.entry(*pid).or_insert((0, 0));
+                total.0 += wait_ns;
+                total.1 += waits;
+            }
+        }
+
+        // Context switch rates and scheduling delay (same interval as net);
+        // tid keys process and thread rows alike
+        for proc in &mut processes {
+            let runq = if proc.is_thread {
+                thread_runq.get(&proc.tid)
+            } else {
+                group_runq.get(&proc.pid)
+            };
+            if let Some(&(wait_ns, waits)) = runq {
+                let (prev_ns, prev_waits) = self
+                    .prev_runq
+                    .get(&proc.tid)
+                    .copied()
+                    .unwrap_or((wait_ns, waits));
+                let delta_waits = waits.saturating_sub(prev_waits);
+                proc.runq_wait_ns = wait_ns;
+                proc.runq_waits = waits;
+                if delta_waits > 0 {
+                    proc.runq_latency_ns = wait_ns.saturating_sub(prev_ns) as f64 / delta_waits as f64;
+                }
+            }
+
+            let (prev_v, prev_iv) = self
+                .prev_csw
+                .get(&proc.tid)
+                .copied()
+                .unwrap_or((proc.nvcsw, proc.nivcsw));
+            proc.csw_rate = proc.nvcsw.saturating_sub(prev_v) as f64 / wall_delta_secs;
+            proc.icsw_rate = proc.nivcsw.saturating_sub(prev_iv) as f64 / wall_delta_secs;
+        }
+
+        self.update_perf_counters(&mut processes, inspect_pid);
+        if let Some(ref mut insp) = inspection {
+            insp.perf_unavailable = self.perf.unavailable().map(str::to_string);
+        }
+
+        let exit_events = self.ebpf.drain_exit_events();
+        let exits = exit_events.iter().map(ExitRecord::from_raw).collect();
+
+        for oom in &mut ooms {
+            oom.resolve(&processes, &exit_events);
+        }
+        // Last, so the UI flashes an OOM kill over anything else
+        alerts.extend(ooms.iter().map(OomRecord::alert));
+
+        // Save current state for next delta calculation
+        self.prev_cpu_total = cpu_total.clone();
+        self.prev_cpus = cpus.clone();
+        self.prev_proc_times = processes
+            .iter()
+            .map(|p| (p.tid, p.prev_cpu_ns))
+            .collect();
+        self.prev_net_bytes = new_net_bytes;
+        self.prev_csw = processes
+            .iter()
+            .map(|p| (p.tid, (p.nvcsw, p.nivcsw)))
+            .collect();
+        self.prev_runq = processes
+            .iter()
+            .map(|p| (p.tid, (p.runq_wait_ns, p.runq_waits)))
+            .collect();
+        self.prev_net_time = now;
+        self.prev_disk_bytes = new_disk_bytes;
+
+        let sys_info = SystemInfo {
+            cpu_total,
+            cpus,
+            memory,
+            swap,
+            load_avg,
+            uptime_secs: uptime,
+            monotonic_secs: read_monotonic_ns() as f64 / 1_000_000_000.0,
+            total_tasks: task_count,
+            user_threads,
+            kernel_threads,
+            running_tasks: running,
+            sleeping_tasks: sleeping,
+            gpus,
+            pressure,
+            disks,
+            net_ifaces,
+            rapl,
+            thermal,
+        };
+        attribute_power(&mut processes, sys_info.package_watts());
+
+        self.update_run_stats();
+        let bpf_objects = self
+            .bpf_objects_wanted
+            .load(Ordering::Relaxed)
+            .then(|| self.bpf_object_sampler.sample(self.clock.now()));
+        Ok(Snapshot {
+            sys: sys_info,
+            processes,
+            inspection,
+            alerts,
+            execs,
+            exits,
+            ooms,
+            bpf_stats: self.sample_program_load().map(|programs| BpfStats {
+                programs,
+                maps: map_usage.unwrap_or_default(),
+            }),
+            bpf_objects,
+        })
+    }
+
+    /// Commands exempt from privilege escalation alerts, from
+    /// `[alerts] setuid_allowlist`.
+    pub fn set_setuid_allowlist(&mut self, comms: &[String]) {
+        self.setuid_allowlist = comms.iter().cloned().collect();
+    }
+
+    /// Alert threshold for socket drops, from `[alerts] drops_per_sec`.
+    pub fn set_drop_alert(&mut self, per_sec: f64) {
+        self.drop_alert_per_sec = per_sec;
+    }
+
+    /// Processes counted from `[perf] top`.
+    pub fn set_perf_top(&mut self, top: usize) {
+        self.perf_top = top;
+    }
+
+    /// Hardware counters for the `perf_top` busiest processes, plus the
+    /// inspected one while the UI asks for it.
+    fn update_perf_counters(&mut self, processes: &mut [ProcessInfo], inspect_pid: u32) {
+        let mut pids: HashSet<u32> = HashSet::new();
+        if self.perf_top > 0 {
+            let mut busiest: Vec<&ProcessInfo> = processes
+                .iter()
+                .filter(|p| !p.is_thread && !p.is_kernel_thread)
+                .collect();
+            busiest.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent));
+            pids.extend(busiest.iter().take(self.perf_top).map(|p| p.pid));
+        }
+        if inspect_pid != 0 && self.perf_wanted.load(Ordering::Relaxed) {
+            pids.insert(inspect_pid);
+        }
+        let rates = self.perf.update(&pids, self.clock.now());
+        for proc in processes.iter_mut().filter(|p| !p.is_thread) {
+            proc.perf = rates.get(&proc.pid).copied();
+        }
+    }
+
+    /// Leak window from `[alerts] leak_minutes`; 0 keeps the GROW column
+    /// on the default window but never alerts.
+    pub fn set_leak_alert(&mut self, minutes: u64) {
+        self.leak_alert = minutes > 0;
+        if minutes > 0 {
+            self.rss_tracker.set_window(Duration::from_secs(minutes * 60));
+        }
+    }
+
+    /// Flag processes whose sockets started dropping packets faster than
+    /// `drop_alert_per_sec`: an overloaded server not keeping up with
+    /// accept() or recv(). Alerts once per episode, not every refresh.
+    fn detect_sock_drops(&mut self, processes: &[ProcessInfo]) -> Vec<Alert> {
+        if self.drop_alert_per_sec <= 0.0 {
+            return Vec::new();
+        }
+        let mut alerts = Vec::new();
+        let mut dropping = HashSet::new();
+        for p in processes.iter().filter(|p| p.sock_drop_rate >= self.drop_alert_per_sec) {
+            dropping.insert(p.pid);
+            if self.dropping_pids.contains(&p.pid) {
+                continue;
+            }
+            alerts.push(Alert {
+                severity: Severity::Warning,
+                kind: "sock_drops",
+                message: format!(
+                    "PID {} ({}) is dropping {:.0} packets/s (listen queue or receive buffer full)",
+                    p.pid, p.comm, p.sock_drop_rate
+                ),
+                fields: vec![
+                    ("PID", p.pid.to_string()),
+                    ("COMM", p.comm.clone()),
+                    ("DROPS_PER_SEC", format!("{:.1}", p.sock_drop_rate)),
+                    ("DROPS", p.sock_drops.to_string()),
+                    ("CMDLINE", p.cmdline.clone()),
+                ],
+            });
+        }
+        self.dropping_pids = dropping;
+        alerts
+    }
+
+    /// Flag processes whose RSS rose steadily over the whole leak window.
+    /// A PID alerts once, then again only after its RSS has stopped
+    /// climbing for a while.
+    fn detect_leaks(&mut self, processes: &[ProcessInfo]) -> Vec<Alert> {
+        if !self.leak_alert {
+            return Vec::new();
+        }
+        let minutes = self.rss_tracker.window().as_secs() / 60;
+        let mut alerts = Vec::new();
+        let mut leaking = HashSet::new();
+        for p in processes.iter().filter(|p| !p.is_thread) {
+            let Some(grown) = self.rss_tracker.leaked_bytes(p) else {
+                continue;
+            };
+            leaking.insert(p.pid);
+            if self.leaking_pids.contains(&p.pid) {
+                continue;
+            }
+            alerts.push(Alert {
+                severity: Severity::Warning,
+                kind: "rss_growth",
+                message: format!(
+                    "PID {} ({}) RSS grew {} in {} min without shrinking (possible leak)",
+                    p.pid, p.comm, format_bytes(grown), minutes
+                ),
+                fields: vec![
+                    ("PID", p.pid.to_string()),
+                    ("COMM", p.comm.clone()),
+                    ("RSS", p.res_bytes.to_string()),
+                    ("GROWTH_BYTES", grown.to_string()),
+                    ("GROWTH_PER_SEC", format!("{:.0}", p.rss_growth)),
+                    ("WINDOW_MIN", minutes.to_string()),
+                    ("CMDLINE", p.cmdline.clone()),
+                ],
+            });
+        }
+        self.leaking_pids = leaking;
+        alerts
+    }
+
+    /// Throttling alert threshold from `[alerts] throttle_secs`.
+    pub fn set_throttle_alert(&mut self, secs: u64) {
+        self.throttle_alert_secs = secs;
+    }
+
+    /// Thaw the cgroups whose timer ran out and forget those something else
+    /// thawed meanwhile. Returns the seconds left for the rest.
+    fn run_thaw_timers(&mut self) -> HashMap<String, u64> {
+        let now = self.clock.now();
+        let mut thaw_at = self.thaw_at.lock().unwrap();
+        run_thaw_schedule(&mut thaw_at, now, cgroup_control::is_frozen, |path| {
+            if let Err(e) = cgroup_control::thaw_cgroup(path) {
+                log::warn!("auto-thaw of {path} failed: {e}");
+            }
+        })
+    }
+
+    /// Flag CPUs that kept hitting their thermal limit for
+    /// `throttle_alert_secs`, with how far the clock dropped. Alerts once
+    /// per episode; an episode ends after `THROTTLE_QUIET` without events.
+    fn detect_throttling(&mut self, thermal: Option<&CpuThermal>, now: Instant) -> Vec<Alert> {
+        let Some(thermal) = thermal else {
+            return Vec::new();
+        };
+        if thermal.throttling() {
+            self.throttle_since.get_or_insert(now);
+            self.last_throttle = Some(now);
+        } else if self.last_throttle.is_none_or(|last| now.duration_since(last) >= THROTTLE_QUIET) {
+            self.throttle_since = None;
+            self.last_throttle = None;
+            self.throttle_alerted = false;
+        }
+        let Some(since) = self.throttle_since else {
+            return Vec::new();
+        };
+        let secs = now.duration_since(since).as_secs();
+        if self.throttle_alert_secs == 0 || self.throttle_alerted || secs < self.throttle_alert_secs {
+            return Vec::new();
+        }
+        self.throttle_alerted = true;
+        let freq = match thermal.freq_percent() {
+            Some(pct) => format!(
+                ", running at {:.1}/{:.1} GHz ({pct:.0}%)",
+                thermal.cur_khz as f64 / 1e6,
+                thermal.max_khz as f64 / 1e6
+            ),
+            None => String::new(),
+        };
+        vec![Alert {
+            severity: Severity::Warning,
+            kind: "thermal_throttle",
+            message: format!("CPUs thermally throttled for {secs}s{freq}"),
+            fields: vec![
+                ("SECONDS", secs.to_string()),
+                ("FREQ_MHZ", (thermal.cur_khz / 1000).to_string()),
+                ("MAX_FREQ_MHZ", (thermal.max_khz / 1000).to_string()),
+                ("THROTTLE_EVENTS", thermal.throttle_count.unwrap_or(0).to_string()),
+            ],
+        }]
+    }
+
+    /// Flag processes whose uid changed from non-root to root while the
+    /// process itself (pid + start time) stayed the same, i.e. setuid(0).
+    /// Every process's uid is recorded before deciding whether to alert,
+    /// so an allowlisted helper still leaves the state the next refresh
+    /// compares against.
+    fn detect_priv_escalation(&mut self, processes: &[ProcessInfo]) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        let mut uids = HashMap::with_capacity(processes.len());
+        for p in processes.iter().filter(|p| !p.is_thread) {
+            uids.insert(p.pid, (p.start_time_ns, p.uid));
+            let Some(&(start, prev_uid)) = self.prev_uids.get(&p.pid) else {
+                continue;
+            };
+            if start != p.start_time_ns
+                || prev_uid == 0
+                || p.uid != 0
+                || self.setuid_allowlist.contains(&p.comm)
+                || !self.escalated.insert((p.pid, p.start_time_ns))
+            {
+                continue;
+            }
+            alerts.push(Alert {
+                severity: Severity::Warning,
+                kind: "priv_escalation",
+                message: format!("PID {} ({}) changed uid {} -> 0", p.pid, p.comm, prev_uid),
+                fields: vec![
+                    ("PID", p.pid.to_string()),
+                    ("COMM", p.comm.clone()),
+                    ("PREV_UID", prev_uid.to_string()),
+                    ("UID", p.uid.to_string()),
+                    ("CMDLINE", p.cmdline.clone()),
+                ],
+            });
+        }
+        self.escalated
+            .retain(|(pid, start)| uids.get(pid).is_some_and(|&(s, _)| s == *start));
+        self.prev_uids = uids;
+        alerts
+    }
+}
+
+/// Share the package power out by CPU time over the last refresh, the
+/// way RAPL-based tools like Scaphandre do. Approximate: the idle floor
+/// and uncore power go to whoever was busy, and a memory-bound process
+/// draws less per CPU-second than a vector-heavy one.
+fn attribute_power(processes: &mut [ProcessInfo], package_watts: Option<f64>) {
+    let Some(watts) = package_watts else {
+        return;
+    };
+    // Thread rows repeat their process's CPU time
+    let busy: f64 = processes.iter().filter(|p| !p.is_thread).map(|p| p.cpu_percent).sum();
+    if busy <= 0.0 {
+        return;
+    }
+    for proc in processes {
+        proc.watts = watts * proc.cpu_percent / busy;
+    }
+}
+
+/// SOCK_DROPS key a socket's drops are counted under: listening TCP
+/// sockets and UDP sockets, by netns and local port. `None` for others.
+fn drop_key(file: &FileInfo) -> Option<DropKey> {
+    if !OpenFile::is_inet_socket(file) {
+        return None;
+    }
+    let kind = match (file.sock_type, file.sock_state) {
+        (SOCK_STREAM, TCP_LISTEN) => DROP_LISTEN_OVERFLOW,
+        (SOCK_DGRAM, _) => DROP_RCVBUF,
+        _ => return None,
+    };
+    Some(DropKey {
+        netns: file.netns,
+        port: u16::from_be(file.src_port),
+        kind,
+    })
+}
+
+/// Convert a null-terminated comm byte array to a String.
+fn comm_to_string(comm: &[u8; 16]) -> String {
+    let nul = comm.iter().position(|&b| b == 0).unwrap_or(16);
+    String::from_utf8_lossy(&comm[..nul]).to_string()
+}
+
+fn resolve_username(uid: u32) -> String {
+    nix::unistd::User::from_uid(nix::unistd::Uid::from_raw(uid))
+        .ok()
+        .flatten()
+        .map(|u| u.name)
+        .unwrap_or_else(|| uid.to_string())
+}
+
+/// Call `thaw` on the cgroups in `schedule` whose deadline is past `now`,
+/// and drop them along with those `is_frozen` says were thawed meanwhile.
+/// Returns the seconds left for the rest.
+fn run_thaw_schedule(
+    schedule: &mut HashMap<String, Instant>,
+    now: Instant,
+    is_frozen: impl Fn(&str) -> bool,
+    mut thaw: impl FnMut(&str),
+) -> HashMap<String, u64> {
+    schedule.retain(|path, deadline| {
+        if !is_frozen(path) {
+            return false;
+        }
+        if *deadline > now {
+            return true;
+        }
+        thaw(path);
+        false
+    });
+    schedule
+        .iter()
+        .map(|(path, deadline)| {
+            // Round up so the countdown reaches 0 only as the thaw happens
+            let left = deadline.saturating_duration_since(now);
+            (path.clone(), left.as_millis().div_ceil(1000) as u64)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::clock::ManualClock;
+    use crate::data::process::tests::make_proc;
+
+    fn throttled(count: u64, new_throttles: u64) -> CpuThermal {
+        CpuThermal {
+            cur_khz: 1_200_000,
+            max_khz: 4_800_000,
+            throttle_count: Some(count),
+            new_throttles,
+        }
+    }
+
+    #[test]
+    fn throttling_alerts_once_per_sustained_episode() {
+        let clock = ManualClock::new();
+        let mut collector = Collector::noop();
+        collector.set_throttle_alert(10);
+        let mut count = 0;
+        let mut step = |collector: &mut Collector, secs: u64, new: u64| {
+            clock.advance(Duration::from_secs(secs));
+            count += new;
+            collector.detect_throttling(Some(&throttled(count, new)), clock.now())
+        };
+
+        // Events every 2s: no alert until 10s in, then only one
+        assert!(step(&mut collector, 0, 3).is_empty());
+        for _ in 0..4 {
+            assert!(step(&mut collector, 2, 3).is_empty());
+        }
+        let alerts = step(&mut collector, 2, 3);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].kind, "thermal_throttle");
+        assert!(alerts[0].message.contains("1.2/4.8 GHz (25%)"));
+        assert!(step(&mut collector, 2, 3).is_empty());
+
+        // A quiet spell shorter than THROTTLE_QUIET doesn't end the episode
+        assert!(step(&mut collector, 4, 0).is_empty());
+        assert!(step(&mut collector, 1, 3).is_empty());
+
+        // A longer one does, and the next episode starts from zero
+        assert!(step(&mut collector, 5, 0).is_empty());
+        assert!(step(&mut collector, 1, 0).is_empty());
+        assert!(step(&mut collector, 1, 3).is_empty());
+        assert!(step(&mut collector, 9, 3).is_empty());
+        assert_eq!(step(&mut collector, 1, 3).len(), 1);
+    }
+
+    #[test]
+    fn thaw_timers_count_down_and_thaw_once() {
+        let clock = ManualClock::new();
+        let mut schedule = HashMap::from([
+            ("/a".to_string(), clock.now() + Duration::from_secs(30)),
+            ("/b".to_string(), clock.now() + Duration::from_millis(90_500)),
+            ("/thawed".to_string(), clock.now() + Duration::from_secs(30)),
+        ]);
+        let mut thawed = Vec::new();
+        let mut step = |schedule: &mut HashMap<String, Instant>, secs: u64| {
+            clock.advance(Duration::from_secs(secs));
+            run_thaw_schedule(schedule, clock.now(), |path| path != "/thawed", |path| {
+                thawed.push(path.to_string())
+            })
+        };
+
+        // Thawed by hand meanwhile: dropped without thawing again
+        let left = step(&mut schedule, 0);
+        assert_eq!(left, HashMap::from([("/a".to_string(), 30), ("/b".to_string(), 91)]));
+        let left = step(&mut schedule, 29);
+        assert_eq!(left["/a"], 1);
+        // Due: thawed once and dropped
+        let left = step(&mut schedule, 1);
+        assert_eq!(left, HashMap::from([("/b".to_string(), 61)]));
+        step(&mut schedule, 61);
+        assert!(schedule.is_empty());
+        assert_eq!(thawed, ["/a", "/b"]);
+    }
+
+    #[test]
+    fn priv_escalation_alerts_once_and_skips_routine_helpers() {
+        let mut collector = Collector::noop();
+        let proc = |pid: u32, comm: &str, uid: u32| ProcessInfo {
+            comm: comm.to_string(),
+            uid,
+            start_time_ns: 100,
+            ..make_proc(pid, 0.0)
+        };
+
+        // The first refresh only seeds the uids
+        assert!(collector
+            .detect_priv_escalation(&[proc(10, "exploit", 1000), proc(11, "sudo", 1000)])
+            .is_empty());
+        let alerts =
+            collector.detect_priv_escalation(&[proc(10, "exploit", 0), proc(11, "sudo", 0)]);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].kind, "priv_escalation");
+        assert!(alerts[0].message.starts_with("PID 10 (exploit)"));
+
+        // Flapping back and forth doesn't alert again
+        collector.detect_priv_escalation(&[proc(10, "exploit", 1000)]);
+        assert!(collector.detect_priv_escalation(&[proc(10, "exploit", 0)]).is_empty());
+
+        // An allowlisted helper still records its uid, so what it execs
+        // into compares against root and stays quiet
+        collector.detect_priv_escalation(&[proc(12, "sudo", 1000)]);
+        collector.detect_priv_escalation(&[proc(12, "sudo", 0)]);
+        assert!(collector.detect_priv_escalation(&[proc(12, "bash", 0)]).is_empty());
+    }
+}
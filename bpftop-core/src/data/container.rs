@@ -0,0 +1,432 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::container_names::{ContainerName, NameLookup, Pod};
+
+/// Container runtime information for a process.
+#[derive(Debug, Clone)]
+pub struct ContainerInfo {
+    /// The runtime's name for it once known, the short ID until then.
+    pub name: String,
+    /// Full container ID.
+    pub id: String,
+    pub image: Option<String>,
+    /// UID of the Kubernetes pod it belongs to.
+    pub pod_uid: Option<String>,
+    /// The pod's name and namespace, once known.
+    pub pod: Option<Pod>,
+    pub runtime: ContainerRuntime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum ContainerRuntime {
+    Docker,
+    Containerd,
+    CriO,
+    Podman,
+    Unknown,
+}
+
+impl ContainerRuntime {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Docker => "docker",
+            Self::Containerd => "containerd",
+            Self::CriO => "cri-o",
+            Self::Podman => "podman",
+            Self::Unknown => "?",
+        }
+    }
+}
+
+/// Which systemd unit display mode to use for the SERVICE column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceDisplayMode {
+    /// Show only `.service` unit names, suffix stripped (e.g. `sshd`).
+    ServiceOnly,
+    /// Show the deepest systemd unit of any type (e.g. `sshd.service`, `session-2.scope`).
+    AllUnits,
+    /// Show the full cgroup path (e.g. `system.slice/sshd.service`).
+    FullSlice,
+}
+
+const UNIT_SUFFIXES: &[&str] = &[".service", ".scope", ".slice", ".timer", ".socket"];
+
+/// Wait before asking again about a container no runtime knew, doubled on
+/// each miss up to [`NAME_RETRY_MAX`].
+const NAME_RETRY_MIN: Duration = Duration::from_secs(2);
+const NAME_RETRY_MAX: Duration = Duration::from_secs(300);
+
+/// Where the name lookup of one container stands.
+enum NameState {
+    /// Asked, after `failures` earlier asks found nothing.
+    Asked { failures: u32 },
+    Known(ContainerName),
+    /// No runtime knew it, e.g. it wasn't registered yet when first seen.
+    /// Asked again from `retry_at`.
+    Unknown { failures: u32, retry_at: Instant },
+}
+
+/// How long to wait after the `failures`th lookup that found nothing.
+fn name_retry_delay(failures: u32) -> Duration {
+    NAME_RETRY_MIN
+        .saturating_mul(1 << failures.saturating_sub(1).min(16))
+        .min(NAME_RETRY_MAX)
+}
+
+/// Resolves cgroup inode IDs to container names.
+///
+/// BPF provides `cgroup_id` (the inode number of the cgroup directory
+/// in cgroupfs). We walk /sys/fs/cgroup/ to build a mapping from
+/// inode ID → cgroup path, then parse container info from the path.
+///
+/// No per-PID /proc reads are needed. Container names and images come
+/// from the runtimes, asked once per container in the background (again,
+/// with backoff, while none knows it); the short ID stands in until they
+/// answer.
+pub struct CgroupResolver {
+    /// cgroup inode ID → cgroup path (e.g. "/system.slice/docker-abc.scope")
+    id_to_path: HashMap<u64, String>,
+    /// cgroup path → parsed container info (cache)
+    path_to_container: HashMap<String, Option<ContainerInfo>>,
+    /// Full container ID → runtime's name, or how asking for it went.
+    names: HashMap<String, NameState>,
+    /// Container IDs resolved since the inode map was last refreshed;
+    /// `names` keeps only these across a refresh.
+    seen: HashSet<String>,
+    /// Started at the first container seen.
+    lookup: Option<NameLookup>,
+    /// Counter to trigger periodic refresh of the inode map.
+    cycles_since_refresh: u32,
+}
+
+impl Default for CgroupResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CgroupResolver {
+    pub fn new() -> Self {
+        let mut resolver = Self {
+            id_to_path: HashMap::new(),
+            path_to_container: HashMap::new(),
+            names: HashMap::new(),
+            seen: HashSet::new(),
+            lookup: None,
+            cycles_since_refresh: 0,
+        };
+        resolver.refresh_inode_map();
+        resolver
+    }
+
+    /// Call once per collect cycle to periodically refresh the cgroup inode map.
+    pub fn tick(&mut self) {
+        if let Some(lookup) = &self.lookup {
+            let now = Instant::now();
+            for (id, name) in lookup.answers() {
+                // Dropped meanwhile if the container went away
+                let Some(state) = self.names.get_mut(&id) else {
+                    continue;
+                };
+                let failures = match state {
+                    NameState::Asked { failures } => *failures,
+                    _ => 0,
+                };
+                *state = match name {
+                    Some(name) => NameState::Known(name),
+                    None => NameState::Unknown {
+                        failures: failures + 1,
+                        retry_at: now + name_retry_delay(failures + 1),
+                    },
+                };
+            }
+        }
+        self.cycles_since_refresh += 1;
+        if self.cycles_since_refresh >= 10 {
+            self.refresh_inode_map();
+            self.cycles_since_refresh = 0;
+            // Forget containers that are gone, so their IDs don't pile up
+            self.names.retain(|id, _| self.seen.contains(id));
+            self.seen.clear();
+        }
+    }
+
+    /// Resolve a cgroup inode ID (from BPF) to container info.
+    /// Returns None for non-container cgroups.
+    pub fn resolve(&mut self, cgroup_id: u64) -> Option<ContainerInfo> {
+        if cgroup_id == 0 {
+            return None;
+        }
+
+        let path = self.id_to_path.get(&cgroup_id)?;
+
+        let mut info = match self.path_to_container.get(path) {
+            Some(cached) => cached.clone(),
+            None => {
+                let info = parse_container_from_cgroup(path);
+                self.path_to_container.insert(path.clone(), info.clone());
+                info
+            }
+        }?;
+
+        if !self.seen.contains(&info.id) {
+            self.seen.insert(info.id.clone());
+        }
+        let ask = match self.names.get(&info.id) {
+            Some(NameState::Known(known)) => {
+                if let Some(name) = &known.name {
+                    info.name = name.clone();
+                }
+                info.image = known.image.clone();
+                info.pod = known.pod.clone();
+                None
+            }
+            Some(NameState::Asked { .. }) => None,
+            Some(&NameState::Unknown { failures, retry_at }) => {
+                (Instant::now() >= retry_at).then_some(failures)
+            }
+            None => Some(0),
+        };
+        if let Some(failures) = ask {
+            self.names.insert(info.id.clone(), NameState::Asked { failures });
+            self.lookup
+                .get_or_insert_with(NameLookup::spawn)
+                .request(info.runtime, &info.id, info.pod_uid.as_deref());
+        }
+        Some(info)
+    }
+
+    /// Also return the cgroup path string for a given cgroup_id.
+    pub fn resolve_path(&self, cgroup_id: u64) -> String {
+        self.id_to_path
+            .get(&cgroup_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Walk /sys/fs/cgroup/ and map inode numbers to cgroup paths.
+    fn refresh_inode_map(&mut self) {
+        self.id_to_path.clear();
+        let base = Path::new("/sys/fs/cgroup");
+        if base.exists() {
+            self.walk_cgroup_tree(base, base);
+        }
+    }
+
+    fn walk_cgroup_tree(&mut self, dir: &Path, base: &Path) {
+        let entries = match fs::read_dir(dir) {
+            Ok(e) => e,
+            Err(_) => return,
+        };
+
+        // Record this directory's inode
+        if let Ok(meta) = fs::metadata(dir) {
+            let ino = meta.ino();
+            let relative = dir
+                .strip_prefix(base)
+                .map(|p| format!("/{}", p.display()))
+                .unwrap_or_else(|_| "/".to_string());
+            self.id_to_path.insert(ino, relative);
+        }
+
+        for entry in entries {
+            let entry = match entry {
+                Ok(e) => e,
+                Err(_) => continue,
+            };
+            let path = entry.path();
+            if path.is_dir() {
+                self.walk_cgroup_tree(&path, base);
+            }
+        }
+    }
+}
+
+/// Parse container ID/name from a cgroup path.
+///
+/// Docker:     /system.slice/docker-<id>.scope
+/// Containerd: /system.slice/containerd-<id>.scope
+/// Podman:     /user.slice/user-1000.slice/.../libpod-<id>.scope
+/// k8s:        /kubepods/burstable/pod<uid>/<container-id>
+///             /kubepods.slice/.../kubepods-burstable-pod<uid>.slice/cri-containerd-<id>.scope
+pub fn parse_container_from_cgroup(cgroup_path: &str) -> Option<ContainerInfo> {
+    let (id, runtime) = parse_container_id(cgroup_path)?;
+    Some(ContainerInfo {
+        name: short_id(&id),
+        id,
+        image: None,
+        pod_uid: pod_uid_from_cgroup(cgroup_path),
+        pod: None,
+        runtime,
+    })
+}
+
+fn parse_container_id(cgroup_path: &str) -> Option<(String, ContainerRuntime)> {
+    // Docker pattern
+    if let Some(id) = extract_scope_id(cgroup_path, "docker-") {
+        return Some((id, ContainerRuntime::Docker));
+    }
+
+    // Containerd pattern
+    if let Some(id) = extract_scope_id(cgroup_path, "containerd-") {
+        return Some((id, ContainerRuntime::Containerd));
+    }
+
+    // Podman pattern
+    if let Some(id) = extract_scope_id(cgroup_path, "libpod-") {
+        return Some((id, ContainerRuntime::Podman));
+    }
+
+    // Kubernetes pattern: /kubepods/.../pod<uid>/<container-id>, or a
+    // runtime-named scope under the pod's slice with the systemd driver
+    if cgroup_path.contains("kubepods") {
+        let last = cgroup_path.rsplit('/').next().unwrap_or("");
+        if !last.is_empty() && !last.starts_with("pod") && !last.starts_with("kubepods") {
+            let last = last.trim_end_matches(".scope");
+            if let Some(id) = last.strip_prefix("cri-containerd-") {
+                return Some((id.to_string(), ContainerRuntime::Containerd));
+            }
+            // conmon gets a scope of its own next to the container's
+            if let Some(id) = last.strip_prefix("crio-") {
+                let id = id.strip_prefix("conmon-").unwrap_or(id);
+                return Some((id.to_string(), ContainerRuntime::CriO));
+            }
+            // The cgroupfs driver doesn't say which CRI runtime it is
+            return Some((last.to_string(), ContainerRuntime::Containerd));
+        }
+    }
+
+    None
+}
+
+/// UID of the Kubernetes pod whose cgroup holds `cgroup_path`: a
+/// `pod<uid>` directory, or `kubepods-<qos>-pod<uid>.slice` with dashes
+/// turned to underscores under the systemd driver.
+pub fn pod_uid_from_cgroup(cgroup_path: &str) -> Option<String> {
+    if !cgroup_path.contains("kubepods") {
+        return None;
+    }
+    cgroup_path.split('/').find_map(|segment| {
+        let uid = match segment.strip_suffix(".slice") {
+            Some(slice) => slice.rsplit_once("-pod")?.1.replace('_', "-"),
+            None => segment.strip_prefix("pod")?.to_string(),
+        };
+        (!uid.is_empty()).then_some(uid)
+    })
+}
+
+pub fn extract_scope_id(path: &str, prefix: &str) -> Option<String> {
+    for segment in path.split('/') {
+        if let Some(rest) = segment.strip_prefix(prefix) {
+            let id = rest.trim_end_matches(".scope");
+            if !id.is_empty() {
+                return Some(id.to_string());
+            }
+        }
+    }
+    None
+}
+
+/// Resolve a systemd unit name from a cgroup path string.
+pub fn resolve_service_from_path(path: &str, mode: ServiceDisplayMode) -> Option<String> {
+    if path.is_empty() {
+        return None;
+    }
+    match mode {
+        ServiceDisplayMode::ServiceOnly => {
+            path.rsplit('/')
+                .find(|seg| seg.ends_with(".service"))
+                .map(|seg| seg.strip_suffix(".service").unwrap().to_string())
+        }
+        ServiceDisplayMode::AllUnits => {
+            path.rsplit('/')
+                .find(|seg| UNIT_SUFFIXES.iter().any(|suf| seg.ends_with(suf)))
+                .map(|seg| seg.to_string())
+        }
+        ServiceDisplayMode::FullSlice => {
+            let trimmed = path.trim_start_matches('/');
+            if trimmed.is_empty() {
+                None
+            } else {
+                Some(trimmed.to_string())
+            }
+        }
+    }
+}
+
+/// First 12 characters of a container ID. Cgroup names are arbitrary
+/// text, so this counts chars rather than slicing bytes.
+fn short_id(id: &str) -> String {
+    id.chars().take(12).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn container_from_known_layouts() {
+        let id = "0123456789abcdef0123456789abcdef";
+        let docker = parse_container_from_cgroup(&format!("/system.slice/docker-{id}.scope")).unwrap();
+        assert_eq!(docker.name, "0123456789ab");
+        assert_eq!(docker.runtime, ContainerRuntime::Docker);
+        let podman =
+            parse_container_from_cgroup(&format!("/user.slice/user-1000.slice/user@1000.service/libpod-{id}.scope"))
+                .unwrap();
+        assert_eq!(podman.runtime, ContainerRuntime::Podman);
+        let k8s = parse_container_from_cgroup(&format!("/kubepods/burstable/pod1234/{id}")).unwrap();
+        assert_eq!(k8s.name, "0123456789ab");
+        assert_eq!(k8s.pod_uid.as_deref(), Some("1234"));
+        assert!(parse_container_from_cgroup("/kubepods/burstable/pod1234").is_none());
+        let systemd = parse_container_from_cgroup(&format!(
+            "/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod5f1c_02ab.slice/cri-containerd-{id}.scope"
+        ))
+        .unwrap();
+        assert_eq!(systemd.name, "0123456789ab");
+        assert_eq!(systemd.runtime, ContainerRuntime::Containerd);
+        assert_eq!(systemd.pod_uid.as_deref(), Some("5f1c-02ab"));
+        let crio = parse_container_from_cgroup(&format!(
+            "/kubepods.slice/kubepods-pod77.slice/crio-conmon-{id}.scope"
+        ))
+        .unwrap();
+        assert_eq!((crio.id.as_str(), crio.runtime), (id, ContainerRuntime::CriO));
+        assert!(parse_container_from_cgroup("/kubepods.slice/kubepods-burstable.slice").is_none());
+        assert!(parse_container_from_cgroup("/system.slice/sshd.service").is_none());
+    }
+
+    #[test]
+    fn name_retries_back_off() {
+        assert_eq!(name_retry_delay(1), NAME_RETRY_MIN);
+        assert_eq!(name_retry_delay(2), NAME_RETRY_MIN * 2);
+        assert_eq!(name_retry_delay(4), NAME_RETRY_MIN * 8);
+        assert_eq!(name_retry_delay(20), NAME_RETRY_MAX);
+        assert_eq!(name_retry_delay(u32::MAX), NAME_RETRY_MAX);
+    }
+
+    proptest! {
+        // Cgroup names are whatever their creator chose, so any text must
+        // parse without panicking
+        #[test]
+        fn container_parse_never_panics(path in "\\PC*") {
+            let _ = parse_container_from_cgroup(&path);
+        }
+
+        #[test]
+        fn container_name_is_a_short_prefix_of_the_id(
+            prefix in "(docker|containerd|libpod)-",
+            id in "[^/]{1,80}",
+            parent in "(/[a-z.@0-9]{1,20}){0,4}",
+        ) {
+            prop_assume!(!id.ends_with(".scope"));
+            let info = parse_container_from_cgroup(&format!("{parent}/{prefix}{id}.scope")).unwrap();
+            prop_assert!(info.name.chars().count() <= 12);
+            prop_assert!(id.starts_with(&info.name));
+        }
+    }
+}
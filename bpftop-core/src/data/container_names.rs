@@ -0,0 +1,246 @@
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use bollard::{Docker, API_DEFAULT_VERSION};
+
+use super::container::ContainerRuntime;
+
+/// Where containerd's runc shims keep each running container's OCI
+/// bundle, one directory per namespace ("moby", "k8s.io", "default").
+const CONTAINERD_TASKS: &str = "/run/containerd/io.containerd.runtime.v2.task";
+/// Where CRI-O keeps each container's OCI config.
+const CRIO_CONTAINERS: &str = "/run/containers/storage/overlay-containers";
+/// The kubelet's per-pod state, one directory per pod UID.
+const KUBELET_PODS: &str = "/var/lib/kubelet/pods";
+/// Seconds to wait on a Docker or Podman API socket.
+const API_TIMEOUT_SECS: u64 = 2;
+
+/// What a container's runtime (or the kubelet) knows about it beyond its
+/// ID.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContainerName {
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub pod: Option<Pod>,
+}
+
+/// The Kubernetes pod a container runs in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pod {
+    pub name: String,
+    /// Unknown when only the kubelet's pod directory named the pod.
+    pub namespace: Option<String>,
+}
+
+/// Asks container runtimes for names on a background thread. The Docker
+/// and Podman APIs answer over a socket and can stall on a busy daemon,
+/// which the collector must not.
+pub struct NameLookup {
+    tx: Sender<Request>,
+    rx: Receiver<(String, Option<ContainerName>)>,
+}
+
+impl NameLookup {
+    pub fn spawn() -> Self {
+        let (tx, requests) = mpsc::channel::<Request>();
+        let (answers, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let Ok(rt) = tokio::runtime::Builder::new_current_thread().enable_all().build() else {
+                return;
+            };
+            for Request { runtime, id, pod_uid } in requests {
+                let name = rt.block_on(lookup(runtime, &id, pod_uid.as_deref()));
+                if answers.send((id, name)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { tx, rx }
+    }
+
+    /// Queue a lookup of the full container ID `id`, in pod `pod_uid`
+    /// if it runs under Kubernetes.
+    pub fn request(&self, runtime: ContainerRuntime, id: &str, pod_uid: Option<&str>) {
+        let _ = self.tx.send(Request {
+            runtime,
+            id: id.to_string(),
+            pod_uid: pod_uid.map(str::to_string),
+        });
+    }
+
+    /// Answers that arrived since the last call, `None` for containers no
+    /// runtime knew.
+    pub fn answers(&self) -> impl Iterator<Item = (String, Option<ContainerName>)> + '_ {
+        self.rx.try_iter()
+    }
+}
+
+struct Request {
+    runtime: ContainerRuntime,
+    id: String,
+    pod_uid: Option<String>,
+}
+
+async fn lookup(runtime: ContainerRuntime, id: &str, pod_uid: Option<&str>) -> Option<ContainerName> {
+    let mut found = match runtime {
+        ContainerRuntime::Docker => inspect(&docker_socket(), id).await,
+        ContainerRuntime::Podman => {
+            for socket in podman_sockets() {
+                if let Some(name) = inspect(&socket, id).await {
+                    return Some(name);
+                }
+            }
+            None
+        }
+        // A kubepods cgroup under the cgroupfs driver doesn't say which
+        // CRI runtime made it, so try both
+        ContainerRuntime::Containerd | ContainerRuntime::CriO => {
+            from_containerd_bundle(id).or_else(|| from_crio_config(id))
+        }
+        ContainerRuntime::Unknown => None,
+    };
+    if let Some(uid) = pod_uid {
+        if found.as_ref().is_none_or(|f| f.pod.is_none()) {
+            if let Some(pod) = pod_from_kubelet(uid) {
+                found.get_or_insert_with(ContainerName::default).pod = Some(pod);
+            }
+        }
+    }
+    found
+}
+
+/// `GET /containers/{id}/json` on a Docker-compatible API socket. Podman
+/// serves the same API, rootful and per user. Kubernetes on Docker
+/// (cri-dockerd) labels its containers with their pod.
+async fn inspect(socket: &str, id: &str) -> Option<ContainerName> {
+    if !Path::new(socket).exists() {
+        return None;
+    }
+    let docker = Docker::connect_with_unix(socket, API_TIMEOUT_SECS, API_DEFAULT_VERSION).ok()?;
+    let info = docker.inspect_container(id, None).await.ok()?;
+    let config = info.config.unwrap_or_default();
+    let labels = config.labels.unwrap_or_default();
+    let label = |key: &str| labels.get(key).cloned();
+    let name = label("io.kubernetes.container.name")
+        .or_else(|| info.name.map(|n| n.trim_start_matches('/').to_string()))
+        .filter(|n| !n.is_empty())?;
+    Some(ContainerName {
+        name: Some(name),
+        image: config.image,
+        pod: label("io.kubernetes.pod.name").map(|name| Pod {
+            name,
+            namespace: label("io.kubernetes.pod.namespace"),
+        }),
+    })
+}
+
+/// The Docker socket, honouring a `unix://` DOCKER_HOST.
+fn docker_socket() -> String {
+    std::env::var("DOCKER_HOST")
+        .ok()
+        .and_then(|host| host.strip_prefix("unix://").map(str::to_string))
+        .unwrap_or_else(|| "/var/run/docker.sock".to_string())
+}
+
+/// The rootful Podman socket, then every user's rootless one.
+fn podman_sockets() -> Vec<String> {
+    let mut sockets = vec!["/run/podman/podman.sock".to_string()];
+    if let Ok(users) = fs::read_dir("/run/user") {
+        for user in users.flatten() {
+            sockets.push(format!("{}/podman/podman.sock", user.path().display()));
+        }
+    }
+    sockets
+}
+
+/// containerd's own API is gRPC; the bundle its shim hands to runc carries
+/// the same name and image as OCI annotations, without a client.
+fn from_containerd_bundle(id: &str) -> Option<ContainerName> {
+    for ns in fs::read_dir(CONTAINERD_TASKS).ok()?.flatten() {
+        if let Ok(config) = fs::read_to_string(ns.path().join(id).join("config.json")) {
+            return name_from_annotations(&config);
+        }
+    }
+    None
+}
+
+fn from_crio_config(id: &str) -> Option<ContainerName> {
+    let config = fs::read_to_string(format!("{CRIO_CONTAINERS}/{id}/userdata/config.json")).ok()?;
+    name_from_annotations(&config)
+}
+
+/// Name, image and pod from an OCI config's annotations, as set by
+/// containerd's CRI plugin, CRI-O or nerdctl.
+fn name_from_annotations(config: &str) -> Option<ContainerName> {
+    let config: serde_json::Value = serde_json::from_str(config).ok()?;
+    let annotations = config.get("annotations")?;
+    let get = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| annotations.get(key)?.as_str())
+            .map(str::to_string)
+    };
+    let name = get(&["io.kubernetes.cri.container-name", "io.kubernetes.container.name", "nerdctl/name"])?;
+    Some(ContainerName {
+        name: Some(name),
+        image: get(&["io.kubernetes.cri.image-name", "io.kubernetes.cri-o.ImageName"]),
+        pod: get(&["io.kubernetes.cri.sandbox-name", "io.kubernetes.pod.name"]).map(|name| Pod {
+            name,
+            namespace: get(&["io.kubernetes.cri.sandbox-namespace", "io.kubernetes.pod.namespace"]),
+        }),
+    })
+}
+
+/// The pod's name from the hosts file the kubelet writes for it, whose
+/// last entry is the pod's own hostname. Host-network pods share the
+/// node's /etc/hosts instead and stay unnamed.
+fn pod_from_kubelet(uid: &str) -> Option<Pod> {
+    let hosts = fs::read_to_string(format!("{KUBELET_PODS}/{uid}/etc-hosts")).ok()?;
+    if !hosts.starts_with("# Kubernetes-managed hosts file") {
+        return None;
+    }
+    let name = hosts
+        .lines()
+        .rfind(|line| !line.trim().is_empty() && !line.starts_with('#'))?
+        .split_whitespace()
+        .nth(1)?;
+    Some(Pod {
+        name: name.to_string(),
+        namespace: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_from_oci_annotations() {
+        let cri = r#"{"ociVersion":"1.1.0","annotations":{
+            "io.kubernetes.cri.container-type":"container",
+            "io.kubernetes.cri.container-name":"coredns",
+            "io.kubernetes.cri.sandbox-name":"coredns-76f75df574-x2x8q",
+            "io.kubernetes.cri.sandbox-namespace":"kube-system",
+            "io.kubernetes.cri.image-name":"registry.k8s.io/coredns/coredns:v1.11.1"}}"#;
+        assert_eq!(
+            name_from_annotations(cri),
+            Some(ContainerName {
+                name: Some("coredns".to_string()),
+                image: Some("registry.k8s.io/coredns/coredns:v1.11.1".to_string()),
+                pod: Some(Pod {
+                    name: "coredns-76f75df574-x2x8q".to_string(),
+                    namespace: Some("kube-system".to_string()),
+                }),
+            })
+        );
+        let crio = r#"{"annotations":{"io.kubernetes.container.name":"etcd",
+            "io.kubernetes.pod.name":"etcd-node1","io.kubernetes.pod.namespace":"kube-system"}}"#;
+        let crio = name_from_annotations(crio).unwrap();
+        assert_eq!(crio.pod.unwrap().namespace.as_deref(), Some("kube-system"));
+        let nerdctl = r#"{"annotations":{"nerdctl/name":"web"}}"#;
+        assert_eq!(name_from_annotations(nerdctl).unwrap().name.as_deref(), Some("web"));
+        // Pause containers and plain `ctr run` carry no name
+        assert_eq!(name_from_annotations(r#"{"annotations":{}}"#), None);
+        assert_eq!(name_from_annotations("{}"), None);
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::{HashMap, VecDeque};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use bpftop_common::DnsEvent;
+
+/// Queries kept per process for the detail view.
+pub const DNS_QUERIES_PER_PID: usize = 32;
+
+const AF_INET: u16 = 2;
+const AF_INET6: u16 = 10;
+
+/// One DNS question a process sent.
+#[derive(Debug, Clone)]
+pub struct DnsQuery {
+    pub name: String,
+    pub qtype: u16,
+    /// Resolver it was sent to (127.0.0.53 for systemd-resolved).
+    pub server: Option<IpAddr>,
+    /// Seconds since boot, comparable with `SystemInfo::uptime_secs`.
+    pub time_secs: f64,
+}
+
+impl DnsQuery {
+    /// `None` if the payload isn't a DNS query (something else talking to
+    /// port 53, or a name longer than the captured bytes).
+    pub fn from_raw(ev: &DnsEvent) -> Option<Self> {
+        let len = (ev.len as usize).min(ev.payload.len());
+        let (name, qtype) = parse_query(&ev.payload[..len])?;
+        let server = match ev.family {
+            AF_INET => {
+                let a: [u8; 4] = ev.daddr[..4].try_into().ok()?;
+                Some(IpAddr::V4(Ipv4Addr::from(a)))
+            }
+            AF_INET6 => Some(IpAddr::V6(Ipv6Addr::from(ev.daddr))),
+            _ => None,
+        };
+        Some(Self {
+            name,
+            qtype,
+            server,
+            time_secs: ev.timestamp_ns as f64 / 1_000_000_000.0,
+        })
+    }
+
+    /// Record type mnemonic, like dig prints it.
+    pub fn type_label(&self) -> String {
+        match self.qtype {
+            1 => "A".to_string(),
+            2 => "NS".to_string(),
+            5 => "CNAME".to_string(),
+            6 => "SOA".to_string(),
+            12 => "PTR".to_string(),
+            15 => "MX".to_string(),
+            16 => "TXT".to_string(),
+            28 => "AAAA".to_string(),
+            33 => "SRV".to_string(),
+            64 => "SVCB".to_string(),
+            65 => "HTTPS".to_string(),
+            255 => "ANY".to_string(),
+            n => format!("TYPE{n}"),
+        }
+    }
+}
+
+/// Name and type of the first question in a DNS query message, or `None`
+/// for responses and anything malformed. Bytes outside printable ASCII
+/// (and dots inside labels) are escaped as `\DDD`, as in zone files.
+pub fn parse_query(msg: &[u8]) -> Option<(String, u16)> {
+    let header = msg.get(..12)?;
+    // QR set means a response; QDCOUNT 0 means no question
+    if header[2] & 0x80 != 0 || u16::from_be_bytes([header[4], header[5]]) == 0 {
+        return None;
+    }
+    let mut name = String::new();
+    let mut pos = 12;
+    loop {
+        let len = *msg.get(pos)? as usize;
+        pos += 1;
+        if len == 0 {
+            break;
+        }
+        // Longer labels are compression pointers, which a lone question
+        // has nothing to point back to
+        if len > 63 {
+            return None;
+        }
+        let label = msg.get(pos..pos + len)?;
+        if !name.is_empty() {
+            name.push('.');
+        }
+        for &b in label {
+            if b.is_ascii_graphic() && b != b'.' && b != b'\\' {
+                name.push(b as char);
+            } else {
+                name.push_str(&format!("\\{b:03}"));
+            }
+        }
+        pos += len;
+    }
+    let qtype = u16::from_be_bytes([*msg.get(pos)?, *msg.get(pos + 1)?]);
+    if name.is_empty() {
+        name.push('.');
+    }
+    Some((name, qtype))
+}
+
+/// Recent queries per process, kept across refreshes so the detail view
+/// has history as soon as it opens.
+#[derive(Default)]
+pub struct DnsLog {
+    by_pid: HashMap<u32, VecDeque<DnsQuery>>,
+}
+
+impl DnsLog {
+    pub fn record(&mut self, events: &[DnsEvent]) {
+        for ev in events {
+            let Some(query) = DnsQuery::from_raw(ev) else {
+                continue;
+            };
+            let log = self.by_pid.entry(ev.pid).or_default();
+            log.push_back(query);
+            if log.len() > DNS_QUERIES_PER_PID {
+                log.pop_front();
+            }
+        }
+    }
+
+    /// Forget processes that have exited.
+    pub fn retain(&mut self, live: impl Fn(u32) -> bool) {
+        self.by_pid.retain(|&pid, _| live(pid));
+    }
+
+    /// Queries of `pid`, newest first.
+    pub fn recent(&self, pid: u32) -> Vec<DnsQuery> {
+        self.by_pid
+            .get(&pid)
+            .map(|log| log.iter().rev().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// A query as a stub resolver sends it: header, question, no EDNS.
+    fn query(labels: &[&[u8]], qtype: u16) -> Vec<u8> {
+        let mut msg = vec![0x12, 0x34, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+        for label in labels {
+            msg.push(label.len() as u8);
+            msg.extend_from_slice(label);
+        }
+        msg.push(0);
+        msg.extend_from_slice(&qtype.to_be_bytes());
+        msg.extend_from_slice(&1u16.to_be_bytes());
+        msg
+    }
+
+    #[test]
+    fn parses_question() {
+        let msg = query(&[b"api", b"example", b"com"], 28);
+        assert_eq!(parse_query(&msg), Some(("api.example.com".to_string(), 28)));
+        assert_eq!(parse_query(&query(&[], 2)), Some((".".to_string(), 2)));
+        // Dots and spaces inside a label can't be mistaken for separators
+        assert_eq!(
+            parse_query(&query(&[b"a.b c"], 16)),
+            Some(("a\\046b\\032c".to_string(), 16))
+        );
+
+        let mut response = msg.clone();
+        response[2] |= 0x80;
+        assert_eq!(parse_query(&response), None);
+        // Cut off inside the name or before the type
+        assert_eq!(parse_query(&msg[..16]), None);
+        assert_eq!(parse_query(&msg[..msg.len() - 3]), None);
+        let mut pointer = msg[..12].to_vec();
+        pointer.extend_from_slice(&[0xc0, 0x0c, 0, 1, 0, 1]);
+        assert_eq!(parse_query(&pointer), None);
+    }
+
+    proptest! {
+        // The payload is whatever a process sent to port 53, cut at the
+        // capture size
+        #[test]
+        fn parse_query_never_panics(msg in prop::collection::vec(any::<u8>(), 0..300)) {
+            let _ = parse_query(&msg);
+        }
+
+        #[test]
+        fn parse_query_round_trips(
+            labels in prop::collection::vec("[a-z0-9-]{1,20}", 1..6),
+            qtype in any::<u16>(),
+        ) {
+            let raw: Vec<&[u8]> = labels.iter().map(|l| l.as_bytes()).collect();
+            prop_assert_eq!(parse_query(&query(&raw, qtype)), Some((labels.join("."), qtype)));
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use bpftop_common::ExecEvent;
+
+/// Maximum exec records kept for the recent-execs panel.
+pub const EXEC_LOG_CAPACITY: usize = 1000;
+
+/// One exec seen by the sched_process_exec tracepoint.
+#[derive(Debug, Clone)]
+pub struct ExecRecord {
+    pub pid: u32,
+    pub ppid: u32,
+    #[allow(dead_code)]
+    pub uid: u32,
+    pub user: String,
+    pub cmdline: String,
+    /// Seconds since boot, comparable with `SystemInfo::uptime_secs`.
+    pub time_secs: f64,
+}
+
+impl ExecRecord {
+    pub fn from_raw(ev: &ExecEvent, user: String) -> Self {
+        let nul = ev.comm.iter().position(|&b| b == 0).unwrap_or(ev.comm.len());
+        let comm = String::from_utf8_lossy(&ev.comm[..nul]).to_string();
+
+        // argv is NUL-separated; show it space-separated like the table does
+        let len = (ev.len as usize).min(ev.cmdline.len());
+        let cmdline: String = ev.cmdline[..len]
+            .iter()
+            .map(|&b| if b == 0 { ' ' } else { b as char })
+            .collect::<String>()
+            .trim()
+            .to_string();
+
+        Self {
+            pid: ev.pid,
+            ppid: ev.ppid,
+            uid: ev.uid,
+            user,
+            cmdline: if cmdline.is_empty() { format!("[{comm}]") } else { cmdline },
+            time_secs: ev.timestamp_ns as f64 / 1_000_000_000.0,
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use bpftop_common::ExitEvent;
+
+/// Refreshes a process stays (greyed out) in the table after exiting.
+pub const DYING_REFRESHES: u8 = 2;
+
+/// A thread group leader exit seen by the sched_process_exit tracepoint.
+#[derive(Debug, Clone)]
+pub struct ExitRecord {
+    pub pid: u32,
+    /// Raw do_exit code, in wait(2) status encoding.
+    pub code: u32,
+    pub lifetime_secs: f64,
+}
+
+impl ExitRecord {
+    pub fn from_raw(ev: &ExitEvent) -> Self {
+        Self {
+            pid: ev.pid,
+            code: ev.exit_code,
+            lifetime_secs: ev.lifetime_ns as f64 / 1_000_000_000.0,
+        }
+    }
+
+    /// Short status like "exit 0", "exit 137" or "SIGSEGV (core)".
+    pub fn status_label(&self) -> String {
+        let signal = (self.code & 0x7f) as i32;
+        if signal == 0 {
+            return format!("exit {}", (self.code >> 8) & 0xff);
+        }
+        let name = nix::sys::signal::Signal::try_from(signal)
+            .map(|s| s.as_str().to_string())
+            .unwrap_or_else(|_| format!("signal {signal}"));
+        if self.code & 0x80 != 0 {
+            format!("{name} (core)")
+        } else {
+            name
+        }
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use bpftop_common::{FileInfo, TcpConn};
+
+const AF_UNIX: u8 = 1;
+const AF_INET: u8 = 2;
+const AF_INET6: u8 = 10;
+
+pub const SOCK_STREAM: u8 = 1;
+pub const SOCK_DGRAM: u8 = 2;
+
+/// `skc_state` of a listening TCP socket.
+pub const TCP_LISTEN: u8 = 10;
+
+/// `OpenFile.fd` of a TCP socket no descriptor refers to any more (closed
+/// but still shutting down, or not yet accepted).
+pub const NO_FD: u32 = u32::MAX;
+
+/// What an open file descriptor refers to (`FileInfo.fd_type`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FdKind {
+    Regular,
+    Socket,
+    Pipe,
+    Other,
+}
+
+impl FdKind {
+    pub fn from_raw(fd_type: u8) -> Self {
+        match fd_type {
+            0 => Self::Regular,
+            1 => Self::Socket,
+            2 => Self::Pipe,
+            _ => Self::Other,
+        }
+    }
+
+    /// Short lsof-style type label.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Regular => "REG",
+            Self::Socket => "SOCK",
+            Self::Pipe => "FIFO",
+            Self::Other => "OTHER",
+        }
+    }
+}
+
+/// Endpoint details for an AF_INET/AF_INET6 socket.
+#[derive(Debug, Clone)]
+pub struct SocketInfo {
+    pub family: u8,
+    pub sock_type: u8,
+    /// Kernel `skc_state` (TCP state numbering, also used by UDP).
+    pub state: u8,
+    pub local: SocketAddr,
+    pub remote: SocketAddr,
+    /// Smoothed TCP round-trip time in microseconds (0 if not measured).
+    pub srtt_us: u32,
+    /// TCP congestion window in segments.
+    pub cwnd: u32,
+}
+
+impl SocketInfo {
+    /// Protocol label, e.g. "TCP", "UDP6".
+    pub fn protocol(&self) -> &'static str {
+        match (self.sock_type, self.family) {
+            (SOCK_STREAM, AF_INET) => "TCP",
+            (SOCK_STREAM, AF_INET6) => "TCP6",
+            (SOCK_DGRAM, AF_INET) => "UDP",
+            (SOCK_DGRAM, AF_INET6) => "UDP6",
+            (_, AF_INET6) => "RAW6",
+            _ => "RAW",
+        }
+    }
+
+    /// Human-readable connection state. UDP sockets only distinguish
+    /// connected (ESTABLISHED) from unconnected (CLOSE).
+    pub fn state_name(&self) -> &'static str {
+        match self.state {
+            1 => "ESTABLISHED",
+            2 => "SYN_SENT",
+            3 => "SYN_RECV",
+            4 => "FIN_WAIT1",
+            5 => "FIN_WAIT2",
+            6 => "TIME_WAIT",
+            7 => "CLOSE",
+            8 => "CLOSE_WAIT",
+            9 => "LAST_ACK",
+            10 => "LISTEN",
+            11 => "CLOSING",
+            12 => "NEW_SYN_RECV",
+            _ => "UNKNOWN",
+        }
+    }
+
+    /// Smoothed RTT for display, e.g. "0.42ms"; empty until TCP has a sample.
+    pub fn rtt_label(&self) -> String {
+        match self.srtt_us {
+            0 => String::new(),
+            us if us < 10_000 => format!("{:.2}ms", us as f64 / 1000.0),
+            us if us < 1_000_000 => format!("{:.1}ms", us as f64 / 1000.0),
+            us => format!("{:.2}s", us as f64 / 1_000_000.0),
+        }
+    }
+
+    /// Congestion window for display; empty alongside an empty RTT, since
+    /// the window of a socket that never exchanged data means nothing.
+    pub fn cwnd_label(&self) -> String {
+        if self.srtt_us == 0 {
+            String::new()
+        } else {
+            self.cwnd.to_string()
+        }
+    }
+}
+
+/// One open file descriptor of a process, decoded from `FileInfo`.
+#[derive(Debug, Clone)]
+pub struct OpenFile {
+    pub pid: u32,
+    pub fd: u32,
+    pub kind: FdKind,
+    /// Path from d_path: "/real/path", "pipe:[ino]", "socket:[ino]", ...
+    pub path: String,
+    /// Socket address family (0 for non-sockets).
+    pub sock_family: u8,
+    /// Present for AF_INET/AF_INET6 sockets only.
+    pub socket: Option<SocketInfo>,
+    /// Network namespace inode for sockets (0 if unknown or not a socket).
+    pub netns: u32,
+}
+
+impl OpenFile {
+    /// Whether `info` is a TCP/UDP socket, checked before decoding it.
+    pub fn is_inet_socket(info: &FileInfo) -> bool {
+        FdKind::from_raw(info.fd_type) == FdKind::Socket
+            && (info.sock_family == AF_INET || info.sock_family == AF_INET6)
+    }
+
+    pub fn from_raw(info: &FileInfo) -> Self {
+        let nul = info.path.iter().position(|&b| b == 0).unwrap_or(info.path.len());
+        let path = String::from_utf8_lossy(&info.path[..nul]).into_owned();
+        let kind = FdKind::from_raw(info.fd_type);
+
+        let socket = if Self::is_inet_socket(info) {
+            Some(SocketInfo {
+                family: info.sock_family,
+                sock_type: info.sock_type,
+                state: info.sock_state,
+                local: decode_addr(info.sock_family, &info.src_addr, info.src_port),
+                remote: decode_addr(info.sock_family, &info.dst_addr, info.dst_port),
+                srtt_us: info.srtt_us,
+                cwnd: info.snd_cwnd,
+            })
+        } else {
+            None
+        };
+
+        Self {
+            pid: info.pid,
+            fd: info.fd,
+            kind,
+            path,
+            sock_family: info.sock_family,
+            socket,
+            netns: info.netns,
+        }
+    }
+
+    /// A socket from the TCP state tracker, owned by `pid`.
+    fn from_tcp_conn(conn: &TcpConn, pid: u32) -> Self {
+        Self {
+            pid,
+            fd: NO_FD,
+            kind: FdKind::Socket,
+            path: String::new(),
+            sock_family: conn.family,
+            socket: Some(SocketInfo {
+                family: conn.family,
+                sock_type: SOCK_STREAM,
+                state: conn.state,
+                local: decode_addr(conn.family, &conn.src_addr, conn.src_port),
+                remote: decode_addr(conn.family, &conn.dst_addr, conn.dst_port),
+                srtt_us: 0,
+                cwnd: 0,
+            }),
+            netns: conn.netns,
+        }
+    }
+
+    /// FD column text; "-" for sockets without one.
+    pub fn fd_label(&self) -> String {
+        if self.fd == NO_FD {
+            "-".to_string()
+        } else {
+            self.fd.to_string()
+        }
+    }
+
+    /// Type column text: the protocol for inet sockets, "UNIX" for unix
+    /// sockets, otherwise the FD kind.
+    pub fn type_label(&self) -> &'static str {
+        match &self.socket {
+            Some(sock) => sock.protocol(),
+            None if self.kind == FdKind::Socket && self.sock_family == AF_UNIX => "UNIX",
+            None => self.kind.label(),
+        }
+    }
+
+    /// One-line description used for display and filtering.
+    pub fn describe(&self) -> String {
+        match &self.socket {
+            Some(sock) if sock.state == TCP_LISTEN => format!("{} (LISTEN)", sock.local),
+            Some(sock) if sock.remote.port() == 0 => sock.local.to_string(),
+            Some(sock) => format!("{}->{} ({})", sock.local, sock.remote, sock.state_name()),
+            None => self.path.clone(),
+        }
+    }
+
+    /// Case-insensitive substring match against the FD number, type, and description.
+    pub fn matches(&self, query: &str) -> bool {
+        if query.is_empty() {
+            return true;
+        }
+        let q = query.to_lowercase();
+        self.fd.to_string().contains(&q)
+            || self.type_label().to_lowercase().contains(&q)
+            || self.describe().to_lowercase().contains(&q)
+    }
+}
+
+/// TCP sockets from the state tracker that none of `fd_sockets` (the FD
+/// walk's inet sockets) covers. Sockets created by a listener carry no
+/// pid and are credited to the process holding the listening port.
+pub fn fdless_tcp_sockets(fd_sockets: &[OpenFile], conns: &[TcpConn]) -> Vec<OpenFile> {
+    let mut seen = HashSet::new();
+    let mut listeners: HashMap<(u32, u16), u32> = HashMap::new();
+    for f in fd_sockets {
+        let Some(sock) = f.socket.as_ref().filter(|s| s.sock_type == SOCK_STREAM) else {
+            continue;
+        };
+        seen.insert((f.netns, sock.local, sock.remote));
+        if sock.state == TCP_LISTEN {
+            let owner = listeners.entry((f.netns, sock.local.port())).or_insert(f.pid);
+            *owner = (*owner).min(f.pid);
+        }
+    }
+    conns
+        .iter()
+        .filter_map(|conn| {
+            let file = OpenFile::from_tcp_conn(conn, conn.pid);
+            let sock = file.socket.as_ref()?;
+            if seen.contains(&(file.netns, sock.local, sock.remote)) {
+                return None;
+            }
+            let pid = match conn.pid {
+                0 => *listeners.get(&(file.netns, sock.local.port()))?,
+                pid => pid,
+            };
+            Some(OpenFile { pid, ..file })
+        })
+        .collect()
+}
+
+/// Network namespace inode of PID 1, i.e. the host's, from the
+/// `net:[4026531840]` link in /proc.
+pub fn host_netns() -> Option<u32> {
+    let link = fs::read_link("/proc/1/ns/net").ok()?;
+    link.to_str()?
+        .strip_prefix("net:[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+/// Decode an address as written by the BPF file iterator. Ports arrive
+/// in network byte order.
+pub fn decode_addr(family: u8, addr: &[u8; 16], port_be: u16) -> SocketAddr {
+    let port = u16::from_be(port_be);
+    let ip = if family == AF_INET6 {
+        IpAddr::V6(Ipv6Addr::from(*addr))
+    } else {
+        IpAddr::V4(Ipv4Addr::new(addr[0], addr[1], addr[2], addr[3]))
+    };
+    SocketAddr::new(ip, port)
+}
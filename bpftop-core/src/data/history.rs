@@ -0,0 +1,177 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use super::process::ProcessInfo;
+use super::system::SystemInfo;
+
+/// Samples kept per series: ten minutes at the default 1s refresh, and
+/// more than a full-width graph shows at two samples per cell.
+pub const HISTORY_SAMPLES: usize = 600;
+
+/// Rolling system-wide series for the header graphs, oldest sample first.
+#[derive(Debug, Clone, Default)]
+pub struct SystemHistory {
+    /// Total CPU busy %.
+    pub cpu: VecDeque<f64>,
+    /// Memory used %.
+    pub mem: VecDeque<f64>,
+    /// TCP/UDP throughput summed over all processes, bytes/s.
+    pub net: VecDeque<f64>,
+}
+
+impl SystemHistory {
+    /// Record one collect cycle.
+    pub fn push(&mut self, sys: &SystemInfo, processes: &[ProcessInfo]) {
+        let net = processes
+            .iter()
+            .filter(|p| !p.is_thread)
+            .map(|p| p.net_rate)
+            .sum();
+        for (series, value) in [
+            (&mut self.cpu, sys.cpu_total.total_pct),
+            (&mut self.mem, sys.memory.used_pct()),
+            (&mut self.net, net),
+        ] {
+            if series.len() == HISTORY_SAMPLES {
+                series.pop_front();
+            }
+            series.push_back(value);
+        }
+    }
+}
+
+/// Rolling series for one process, for the history popup.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessSeries {
+    /// CPU %, 100 = one core.
+    pub cpu: VecDeque<f64>,
+    /// Resident memory, bytes.
+    pub rss: VecDeque<f64>,
+    /// TCP/UDP throughput, bytes/s.
+    pub net: VecDeque<f64>,
+}
+
+/// Per-process series keyed by (pid, start time), so a recycled PID
+/// starts with an empty graph. Exited processes are dropped.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessHistory {
+    series: HashMap<(u32, u64), ProcessSeries>,
+}
+
+impl ProcessHistory {
+    /// Record one collect cycle.
+    pub fn push(&mut self, processes: &[ProcessInfo]) {
+        let mut series = HashMap::with_capacity(self.series.len());
+        for p in processes.iter().filter(|p| !p.is_thread) {
+            let key = (p.pid, p.start_time_ns);
+            let mut s = self.series.remove(&key).unwrap_or_default();
+            for (samples, value) in [
+                (&mut s.cpu, p.cpu_percent),
+                (&mut s.rss, p.res_bytes as f64),
+                (&mut s.net, p.net_rate),
+            ] {
+                if samples.len() == HISTORY_SAMPLES {
+                    samples.pop_front();
+                }
+                samples.push_back(value);
+            }
+            series.insert(key, s);
+        }
+        self.series = series;
+    }
+
+    pub fn get(&self, p: &ProcessInfo) -> Option<&ProcessSeries> {
+        self.series.get(&(p.pid, p.start_time_ns))
+    }
+}
+
+/// Minimum spacing between RSS samples; the trend only needs a handful of
+/// points per minute and this keeps memory flat at fast refresh rates.
+const RSS_SAMPLE_SECS: u64 = 10;
+
+/// Smallest total growth over the window that counts as a leak, so
+/// allocators settling by a few pages don't raise alerts.
+const LEAK_MIN_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Per-process RSS samples over a trailing window, for the GROW column and
+/// the leak alert. Keyed by (pid, start time) so a recycled PID starts over.
+#[derive(Debug)]
+pub struct RssTracker {
+    window: Duration,
+    samples: HashMap<(u32, u64), VecDeque<(Instant, u64)>>,
+}
+
+impl RssTracker {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            samples: HashMap::new(),
+        }
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    pub fn set_window(&mut self, window: Duration) {
+        self.window = window;
+    }
+
+    /// Record RSS for every live process and forget exited ones.
+    pub fn update(&mut self, now: Instant, processes: &[ProcessInfo]) {
+        let mut samples = HashMap::with_capacity(self.samples.len());
+        for p in processes.iter().filter(|p| !p.is_thread) {
+            let key = (p.pid, p.start_time_ns);
+            let mut series = self.samples.remove(&key).unwrap_or_default();
+            let due = series
+                .back()
+                .is_none_or(|&(t, _)| now.duration_since(t).as_secs() >= RSS_SAMPLE_SECS);
+            if due {
+                series.push_back((now, p.res_bytes));
+            }
+            while series
+                .front()
+                .is_some_and(|&(t, _)| now.duration_since(t) > self.window)
+            {
+                series.pop_front();
+            }
+            samples.insert(key, series);
+        }
+        self.samples = samples;
+    }
+
+    /// RSS growth in bytes/s from the oldest to the newest sample, or 0.0
+    /// when the process is shrinking or too new to tell.
+    pub fn growth(&self, p: &ProcessInfo) -> f64 {
+        let Some(series) = self.samples.get(&(p.pid, p.start_time_ns)) else {
+            return 0.0;
+        };
+        let (Some(&(t0, first)), Some(&(t1, last))) = (series.front(), series.back()) else {
+            return 0.0;
+        };
+        let secs = t1.duration_since(t0).as_secs_f64();
+        if secs <= 0.0 || last <= first {
+            return 0.0;
+        }
+        (last - first) as f64 / secs
+    }
+
+    /// Total growth if RSS never dropped across a (nearly) full window and
+    /// rose by at least `LEAK_MIN_BYTES`: the shape of a leak rather than a
+    /// cache warming up and plateauing.
+    pub fn leaked_bytes(&self, p: &ProcessInfo) -> Option<u64> {
+        let series = self.samples.get(&(p.pid, p.start_time_ns))?;
+        let &(t0, first) = series.front()?;
+        let &(t1, last) = series.back()?;
+        let span = t1.duration_since(t0);
+        if span + Duration::from_secs(RSS_SAMPLE_SECS) < self.window {
+            return None;
+        }
+        let monotonic = series
+            .iter()
+            .zip(series.iter().skip(1))
+            .all(|(a, b)| b.1 >= a.1);
+        let grown = last.saturating_sub(first);
+        (monotonic && grown >= LEAK_MIN_BYTES).then_some(grown)
+    }
+}
@@ -0,0 +1,17 @@
+pub mod bpf_objects;
+pub mod cgroup_control;
+pub mod clock;
+pub mod collector;
+pub mod container;
+pub mod container_names;
+pub mod dns;
+pub mod execs;
+pub mod exits;
+pub mod files;
+pub mod gpu;
+pub mod history;
+pub mod oom;
+pub mod perf;
+pub mod process;
+pub mod session;
+pub mod system;
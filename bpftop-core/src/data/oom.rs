@@ -0,0 +1,114 @@
+use bpftop_common::{ExitEvent, OomEvent};
+
+use super::process::{format_bytes, ProcessInfo};
+use crate::alerts::{Alert, Severity};
+
+/// Maximum OOM kills kept for the OOM log panel.
+pub const OOM_LOG_CAPACITY: usize = 100;
+
+/// One OOM kill seen by the oom:mark_victim tracepoint.
+#[derive(Debug, Clone)]
+pub struct OomRecord {
+    pub pid: u32,
+    /// Victim's name; empty when neither the kernel nor the process list
+    /// knew it.
+    pub comm: String,
+    /// Victim's owner, from the process list.
+    pub user: Option<String>,
+    /// RSS when killed (last seen RSS on kernels before 6.8); 0 if unknown.
+    pub rss_bytes: u64,
+    /// Container or service the victim ran in, else its cgroup.
+    pub group: Option<String>,
+    /// Task whose allocation invoked the OOM killer.
+    pub trigger_pid: u32,
+    pub trigger_comm: String,
+    /// Seconds since boot, comparable with `SystemInfo::uptime_secs`.
+    pub time_secs: f64,
+}
+
+impl OomRecord {
+    pub fn from_raw(ev: &OomEvent) -> Self {
+        Self {
+            pid: ev.pid,
+            comm: comm_string(&ev.comm),
+            user: None,
+            rss_bytes: ev.rss_kb * 1024,
+            group: None,
+            trigger_pid: ev.trigger_pid,
+            trigger_comm: comm_string(&ev.trigger_comm),
+            time_secs: ev.timestamp_ns as f64 / 1_000_000_000.0,
+        }
+    }
+
+    /// Fill in what the tracepoint didn't record: from the victim's row in
+    /// `processes` if it was still around for the walk, else the name from
+    /// its exit (usually moments after the kill).
+    pub fn resolve(&mut self, processes: &[ProcessInfo], exits: &[ExitEvent]) {
+        // The kernel reports the thread it picked, not always the leader
+        let Some(victim) = processes
+            .iter()
+            .find(|p| p.tid == self.pid)
+            .or_else(|| processes.iter().find(|p| p.pid == self.pid))
+        else {
+            if self.comm.is_empty() {
+                if let Some(ev) = exits.iter().find(|ev| ev.pid == self.pid) {
+                    self.comm = comm_string(&ev.comm);
+                }
+            }
+            return;
+        };
+        self.pid = victim.pid;
+        if self.comm.is_empty() {
+            self.comm = victim.comm.clone();
+        }
+        if self.rss_bytes == 0 {
+            self.rss_bytes = victim.res_bytes;
+        }
+        self.user = Some(victim.user.clone());
+        self.group = victim
+            .container
+            .clone()
+            .or_else(|| victim.service.clone())
+            .or_else(|| (!victim.cgroup_path.is_empty()).then(|| victim.cgroup_path.clone()));
+    }
+
+    /// "java (PID 1234)", or just the PID when the name is unknown.
+    pub fn victim_label(&self) -> String {
+        if self.comm.is_empty() {
+            format!("PID {}", self.pid)
+        } else {
+            format!("{} (PID {})", self.comm, self.pid)
+        }
+    }
+
+    pub fn alert(&self) -> Alert {
+        let mut message = format!("OOM killer killed {}", self.victim_label());
+        if self.rss_bytes > 0 {
+            message.push_str(&format!(", RSS {}", format_bytes(self.rss_bytes)));
+        }
+        if let Some(ref group) = self.group {
+            message.push_str(&format!(" in {group}"));
+        }
+        let mut fields = vec![
+            ("PID", self.pid.to_string()),
+            ("COMM", self.comm.clone()),
+            ("RSS", self.rss_bytes.to_string()),
+            ("TRIGGER_PID", self.trigger_pid.to_string()),
+            ("TRIGGER_COMM", self.trigger_comm.clone()),
+        ];
+        if let Some(ref group) = self.group {
+            fields.push(("GROUP", group.clone()));
+        }
+        Alert {
+            severity: Severity::Critical,
+            kind: "oom_kill",
+            message,
+            fields,
+        }
+    }
+}
+
+fn comm_string(comm: &[u8; 16]) -> String {
+    let nul = comm.iter().position(|&b| b == 0).unwrap_or(comm.len());
+    String::from_utf8_lossy(&comm[..nul]).to_string()
+}
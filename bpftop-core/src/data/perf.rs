@@ -0,0 +1,280 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::os::fd::{AsRawFd, FromRawFd};
+use std::time::Instant;
+
+const PERF_TYPE_HARDWARE: u32 = 0;
+/// Counted together in one group, leader first.
+const HW_EVENTS: [u64; 4] = [
+    0, // PERF_COUNT_HW_CPU_CYCLES
+    1, // PERF_COUNT_HW_INSTRUCTIONS
+    2, // PERF_COUNT_HW_CACHE_REFERENCES
+    3, // PERF_COUNT_HW_CACHE_MISSES
+];
+const PERF_FORMAT_TOTAL_TIME_ENABLED: u64 = 1 << 0;
+const PERF_FORMAT_TOTAL_TIME_RUNNING: u64 = 1 << 1;
+const PERF_FORMAT_GROUP: u64 = 1 << 3;
+/// `exclude_hv` in the attr bitfield; hypervisor time isn't the process's.
+const ATTR_EXCLUDE_HV: u64 = 1 << 6;
+const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+/// Threads counted per process; each costs one FD per event.
+const MAX_THREADS: usize = 128;
+
+/// FDs the counters may hold: half the soft RLIMIT_NOFILE, leaving the
+/// rest for /proc reads, BPF objects and the like.
+fn fd_budget() -> usize {
+    let mut limit = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+    // SAFETY: getrlimit only writes the struct it is given
+    if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) } != 0 {
+        return 1024 / 2;
+    }
+    usize::try_from(limit.rlim_cur).unwrap_or(usize::MAX) / 2
+}
+
+/// `struct perf_event_attr` up to `config1` (PERF_ATTR_SIZE_VER0), all
+/// plain counting needs.
+#[repr(C)]
+#[derive(Default)]
+struct PerfEventAttr {
+    kind: u32,
+    size: u32,
+    config: u64,
+    sample_period: u64,
+    sample_type: u64,
+    read_format: u64,
+    flags: u64,
+    wakeup_events: u32,
+    bp_type: u32,
+    config1: u64,
+}
+
+/// Hardware event rates of one process over the last refresh, summed
+/// over its threads.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PerfRates {
+    pub cycles_per_sec: f64,
+    pub instructions_per_sec: f64,
+    pub cache_refs_per_sec: f64,
+    pub cache_misses_per_sec: f64,
+}
+
+impl PerfRates {
+    /// Instructions per cycle; `None` if the process didn't run.
+    pub fn ipc(&self) -> Option<f64> {
+        (self.cycles_per_sec > 0.0).then(|| self.instructions_per_sec / self.cycles_per_sec)
+    }
+
+    /// Share of cache references that missed, in percent.
+    pub fn miss_percent(&self) -> Option<f64> {
+        (self.cache_refs_per_sec > 0.0).then(|| self.cache_misses_per_sec * 100.0 / self.cache_refs_per_sec)
+    }
+
+    /// Cache misses per thousand instructions.
+    pub fn misses_per_kilo_instr(&self) -> Option<f64> {
+        (self.instructions_per_sec > 0.0).then(|| self.cache_misses_per_sec * 1000.0 / self.instructions_per_sec)
+    }
+}
+
+/// One thread's counter group, read in one go.
+struct ThreadCounters {
+    leader: File,
+    _members: Vec<File>,
+    /// Scaled counts at the previous read.
+    prev: [u64; HW_EVENTS.len()],
+}
+
+impl ThreadCounters {
+    fn open(tid: u32) -> io::Result<Self> {
+        let leader = open_counter(tid, HW_EVENTS[0], -1)?;
+        let leader_fd = leader.as_raw_fd();
+        let members = HW_EVENTS[1..]
+            .iter()
+            .map(|&event| open_counter(tid, event, leader_fd))
+            .collect::<io::Result<_>>()?;
+        let mut counters = Self {
+            leader,
+            _members: members,
+            prev: [0; HW_EVENTS.len()],
+        };
+        counters.prev = counters.read()?;
+        Ok(counters)
+    }
+
+    /// Counts so far, scaled up for the time the PMU was shared with other
+    /// events (the kernel multiplexes when there are too few counters).
+    fn read(&mut self) -> io::Result<[u64; HW_EVENTS.len()]> {
+        // nr, time_enabled, time_running, then one value per event
+        let mut buf = [0u8; 8 * (3 + HW_EVENTS.len())];
+        let n = self.leader.read(&mut buf)?;
+        if n < buf.len() {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let word = |i: usize| u64::from_ne_bytes(buf[i * 8..i * 8 + 8].try_into().unwrap());
+        let (enabled, running) = (word(1), word(2));
+        let mut counts = [0; HW_EVENTS.len()];
+        for (i, count) in counts.iter_mut().enumerate() {
+            let raw = word(3 + i);
+            *count = if running > 0 && running < enabled {
+                (raw as u128 * enabled as u128 / running as u128) as u64
+            } else {
+                raw
+            };
+        }
+        Ok(counts)
+    }
+}
+
+fn open_counter(tid: u32, event: u64, group_fd: i32) -> io::Result<File> {
+    let attr = PerfEventAttr {
+        kind: PERF_TYPE_HARDWARE,
+        size: std::mem::size_of::<PerfEventAttr>() as u32,
+        config: event,
+        read_format: PERF_FORMAT_GROUP | PERF_FORMAT_TOTAL_TIME_ENABLED | PERF_FORMAT_TOTAL_TIME_RUNNING,
+        flags: ATTR_EXCLUDE_HV,
+        ..Default::default()
+    };
+    // SAFETY: attr outlives the call and has the size it claims
+    let fd = unsafe {
+        libc::syscall(
+            libc::SYS_perf_event_open,
+            &attr as *const PerfEventAttr,
+            tid as libc::pid_t,
+            -1 as libc::c_int,
+            group_fd,
+            PERF_FLAG_FD_CLOEXEC,
+        )
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: a fresh FD nothing else owns
+    Ok(unsafe { File::from_raw_fd(fd as i32) })
+}
+
+/// Hardware counters (cycles, instructions, cache references and misses)
+/// for a changing set of processes, one counter group per thread. Threads
+/// past the FD budget go uncounted.
+pub struct PerfCounters {
+    /// pid -> tid -> counters.
+    processes: HashMap<u32, HashMap<u32, ThreadCounters>>,
+    /// Counter groups that fit in [`fd_budget`].
+    max_groups: usize,
+    prev_time: Instant,
+    /// Why counters can't be opened on this system, once that's known.
+    unavailable: Option<String>,
+}
+
+impl PerfCounters {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            processes: HashMap::new(),
+            max_groups: fd_budget() / HW_EVENTS.len(),
+            prev_time: now,
+            unavailable: None,
+        }
+    }
+
+    /// Why there are no counters (perf access restricted, no PMU), if so.
+    pub fn unavailable(&self) -> Option<&str> {
+        self.unavailable.as_deref()
+    }
+
+    /// Count exactly `pids` from `now` on and return their rates since the
+    /// previous call. Newly added processes have no rates until the next.
+    pub fn update(&mut self, pids: &HashSet<u32>, now: Instant) -> HashMap<u32, PerfRates> {
+        let elapsed = now.duration_since(self.prev_time).as_secs_f64().max(f64::EPSILON);
+        self.prev_time = now;
+
+        self.processes.retain(|pid, _| pids.contains(pid));
+        let mut rates = HashMap::new();
+        if self.unavailable.is_some() {
+            return rates;
+        }
+        let mut groups: usize = self.processes.values().map(HashMap::len).sum();
+        for &pid in pids {
+            let Ok(tasks) = fs::read_dir(format!("/proc/{pid}/task")) else {
+                if let Some(gone) = self.processes.remove(&pid) {
+                    groups -= gone.len();
+                }
+                continue;
+            };
+            let tids: HashSet<u32> = tasks
+                .flatten()
+                .filter_map(|t| t.file_name().to_str()?.parse().ok())
+                .take(MAX_THREADS)
+                .collect();
+            let is_new = !self.processes.contains_key(&pid);
+            let threads = self.processes.entry(pid).or_default();
+            let before = threads.len();
+            threads.retain(|tid, _| tids.contains(tid));
+            groups -= before - threads.len();
+
+            let mut delta = [0u64; HW_EVENTS.len()];
+            for counters in threads.values_mut() {
+                let Ok(counts) = counters.read() else {
+                    continue;
+                };
+                for (i, d) in delta.iter_mut().enumerate() {
+                    *d += counts[i].saturating_sub(counters.prev[i]);
+                }
+                counters.prev = counts;
+            }
+            for &tid in &tids {
+                if threads.contains_key(&tid) {
+                    continue;
+                }
+                if groups >= self.max_groups {
+                    break;
+                }
+                match ThreadCounters::open(tid) {
+                    Ok(counters) => {
+                        threads.insert(tid, counters);
+                        groups += 1;
+                    }
+                    Err(e) => {
+                        if let Some(reason) = unavailable_reason(&e) {
+                            self.unavailable = Some(reason);
+                            self.processes.clear();
+                            return HashMap::new();
+                        }
+                        // Exited meanwhile, or out of FDs: try next time
+                    }
+                }
+            }
+            if !is_new {
+                rates.insert(
+                    pid,
+                    PerfRates {
+                        cycles_per_sec: delta[0] as f64 / elapsed,
+                        instructions_per_sec: delta[1] as f64 / elapsed,
+                        cache_refs_per_sec: delta[2] as f64 / elapsed,
+                        cache_misses_per_sec: delta[3] as f64 / elapsed,
+                    },
+                );
+            }
+        }
+        rates
+    }
+}
+
+/// Errors that mean no process can be counted, as opposed to this one
+/// thread having exited.
+fn unavailable_reason(e: &io::Error) -> Option<String> {
+    match e.raw_os_error()? {
+        libc::EACCES | libc::EPERM => {
+            let paranoid = fs::read_to_string("/proc/sys/kernel/perf_event_paranoid")
+                .map(|s| s.trim().to_string())
+                .unwrap_or_else(|_| "?".to_string());
+            Some(format!(
+                "not permitted (perf_event_paranoid is {paranoid}; needs CAP_PERFMON or a seccomp profile allowing perf_event_open)"
+            ))
+        }
+        libc::ENOENT | libc::ENODEV | libc::EOPNOTSUPP => {
+            Some("no hardware counters (virtual machine without a virtual PMU?)".to_string())
+        }
+        libc::ENOSYS => Some("kernel built without perf events".to_string()),
+        libc::EINVAL => Some("the PMU can't count cycles, instructions and cache events together".to_string()),
+        _ => None,
+    }
+}
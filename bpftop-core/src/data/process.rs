@@ -0,0 +1,1402 @@
+use std::cmp::Ordering;
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use super::exits::ExitRecord;
+use super::perf::PerfRates;
+
+/// CPU% below this counts as idle (it displays as 0.0).
+const IDLE_CPU_PERCENT: f64 = 0.05;
+
+/// Full process information combining eBPF data and /proc supplements.
+#[derive(Debug, Clone)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub ppid: u32,
+    /// Parent when bpftop first saw the process, or at its exec if that
+    /// was seen; differs from `ppid` once the process has been reparented.
+    pub orig_ppid: u32,
+    /// Process group and session IDs.
+    pub pgid: u32,
+    pub sid: u32,
+    /// Controlling terminal of the session, e.g. "pts/3".
+    pub tty: Option<String>,
+    pub uid: u32,
+    pub user: String,
+    pub state: ProcessState,
+    pub priority: i32,
+    pub nice: i32,
+    pub virt_bytes: u64,
+    pub res_bytes: u64,
+    pub shr_bytes: u64,
+    pub cpu_percent: f64,
+    pub mem_percent: f64,
+    /// RSS growth in bytes/s over the `[alerts] leak_minutes` window.
+    pub rss_growth: f64,
+    pub gpu_percent: f64,
+    pub gpu_mem_bytes: u64,
+    pub net_rx_bytes: u64,
+    pub net_tx_bytes: u64,
+    pub net_rate: f64,
+    /// Per-direction byte rates over the last refresh interval.
+    pub net_tx_rate: f64,
+    pub net_rx_rate: f64,
+    pub net_ifname: String,
+    /// Cumulative regular-file read/write bytes (vfs_read/vfs_write kprobes).
+    pub disk_read_bytes: u64,
+    pub disk_write_bytes: u64,
+    /// Per-direction file I/O rates over the last refresh interval.
+    pub disk_read_rate: f64,
+    pub disk_write_rate: f64,
+    /// Packets dropped on the process's listening TCP and UDP sockets
+    /// (full accept/SYN queue or receive buffer), and the rate of the last
+    /// refresh interval.
+    pub sock_drops: u64,
+    pub sock_drop_rate: f64,
+    /// Open file descriptor count (from the task_file iterator).
+    pub fd_count: u32,
+    /// Voluntary (blocked) and involuntary (preempted) context switches,
+    /// summed over the threads of a process, and their rates over the last
+    /// refresh interval. Many involuntary switches mean CPU contention.
+    pub nvcsw: u64,
+    pub nivcsw: u64,
+    pub csw_rate: f64,
+    pub icsw_rate: f64,
+    /// Time spent runnable but waiting for a CPU, and how many waits that
+    /// was, since bpftop started (sched tracepoints, summed over threads).
+    pub runq_wait_ns: u64,
+    pub runq_waits: u64,
+    /// Average wait per wakeup over the last refresh interval.
+    pub runq_latency_ns: f64,
+    pub cpu_time_secs: f64,
+    pub start_time_ns: u64,
+    pub comm: String,
+    pub cmdline: String,
+    pub container: Option<String>,
+    /// Full container ID, which `container:` filters match as well as the
+    /// name.
+    pub container_id: Option<String>,
+    /// Image the container runs, once its runtime has said.
+    pub container_image: Option<String>,
+    /// Share of the CPU packages' power (RAPL), by CPU time.
+    pub watts: f64,
+    /// Hardware counter rates, for the processes they are open for.
+    pub perf: Option<PerfRates>,
+    /// Kubernetes pod and namespace of the container.
+    pub pod: Option<String>,
+    pub pod_namespace: Option<String>,
+    pub service: Option<String>,
+    /// Workload badge (db, jvm, ...), an index into the UI's badge table.
+    pub badge: Option<usize>,
+    pub cgroup_path: String,
+    /// Children PIDs for tree view.
+    pub children: Vec<u32>,
+    /// Previous utime+stime for delta calculation.
+    pub prev_cpu_ns: u64,
+    /// Whether this is a kernel thread (PF_KTHREAD set).
+    pub is_kernel_thread: bool,
+    /// Whether this is a user thread (tid != pid).
+    pub is_thread: bool,
+    /// Thread ID (only differs from pid for threads). Unique per row, so
+    /// it identifies rows where pid can't; `tid == pid` finds the leader.
+    pub tid: u32,
+    /// Tagged by user (space key).
+    pub tagged: bool,
+    /// Whether this process's cgroup is currently frozen.
+    pub frozen: bool,
+    /// Seconds until a frozen cgroup is thawed automatically, if it was
+    /// frozen with a timer.
+    pub thaw_in_secs: Option<u64>,
+    /// Tree view prefix (e.g. "├─- ") set during tree ordering.
+    pub tree_prefix: String,
+    /// Set while an exited process is kept in the table, greyed out.
+    pub exited: Option<ExitRecord>,
+    /// No CPU, disk or network activity for the last `idle_dim_refreshes`
+    /// refreshes; the row is drawn dimmed.
+    pub idle: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum ProcessState {
+    Running,
+    Sleeping,
+    DiskSleep,
+    Zombie,
+    Stopped,
+    TracingStop,
+    Dead,
+    Idle,
+    Unknown,
+}
+
+impl ProcessState {
+    #[allow(dead_code)]
+    pub fn from_proc_stat(c: char) -> Self {
+        match c {
+            'R' => Self::Running,
+            'S' => Self::Sleeping,
+            'D' => Self::DiskSleep,
+            'Z' => Self::Zombie,
+            'T' => Self::Stopped,
+            't' => Self::TracingStop,
+            'X' | 'x' => Self::Dead,
+            'I' => Self::Idle,
+            _ => Self::Unknown,
+        }
+    }
+
+    pub fn from_kernel_state(state: u8) -> Self {
+        // Kernel task states are bitmask values
+        match state {
+            0 => Self::Running,
+            1 => Self::Sleeping,
+            2 => Self::DiskSleep,
+            4 => Self::Zombie,
+            8 => Self::Stopped,
+            _ => Self::Unknown,
+        }
+    }
+
+    pub fn as_char(&self) -> char {
+        match self {
+            Self::Running => 'R',
+            Self::Sleeping => 'S',
+            Self::DiskSleep => 'D',
+            Self::Zombie => 'Z',
+            Self::Stopped => 'T',
+            Self::TracingStop => 't',
+            Self::Dead => 'X',
+            Self::Idle => 'I',
+            Self::Unknown => '?',
+        }
+    }
+
+    /// Spelled-out state, for screen-reader output.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Sleeping => "sleeping",
+            Self::DiskSleep => "waiting on disk",
+            Self::Zombie => "zombie",
+            Self::Stopped => "stopped",
+            Self::TracingStop => "stopped by tracer",
+            Self::Dead => "dead",
+            Self::Idle => "idle",
+            Self::Unknown => "unknown state",
+        }
+    }
+}
+
+impl ProcessInfo {
+    /// Update only volatile fields from a fresh snapshot, preserving stable
+    /// fields like `tagged`, `tree_prefix`, and ordering position.
+    pub fn update_dynamic_fields(&mut self, src: &ProcessInfo) {
+        self.state = src.state;
+        self.priority = src.priority;
+        self.nice = src.nice;
+        self.virt_bytes = src.virt_bytes;
+        self.res_bytes = src.res_bytes;
+        self.shr_bytes = src.shr_bytes;
+        self.cpu_percent = src.cpu_percent;
+        self.mem_percent = src.mem_percent;
+        self.rss_growth = src.rss_growth;
+        self.gpu_percent = src.gpu_percent;
+        self.gpu_mem_bytes = src.gpu_mem_bytes;
+        self.net_rx_bytes = src.net_rx_bytes;
+        self.net_tx_bytes = src.net_tx_bytes;
+        self.net_rate = src.net_rate;
+        self.net_tx_rate = src.net_tx_rate;
+        self.net_rx_rate = src.net_rx_rate;
+        self.net_ifname = src.net_ifname.clone();
+        self.disk_read_bytes = src.disk_read_bytes;
+        self.disk_write_bytes = src.disk_write_bytes;
+        self.disk_read_rate = src.disk_read_rate;
+        self.disk_write_rate = src.disk_write_rate;
+        self.sock_drops = src.sock_drops;
+        self.sock_drop_rate = src.sock_drop_rate;
+        self.fd_count = src.fd_count;
+        self.nvcsw = src.nvcsw;
+        self.nivcsw = src.nivcsw;
+        self.csw_rate = src.csw_rate;
+        self.icsw_rate = src.icsw_rate;
+        self.runq_wait_ns = src.runq_wait_ns;
+        self.runq_waits = src.runq_waits;
+        self.runq_latency_ns = src.runq_latency_ns;
+        self.cpu_time_secs = src.cpu_time_secs;
+        self.comm = src.comm.clone();
+        self.cmdline = src.cmdline.clone();
+        self.children = src.children.clone();
+        self.prev_cpu_ns = src.prev_cpu_ns;
+        self.frozen = src.frozen;
+        self.thaw_in_secs = src.thaw_in_secs;
+        self.exited = src.exited.clone();
+        self.idle = src.idle;
+    }
+
+    /// Whether the original parent died and the process was handed to a
+    /// subreaper or init.
+    pub fn reparented(&self) -> bool {
+        !self.is_thread && self.orig_ppid != self.ppid
+    }
+
+    /// Whether this refresh showed no measurable activity.
+    pub fn is_quiet(&self) -> bool {
+        self.cpu_percent < IDLE_CPU_PERCENT
+            && self.net_rate < 1.0
+            && self.disk_read_rate < 1.0
+            && self.disk_write_rate < 1.0
+    }
+}
+
+/// Which field to yank from a process row.
+#[derive(Debug, Clone, Copy)]
+pub enum YankField {
+    Row,
+    Pid,
+    User,
+    Container,
+    Service,
+    Name,
+    Cmdline,
+    GpuPercent,
+    GpuMem,
+    Cgroup,
+    CpuPercent,
+    MemPercent,
+    Time,
+    Res,
+    Virt,
+    NetRate,
+    DiskRead,
+    DiskWrite,
+}
+
+impl YankField {
+    /// Field for the key after `Y` in visual mode: the `y` keys plus
+    /// C (cgroup), P/M/T (CPU%/MEM%/TIME+, like the quick-sort keys),
+    /// R/V (RES/VIRT), N (NET/s), r/w (DISK R/s, W/s).
+    pub fn from_column_key(c: char) -> Option<Self> {
+        Some(match c {
+            'p' => Self::Pid,
+            'u' => Self::User,
+            'c' => Self::Container,
+            's' => Self::Service,
+            'n' => Self::Name,
+            'l' => Self::Cmdline,
+            'g' => Self::GpuPercent,
+            'v' => Self::GpuMem,
+            'C' => Self::Cgroup,
+            'P' => Self::CpuPercent,
+            'M' => Self::MemPercent,
+            'T' => Self::Time,
+            'R' => Self::Res,
+            'V' => Self::Virt,
+            'N' => Self::NetRate,
+            'r' => Self::DiskRead,
+            'w' => Self::DiskWrite,
+            _ => return None,
+        })
+    }
+
+    /// Column name used in yank feedback.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Row => "row",
+            Self::Pid => "PID",
+            Self::User => "user",
+            Self::Container => "container",
+            Self::Service => "service",
+            Self::Name => "name",
+            Self::Cmdline => "cmdline",
+            Self::GpuPercent => "GPU%",
+            Self::GpuMem => "GMEM",
+            Self::Cgroup => "cgroup",
+            Self::CpuPercent => "CPU%",
+            Self::MemPercent => "MEM%",
+            Self::Time => "TIME+",
+            Self::Res => "RES",
+            Self::Virt => "VIRT",
+            Self::NetRate => "NET/s",
+            Self::DiskRead => "DISK R/s",
+            Self::DiskWrite => "DISK W/s",
+        }
+    }
+}
+
+impl ProcessInfo {
+    /// Format the requested field for clipboard yanking.
+    pub fn yank_text(&self, field: YankField) -> String {
+        match field {
+            YankField::Row => format!(
+                "{}\t{}\t{:.1}\t{:.1}\t{:.1}\t{}\t{}\t{}",
+                self.pid,
+                self.user,
+                self.cpu_percent,
+                self.mem_percent,
+                self.gpu_percent,
+                format_bytes(self.gpu_mem_bytes),
+                format_time(self.cpu_time_secs),
+                self.cmdline,
+            ),
+            YankField::Pid => self.pid.to_string(),
+            YankField::User => self.user.clone(),
+            YankField::Container => self.container.clone().unwrap_or_else(|| "-".to_string()),
+            YankField::Service => self.service.clone().unwrap_or_else(|| "-".to_string()),
+            YankField::Name => self.comm.clone(),
+            YankField::Cmdline => self.cmdline.clone(),
+            YankField::GpuPercent => format!("{:.1}", self.gpu_percent),
+            YankField::GpuMem => format_bytes(self.gpu_mem_bytes),
+            YankField::Cgroup => self.cgroup_path.clone(),
+            YankField::CpuPercent => format!("{:.1}", self.cpu_percent),
+            YankField::MemPercent => format!("{:.1}", self.mem_percent),
+            YankField::Time => format_time(self.cpu_time_secs),
+            YankField::Res => format_bytes(self.res_bytes),
+            YankField::Virt => format_bytes(self.virt_bytes),
+            YankField::NetRate => format_rate(self.net_rate),
+            YankField::DiskRead => format_rate(self.disk_read_rate),
+            YankField::DiskWrite => format_rate(self.disk_write_rate),
+        }
+    }
+}
+
+/// Column that the process table can be sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortColumn {
+    Pid,
+    Pgrp,
+    Sid,
+    Tty,
+    User,
+    Priority,
+    Nice,
+    Virt,
+    Res,
+    Shr,
+    State,
+    CpuPercent,
+    MemPercent,
+    Grow,
+    GpuPercent,
+    GpuMem,
+    Watts,
+    NetRate,
+    NetTx,
+    NetRx,
+    NetTotal,
+    NetIf,
+    Drops,
+    DiskRead,
+    DiskWrite,
+    Time,
+    Csw,
+    Icsw,
+    RunqLat,
+    Namespace,
+    Pod,
+    Container,
+    Service,
+    Command,
+}
+
+impl SortColumn {
+    pub fn all() -> &'static [SortColumn] {
+        &[
+            Self::Pid,
+            Self::Pgrp,
+            Self::Sid,
+            Self::Tty,
+            Self::User,
+            Self::Priority,
+            Self::Nice,
+            Self::Virt,
+            Self::Res,
+            Self::Shr,
+            Self::State,
+            Self::CpuPercent,
+            Self::MemPercent,
+            Self::Grow,
+            Self::GpuPercent,
+            Self::GpuMem,
+            Self::Watts,
+            Self::NetRate,
+            Self::NetTx,
+            Self::NetRx,
+            Self::NetTotal,
+            Self::NetIf,
+            Self::Drops,
+            Self::DiskRead,
+            Self::DiskWrite,
+            Self::Time,
+            Self::Csw,
+            Self::Icsw,
+            Self::RunqLat,
+            Self::Namespace,
+            Self::Pod,
+            Self::Container,
+            Self::Service,
+            Self::Command,
+        ]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Pid => "PID",
+            Self::Pgrp => "PGRP",
+            Self::Sid => "SID",
+            Self::Tty => "TTY",
+            Self::User => "USER",
+            Self::Priority => "PRI",
+            Self::Nice => "NI",
+            Self::Virt => "VIRT",
+            Self::Res => "RES",
+            Self::Shr => "SHR",
+            Self::State => "S",
+            Self::CpuPercent => "CPU%",
+            Self::MemPercent => "MEM%",
+            Self::Grow => "GROW",
+            Self::GpuPercent => "GPU%",
+            Self::GpuMem => "GMEM",
+            Self::Watts => "WATTS",
+            Self::NetRate => "NET/s",
+            Self::NetTx => "TX/s",
+            Self::NetRx => "RX/s",
+            Self::NetTotal => "NET TOT",
+            Self::NetIf => "IF",
+            Self::Drops => "DROPS",
+            Self::DiskRead => "DISK R/s",
+            Self::DiskWrite => "DISK W/s",
+            Self::Time => "TIME+",
+            Self::Csw => "CSW/s",
+            Self::Icsw => "ICSW/s",
+            Self::RunqLat => "LAT",
+            Self::Namespace => "NAMESPACE",
+            Self::Pod => "POD",
+            Self::Container => "CONT",
+            Self::Service => "UNIT",
+            Self::Command => "Command",
+        }
+    }
+
+    /// One-line explanation of what the column measures and where it
+    /// comes from, shown while choosing a sort column.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Pid => "Process ID (thread group ID)",
+            Self::Pgrp => "Process group ID; job-control signals go to the whole group",
+            Self::Sid => "Session ID, usually the PID of the login shell or daemon",
+            Self::Tty => "Controlling terminal of the session (pts/N for SSH and terminal windows)",
+            Self::User => "Real user owning the process",
+            Self::Priority => "Kernel scheduling priority (task prio - 100; lower runs first)",
+            Self::Nice => "Nice value, -20 (favoured) to 19 (background)",
+            Self::Virt => "Virtual address space size (mm total_vm), mostly unbacked",
+            Self::Res => "Resident memory: anon + file-backed pages in RAM (mm rss_stat)",
+            Self::Shr => "Resident shared memory (shmem/tmpfs pages), a subset of RES",
+            Self::State => "R running, S sleeping, D uninterruptible I/O, Z zombie, T stopped",
+            Self::CpuPercent => "CPU time used over the last refresh, 100% = one full core",
+            Self::MemPercent => "RES as a share of total physical memory",
+            Self::Grow => "RES growth per second over the leak-detection window",
+            Self::GpuPercent => "GPU SM utilisation attributed to the process (NVML)",
+            Self::GpuMem => "GPU memory allocated by the process (NVML)",
+            Self::Watts => "CPU package power (RAPL) shared out by CPU time; approximate",
+            Self::NetRate => "TCP/UDP bytes sent + received per second (eBPF kprobes)",
+            Self::NetTx => "TCP/UDP bytes sent per second (eBPF kprobe on *_sendmsg)",
+            Self::NetRx => "TCP/UDP bytes received per second (eBPF kretprobe on *_recvmsg)",
+            Self::NetTotal => "TCP/UDP bytes sent + received since bpftop started",
+            Self::NetIf => "Network interface of the most recent send/receive",
+            Self::Drops => "Packets/s dropped on listening/UDP sockets (full accept queue or receive buffer)",
+            Self::DiskRead => "Regular-file bytes read per second via vfs_read, incl. page cache hits",
+            Self::DiskWrite => "Regular-file bytes written per second via vfs_write, before writeback",
+            Self::Time => "Total CPU time (user + system) since process start",
+            Self::Csw => "Voluntary context switches per second (blocked on I/O, locks, sleep)",
+            Self::Icsw => "Involuntary context switches per second (preempted; high means CPU contention)",
+            Self::RunqLat => "Average wait in the run queue per wakeup over the last refresh (sched_wakeup/sched_switch)",
+            Self::Namespace => "Kubernetes namespace of the process's pod",
+            Self::Pod => "Kubernetes pod the process's container runs in",
+            Self::Container => "Docker/Podman container owning the process's cgroup",
+            Self::Service => "systemd unit owning the process's cgroup",
+            Self::Command => "Full command line (argv captured at exec)",
+        }
+    }
+
+    /// Column width in characters.
+    pub fn width(&self) -> u16 {
+        match self {
+            Self::Pid => 7,
+            Self::Pgrp => 7,
+            Self::Sid => 7,
+            Self::Tty => 7,
+            Self::User => 9,
+            Self::Priority => 4,
+            Self::Nice => 4,
+            Self::Virt => 7,
+            Self::Res => 7,
+            Self::Shr => 7,
+            Self::State => 2,
+            Self::CpuPercent => 6,
+            Self::MemPercent => 6,
+            Self::Grow => 7,
+            Self::GpuPercent => 5,
+            Self::GpuMem => 6,
+            Self::Watts => 6,
+            Self::NetRate => 8,
+            Self::NetTx => 8,
+            Self::NetRx => 8,
+            Self::NetTotal => 7,
+            Self::NetIf => 6,
+            Self::Drops => 6,
+            Self::DiskRead => 9,
+            Self::DiskWrite => 9,
+            Self::Time => 10,
+            Self::Csw => 7,
+            Self::Icsw => 7,
+            Self::RunqLat => 7,
+            Self::Namespace => 12,
+            Self::Pod => 20,
+            Self::Container => 12,
+            Self::Service => 16,
+            Self::Command => 0, // fills remaining space
+        }
+    }
+
+    /// Column width in compact mode: just wide enough for the label and
+    /// typical values.
+    pub fn compact_width(&self) -> u16 {
+        match self {
+            Self::User => 8,
+            Self::Priority | Self::Nice => 3,
+            Self::Virt | Self::Res | Self::Shr | Self::Grow => 6,
+            Self::State => 1,
+            Self::CpuPercent | Self::MemPercent => 5,
+            Self::GpuPercent => 4,
+            Self::GpuMem => 5,
+            Self::Watts => 5,
+            Self::NetRate | Self::NetTx | Self::NetRx | Self::NetTotal => 7,
+            Self::NetIf => 5,
+            Self::Drops => 5,
+            Self::DiskRead | Self::DiskWrite => 8,
+            Self::Time => 8,
+            Self::Csw | Self::Icsw | Self::RunqLat => 6,
+            Self::Namespace => 10,
+            Self::Pod => 14,
+            Self::Container => 10,
+            _ => self.width(),
+        }
+    }
+
+    /// Default sort direction when first clicking a column header.
+    /// Text columns default to ascending; numeric columns to descending.
+    pub fn default_ascending(&self) -> bool {
+        matches!(self, Self::Pid | Self::Pgrp | Self::Sid | Self::Tty | Self::User | Self::State | Self::NetIf | Self::Namespace | Self::Pod | Self::Container | Self::Service | Self::Command)
+    }
+}
+
+/// Truncate a float to 1 decimal place for stable comparison.
+/// Values that display identically (e.g. 0.04 and 0.02 both show "0.0")
+/// compare as equal, letting the PID tiebreaker kick in.
+fn quantize(v: f64) -> i64 {
+    (v * 10.0) as i64
+}
+
+/// Compare two processes by the given sort column, with PID as a
+/// stable tiebreaker so the display doesn't shuffle on equal values.
+pub fn compare_processes(a: &ProcessInfo, b: &ProcessInfo, col: SortColumn, ascending: bool) -> Ordering {
+    compare_processes_by(a, b, &[(col, ascending)])
+}
+
+/// Compare by several sort keys in turn (e.g. user, then CPU%), each with
+/// its own direction. PID breaks the remaining ties, in the direction of
+/// the first key.
+pub fn compare_processes_by(a: &ProcessInfo, b: &ProcessInfo, keys: &[(SortColumn, bool)]) -> Ordering {
+    for &(col, ascending) in keys {
+        let ord = compare_column(a, b, col);
+        let ord = if ascending { ord } else { ord.reverse() };
+        if ord.is_ne() {
+            return ord;
+        }
+    }
+    let ord = a.pid.cmp(&b.pid);
+    match keys.first() {
+        Some(&(_, false)) => ord.reverse(),
+        _ => ord,
+    }
+}
+
+/// Ascending order of one column, without the PID tiebreaker.
+fn compare_column(a: &ProcessInfo, b: &ProcessInfo, col: SortColumn) -> Ordering {
+    match col {
+        SortColumn::Pid => a.pid.cmp(&b.pid),
+        SortColumn::Pgrp => a.pgid.cmp(&b.pgid),
+        SortColumn::Sid => a.sid.cmp(&b.sid),
+        SortColumn::Tty => a.tty.cmp(&b.tty).then(a.sid.cmp(&b.sid)),
+        SortColumn::User => a.user.cmp(&b.user),
+        SortColumn::Priority => a.priority.cmp(&b.priority),
+        SortColumn::Nice => a.nice.cmp(&b.nice),
+        SortColumn::Virt => a.virt_bytes.cmp(&b.virt_bytes),
+        SortColumn::Res => a.res_bytes.cmp(&b.res_bytes),
+        SortColumn::Shr => a.shr_bytes.cmp(&b.shr_bytes),
+        SortColumn::State => (a.state.as_char()).cmp(&b.state.as_char()),
+        SortColumn::CpuPercent => quantize(a.cpu_percent).cmp(&quantize(b.cpu_percent)),
+        SortColumn::MemPercent => quantize(a.mem_percent).cmp(&quantize(b.mem_percent)),
+        SortColumn::Grow => quantize(a.rss_growth).cmp(&quantize(b.rss_growth)),
+        SortColumn::GpuPercent => quantize(a.gpu_percent).cmp(&quantize(b.gpu_percent)),
+        SortColumn::GpuMem => a.gpu_mem_bytes.cmp(&b.gpu_mem_bytes),
+        SortColumn::Watts => quantize(a.watts).cmp(&quantize(b.watts)),
+        SortColumn::NetRate => quantize(a.net_rate).cmp(&quantize(b.net_rate)),
+        SortColumn::NetTx => quantize(a.net_tx_rate).cmp(&quantize(b.net_tx_rate)),
+        SortColumn::NetRx => quantize(a.net_rx_rate).cmp(&quantize(b.net_rx_rate)),
+        SortColumn::NetTotal => (a.net_tx_bytes + a.net_rx_bytes).cmp(&(b.net_tx_bytes + b.net_rx_bytes)),
+        SortColumn::NetIf => a.net_ifname.cmp(&b.net_ifname),
+        SortColumn::Drops => quantize(a.sock_drop_rate).cmp(&quantize(b.sock_drop_rate)).then(a.sock_drops.cmp(&b.sock_drops)),
+        SortColumn::DiskRead => quantize(a.disk_read_rate).cmp(&quantize(b.disk_read_rate)),
+        SortColumn::DiskWrite => quantize(a.disk_write_rate).cmp(&quantize(b.disk_write_rate)),
+        SortColumn::Time => quantize(a.cpu_time_secs).cmp(&quantize(b.cpu_time_secs)),
+        SortColumn::Csw => quantize(a.csw_rate).cmp(&quantize(b.csw_rate)).then(a.nvcsw.cmp(&b.nvcsw)),
+        SortColumn::Icsw => quantize(a.icsw_rate).cmp(&quantize(b.icsw_rate)).then(a.nivcsw.cmp(&b.nivcsw)),
+        SortColumn::RunqLat => a.runq_latency_ns.total_cmp(&b.runq_latency_ns),
+        SortColumn::Namespace => a.pod_namespace.cmp(&b.pod_namespace).then(a.pod.cmp(&b.pod)),
+        SortColumn::Pod => a.pod.cmp(&b.pod),
+        SortColumn::Container => a.container.cmp(&b.container),
+        SortColumn::Service => a.service.cmp(&b.service),
+        SortColumn::Command => a.cmdline.cmp(&b.cmdline),
+    }
+}
+
+/// A parsed filter expression: whitespace-separated terms that must all
+/// match. A term is plain text (substring of comm, cmdline, PID, user, unit
+/// or interface), `/regex/`, either one behind a `field:` qualifier such as
+/// `user:www-data` or `state:Z`, or a numeric comparison like `cpu>50` or
+/// `mem>=2G`. Any term may be negated with a leading `!`.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessFilter {
+    terms: Vec<FilterTerm>,
+}
+
+#[derive(Debug, Clone)]
+struct FilterTerm {
+    negate: bool,
+    field: Option<FilterField>,
+    pattern: FilterPattern,
+}
+
+#[derive(Debug, Clone)]
+enum FilterPattern {
+    Text { raw: String, lower: String },
+    Regex(Regex),
+    /// `metric op value`; the term's `field` is unused.
+    Compare(Metric, CompareOp, f64),
+}
+
+/// Numeric quantity a filter term can compare against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Metric {
+    Cpu,
+    MemPercent,
+    Res,
+    Virt,
+    Gpu,
+    /// Network rate, bytes/s.
+    Net,
+    /// Disk read + write rate, bytes/s.
+    Disk,
+    /// CPU time, seconds.
+    Time,
+    Nice,
+    Pid,
+    Fds,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompareOp {
+    Lt,
+    Le,
+    Eq,
+    Ne,
+    Ge,
+    Gt,
+}
+
+/// How a metric's value is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Plain,
+    Percent,
+    /// K/M/G/T suffixes, 1024-based like the table.
+    Bytes,
+    /// s/m/h/d suffixes; plain numbers are seconds.
+    Seconds,
+}
+
+impl Metric {
+    /// Resolve `name` given the value it is compared with: `mem` means
+    /// MEM% for a plain number and RES for a size like `2G`.
+    fn from_name(name: &str, value: &str) -> Option<Self> {
+        Some(match name {
+            "cpu" => Self::Cpu,
+            "mem" if value.ends_with(|c: char| c.is_ascii_alphabetic()) => Self::Res,
+            "mem" => Self::MemPercent,
+            "res" | "rss" => Self::Res,
+            "virt" => Self::Virt,
+            "gpu" => Self::Gpu,
+            "net" => Self::Net,
+            "disk" | "io" => Self::Disk,
+            "time" => Self::Time,
+            "nice" | "ni" => Self::Nice,
+            "pid" => Self::Pid,
+            "fds" => Self::Fds,
+            _ => return None,
+        })
+    }
+
+    fn unit(self) -> Unit {
+        match self {
+            Self::Cpu | Self::MemPercent | Self::Gpu => Unit::Percent,
+            Self::Res | Self::Virt | Self::Net | Self::Disk => Unit::Bytes,
+            Self::Time => Unit::Seconds,
+            Self::Nice | Self::Pid | Self::Fds => Unit::Plain,
+        }
+    }
+
+    fn value(self, proc: &ProcessInfo) -> f64 {
+        match self {
+            Self::Cpu => proc.cpu_percent,
+            Self::MemPercent => proc.mem_percent,
+            Self::Res => proc.res_bytes as f64,
+            Self::Virt => proc.virt_bytes as f64,
+            Self::Gpu => proc.gpu_percent,
+            Self::Net => proc.net_rate,
+            Self::Disk => proc.disk_read_rate + proc.disk_write_rate,
+            Self::Time => proc.cpu_time_secs,
+            Self::Nice => proc.nice as f64,
+            Self::Pid => proc.pid as f64,
+            Self::Fds => proc.fd_count as f64,
+        }
+    }
+}
+
+impl CompareOp {
+    fn apply(self, lhs: f64, rhs: f64) -> bool {
+        match self {
+            Self::Lt => lhs < rhs,
+            Self::Le => lhs <= rhs,
+            Self::Eq => lhs == rhs,
+            Self::Ne => lhs != rhs,
+            Self::Ge => lhs >= rhs,
+            Self::Gt => lhs > rhs,
+        }
+    }
+}
+
+/// Parse a number written in `unit`, e.g. "2G", "1.5M/s", "50%", "2h".
+pub fn parse_quantity(text: &str, unit: Unit) -> Option<f64> {
+    let text = text.trim();
+    let (number, scale) = match unit {
+        Unit::Plain => (text, 1.0),
+        Unit::Percent => (text.strip_suffix('%').unwrap_or(text), 1.0),
+        Unit::Bytes => {
+            let t = text.strip_suffix("/s").unwrap_or(text);
+            let t = t.strip_suffix(['B', 'b']).unwrap_or(t);
+            match t.char_indices().last() {
+                Some((i, c)) if c.is_ascii_alphabetic() => {
+                    let scale = match c.to_ascii_uppercase() {
+                        'K' => 1024.0,
+                        'M' => 1024.0 * 1024.0,
+                        'G' => 1024.0 * 1024.0 * 1024.0,
+                        'T' => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+                        _ => return None,
+                    };
+                    (&t[..i], scale)
+                }
+                _ => (t, 1.0),
+            }
+        }
+        Unit::Seconds => match text.char_indices().last() {
+            Some((i, c)) if c.is_ascii_alphabetic() => {
+                let scale = match c {
+                    's' => 1.0,
+                    'm' => 60.0,
+                    'h' => 3600.0,
+                    'd' => 86400.0,
+                    _ => return None,
+                };
+                (&text[..i], scale)
+            }
+            _ => (text, 1.0),
+        },
+    };
+    number.parse::<f64>().ok().map(|n| n * scale)
+}
+
+/// Split "cpu>=50" into ("cpu", op, "50") if it is a comparison.
+fn split_comparison(term: &str) -> Option<(&str, CompareOp, &str)> {
+    let at = term.find(['<', '>', '=', '!'])?;
+    let (name, rest) = term.split_at(at);
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let (op, len) = match rest.as_bytes() {
+        [b'>', b'=', ..] => (CompareOp::Ge, 2),
+        [b'<', b'=', ..] => (CompareOp::Le, 2),
+        [b'!', b'=', ..] => (CompareOp::Ne, 2),
+        [b'=', b'=', ..] => (CompareOp::Eq, 2),
+        [b'>', ..] => (CompareOp::Gt, 1),
+        [b'<', ..] => (CompareOp::Lt, 1),
+        [b'=', ..] => (CompareOp::Eq, 1),
+        _ => return None,
+    };
+    Some((name, op, &rest[len..]))
+}
+
+/// Field a filter term can be qualified with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FilterField {
+    Pid,
+    Ppid,
+    Sid,
+    Tty,
+    User,
+    Comm,
+    Cmdline,
+    Container,
+    Pod,
+    Namespace,
+    Service,
+    /// One or more state letters, e.g. `state:DZ`; case-sensitive.
+    State,
+    NetIf,
+    Cgroup,
+}
+
+/// Fields an unqualified term is matched against.
+const BARE_FIELDS: [FilterField; 6] = [
+    FilterField::Comm,
+    FilterField::Cmdline,
+    FilterField::Pid,
+    FilterField::User,
+    FilterField::Service,
+    FilterField::NetIf,
+];
+
+impl FilterField {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "pid" => Self::Pid,
+            "ppid" => Self::Ppid,
+            "sid" | "session" => Self::Sid,
+            "tty" => Self::Tty,
+            "user" => Self::User,
+            "comm" | "name" => Self::Comm,
+            "cmd" | "cmdline" => Self::Cmdline,
+            "container" => Self::Container,
+            "pod" => Self::Pod,
+            "namespace" | "k8s-ns" => Self::Namespace,
+            "unit" | "service" => Self::Service,
+            "state" => Self::State,
+            "if" | "iface" => Self::NetIf,
+            "cgroup" => Self::Cgroup,
+            _ => return None,
+        })
+    }
+
+    fn value(self, proc: &ProcessInfo) -> Option<String> {
+        match self {
+            Self::Pid => Some(proc.pid.to_string()),
+            Self::Ppid => Some(proc.ppid.to_string()),
+            Self::Sid => Some(proc.sid.to_string()),
+            Self::Tty => proc.tty.clone(),
+            Self::User => Some(proc.user.clone()),
+            Self::Comm => Some(proc.comm.clone()),
+            Self::Cmdline => Some(proc.cmdline.clone()),
+            Self::Container => proc.container.clone(),
+            Self::Pod => proc.pod.clone(),
+            Self::Namespace => proc.pod_namespace.clone(),
+            Self::Service => proc.service.clone(),
+            Self::State => Some(proc.state.as_char().to_string()),
+            Self::NetIf => (!proc.net_ifname.is_empty()).then(|| proc.net_ifname.clone()),
+            Self::Cgroup => Some(proc.cgroup_path.clone()),
+        }
+    }
+}
+
+impl ProcessFilter {
+    /// Parse a filter expression. Fails on an invalid regex or a
+    /// comparison whose value is not a number.
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let terms = split_terms(expr)
+            .into_iter()
+            .map(parse_term)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { terms })
+    }
+}
+
+/// Split on whitespace, keeping `/regex with spaces/` in one term.
+fn split_terms(expr: &str) -> Vec<&str> {
+    let mut terms = Vec::new();
+    let mut start = None;
+    let mut in_regex = false;
+    let mut prev = ' ';
+    for (i, c) in expr.char_indices() {
+        match start {
+            Some(_) if in_regex => in_regex = !(c == '/' && prev != '\\'),
+            Some(s) if c.is_whitespace() => {
+                terms.push(&expr[s..i]);
+                start = None;
+            }
+            Some(s) => in_regex = c == '/' && opens_regex(&expr[s..i]),
+            None if c.is_whitespace() => {}
+            None => {
+                start = Some(i);
+                in_regex = c == '/';
+            }
+        }
+        prev = c;
+    }
+    if let Some(s) = start {
+        terms.push(&expr[s..]);
+    }
+    terms
+}
+
+/// Whether a `/` following `prefix` (e.g. "!", "user:") starts a regex.
+fn opens_regex(prefix: &str) -> bool {
+    let prefix = prefix.strip_prefix('!').unwrap_or(prefix);
+    prefix.is_empty()
+        || prefix
+            .strip_suffix(':')
+            .is_some_and(|name| FilterField::from_name(name).is_some())
+}
+
+fn parse_term(term: &str) -> Result<FilterTerm, String> {
+    let (negate, rest) = match term.strip_prefix('!') {
+        Some(rest) if !rest.is_empty() => (true, rest),
+        _ => (false, term),
+    };
+    if let Some((name, op, value)) = split_comparison(rest) {
+        if let Some(metric) = Metric::from_name(name, value) {
+            let number = parse_quantity(value, metric.unit())
+                .ok_or_else(|| format!("{name}: expected a number, got \"{value}\""))?;
+            return Ok(FilterTerm {
+                negate,
+                field: None,
+                pattern: FilterPattern::Compare(metric, op, number),
+            });
+        }
+    }
+    // Unknown qualifiers are ordinary text, e.g. "localhost:8080"
+    let (field, value) = match rest.split_once(':') {
+        Some((name, value)) => match FilterField::from_name(name) {
+            Some(field) => (Some(field), value),
+            None => (None, rest),
+        },
+        None => (None, rest),
+    };
+    let pattern = match value.strip_prefix('/') {
+        Some(re) => {
+            let re = re.strip_suffix('/').unwrap_or(re);
+            // Smart case: case-insensitive unless the pattern has capitals
+            let regex = RegexBuilder::new(re)
+                .case_insensitive(!re.chars().any(char::is_uppercase))
+                .build()
+                .map_err(regex_error)?;
+            FilterPattern::Regex(regex)
+        }
+        None => FilterPattern::Text {
+            raw: value.to_string(),
+            lower: value.to_lowercase(),
+        },
+    };
+    Ok(FilterTerm {
+        negate,
+        field,
+        pattern,
+    })
+}
+
+/// Last line of a regex error, which names the actual problem.
+fn regex_error(e: regex::Error) -> String {
+    let msg = e.to_string();
+    let reason = msg.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or(&msg);
+    reason.trim().trim_start_matches("error: ").to_string()
+}
+
+impl FilterTerm {
+    fn matches(&self, proc: &ProcessInfo) -> bool {
+        let hit = match (&self.pattern, self.field) {
+            (FilterPattern::Compare(metric, op, value), _) => op.apply(metric.value(proc), *value),
+            (_, Some(field)) => self.matches_field(proc, field),
+            (_, None) => BARE_FIELDS.iter().any(|&f| self.matches_field(proc, f)),
+        };
+        hit != self.negate
+    }
+
+    fn matches_field(&self, proc: &ProcessInfo, field: FilterField) -> bool {
+        // Containers go by their ID as well as the name the runtime gave
+        if field == FilterField::Container
+            && proc.container_id.as_deref().is_some_and(|id| self.matches_value(field, id))
+        {
+            return true;
+        }
+        field
+            .value(proc)
+            .is_some_and(|value| self.matches_value(field, &value))
+    }
+
+    fn matches_value(&self, field: FilterField, value: &str) -> bool {
+        match &self.pattern {
+            FilterPattern::Regex(re) => re.is_match(value),
+            // Qualified PIDs and terminals match exactly (pts/1 is not
+            // pts/12); a bare number still matches any PID containing it
+            FilterPattern::Text { raw, .. }
+                if self.field.is_some()
+                    && matches!(field, FilterField::Pid | FilterField::Ppid | FilterField::Sid | FilterField::Tty) =>
+            {
+                raw.is_empty() || value == raw
+            }
+            FilterPattern::Text { raw, .. } if field == FilterField::State => {
+                raw.is_empty() || raw.contains(value)
+            }
+            FilterPattern::Text { lower, .. } => value.to_lowercase().contains(lower.as_str()),
+            FilterPattern::Compare(..) => false,
+        }
+    }
+}
+
+/// Whether `proc` passes the filter (an empty filter passes everything).
+pub fn matches_filter(proc: &ProcessInfo, filter: &ProcessFilter) -> bool {
+    filter.terms.iter().all(|t| t.matches(proc))
+}
+
+/// Format bytes into human-readable form (K, M, G).
+pub fn format_bytes(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 * 1024 {
+        format!("{:.1}G", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
+    } else if bytes >= 1024 * 1024 {
+        format!("{:.0}M", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{:.0}K", bytes as f64 / 1024.0)
+    } else {
+        format!("{}B", bytes)
+    }
+}
+
+/// Format a network rate (bytes/sec) into human-readable form with /s suffix.
+pub fn format_rate(bytes_per_sec: f64) -> String {
+    if bytes_per_sec >= 1024.0 * 1024.0 * 1024.0 {
+        format!("{:.1}G/s", bytes_per_sec / (1024.0 * 1024.0 * 1024.0))
+    } else if bytes_per_sec >= 1024.0 * 1024.0 {
+        format!("{:.1}M/s", bytes_per_sec / (1024.0 * 1024.0))
+    } else if bytes_per_sec >= 1024.0 {
+        format!("{:.0}K/s", bytes_per_sec / 1024.0)
+    } else {
+        format!("{:.0}B/s", bytes_per_sec)
+    }
+}
+
+/// Format an event count (cycles, instructions) with decimal K/M/G.
+pub fn format_count(n: f64) -> String {
+    if n >= 1e9 {
+        format!("{:.1}G", n / 1e9)
+    } else if n >= 1e6 {
+        format!("{:.1}M", n / 1e6)
+    } else if n >= 1e3 {
+        format!("{:.0}K", n / 1e3)
+    } else {
+        format!("{n:.0}")
+    }
+}
+
+/// Format CPU time as HH:MM:SS.cc.
+pub fn format_time(secs: f64) -> String {
+    let total_centisecs = (secs * 100.0) as u64;
+    let hours = total_centisecs / 360000;
+    let mins = (total_centisecs % 360000) / 6000;
+    let s = (total_centisecs % 6000) / 100;
+    let cs = total_centisecs % 100;
+    if hours > 0 {
+        format!("{hours}:{mins:02}:{s:02}.{cs:02}")
+    } else {
+        format!("{mins}:{s:02}.{cs:02}")
+    }
+}
+
+/// Format CPU time as HH:MM:SS, or MM:SS under an hour (compact mode).
+pub fn format_time_short(secs: f64) -> String {
+    let total = secs as u64;
+    let hours = total / 3600;
+    let mins = (total % 3600) / 60;
+    let s = total % 60;
+    if hours > 0 {
+        format!("{hours}:{mins:02}:{s:02}")
+    } else {
+        format!("{mins}:{s:02}")
+    }
+}
+
+/// Human-readable duration for sub-millisecond times (eBPF program run
+/// times, scheduling delays).
+pub fn format_ns(ns: f64) -> String {
+    if ns >= 1_000_000.0 {
+        format!("{:.1}ms", ns / 1_000_000.0)
+    } else if ns >= 1_000.0 {
+        format!("{:.1}us", ns / 1_000.0)
+    } else {
+        format!("{ns:.0}ns")
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    pub(crate) fn make_proc(pid: u32, cpu_percent: f64) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            ppid: 1,
+            orig_ppid: 1,
+            pgid: pid,
+            sid: 1,
+            tty: None,
+            uid: 1000,
+            user: String::from("test"),
+            state: ProcessState::Sleeping,
+            priority: 20,
+            nice: 0,
+            virt_bytes: 0,
+            res_bytes: 0,
+            shr_bytes: 0,
+            cpu_percent,
+            mem_percent: 0.0,
+            rss_growth: 0.0,
+            gpu_percent: 0.0,
+            watts: 0.0,
+            gpu_mem_bytes: 0,
+            net_rx_bytes: 0,
+            net_tx_bytes: 0,
+            net_rate: 0.0,
+            net_tx_rate: 0.0,
+            net_rx_rate: 0.0,
+            disk_read_bytes: 0,
+            disk_write_bytes: 0,
+            disk_read_rate: 0.0,
+            disk_write_rate: 0.0,
+            sock_drops: 0,
+            sock_drop_rate: 0.0,
+            net_ifname: String::new(),
+            fd_count: 0,
+            nvcsw: 0,
+            nivcsw: 0,
+            csw_rate: 0.0,
+            icsw_rate: 0.0,
+            runq_wait_ns: 0,
+            runq_waits: 0,
+            runq_latency_ns: 0.0,
+            cpu_time_secs: 0.0,
+            start_time_ns: 0,
+            comm: String::from("test"),
+            cmdline: String::from("test"),
+            container: None,
+            container_id: None,
+            container_image: None,
+            perf: None,
+            pod: None,
+            pod_namespace: None,
+            service: None,
+            badge: None,
+            cgroup_path: String::new(),
+            children: Vec::new(),
+            prev_cpu_ns: 0,
+            is_kernel_thread: false,
+            is_thread: false,
+            tid: pid,
+            tagged: false,
+            frozen: false,
+            thaw_in_secs: None,
+            tree_prefix: String::new(),
+            exited: None,
+            idle: false,
+        }
+    }
+
+    #[test]
+    fn quantized_sort_stability() {
+        // Processes with CPU% that all display as "0.0" should sort by PID
+        let mut procs = vec![
+            make_proc(300, 0.04),
+            make_proc(100, 0.02),
+            make_proc(200, 0.01),
+        ];
+
+        // Sort descending by CPU% (default)
+        procs.sort_by(|a, b| compare_processes(a, b, SortColumn::CpuPercent, false));
+        let pids: Vec<u32> = procs.iter().map(|p| p.pid).collect();
+        // All quantize to 0, so PID tiebreaker (reversed) gives 300, 200, 100
+        assert_eq!(pids, vec![300, 200, 100]);
+
+        // Re-sort with slightly jittered values — order must not change
+        procs[0].cpu_percent = 0.03;
+        procs[1].cpu_percent = 0.05;
+        procs[2].cpu_percent = 0.01;
+        procs.sort_by(|a, b| compare_processes(a, b, SortColumn::CpuPercent, false));
+        let pids2: Vec<u32> = procs.iter().map(|p| p.pid).collect();
+        assert_eq!(pids2, vec![300, 200, 100]);
+    }
+
+    #[test]
+    fn secondary_sort_key_breaks_ties() {
+        let mut procs = [
+            make_proc(1, 2.0),
+            make_proc(2, 9.0),
+            make_proc(3, 5.0),
+            make_proc(4, 1.0),
+        ];
+        procs[0].user = "bob".to_string();
+        procs[1].user = "alice".to_string();
+        procs[2].user = "bob".to_string();
+        procs[3].user = "alice".to_string();
+
+        let keys = [(SortColumn::User, true), (SortColumn::CpuPercent, false)];
+        procs.sort_by(|a, b| compare_processes_by(a, b, &keys));
+        let pids: Vec<u32> = procs.iter().map(|p| p.pid).collect();
+        assert_eq!(pids, vec![2, 4, 3, 1]);
+    }
+
+    #[test]
+    fn quantized_sort_distinguishes_different_values() {
+        // Processes with clearly different CPU% should still sort correctly
+        let mut procs = vec![
+            make_proc(1, 0.5),
+            make_proc(2, 5.0),
+            make_proc(3, 1.2),
+        ];
+
+        procs.sort_by(|a, b| compare_processes(a, b, SortColumn::CpuPercent, false));
+        let pids: Vec<u32> = procs.iter().map(|p| p.pid).collect();
+        assert_eq!(pids, vec![2, 3, 1]);
+    }
+
+    #[test]
+    fn filter_qualifiers_regex_and_negation() {
+        let mut nginx = make_proc(42, 0.0);
+        nginx.user = String::from("www-data");
+        nginx.comm = String::from("nginx");
+        nginx.cmdline = String::from("nginx: worker process");
+        let mut zombie = make_proc(420, 0.0);
+        zombie.state = ProcessState::Zombie;
+
+        let matches = |expr: &str, p: &ProcessInfo| {
+            matches_filter(p, &ProcessFilter::parse(expr).unwrap())
+        };
+        assert!(matches("", &nginx));
+        assert!(matches("NGINX", &nginx));
+        assert!(matches("user:www-data", &nginx));
+        assert!(!matches("user:www-data", &zombie));
+        assert!(matches("/nginx.*worker/", &nginx));
+        assert!(matches("cmd:/: worker pro/", &nginx));
+        assert!(matches("state:DZ", &zombie));
+        assert!(!matches("!state:Z", &zombie));
+        // Qualified PIDs are exact; bare numbers are substrings
+        assert!(matches("42", &zombie));
+        assert!(!matches("pid:42", &zombie));
+        // Unknown qualifiers are plain text
+        assert!(!matches("localhost:8080", &nginx));
+        // Sessions and terminals, for everything started from one login
+        nginx.sid = 4100;
+        nginx.tty = Some(String::from("pts/12"));
+        assert!(matches("sid:4100 tty:pts/12", &nginx));
+        assert!(!matches("tty:pts/1", &nginx));
+        assert!(!matches("tty:pts/12", &zombie));
+        // Containers match by name or ID
+        nginx.container = Some(String::from("web"));
+        nginx.container_id = Some(String::from("0123456789abcdef"));
+        assert!(matches("container:web", &nginx));
+        assert!(matches("container:0123456789ab", &nginx));
+        assert!(!matches("container:fedcba", &nginx));
+        assert!(!matches("container:web", &zombie));
+        assert!(ProcessFilter::parse("/worker(/").is_err());
+    }
+
+    #[test]
+    fn filter_numeric_comparisons() {
+        let mut heavy = make_proc(7, 75.0);
+        heavy.res_bytes = 3 * 1024 * 1024 * 1024;
+        heavy.mem_percent = 20.0;
+        heavy.cpu_time_secs = 7200.0;
+
+        let matches = |expr: &str| matches_filter(&heavy, &ProcessFilter::parse(expr).unwrap());
+        assert!(matches("cpu>50"));
+        assert!(!matches("cpu<=50"));
+        assert!(matches("mem>2G"));
+        assert!(!matches("mem>4G"));
+        assert!(matches("mem>=20"));
+        assert!(matches("mem<25%"));
+        assert!(matches("time>1h"));
+        assert!(matches("!pid=8"));
+        assert!(matches("cpu>50 user:test"));
+        assert!(ProcessFilter::parse("cpu>").is_err());
+        assert!(ProcessFilter::parse("mem>2X").is_err());
+    }
+
+    proptest! {
+        // Filters are typed a character at a time, so every prefix of a
+        // valid expression gets parsed too
+        #[test]
+        fn filter_parse_never_panics(expr in "\\PC*") {
+            if let Ok(filter) = ProcessFilter::parse(&expr) {
+                let _ = matches_filter(&make_proc(1, 0.0), &filter);
+            }
+        }
+
+        #[test]
+        fn filter_negation_inverts(
+            term in "[a-z0-9:<>=.%]{1,12}",
+            pid in 1u32..100_000,
+            cpu in 0.0f64..400.0,
+        ) {
+            let (Ok(plain), Ok(negated)) = (
+                ProcessFilter::parse(&term),
+                ProcessFilter::parse(&format!("!{term}")),
+            ) else {
+                return Ok(());
+            };
+            let proc = make_proc(pid, cpu);
+            prop_assert_eq!(matches_filter(&proc, &negated), !matches_filter(&proc, &plain));
+        }
+
+        #[test]
+        fn filter_terms_are_anded(
+            a in "[a-z0-9:<>=]{1,10}",
+            b in "[a-z0-9:<>=]{1,10}",
+            comm in "[a-z0-9]{1,15}",
+        ) {
+            let (Ok(fa), Ok(fb), Ok(both)) = (
+                ProcessFilter::parse(&a),
+                ProcessFilter::parse(&b),
+                ProcessFilter::parse(&format!("{a} {b}")),
+            ) else {
+                return Ok(());
+            };
+            let mut proc = make_proc(7, 12.5);
+            proc.comm = comm;
+            prop_assert_eq!(
+                matches_filter(&proc, &both),
+                matches_filter(&proc, &fa) && matches_filter(&proc, &fb)
+            );
+        }
+
+        #[test]
+        fn filter_substring_of_comm_matches(comm in "[a-zA-Z0-9_-]{1,15}", start in 0usize..15, len in 1usize..15) {
+            let start = start % comm.len();
+            let end = (start + len).min(comm.len());
+            let needle = &comm[start..end];
+            let mut proc = make_proc(1, 0.0);
+            proc.comm = comm.clone();
+            // Plain text matches case-insensitively
+            let filter = ProcessFilter::parse(&needle.to_uppercase()).unwrap();
+            prop_assert!(matches_filter(&proc, &filter));
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+use super::process::ProcessInfo;
+
+/// Controlling terminal of each session, read once per session from
+/// /proc/<pid>/stat (the task iterator does not follow signal->tty).
+#[derive(Default)]
+pub struct TtyResolver {
+    /// sid -> terminal name, `None` for sessions without one.
+    by_sid: HashMap<u32, Option<String>>,
+}
+
+impl TtyResolver {
+    /// Fill in `tty` for every process, reading /proc only for sessions
+    /// not seen before. Sessions that are gone are forgotten, so a reused
+    /// SID is looked up again.
+    pub fn resolve(&mut self, processes: &mut [ProcessInfo]) {
+        let live: HashSet<u32> = processes.iter().map(|p| p.sid).collect();
+        self.by_sid.retain(|sid, _| live.contains(sid));
+        for proc in processes.iter_mut() {
+            if proc.sid == 0 || proc.is_kernel_thread {
+                continue;
+            }
+            let tty = self.by_sid.entry(proc.sid).or_insert_with(|| {
+                // The leader may have exited; any member shares its terminal
+                read_tty_nr(proc.sid)
+                    .or_else(|| read_tty_nr(proc.pid))
+                    .and_then(tty_name)
+            });
+            proc.tty = tty.clone();
+        }
+    }
+}
+
+/// tty_nr, field 7 of /proc/<pid>/stat.
+fn read_tty_nr(pid: u32) -> Option<u32> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    // comm may contain spaces and parens; fields resume after the last ')'
+    let rest = &stat[stat.rfind(')')? + 1..];
+    rest.split_whitespace().nth(4)?.parse::<i32>().ok().map(|n| n as u32)
+}
+
+/// Device name for a tty_nr, like ps prints it ("pts/3", "tty1"), or
+/// `None` for no controlling terminal.
+fn tty_name(tty_nr: u32) -> Option<String> {
+    if tty_nr == 0 {
+        return None;
+    }
+    let major = (tty_nr >> 8) & 0xfff;
+    let minor = (tty_nr & 0xff) | ((tty_nr >> 12) & 0xfff00);
+    Some(match major {
+        // Unix98 pseudo-terminals span majors 136-143
+        136..=143 => format!("pts/{}", (major - 136) * 256 + minor),
+        4 if minor < 64 => format!("tty{minor}"),
+        4 => format!("ttyS{}", minor - 64),
+        5 if minor == 1 => "console".to_string(),
+        _ => format!("{major}:{minor}"),
+    })
+}
+
+/// One session for the sessions panel: a login shell, an SSH connection, a
+/// tmux server, or a daemon that called setsid().
+#[derive(Debug, Clone)]
+pub struct SessionSummary {
+    pub sid: u32,
+    pub tty: Option<String>,
+    pub user: String,
+    /// Leader's name, or `None` if the leader has exited.
+    pub leader: Option<String>,
+    /// What started the leader (its parent's name), e.g. sshd-session.
+    pub origin: Option<String>,
+    pub processes: usize,
+    pub cpu_percent: f64,
+    pub res_bytes: u64,
+}
+
+/// Sessions of `processes`, terminal sessions first, each in SID order so
+/// rows stay put between refreshes. Only sessions with a controlling
+/// terminal unless `all` is set.
+pub fn summarize(processes: &[ProcessInfo], all: bool) -> Vec<SessionSummary> {
+    let by_pid: HashMap<u32, &ProcessInfo> = processes
+        .iter()
+        .filter(|p| !p.is_thread)
+        .map(|p| (p.pid, p))
+        .collect();
+    let mut sessions: HashMap<u32, SessionSummary> = HashMap::new();
+    for proc in processes {
+        if proc.is_thread || proc.is_kernel_thread || proc.sid == 0 || proc.exited.is_some() {
+            continue;
+        }
+        if proc.tty.is_none() && !all {
+            continue;
+        }
+        let s = sessions.entry(proc.sid).or_insert_with(|| {
+            let leader = by_pid.get(&proc.sid);
+            SessionSummary {
+                sid: proc.sid,
+                tty: proc.tty.clone(),
+                user: leader.map_or_else(|| proc.user.clone(), |l| l.user.clone()),
+                leader: leader.map(|l| l.comm.clone()),
+                origin: leader
+                    .and_then(|l| by_pid.get(&l.ppid))
+                    .map(|parent| parent.comm.clone()),
+                processes: 0,
+                cpu_percent: 0.0,
+                res_bytes: 0,
+            }
+        });
+        s.processes += 1;
+        s.cpu_percent += proc.cpu_percent;
+        s.res_bytes += proc.res_bytes;
+    }
+    let mut sessions: Vec<SessionSummary> = sessions.into_values().collect();
+    sessions.sort_by_key(|s| (s.tty.is_none(), s.sid));
+    sessions
+}
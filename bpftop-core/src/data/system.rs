@@ -0,0 +1,617 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use anyhow::{Context, Result};
+
+use super::gpu::GpuDeviceInfo;
+
+/// System-wide information: CPU, memory, swap, load, uptime.
+#[derive(Debug, Clone, Default)]
+pub struct SystemInfo {
+    pub cpu_total: CpuStats,
+    pub cpus: Vec<CpuStats>,
+    pub memory: MemoryInfo,
+    pub swap: SwapInfo,
+    pub load_avg: [f64; 3],
+    pub uptime_secs: f64,
+    /// Seconds since boot not counting suspend (CLOCK_MONOTONIC), the clock
+    /// process start times are on.
+    pub monotonic_secs: f64,
+    pub total_tasks: u32,
+    pub user_threads: u32,
+    pub kernel_threads: u32,
+    pub running_tasks: u32,
+    #[allow(dead_code)]
+    pub sleeping_tasks: u32,
+    pub gpus: Vec<GpuDeviceInfo>,
+    /// None when the kernel has no PSI (CONFIG_PSI=n or booted with psi=0).
+    pub pressure: Option<Pressure>,
+    /// Whole physical disks, in /proc/diskstats order.
+    pub disks: Vec<BlockDevice>,
+    /// Network interfaces other than loopback, in /proc/net/dev order.
+    pub net_ifaces: Vec<NetInterface>,
+    /// CPU packages with RAPL energy counters; empty without RAPL.
+    pub rapl: Vec<RaplPackage>,
+    /// None without cpufreq or thermal throttle counters (most VMs).
+    pub thermal: Option<CpuThermal>,
+}
+
+impl SystemInfo {
+    /// Power drawn by all CPU packages, `None` without RAPL.
+    pub fn package_watts(&self) -> Option<f64> {
+        (!self.rapl.is_empty()).then(|| self.rapl.iter().map(|p| p.watts).sum())
+    }
+}
+
+/// Per-CPU tick counters from /proc/stat.
+#[derive(Debug, Clone, Default)]
+pub struct CpuStats {
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
+    pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+    // Computed percentages (from delta)
+    pub user_pct: f64,
+    pub system_pct: f64,
+    pub nice_pct: f64,
+    pub iowait_pct: f64,
+    pub idle_pct: f64,
+    pub total_pct: f64,
+}
+
+impl CpuStats {
+    pub fn total_ticks(&self) -> u64 {
+        self.user + self.nice + self.system + self.idle + self.iowait + self.irq + self.softirq + self.steal
+    }
+
+    pub fn busy_ticks(&self) -> u64 {
+        self.total_ticks() - self.idle - self.iowait
+    }
+
+    /// Calculate percentage deltas from a previous sample.
+    pub fn calc_percentages(&mut self, prev: &CpuStats) {
+        let total_delta = self.total_ticks().saturating_sub(prev.total_ticks());
+        if total_delta == 0 {
+            return;
+        }
+        let d = total_delta as f64;
+        self.user_pct = (self.user.saturating_sub(prev.user)) as f64 / d * 100.0;
+        self.nice_pct = (self.nice.saturating_sub(prev.nice)) as f64 / d * 100.0;
+        self.system_pct = (self.system.saturating_sub(prev.system)) as f64 / d * 100.0;
+        self.iowait_pct = (self.iowait.saturating_sub(prev.iowait)) as f64 / d * 100.0;
+        self.idle_pct = (self.idle.saturating_sub(prev.idle)) as f64 / d * 100.0;
+        self.total_pct = (self.busy_ticks().saturating_sub(prev.busy_ticks())) as f64 / d * 100.0;
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MemoryInfo {
+    pub total: u64,
+    pub free: u64,
+    pub available: u64,
+    pub buffers: u64,
+    pub cached: u64,
+    pub s_reclaimable: u64,
+    pub used: u64,
+    /// Live physical RAM backing zram (3rd field of mm_stat).
+    pub zram_mem_used: u64,
+}
+
+impl MemoryInfo {
+    pub fn used_pct(&self) -> f64 {
+        if self.total == 0 { 0.0 } else { self.used as f64 / self.total as f64 * 100.0 }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SwapInfo {
+    pub total: u64,
+    pub free: u64,
+    pub used: u64,
+    /// zram compression ratio (orig_data / compr_data); None if no zram or no data stored.
+    pub zram_compression_ratio: Option<f64>,
+}
+
+impl SwapInfo {
+    #[allow(dead_code)]
+    pub fn used_pct(&self) -> f64 {
+        if self.total == 0 { 0.0 } else { self.used as f64 / self.total as f64 * 100.0 }
+    }
+}
+
+/// Read CPU stats from /proc/stat.
+pub fn read_cpu_stats() -> Result<(CpuStats, Vec<CpuStats>)> {
+    let content = fs::read_to_string("/proc/stat").context("reading /proc/stat")?;
+    let mut total = CpuStats::default();
+    let mut cpus = Vec::new();
+
+    for line in content.lines() {
+        if line.starts_with("cpu ") {
+            total = parse_cpu_line(line);
+        } else if line.starts_with("cpu") {
+            cpus.push(parse_cpu_line(line));
+        }
+    }
+
+    Ok((total, cpus))
+}
+
+fn parse_cpu_line(line: &str) -> CpuStats {
+    let parts: Vec<u64> = line
+        .split_whitespace()
+        .skip(1)
+        .filter_map(|s| s.parse().ok())
+        .collect();
+
+    CpuStats {
+        user: parts.first().copied().unwrap_or(0),
+        nice: parts.get(1).copied().unwrap_or(0),
+        system: parts.get(2).copied().unwrap_or(0),
+        idle: parts.get(3).copied().unwrap_or(0),
+        iowait: parts.get(4).copied().unwrap_or(0),
+        irq: parts.get(5).copied().unwrap_or(0),
+        softirq: parts.get(6).copied().unwrap_or(0),
+        steal: parts.get(7).copied().unwrap_or(0),
+        ..Default::default()
+    }
+}
+
+/// Read memory info from /proc/meminfo.
+pub fn read_memory_info() -> Result<(MemoryInfo, SwapInfo)> {
+    let content = fs::read_to_string("/proc/meminfo").context("reading /proc/meminfo")?;
+
+    let mut mem = MemoryInfo::default();
+    let mut swap = SwapInfo::default();
+
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let key = match parts.next() {
+            Some(k) => k.trim_end_matches(':'),
+            None => continue,
+        };
+        let val: u64 = match parts.next().and_then(|v| v.parse::<u64>().ok()) {
+            Some(v) => v * 1024, // Convert from kB to bytes
+            None => continue,
+        };
+
+        match key {
+            "MemTotal" => mem.total = val,
+            "MemFree" => mem.free = val,
+            "MemAvailable" => mem.available = val,
+            "Buffers" => mem.buffers = val,
+            "Cached" => mem.cached = val,
+            "SReclaimable" => mem.s_reclaimable = val,
+            "SwapTotal" => swap.total = val,
+            "SwapFree" => swap.free = val,
+            _ => {}
+        }
+    }
+
+    mem.used = mem.total.saturating_sub(mem.free + mem.buffers + mem.cached + mem.s_reclaimable);
+    let (zram_mem_used, zram_ratio) = read_zram_stats();
+    mem.zram_mem_used = zram_mem_used;
+    swap.used = swap.total.saturating_sub(swap.free);
+    swap.zram_compression_ratio = zram_ratio;
+
+    Ok((mem, swap))
+}
+
+/// Read zram stats from /sys/block/zram*/mm_stat.
+/// Returns (total_mem_used, compression_ratio).
+/// mm_stat fields: orig_data_size compr_data_size mem_used_total ...
+fn read_zram_stats() -> (u64, Option<f64>) {
+    let Ok(entries) = fs::read_dir("/sys/block") else { return (0, None) };
+    let mut total_mem_used = 0u64;
+    let mut total_orig = 0u64;
+    let mut total_compr = 0u64;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else { continue };
+        if !name.starts_with("zram") { continue; }
+        if let Ok(content) = fs::read_to_string(entry.path().join("mm_stat")) {
+            let fields: Vec<u64> = content
+                .split_whitespace()
+                .take(3)
+                .filter_map(|s| s.parse().ok())
+                .collect();
+            if fields.len() >= 3 {
+                total_orig += fields[0];
+                total_compr += fields[1];
+                total_mem_used += fields[2];
+            }
+        }
+    }
+    let ratio = if total_compr > 0 {
+        Some(total_orig as f64 / total_compr as f64)
+    } else {
+        None
+    };
+    (total_mem_used, ratio)
+}
+
+/// Read load averages from /proc/loadavg.
+pub fn read_load_avg() -> Result<[f64; 3]> {
+    let content = fs::read_to_string("/proc/loadavg").context("reading /proc/loadavg")?;
+    let parts: Vec<&str> = content.split_whitespace().collect();
+    let load1 = parts.first().and_then(|s| s.parse().ok()).unwrap_or(0.0);
+    let load5 = parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(0.0);
+    let load15 = parts.get(2).and_then(|s| s.parse().ok()).unwrap_or(0.0);
+    Ok([load1, load5, load15])
+}
+
+/// Share of wall time some task was stalled on a resource, in percent,
+/// from the "some" line of a /proc/pressure file.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PsiAverages {
+    pub avg10: f64,
+    pub avg60: f64,
+}
+
+/// Pressure stall information for CPU, memory and I/O.
+#[derive(Debug, Clone, Default)]
+pub struct Pressure {
+    pub cpu: PsiAverages,
+    pub memory: PsiAverages,
+    pub io: PsiAverages,
+}
+
+/// Read /proc/pressure/{cpu,memory,io}. Returns None if PSI is unavailable.
+pub fn read_pressure() -> Option<Pressure> {
+    let read = |name: &str| {
+        let content = fs::read_to_string(format!("/proc/pressure/{name}")).ok()?;
+        parse_psi_some(&content)
+    };
+    Some(Pressure {
+        cpu: read("cpu")?,
+        memory: read("memory")?,
+        io: read("io")?,
+    })
+}
+
+/// Parse "some avg10=0.12 avg60=0.05 avg300=0.00 total=12345".
+pub fn parse_psi_some(content: &str) -> Option<PsiAverages> {
+    let line = content.lines().find(|l| l.starts_with("some "))?;
+    let mut avgs = PsiAverages::default();
+    for field in line.split_whitespace().skip(1) {
+        match field.split_once('=') {
+            Some(("avg10", v)) => avgs.avg10 = v.parse().ok()?,
+            Some(("avg60", v)) => avgs.avg60 = v.parse().ok()?,
+            _ => {}
+        }
+    }
+    Some(avgs)
+}
+
+/// One disk's counters from /proc/diskstats.
+#[derive(Debug, Clone, Default)]
+pub struct BlockDevice {
+    pub name: String,
+    pub sectors_read: u64,
+    pub sectors_written: u64,
+    /// Milliseconds the device had I/O in flight.
+    pub io_ticks_ms: u64,
+    // Computed rates (from delta)
+    pub read_rate: f64,
+    pub write_rate: f64,
+    /// Share of wall time the device was busy, like iostat's %util.
+    pub busy_pct: f64,
+}
+
+impl BlockDevice {
+    /// Calculate rates from a previous sample taken `secs` ago.
+    pub fn calc_rates(&mut self, prev: &BlockDevice, secs: f64) {
+        self.read_rate = self.sectors_read.saturating_sub(prev.sectors_read) as f64 * 512.0 / secs;
+        self.write_rate = self.sectors_written.saturating_sub(prev.sectors_written) as f64 * 512.0 / secs;
+        let busy_ms = self.io_ticks_ms.saturating_sub(prev.io_ticks_ms) as f64;
+        self.busy_pct = (busy_ms / (secs * 1000.0) * 100.0).min(100.0);
+    }
+}
+
+/// One CPU package's RAPL energy counter, from /sys/class/powercap.
+#[derive(Debug, Clone, Default)]
+pub struct RaplPackage {
+    /// Powercap zone, e.g. "intel-rapl:0" (AMD's RAPL uses the same name).
+    pub zone: String,
+    pub energy_uj: u64,
+    /// The counter wraps to 0 past this.
+    pub max_energy_uj: u64,
+    // Computed rate (from delta)
+    pub watts: f64,
+}
+
+impl RaplPackage {
+    /// Calculate power from a previous sample taken `secs` ago.
+    pub fn calc_rates(&mut self, prev: &RaplPackage, secs: f64) {
+        let used_uj = if self.energy_uj >= prev.energy_uj {
+            self.energy_uj - prev.energy_uj
+        } else {
+            self.max_energy_uj.saturating_sub(prev.energy_uj) + self.energy_uj
+        };
+        self.watts = used_uj as f64 / 1e6 / secs;
+    }
+}
+
+/// CPU frequency and thermal throttling across all CPUs, from sysfs.
+#[derive(Debug, Clone, Default)]
+pub struct CpuThermal {
+    /// Mean current frequency over the CPUs with cpufreq, in kHz.
+    pub cur_khz: u64,
+    /// Highest rated frequency of any CPU, in kHz.
+    pub max_khz: u64,
+    /// Core and package throttle events since boot. The kernel counts them
+    /// in its thermal interrupt handler from the THERM_STATUS MSRs; `None`
+    /// on CPUs without them (AMD, ARM).
+    pub throttle_count: Option<u64>,
+    // Computed (from delta)
+    pub new_throttles: u64,
+}
+
+impl CpuThermal {
+    /// Count the throttle events since a previous sample.
+    pub fn calc_rates(&mut self, prev: &CpuThermal) {
+        if let (Some(count), Some(prev)) = (self.throttle_count, prev.throttle_count) {
+            self.new_throttles = count.saturating_sub(prev);
+        }
+    }
+
+    /// Whether any CPU was throttled over the last refresh.
+    pub fn throttling(&self) -> bool {
+        self.new_throttles > 0
+    }
+
+    /// Current frequency as a share of the rated maximum.
+    pub fn freq_percent(&self) -> Option<f64> {
+        (self.max_khz > 0 && self.cur_khz > 0).then(|| self.cur_khz as f64 * 100.0 / self.max_khz as f64)
+    }
+}
+
+/// Read cpufreq and the thermal_throttle counts of every CPU. A package's
+/// count shows up under each of its CPUs, so it is taken once per
+/// physical_package_id.
+pub fn read_cpu_thermal() -> Option<CpuThermal> {
+    let cpus = fs::read_dir("/sys/devices/system/cpu").ok()?;
+    let read = |path: &Path, file: &str| -> Option<u64> {
+        fs::read_to_string(path.join(file)).ok()?.trim().parse().ok()
+    };
+    let mut thermal = CpuThermal::default();
+    let (mut freq_sum, mut freq_cpus) = (0, 0);
+    let mut throttles = None;
+    let mut packages = HashSet::new();
+    for cpu in cpus.flatten() {
+        let name = cpu.file_name();
+        let Some(n) = name.to_str().and_then(|n| n.strip_prefix("cpu")) else {
+            continue;
+        };
+        if n.is_empty() || !n.bytes().all(|b| b.is_ascii_digit()) {
+            continue;
+        }
+        let path = cpu.path();
+        if let Some(khz) = read(&path, "cpufreq/scaling_cur_freq") {
+            freq_sum += khz;
+            freq_cpus += 1;
+        }
+        if let Some(khz) = read(&path, "cpufreq/cpuinfo_max_freq") {
+            thermal.max_khz = thermal.max_khz.max(khz);
+        }
+        if let Some(count) = read(&path, "thermal_throttle/core_throttle_count") {
+            *throttles.get_or_insert(0) += count;
+        }
+        let package = read(&path, "topology/physical_package_id");
+        if packages.insert(package) {
+            if let Some(count) = read(&path, "thermal_throttle/package_throttle_count") {
+                *throttles.get_or_insert(0) += count;
+            }
+        }
+    }
+    if freq_cpus == 0 && throttles.is_none() {
+        return None;
+    }
+    thermal.cur_khz = freq_sum.checked_div(freq_cpus).unwrap_or(0);
+    thermal.throttle_count = throttles;
+    Some(thermal)
+}
+
+/// Read the package-level RAPL zones. Their core/uncore/dram subzones
+/// (`intel-rapl:0:0`) are skipped, being part of the package or outside
+/// the CPU, as is `psys`, which covers the whole platform. energy_uj is
+/// root-only since CVE-2020-8694.
+pub fn read_rapl_packages() -> Vec<RaplPackage> {
+    let Ok(zones) = fs::read_dir("/sys/class/powercap") else {
+        return Vec::new();
+    };
+    let mut packages: Vec<RaplPackage> = zones
+        .flatten()
+        .filter_map(|zone| {
+            let name = zone.file_name().to_str()?.to_string();
+            if name.strip_prefix("intel-rapl:")?.contains(':') {
+                return None;
+            }
+            let path = zone.path();
+            let read = |file: &str| fs::read_to_string(path.join(file)).ok();
+            if !read("name")?.starts_with("package") {
+                return None;
+            }
+            Some(RaplPackage {
+                zone: name,
+                energy_uj: read("energy_uj")?.trim().parse().ok()?,
+                max_energy_uj: read("max_energy_range_uj")?.trim().parse().ok()?,
+                watts: 0.0,
+            })
+        })
+        .collect();
+    packages.sort_by(|a, b| a.zone.cmp(&b.zone));
+    packages
+}
+
+/// Read whole-disk counters from /proc/diskstats. Partitions are skipped
+/// (they only appear under their parent in /sys/block), as are loop, ram and
+/// zram devices and device-mapper/md volumes, which would count the I/O of
+/// the disks beneath them a second time.
+pub fn read_block_devices() -> Result<Vec<BlockDevice>> {
+    let content = fs::read_to_string("/proc/diskstats").context("reading /proc/diskstats")?;
+    let mut disks = Vec::new();
+    for line in content.lines() {
+        // major minor name reads merged sectors_read ms writes merged sectors_written ms in_flight io_ticks ...
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 13 {
+            continue;
+        }
+        let name = fields[2];
+        if ["loop", "ram", "zram", "dm-", "md"].iter().any(|p| name.starts_with(p))
+            || !Path::new("/sys/block").join(name).exists()
+        {
+            continue;
+        }
+        let num = |i: usize| fields[i].parse::<u64>().unwrap_or(0);
+        disks.push(BlockDevice {
+            name: name.to_string(),
+            sectors_read: num(5),
+            sectors_written: num(9),
+            io_ticks_ms: num(12),
+            ..Default::default()
+        });
+    }
+    Ok(disks)
+}
+
+/// One network interface's counters from /proc/net/dev.
+#[derive(Debug, Clone, Default)]
+pub struct NetInterface {
+    pub name: String,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    /// Backed by a device (NIC, Wi-Fi) rather than a bridge, veth or tunnel.
+    pub physical: bool,
+    /// Link speed in bytes/s, 0 if the driver doesn't report one.
+    pub speed: f64,
+    // Computed rates (from delta)
+    pub rx_rate: f64,
+    pub tx_rate: f64,
+    /// Fastest rate in either direction seen so far, the bar's scale when
+    /// the link speed is unknown.
+    pub peak_rate: f64,
+    /// Busier direction as a share of the link speed (or of `peak_rate`).
+    pub util_pct: f64,
+}
+
+impl NetInterface {
+    /// Calculate rates from a previous sample taken `secs` ago.
+    pub fn calc_rates(&mut self, prev: &NetInterface, secs: f64) {
+        self.rx_rate = self.rx_bytes.saturating_sub(prev.rx_bytes) as f64 / secs;
+        self.tx_rate = self.tx_bytes.saturating_sub(prev.tx_bytes) as f64 / secs;
+        let busiest = self.rx_rate.max(self.tx_rate);
+        self.peak_rate = prev.peak_rate.max(busiest);
+        let scale = if self.speed > 0.0 { self.speed } else { self.peak_rate };
+        self.util_pct = if scale > 0.0 { (busiest / scale * 100.0).min(100.0) } else { 0.0 };
+    }
+}
+
+/// Read interface byte counters from /proc/net/dev, skipping loopback.
+pub fn read_net_interfaces() -> Result<Vec<NetInterface>> {
+    let content = fs::read_to_string("/proc/net/dev").context("reading /proc/net/dev")?;
+    let mut ifaces = Vec::new();
+    // Two header lines, then "name: rx_bytes packets errs drop fifo frame compressed multicast tx_bytes ..."
+    for line in content.lines().skip(2) {
+        let Some((name, counters)) = line.split_once(':') else {
+            continue;
+        };
+        let name = name.trim();
+        let fields: Vec<&str> = counters.split_whitespace().collect();
+        if name == "lo" || fields.len() < 9 {
+            continue;
+        }
+        let sys = Path::new("/sys/class/net").join(name);
+        // speed is in Mbit/s, -1 (or unreadable) when unknown
+        let speed = fs::read_to_string(sys.join("speed"))
+            .ok()
+            .and_then(|s| s.trim().parse::<i64>().ok())
+            .filter(|&mbit| mbit > 0)
+            .map_or(0.0, |mbit| mbit as f64 * 1_000_000.0 / 8.0);
+        ifaces.push(NetInterface {
+            name: name.to_string(),
+            rx_bytes: fields[0].parse().unwrap_or(0),
+            tx_bytes: fields[8].parse().unwrap_or(0),
+            physical: sys.join("device").exists(),
+            speed,
+            ..Default::default()
+        });
+    }
+    Ok(ifaces)
+}
+
+/// Mount point of each mounted filesystem, keyed by kernel dev_t
+/// (major << 20 | minor, as in `super_block.s_dev`). Where a filesystem is
+/// mounted more than once (bind mounts, containers) the shortest path wins.
+pub fn read_mount_points() -> Result<HashMap<u32, String>> {
+    let content =
+        fs::read_to_string("/proc/self/mountinfo").context("reading /proc/self/mountinfo")?;
+    let mut mounts = HashMap::<u32, String>::new();
+    for line in content.lines() {
+        // id parent major:minor root mount_point options ...
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (Some(dev), Some(path)) = (fields.get(2), fields.get(4)) else {
+            continue;
+        };
+        let Some((major, minor)) = dev.split_once(':') else {
+            continue;
+        };
+        let (Ok(major), Ok(minor)) = (major.parse::<u32>(), minor.parse::<u32>()) else {
+            continue;
+        };
+        // mountinfo octal-escapes spaces in paths
+        let path = path.replace("\\040", " ");
+        let entry = mounts.entry(major << 20 | minor).or_insert_with(|| path.clone());
+        if path.len() < entry.len() {
+            *entry = path;
+        }
+    }
+    Ok(mounts)
+}
+
+/// Read system uptime from /proc/uptime.
+pub fn read_uptime() -> Result<f64> {
+    let content = fs::read_to_string("/proc/uptime").context("reading /proc/uptime")?;
+    let uptime: f64 = content
+        .split_whitespace()
+        .next()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0.0);
+    Ok(uptime)
+}
+
+/// Nanoseconds spent suspended since boot: CLOCK_BOOTTIME keeps counting
+/// through a system suspend, CLOCK_MONOTONIC doesn't.
+pub fn read_suspended_ns() -> u64 {
+    read_clock_ns(libc::CLOCK_BOOTTIME).saturating_sub(read_monotonic_ns())
+}
+
+/// CLOCK_MONOTONIC in nanoseconds, as task_struct.start_time counts.
+pub fn read_monotonic_ns() -> u64 {
+    read_clock_ns(libc::CLOCK_MONOTONIC)
+}
+
+fn read_clock_ns(clock: libc::clockid_t) -> u64 {
+    let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    // SAFETY: ts is a valid timespec to write to; both clocks read here
+    // exist on every kernel bpftop runs on
+    unsafe { libc::clock_gettime(clock, &mut ts) };
+    ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64
+}
+
+/// Format uptime as Xd HH:MM:SS.
+pub fn format_uptime(secs: f64) -> String {
+    let total = secs as u64;
+    let days = total / 86400;
+    let hours = (total % 86400) / 3600;
+    let mins = (total % 3600) / 60;
+    let s = total % 60;
+    if days > 0 {
+        format!("{days}d {hours:02}:{mins:02}:{s:02}")
+    } else {
+        format!("{hours:02}:{mins:02}:{s:02}")
+    }
+}
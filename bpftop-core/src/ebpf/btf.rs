@@ -0,0 +1,226 @@
+//! Checks the compiled-in task_struct offsets against the running kernel.
+//!
+//! The eBPF programs read kernel structs at hardcoded offsets (there is no
+//! CO-RE in aya-ebpf), so a kernel whose layout differs from the one the
+//! object was built for silently yields garbage. Before loading, the
+//! compiled-in `TASK_OFFSETS` global is read out of the ELF, the same
+//! fields are looked up in /sys/kernel/btf/vmlinux, and the loader patches
+//! in whatever differs. The msghdr/iov_iter offsets of the DNS kprobes are
+//! resolved the same way.
+
+use std::fs;
+
+use anyhow::{bail, Context, Result};
+use bpftop_common::btf::Btf;
+use bpftop_common::{MsgOffsets, TaskOffsets};
+
+const BTF_PATH: &str = "/sys/kernel/btf/vmlinux";
+
+/// Global in the eBPF object holding its `TaskOffsets`.
+pub const TASK_OFFSETS_SYMBOL: &str = "TASK_OFFSETS";
+
+/// Global in the eBPF object holding its `MsgOffsets`.
+pub const MSG_OFFSETS_SYMBOL: &str = "MSG_OFFSETS";
+
+/// One `TaskOffsets` slot: field name in task_struct, and its accessor.
+type OffsetField = (&'static str, fn(&mut TaskOffsets) -> &mut u32);
+
+const TASK_FIELDS: &[OffsetField] = &[
+    ("__state", |o| &mut o.state),
+    ("flags", |o| &mut o.flags),
+    ("prio", |o| &mut o.prio),
+    ("static_prio", |o| &mut o.static_prio),
+    ("mm", |o| &mut o.mm),
+    ("pid", |o| &mut o.pid),
+    ("tgid", |o| &mut o.tgid),
+    ("real_parent", |o| &mut o.real_parent),
+    ("utime", |o| &mut o.utime),
+    ("stime", |o| &mut o.stime),
+    ("nvcsw", |o| &mut o.nvcsw),
+    ("nivcsw", |o| &mut o.nivcsw),
+    ("start_time", |o| &mut o.start_time),
+    ("cred", |o| &mut o.cred),
+    ("comm", |o| &mut o.comm),
+    ("cgroups", |o| &mut o.cgroups),
+    ("signal", |o| &mut o.signal),
+];
+
+/// A task_struct field whose compiled-in offset is wrong for this kernel.
+#[derive(Debug, Clone)]
+pub struct OffsetFix {
+    pub field: &'static str,
+    pub compiled: u32,
+    pub kernel: u32,
+}
+
+/// The running kernel's BTF.
+pub fn read_kernel_btf() -> Result<Btf> {
+    let data = fs::read(BTF_PATH).with_context(|| format!("reading {BTF_PATH}"))?;
+    Btf::parse(&data).with_context(|| format!("parsing {BTF_PATH}"))
+}
+
+/// Offsets to load the programs with, plus what had to change.
+pub fn resolve_task_offsets(obj: &[u8], btf: &Btf) -> Result<(TaskOffsets, Vec<OffsetFix>)> {
+    let compiled = compiled_task_offsets(obj)?;
+    let task = btf
+        .struct_id("task_struct")
+        .context("task_struct not found in kernel BTF")?;
+
+    let mut offsets = compiled;
+    let mut fixes = Vec::new();
+    for &(field, slot) in TASK_FIELDS {
+        let Ok(kernel) = btf.offset_of(task, field) else {
+            bail!(
+                "task_struct.{field} not found in kernel BTF; this kernel is not supported \
+                 by the compiled eBPF object (rebuild with the matching kernel-* feature)"
+            );
+        };
+        let kernel = kernel as u32;
+        let current = slot(&mut offsets);
+        if *current != kernel {
+            fixes.push(OffsetFix {
+                field,
+                compiled: *current,
+                kernel,
+            });
+            *current = kernel;
+        }
+    }
+    Ok((offsets, fixes))
+}
+
+/// Offsets for the DNS kprobes on this kernel. Fails before 6.4, where
+/// iov_iter numbers its iter_type values differently; that layout is told
+/// apart by `__iov` still being called `iov`.
+pub fn resolve_msg_offsets(btf: &Btf) -> Result<MsgOffsets> {
+    let offset = |ty: &str, path: &str| -> Result<u32> {
+        let id = btf
+            .struct_id(ty)
+            .with_context(|| format!("{ty} not found in kernel BTF"))?;
+        let offset = btf
+            .offset_of(id, path)
+            .with_context(|| format!("{ty}.{path} not found in kernel BTF (needs 6.4+)"))?;
+        Ok(offset as u32)
+    };
+    Ok(MsgOffsets {
+        msg_iter: offset("msghdr", "msg_iter")?,
+        iter_type: offset("iov_iter", "iter_type")?,
+        iter_ptr: offset("iov_iter", "__iov")?,
+        iter_count: offset("iov_iter", "count")?,
+    })
+}
+
+/// Read the `TASK_OFFSETS` global's initial value out of the eBPF ELF.
+fn compiled_task_offsets(obj: &[u8]) -> Result<TaskOffsets> {
+    let bytes = elf_symbol_data(obj, TASK_OFFSETS_SYMBOL)
+        .with_context(|| format!("{TASK_OFFSETS_SYMBOL} not found in eBPF object"))?;
+    if bytes.len() < std::mem::size_of::<TaskOffsets>() {
+        bail!("{TASK_OFFSETS_SYMBOL} is smaller than TaskOffsets");
+    }
+    Ok(unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const TaskOffsets) })
+}
+
+fn u16_at(data: &[u8], off: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(off..off + 2)?.try_into().ok()?))
+}
+
+fn u32_at(data: &[u8], off: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(off..off + 4)?.try_into().ok()?))
+}
+
+fn u64_at(data: &[u8], off: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(off..off + 8)?.try_into().ok()?))
+}
+
+/// Bytes of a defined symbol in a little-endian ELF64 relocatable object
+/// (what bpf-linker emits).
+fn elf_symbol_data<'a>(obj: &'a [u8], name: &str) -> Option<&'a [u8]> {
+    const SHT_SYMTAB: u32 = 2;
+    if obj.get(..4)? != b"\x7fELF" {
+        return None;
+    }
+    let shoff = u64_at(obj, 0x28)? as usize;
+    let shentsize = u16_at(obj, 0x3a)? as usize;
+    let shnum = u16_at(obj, 0x3c)? as usize;
+    let section = |idx: usize| {
+        let base = shoff + idx * shentsize;
+        Some((
+            u32_at(obj, base + 4)?,            // sh_type
+            u64_at(obj, base + 24)? as usize,  // sh_offset
+            u64_at(obj, base + 32)? as usize,  // sh_size
+            u32_at(obj, base + 40)? as usize,  // sh_link
+        ))
+    };
+
+    let (_, sym_off, sym_size, strtab) = (0..shnum)
+        .filter_map(section)
+        .find(|s| s.0 == SHT_SYMTAB)?;
+    let (_, str_off, _, _) = section(strtab)?;
+    for sym in (sym_off..sym_off + sym_size).step_by(24) {
+        let name_off = str_off + u32_at(obj, sym)? as usize;
+        let end = name_off + obj.get(name_off..)?.iter().position(|&b| b == 0)?;
+        if &obj[name_off..end] != name.as_bytes() {
+            continue;
+        }
+        let shndx = u16_at(obj, sym + 6)? as usize;
+        let value = u64_at(obj, sym + 8)? as usize;
+        let size = u64_at(obj, sym + 16)? as usize;
+        let (_, data_off, _, _) = section(shndx)?;
+        return obj.get(data_off + value..data_off + value + size);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ELF64 relocatable object with `data` in a section, described by a
+    /// symbol called `name`.
+    fn elf_with_symbol(name: &str, data: &[u8]) -> Vec<u8> {
+        let strtab = [b"\0", name.as_bytes(), b"\0"].concat();
+        let mut symtab = vec![0u8; 24]; // the null symbol
+        symtab.extend_from_slice(&1u32.to_le_bytes()); // st_name
+        symtab.extend_from_slice(&[0x11, 0]); // st_info (global object), st_other
+        symtab.extend_from_slice(&1u16.to_le_bytes()); // st_shndx: the data section
+        symtab.extend_from_slice(&0u64.to_le_bytes()); // st_value
+        symtab.extend_from_slice(&(data.len() as u64).to_le_bytes());
+
+        let mut obj = vec![0u8; 64];
+        obj[..4].copy_from_slice(b"\x7fELF");
+        let mut sections = vec![(0u32, 0usize, 0usize, 0u32)];
+        for (sh_type, bytes, link) in [(1, data, 0), (2, &symtab[..], 3), (3, &strtab[..], 0)] {
+            sections.push((sh_type, obj.len(), bytes.len(), link));
+            obj.extend_from_slice(bytes);
+        }
+        let shoff = obj.len() as u64;
+        for (sh_type, offset, size, link) in sections {
+            let mut header = vec![0u8; 64];
+            header[4..8].copy_from_slice(&sh_type.to_le_bytes());
+            header[24..32].copy_from_slice(&(offset as u64).to_le_bytes());
+            header[32..40].copy_from_slice(&(size as u64).to_le_bytes());
+            header[40..44].copy_from_slice(&link.to_le_bytes());
+            obj.extend_from_slice(&header);
+        }
+        obj[0x28..0x30].copy_from_slice(&shoff.to_le_bytes());
+        obj[0x3a..0x3c].copy_from_slice(&64u16.to_le_bytes());
+        obj[0x3c..0x3e].copy_from_slice(&4u16.to_le_bytes());
+        obj
+    }
+
+    #[test]
+    fn reads_compiled_offsets_from_the_object() {
+        let offsets: Vec<u8> = (0..TASK_FIELDS.len() as u32)
+            .flat_map(|i| (i * 8).to_le_bytes())
+            .collect();
+        let obj = elf_with_symbol(TASK_OFFSETS_SYMBOL, &offsets);
+        let compiled = compiled_task_offsets(&obj).unwrap();
+        assert_eq!(compiled.state, 0);
+        assert_eq!(compiled.flags, 8);
+        assert_eq!(compiled.signal, 128);
+
+        assert_eq!(elf_symbol_data(&obj, "OTHER"), None);
+        assert!(compiled_task_offsets(&elf_with_symbol(TASK_OFFSETS_SYMBOL, &offsets[..8])).is_err());
+        assert!(compiled_task_offsets(b"not an elf").is_err());
+    }
+}
@@ -0,0 +1,1269 @@
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::os::fd::OwnedFd;
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{Context, Result};
+use aya::maps::{Array, HashMap as BpfHashMap, Map, MapData, RingBuf};
+use aya::programs::iter::{Iter, IterLink};
+use aya::programs::kprobe::KProbeLinkId;
+use aya::programs::trace_point::TracePointLinkId;
+use aya::programs::{FEntry, FExit, KProbe, Program, TracePoint};
+use aya::{Btf, Ebpf, Pod};
+use super::btf::{
+    read_kernel_btf, resolve_msg_offsets, resolve_task_offsets, MSG_OFFSETS_SYMBOL, TASK_OFFSETS_SYMBOL,
+};
+use bpftop_common::{
+    BioLatency, CmdlineEvent, CmdlineKey, DevWriteKey, DiskStats, DnsEvent, DropKey, ExecEvent, ExitEvent, FileInfo, NetStats, OffCpuStart, OffCpuTime,
+    OomEvent, PeerKey, PeerStats, RunqLatency, TcpConn, TaskInfo, DROP_LISTEN_OVERFLOW, DROP_RCVBUF, OFFCPU_STATES,
+    OOM_FIELD_ANON_RSS, OOM_FIELD_COMM, OOM_FIELD_FILE_RSS, OOM_FIELD_SHMEM_RSS,
+};
+
+/// The compiled eBPF object. Built by xtask (cargo xtask build-ebpf)
+/// before the userspace crate is compiled.
+///
+/// Path is relative to this source file:
+///   loader.rs  ∈  bpftop-core/src/ebpf/
+///   workspace root = ../../../
+///   eBPF binary    = bpftop-ebpf/target/bpfel-unknown-none/release/bpftop-ebpf
+///
+/// NOTE: eBPF programs MUST be built in release mode because debug builds
+/// include core::fmt code that exceeds BPF's function argument limit.
+static BPF_OBJ: &[u8] =
+    aya::include_bytes_aligned!("../../../bpftop-ebpf/target/bpfel-unknown-none/release/bpftop-ebpf");
+
+/// Manages eBPF program loading, attachment, and data retrieval.
+pub struct EbpfLoader {
+    bpf: Option<Ebpf>,
+    /// Consumer sides of the EXEC_EVENTS/EXIT_EVENTS/OOM_EVENTS/DNS_EVENTS
+    /// ring buffers (taken out of `bpf`).
+    exec_events: Option<RingBuf<MapData>>,
+    exit_events: Option<RingBuf<MapData>>,
+    oom_events: Option<RingBuf<MapData>>,
+    dns_events: Option<RingBuf<MapData>>,
+    unavailable: Vec<Subsystem>,
+    /// Keeps kernel BPF run-time accounting on while held.
+    run_stats: Option<OwnedFd>,
+    /// TGID the off-CPU programs count (0 = detached), and their links.
+    offcpu_target: u32,
+    offcpu_links: Vec<(&'static str, TracePointLinkId)>,
+    /// Links of the attached programs of on-demand subsystems.
+    on_demand_links: Vec<(Subsystem, &'static str, ProgramLink)>,
+    /// Iterator output buffer, kept across reads so it is allocated once
+    /// at its high-water size rather than regrown every refresh.
+    iter_buf: Vec<u8>,
+}
+
+/// Fill level of one BPF hash map.
+#[derive(Debug, Clone)]
+pub struct MapUsage {
+    pub name: &'static str,
+    pub entries: u32,
+    pub max_entries: u32,
+}
+
+/// Counts the entries of one map.
+type EntryCounter = fn(&Map) -> Option<u32>;
+
+/// Plain (non-LRU) hash maps, which silently refuse inserts once full, with
+/// a function counting their entries. LRU maps evict instead and aren't
+/// listed.
+const WATCHED_MAPS: &[(&str, EntryCounter)] = &[
+    ("CMDLINE_MAP", count_entries::<CmdlineKey, CmdlineEvent>),
+    ("DISK_STATS", count_entries::<u32, DiskStats>),
+    ("EXIT_CODE_STASH", count_entries::<u64, u32>),
+];
+
+/// Hash maps whose size can be overridden from `[ebpf] map_sizes`. Ring
+/// buffers need page-aligned power-of-two sizes and DROP_REASONS is fixed
+/// by the kernel's reason list, so they're left out.
+const SIZABLE_MAPS: &[&str] = &[
+    "CMDLINE_MAP",
+    "EXIT_CODE_STASH",
+    "NET_STATS",
+    "PEER_STATS",
+    "RECV_SOCK_STASH",
+    "SOCK_DROPS",
+    "DISK_STATS",
+    "DEV_WRITES",
+    "VFS_IO_STASH",
+    "RQ_START",
+    "BIO_LATENCY",
+    "RUNQ_START",
+    "RUNQ_LATENCY",
+    "OFFCPU_START",
+    "OFFCPU_TIME",
+];
+
+/// Number of keys in a hash map (one syscall per key).
+fn count_entries<K: Pod, V: Pod>(map: &Map) -> Option<u32> {
+    let hash = BpfHashMap::<_, K, V>::try_from(map).ok()?;
+    Some(hash.keys().filter(Result::is_ok).count() as u32)
+}
+
+fn max_entries(map: &Map) -> Option<u32> {
+    match map {
+        Map::HashMap(data) | Map::LruHashMap(data) => Some(data.info().ok()?.max_entries()),
+        _ => None,
+    }
+}
+
+/// Cumulative run-time counters of one loaded program.
+#[derive(Debug, Clone)]
+pub struct ProgramStats {
+    pub name: String,
+    pub run_count: u64,
+    pub run_time_ns: u64,
+}
+
+/// Groups of programs that load independently. The task iterator is not
+/// one of them: without it there is no process list, so it failing fails
+/// the whole load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    /// dump_task_file iterator (FD counts, open files, socket owners).
+    Files,
+    /// exec/exit tracepoints (cmdlines, exec and exit events).
+    ProcEvents,
+    /// TCP/UDP send/recv fentry/fexit programs, or kprobes without BTF
+    /// trampoline support.
+    Network,
+    /// vfs_read/vfs_write kprobes, attached on demand.
+    FileIo,
+    /// kfree_skb tracepoint.
+    SockDrops,
+    /// block_io_start/done tracepoints, or block_rq_issue/complete on
+    /// kernels before 6.5.
+    BlockIo,
+    /// inet_sock_set_state tracepoint (TCP sockets without an FD).
+    TcpStates,
+    /// sched_wakeup/sched_switch tracepoints (run-queue latency), attached
+    /// on demand.
+    RunQueue,
+    /// sched_wakeup/sched_switch tracepoints for one process, attached on
+    /// demand (off-CPU breakdown).
+    OffCpu,
+    /// oom:mark_victim tracepoint (OOM kill alerts).
+    Oom,
+    /// udp_sendmsg/udpv6_sendmsg kprobes (DNS queries).
+    Dns,
+}
+
+impl Subsystem {
+    /// Short tag for the status bar.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Files => "fds",
+            Self::ProcEvents => "exec",
+            Self::Network => "net",
+            Self::FileIo => "io",
+            Self::SockDrops => "drops",
+            Self::BlockIo => "blkio",
+            Self::TcpStates => "tcpst",
+            Self::RunQueue => "runq",
+            Self::OffCpu => "offcpu",
+            Self::Oom => "oom",
+            Self::Dns => "dns",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Files => "open file counts",
+            Self::ProcEvents => "cmdline and exec/exit tracking",
+            Self::Network => "per-process network stats",
+            Self::FileIo => "per-process disk I/O",
+            Self::SockDrops => "socket drop counters",
+            Self::BlockIo => "block I/O latency",
+            Self::TcpStates => "TCP connection tracking",
+            Self::RunQueue => "run-queue latency",
+            Self::OffCpu => "off-CPU breakdown",
+            Self::Oom => "OOM kill events",
+            Self::Dns => "DNS query tracing",
+        }
+    }
+}
+
+/// How a program is loaded and attached.
+#[derive(Clone, Copy)]
+enum Attach {
+    /// Iterator over the given BTF type; attached per read.
+    Iter(&'static str),
+    TracePoint(&'static str, &'static str),
+    /// kprobe or kretprobe (the program type decides) on a kernel function.
+    KProbe(&'static str),
+    FEntry(&'static str),
+    FExit(&'static str),
+}
+
+const PROGRAMS: &[(Subsystem, &str, Attach)] = &[
+    (Subsystem::Files, "dump_task_file", Attach::Iter("task_file")),
+    (Subsystem::ProcEvents, "capture_cmdline", Attach::TracePoint("sched", "sched_process_exec")),
+    (Subsystem::ProcEvents, "cleanup_cmdline", Attach::TracePoint("sched", "sched_process_exit")),
+    (Subsystem::ProcEvents, "kprobe_do_exit", Attach::KProbe("do_exit")),
+    (Subsystem::SockDrops, "count_sock_drop", Attach::TracePoint("skb", "kfree_skb")),
+    (Subsystem::TcpStates, "track_tcp_state", Attach::TracePoint("sock", "inet_sock_set_state")),
+    (Subsystem::Network, "kprobe_tcp_sendmsg", Attach::KProbe("tcp_sendmsg")),
+    (Subsystem::Network, "kprobe_udp_sendmsg", Attach::KProbe("udp_sendmsg")),
+    (Subsystem::Network, "kprobe_tcp_recvmsg", Attach::KProbe("tcp_recvmsg")),
+    (Subsystem::Network, "kprobe_udp_recvmsg", Attach::KProbe("udp_recvmsg")),
+    (Subsystem::Network, "kretprobe_tcp_recvmsg", Attach::KProbe("tcp_recvmsg")),
+    (Subsystem::Network, "kretprobe_udp_recvmsg", Attach::KProbe("udp_recvmsg")),
+    (Subsystem::Oom, "oom_mark_victim", Attach::TracePoint("oom", "mark_victim")),
+    (Subsystem::Dns, "kprobe_dns_udp_sendmsg", Attach::KProbe("udp_sendmsg")),
+    (Subsystem::Dns, "kprobe_dns_udpv6_sendmsg", Attach::KProbe("udpv6_sendmsg")),
+];
+
+/// Programs that fire on hot paths (every read, write or context switch),
+/// loaded up front but only attached while something shows their data; see
+/// [`EbpfLoader::set_attached`].
+const ON_DEMAND_PROGRAMS: &[(Subsystem, &str, Attach)] = &[
+    (Subsystem::FileIo, "kprobe_vfs_read", Attach::KProbe("vfs_read")),
+    (Subsystem::FileIo, "kprobe_vfs_write", Attach::KProbe("vfs_write")),
+    (Subsystem::FileIo, "kretprobe_vfs_read", Attach::KProbe("vfs_read")),
+    (Subsystem::FileIo, "kretprobe_vfs_write", Attach::KProbe("vfs_write")),
+    (Subsystem::RunQueue, "runq_wakeup", Attach::TracePoint("sched", "sched_wakeup")),
+    (Subsystem::RunQueue, "runq_wakeup_new", Attach::TracePoint("sched", "sched_wakeup_new")),
+    (Subsystem::RunQueue, "runq_switch", Attach::TracePoint("sched", "sched_switch")),
+];
+
+/// Link of one attached on-demand program, kept to detach it again.
+enum ProgramLink {
+    KProbe(KProbeLinkId),
+    TracePoint(TracePointLinkId),
+}
+
+/// Tracepoints for the off-CPU breakdown. They fire on every context switch
+/// in the system, so they are loaded up front but only attached while a
+/// target is set; see [`EbpfLoader::set_offcpu_target`].
+const OFFCPU_PROGRAMS: &[(&str, &str, &str)] = &[
+    ("offcpu_wakeup", "sched", "sched_wakeup"),
+    ("offcpu_switch", "sched", "sched_switch"),
+];
+
+/// Block I/O latency probe pairs, best first. block_io_start/done (6.5+)
+/// see the submitting task and scheduler queueing; block_rq_issue/complete
+/// exist everywhere but only time the device.
+const BLOCK_IO_PAIRS: &[[(&str, Attach); 2]] = &[
+    [
+        ("block_io_start", Attach::TracePoint("block", "block_io_start")),
+        ("block_io_done", Attach::TracePoint("block", "block_io_done")),
+    ],
+    [
+        ("block_rq_issue", Attach::TracePoint("block", "block_rq_issue")),
+        ("block_rq_complete", Attach::TracePoint("block", "block_rq_complete")),
+    ],
+];
+
+/// Attach the first block I/O pair this kernel has. Both pairs feed the
+/// same map, so a half-attached pair is unloaded before trying the next.
+fn load_block_io(bpf: &mut Ebpf, btf: &Btf) -> Result<()> {
+    let mut last_err = None;
+    for pair in BLOCK_IO_PAIRS {
+        match pair
+            .iter()
+            .try_for_each(|&(prog_name, attach)| load_program(bpf, btf, prog_name, attach))
+        {
+            Ok(()) => {
+                log::info!("block I/O latency via {} / {}", pair[0].0, pair[1].0);
+                return Ok(());
+            }
+            Err(e) => {
+                log::info!("{e:#}");
+                for &(prog_name, _) in pair {
+                    if let Some(prog) = bpf.program_mut(prog_name) {
+                        let _ = prog.unload();
+                    }
+                }
+                last_err = Some(e);
+            }
+        }
+    }
+    Err(last_err.expect("BLOCK_IO_PAIRS is not empty"))
+}
+
+/// fentry/fexit versions of the Network kprobes, preferred when the kernel
+/// supports BTF trampolines.
+const NET_TRAMPOLINES: &[(&str, Attach)] = &[
+    ("fentry_tcp_sendmsg", Attach::FEntry("tcp_sendmsg")),
+    ("fentry_udp_sendmsg", Attach::FEntry("udp_sendmsg")),
+    ("fexit_tcp_recvmsg", Attach::FExit("tcp_recvmsg")),
+    ("fexit_udp_recvmsg", Attach::FExit("udp_recvmsg")),
+];
+
+/// Attach all of the network fentry/fexit programs or none of them, since a
+/// partial set next to the kprobes would count some traffic twice.
+fn load_net_trampolines(bpf: &mut Ebpf, btf: &Btf) -> bool {
+    for (i, &(prog_name, attach)) in NET_TRAMPOLINES.iter().enumerate() {
+        if let Err(e) = load_program(bpf, btf, prog_name, attach) {
+            log::info!("{e:#}; using kprobes for network stats");
+            for &(prog_name, _) in &NET_TRAMPOLINES[..=i] {
+                if let Some(prog) = bpf.program_mut(prog_name) {
+                    let _ = prog.unload();
+                }
+            }
+            return false;
+        }
+    }
+    true
+}
+
+fn load_program(bpf: &mut Ebpf, btf: &Btf, prog_name: &str, attach: Attach) -> Result<()> {
+    let prog = bpf
+        .program_mut(prog_name)
+        .context(format!("{prog_name} program not found"))?;
+    match attach {
+        Attach::Iter(btf_type) => {
+            let prog: &mut Iter = prog
+                .try_into()
+                .context(format!("{prog_name} is not an Iter program"))?;
+            prog.load(btf_type, btf)
+                .context(format!("loading {prog_name} iterator"))?;
+        }
+        Attach::TracePoint(category, name) => {
+            let prog: &mut TracePoint = prog
+                .try_into()
+                .context(format!("{prog_name} is not a TracePoint"))?;
+            prog.load().context(format!("loading {prog_name}"))?;
+            prog.attach(category, name)
+                .context(format!("attaching {prog_name}"))?;
+        }
+        Attach::KProbe(func_name) => {
+            let prog: &mut KProbe = prog
+                .try_into()
+                .context(format!("{prog_name} is not a KProbe"))?;
+            prog.load().context(format!("loading {prog_name}"))?;
+            prog.attach(func_name, 0)
+                .context(format!("attaching {prog_name}"))?;
+        }
+        Attach::FEntry(func_name) => {
+            let prog: &mut FEntry = prog
+                .try_into()
+                .context(format!("{prog_name} is not an FEntry program"))?;
+            prog.load(func_name, btf)
+                .context(format!("loading {prog_name}"))?;
+            prog.attach().context(format!("attaching {prog_name}"))?;
+        }
+        Attach::FExit(func_name) => {
+            let prog: &mut FExit = prog
+                .try_into()
+                .context(format!("{prog_name} is not an FExit program"))?;
+            prog.load(func_name, btf)
+                .context(format!("loading {prog_name}"))?;
+            prog.attach().context(format!("attaching {prog_name}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Detach and unload whatever of `subsystem` in `programs` already loaded.
+/// Half a subsystem gives wrong numbers (exits without their exec
+/// cmdlines, reads without writes), so one failed program takes its
+/// siblings down with it.
+fn unload_subsystem(bpf: &mut Ebpf, programs: &[(Subsystem, &str, Attach)], subsystem: Subsystem) {
+    for &(_, prog_name, _) in programs.iter().filter(|&&(s, _, _)| s == subsystem) {
+        if let Some(prog) = bpf.program_mut(prog_name) {
+            let _ = prog.unload();
+        }
+    }
+}
+
+/// Load an on-demand program without attaching it.
+fn load_detached(bpf: &mut Ebpf, prog_name: &str, attach: Attach) -> Result<()> {
+    let prog = bpf
+        .program_mut(prog_name)
+        .context(format!("{prog_name} program not found"))?;
+    match attach {
+        Attach::KProbe(_) => {
+            let prog: &mut KProbe = prog
+                .try_into()
+                .context(format!("{prog_name} is not a KProbe"))?;
+            prog.load().context(format!("loading {prog_name}"))?;
+        }
+        Attach::TracePoint(..) => {
+            let prog: &mut TracePoint = prog
+                .try_into()
+                .context(format!("{prog_name} is not a TracePoint"))?;
+            prog.load().context(format!("loading {prog_name}"))?;
+        }
+        _ => anyhow::bail!("{prog_name} can't be attached on demand"),
+    }
+    Ok(())
+}
+
+/// Attach a program loaded by [`load_detached`].
+fn attach_loaded(bpf: &mut Ebpf, prog_name: &str, attach: Attach) -> Result<ProgramLink> {
+    let prog = bpf
+        .program_mut(prog_name)
+        .context(format!("{prog_name} program not found"))?;
+    let link = match attach {
+        Attach::KProbe(func_name) => {
+            let prog: &mut KProbe = prog
+                .try_into()
+                .context(format!("{prog_name} is not a KProbe"))?;
+            ProgramLink::KProbe(prog.attach(func_name, 0)?)
+        }
+        Attach::TracePoint(category, name) => {
+            let prog: &mut TracePoint = prog
+                .try_into()
+                .context(format!("{prog_name} is not a TracePoint"))?;
+            ProgramLink::TracePoint(prog.attach(category, name)?)
+        }
+        _ => anyhow::bail!("{prog_name} can't be attached on demand"),
+    };
+    Ok(link)
+}
+
+/// Detach the programs of `subsystem` and drop their links.
+fn detach_subsystem(
+    bpf: &mut Ebpf,
+    links: &mut Vec<(Subsystem, &'static str, ProgramLink)>,
+    subsystem: Subsystem,
+) {
+    let (detach, keep) = std::mem::take(links)
+        .into_iter()
+        .partition(|&(s, _, _)| s == subsystem);
+    *links = keep;
+    for (_, prog_name, link) in detach {
+        let Some(prog) = bpf.program_mut(prog_name) else {
+            continue;
+        };
+        detach_link(prog, link);
+    }
+}
+
+fn detach_link(prog: &mut Program, link: ProgramLink) {
+    match link {
+        ProgramLink::KProbe(link) => {
+            let prog: Result<&mut KProbe, _> = prog.try_into();
+            if let Ok(prog) = prog {
+                let _ = prog.detach(link);
+            }
+        }
+        ProgramLink::TracePoint(link) => {
+            let prog: Result<&mut TracePoint, _> = prog.try_into();
+            if let Ok(prog) = prog {
+                let _ = prog.detach(link);
+            }
+        }
+    }
+}
+
+/// Remove every entry of a hash map.
+fn clear_map<K: Pod, V: Pod>(bpf: &mut Ebpf, name: &str) {
+    let Some(map) = bpf.map_mut(name) else {
+        return;
+    };
+    let Ok(mut hash) = BpfHashMap::<_, K, V>::try_from(map) else {
+        return;
+    };
+    let keys: Vec<K> = hash.keys().flatten().collect();
+    for key in keys {
+        let _ = hash.remove(&key);
+    }
+}
+
+/// Tell count_sock_drop which drop reasons to count.
+fn fill_drop_reasons(bpf: &mut Ebpf) -> Result<()> {
+    let mut reasons: BpfHashMap<_, u32, u16> = bpf
+        .map_mut("DROP_REASONS")
+        .context("DROP_REASONS map not found")?
+        .try_into()
+        .context("DROP_REASONS is not a HashMap")?;
+    for (reason, kind) in read_drop_reasons() {
+        reasons.insert(reason, kind, 0).context("filling DROP_REASONS")?;
+    }
+    Ok(())
+}
+
+/// Tell oom_mark_victim where the victim's comm and RSS are in the
+/// record. Kernels before 6.8 have neither; events then carry only PIDs.
+fn fill_oom_fields(bpf: &mut Ebpf) -> Result<()> {
+    let mut fields: Array<_, u32> = bpf
+        .map_mut("OOM_FIELDS")
+        .context("OOM_FIELDS map not found")?
+        .try_into()
+        .context("OOM_FIELDS is not an Array")?;
+    let format = read_tracepoint_format("oom", "mark_victim");
+    for (slot, name) in [
+        (OOM_FIELD_COMM, "comm"),
+        (OOM_FIELD_ANON_RSS, "anon_rss"),
+        (OOM_FIELD_FILE_RSS, "file_rss"),
+        (OOM_FIELD_SHMEM_RSS, "shmem_rss"),
+    ] {
+        if let Some(offset) = oom_field_offset(&format, name) {
+            fields.set(slot, offset, 0).context("filling OOM_FIELDS")?;
+        }
+    }
+    Ok(())
+}
+
+impl EbpfLoader {
+    /// Load the task iterator and as many of the other programs as this
+    /// kernel allows; see [`EbpfLoader::unavailable`]. `map_sizes`
+    /// overrides the compiled-in max_entries of maps in [`SIZABLE_MAPS`].
+    pub fn load(map_sizes: &BTreeMap<String, u32>) -> Result<Self> {
+        let btf = Btf::from_sys_fs().context("reading kernel BTF")?;
+
+        // Check the compiled-in task_struct layout against this kernel and
+        // patch in the real offsets where they differ
+        let kernel_btf = read_kernel_btf().context("validating task_struct offsets")?;
+        let (task_offsets, fixes) = resolve_task_offsets(BPF_OBJ, &kernel_btf)
+            .context("validating task_struct offsets")?;
+        for fix in &fixes {
+            log::warn!(
+                "task_struct.{} is at offset {} on this kernel, not {}; using the kernel's",
+                fix.field, fix.kernel, fix.compiled
+            );
+        }
+        // Same for the DNS kprobes, which can't run on an older iov_iter
+        let msg_offsets = resolve_msg_offsets(&kernel_btf);
+        let mut loader = aya::EbpfLoader::new();
+        loader.set_global(TASK_OFFSETS_SYMBOL, &task_offsets, true);
+        if let Ok(offsets) = &msg_offsets {
+            loader.set_global(MSG_OFFSETS_SYMBOL, offsets, true);
+        }
+        for (name, &size) in map_sizes {
+            if !SIZABLE_MAPS.contains(&name.as_str()) {
+                log::warn!("ebpf.map_sizes: {name} is not a resizable map; ignoring");
+            } else if size == 0 {
+                log::warn!("ebpf.map_sizes: {name} must have at least one entry; ignoring");
+            } else {
+                log::info!("{name}: max_entries = {size}");
+                loader.set_max_entries(name, size);
+            }
+        }
+        let mut bpf = loader.load(BPF_OBJ).context("loading eBPF object")?;
+
+        // Load the task iterator (attachment happens per-read)
+        load_program(&mut bpf, &btf, "dump_task", Attach::Iter("task"))?;
+
+        // Everything else is optional: a probe that fails to load or attach
+        // (e.g. a renamed kernel function) only loses its own subsystem
+        let mut unavailable = Vec::new();
+        if let Err(e) = &msg_offsets {
+            log::warn!("{e:#}; {} unavailable", Subsystem::Dns.description());
+            unavailable.push(Subsystem::Dns);
+        }
+        if let Err(e) = fill_drop_reasons(&mut bpf) {
+            log::warn!("{e:#}; {} unavailable", Subsystem::SockDrops.description());
+            unavailable.push(Subsystem::SockDrops);
+        }
+        // Network probes prefer fentry/fexit; the kprobes are the fallback
+        let net_via_trampolines = load_net_trampolines(&mut bpf, &btf);
+        if net_via_trampolines {
+            log::info!("network stats via fentry/fexit");
+        }
+        for &(subsystem, prog_name, attach) in PROGRAMS {
+            if unavailable.contains(&subsystem)
+                || (subsystem == Subsystem::Network && net_via_trampolines)
+            {
+                continue;
+            }
+            if let Err(e) = load_program(&mut bpf, &btf, prog_name, attach) {
+                log::warn!("{e:#}; {} unavailable", subsystem.description());
+                unavailable.push(subsystem);
+                unload_subsystem(&mut bpf, PROGRAMS, subsystem);
+            }
+        }
+        for &(subsystem, prog_name, attach) in ON_DEMAND_PROGRAMS {
+            if unavailable.contains(&subsystem) {
+                continue;
+            }
+            if let Err(e) = load_detached(&mut bpf, prog_name, attach) {
+                log::warn!("{e:#}; {} unavailable", subsystem.description());
+                unavailable.push(subsystem);
+                unload_subsystem(&mut bpf, ON_DEMAND_PROGRAMS, subsystem);
+            }
+        }
+        if let Err(e) = load_block_io(&mut bpf, &btf) {
+            log::warn!("{e:#}; {} unavailable", Subsystem::BlockIo.description());
+            unavailable.push(Subsystem::BlockIo);
+        }
+        if !unavailable.contains(&Subsystem::Oom) {
+            if let Err(e) = fill_oom_fields(&mut bpf) {
+                log::warn!("{e:#}; OOM victims will be named from the process list");
+            }
+        }
+
+        for &(prog_name, _, _) in OFFCPU_PROGRAMS {
+            let loaded = bpf
+                .program_mut(prog_name)
+                .context(format!("{prog_name} program not found"))
+                .and_then(|prog| {
+                    let prog: &mut TracePoint = prog
+                        .try_into()
+                        .context(format!("{prog_name} is not a TracePoint"))?;
+                    prog.load().context(format!("loading {prog_name}"))
+                });
+            if let Err(e) = loaded {
+                log::warn!("{e:#}; {} unavailable", Subsystem::OffCpu.description());
+                unavailable.push(Subsystem::OffCpu);
+                break;
+            }
+        }
+
+        let exec_events = take_ring_buf(&mut bpf, "EXEC_EVENTS")?;
+        let exit_events = take_ring_buf(&mut bpf, "EXIT_EVENTS")?;
+        let oom_events = take_ring_buf(&mut bpf, "OOM_EVENTS")?;
+        let dns_events = take_ring_buf(&mut bpf, "DNS_EVENTS")?;
+
+        Ok(Self {
+            bpf: Some(bpf),
+            exec_events: Some(exec_events),
+            exit_events: Some(exit_events),
+            oom_events: Some(oom_events),
+            dns_events: Some(dns_events),
+            unavailable,
+            run_stats: None,
+            offcpu_target: 0,
+            offcpu_links: Vec::new(),
+            on_demand_links: Vec::new(),
+            iter_buf: Vec::new(),
+        })
+    }
+
+    /// Create a no-op loader (used as placeholder after moving the real one).
+    pub fn noop() -> Self {
+        Self {
+            bpf: None,
+            exec_events: None,
+            exit_events: None,
+            oom_events: None,
+            dns_events: None,
+            unavailable: Vec::new(),
+            run_stats: None,
+            offcpu_target: 0,
+            offcpu_links: Vec::new(),
+            on_demand_links: Vec::new(),
+            iter_buf: Vec::new(),
+        }
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.bpf.is_some()
+    }
+
+    /// Subsystems whose programs failed to load or attach.
+    pub fn unavailable(&self) -> &[Subsystem] {
+        &self.unavailable
+    }
+
+    pub fn run_stats_enabled(&self) -> bool {
+        self.run_stats.is_some()
+    }
+
+    /// Turn the kernel's BPF run-time accounting (BPF_ENABLE_STATS) on or
+    /// off. It costs a little on every program run system-wide, so it is
+    /// only held while someone is looking at the numbers.
+    pub fn set_run_stats(&mut self, enabled: bool) -> Result<()> {
+        if !enabled {
+            self.run_stats = None;
+        } else if self.run_stats.is_none() {
+            let fd = aya::sys::enable_stats(aya::sys::Stats::RunTime)
+                .context("enabling BPF run-time stats")?;
+            self.run_stats = Some(fd);
+        }
+        Ok(())
+    }
+
+    pub fn offcpu_target(&self) -> u32 {
+        self.offcpu_target
+    }
+
+    /// Break down where the threads of `tgid` spend their time, starting
+    /// from zero, or stop with 0. The tracepoints stay attached only while
+    /// there is a target.
+    pub fn set_offcpu_target(&mut self, tgid: u32) -> Result<()> {
+        if tgid == self.offcpu_target || self.unavailable.contains(&Subsystem::OffCpu) {
+            return Ok(());
+        }
+        let Some(bpf) = self.bpf.as_mut() else {
+            return Ok(());
+        };
+        // Switch the target first so nothing more is booked to the old one
+        let mut target: Array<_, u32> = bpf
+            .map_mut("OFFCPU_TARGET")
+            .context("OFFCPU_TARGET map not found")?
+            .try_into()
+            .context("OFFCPU_TARGET is not an Array")?;
+        target.set(0, tgid, 0).context("setting OFFCPU_TARGET")?;
+        self.offcpu_target = tgid;
+        clear_map::<u32, OffCpuStart>(bpf, "OFFCPU_START");
+        clear_map::<u32, OffCpuTime>(bpf, "OFFCPU_TIME");
+
+        if tgid == 0 {
+            for (prog_name, link) in self.offcpu_links.drain(..) {
+                if let Some(prog) = bpf.program_mut(prog_name) {
+                    let prog: Result<&mut TracePoint, _> = prog.try_into();
+                    if let Ok(prog) = prog {
+                        let _ = prog.detach(link);
+                    }
+                }
+            }
+        } else if self.offcpu_links.is_empty() {
+            for &(prog_name, category, name) in OFFCPU_PROGRAMS {
+                let prog: &mut TracePoint = bpf
+                    .program_mut(prog_name)
+                    .context(format!("{prog_name} program not found"))?
+                    .try_into()
+                    .context(format!("{prog_name} is not a TracePoint"))?;
+                match prog.attach(category, name) {
+                    Ok(link) => self.offcpu_links.push((prog_name, link)),
+                    Err(e) => {
+                        self.unavailable.push(Subsystem::OffCpu);
+                        return Err(e).context(format!("attaching {prog_name}"));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the programs of an on-demand subsystem are attached.
+    pub fn is_attached(&self, subsystem: Subsystem) -> bool {
+        self.on_demand_links.iter().any(|&(s, _, _)| s == subsystem)
+    }
+
+    /// Attach or detach the programs of an on-demand subsystem. Attaching
+    /// is all or nothing; a failure marks the subsystem unavailable.
+    pub fn set_attached(&mut self, subsystem: Subsystem, attached: bool) -> Result<()> {
+        if attached == self.is_attached(subsystem) || self.unavailable.contains(&subsystem) {
+            return Ok(());
+        }
+        let Some(bpf) = self.bpf.as_mut() else {
+            return Ok(());
+        };
+        if !attached {
+            detach_subsystem(bpf, &mut self.on_demand_links, subsystem);
+            // A start stamped before the detach would be charged the whole
+            // detached time when the thread next runs
+            if subsystem == Subsystem::RunQueue {
+                clear_map::<u32, u64>(bpf, "RUNQ_START");
+            }
+            return Ok(());
+        }
+        for &(s, prog_name, attach) in ON_DEMAND_PROGRAMS {
+            if s != subsystem {
+                continue;
+            }
+            match attach_loaded(bpf, prog_name, attach) {
+                Ok(link) => self.on_demand_links.push((subsystem, prog_name, link)),
+                Err(e) => {
+                    self.unavailable.push(subsystem);
+                    detach_subsystem(bpf, &mut self.on_demand_links, subsystem);
+                    return Err(e).context(format!(
+                        "attaching {prog_name}; {} unavailable",
+                        subsystem.description()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Entry counts of the hash maps that drop data when full. Walks every
+    /// key, so callers should not do this on every refresh.
+    pub fn map_usage(&self) -> Vec<MapUsage> {
+        let Some(bpf) = self.bpf.as_ref() else {
+            return Vec::new();
+        };
+        WATCHED_MAPS
+            .iter()
+            .filter_map(|&(name, count)| {
+                let map = bpf.map(name)?;
+                Some(MapUsage {
+                    name,
+                    entries: count(map)?,
+                    max_entries: max_entries(map)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Run counts and total run time of every loaded program, counted
+    /// since run-time stats were last enabled by anyone.
+    pub fn program_stats(&self) -> Vec<ProgramStats> {
+        let Some(bpf) = self.bpf.as_ref() else {
+            return Vec::new();
+        };
+        bpf.programs()
+            .filter_map(|(name, prog)| {
+                let info = prog.info().ok()?;
+                Some(ProgramStats {
+                    name: name.to_string(),
+                    run_count: info.run_count(),
+                    run_time_ns: info.run_time().as_nanos() as u64,
+                })
+            })
+            .collect()
+    }
+
+    /// Run the task iterator once, returning all TaskInfo structs.
+    ///
+    /// Each call creates a new iterator link, reads all output, and
+    /// drops the link (aya's IterLink only offers `into_file(self)`, so a
+    /// link can't be walked twice). The iterator walks every task in the
+    /// kernel.
+    pub fn read_tasks(&mut self) -> Result<Vec<TaskInfo>> {
+        self.read_iter("dump_task")
+    }
+
+    /// Run the file iterator once, returning one FileInfo per open FD.
+    ///
+    /// Threads sharing their leader's fd table are skipped by the kernel,
+    /// so each FD appears once per process.
+    pub fn read_files(&mut self) -> Result<Vec<FileInfo>> {
+        self.read_iter("dump_task_file")
+    }
+
+    /// Attach an iterator program, drain its seq_file, and decode the
+    /// fixed-size records it wrote.
+    fn read_iter<T: Pod>(&mut self, prog_name: &str) -> Result<Vec<T>> {
+        let bpf = self
+            .bpf
+            .as_mut()
+            .context("eBPF not loaded")?;
+
+        let prog: &mut Iter = bpf
+            .program_mut(prog_name)
+            .context(format!("{prog_name} program not found"))?
+            .try_into()
+            .context(format!("{prog_name} is not an Iter program"))?;
+
+        let link_id = prog.attach().context(format!("attaching {prog_name} iterator"))?;
+        let link: IterLink = prog
+            .take_link(link_id)
+            .context(format!("taking {prog_name} link"))?;
+        let mut file = link.into_file().context("creating iterator file")?;
+
+        let len = read_all(&mut file, &mut self.iter_buf)
+            .context("reading iterator output")?;
+
+        let record_size = std::mem::size_of::<T>();
+        let records: Vec<T> = self.iter_buf[..len]
+            .chunks_exact(record_size)
+            .map(|chunk| unsafe { std::ptr::read_unaligned(chunk.as_ptr() as *const T) })
+            .collect();
+
+        Ok(records)
+    }
+
+    /// Read every cmdline in the BPF CMDLINE_MAP in one pass.
+    /// Returns a map of (pid, start_time_ns) -> cmdline, skipping empty
+    /// entries; match both so a reused PID doesn't pick up its
+    /// predecessor's cmdline.
+    pub fn read_all_cmdlines(&self) -> HashMap<(u32, u64), String> {
+        let mut result = HashMap::new();
+        let Some(map) = self.bpf.as_ref().and_then(|bpf| bpf.map("CMDLINE_MAP")) else {
+            return result;
+        };
+        let Ok(hash) = BpfHashMap::<_, CmdlineKey, CmdlineEvent>::try_from(map) else {
+            return result;
+        };
+        for (key, event) in hash.iter().flatten() {
+            if let Some(cmdline) = cmdline_string(&event) {
+                result.insert((key.pid, key.start_time_ns), cmdline);
+            }
+        }
+        result
+    }
+
+    /// Read per-thread network stats from the BPF NET_STATS map, keyed by
+    /// TID. A leader's entry includes its exited threads' counts.
+    /// Returns a map of tid -> (tx_bytes, rx_bytes, ifindex).
+    pub fn read_net_stats(&self) -> HashMap<u32, (u64, u64, u32)> {
+        let mut result = HashMap::new();
+        let bpf = match self.bpf.as_ref() {
+            Some(b) => b,
+            None => return result,
+        };
+        let map = match bpf.map("NET_STATS") {
+            Some(m) => m,
+            None => return result,
+        };
+        let hash = match BpfHashMap::<_, u32, NetStats>::try_from(map) {
+            Ok(h) => h,
+            Err(_) => return result,
+        };
+        for item in hash.iter() {
+            if let Ok((pid, stats)) = item {
+                result.insert(pid, (stats.tx_bytes, stats.rx_bytes, stats.ifindex));
+            }
+        }
+        result
+    }
+
+    /// Read per-PID file I/O counters from the BPF DISK_STATS map.
+    /// Returns a map of pid -> (read_bytes, write_bytes).
+    pub fn read_disk_stats(&self) -> HashMap<u32, (u64, u64)> {
+        let mut result = HashMap::new();
+        let bpf = match self.bpf.as_ref() {
+            Some(b) => b,
+            None => return result,
+        };
+        let map = match bpf.map("DISK_STATS") {
+            Some(m) => m,
+            None => return result,
+        };
+        let hash = match BpfHashMap::<_, u32, DiskStats>::try_from(map) {
+            Ok(h) => h,
+            Err(_) => return result,
+        };
+        for (pid, stats) in hash.iter().flatten() {
+            result.insert(pid, (stats.read_bytes, stats.write_bytes));
+        }
+        result
+    }
+
+    /// Read the remote peers of one process from the BPF PEER_STATS map.
+    /// Returns (family, addr, tx_bytes, rx_bytes) per peer.
+    pub fn read_peer_stats(&self, pid: u32) -> Vec<(u8, [u8; 16], u64, u64)> {
+        let Some(map) = self.bpf.as_ref().and_then(|bpf| bpf.map("PEER_STATS")) else {
+            return Vec::new();
+        };
+        let Ok(hash) = BpfHashMap::<_, PeerKey, PeerStats>::try_from(map) else {
+            return Vec::new();
+        };
+        hash.iter()
+            .flatten()
+            .filter(|(key, _)| key.pid == pid)
+            .map(|(key, stats)| (key.family as u8, key.addr, stats.tx_bytes, stats.rx_bytes))
+            .collect()
+    }
+
+    /// Read the bytes one process wrote per filesystem from the BPF
+    /// DEV_WRITES map. Returns (superblock dev_t, bytes) pairs.
+    pub fn read_dev_writes(&self, pid: u32) -> Vec<(u32, u64)> {
+        let Some(map) = self.bpf.as_ref().and_then(|bpf| bpf.map("DEV_WRITES")) else {
+            return Vec::new();
+        };
+        let Ok(hash) = BpfHashMap::<_, DevWriteKey, u64>::try_from(map) else {
+            return Vec::new();
+        };
+        hash.iter()
+            .flatten()
+            .filter(|(key, _)| key.pid == pid)
+            .map(|(key, bytes)| (key.dev, bytes))
+            .collect()
+    }
+
+    /// Read the block I/O latency histogram of one process from the BPF
+    /// BIO_LATENCY map, or `None` if it hasn't completed any requests.
+    pub fn read_bio_latency(&self, pid: u32) -> Option<BioLatency> {
+        let map = self.bpf.as_ref()?.map("BIO_LATENCY")?;
+        let hash = BpfHashMap::<_, u32, BioLatency>::try_from(map).ok()?;
+        hash.get(&pid, 0).ok()
+    }
+
+    /// Read per-TID run-queue waits from the BPF RUNQ_LATENCY map.
+    /// Returns a map of tid -> (total wait ns, number of waits).
+    pub fn read_runq_latency(&self) -> HashMap<u32, (u64, u64)> {
+        let Some(map) = self.bpf.as_ref().and_then(|bpf| bpf.map("RUNQ_LATENCY")) else {
+            return HashMap::new();
+        };
+        let Ok(hash) = BpfHashMap::<_, u32, RunqLatency>::try_from(map) else {
+            return HashMap::new();
+        };
+        hash.iter()
+            .flatten()
+            .map(|(tid, lat)| (tid, (lat.total_ns, lat.count)))
+            .collect()
+    }
+
+    /// Time the off-CPU target's threads spent in each `OFFCPU_*` state
+    /// since it was set, summed from the BPF OFFCPU_TIME map.
+    pub fn read_offcpu_time(&self) -> Option<[u64; OFFCPU_STATES]> {
+        if self.offcpu_links.is_empty() {
+            return None;
+        }
+        let map = self.bpf.as_ref()?.map("OFFCPU_TIME")?;
+        let hash = BpfHashMap::<_, u32, OffCpuTime>::try_from(map).ok()?;
+        let mut total = [0; OFFCPU_STATES];
+        for (_, time) in hash.iter().flatten() {
+            if time.tgid == self.offcpu_target {
+                for (sum, ns) in total.iter_mut().zip(time.ns) {
+                    *sum += ns;
+                }
+            }
+        }
+        Some(total)
+    }
+
+    /// Read socket drop counters from the BPF SOCK_DROPS map.
+    /// Returns a map of (netns, local port, kind) -> dropped packets.
+    pub fn read_sock_drops(&self) -> HashMap<DropKey, u64> {
+        let Some(map) = self.bpf.as_ref().and_then(|bpf| bpf.map("SOCK_DROPS")) else {
+            return HashMap::new();
+        };
+        let Ok(hash) = BpfHashMap::<_, DropKey, u64>::try_from(map) else {
+            return HashMap::new();
+        };
+        hash.iter().flatten().collect()
+    }
+
+    /// Read every TCP socket tracked by the inet_sock_set_state tracepoint.
+    pub fn read_tcp_conns(&self) -> Vec<TcpConn> {
+        let Some(map) = self.bpf.as_ref().and_then(|bpf| bpf.map("TCP_CONNS")) else {
+            return Vec::new();
+        };
+        let Ok(hash) = BpfHashMap::<_, u64, TcpConn>::try_from(map) else {
+            return Vec::new();
+        };
+        hash.iter().flatten().map(|(_, conn)| conn).collect()
+    }
+
+    /// Drain every exec event queued since the last call.
+    pub fn drain_exec_events(&mut self) -> Vec<ExecEvent> {
+        drain_ring_buf(self.exec_events.as_mut())
+    }
+
+    /// Drain every process exit event queued since the last call.
+    pub fn drain_exit_events(&mut self) -> Vec<ExitEvent> {
+        drain_ring_buf(self.exit_events.as_mut())
+    }
+
+    /// Drain every OOM kill queued since the last call.
+    pub fn drain_oom_events(&mut self) -> Vec<OomEvent> {
+        drain_ring_buf(self.oom_events.as_mut())
+    }
+
+    /// Drain every DNS query queued since the last call.
+    pub fn drain_dns_events(&mut self) -> Vec<DnsEvent> {
+        drain_ring_buf(self.dns_events.as_mut())
+    }
+
+    /// Insert a cmdline entry into the BPF map (used for startup seeding).
+    pub fn seed_cmdline(&mut self, pid: u32, start_time_ns: u64, cmdline: &str) -> Result<()> {
+        let bpf = self
+            .bpf
+            .as_mut()
+            .context("eBPF not loaded")?;
+        let map = bpf
+            .map_mut("CMDLINE_MAP")
+            .context("CMDLINE_MAP not found")?;
+        let mut hash = BpfHashMap::<_, CmdlineKey, CmdlineEvent>::try_from(map)
+            .map_err(|e| anyhow::anyhow!("CMDLINE_MAP is not a HashMap: {e}"))?;
+
+        let mut event = CmdlineEvent {
+            pid,
+            len: 0,
+            cmdline: [0u8; 256],
+        };
+        let bytes = cmdline.as_bytes();
+        let copy_len = bytes.len().min(255);
+        event.cmdline[..copy_len].copy_from_slice(&bytes[..copy_len]);
+        event.len = copy_len as u32;
+
+        let key = CmdlineKey {
+            pid,
+            _pad: 0,
+            start_time_ns,
+        };
+        hash.insert(key, event, 0)
+            .context("inserting into CMDLINE_MAP")?;
+        Ok(())
+    }
+}
+
+/// Decode a CMDLINE_MAP entry. Arguments are NUL-separated in the map;
+/// they're joined with spaces here.
+fn cmdline_string(event: &CmdlineEvent) -> Option<String> {
+    let len = (event.len as usize).min(event.cmdline.len());
+    let s: String = event.cmdline[..len]
+        .iter()
+        .map(|&b| if b == 0 { ' ' } else { b as char })
+        .collect::<String>()
+        .trim()
+        .to_string();
+    if s.is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
+
+/// Drain `file` into `buf`, reusing its allocation, and return the number
+/// of bytes read. Unlike `read_to_end`, every read asks for all of the
+/// buffer's free space instead of ramping up from 8 KiB, and the buffer
+/// only grows when a walk outgrows every earlier one.
+fn read_all(file: &mut File, buf: &mut Vec<u8>) -> io::Result<usize> {
+    let mut len = 0;
+    loop {
+        if len == buf.len() {
+            buf.resize((buf.len() * 2).max(64 * 1024), 0);
+        }
+        match file.read(&mut buf[len..]) {
+            Ok(0) => return Ok(len),
+            Ok(n) => len += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Move a ring buffer map out of `bpf` so it can be polled without
+/// re-mapping it on every read.
+fn take_ring_buf(bpf: &mut Ebpf, name: &str) -> Result<RingBuf<MapData>> {
+    let map = bpf
+        .take_map(name)
+        .context(format!("{name} map not found"))?;
+    RingBuf::try_from(map).context(format!("{name} is not a RingBuf"))
+}
+
+/// Decode every record currently queued in a ring buffer.
+fn drain_ring_buf<T: Pod>(ring: Option<&mut RingBuf<MapData>>) -> Vec<T> {
+    let mut records = Vec::new();
+    let Some(ring) = ring else {
+        return records;
+    };
+    let record_size = std::mem::size_of::<T>();
+    while let Some(item) = ring.next() {
+        if item.len() >= record_size {
+            records.push(unsafe { std::ptr::read_unaligned(item.as_ptr() as *const T) });
+        }
+    }
+    records
+}
+
+/// skb_drop_reason values worth counting, with the DropKey kind each maps
+/// to. Read from the kfree_skb format file, whose print fmt lists every
+/// reason as `{ 16, "SOCKET_RCVBUFF" }`; reasons this kernel lacks are
+/// skipped.
+fn read_drop_reasons() -> Vec<(u32, u16)> {
+    let format = read_tracepoint_format("skb", "kfree_skb");
+    let value = |name: &str| {
+        format.split('{').find_map(|entry| {
+            let (num, rest) = entry.split_once(',')?;
+            let quoted = rest.trim_start().strip_prefix('"')?;
+            (quoted.split('"').next()? == name).then(|| num.trim().parse().ok())?
+        })
+    };
+    [
+        ("TCP_LISTEN_OVERFLOW", DROP_LISTEN_OVERFLOW),
+        ("TCP_REQQFULLDROP", DROP_LISTEN_OVERFLOW),
+        ("SOCKET_RCVBUFF", DROP_RCVBUF),
+    ]
+    .into_iter()
+    .filter_map(|(name, kind)| Some((value(name)?, kind)))
+    .collect()
+}
+
+/// A tracepoint's format file, or "" if tracefs isn't mounted.
+fn read_tracepoint_format(category: &str, name: &str) -> String {
+    ["/sys/kernel/tracing", "/sys/kernel/debug/tracing"]
+        .iter()
+        .find_map(|root| fs::read_to_string(format!("{root}/events/{category}/{name}/format")).ok())
+        .unwrap_or_default()
+}
+
+/// Record offset of a mark_victim field, from format lines like
+/// `field:unsigned long anon_rss;\toffset:24;\tsize:8;\tsigned:0;`. The
+/// comm is only taken as a `__data_loc` string, the form the BPF side
+/// decodes.
+fn oom_field_offset(format: &str, name: &str) -> Option<u32> {
+    format.lines().find_map(|line| {
+        let (decl, rest) = line.trim().strip_prefix("field:")?.split_once(';')?;
+        let field = decl.rsplit(' ').next()?.split('[').next()?;
+        if field != name || (name == "comm" && !decl.starts_with("__data_loc")) {
+            return None;
+        }
+        rest.split(';')
+            .find_map(|part| part.trim().strip_prefix("offset:"))?
+            .parse()
+            .ok()
+    })
+}
+
+/// One-shot scan of /proc/*/cmdline to seed the CMDLINE_MAP for
+/// processes that were already running before the BPF tracepoints
+/// were attached. After this, no per-PID /proc reads occur.
+///
+/// The PIDs and start times come from one task iterator walk, since
+/// /proc/PID/stat only has the start time in clock ticks and the map is
+/// keyed by the kernel's nanoseconds.
+pub fn seed_cmdlines_from_proc(loader: &mut EbpfLoader) {
+    let tasks = match loader.read_tasks() {
+        Ok(t) => t,
+        Err(_) => return,
+    };
+    for task in tasks.iter().filter(|t| t.tid == t.pid) {
+        let pid = task.pid;
+        let cmdline = match fs::read_to_string(format!("/proc/{pid}/cmdline")) {
+            Ok(s) => s.replace('\0', " ").trim().to_string(),
+            Err(_) => continue,
+        };
+        if cmdline.is_empty() {
+            continue;
+        }
+        let _ = loader.seed_cmdline(pid, task.start_time_ns, &cmdline);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn event(bytes: &[u8], len: u32) -> CmdlineEvent {
+        let mut event = CmdlineEvent {
+            pid: 1,
+            len,
+            cmdline: [0u8; 256],
+        };
+        let n = bytes.len().min(event.cmdline.len());
+        event.cmdline[..n].copy_from_slice(&bytes[..n]);
+        event
+    }
+
+    proptest! {
+        // The map holds whatever the task put in its argv, and `len` comes
+        // from a BPF probe read that may disagree with the buffer size
+        #[test]
+        fn cmdline_decode_is_total(bytes in prop::collection::vec(any::<u8>(), 0..300), len in any::<u32>()) {
+            if let Some(s) = cmdline_string(&event(&bytes, len)) {
+                prop_assert!(!s.contains('\0'));
+                prop_assert_eq!(s.trim(), s.as_str());
+                prop_assert!(s.chars().count() <= (len as usize).min(256));
+            }
+        }
+
+        #[test]
+        fn cmdline_nuls_become_spaces(args in prop::collection::vec("[!-~]{1,16}", 1..8)) {
+            // argv as the kernel lays it out: each argument NUL-terminated
+            let mut raw = Vec::new();
+            for arg in &args {
+                raw.extend_from_slice(arg.as_bytes());
+                raw.push(0);
+            }
+            prop_assume!(raw.len() <= 256);
+            let decoded = cmdline_string(&event(&raw, raw.len() as u32));
+            prop_assert_eq!(decoded, Some(args.join(" ")));
+        }
+    }
+
+    #[test]
+    fn oom_field_offsets_from_format() {
+        let format = "name: mark_victim\nID: 512\nformat:\n\
+            \tfield:unsigned short common_type;\toffset:0;\tsize:2;\tsigned:0;\n\
+            \tfield:int pid;\toffset:8;\tsize:4;\tsigned:1;\n\
+            \tfield:__data_loc char[] comm;\toffset:12;\tsize:4;\tsigned:0;\n\
+            \tfield:unsigned long total_vm;\toffset:16;\tsize:8;\tsigned:0;\n\
+            \tfield:unsigned long anon_rss;\toffset:24;\tsize:8;\tsigned:0;\n\
+            \tfield:unsigned long file_rss;\toffset:32;\tsize:8;\tsigned:0;\n\
+            \tfield:unsigned long shmem_rss;\toffset:40;\tsize:8;\tsigned:0;\n";
+        assert_eq!(oom_field_offset(format, "comm"), Some(12));
+        assert_eq!(oom_field_offset(format, "anon_rss"), Some(24));
+        assert_eq!(oom_field_offset(format, "shmem_rss"), Some(40));
+        assert_eq!(oom_field_offset(format, "rss"), None);
+
+        // Pre-6.8 kernels record only the PID
+        let old = "\tfield:int pid;\toffset:8;\tsize:4;\tsigned:1;\n";
+        assert_eq!(oom_field_offset(old, "comm"), None);
+        assert_eq!(oom_field_offset(old, "anon_rss"), None);
+        // An inline char array is not a __data_loc string
+        let inline = "\tfield:char comm[16];\toffset:12;\tsize:16;\tsigned:0;\n";
+        assert_eq!(oom_field_offset(inline, "comm"), None);
+    }
+}
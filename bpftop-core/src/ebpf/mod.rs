@@ -1 +1,2 @@
+pub mod btf;
 pub mod loader;
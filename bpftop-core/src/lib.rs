@@ -0,0 +1,27 @@
+//! bpftop's data collection without the TUI: the eBPF loader, the
+//! collector that turns its maps and /proc into per-refresh snapshots,
+//! and the process and system types those snapshots carry.
+//!
+//! ```no_run
+//! use bpftop_core::{Collector, EbpfLoader};
+//!
+//! let ebpf = EbpfLoader::load(&Default::default())?;
+//! let mut collector = Collector::new(ebpf);
+//! collector.collect()?; // primes the rate deltas
+//! std::thread::sleep(std::time::Duration::from_secs(1));
+//! for p in collector.collect()?.processes.iter().filter(|p| !p.is_thread) {
+//!     println!("{:>7} {:5.1}% {}", p.pid, p.cpu_percent, p.comm);
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+pub mod alerts;
+pub mod data;
+pub mod ebpf;
+
+pub use alerts::{Alert, Severity};
+pub use data::clock::{Clock, SystemClock};
+pub use data::collector::{Collector, Inspection, Snapshot};
+pub use data::process::{ProcessInfo, ProcessState};
+pub use data::system::SystemInfo;
+pub use ebpf::loader::EbpfLoader;
@@ -7,13 +7,148 @@
 //! This is a secondary data source - the main task iterator in main.rs
 //! collects process-level info, while this collects per-FD info for
 //! network and file details.
+//!
+//! The kernel skips threads that share their leader's files_struct, so
+//! each FD is emitted once per process, not once per thread.
+
+use bpftop_common::FileInfo;
+
+use super::*;
+
+// inode.i_mode file type bits (S_IFMT and S_IFREG live in main.rs)
+const S_IFSOCK: u16 = 0o140000;
+const S_IFIFO: u16 = 0o010000;
+
+const SOCK_STREAM: i16 = 1;
+
+// FileInfo.fd_type values
+const FD_REGULAR: u8 = 0;
+const FD_SOCKET: u8 = 1;
+const FD_PIPE: u8 = 2;
+const FD_OTHER: u8 = 3;
+
+/// Matches kernel `struct bpf_iter__task_file`.
+#[repr(C)]
+pub struct IterTaskFileCtx {
+    meta: *mut IterMeta,
+    task: *const u8,
+    fd: u32,
+    file: *const u8,
+}
+
+#[no_mangle]
+#[link_section = "iter/task_file"]
+pub fn dump_task_file(ctx: *mut IterTaskFileCtx) -> i32 {
+    unsafe { try_dump_task_file(ctx).unwrap_or(0) }
+}
+
+unsafe fn try_dump_task_file(ctx: *mut IterTaskFileCtx) -> Result<i32, i64> {
+    let task = (*ctx).task;
+    let file = (*ctx).file;
+    if task.is_null() || file.is_null() {
+        return Ok(0);
+    }
+    let seq = (*(*ctx).meta).seq;
+
+    let tgid: i32 = read_field(task, task_off!(tgid)).unwrap_or(0);
+
+    let mut info = FileInfo {
+        pid: tgid as u32,
+        fd: (*ctx).fd,
+        fd_type: FD_OTHER,
+        sock_family: 0,
+        sock_type: 0,
+        sock_state: 0,
+        src_addr: [0u8; 16],
+        dst_addr: [0u8; 16],
+        src_port: 0,
+        dst_port: 0,
+        netns: 0,
+        srtt_us: 0,
+        snd_cwnd: 0,
+        path: [0u8; 256],
+    };
+
+    let inode: *const u8 = read_field(file, FILE_F_INODE).unwrap_or(core::ptr::null());
+    let mode: u16 = if !inode.is_null() {
+        read_field(inode, INODE_I_MODE).unwrap_or(0)
+    } else {
+        0
+    };
+
+    match mode & S_IFMT {
+        S_IFREG => info.fd_type = FD_REGULAR,
+        S_IFIFO => info.fd_type = FD_PIPE,
+        S_IFSOCK => {
+            info.fd_type = FD_SOCKET;
+            let socket: *const u8 = read_field(file, FILE_PRIVATE_DATA).unwrap_or(core::ptr::null());
+            if !socket.is_null() {
+                read_socket(socket, &mut info);
+            }
+        }
+        _ => {}
+    }
+
+    // d_path renders "/real/path" for files and "pipe:[ino]" / "socket:[ino]"
+    // / "anon_inode:[eventfd]" for pseudo files. iter programs are on the
+    // kernel's bpf_d_path allowlist.
+    let _ = aya_ebpf::helpers::bpf_d_path(
+        file.add(FILE_F_PATH) as *mut _,
+        info.path.as_mut_ptr() as *mut _,
+        info.path.len() as u32,
+    );
+
+    let ptr = &info as *const FileInfo as *const u8;
+    let size = core::mem::size_of::<FileInfo>() as u32;
+    aya_ebpf::helpers::bpf_seq_write(seq as *mut _, ptr as *const _, size);
+
+    Ok(0)
+}
+
+/// Fill socket type, family, state, network namespace, and endpoints from
+/// a `struct socket`.
+#[inline(always)]
+unsafe fn read_socket(socket: *const u8, info: &mut FileInfo) {
+    let sock_type: i16 = read_field(socket, SOCKET_TYPE).unwrap_or(0);
+    info.sock_type = sock_type as u8;
+
+    let sk: *const u8 = read_field(socket, SOCKET_SK).unwrap_or(core::ptr::null());
+    if sk.is_null() {
+        return;
+    }
+
+    let family: u16 = read_field(sk, SOCK_FAMILY).unwrap_or(0);
+    info.sock_family = family as u8;
+    info.sock_state = read_field(sk, SOCK_STATE).unwrap_or(0);
+
+    let net: *const u8 = read_field(sk, SOCK_NET).unwrap_or(core::ptr::null());
+    if !net.is_null() {
+        info.netns = read_field(net, NET_NS_INUM).unwrap_or(0);
+    }
+
+    match family {
+        AF_INET => {
+            let src: [u8; 4] = read_field(sk, SOCK_RCV_SADDR).unwrap_or([0; 4]);
+            let dst: [u8; 4] = read_field(sk, SOCK_DADDR).unwrap_or([0; 4]);
+            info.src_addr[..4].copy_from_slice(&src);
+            info.dst_addr[..4].copy_from_slice(&dst);
+        }
+        AF_INET6 => {
+            info.src_addr = read_field(sk, SOCK_V6_RCV_SADDR).unwrap_or([0; 16]);
+            info.dst_addr = read_field(sk, SOCK_V6_DADDR).unwrap_or([0; 16]);
+        }
+        _ => return,
+    }
+
+    // skc_num is host order; store both ports in network order like skc_dport
+    let num: u16 = read_field(sk, SOCK_NUM).unwrap_or(0);
+    info.src_port = num.to_be();
+    info.dst_port = read_field(sk, SOCK_DPORT).unwrap_or(0);
 
-// NOTE: This module is compiled as part of the bpftop-ebpf crate but
-// the file iterator is a separate BPF program entry point. For now,
-// it serves as documentation and a placeholder for the file iterator
-// implementation. The actual BPF program will be added when the
-// iter/task_file infrastructure is needed.
-//
-// The task_file iterator is less widely supported than iter/task,
-// so we initially rely on /proc/[pid]/fd for file information in
-// userspace, and will add this eBPF program when needed for performance.
+    // Connection quality straight from tcp_sock, as `ss -i` reports it
+    if sock_type == SOCK_STREAM {
+        let srtt: u32 = read_field(sk, TCP_SRTT_US).unwrap_or(0);
+        info.srtt_us = srtt >> 3;
+        info.snd_cwnd = read_field(sk, TCP_SND_CWND).unwrap_or(0);
+    }
+}
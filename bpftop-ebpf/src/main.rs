@@ -2,22 +2,34 @@
 #![no_main]
 
 use aya_ebpf::{
-    helpers::{bpf_get_current_pid_tgid, bpf_get_current_task, bpf_probe_read_kernel},
-    macros::{kprobe, kretprobe, map, tracepoint},
-    maps::HashMap,
-    programs::{ProbeContext, RetProbeContext, TracePointContext},
+    helpers::{
+        bpf_get_current_comm, bpf_get_current_pid_tgid, bpf_get_current_task,
+        bpf_get_current_uid_gid, bpf_ktime_get_boot_ns, bpf_ktime_get_ns, bpf_probe_read_kernel,
+        bpf_probe_read_kernel_str_bytes, bpf_probe_read_user_buf,
+    },
+    macros::{fentry, fexit, kprobe, kretprobe, map, tracepoint},
+    maps::{Array, HashMap, LruHashMap, RingBuf},
+    programs::{FEntryContext, FExitContext, ProbeContext, RetProbeContext, TracePointContext},
+    EbpfContext,
+};
+use bpftop_common::{
+    BioLatency, CmdlineEvent, CmdlineKey, DevWriteKey, DiskStats, DnsEvent, DropKey, ExecEvent, ExitEvent, MsgOffsets, NetStats,
+    OffCpuStart, OffCpuTime, OomEvent, PeerKey, PeerStats, RunqLatency, TaskInfo, TaskOffsets, TcpConn,
+    BIO_LATENCY_BUCKETS, DNS_PAYLOAD_MAX, OFFCPU_BLOCKED, OFFCPU_RUNNABLE, OFFCPU_RUNNING, OFFCPU_SLEEPING, OFFCPU_STATES, OOM_FIELD_ANON_RSS, OOM_FIELD_COMM,
+    OOM_FIELD_FILE_RSS, OOM_FIELD_SHMEM_RSS, OOM_FIELD_SLOTS,
 };
-use bpftop_common::{CmdlineEvent, NetStats, TaskInfo};
 
 // ============================================================
-// Kernel struct field byte offsets (from BTF via pahole)
+// Kernel struct field byte offsets (from BTF)
 // ============================================================
 //
-// Generated from: pahole -C <struct> /sys/kernel/btf/vmlinux
-// These are NOT portable across kernel versions. Without CO-RE
-// support in aya-ebpf, we must regenerate if the kernel changes.
+// The `mod offsets` blocks are generated by `cargo xtask gen-offsets`,
+// which reads /sys/kernel/btf/vmlinux on the running kernel (and adds a
+// new kernel-* feature if needed). These are NOT portable across kernel
+// versions. Without CO-RE support in aya-ebpf, we must regenerate if the
+// kernel changes.
 //
-// Build with --features arch-{x86_64,aarch64} AND kernel-{6_12,6_18}.
+// Build with --features arch-{x86_64,aarch64} AND one kernel-* feature.
 
 #[cfg(not(any(feature = "arch-x86_64", feature = "arch-aarch64")))]
 compile_error!("must enable exactly one of: arch-x86_64, arch-aarch64");
@@ -28,6 +40,7 @@ compile_error!("must enable exactly one of: kernel-6_12, kernel-6_18");
 // --- Architecture-independent, kernel-independent constants ---
 
 const TASK_STATE: usize = 24;       // task_struct.__state: u32
+const TASK_FLAGS: usize = 44;       // task_struct.flags: u32 (PF_*)
 const TASK_PRIO: usize = 124;       // task_struct.prio: i32
 const TASK_STATIC_PRIO: usize = 128; // task_struct.static_prio: i32
 
@@ -37,18 +50,37 @@ const CRED_EUID: usize = 24;        // cred.euid: kuid_t
 const CGROUP_KN: usize = 256;       // cgroup.kn: *kernfs_node
 const KN_ID: usize = 96;            // kernfs_node.id: u64
 
+const SOCK_DADDR: usize = 0;        // sock.__sk_common.skc_daddr: __be32
+const SOCK_RCV_SADDR: usize = 4;    // sock.__sk_common.skc_rcv_saddr: __be32
+const SOCK_DPORT: usize = 12;       // sock.__sk_common.skc_dport: __be16
+const SOCK_NUM: usize = 14;         // sock.__sk_common.skc_num: u16 (host order)
+const SOCK_FAMILY: usize = 16;      // sock.__sk_common.skc_family: u16
+const SOCK_STATE: usize = 18;       // sock.__sk_common.skc_state: u8
 const SOCK_BOUND_DEV_IF: usize = 20; // sock.__sk_common.skc_bound_dev_if: i32
+const SOCK_NET: usize = 48;         // sock.__sk_common.skc_net: possible_net_t (*net)
+const SOCK_V6_DADDR: usize = 56;    // sock.__sk_common.skc_v6_daddr: in6_addr
+const SOCK_V6_RCV_SADDR: usize = 72; // sock.__sk_common.skc_v6_rcv_saddr: in6_addr
+const AF_INET: u16 = 2;             // skc_family values
+const AF_INET6: u16 = 10;
+
+const FILE_PRIVATE_DATA: usize = 32; // file.private_data: *void (struct socket * for sockets)
+const FILE_F_INODE: usize = 40;     // file.f_inode: *inode
+const FILE_F_PATH: usize = 64;      // file.f_path: struct path
+const INODE_I_MODE: usize = 0;      // inode.i_mode: umode_t
+const INODE_I_SB: usize = 40;       // inode.i_sb: *super_block
+const SB_S_DEV: usize = 16;         // super_block.s_dev: dev_t
+const S_IFMT: u16 = 0o170000;       // i_mode file type mask
+const S_IFREG: u16 = 0o100000;      // i_mode: regular file
+const SOCKET_TYPE: usize = 4;       // socket.type: short
+const SOCKET_SK: usize = 24;        // socket.sk: *sock
 const DST_DEV: usize = 0;           // dst_entry.dev: *net_device
 const NETDEV_IFINDEX: usize = 224;  // net_device.ifindex: i32
 
-// --- Kernel-dependent, architecture-independent constants ---
-
-#[cfg(feature = "kernel-6_12")]
-const SOCK_DST_CACHE: usize = 528;  // sock.sk_dst_cache: *dst_entry
-#[cfg(feature = "kernel-6_18")]
-const SOCK_DST_CACHE: usize = 536;  // sock.sk_dst_cache: *dst_entry
+const PIDTYPE_PGID: usize = 2;
+const PIDTYPE_SID: usize = 3;
 
 // --- Architecture-dependent AND kernel-dependent offsets ---
+// (generated; edit xtask/src/offsets.rs rather than these blocks)
 
 #[cfg(all(feature = "arch-x86_64", feature = "kernel-6_12"))]
 mod offsets {
@@ -59,10 +91,13 @@ mod offsets {
     pub const TASK_REAL_PARENT: usize = 1856;
     pub const TASK_UTIME: usize = 2064;
     pub const TASK_STIME: usize = 2072;
+    pub const TASK_NVCSW: usize = 2168;
+    pub const TASK_NIVCSW: usize = 2176;
     pub const TASK_START_TIME: usize = 2184;
     pub const TASK_CRED: usize = 2368;
     pub const TASK_COMM: usize = 2384;
     pub const TASK_CGROUPS: usize = 2872;
+    pub const TASK_SIGNAL: usize = 2480;
     // mm_struct (rss_stat is percpu_counter[4]; each 40 bytes, count at +8)
     pub const MM_TOTAL_VM: usize = 248;
     pub const MM_ARG_START: usize = 368;
@@ -72,6 +107,16 @@ mod offsets {
     pub const MM_RSS_SHMEM_COUNT: usize = 944; // rss_stat[3].count
     // css_set
     pub const CSS_SET_DFL_CGRP: usize = 136;
+    // sock
+    pub const SOCK_DST_CACHE: usize = 528;   // sock.sk_dst_cache: *dst_entry
+    // signal_struct, pid
+    pub const SIGNAL_PIDS: usize = 352;      // signal_struct.pids: [*pid; PIDTYPE_MAX]
+    pub const PID_NUMBERS_NR: usize = 112;   // pid.numbers[0].nr: i32 (init pid namespace)
+    // net
+    pub const NET_NS_INUM: usize = 136;      // net.ns.inum: u32
+    // tcp_sock embeds struct sock, so these are relative to the sock pointer
+    pub const TCP_SND_CWND: usize = 1436;    // tcp_sock.snd_cwnd: u32
+    pub const TCP_SRTT_US: usize = 1612;     // tcp_sock.srtt_us: u32 (usecs << 3)
 }
 
 #[cfg(all(feature = "arch-x86_64", feature = "kernel-6_18"))]
@@ -83,10 +128,13 @@ mod offsets {
     pub const TASK_REAL_PARENT: usize = 1872;
     pub const TASK_UTIME: usize = 2080;
     pub const TASK_STIME: usize = 2088;
+    pub const TASK_NVCSW: usize = 2184;
+    pub const TASK_NIVCSW: usize = 2192;
     pub const TASK_START_TIME: usize = 2200;
     pub const TASK_CRED: usize = 2384;
     pub const TASK_COMM: usize = 2400;
     pub const TASK_CGROUPS: usize = 2904;
+    pub const TASK_SIGNAL: usize = 2496;
     // mm_struct (rss_stat is percpu_counter[4]; each 40 bytes, count at +8)
     pub const MM_TOTAL_VM: usize = 360;
     pub const MM_ARG_START: usize = 480;
@@ -96,6 +144,16 @@ mod offsets {
     pub const MM_RSS_SHMEM_COUNT: usize = 1056; // rss_stat[3].count
     // css_set
     pub const CSS_SET_DFL_CGRP: usize = 144;
+    // sock
+    pub const SOCK_DST_CACHE: usize = 536;   // sock.sk_dst_cache: *dst_entry
+    // signal_struct, pid
+    pub const SIGNAL_PIDS: usize = 360;      // signal_struct.pids: [*pid; PIDTYPE_MAX]
+    pub const PID_NUMBERS_NR: usize = 144;   // pid.numbers[0].nr: i32 (init pid namespace)
+    // net
+    pub const NET_NS_INUM: usize = 144;      // net.ns.inum: u32 (ns_common gained ns_type)
+    // tcp_sock embeds struct sock, so these are relative to the sock pointer
+    pub const TCP_SND_CWND: usize = 1444;    // tcp_sock.snd_cwnd: u32
+    pub const TCP_SRTT_US: usize = 1620;     // tcp_sock.srtt_us: u32 (usecs << 3)
 }
 
 #[cfg(all(feature = "arch-aarch64", feature = "kernel-6_12"))]
@@ -107,10 +165,13 @@ mod offsets {
     pub const TASK_REAL_PARENT: usize = 1760;
     pub const TASK_UTIME: usize = 1968;
     pub const TASK_STIME: usize = 1976;
+    pub const TASK_NVCSW: usize = 2072;
+    pub const TASK_NIVCSW: usize = 2080;
     pub const TASK_START_TIME: usize = 2088;
     pub const TASK_CRED: usize = 2272;
     pub const TASK_COMM: usize = 2288;
     pub const TASK_CGROUPS: usize = 2744;
+    pub const TASK_SIGNAL: usize = 2384;
     // mm_struct (rss_stat is percpu_counter[4]; each 40 bytes, count at +8)
     pub const MM_TOTAL_VM: usize = 232;
     pub const MM_ARG_START: usize = 352;
@@ -120,22 +181,35 @@ mod offsets {
     pub const MM_RSS_SHMEM_COUNT: usize = 912; // rss_stat[3].count
     // css_set
     pub const CSS_SET_DFL_CGRP: usize = 120;
+    // sock
+    pub const SOCK_DST_CACHE: usize = 528;   // sock.sk_dst_cache: *dst_entry
+    // signal_struct, pid
+    pub const SIGNAL_PIDS: usize = 352;      // signal_struct.pids: [*pid; PIDTYPE_MAX]
+    pub const PID_NUMBERS_NR: usize = 112;   // pid.numbers[0].nr: i32 (init pid namespace)
+    // net
+    pub const NET_NS_INUM: usize = 136;      // net.ns.inum: u32
+    // tcp_sock embeds struct sock, so these are relative to the sock pointer
+    pub const TCP_SND_CWND: usize = 1436;    // tcp_sock.snd_cwnd: u32
+    pub const TCP_SRTT_US: usize = 1612;     // tcp_sock.srtt_us: u32 (usecs << 3)
 }
 
 #[cfg(all(feature = "arch-aarch64", feature = "kernel-6_18"))]
 mod offsets {
     // task_struct (aarch64, Linux 6.18)
-    // TODO: these are placeholders copied from 6.12 — verify with pahole on aarch64 6.18
+    // TODO: placeholders copied from 6.12 — run `cargo xtask gen-offsets` on aarch64 6.18
     pub const TASK_MM: usize = 1616;
     pub const TASK_PID: usize = 1744;
     pub const TASK_TGID: usize = 1748;
     pub const TASK_REAL_PARENT: usize = 1760;
     pub const TASK_UTIME: usize = 1968;
     pub const TASK_STIME: usize = 1976;
+    pub const TASK_NVCSW: usize = 2072;
+    pub const TASK_NIVCSW: usize = 2080;
     pub const TASK_START_TIME: usize = 2088;
     pub const TASK_CRED: usize = 2272;
     pub const TASK_COMM: usize = 2288;
     pub const TASK_CGROUPS: usize = 2744;
+    pub const TASK_SIGNAL: usize = 2384;
     // mm_struct
     pub const MM_TOTAL_VM: usize = 232;
     pub const MM_ARG_START: usize = 352;
@@ -145,10 +219,53 @@ mod offsets {
     pub const MM_RSS_SHMEM_COUNT: usize = 912;
     // css_set
     pub const CSS_SET_DFL_CGRP: usize = 120;
+    // sock
+    pub const SOCK_DST_CACHE: usize = 536;   // sock.sk_dst_cache: *dst_entry
+    // signal_struct, pid
+    pub const SIGNAL_PIDS: usize = 360;      // signal_struct.pids: [*pid; PIDTYPE_MAX]
+    pub const PID_NUMBERS_NR: usize = 144;   // pid.numbers[0].nr: i32 (init pid namespace)
+    // net
+    pub const NET_NS_INUM: usize = 144;      // net.ns.inum: u32 (ns_common gained ns_type)
+    // tcp_sock embeds struct sock, so these are relative to the sock pointer
+    pub const TCP_SND_CWND: usize = 1444;    // tcp_sock.snd_cwnd: u32
+    pub const TCP_SRTT_US: usize = 1620;     // tcp_sock.srtt_us: u32 (usecs << 3)
 }
 
 use offsets::*;
 
+/// task_struct offsets the programs actually read: the compiled-in values
+/// above, unless the loader patched in the running kernel's layout from BTF.
+#[no_mangle]
+static TASK_OFFSETS: TaskOffsets = TaskOffsets {
+    state: TASK_STATE as u32,
+    flags: TASK_FLAGS as u32,
+    prio: TASK_PRIO as u32,
+    static_prio: TASK_STATIC_PRIO as u32,
+    mm: TASK_MM as u32,
+    pid: TASK_PID as u32,
+    tgid: TASK_TGID as u32,
+    real_parent: TASK_REAL_PARENT as u32,
+    utime: TASK_UTIME as u32,
+    stime: TASK_STIME as u32,
+    nvcsw: TASK_NVCSW as u32,
+    nivcsw: TASK_NIVCSW as u32,
+    start_time: TASK_START_TIME as u32,
+    cred: TASK_CRED as u32,
+    comm: TASK_COMM as u32,
+    cgroups: TASK_CGROUPS as u32,
+    signal: TASK_SIGNAL as u32,
+};
+
+/// One `TASK_OFFSETS` field. The volatile read keeps the compiler from
+/// folding in the compiled-in value, which the loader may have replaced.
+macro_rules! task_off {
+    ($field:ident) => {
+        unsafe { core::ptr::read_volatile(&$crate::TASK_OFFSETS.$field) as usize }
+    };
+}
+
+mod files;
+
 // ============================================================
 // Helper: read a kernel field at a fixed byte offset
 // ============================================================
@@ -188,20 +305,23 @@ unsafe fn try_dump_task(ctx: *mut IterTaskCtx) -> Result<i32, i64> {
     }
     let seq = (*(*ctx).meta).seq;
 
-    let tgid: i32 = read_field(task, TASK_TGID).unwrap_or(0);
-    let tid: i32 = read_field(task, TASK_PID).unwrap_or(0);
+    let tgid: i32 = read_field(task, task_off!(tgid)).unwrap_or(0);
+    let tid: i32 = read_field(task, task_off!(pid)).unwrap_or(0);
     let pid = tgid as u32;
 
     // Parent PID
-    let parent_ptr: *const u8 = read_field(task, TASK_REAL_PARENT).unwrap_or(core::ptr::null());
+    let parent_ptr: *const u8 = read_field(task, task_off!(real_parent)).unwrap_or(core::ptr::null());
     let ppid = if !parent_ptr.is_null() {
-        read_field::<i32>(parent_ptr, TASK_TGID).unwrap_or(0) as u32
+        read_field::<i32>(parent_ptr, task_off!(tgid)).unwrap_or(0) as u32
     } else {
         0
     };
 
+    // Process group and session, from the shared signal_struct
+    let (pgid, sid) = read_pgid_sid(task);
+
     // Credentials
-    let cred_ptr: *const u8 = read_field(task, TASK_CRED).unwrap_or(core::ptr::null());
+    let cred_ptr: *const u8 = read_field(task, task_off!(cred)).unwrap_or(core::ptr::null());
     let (euid, ruid) = if !cred_ptr.is_null() {
         let euid: u32 = read_field(cred_ptr, CRED_EUID).unwrap_or(0);
         let ruid: u32 = read_field(cred_ptr, CRED_UID).unwrap_or(0);
@@ -210,19 +330,20 @@ unsafe fn try_dump_task(ctx: *mut IterTaskCtx) -> Result<i32, i64> {
         (0, 0)
     };
 
-    // Task state
-    let state: u32 = read_field(task, TASK_STATE).unwrap_or(0);
+    // Task state and flags
+    let state: u32 = read_field(task, task_off!(state)).unwrap_or(0);
+    let flags: u32 = read_field(task, task_off!(flags)).unwrap_or(0);
 
     // CPU times (nanoseconds)
-    let utime: u64 = read_field(task, TASK_UTIME).unwrap_or(0);
-    let stime: u64 = read_field(task, TASK_STIME).unwrap_or(0);
+    let utime: u64 = read_field(task, task_off!(utime)).unwrap_or(0);
+    let stime: u64 = read_field(task, task_off!(stime)).unwrap_or(0);
 
     // Priority
-    let prio: i32 = read_field(task, TASK_PRIO).unwrap_or(120);
-    let static_prio: i32 = read_field(task, TASK_STATIC_PRIO).unwrap_or(120);
+    let prio: i32 = read_field(task, task_off!(prio)).unwrap_or(120);
+    let static_prio: i32 = read_field(task, task_off!(static_prio)).unwrap_or(120);
 
     // Memory info
-    let mm_ptr: *const u8 = read_field(task, TASK_MM).unwrap_or(core::ptr::null());
+    let mm_ptr: *const u8 = read_field(task, task_off!(mm)).unwrap_or(core::ptr::null());
     let (vsize, rss_pages, shmem_pages) = if !mm_ptr.is_null() {
         let total_vm: u64 = read_field(mm_ptr, MM_TOTAL_VM).unwrap_or(0);
         let file_count: i64 = read_field(mm_ptr, MM_RSS_FILE_COUNT).unwrap_or(0);
@@ -237,11 +358,15 @@ unsafe fn try_dump_task(ctx: *mut IterTaskCtx) -> Result<i32, i64> {
         (0, 0, 0)
     };
 
+    // Context switches (per thread; summed per process in userspace)
+    let nvcsw: u64 = read_field(task, task_off!(nvcsw)).unwrap_or(0);
+    let nivcsw: u64 = read_field(task, task_off!(nivcsw)).unwrap_or(0);
+
     // Start time
-    let start_time: u64 = read_field(task, TASK_START_TIME).unwrap_or(0);
+    let start_time: u64 = read_field(task, task_off!(start_time)).unwrap_or(0);
 
     // Comm
-    let comm: [u8; 16] = read_field(task, TASK_COMM).unwrap_or([0u8; 16]);
+    let comm: [u8; 16] = read_field(task, task_off!(comm)).unwrap_or([0u8; 16]);
 
     // Cgroup ID: task->cgroups->dfl_cgrp->kn->id
     let cgroup_id = read_cgroup_id(task).unwrap_or(0);
@@ -250,10 +375,13 @@ unsafe fn try_dump_task(ctx: *mut IterTaskCtx) -> Result<i32, i64> {
         pid,
         tid: tid as u32,
         ppid,
+        pgid,
+        sid,
         euid,
         ruid,
+        flags,
         state: state as u8,
-        _pad: [0; 3],
+        _pad: [0; 7],
         utime_ns: utime,
         stime_ns: stime,
         vsize_bytes: vsize,
@@ -264,6 +392,8 @@ unsafe fn try_dump_task(ctx: *mut IterTaskCtx) -> Result<i32, i64> {
         static_prio,
         shmem_pages,
         cgroup_id,
+        nvcsw,
+        nivcsw,
     };
 
     // Write the struct to the seq_file output
@@ -276,7 +406,7 @@ unsafe fn try_dump_task(ctx: *mut IterTaskCtx) -> Result<i32, i64> {
 
 /// Read the default cgroup2 inode ID from task->cgroups->dfl_cgrp->kn->id.
 unsafe fn read_cgroup_id(task: *const u8) -> Result<u64, i64> {
-    let css_set: *const u8 = read_field(task, TASK_CGROUPS)?;
+    let css_set: *const u8 = read_field(task, task_off!(cgroups))?;
     if css_set.is_null() {
         return Ok(0);
     }
@@ -292,12 +422,46 @@ unsafe fn read_cgroup_id(task: *const u8) -> Result<u64, i64> {
     Ok(id)
 }
 
+/// Read (pgid, sid) from task->signal->pids[PIDTYPE_{PGID,SID}]->numbers[0].nr.
+/// Either is 0 if the task is exiting and its signal_struct is gone.
+unsafe fn read_pgid_sid(task: *const u8) -> (u32, u32) {
+    let signal: *const u8 = read_field(task, task_off!(signal)).unwrap_or(core::ptr::null());
+    if signal.is_null() {
+        return (0, 0);
+    }
+    let nr = |pidtype: usize| -> u32 {
+        let pid: *const u8 =
+            read_field(signal, SIGNAL_PIDS + pidtype * 8).unwrap_or(core::ptr::null());
+        if pid.is_null() {
+            return 0;
+        }
+        read_field::<i32>(pid, PID_NUMBERS_NR).unwrap_or(0) as u32
+    };
+    (nr(PIDTYPE_PGID), nr(PIDTYPE_SID))
+}
+
 // ============================================================
 // Tracepoints + CMDLINE_MAP
 // ============================================================
 
 #[map]
-static CMDLINE_MAP: HashMap<u32, CmdlineEvent> = HashMap::with_max_entries(32768, 0);
+static CMDLINE_MAP: HashMap<CmdlineKey, CmdlineEvent> = HashMap::with_max_entries(32768, 0);
+
+/// CMDLINE_MAP key for the current task. After exec (and for the leader at
+/// exit) the task's start_time is the thread group's, so exits of other
+/// threads don't match and leave the process's entry alone.
+#[inline(always)]
+unsafe fn cmdline_key(task: *const u8, pid: u32) -> CmdlineKey {
+    CmdlineKey {
+        pid,
+        _pad: 0,
+        start_time_ns: read_field(task, task_off!(start_time)).unwrap_or(0),
+    }
+}
+
+/// Every exec, in order, for the recent-execs panel (~880 events of headroom).
+#[map]
+static EXEC_EVENTS: RingBuf = RingBuf::with_byte_size(256 * 1024, 0);
 
 /// Capture cmdline at exec time. The new mm->arg_start..arg_end
 /// contains the argv of the newly exec'd process.
@@ -311,7 +475,7 @@ unsafe fn try_capture_cmdline() -> Result<i32, i64> {
     let pid = (pid_tgid >> 32) as u32;
 
     let task = bpf_get_current_task() as *const u8;
-    let mm: *const u8 = read_field(task, TASK_MM).map_err(|_| -1i64)?;
+    let mm: *const u8 = read_field(task, task_off!(mm)).map_err(|_| -1i64)?;
     if mm.is_null() {
         return Ok(0);
     }
@@ -337,25 +501,167 @@ unsafe fn try_capture_cmdline() -> Result<i32, i64> {
         Err(_) => {}
     }
 
-    CMDLINE_MAP.insert(&pid, &event, 0).map_err(|_| -1i64)?;
+    emit_exec_event(task, &event);
+    CMDLINE_MAP
+        .insert(&cmdline_key(task, pid), &event, 0)
+        .map_err(|_| -1i64)?;
     Ok(0)
 }
 
-/// Clean up CMDLINE_MAP and NET_STATS entries when a process exits.
+/// Push an ExecEvent to the ring buffer. Dropped silently if userspace
+/// has fallen behind and the buffer is full.
+unsafe fn emit_exec_event(task: *const u8, cmdline: &CmdlineEvent) {
+    let Some(mut entry) = EXEC_EVENTS.reserve::<ExecEvent>(0) else {
+        return;
+    };
+    // Fill in place: ExecEvent plus the caller's CmdlineEvent would blow
+    // the 512-byte BPF stack
+    let ev = entry.as_mut_ptr();
+    let parent: *const u8 = read_field(task, task_off!(real_parent)).unwrap_or(core::ptr::null());
+    (*ev).timestamp_ns = bpf_ktime_get_boot_ns();
+    (*ev).pid = cmdline.pid;
+    (*ev).ppid = if parent.is_null() {
+        0
+    } else {
+        read_field::<i32>(parent, task_off!(tgid)).unwrap_or(0) as u32
+    };
+    (*ev).uid = bpf_get_current_uid_gid() as u32;
+    (*ev).len = cmdline.len;
+    (*ev).comm = bpf_get_current_comm().unwrap_or([0u8; 16]);
+    (*ev).cmdline = cmdline.cmdline;
+    entry.submit(0);
+}
+
+/// Exit codes of exiting thread group leaders, stashed by kprobe_do_exit
+/// for the sched_process_exit tracepoint (which doesn't carry the code).
+#[map]
+static EXIT_CODE_STASH: HashMap<u64, u32> = HashMap::with_max_entries(8192, 0);
+
+/// Process exits with exit codes, for greying out dying rows in the table.
+#[map]
+static EXIT_EVENTS: RingBuf = RingBuf::with_byte_size(64 * 1024, 0);
+
+#[kprobe]
+pub fn kprobe_do_exit(ctx: ProbeContext) -> u32 {
+    let pid_tgid = bpf_get_current_pid_tgid();
+    // Only the leader's exit ends the process as far as the table is concerned
+    if (pid_tgid >> 32) as u32 != pid_tgid as u32 {
+        return 0;
+    }
+    let code: u64 = ctx.arg(0).unwrap_or(0);
+    let _ = EXIT_CODE_STASH.insert(&pid_tgid, &(code as u32), 0);
+    0
+}
+
+/// Clean up CMDLINE_MAP, NET_STATS, RUNQ_LATENCY and DISK_STATS entries
+/// when a process exits, and report the exit code of thread group leaders.
+/// This fires for every thread; DISK_STATS is keyed by TGID, so only the
+/// leader's exit removes it.
 #[tracepoint(category = "sched", name = "sched_process_exit")]
 pub fn cleanup_cmdline(_ctx: TracePointContext) -> i32 {
-    let pid = (bpf_get_current_pid_tgid() >> 32) as u32;
-    let _ = CMDLINE_MAP.remove(&pid);
-    let _ = NET_STATS.remove(&pid);
+    let pid_tgid = bpf_get_current_pid_tgid();
+    let pid = (pid_tgid >> 32) as u32;
+    let key = unsafe { cmdline_key(bpf_get_current_task() as *const u8, pid) };
+    let _ = CMDLINE_MAP.remove(&key);
+    unsafe { fold_thread_net_stats(pid_tgid) };
+    unsafe { fold_thread_runq_latency(pid_tgid) };
+    if pid_tgid as u32 == pid {
+        let _ = DISK_STATS.remove(&pid);
+    }
+    unsafe { emit_exit_event(pid_tgid) };
     0
 }
 
+/// Drop an exiting thread's NET_STATS entry. A non-leader's counts move
+/// to the leader's entry first, so the process total doesn't shrink.
+#[inline(always)]
+unsafe fn fold_thread_net_stats(pid_tgid: u64) {
+    let pid = (pid_tgid >> 32) as u32;
+    let tid = pid_tgid as u32;
+    if tid != pid {
+        if let Some(thread) = NET_STATS.get(&tid) {
+            let thread = *thread;
+            match NET_STATS.get_ptr_mut(&pid) {
+                Some(leader) => {
+                    (*leader).tx_bytes += thread.tx_bytes;
+                    (*leader).rx_bytes += thread.rx_bytes;
+                }
+                None => {
+                    let _ = NET_STATS.insert(&pid, &thread, 0);
+                }
+            }
+        }
+    }
+    let _ = NET_STATS.remove(&tid);
+}
+
+/// Same as [`fold_thread_net_stats`], for RUNQ_LATENCY.
+#[inline(always)]
+unsafe fn fold_thread_runq_latency(pid_tgid: u64) {
+    let pid = (pid_tgid >> 32) as u32;
+    let tid = pid_tgid as u32;
+    if tid != pid {
+        if let Some(thread) = RUNQ_LATENCY.get(&tid) {
+            let thread = *thread;
+            match RUNQ_LATENCY.get_ptr_mut(&pid) {
+                Some(leader) => {
+                    (*leader).total_ns += thread.total_ns;
+                    (*leader).count += thread.count;
+                }
+                None => {
+                    let _ = RUNQ_LATENCY.insert(&pid, &thread, 0);
+                }
+            }
+        }
+    }
+    let _ = RUNQ_LATENCY.remove(&tid);
+    let _ = RUNQ_START.remove(&tid);
+}
+
+unsafe fn emit_exit_event(pid_tgid: u64) {
+    let Some(&exit_code) = EXIT_CODE_STASH.get(&pid_tgid) else {
+        return;
+    };
+    let _ = EXIT_CODE_STASH.remove(&pid_tgid);
+
+    let Some(mut entry) = EXIT_EVENTS.reserve::<ExitEvent>(0) else {
+        return;
+    };
+    let ev = entry.as_mut_ptr();
+    let task = bpf_get_current_task() as *const u8;
+    let parent: *const u8 = read_field(task, task_off!(real_parent)).unwrap_or(core::ptr::null());
+    // task_struct.start_time is CLOCK_MONOTONIC, same as bpf_ktime_get_ns
+    let start_time: u64 = read_field(task, task_off!(start_time)).unwrap_or(0);
+    (*ev).timestamp_ns = bpf_ktime_get_boot_ns();
+    (*ev).lifetime_ns = bpf_ktime_get_ns().saturating_sub(start_time);
+    (*ev).pid = (pid_tgid >> 32) as u32;
+    (*ev).ppid = if parent.is_null() {
+        0
+    } else {
+        read_field::<i32>(parent, task_off!(tgid)).unwrap_or(0) as u32
+    };
+    (*ev).exit_code = exit_code;
+    (*ev)._pad = 0;
+    (*ev).comm = bpf_get_current_comm().unwrap_or([0u8; 16]);
+    entry.submit(0);
+}
+
 // ============================================================
 // Network kprobes + NET_STATS map
 // ============================================================
 
+/// Per-TID network counters; userspace sums them per process. LRU because
+/// the exit tracepoint doesn't catch every way an entry goes stale (e.g.
+/// kernel threads that never exit); once full, the least recently active
+/// thread is evicted rather than new threads silently going uncounted.
 #[map]
-static NET_STATS: HashMap<u32, NetStats> = HashMap::with_max_entries(32768, 0);
+static NET_STATS: LruHashMap<u32, NetStats> = LruHashMap::with_max_entries(32768, 0);
+
+/// Bytes per (pid, remote address). LRU so busy servers with many clients
+/// evict their quietest peers instead of failing inserts; entries of exited
+/// processes age out the same way.
+#[map]
+static PEER_STATS: LruHashMap<PeerKey, PeerStats> = LruHashMap::with_max_entries(16384, 0);
 
 /// Read the interface index from a sock's cached route destination.
 /// Falls back to sk_bound_dev_if if sk_dst_cache is NULL.
@@ -378,13 +684,54 @@ unsafe fn read_sock_ifindex(sk: *const u8) -> u32 {
     if bound > 0 { bound as u32 } else { 0 }
 }
 
-/// Update NET_STATS for the current pid, adding tx_bytes.
+/// Add bytes to PEER_STATS for the socket's remote address. Skips
+/// unconnected sockets (e.g. UDP sendto), whose peer isn't on the sock.
+#[inline(always)]
+unsafe fn account_peer(pid: u32, sk: *const u8, tx: u64, rx: u64) {
+    let family: u16 = read_field(sk, SOCK_FAMILY).unwrap_or(0);
+    let mut key = PeerKey {
+        pid,
+        family,
+        _pad: 0,
+        addr: [0u8; 16],
+    };
+    match family {
+        AF_INET => {
+            let dst: [u8; 4] = read_field(sk, SOCK_DADDR).unwrap_or([0; 4]);
+            key.addr[..4].copy_from_slice(&dst);
+        }
+        AF_INET6 => key.addr = read_field(sk, SOCK_V6_DADDR).unwrap_or([0; 16]),
+        _ => return,
+    }
+    if key.addr == [0u8; 16] {
+        return;
+    }
+
+    match PEER_STATS.get_ptr_mut(&key) {
+        Some(stats) => {
+            (*stats).tx_bytes += tx;
+            (*stats).rx_bytes += rx;
+        }
+        None => {
+            let stats = PeerStats {
+                tx_bytes: tx,
+                rx_bytes: rx,
+            };
+            let _ = PEER_STATS.insert(&key, &stats, 0);
+        }
+    }
+}
+
+/// Update NET_STATS for the current thread, adding tx_bytes.
 #[inline(always)]
 unsafe fn account_tx(size: u64, sk: *const u8) {
-    let pid = (bpf_get_current_pid_tgid() >> 32) as u32;
+    let pid_tgid = bpf_get_current_pid_tgid();
+    let pid = (pid_tgid >> 32) as u32;
+    let tid = pid_tgid as u32;
     let ifindex = read_sock_ifindex(sk);
+    account_peer(pid, sk, size, 0);
 
-    match NET_STATS.get_ptr_mut(&pid) {
+    match NET_STATS.get_ptr_mut(&tid) {
         Some(stats) => {
             (*stats).tx_bytes += size;
             if ifindex != 0 {
@@ -398,22 +745,25 @@ unsafe fn account_tx(size: u64, sk: *const u8) {
                 ifindex,
                 _pad: 0,
             };
-            let _ = NET_STATS.insert(&pid, &stats, 0);
+            let _ = NET_STATS.insert(&tid, &stats, 0);
         }
     }
 }
 
-/// Update NET_STATS for the current pid, adding rx_bytes + ifindex from stashed sock.
+/// Update NET_STATS for the current thread, adding rx_bytes + ifindex from stashed sock.
 #[inline(always)]
 unsafe fn account_rx(size: u64, sk: *const u8) {
-    let pid = (bpf_get_current_pid_tgid() >> 32) as u32;
+    let pid_tgid = bpf_get_current_pid_tgid();
+    let pid = (pid_tgid >> 32) as u32;
+    let tid = pid_tgid as u32;
     let ifindex = if !sk.is_null() {
+        account_peer(pid, sk, 0, size);
         read_sock_ifindex(sk)
     } else {
         0
     };
 
-    match NET_STATS.get_ptr_mut(&pid) {
+    match NET_STATS.get_ptr_mut(&tid) {
         Some(stats) => {
             (*stats).rx_bytes += size;
             if ifindex != 0 {
@@ -427,14 +777,16 @@ unsafe fn account_rx(size: u64, sk: *const u8) {
                 ifindex,
                 _pad: 0,
             };
-            let _ = NET_STATS.insert(&pid, &stats, 0);
+            let _ = NET_STATS.insert(&tid, &stats, 0);
         }
     }
 }
 
 /// Stash map: pid_tgid -> sock pointer, used to pass sock from kprobe entry to kretprobe.
+/// LRU so entries left behind by threads that never returned (killed
+/// mid-recv) are evicted instead of filling the map.
 #[map]
-static RECV_SOCK_STASH: HashMap<u64, u64> = HashMap::with_max_entries(8192, 0);
+static RECV_SOCK_STASH: LruHashMap<u64, u64> = LruHashMap::with_max_entries(8192, 0);
 
 // --- TCP ---
 
@@ -528,6 +880,789 @@ unsafe fn pop_recv_sock(pid_tgid: u64) -> u64 {
     sk
 }
 
+// --- fentry/fexit ---
+//
+// Used instead of the kprobes above when the kernel supports BTF
+// trampolines: cheaper per call, and fexit sees the arguments next to the
+// return value, so receives need no stash map. Userspace attaches either
+// this set or the kprobes, never both.
+
+#[fentry(function = "tcp_sendmsg")]
+pub fn fentry_tcp_sendmsg(ctx: FEntryContext) -> i32 {
+    unsafe { fentry_sendmsg(&ctx) }
+}
+
+#[fentry(function = "udp_sendmsg")]
+pub fn fentry_udp_sendmsg(ctx: FEntryContext) -> i32 {
+    unsafe { fentry_sendmsg(&ctx) }
+}
+
+/// tcp_sendmsg/udp_sendmsg(struct sock *sk, struct msghdr *msg, size_t size)
+unsafe fn fentry_sendmsg(ctx: &FEntryContext) -> i32 {
+    let sk: *const u8 = ctx.arg(0);
+    let size: u64 = ctx.arg(2);
+    account_tx(size, sk);
+    0
+}
+
+#[fexit(function = "tcp_recvmsg")]
+pub fn fexit_tcp_recvmsg(ctx: FExitContext) -> i32 {
+    unsafe { fexit_recvmsg(&ctx) }
+}
+
+#[fexit(function = "udp_recvmsg")]
+pub fn fexit_udp_recvmsg(ctx: FExitContext) -> i32 {
+    unsafe { fexit_recvmsg(&ctx) }
+}
+
+/// tcp_recvmsg/udp_recvmsg(struct sock *sk, struct msghdr *msg, size_t len,
+/// int flags, int *addr_len) -> int; fexit passes the return value after
+/// the five arguments.
+unsafe fn fexit_recvmsg(ctx: &FExitContext) -> i32 {
+    let sk: *const u8 = ctx.arg(0);
+    let ret: i32 = ctx.arg(5);
+    if ret > 0 {
+        account_rx(ret as u64, sk);
+    }
+    0
+}
+
+// ============================================================
+// DNS query kprobes + DNS_EVENTS ring buffer
+// ============================================================
+//
+// UDP sends to port 53. The server comes from msg_name (sendto) or the
+// connected socket, the query from the first segment of msg_iter;
+// userspace parses the question out of the payload. DNS over TCP and
+// in-kernel senders (kvec iterators) are not seen.
+
+const MSGHDR_NAME: usize = 0;       // msghdr.msg_name: *void (kernel copy of the sockaddr)
+const MSGHDR_ITER: usize = 16;      // msghdr.msg_iter: iov_iter
+const ITER_TYPE: usize = 0;         // iov_iter.iter_type: u8
+const ITER_PTR: usize = 16;         // iov_iter.ubuf / __iov: *void
+const ITER_COUNT: usize = 24;       // iov_iter.count: usize
+const ITER_UBUF: u8 = 0;            // iter_type values (6.4+ numbering)
+const ITER_IOVEC: u8 = 1;
+const IOVEC_BASE: usize = 0;        // iovec.iov_base: *void (user)
+const IOVEC_LEN: usize = 8;         // iovec.iov_len: usize
+const SOCKADDR_FAMILY: usize = 0;   // sockaddr_in{,6}.sin{,6}_family: u16
+const SOCKADDR_PORT: usize = 2;     // sockaddr_in{,6}.sin{,6}_port: __be16
+const SIN_ADDR: usize = 4;          // sockaddr_in.sin_addr: __be32
+const SIN6_ADDR: usize = 8;         // sockaddr_in6.sin6_addr: in6_addr
+const DNS_PORT: u16 = 53u16.to_be();
+/// Header plus the shortest question (root name, type, class).
+const DNS_MIN_QUERY: usize = 17;
+
+/// msghdr/iov_iter offsets the kprobes actually read: the compiled-in
+/// values above, unless the loader patched in the running kernel's from
+/// BTF. On kernels before 6.4 the loader doesn't load these programs.
+#[no_mangle]
+static MSG_OFFSETS: MsgOffsets = MsgOffsets {
+    msg_iter: MSGHDR_ITER as u32,
+    iter_type: ITER_TYPE as u32,
+    iter_ptr: ITER_PTR as u32,
+    iter_count: ITER_COUNT as u32,
+};
+
+/// One `MSG_OFFSETS` field, read like `task_off!`.
+macro_rules! msg_off {
+    ($field:ident) => {
+        unsafe { core::ptr::read_volatile(&$crate::MSG_OFFSETS.$field) as usize }
+    };
+}
+
+/// Recent queries (~440 events of headroom).
+#[map]
+static DNS_EVENTS: RingBuf = RingBuf::with_byte_size(128 * 1024, 0);
+
+#[kprobe]
+pub fn kprobe_dns_udp_sendmsg(ctx: ProbeContext) -> u32 {
+    unsafe { try_dns_sendmsg(&ctx, false).unwrap_or(0) }
+}
+
+#[kprobe]
+pub fn kprobe_dns_udpv6_sendmsg(ctx: ProbeContext) -> u32 {
+    unsafe { try_dns_sendmsg(&ctx, true).unwrap_or(0) }
+}
+
+unsafe fn try_dns_sendmsg(ctx: &ProbeContext, v6: bool) -> Result<u32, i64> {
+    // udp{,v6}_sendmsg(struct sock *sk, struct msghdr *msg, size_t len)
+    let sk: *const u8 = ctx.arg(0).ok_or(-1i64)?;
+    let msg: *const u8 = ctx.arg(1).ok_or(-1i64)?;
+
+    let name: *const u8 = read_field(msg, MSGHDR_NAME)?;
+    let mut daddr = [0u8; 16];
+    let (family, port): (u16, u16) = if name.is_null() {
+        let family = read_field(sk, SOCK_FAMILY)?;
+        match family {
+            AF_INET => daddr[..4].copy_from_slice(&read_field::<[u8; 4]>(sk, SOCK_DADDR)?),
+            AF_INET6 => daddr = read_field(sk, SOCK_V6_DADDR)?,
+            _ => return Ok(0),
+        }
+        (family, read_field(sk, SOCK_DPORT)?)
+    } else {
+        let family = read_field(name, SOCKADDR_FAMILY)?;
+        match family {
+            AF_INET => daddr[..4].copy_from_slice(&read_field::<[u8; 4]>(name, SIN_ADDR)?),
+            AF_INET6 => daddr = read_field(name, SIN6_ADDR)?,
+            _ => return Ok(0),
+        }
+        (family, read_field(name, SOCKADDR_PORT)?)
+    };
+    if port != DNS_PORT {
+        return Ok(0);
+    }
+    // udpv6_sendmsg hands v4-mapped destinations on to udp_sendmsg,
+    // which reports them
+    if v6 && daddr[..12] == [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff] {
+        return Ok(0);
+    }
+
+    let iter = msg.add(msg_off!(msg_iter));
+    let ptr = msg_off!(iter_ptr);
+    let (base, len): (*const u8, usize) = match read_field::<u8>(iter, msg_off!(iter_type))? {
+        ITER_UBUF => (read_field(iter, ptr)?, read_field(iter, msg_off!(iter_count))?),
+        ITER_IOVEC => {
+            let iov: *const u8 = read_field(iter, ptr)?;
+            (read_field(iov, IOVEC_BASE)?, read_field(iov, IOVEC_LEN)?)
+        }
+        _ => return Ok(0),
+    };
+    if base.is_null() || len < DNS_MIN_QUERY {
+        return Ok(0);
+    }
+
+    let Some(mut entry) = DNS_EVENTS.reserve::<DnsEvent>(0) else {
+        return Ok(0);
+    };
+    let ev = entry.as_mut_ptr();
+    let len = len.min(DNS_PAYLOAD_MAX);
+    if bpf_probe_read_user_buf(base, &mut (&mut (*ev).payload)[..len]).is_err() {
+        entry.discard(0);
+        return Ok(0);
+    }
+    (*ev).timestamp_ns = bpf_ktime_get_boot_ns();
+    (*ev).pid = (bpf_get_current_pid_tgid() >> 32) as u32;
+    (*ev).len = len as u32;
+    (*ev).family = family;
+    (*ev)._pad = [0; 6];
+    (*ev).daddr = daddr;
+    entry.submit(0);
+    Ok(0)
+}
+
+// ============================================================
+// Socket drop tracepoint + SOCK_DROPS map
+// ============================================================
+
+// skb:kfree_skb record layout (rx_sk was added in 6.11)
+const KFREE_SKB_RX_SK: usize = 24;  // void *rx_sk
+const KFREE_SKB_REASON: usize = 36; // enum skb_drop_reason reason
+
+/// skb_drop_reason value -> DropKey.kind. The enum is renumbered between
+/// kernels, so userspace fills this from the tracepoint's format file.
+#[map]
+static DROP_REASONS: HashMap<u32, u16> = HashMap::with_max_entries(8, 0);
+
+/// Packets dropped per (netns, local port, kind), since load.
+#[map]
+static SOCK_DROPS: LruHashMap<DropKey, u64> = LruHashMap::with_max_entries(4096, 0);
+
+#[tracepoint(category = "skb", name = "kfree_skb")]
+pub fn count_sock_drop(ctx: TracePointContext) -> u32 {
+    unsafe { try_count_sock_drop(&ctx).unwrap_or(0) }
+}
+
+unsafe fn try_count_sock_drop(ctx: &TracePointContext) -> Result<u32, i64> {
+    let reason: u32 = ctx.read_at(KFREE_SKB_REASON)?;
+    let Some(&kind) = DROP_REASONS.get(&reason) else {
+        return Ok(0);
+    };
+    let sk: *const u8 = ctx.read_at(KFREE_SKB_RX_SK)?;
+    if sk.is_null() {
+        return Ok(0);
+    }
+
+    let net: *const u8 = read_field(sk, SOCK_NET).unwrap_or(core::ptr::null());
+    let key = DropKey {
+        netns: if net.is_null() { 0 } else { read_field(net, NET_NS_INUM).unwrap_or(0) },
+        port: read_field(sk, SOCK_NUM).unwrap_or(0),
+        kind,
+    };
+    match SOCK_DROPS.get_ptr_mut(&key) {
+        Some(count) => *count += 1,
+        None => {
+            let _ = SOCK_DROPS.insert(&key, &1, 0);
+        }
+    }
+    Ok(0)
+}
+
+// ============================================================
+// TCP state tracepoint + TCP_CONNS map
+// ============================================================
+
+// sock:inet_sock_set_state record layout
+const SET_STATE_SKADDR: usize = 8;     // const void *skaddr
+const SET_STATE_OLDSTATE: usize = 16;  // int oldstate
+const SET_STATE_NEWSTATE: usize = 20;  // int newstate
+const SET_STATE_SPORT: usize = 24;     // __u16 sport (host order)
+const SET_STATE_DPORT: usize = 26;     // __u16 dport (host order)
+const SET_STATE_FAMILY: usize = 28;    // __u16 family
+const SET_STATE_PROTOCOL: usize = 30;  // __u16 protocol
+const SET_STATE_SADDR: usize = 32;     // __u8 saddr[4]
+const SET_STATE_DADDR: usize = 36;     // __u8 daddr[4]
+const SET_STATE_SADDR_V6: usize = 40;  // __u8 saddr_v6[16]
+const SET_STATE_DADDR_V6: usize = 56;  // __u8 daddr_v6[16]
+
+const IPPROTO_TCP: u16 = 6;
+const TCP_SYN_SENT: i32 = 2;
+const TCP_CLOSE: i32 = 7;
+const TCP_LISTEN: i32 = 10;
+
+/// Live TCP sockets keyed by sock address. LRU so sockets whose close was
+/// missed (e.g. loaded mid-teardown) age out instead of filling the map.
+#[map]
+static TCP_CONNS: LruHashMap<u64, TcpConn> = LruHashMap::with_max_entries(16384, 0);
+
+#[tracepoint(category = "sock", name = "inet_sock_set_state")]
+pub fn track_tcp_state(ctx: TracePointContext) -> u32 {
+    unsafe { try_track_tcp_state(&ctx).unwrap_or(0) }
+}
+
+unsafe fn try_track_tcp_state(ctx: &TracePointContext) -> Result<u32, i64> {
+    let protocol: u16 = ctx.read_at(SET_STATE_PROTOCOL)?;
+    if protocol != IPPROTO_TCP {
+        return Ok(0);
+    }
+    let sk: u64 = ctx.read_at(SET_STATE_SKADDR)?;
+    let oldstate: i32 = ctx.read_at(SET_STATE_OLDSTATE)?;
+    let newstate: i32 = ctx.read_at(SET_STATE_NEWSTATE)?;
+    if newstate == TCP_CLOSE {
+        let _ = TCP_CONNS.remove(&sk);
+        return Ok(0);
+    }
+
+    // connect() and listen() run in the owner's context; later transitions
+    // mostly run in softirq, where the current task is whoever was
+    // interrupted, so keep the pid from the first one
+    let pid = match TCP_CONNS.get(&sk) {
+        Some(conn) => conn.pid,
+        None if oldstate == TCP_CLOSE && (newstate == TCP_SYN_SENT || newstate == TCP_LISTEN) => {
+            (bpf_get_current_pid_tgid() >> 32) as u32
+        }
+        None => 0,
+    };
+    let family: u16 = ctx.read_at(SET_STATE_FAMILY)?;
+    let sport: u16 = ctx.read_at(SET_STATE_SPORT)?;
+    let dport: u16 = ctx.read_at(SET_STATE_DPORT)?;
+    let mut conn = TcpConn {
+        pid,
+        netns: 0,
+        family: family as u8,
+        state: newstate as u8,
+        src_port: sport.to_be(),
+        dst_port: dport.to_be(),
+        _pad: 0,
+        src_addr: [0u8; 16],
+        dst_addr: [0u8; 16],
+    };
+    if family == AF_INET {
+        let saddr: [u8; 4] = ctx.read_at(SET_STATE_SADDR)?;
+        let daddr: [u8; 4] = ctx.read_at(SET_STATE_DADDR)?;
+        conn.src_addr[..4].copy_from_slice(&saddr);
+        conn.dst_addr[..4].copy_from_slice(&daddr);
+    } else {
+        conn.src_addr = ctx.read_at(SET_STATE_SADDR_V6)?;
+        conn.dst_addr = ctx.read_at(SET_STATE_DADDR_V6)?;
+    }
+    let net: *const u8 = read_field(sk as *const u8, SOCK_NET).unwrap_or(core::ptr::null());
+    if !net.is_null() {
+        conn.netns = read_field(net, NET_NS_INUM).unwrap_or(0);
+    }
+    let _ = TCP_CONNS.insert(&sk, &conn, 0);
+    Ok(0)
+}
+
+// ============================================================
+// Disk I/O kprobes + DISK_STATS map
+// ============================================================
+//
+// vfs_read/vfs_write on regular files only, so pipes, sockets, and ttys
+// don't count. Like /proc/[pid]/io rchar/wchar this includes page cache
+// hits; it's the I/O the process asked for, not what reached the device.
+
+#[map]
+static DISK_STATS: HashMap<u32, DiskStats> = HashMap::with_max_entries(32768, 0);
+
+/// Bytes written per (pid, filesystem), so writes can be put down to the
+/// mount they went to.
+#[map]
+static DEV_WRITES: LruHashMap<DevWriteKey, u64> = LruHashMap::with_max_entries(16384, 0);
+
+/// Stash map: pid_tgid -> superblock dev, set on vfs_read/vfs_write entry
+/// when the file is a regular file so the kretprobe knows to account the
+/// result, and where. LRU because detaching the probes mid-call strands
+/// entries whose kretprobe never runs.
+#[map]
+static VFS_IO_STASH: LruHashMap<u64, u32> = LruHashMap::with_max_entries(8192, 0);
+
+#[kprobe]
+pub fn kprobe_vfs_read(ctx: ProbeContext) -> u32 {
+    unsafe { try_stash_vfs_io(&ctx).unwrap_or(0) }
+}
+
+#[kretprobe]
+pub fn kretprobe_vfs_read(ctx: RetProbeContext) -> u32 {
+    unsafe { try_account_vfs_io(&ctx, false).unwrap_or(0) }
+}
+
+#[kprobe]
+pub fn kprobe_vfs_write(ctx: ProbeContext) -> u32 {
+    unsafe { try_stash_vfs_io(&ctx).unwrap_or(0) }
+}
+
+#[kretprobe]
+pub fn kretprobe_vfs_write(ctx: RetProbeContext) -> u32 {
+    unsafe { try_account_vfs_io(&ctx, true).unwrap_or(0) }
+}
+
+/// Shared entry: vfs_{read,write}(struct file *file, ...). Mark this
+/// pid_tgid with the file's filesystem if the file is a regular file.
+unsafe fn try_stash_vfs_io(ctx: &ProbeContext) -> Result<u32, i64> {
+    let file: *const u8 = ctx.arg(0).ok_or(-1i64)?;
+    if file.is_null() {
+        return Ok(0);
+    }
+    let inode: *const u8 = read_field(file, FILE_F_INODE)?;
+    if inode.is_null() {
+        return Ok(0);
+    }
+    let mode: u16 = read_field(inode, INODE_I_MODE)?;
+    if mode & S_IFMT == S_IFREG {
+        let sb: *const u8 = read_field(inode, INODE_I_SB).unwrap_or(core::ptr::null());
+        let dev: u32 = if sb.is_null() { 0 } else { read_field(sb, SB_S_DEV).unwrap_or(0) };
+        let pid_tgid = bpf_get_current_pid_tgid();
+        let _ = VFS_IO_STASH.insert(&pid_tgid, &dev, 0);
+    }
+    Ok(0)
+}
+
+/// Shared return: add the byte count to DISK_STATS if entry marked this call.
+unsafe fn try_account_vfs_io(ctx: &RetProbeContext, is_write: bool) -> Result<u32, i64> {
+    let pid_tgid = bpf_get_current_pid_tgid();
+    let Some(dev) = VFS_IO_STASH.get(&pid_tgid).copied() else {
+        return Ok(0);
+    };
+    let _ = VFS_IO_STASH.remove(&pid_tgid);
+
+    // vfs_read/vfs_write return ssize_t
+    let ret: i64 = ctx.ret();
+    if ret <= 0 {
+        return Ok(0);
+    }
+    let size = ret as u64;
+    let pid = (pid_tgid >> 32) as u32;
+
+    match DISK_STATS.get_ptr_mut(&pid) {
+        Some(stats) => {
+            if is_write {
+                (*stats).write_bytes += size;
+            } else {
+                (*stats).read_bytes += size;
+            }
+        }
+        None => {
+            let stats = DiskStats {
+                read_bytes: if is_write { 0 } else { size },
+                write_bytes: if is_write { size } else { 0 },
+            };
+            let _ = DISK_STATS.insert(&pid, &stats, 0);
+        }
+    }
+
+    if is_write {
+        let key = DevWriteKey { pid, dev };
+        match DEV_WRITES.get_ptr_mut(&key) {
+            Some(bytes) => *bytes += size,
+            None => {
+                let _ = DEV_WRITES.insert(&key, &size, 0);
+            }
+        }
+    }
+    Ok(0)
+}
+
+// ============================================================
+// Block I/O latency tracepoints + BIO_LATENCY map
+// ============================================================
+//
+// block_io_start fires when a bio becomes a request, still in the context
+// of the task that submitted it, and block_io_done when the request
+// completes. The time between is the latency that task saw, queueing in
+// the I/O scheduler included. Writeback issued by kworkers is charged to
+// the kworker, as in biosnoop.
+//
+// Kernels before 6.5 lack those two; there block_rq_issue/complete take
+// their place. Issue is when the request goes to the driver, so the
+// latency is device time only, and with an I/O scheduler the dispatch can
+// run in kblockd rather than the submitter. The loader attaches one pair
+// or the other, never both.
+
+// block_rq event class record layout (shared by block_io_start/done and
+// block_rq_issue); block_rq_complete starts with the same three fields
+const BLOCK_RQ_DEV: usize = 8;        // dev_t dev
+const BLOCK_RQ_SECTOR: usize = 16;    // sector_t sector
+const BLOCK_RQ_NR_SECTOR: usize = 24; // unsigned int nr_sector
+
+/// A request in flight, identified the way the tracepoints can see it.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct RqKey {
+    dev: u32,
+    _pad: u32,
+    sector: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct RqStart {
+    pid: u32,
+    _pad: u32,
+    ts_ns: u64,
+}
+
+/// Requests started but not yet completed. LRU so that requests whose
+/// completion we never see (merged, requeued, device removed) age out.
+#[map]
+static RQ_START: LruHashMap<RqKey, RqStart> = LruHashMap::with_max_entries(8192, 0);
+
+/// Completion latency histogram per PID, since load.
+#[map]
+static BIO_LATENCY: LruHashMap<u32, BioLatency> = LruHashMap::with_max_entries(8192, 0);
+
+#[tracepoint(category = "block", name = "block_io_start")]
+pub fn block_io_start(ctx: TracePointContext) -> u32 {
+    unsafe { try_block_io_start(&ctx).unwrap_or(0) }
+}
+
+unsafe fn try_block_io_start(ctx: &TracePointContext) -> Result<u32, i64> {
+    let Some(key) = rq_key(ctx)? else {
+        return Ok(0);
+    };
+    let start = RqStart {
+        pid: (bpf_get_current_pid_tgid() >> 32) as u32,
+        _pad: 0,
+        ts_ns: bpf_ktime_get_ns(),
+    };
+    let _ = RQ_START.insert(&key, &start, 0);
+    Ok(0)
+}
+
+#[tracepoint(category = "block", name = "block_io_done")]
+pub fn block_io_done(ctx: TracePointContext) -> u32 {
+    unsafe { try_block_io_done(&ctx).unwrap_or(0) }
+}
+
+unsafe fn try_block_io_done(ctx: &TracePointContext) -> Result<u32, i64> {
+    let Some(key) = rq_key(ctx)? else {
+        return Ok(0);
+    };
+    let Some(start) = RQ_START.get(&key).copied() else {
+        return Ok(0);
+    };
+    let _ = RQ_START.remove(&key);
+
+    let us = bpf_ktime_get_ns().saturating_sub(start.ts_ns) / 1000;
+    // floor(log2(us)), clamped to the last bucket
+    let bucket = if us == 0 { 0 } else { 63 - us.leading_zeros() as usize };
+    let bucket = bucket.min(BIO_LATENCY_BUCKETS - 1);
+
+    match BIO_LATENCY.get_ptr_mut(&start.pid) {
+        Some(hist) => (*hist).buckets[bucket] += 1,
+        None => {
+            let mut hist = BioLatency { buckets: [0; BIO_LATENCY_BUCKETS] };
+            hist.buckets[bucket] = 1;
+            let _ = BIO_LATENCY.insert(&start.pid, &hist, 0);
+        }
+    }
+    Ok(0)
+}
+
+#[tracepoint(category = "block", name = "block_rq_issue")]
+pub fn block_rq_issue(ctx: TracePointContext) -> u32 {
+    unsafe { try_block_io_start(&ctx).unwrap_or(0) }
+}
+
+#[tracepoint(category = "block", name = "block_rq_complete")]
+pub fn block_rq_complete(ctx: TracePointContext) -> u32 {
+    unsafe { try_block_io_done(&ctx).unwrap_or(0) }
+}
+
+/// Key of the request a block_rq record describes. Flushes carry no
+/// sectors and can't be told apart, so they're skipped.
+unsafe fn rq_key(ctx: &TracePointContext) -> Result<Option<RqKey>, i64> {
+    let nr_sector: u32 = ctx.read_at(BLOCK_RQ_NR_SECTOR)?;
+    if nr_sector == 0 {
+        return Ok(None);
+    }
+    Ok(Some(RqKey {
+        dev: ctx.read_at(BLOCK_RQ_DEV)?,
+        _pad: 0,
+        sector: ctx.read_at(BLOCK_RQ_SECTOR)?,
+    }))
+}
+
+// ============================================================
+// Scheduler tracepoints + RUNQ_LATENCY map
+// ============================================================
+//
+// A thread joins a run queue at sched_wakeup/sched_wakeup_new, or stays
+// on one when sched_switch preempts it. The next sched_switch onto it ends
+// the wait; the time in between is its run-queue latency, as in runqlat.
+// sched_switch is one of the hottest tracepoints there is, so these do no
+// more than a timestamp and two map operations.
+
+const SCHED_WAKEUP_PID: usize = 24;      // pid_t pid (sched_wakeup_template)
+const SCHED_SWITCH_PREV_PID: usize = 24; // pid_t prev_pid
+const SCHED_SWITCH_NEXT_PID: usize = 56; // pid_t next_pid
+
+const TASK_RUNNING: u32 = 0;
+
+/// TID -> when it became runnable. LRU since a thread that exits while
+/// queued, or a wakeup we never see the switch for, leaves its entry.
+#[map]
+static RUNQ_START: LruHashMap<u32, u64> = LruHashMap::with_max_entries(16384, 0);
+
+/// Per-TID run-queue waits since load; userspace sums them per process.
+#[map]
+static RUNQ_LATENCY: LruHashMap<u32, RunqLatency> = LruHashMap::with_max_entries(32768, 0);
+
+#[tracepoint(category = "sched", name = "sched_wakeup")]
+pub fn runq_wakeup(ctx: TracePointContext) -> u32 {
+    unsafe { try_runq_wakeup(&ctx).unwrap_or(0) }
+}
+
+#[tracepoint(category = "sched", name = "sched_wakeup_new")]
+pub fn runq_wakeup_new(ctx: TracePointContext) -> u32 {
+    unsafe { try_runq_wakeup(&ctx).unwrap_or(0) }
+}
+
+unsafe fn try_runq_wakeup(ctx: &TracePointContext) -> Result<u32, i64> {
+    let tid: i32 = ctx.read_at(SCHED_WAKEUP_PID)?;
+    if tid > 0 {
+        let _ = RUNQ_START.insert(&(tid as u32), &bpf_ktime_get_ns(), 0);
+    }
+    Ok(0)
+}
+
+#[tracepoint(category = "sched", name = "sched_switch")]
+pub fn runq_switch(ctx: TracePointContext) -> u32 {
+    unsafe { try_runq_switch(&ctx).unwrap_or(0) }
+}
+
+unsafe fn try_runq_switch(ctx: &TracePointContext) -> Result<u32, i64> {
+    let now = bpf_ktime_get_ns();
+
+    // Still in prev's context: if it is TASK_RUNNING it was preempted
+    // rather than going to sleep, and starts waiting right away
+    let prev: i32 = ctx.read_at(SCHED_SWITCH_PREV_PID)?;
+    let task = bpf_get_current_task() as *const u8;
+    let state: u32 = read_field(task, task_off!(state)).unwrap_or(u32::MAX);
+    if prev > 0 && state == TASK_RUNNING {
+        let _ = RUNQ_START.insert(&(prev as u32), &now, 0);
+    }
+
+    let next: i32 = ctx.read_at(SCHED_SWITCH_NEXT_PID)?;
+    let next = next as u32;
+    let Some(&start) = RUNQ_START.get(&next) else {
+        return Ok(0);
+    };
+    let _ = RUNQ_START.remove(&next);
+    let wait = now.saturating_sub(start);
+
+    match RUNQ_LATENCY.get_ptr_mut(&next) {
+        Some(lat) => {
+            (*lat).total_ns += wait;
+            (*lat).count += 1;
+        }
+        None => {
+            let lat = RunqLatency { total_ns: wait, count: 1 };
+            let _ = RUNQ_LATENCY.insert(&next, &lat, 0);
+        }
+    }
+    Ok(0)
+}
+
+// ============================================================
+// Off-CPU breakdown (attached on demand) + OFFCPU_TIME map
+// ============================================================
+//
+// Attached only while the detail view asks for it, and only counts the
+// process in OFFCPU_TARGET. Each of its threads is timed from one
+// scheduler event to the next, and the time in between is booked to what
+// the thread was doing: on a CPU, waiting in a run queue, or asleep in S
+// or D. A thread is picked up the first time it leaves a CPU.
+
+const TASK_UNINTERRUPTIBLE: u32 = 0x2;
+const TASK_NOLOAD: u32 = 0x400;          // with UNINTERRUPTIBLE: TASK_IDLE
+
+/// Index 0: TGID to break down, 0 for none.
+#[map]
+static OFFCPU_TARGET: Array<u32> = Array::with_max_entries(1, 0);
+
+/// TID -> what a thread of the target is doing, and since when.
+#[map]
+static OFFCPU_START: LruHashMap<u32, OffCpuStart> = LruHashMap::with_max_entries(8192, 0);
+
+/// Per-TID time by state; userspace sums the target's entries.
+#[map]
+static OFFCPU_TIME: LruHashMap<u32, OffCpuTime> = LruHashMap::with_max_entries(8192, 0);
+
+#[tracepoint(category = "sched", name = "sched_wakeup")]
+pub fn offcpu_wakeup(ctx: TracePointContext) -> u32 {
+    unsafe { try_offcpu_wakeup(&ctx).unwrap_or(0) }
+}
+
+unsafe fn try_offcpu_wakeup(ctx: &TracePointContext) -> Result<u32, i64> {
+    let tid: i32 = ctx.read_at(SCHED_WAKEUP_PID)?;
+    let tid = tid as u32;
+    // Only threads we are already timing, and only out of a sleep
+    let Some(start) = OFFCPU_START.get(&tid) else {
+        return Ok(0);
+    };
+    if start.state == OFFCPU_SLEEPING || start.state == OFFCPU_BLOCKED {
+        offcpu_enter(tid, start.tgid, OFFCPU_RUNNABLE, bpf_ktime_get_ns());
+    }
+    Ok(0)
+}
+
+#[tracepoint(category = "sched", name = "sched_switch")]
+pub fn offcpu_switch(ctx: TracePointContext) -> u32 {
+    unsafe { try_offcpu_switch(&ctx).unwrap_or(0) }
+}
+
+unsafe fn try_offcpu_switch(ctx: &TracePointContext) -> Result<u32, i64> {
+    let target = OFFCPU_TARGET.get(0).copied().unwrap_or(0);
+    if target == 0 {
+        return Ok(0);
+    }
+    let now = bpf_ktime_get_ns();
+
+    // Still in prev's context, so its TGID and state are the current task's
+    let tgid = (bpf_get_current_pid_tgid() >> 32) as u32;
+    if tgid == target {
+        let prev: i32 = ctx.read_at(SCHED_SWITCH_PREV_PID)?;
+        let task = bpf_get_current_task() as *const u8;
+        let state: u32 = read_field(task, task_off!(state)).unwrap_or(0);
+        let next_state = if state == TASK_RUNNING {
+            OFFCPU_RUNNABLE
+        } else if state & TASK_UNINTERRUPTIBLE != 0 && state & TASK_NOLOAD == 0 {
+            OFFCPU_BLOCKED
+        } else {
+            OFFCPU_SLEEPING
+        };
+        offcpu_enter(prev as u32, tgid, next_state, now);
+    }
+
+    let next: i32 = ctx.read_at(SCHED_SWITCH_NEXT_PID)?;
+    let next = next as u32;
+    if let Some(start) = OFFCPU_START.get(&next) {
+        offcpu_enter(next, start.tgid, OFFCPU_RUNNING, now);
+    }
+    Ok(0)
+}
+
+/// Book the time since the thread's last event to its previous state and
+/// move it to `state`.
+unsafe fn offcpu_enter(tid: u32, tgid: u32, state: u32, now: u64) {
+    if let Some(start) = OFFCPU_START.get(&tid) {
+        let elapsed = now.saturating_sub(start.since_ns);
+        let idx = start.state as usize;
+        match OFFCPU_TIME.get_ptr_mut(&tid) {
+            Some(time) => {
+                if let Some(ns) = (*time).ns.get_mut(idx) {
+                    *ns += elapsed;
+                }
+            }
+            None => {
+                let mut time = OffCpuTime {
+                    ns: [0; OFFCPU_STATES],
+                    tgid,
+                    _pad: 0,
+                };
+                if let Some(ns) = time.ns.get_mut(idx) {
+                    *ns = elapsed;
+                }
+                let _ = OFFCPU_TIME.insert(&tid, &time, 0);
+            }
+        }
+    }
+    let start = OffCpuStart {
+        since_ns: now,
+        state,
+        tgid,
+    };
+    let _ = OFFCPU_START.insert(&tid, &start, 0);
+}
+
+// ============================================================
+// OOM kill tracepoint + OOM_EVENTS ring buffer
+// ============================================================
+//
+// oom:mark_victim fires when the OOM killer has picked a victim, in the
+// context of the task whose allocation failed. Older kernels only record
+// the victim's PID; its comm and RSS were added in 6.8, at offsets
+// userspace reads from the format file into OOM_FIELDS.
+
+// oom:mark_victim record layout
+const MARK_VICTIM_PID: usize = 8; // int pid
+
+/// Record offsets of the optional mark_victim fields, by OOM_FIELD_* slot.
+#[map]
+static OOM_FIELDS: Array<u32> = Array::with_max_entries(OOM_FIELD_SLOTS, 0);
+
+/// OOM kills; rare, so the buffer is small.
+#[map]
+static OOM_EVENTS: RingBuf = RingBuf::with_byte_size(16 * 1024, 0);
+
+#[tracepoint(category = "oom", name = "mark_victim")]
+pub fn oom_mark_victim(ctx: TracePointContext) -> u32 {
+    unsafe { try_oom_mark_victim(&ctx).unwrap_or(0) }
+}
+
+unsafe fn try_oom_mark_victim(ctx: &TracePointContext) -> Result<u32, i64> {
+    let pid: i32 = ctx.read_at(MARK_VICTIM_PID)?;
+    let Some(mut entry) = OOM_EVENTS.reserve::<OomEvent>(0) else {
+        return Ok(0);
+    };
+    let ev = entry.as_mut_ptr();
+    (*ev).timestamp_ns = bpf_ktime_get_boot_ns();
+    (*ev).pid = pid as u32;
+    (*ev).trigger_pid = (bpf_get_current_pid_tgid() >> 32) as u32;
+    (*ev).trigger_comm = bpf_get_current_comm().unwrap_or([0u8; 16]);
+    (*ev).comm = [0u8; 16];
+    (*ev).rss_kb = 0;
+
+    let field = |slot: u32| OOM_FIELDS.get(slot).copied().unwrap_or(0) as usize;
+    let comm = field(OOM_FIELD_COMM);
+    if comm != 0 {
+        // __data_loc: offset of the string in the record (low 16 bits)
+        if let Ok(loc) = ctx.read_at::<u32>(comm) {
+            let src = (ctx.as_ptr() as *const u8).add((loc & 0xffff) as usize);
+            let _ = bpf_probe_read_kernel_str_bytes(src, &mut (*ev).comm);
+        }
+    }
+    for slot in [OOM_FIELD_ANON_RSS, OOM_FIELD_FILE_RSS, OOM_FIELD_SHMEM_RSS] {
+        let offset = field(slot);
+        if offset != 0 {
+            (*ev).rss_kb += ctx.read_at::<u64>(offset).unwrap_or(0);
+        }
+    }
+    entry.submit(0);
+    Ok(0)
+}
+
 #[panic_handler]
 fn panic(_info: &core::panic::PanicInfo) -> ! {
     unsafe { core::hint::unreachable_unchecked() }
@@ -1,6 +1,8 @@
-use std::collections::{HashMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::io;
-use std::sync::mpsc;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use anyhow::Result;
@@ -9,24 +11,74 @@ use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
 };
 use ratatui::backend::CrosstermBackend;
+use ratatui::layout::Rect;
 use ratatui::Terminal;
 
-use crate::config::Config;
-use crate::data::cgroup_control;
-use crate::data::collector::Collector;
-use crate::data::container::{resolve_service_from_path, ServiceDisplayMode};
+use crate::alerts::AlertForwarder;
+use crate::config::{CommandDisplay, Config, CpuMeter};
+use crate::data::cgroup_control::{self, CgroupWriteback};
+use crate::data::bpf_objects::BpfObjects;
+use crate::data::cgroup_tree::{self, CgroupRow};
+use crate::data::clock::{Clock, SystemClock};
+use crate::data::collector::{
+    BpfStats, Collector, Inspection, IoLatency, MountWrite, OffCpuBreakdown, Peer, Snapshot,
+    ThreadInfo,
+};
+use crate::data::container::ServiceDisplayMode;
+use crate::data::diff::{diff_processes, DiffEntry};
+use crate::data::dns::DnsQuery;
+use crate::data::environ::{read_environ, EnvVar};
+use crate::dump::SnapshotDumper;
+#[cfg(feature = "sqlite")]
+use crate::trends::TrendRecorder;
+use crate::data::execs::{ExecRecord, EXEC_LOG_CAPACITY};
+use crate::data::oom::{OomRecord, OOM_LOG_CAPACITY};
+use crate::data::exits::{ExitRecord, DYING_REFRESHES};
+use crate::data::files::{host_netns, OpenFile, NO_FD};
+use crate::data::groups::{self, Group, GroupBy, GroupRow};
+use crate::data::init_system::InitSystem;
+use crate::data::pidfd::PidFd;
 use crate::data::process::{
-    compare_processes, matches_filter, ProcessInfo, SortColumn, YankField,
+    compare_processes_by, format_time_short, matches_filter, ProcessFilter, ProcessInfo, ProcessState, SortColumn, YankField,
 };
+use crate::data::runtime_probe::{Runtime, RuntimeProbe};
+use crate::data::session::{self, SessionSummary};
+use crate::data::spawn::{self, SpawnRequest, Spawned};
+use crate::data::supervisor::{PendingRestart, Supervisor};
+use crate::data::history::{ProcessHistory, ProcessSeries, SystemHistory};
 use crate::data::system::SystemInfo;
-use crate::ebpf::loader::EbpfLoader;
+use crate::ebpf::loader::{EbpfLoader, Subsystem};
 use crate::input;
+use crate::marks::{Mark, Marks};
+use crate::metrics::MetricsPusher;
+use crate::mirror::Mirror;
+use crate::redact::Redact;
 use crate::theme::Theme;
+use crate::ui::badges::Badges;
+use crate::ui::bpf_objects_panel::BpfObjectsPanel;
+use crate::ui::bpf_stats_panel::BpfStatsPanel;
+use crate::ui::connections_panel::{ConnRow, ConnectionsPanel};
+use crate::ui::copy_mode::{CopyBuffer, CopyView};
+use crate::ui::detail::DetailView;
 use crate::ui::dialogs::{FreezeDialog, HelpDialog, KillDialog};
+use crate::ui::diff_view::DiffView;
+use crate::ui::env_panel::EnvPanel;
+use crate::ui::execs_panel::ExecsPanel;
+use crate::ui::files_panel::FilesPanel;
 use crate::ui::filter_bar::{FilterBarWidget, FilterMode};
-use crate::ui::header::HeaderWidget;
-use crate::ui::layout::main_layout;
+use crate::ui::frame_limiter::FrameLimiter;
+use crate::ui::header::{HeaderMode, HeaderWidget};
+use crate::ui::history_view::HistoryView;
+use crate::ui::layout::{main_layout, SUMMARY_ROWS};
+use crate::ui::oom_panel::OomPanel;
+use crate::ui::overlay::Scroll;
 use crate::ui::process_table::{self, ProcessTableWidget};
+use crate::ui::runtime_panel::RuntimePanel;
+use crate::ui::screen_reader::{describe_selection, strip_decoration};
+use crate::ui::cgroups_panel::CgroupsPanel;
+use crate::ui::groups_panel::GroupsPanel;
+use crate::ui::sessions_panel::SessionsPanel;
+use crate::ui::setup::{SetupPane, SetupScreen};
 use crate::ui::status_bar::StatusBarWidget;
 use crate::ui::tree_view;
 
@@ -41,6 +93,23 @@ pub enum AppMode {
     SortSelect,
     Visual,
     Freeze,
+    Files,
+    Diff,
+    Connections,
+    Detail,
+    Execs,
+    Ooms,
+    History,
+    BpfStats,
+    BpfObjects,
+    Runtime,
+    Environ,
+    Sessions,
+    Cgroups,
+    Groups,
+    Command,
+    Copy,
+    Setup,
 }
 
 /// A cgroup targeted for freeze/thaw, with the PIDs it contains.
@@ -51,6 +120,9 @@ pub struct FreezeTarget {
     pub is_root: bool,
 }
 
+/// Auto-thaw timers the freeze dialog steps through with `t`; 0 is off.
+pub const THAW_AFTER_SECS: [u64; 6] = [0, 10, 30, 60, 300, 900];
+
 /// Main application state.
 pub struct App {
     pub mode: AppMode,
@@ -59,6 +131,8 @@ pub struct App {
 
     // Process data
     pub sys_info: SystemInfo,
+    pub sys_history: SystemHistory,
+    pub proc_history: ProcessHistory,
     pub all_processes: Vec<ProcessInfo>,
     pub filtered_processes: Vec<ProcessInfo>,
 
@@ -68,30 +142,72 @@ pub struct App {
     pub visible_rows: usize,
     pub sort_column: SortColumn,
     pub sort_ascending: bool,
+    /// Secondary sort key and its direction, for ties on `sort_column`
+    /// (e.g. USER, then CPU%).
+    pub sort_then: Option<(SortColumn, bool)>,
+    /// The sort picker is editing the secondary key.
+    pub sort_select_then: bool,
 
     // View toggles
     pub tree_view: bool,
     pub show_threads: bool,
     pub show_kernel_threads: bool,
+    /// Header shows history graphs instead of meters.
+    pub header_graphs: bool,
 
     // Tree collapse/expand
     pub collapsed_pids: HashSet<u32>,
+    /// PIDs with thread rows, rebuilt when rows come and go.
+    thread_parents: HashSet<u32>,
 
     // Column toggles
     pub show_net: bool,
+    pub show_disk: bool,
+    pub show_job: bool,
+    pub compact: bool,
+    /// Full command line, with the program's directory stripped, or comm (`p`).
+    pub command_display: CommandDisplay,
     pub show_gpu: bool,
 
     // Service display mode
     pub service_display_mode: ServiceDisplayMode,
+    /// PID 1's init system, which decides where service names come from.
+    pub init_system: InitSystem,
+    /// Workload badge rules for the Command column.
+    pub badges: Badges,
+    /// What `--redact` masks on screen and in snapshot dumps.
+    pub redact: Redact,
 
     // Filter/search
     pub filter_query: String,
     pub active_filter: String,
+    /// Why `active_filter` could not be parsed (it is then ignored).
+    pub filter_error: Option<String>,
     pub user_filter: Option<String>,
 
+    // Command line (`:`) and the processes `:spawn` started, until they exit
+    pub command_input: String,
+    spawned: Vec<Spawned>,
+    /// PID the selection stays on across refreshes (`F`, or a spawned
+    /// command). Moving the cursor by hand stops following.
+    pub follow_pid: Option<u32>,
+    /// A mouse drag that started on the process table scrollbar, holding
+    /// the row of the thumb it caught so the thumb stays under the pointer.
+    pub scrollbar_drag: Option<usize>,
+
     // Kill dialog
     pub kill_signal_idx: usize,
     pub kill_pid_scroll: usize,
+    /// Signal whole process groups (kill(-pgid)) instead of single PIDs.
+    pub kill_group: bool,
+    /// Supervisor of the single process the kill dialog targets, if any.
+    pub kill_supervisor: Option<Supervisor>,
+    /// Supervisor restarts still running.
+    restarts: Vec<PendingRestart>,
+    /// The kill dialog's processes, pinned when it opened.
+    pub kill_pinned: Vec<PidFd>,
+    /// Target PIDs that had already exited (or been reused) by then.
+    pub kill_gone: Vec<u32>,
     pub pre_kill_mode: AppMode,
 
     // Freeze/thaw dialog
@@ -99,6 +215,96 @@ pub struct App {
     pub freeze_is_thaw: bool,
     pub pre_freeze_mode: AppMode,
     pub freeze_scroll: usize,
+    /// Thaw the frozen cgroups again after this many seconds; 0 for never.
+    pub freeze_thaw_secs: u64,
+
+    // Per-process inspection (open files / connections panels)
+    pub inspection: Option<Inspection>,
+    pub inspect_target: u32,
+    pub files_scroll: Scroll,
+    pub files_filter: String,
+    pub files_filter_editing: bool,
+    pub conn_selected: usize,
+    /// Connections panel lists every process's sockets, not just the inspected one's.
+    pub conn_all: bool,
+    /// Connections panel only shows sockets in this network namespace.
+    pub conn_netns: Option<u32>,
+    /// Connections panel lists the slowest TCP round trips first.
+    pub conn_sort_rtt: bool,
+    host_netns: Option<u32>,
+    pub detail_scroll: Scroll,
+    /// Process shown in the history popup.
+    pub history_pid: u32,
+    /// The popup's long-term view (`t`): samples recorded under `[trends]`
+    /// and the seconds they span, or why they couldn't be read.
+    pub history_long_term: Option<Result<(ProcessSeries, u64), String>>,
+    /// eBPF program overhead and map fill, while the BPF stats panel is open.
+    pub bpf_stats: Option<BpfStats>,
+    /// System-wide BPF programs and maps panel: last sample, whether it
+    /// lists maps rather than programs, and scroll.
+    pub bpf_objects: Option<BpfObjects>,
+    pub bpf_objects_maps: bool,
+    pub bpf_objects_scroll: Scroll,
+    /// jcmd / py-spy thread dump shown in the runtime popup.
+    pub runtime_probe: Option<RuntimeProbe>,
+    pub runtime_scroll: Scroll,
+    /// Environment panel: the process, and its variables as read when the
+    /// panel opened or why they couldn't be.
+    pub env_pid: u32,
+    pub env_comm: String,
+    pub env_vars: Result<Vec<EnvVar>, String>,
+    pub env_scroll: Scroll,
+    pub env_filter: String,
+    pub env_filter_editing: bool,
+    /// Sessions panel: selected row, and whether daemons' sessions (no
+    /// terminal) are listed too.
+    pub sessions_selected: usize,
+    pub sessions_all: bool,
+    own_sid: u32,
+    /// Never cgroup.kill'ed, nor any cgroup above it.
+    own_cgroup: Option<String>,
+    /// Cgroup view: selected row and folded cgroup paths.
+    pub cgroups_selected: usize,
+    pub cgroups_collapsed: HashSet<String>,
+    /// Groups panel: what it groups by, selected row, and groups whose
+    /// processes are listed.
+    pub group_by: GroupBy,
+    pub groups_selected: usize,
+    pub groups_expanded: HashSet<String>,
+
+    // Process tree diff: snapshot recorded at T1, entries computed at T2
+    pub diff_base: Option<(Instant, Vec<ProcessInfo>)>,
+    pub diff_entries: Vec<DiffEntry>,
+    pub diff_span: Duration,
+    pub diff_scroll: Scroll,
+
+    // Recent execs (from the EXEC_EVENTS ring buffer), oldest first
+    pub exec_log: VecDeque<ExecRecord>,
+    pub execs_scroll: Scroll,
+
+    // Recent OOM kills, oldest first; kills since the panel was last
+    // opened keep a marker in the status bar
+    pub oom_log: VecDeque<OomRecord>,
+    pub oom_unseen: usize,
+    pub oom_scroll: Scroll,
+
+    // Copy mode: screen text frozen on the first frame drawn in the mode
+    pub copy_buffer: CopyBuffer,
+
+    // Setup screen: the open pane, every column with its visibility, and
+    // the config to restore on cancel
+    pub setup_pane: SetupPane,
+    pub setup_columns: Vec<(SortColumn, bool)>,
+    pub setup_selected: usize,
+    setup_original: Option<Box<Config>>,
+
+    // Exit codes not yet matched to a vanished row, and refreshes left
+    // for rows kept (greyed out) after their process exited
+    pending_exits: HashMap<u32, ExitRecord>,
+    dying: HashMap<u32, u8>,
+
+    // Consecutive quiet refreshes per PID, for idle dimming
+    quiet_streak: HashMap<u32, u32>,
 
     // Vim multi-key sequences
     pub pending_key: Option<char>,
@@ -106,6 +312,11 @@ pub struct App {
     // Jump list
     pub jump_list: Vec<u32>,
     pub jump_pos: usize,
+    /// `m{a-z}` bookmarks.
+    pub marks: Marks,
+
+    /// Read-only viewers of the screen (`--share`).
+    pub mirror: Option<Mirror>,
 
     // Visual mode
     pub visual_anchor: Option<usize>,
@@ -113,6 +324,9 @@ pub struct App {
     // Flash message (transient status bar text)
     pub flash_message: Option<(String, Instant)>,
 
+    /// Shared with the collector; times flash messages and diffs.
+    pub clock: Arc<dyn Clock>,
+
     // Dynamic header height and table width (set during draw)
     pub header_height: u16,
     pub table_width: u16,
@@ -122,17 +336,32 @@ pub struct App {
 
     // Data collection
     collector: Collector,
+    inspect_pid: Arc<AtomicU32>,
+    bpf_stats_wanted: Arc<AtomicBool>,
+    bpf_objects_wanted: Arc<AtomicBool>,
+    /// Off-CPU breakdown of the inspected process is switched on.
+    offcpu_wanted: Arc<AtomicBool>,
+    /// Set while a DISK column is shown; see `Collector::file_io_handle`.
+    file_io_wanted: Arc<AtomicBool>,
+    /// Set while the LAT column is shown; see `Collector::runq_handle`.
+    runq_wanted: Arc<AtomicBool>,
+    /// Set while the detail view is open; see `Collector::detail_handle`.
+    detail_wanted: Arc<AtomicBool>,
+    /// Auto-thaw deadlines per cgroup, enforced by the collector thread.
+    thaw_schedule: Arc<Mutex<HashMap<String, Instant>>>,
+    /// Hardware counters for the inspected process are switched on.
+    perf_wanted: Arc<AtomicBool>,
+    /// Collector interval, shared so the setup screen can change it live.
+    refresh_ms: Arc<AtomicU64>,
     ebpf_loaded: bool,
+    /// eBPF subsystems that failed to attach, shown in the status bar.
+    ebpf_unavailable: Vec<Subsystem>,
     pub ebpf_error: Option<String>,
 }
 
 impl App {
     pub fn new(config: Config) -> Self {
-        let theme = Theme::from_config(&config.theme.preset, &config.theme.overrides);
-        let tree_view = config.general.tree_view;
-        let show_threads = config.general.show_threads;
-        let show_kernel_threads = config.general.show_kernel_threads;
-        let (ebpf, ebpf_error) = match EbpfLoader::load() {
+        let (ebpf, ebpf_error) = match EbpfLoader::load(&config.ebpf.map_sizes) {
             Ok(loader) => {
                 log::info!("eBPF programs loaded successfully");
                 (loader, None)
@@ -147,14 +376,52 @@ impl App {
                 (EbpfLoader::noop(), Some(msg))
             }
         };
+        Self::with_loader(config, ebpf, ebpf_error)
+    }
+
+    /// An app fed only by `merge_data`, for driving the UI without eBPF
+    /// or a terminal (`bpftop bench-ui`).
+    pub fn headless(config: Config) -> Self {
+        Self::with_loader(config, EbpfLoader::noop(), None)
+    }
+
+    fn with_loader(config: Config, ebpf: EbpfLoader, ebpf_error: Option<String>) -> Self {
+        let theme = Theme::from_config(&config.theme.preset, &config.theme.overrides);
+        let tree_view = config.general.tree_view;
+        let show_threads = config.general.show_threads;
+        let show_kernel_threads = config.general.show_kernel_threads;
+        let compact = config.general.compact;
+        let command_display = config.columns.command;
+        let badges = Badges::from_config(&config.badges);
+        let redact = Redact::new(&config.privacy.redact);
+        let marks = Marks::load(config.general.persist_marks);
+        let refresh_ms = Arc::new(AtomicU64::new(config.general.refresh_rate_ms));
         let ebpf_loaded = ebpf.is_loaded();
-        let collector = Collector::new(ebpf);
+        let ebpf_unavailable = ebpf.unavailable().to_vec();
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+        let mut collector = Collector::with_clock(ebpf, Arc::clone(&clock));
+        collector.set_drop_alert(config.alerts.drops_per_sec);
+        collector.set_leak_alert(config.alerts.leak_minutes);
+        collector.set_throttle_alert(config.alerts.throttle_secs);
+        collector.set_setuid_allowlist(&config.alerts.setuid_allowlist);
+        collector.set_perf_top(config.perf.top);
+        let inspect_pid = collector.inspect_handle();
+        let bpf_stats_wanted = collector.bpf_stats_handle();
+        let bpf_objects_wanted = collector.bpf_objects_handle();
+        let offcpu_wanted = collector.offcpu_handle();
+        let file_io_wanted = collector.file_io_handle();
+        let runq_wanted = collector.runq_handle();
+        let detail_wanted = collector.detail_handle();
+        let thaw_schedule = collector.thaw_schedule_handle();
+        let perf_wanted = collector.perf_handle();
 
         Self {
             mode: AppMode::Normal,
             config,
             theme,
             sys_info: SystemInfo::default(),
+            sys_history: SystemHistory::default(),
+            proc_history: ProcessHistory::default(),
             all_processes: Vec::new(),
             filtered_processes: Vec::new(),
             selected: 0,
@@ -162,39 +429,136 @@ impl App {
             visible_rows: 0,
             sort_column: SortColumn::Pid,
             sort_ascending: true,
+            sort_then: None,
+            sort_select_then: false,
             tree_view,
             show_threads,
             show_kernel_threads,
+            header_graphs: false,
             collapsed_pids: HashSet::new(),
+            thread_parents: HashSet::new(),
             show_net: false,
+            show_disk: false,
+            show_job: false,
+            compact,
+            command_display,
             show_gpu: true,
             service_display_mode: ServiceDisplayMode::ServiceOnly,
+            init_system: InitSystem::detect(),
+            badges,
+            redact,
             filter_query: String::new(),
             active_filter: String::new(),
+            filter_error: None,
             user_filter: None,
+            command_input: String::new(),
+            spawned: Vec::new(),
+            follow_pid: None,
+            scrollbar_drag: None,
             kill_signal_idx: 0,
             kill_pid_scroll: 0,
+            kill_group: false,
+            kill_supervisor: None,
+            restarts: Vec::new(),
+            kill_pinned: Vec::new(),
+            kill_gone: Vec::new(),
             pre_kill_mode: AppMode::Normal,
             freeze_targets: Vec::new(),
             freeze_is_thaw: false,
             pre_freeze_mode: AppMode::Normal,
             freeze_scroll: 0,
+            freeze_thaw_secs: 0,
+            inspection: None,
+            inspect_target: 0,
+            files_scroll: Scroll::default(),
+            files_filter: String::new(),
+            files_filter_editing: false,
+            conn_selected: 0,
+            conn_all: false,
+            conn_netns: None,
+            conn_sort_rtt: false,
+            host_netns: host_netns(),
+            detail_scroll: Scroll::default(),
+            history_pid: 0,
+            history_long_term: None,
+            bpf_stats: None,
+            bpf_objects: None,
+            bpf_objects_maps: false,
+            bpf_objects_scroll: Scroll::default(),
+            runtime_probe: None,
+            runtime_scroll: Scroll::default(),
+            env_pid: 0,
+            env_comm: String::new(),
+            env_vars: Ok(Vec::new()),
+            env_scroll: Scroll::default(),
+            env_filter: String::new(),
+            env_filter_editing: false,
+            sessions_selected: 0,
+            sessions_all: false,
+            own_sid: nix::unistd::getsid(None).map_or(0, |sid| sid.as_raw() as u32),
+            own_cgroup: cgroup_control::own_cgroup(),
+            cgroups_selected: 0,
+            cgroups_collapsed: HashSet::new(),
+            group_by: GroupBy::Service,
+            groups_selected: 0,
+            groups_expanded: HashSet::new(),
+            diff_base: None,
+            diff_entries: Vec::new(),
+            diff_span: Duration::ZERO,
+            diff_scroll: Scroll::default(),
+            exec_log: VecDeque::new(),
+            execs_scroll: Scroll::default(),
+            oom_log: VecDeque::new(),
+            oom_unseen: 0,
+            oom_scroll: Scroll::default(),
+            copy_buffer: CopyBuffer::default(),
+            setup_pane: SetupPane::default(),
+            setup_columns: Vec::new(),
+            setup_selected: 0,
+            setup_original: None,
+            pending_exits: HashMap::new(),
+            dying: HashMap::new(),
+            quiet_streak: HashMap::new(),
             pending_key: None,
             jump_list: Vec::new(),
             jump_pos: 0,
+            marks,
+            mirror: None,
             visual_anchor: None,
             flash_message: None,
+            clock,
             header_height: 4,
             table_width: 0,
             dirty: true,
             collector,
+            inspect_pid,
+            bpf_stats_wanted,
+            bpf_objects_wanted,
+            offcpu_wanted,
+            file_io_wanted,
+            runq_wanted,
+            detail_wanted,
+            thaw_schedule,
+            perf_wanted,
+            refresh_ms,
             ebpf_loaded,
+            ebpf_unavailable,
             ebpf_error,
         }
     }
 
     /// Run the main event loop.
     pub fn run(&mut self) -> Result<()> {
+        let result = self.run_tui();
+        // Auto-thaw timers run in bpftop; nothing would thaw these later
+        let thawed = self.thaw_scheduled();
+        if thawed > 0 {
+            eprintln!("Thawed {thawed} cgroup(s) early; auto-thaw timers only run while bpftop does");
+        }
+        result
+    }
+
+    fn run_tui(&mut self) -> Result<()> {
         // Set up terminal
         enable_raw_mode()?;
         let mut stdout = io::stdout();
@@ -202,24 +566,55 @@ impl App {
         let backend = CrosstermBackend::new(stdout);
         let mut terminal = Terminal::new(backend)?;
 
-        let tick_rate = Duration::from_millis(self.config.general.refresh_rate_ms);
-
         // Move collector to background thread
         let (data_tx, data_rx) = mpsc::channel();
         let (shutdown_tx, shutdown_rx) = mpsc::channel::<()>();
         let mut collector = std::mem::replace(&mut self.collector, Collector::noop());
 
+        let forwarder = AlertForwarder::new(self.config.alerts.forward);
+
         // Initial collect on main thread so first frame has data
-        if let Ok(data) = collector.collect() {
-            self.merge_data(data.0, data.1);
+        if let Ok(snapshot) = collector.collect() {
+            if let Some(ref forwarder) = forwarder {
+                for alert in &snapshot.alerts {
+                    forwarder.forward(alert);
+                }
+            }
+            self.merge_data(snapshot);
+        }
+
+        // Scheduled snapshots are written from the collector thread so they
+        // keep going regardless of what the UI is doing
+        let mut dumper = SnapshotDumper::from_config(&self.config.snapshot, self.redact.clone());
+        let mut pusher = MetricsPusher::from_config(&self.config.push, self.redact.clone());
+        #[cfg(feature = "sqlite")]
+        let mut trends = TrendRecorder::from_config(&self.config.trends, self.redact.clone());
+        #[cfg(not(feature = "sqlite"))]
+        if self.config.trends.enabled {
+            log::warn!("[trends] is enabled but this build lacks the sqlite feature");
         }
 
-        let tick_rate_clone = tick_rate;
+        let refresh_ms = Arc::clone(&self.refresh_ms);
         std::thread::spawn(move || {
             loop {
-                std::thread::sleep(tick_rate_clone);
+                std::thread::sleep(Duration::from_millis(refresh_ms.load(Ordering::Relaxed)));
                 match collector.collect() {
                     Ok(data) => {
+                        if let Some(ref mut dumper) = dumper {
+                            dumper.maybe_dump(&data.sys, &data.processes);
+                        }
+                        if let Some(ref mut pusher) = pusher {
+                            pusher.maybe_push(&data.sys, &data.processes);
+                        }
+                        #[cfg(feature = "sqlite")]
+                        if let Some(ref mut trends) = trends {
+                            trends.maybe_record(&data.processes);
+                        }
+                        if let Some(ref forwarder) = forwarder {
+                            for alert in &data.alerts {
+                                forwarder.forward(alert);
+                            }
+                        }
                         if data_tx.send(data).is_err() {
                             break;
                         }
@@ -234,31 +629,59 @@ impl App {
 
         // Short poll timeout so UI stays responsive
         let poll_timeout = Duration::from_millis(50);
+        let mut limiter = FrameLimiter::new(self.config.general.max_fps);
 
-        loop {
+        'main: loop {
             // Drain any available data (use freshest)
-            while let Ok((sys_info, processes)) = data_rx.try_recv() {
-                self.merge_data(sys_info, processes);
+            while let Ok(snapshot) = data_rx.try_recv() {
+                self.merge_data(snapshot);
             }
+            if let Some(ref mut probe) = self.runtime_probe {
+                if probe.poll() {
+                    self.dirty = true;
+                }
+            }
+            self.poll_spawned();
+            self.poll_restarts();
 
             // Expire flash message (forces redraw to clear it)
             if self.active_flash().is_some() {
                 self.dirty = true;
             }
 
-            // Only redraw when something changed
-            if self.dirty {
-                terminal.draw(|frame| self.draw(frame))?;
+            // Only redraw when something changed, and no faster than the
+            // terminal keeps up with
+            limiter.set_max_fps(self.config.general.max_fps);
+            if self.dirty && limiter.wait().is_zero() {
+                let started = Instant::now();
+                let frame = terminal.draw(|frame| self.draw(frame))?;
+                if let Some(ref mut mirror) = self.mirror {
+                    mirror.send(frame.buffer);
+                }
+                limiter.record(started);
                 self.dirty = false;
             }
 
-            // Handle events with short timeout
-            if event::poll(poll_timeout)? {
-                let evt = event::read()?;
-                if input::handle_event(self, evt) {
-                    break;
+            // Wake up in time for a deferred frame
+            let timeout = if self.dirty {
+                limiter.wait().min(poll_timeout)
+            } else {
+                poll_timeout
+            };
+
+            // Handle every queued event before drawing again, so held keys
+            // and mouse scrolling coalesce into one frame
+            if event::poll(timeout)? {
+                loop {
+                    let evt = event::read()?;
+                    if input::handle_event(self, evt) {
+                        break 'main;
+                    }
+                    self.dirty = true;
+                    if !event::poll(Duration::ZERO)? {
+                        break;
+                    }
                 }
-                self.dirty = true;
             }
         }
 
@@ -277,7 +700,7 @@ impl App {
         Ok(())
     }
 
-    fn draw(&mut self, frame: &mut ratatui::Frame) {
+    pub fn draw(&mut self, frame: &mut ratatui::Frame) {
         let area = frame.area();
 
         // Fill background
@@ -286,38 +709,74 @@ impl App {
         let bg = ratatui::widgets::Block::default().style(bg_style);
         frame.render_widget(bg, area);
 
-        let filter_active = self.mode == AppMode::Search || self.mode == AppMode::Filter;
-        let num_cpus = self.sys_info.cpus.len().max(1);
-        let num_gpus = self.sys_info.gpus.len();
-        let has_gpu = num_gpus > 0;
+        let filter_active = matches!(self.mode, AppMode::Search | AppMode::Filter | AppMode::Command);
+        let has_gpu = !self.sys_info.gpus.is_empty();
+        // Screen readers get the one-line header, with plain numbers
+        // instead of the braille CPU strip
+        let screen_reader = self.config.general.screen_reader;
+        let summary = self.config.general.summary;
+        let header_mode = if self.compact || screen_reader {
+            HeaderMode::Compact
+        } else if self.header_graphs {
+            HeaderMode::Graphs
+        } else {
+            HeaderMode::Meters
+        };
+        let mut meters = self.config.meters.clone();
+        // Both want few header rows; a total CPU bar instead of the grid
+        if (screen_reader || summary) && meters.cpu == CpuMeter::PerCpu {
+            meters.cpu = CpuMeter::Total;
+        }
+        if self.sys_info.pressure.is_none() {
+            meters.pressure = false;
+        }
         let (header_area, table_area, status_area, filter_area) =
-            main_layout(area, filter_active, num_cpus, num_gpus);
+            main_layout(
+                area,
+                filter_active,
+                &self.sys_info,
+                &meters,
+                header_mode,
+                summary.then_some(SUMMARY_ROWS),
+            );
         self.header_height = header_area.height;
         self.table_width = table_area.width;
 
         // Header meters
         let header = HeaderWidget {
             sys: &self.sys_info,
+            history: &self.sys_history,
             theme: &self.theme,
+            meters: &meters,
+            mode: header_mode,
         };
         frame.render_widget(header, header_area);
 
         // Process table
         self.visible_rows = process_table::visible_rows(table_area);
-        let has_container = self.filtered_processes.iter().any(|p| p.container.is_some());
-        let has_service = self.filtered_processes.iter().any(|p| p.service.is_some());
+        let (has_container, has_pod, has_service) = self.optional_columns();
         let table = ProcessTableWidget {
             processes: &self.filtered_processes,
             selected: self.selected,
             scroll_offset: self.scroll_offset,
             sort_column: self.sort_column,
             sort_ascending: self.sort_ascending,
+            sort_then: self.sort_then,
             theme: &self.theme,
             show_container: has_container,
+            show_pod: has_pod,
             show_service: has_service,
             service_display_mode: self.service_display_mode,
             show_gpu: self.show_gpu && has_gpu,
+            show_power: self.sys_info.package_watts().is_some(),
             show_net: self.show_net,
+            show_disk: self.show_disk,
+            show_job: self.show_job,
+            compact: self.compact,
+            command_display: self.command_display,
+            columns: &self.config.columns.show,
+            badges: &self.badges,
+            redact: &self.redact,
             visual_range: self.visual_range(),
             error_message: self.ebpf_error.as_deref(),
         };
@@ -327,11 +786,21 @@ impl App {
         if let Some(filter_area) = filter_area {
             let mode = match self.mode {
                 AppMode::Search => FilterMode::Search,
+                AppMode::Command => FilterMode::Command,
                 _ => FilterMode::Filter,
             };
             let filter_bar = FilterBarWidget {
-                query: &self.filter_query,
+                query: if mode == FilterMode::Command {
+                    &self.command_input
+                } else {
+                    &self.filter_query
+                },
                 mode,
+                error: if mode == FilterMode::Command {
+                    None
+                } else {
+                    self.filter_error.as_deref()
+                },
                 theme: &self.theme,
             };
             frame.render_widget(filter_bar, filter_area);
@@ -341,7 +810,57 @@ impl App {
         let status = StatusBarWidget {
             theme: &self.theme,
             ebpf_loaded: self.ebpf_loaded,
+            ebpf_unavailable: &self.ebpf_unavailable,
             flash: self.active_flash(),
+            oom: (self.oom_unseen > 0)
+                .then(|| self.oom_log.back())
+                .flatten()
+                .map(|o| {
+                    let more = if self.oom_unseen > 1 {
+                        format!(" +{}", self.oom_unseen - 1)
+                    } else {
+                        String::new()
+                    };
+                    format!("OOM: {}{more} [X]", o.victim_label())
+                }),
+            // The filter bar is hidden once applied; keep the expression visible
+            filter: (self.mode == AppMode::Normal && !self.active_filter.is_empty())
+                .then_some(self.active_filter.as_str()),
+            follow: self.follow_pid,
+            viewers: self.mirror.as_ref().map(Mirror::viewers),
+            hint: match self.mode {
+                AppMode::SortSelect => {
+                    let arrow = |ascending| if ascending { "^" } else { "v" };
+                    let then = match self.sort_then {
+                        Some((col, ascending)) => format!("{}{}", col.label(), arrow(ascending)),
+                        None => "-".to_string(),
+                    };
+                    // Brackets mark the key j/k changes
+                    let primary = format!("{}{}", self.sort_column.label(), arrow(self.sort_ascending));
+                    let (primary, then) = if self.sort_select_then {
+                        (primary, format!("[{then}]"))
+                    } else {
+                        (format!("[{primary}]"), then)
+                    };
+                    let focused = match (self.sort_select_then, self.sort_then) {
+                        (true, Some((col, _))) => col.description(),
+                        (true, None) => "no secondary key",
+                        (false, _) => self.sort_column.description(),
+                    };
+                    Some(format!(
+                        "Sort {primary} then {then}: {focused}  [j/k] change [Tab] primary/then [r] reverse [d] no then [Enter] done"
+                    ))
+                }
+                AppMode::Copy => Some(
+                    "COPY  [hjkl/w/b/0/$] move [v] select [V] lines [y] yank [Esc] cancel"
+                        .to_string(),
+                ),
+                AppMode::Normal | AppMode::Visual if screen_reader => self
+                    .filtered_processes
+                    .get(self.selected)
+                    .map(|p| describe_selection(p, &self.redact, self.selected, self.filtered_processes.len())),
+                _ => None,
+            },
         };
         frame.render_widget(status, status_area);
 
@@ -352,16 +871,15 @@ impl App {
                 frame.render_widget(help, area);
             }
             AppMode::Kill => {
-                let pids: Vec<u32> = if self.filtered_processes.iter().any(|p| p.tagged) {
-                    self.filtered_processes.iter().filter(|p| p.tagged).map(|p| p.pid).collect()
-                } else if let Some(proc) = self.filtered_processes.get(self.selected) {
-                    vec![proc.pid]
-                } else {
-                    vec![]
-                };
+                let pids = self.signal_targets();
+                let restart = self.kill_supervisor.as_ref().map(Supervisor::label);
                 if !pids.is_empty() {
+                    let cgroups = self.kill_cgroup_targets();
                     let kill = KillDialog {
                         pids: &pids,
+                        group: self.kill_group,
+                        cgroups: &cgroups,
+                        restart: restart.as_deref(),
                         pid_scroll: self.kill_pid_scroll,
                         selected_signal: self.kill_signal_idx,
                         theme: &self.theme,
@@ -375,74 +893,449 @@ impl App {
                         targets: &self.freeze_targets,
                         is_thaw: self.freeze_is_thaw,
                         scroll: self.freeze_scroll,
+                        thaw_after_secs: self.freeze_thaw_secs,
                         theme: &self.theme,
                     };
                     frame.render_widget(dialog, area);
                 }
             }
+            AppMode::Files => {
+                let panel = FilesPanel {
+                    pid: self.inspect_target,
+                    comm: self.inspected_comm(),
+                    files: self.inspected_files(),
+                    filter: &self.files_filter,
+                    filter_editing: self.files_filter_editing,
+                    theme: &self.theme,
+                };
+                let mut scroll = self.files_scroll;
+                frame.render_stateful_widget(panel, area, &mut scroll);
+                self.files_scroll = scroll;
+            }
+            AppMode::Diff => {
+                let view = DiffView {
+                    entries: &self.diff_entries,
+                    span: self.diff_span,
+                    theme: &self.theme,
+                };
+                frame.render_stateful_widget(view, area, &mut self.diff_scroll);
+            }
+            AppMode::Connections => {
+                let labels = self.netns_labels();
+                let comms: HashMap<u32, &str> = self
+                    .all_processes
+                    .iter()
+                    .map(|p| (p.pid, p.comm.as_str()))
+                    .collect();
+                let rows: Option<Vec<ConnRow>> = self.inspected_sockets().map(|socks| {
+                    socks
+                        .into_iter()
+                        .map(|file| ConnRow {
+                            file,
+                            comm: comms.get(&file.pid).copied().unwrap_or(""),
+                            netns: labels.get(&file.netns).map_or("?", String::as_str),
+                        })
+                        .collect()
+                });
+                let netns_filter = self
+                    .conn_netns
+                    .map(|ns| labels.get(&ns).cloned().unwrap_or_else(|| ns.to_string()));
+                let panel = ConnectionsPanel {
+                    pid: self.inspect_target,
+                    comm: self.inspected_comm(),
+                    rows: rows.as_deref(),
+                    all: self.conn_all,
+                    sort_rtt: self.conn_sort_rtt,
+                    netns_filter: netns_filter.as_deref(),
+                    selected: self.conn_selected,
+                    redact: &self.redact,
+                    theme: &self.theme,
+                };
+                frame.render_widget(panel, area);
+            }
+            AppMode::Detail => {
+                let view = DetailView {
+                    pid: self.inspect_target,
+                    process: self.all_processes.iter().find(|p| p.tid == self.inspect_target),
+                    threads: self.inspected_threads(),
+                    peers: self.inspected_peers(),
+                    dns_queries: self.inspected_dns_queries(),
+                    io_latency: self.inspected_io_latency(),
+                    mount_writes: self.inspected_mount_writes(),
+                    cgroup_writeback: self.inspected_cgroup_writeback(),
+                    off_cpu: self.inspected_off_cpu(),
+                    off_cpu_wanted: self.offcpu_wanted(),
+                    perf_wanted: self.perf_wanted.load(Ordering::Relaxed),
+                    perf_unavailable: self.inspected_perf_unavailable(),
+                    init_system: &self.init_system,
+                    uptime_secs: self.sys_info.uptime_secs,
+                    monotonic_secs: self.sys_info.monotonic_secs,
+                    redact: &self.redact,
+                    theme: &self.theme,
+                };
+                let mut scroll = self.detail_scroll;
+                frame.render_stateful_widget(view, area, &mut scroll);
+                self.detail_scroll = scroll;
+            }
+            AppMode::History => {
+                let process = self
+                    .all_processes
+                    .iter()
+                    .find(|p| p.tid == self.history_pid);
+                let (series, span_secs, unavailable) = match &self.history_long_term {
+                    Some(Ok((series, span_secs))) => (Some(series), *span_secs, None),
+                    Some(Err(reason)) => (None, 0, Some(reason.as_str())),
+                    None => {
+                        let series = process.and_then(|p| self.proc_history.get(p));
+                        let samples = series.map_or(0, |s| s.cpu.len() as u64);
+                        (series, samples * self.refresh_ms.load(Ordering::Relaxed) / 1000, None)
+                    }
+                };
+                let view = HistoryView {
+                    pid: self.history_pid,
+                    process,
+                    series,
+                    span_secs,
+                    long_term: self.history_long_term.is_some(),
+                    unavailable,
+                    theme: &self.theme,
+                };
+                frame.render_widget(view, area);
+            }
+            AppMode::BpfStats => {
+                let panel = BpfStatsPanel {
+                    stats: self.bpf_stats.as_ref(),
+                    theme: &self.theme,
+                };
+                frame.render_widget(panel, area);
+            }
+            AppMode::BpfObjects => {
+                let comms: HashMap<u32, String> = self
+                    .all_processes
+                    .iter()
+                    .filter(|p| !p.is_thread)
+                    .map(|p| (p.pid, p.comm.clone()))
+                    .collect();
+                let panel = BpfObjectsPanel {
+                    objects: self.bpf_objects.as_ref(),
+                    show_maps: self.bpf_objects_maps,
+                    comms: &comms,
+                    theme: &self.theme,
+                };
+                frame.render_stateful_widget(panel, area, &mut self.bpf_objects_scroll);
+            }
+            AppMode::Environ => {
+                let panel = EnvPanel {
+                    pid: self.env_pid,
+                    comm: &self.env_comm,
+                    vars: &self.env_vars,
+                    filter: &self.env_filter,
+                    filter_editing: self.env_filter_editing,
+                    redact: &self.redact,
+                    shared: self.mirror.is_some(),
+                    theme: &self.theme,
+                };
+                frame.render_stateful_widget(panel, area, &mut self.env_scroll);
+            }
+            AppMode::Runtime => {
+                if let Some(ref probe) = self.runtime_probe {
+                    let panel = RuntimePanel {
+                        probe,
+                        theme: &self.theme,
+                    };
+                    frame.render_stateful_widget(panel, area, &mut self.runtime_scroll);
+                }
+            }
+            AppMode::Sessions => {
+                let sessions = self.session_list();
+                let panel = SessionsPanel {
+                    sessions: &sessions,
+                    selected: self.sessions_selected,
+                    all: self.sessions_all,
+                    own_sid: self.own_sid,
+                    redact: &self.redact,
+                    theme: &self.theme,
+                };
+                frame.render_widget(panel, area);
+            }
+            AppMode::Cgroups => {
+                let rows = self.cgroup_rows();
+                let panel = CgroupsPanel {
+                    rows: &rows,
+                    selected: self.cgroups_selected,
+                    theme: &self.theme,
+                };
+                frame.render_widget(panel, area);
+            }
+            AppMode::Groups => {
+                let groups = self.group_list();
+                let rows = groups::rows(&groups, &self.groups_expanded);
+                let panel = GroupsPanel {
+                    by: self.group_by,
+                    groups: &groups,
+                    rows: &rows,
+                    expanded: &self.groups_expanded,
+                    selected: self.groups_selected,
+                    redact: &self.redact,
+                    theme: &self.theme,
+                };
+                frame.render_widget(panel, area);
+            }
+            AppMode::Execs => {
+                let live_pids: HashSet<u32> = self.all_processes.iter().map(|p| p.pid).collect();
+                let panel = ExecsPanel {
+                    execs: &self.exec_log,
+                    live_pids: &live_pids,
+                    uptime_secs: self.sys_info.uptime_secs,
+                    redact: &self.redact,
+                    theme: &self.theme,
+                };
+                frame.render_stateful_widget(panel, area, &mut self.execs_scroll);
+            }
+            AppMode::Ooms => {
+                let panel = OomPanel {
+                    ooms: &self.oom_log,
+                    uptime_secs: self.sys_info.uptime_secs,
+                    redact: &self.redact,
+                    theme: &self.theme,
+                };
+                frame.render_stateful_widget(panel, area, &mut self.oom_scroll);
+            }
+            AppMode::Setup => {
+                let setup = SetupScreen {
+                    pane: self.setup_pane,
+                    columns: &self.setup_columns,
+                    selected: self.setup_selected,
+                    config: &self.config,
+                    theme: &self.theme,
+                };
+                frame.render_widget(setup, area);
+            }
+            AppMode::Copy => {
+                // Everything above the status bar becomes the copy buffer
+                let copy_area = Rect {
+                    height: status_area.y.saturating_sub(area.y),
+                    ..area
+                };
+                if self.copy_buffer.is_empty() {
+                    let row = (table_area.y - copy_area.y) as usize
+                        + 1
+                        + self.selected.saturating_sub(self.scroll_offset);
+                    self.copy_buffer = CopyBuffer::capture(frame.buffer_mut(), copy_area, row);
+                }
+                let view = CopyView {
+                    buffer: &self.copy_buffer,
+                    theme: &self.theme,
+                };
+                frame.render_widget(view, copy_area);
+            }
             _ => {}
         }
+
+        if screen_reader {
+            strip_decoration(frame.buffer_mut());
+            // Screen readers follow the cursor; park it on the line that
+            // describes the selection
+            if matches!(self.mode, AppMode::Normal | AppMode::Visual) {
+                frame.set_cursor_position((status_area.x, status_area.y));
+            }
+        }
     }
 
     /// Resolve service names on all processes based on current display mode.
     pub fn resolve_services(&mut self) {
         let mode = self.service_display_mode;
+        let supervised = self
+            .init_system
+            .supervised(&self.all_processes.iter().collect::<Vec<_>>());
         for p in &mut self.all_processes {
-            p.service = resolve_service_from_path(&p.cgroup_path, mode);
+            p.service = self.init_system.service(p, &supervised, mode);
         }
         for p in &mut self.filtered_processes {
-            p.service = resolve_service_from_path(&p.cgroup_path, mode);
+            p.service = self.init_system.service(p, &supervised, mode);
         }
     }
 
-    fn merge_data(&mut self, sys_info: SystemInfo, processes: Vec<ProcessInfo>) {
+    pub fn merge_data(&mut self, snapshot: Snapshot) {
+        let Snapshot {
+            sys: sys_info,
+            processes,
+            inspection,
+            alerts,
+            execs,
+            exits,
+            ooms,
+            bpf_stats,
+            bpf_objects,
+        } = snapshot;
+        self.sys_history.push(&sys_info, &processes);
+        self.proc_history.push(&processes);
         self.sys_info = sys_info;
+        self.inspection = inspection;
+        self.bpf_stats = bpf_stats;
+        if bpf_objects.is_some() {
+            self.bpf_objects = bpf_objects;
+        }
+        if let Some(alert) = alerts.last() {
+            self.flash(alert.message.clone());
+        }
+        // Keep the scroll position anchored while new execs arrive at the top
+        if self.mode == AppMode::Execs {
+            self.execs_scroll.hold(execs.len());
+        }
+        self.exec_log.extend(execs);
+        while self.exec_log.len() > EXEC_LOG_CAPACITY {
+            self.exec_log.pop_front();
+        }
+        if self.mode == AppMode::Ooms {
+            self.oom_scroll.hold(ooms.len());
+        } else {
+            self.oom_unseen += ooms.len();
+        }
+        self.oom_log.extend(ooms);
+        while self.oom_log.len() > OOM_LOG_CAPACITY {
+            self.oom_log.pop_front();
+        }
 
-        let new_map: HashMap<u32, ProcessInfo> =
-            processes.into_iter().map(|p| (p.pid, p)).collect();
-        let old_pids: HashSet<u32> = self.all_processes.iter().map(|p| p.pid).collect();
-        let new_pids: HashSet<u32> = new_map.keys().copied().collect();
-        let structure_changed = old_pids != new_pids;
+        // Keyed by tid, which tells thread rows apart from their process
+        let mut new_map: HashMap<u32, ProcessInfo> =
+            processes.into_iter().map(|p| (p.tid, p)).collect();
+        self.keep_dying_processes(&mut new_map, exits);
+        self.mark_idle(&mut new_map);
+        let old_tids: HashSet<u32> = self.all_processes.iter().map(|p| p.tid).collect();
+        let new_tids: HashSet<u32> = new_map.keys().copied().collect();
+        let structure_changed = old_tids != new_tids;
+        let mode = self.service_display_mode;
+        let supervised = self
+            .init_system
+            .supervised(&new_map.values().collect::<Vec<_>>());
 
         if structure_changed {
-            // PIDs appeared or disappeared — full rebuild required
-            let tagged_pids: HashSet<u32> = self
+            // Rows appeared or disappeared — full rebuild required
+            let tagged_tids: HashSet<u32> = self
                 .all_processes
                 .iter()
                 .chain(self.filtered_processes.iter())
                 .filter(|p| p.tagged)
-                .map(|p| p.pid)
+                .map(|p| p.tid)
                 .collect();
 
             self.all_processes = new_map.into_values().collect();
-            let mode = self.service_display_mode;
+            self.thread_parents = self
+                .all_processes
+                .iter()
+                .filter(|p| p.is_thread)
+                .map(|p| p.pid)
+                .collect();
             for p in &mut self.all_processes {
-                if tagged_pids.contains(&p.pid) {
+                if tagged_tids.contains(&p.tid) {
                     p.tagged = true;
                 }
-                p.service = resolve_service_from_path(&p.cgroup_path, mode);
+                p.service = self.init_system.service(p, &supervised, mode);
+                p.badge = self.badges.classify(p);
             }
             self.update_filtered_processes();
         } else {
             // Same PIDs — update values in-place, no re-sort, no tree rebuild
-            let mode = self.service_display_mode;
             for p in &mut self.all_processes {
-                if let Some(np) = new_map.get(&p.pid) {
+                if let Some(np) = new_map.get(&p.tid) {
                     p.update_dynamic_fields(np);
-                    p.service = resolve_service_from_path(&np.cgroup_path, mode);
+                    p.service = self.init_system.service(np, &supervised, mode);
+                    p.badge = self.badges.classify(p);
                 }
             }
             for p in &mut self.filtered_processes {
-                if let Some(np) = new_map.get(&p.pid) {
+                if let Some(np) = new_map.get(&p.tid) {
                     p.update_dynamic_fields(np);
-                    p.service = resolve_service_from_path(&np.cgroup_path, mode);
+                    p.service = self.init_system.service(np, &supervised, mode);
+                    p.badge = self.badges.classify(p);
                 }
             }
         }
         self.dirty = true;
     }
 
+    /// Update quiet streaks and flag processes idle for long enough.
+    fn mark_idle(&mut self, new_map: &mut HashMap<u32, ProcessInfo>) {
+        let threshold = self.config.general.idle_dim_refreshes;
+        self.quiet_streak.retain(|tid, _| new_map.contains_key(tid));
+        for p in new_map.values_mut() {
+            let streak = self.quiet_streak.entry(p.tid).or_insert(0);
+            *streak = if p.is_quiet() { streak.saturating_add(1) } else { 0 };
+            p.idle = threshold > 0 && *streak >= threshold && p.exited.is_none();
+        }
+    }
+
+    /// Carry rows whose process just exited into `new_map` for a few
+    /// refreshes, marked with their exit status. The exit event can arrive
+    /// before the task disappears (zombie until reaped), so unmatched
+    /// records wait in `pending_exits` while their PID is still listed.
+    fn keep_dying_processes(&mut self, new_map: &mut HashMap<u32, ProcessInfo>, exits: Vec<ExitRecord>) {
+        for e in exits {
+            self.pending_exits.insert(e.pid, e);
+        }
+
+        let mut dying = HashMap::new();
+        // Exit records are per process; threads just disappear
+        for p in self.all_processes.iter().filter(|p| !p.is_thread) {
+            if new_map.contains_key(&p.pid) {
+                continue;
+            }
+            let (record, left) = match (&p.exited, self.pending_exits.remove(&p.pid)) {
+                (Some(record), _) => (record.clone(), self.dying.get(&p.pid).copied().unwrap_or(0)),
+                (None, Some(record)) => (record, DYING_REFRESHES),
+                (None, None) => continue,
+            };
+            if left == 0 {
+                continue;
+            }
+            let mut ghost = p.clone();
+            ghost.state = ProcessState::Dead;
+            ghost.cpu_percent = 0.0;
+            ghost.net_rate = 0.0;
+            ghost.net_tx_rate = 0.0;
+            ghost.net_rx_rate = 0.0;
+            ghost.disk_read_rate = 0.0;
+            ghost.disk_write_rate = 0.0;
+            ghost.sock_drop_rate = 0.0;
+            ghost.rss_growth = 0.0;
+            ghost.exited = Some(record);
+            dying.insert(p.pid, left - 1);
+            new_map.insert(p.pid, ghost);
+        }
+        self.dying = dying;
+        // Short-lived processes never made it into the table; drop their exits
+        self.pending_exits.retain(|pid, _| new_map.contains_key(pid));
+    }
+
+    /// Whether the CONT, NAMESPACE/POD and UNIT columns are shown. They
+    /// appear once any listed process has a container, pod or unit, except
+    /// for screen readers, where columns shifting under the cursor would be
+    /// confusing.
+    pub fn optional_columns(&self) -> (bool, bool, bool) {
+        if self.config.general.screen_reader {
+            return (false, false, false);
+        }
+        (
+            self.filtered_processes.iter().any(|p| p.container.is_some()),
+            self.filtered_processes.iter().any(|p| p.pod.is_some()),
+            self.filtered_processes.iter().any(|p| p.service.is_some()),
+        )
+    }
+
     pub fn update_filtered_processes(&mut self) {
+        // A half-typed regex or comparison is common while filtering; show
+        // why and keep everything visible until it parses
+        let filter = match ProcessFilter::parse(&self.active_filter) {
+            Ok(filter) => {
+                self.filter_error = None;
+                filter
+            }
+            Err(e) => {
+                self.filter_error = Some(e);
+                ProcessFilter::default()
+            }
+        };
         let mut procs: Vec<ProcessInfo> = self
             .all_processes
             .iter()
@@ -462,16 +1355,22 @@ impl App {
                     }
                 }
                 // Text filter
-                matches_filter(p, &self.active_filter)
+                matches_filter(p, &filter)
             })
             .cloned()
             .collect();
 
         if self.tree_view {
-            let tree = tree_view::build_tree(&procs, &self.collapsed_pids);
+            let tree = tree_view::build_tree(
+                &procs,
+                &self.collapsed_pids,
+                self.config.general.group_orphans,
+            );
             procs = tree_view::tree_ordered_processes(&procs, &tree);
         } else {
-            procs.sort_by(|a, b| compare_processes(a, b, self.sort_column, self.sort_ascending));
+            let mut keys = vec![(self.sort_column, self.sort_ascending)];
+            keys.extend(self.sort_then);
+            procs.sort_by(|a, b| compare_processes_by(a, b, &keys));
         }
 
         self.filtered_processes = procs;
@@ -482,6 +1381,16 @@ impl App {
         } else {
             self.selected = 0;
         }
+        if let Some(pid) = self.follow_pid {
+            if let Some(pos) = self.filtered_processes.iter().position(|p| p.tid == pid) {
+                self.selected = pos;
+                self.adjust_scroll();
+                // Stop following once it is gone
+                if self.filtered_processes[pos].exited.is_some() {
+                    self.follow_pid = None;
+                }
+            }
+        }
 
         // Clamp scroll offset
         let max_offset = if self.visible_rows > 0 {
@@ -492,7 +1401,68 @@ impl App {
         self.scroll_offset = self.scroll_offset.min(max_offset);
     }
 
+    /// Keep the selection on the selected process as the order changes
+    /// (`F`), or stop.
+    pub fn toggle_follow(&mut self) {
+        if let Some(pid) = self.follow_pid.take() {
+            self.flash(format!("Stopped following PID {pid}"));
+            return;
+        }
+        let Some(proc) = self.filtered_processes.get(self.selected) else {
+            return;
+        };
+        if proc.exited.is_some() {
+            self.flash(format!("PID {} has exited", proc.tid));
+            return;
+        }
+        self.follow_pid = Some(proc.tid);
+        self.flash(format!("Following PID {}", proc.tid));
+    }
+
+    /// Cycle the Command column between the full command line, the line
+    /// with the program's directory stripped, and comm (`p`).
+    pub fn cycle_command_display(&mut self) {
+        self.command_display = self.command_display.next();
+        self.flash(format!("Command column: {}", self.command_display.label()));
+    }
+
+    // --- Sort picker ---
+
+    /// Move the key the sort picker is editing `delta` columns along.
+    pub fn step_sort_key(&mut self, delta: isize) {
+        let cols = SortColumn::all();
+        let step = |col: SortColumn| {
+            let pos = cols.iter().position(|c| *c == col).unwrap_or(0);
+            cols[pos.saturating_add_signed(delta).min(cols.len() - 1)]
+        };
+        if self.sort_select_then {
+            self.sort_then = match self.sort_then {
+                Some((col, ascending)) => Some((step(col), ascending)),
+                None => Some((step(self.sort_column), false)),
+            };
+        } else {
+            self.sort_column = step(self.sort_column);
+        }
+        self.update_filtered_processes();
+    }
+
+    /// Flip the direction of the key the sort picker is editing.
+    pub fn reverse_sort_key(&mut self) {
+        match (self.sort_select_then, &mut self.sort_then) {
+            (true, Some((_, ascending))) => *ascending = !*ascending,
+            (true, None) => return,
+            (false, _) => self.sort_ascending = !self.sort_ascending,
+        }
+        self.update_filtered_processes();
+    }
+
+    pub fn clear_sort_then(&mut self) {
+        self.sort_then = None;
+        self.update_filtered_processes();
+    }
+
     pub fn move_selection(&mut self, delta: i32) {
+        self.follow_pid = None;
         if self.filtered_processes.is_empty() {
             return;
         }
@@ -501,12 +1471,49 @@ impl App {
         self.adjust_scroll();
     }
 
+    /// Start a scrollbar drag at `row` of the track. A click on the thumb
+    /// holds it where it was caught; one elsewhere centres it on the pointer.
+    pub fn grab_scrollbar(&mut self, row: usize) {
+        let total = self.filtered_processes.len();
+        let Some((start, len)) =
+            process_table::scrollbar_thumb(total, self.visible_rows, self.scroll_offset, self.visible_rows)
+        else {
+            return;
+        };
+        let grab = if (start..start + len).contains(&row) { row - start } else { len / 2 };
+        self.scrollbar_drag = Some(grab);
+        self.scroll_to_track_row(row);
+    }
+
+    /// Scroll so the grabbed row of the scrollbar thumb sits at `row` of the
+    /// track, dragging the selection along when it would leave the screen.
+    pub fn scroll_to_track_row(&mut self, row: usize) {
+        let Some(grab) = self.scrollbar_drag else {
+            return;
+        };
+        self.follow_pid = None;
+        let total = self.filtered_processes.len();
+        if total == 0 || self.visible_rows == 0 {
+            return;
+        }
+        self.scroll_offset = process_table::scroll_offset_at(
+            row.saturating_sub(grab),
+            total,
+            self.visible_rows,
+            self.visible_rows,
+        );
+        let last_visible = (self.scroll_offset + self.visible_rows - 1).min(total - 1);
+        self.selected = self.selected.clamp(self.scroll_offset, last_visible);
+    }
+
     pub fn select_first(&mut self) {
+        self.follow_pid = None;
         self.selected = 0;
         self.adjust_scroll();
     }
 
     pub fn select_last(&mut self) {
+        self.follow_pid = None;
         if !self.filtered_processes.is_empty() {
             self.selected = self.filtered_processes.len() - 1;
         }
@@ -517,9 +1524,9 @@ impl App {
         if let Some(proc) = self.filtered_processes.get_mut(self.selected) {
             proc.tagged = !proc.tagged;
             // Also toggle in all_processes
-            let pid = proc.pid;
+            let tid = proc.tid;
             let tagged = proc.tagged;
-            if let Some(ap) = self.all_processes.iter_mut().find(|p| p.pid == pid) {
+            if let Some(ap) = self.all_processes.iter_mut().find(|p| p.tid == tid) {
                 ap.tagged = tagged;
             }
         }
@@ -584,9 +1591,42 @@ impl App {
         }
     }
 
+    /// Bookmark the selected process as `name` (`m{a-z}`).
+    pub fn set_mark(&mut self, name: char) {
+        let Some(proc) = self.filtered_processes.get(self.selected) else {
+            return;
+        };
+        let mark = Mark {
+            pid: proc.tid,
+            start_time_ns: proc.start_time_ns,
+        };
+        self.flash(format!("Mark '{name}' set on PID {}", mark.pid));
+        self.marks.set(name, mark);
+    }
+
+    /// Select the process marked `name` (`'{a-z}`), as a jump.
+    pub fn jump_to_mark(&mut self, name: char) {
+        let Some(mark) = self.marks.get(name) else {
+            return self.flash(format!("Mark '{name}' is not set"));
+        };
+        let is_marked = |p: &ProcessInfo| p.tid == mark.pid && p.start_time_ns == mark.start_time_ns;
+        if let Some(pos) = self.filtered_processes.iter().position(is_marked) {
+            self.push_jump_mark();
+            self.follow_pid = None;
+            self.selected = pos;
+            self.adjust_scroll();
+        } else if self.all_processes.iter().any(is_marked) {
+            self.flash(format!("Mark '{name}': PID {} is filtered out", mark.pid));
+        } else {
+            self.marks.remove(name);
+            self.flash(format!("Mark '{name}': PID {} has exited", mark.pid));
+        }
+    }
+
     fn navigate_to_jump_pid(&mut self) {
         if let Some(&pid) = self.jump_list.get(self.jump_pos) {
-            if let Some(pos) = self.filtered_processes.iter().position(|p| p.pid == pid) {
+            if let Some(pos) = self.filtered_processes.iter().position(|p| p.tid == pid) {
+                self.follow_pid = None;
                 self.selected = pos;
                 self.adjust_scroll();
             }
@@ -605,30 +1645,60 @@ impl App {
 
     /// Tag all processes in the visual range (both filtered and all_processes).
     pub fn tag_visual_range(&mut self) {
+        self.set_visual_range_tagged(true);
+    }
+
+    pub fn untag_visual_range(&mut self) {
+        self.set_visual_range_tagged(false);
+    }
+
+    fn set_visual_range_tagged(&mut self, tagged: bool) {
         if let Some((lo, hi)) = self.visual_range() {
             for i in lo..=hi {
                 if let Some(proc) = self.filtered_processes.get_mut(i) {
-                    proc.tagged = true;
-                    let pid = proc.pid;
-                    if let Some(ap) = self.all_processes.iter_mut().find(|p| p.pid == pid) {
-                        ap.tagged = true;
+                    proc.tagged = tagged;
+                    let tid = proc.tid;
+                    if let Some(ap) = self.all_processes.iter_mut().find(|p| p.tid == tid) {
+                        ap.tagged = tagged;
                     }
                 }
             }
         }
     }
 
+    /// PIDs of the rows in the visual range.
+    fn visual_pids(&self) -> Vec<u32> {
+        let Some((lo, hi)) = self.visual_range() else {
+            return Vec::new();
+        };
+        self.filtered_processes
+            .iter()
+            .skip(lo)
+            .take(hi - lo + 1)
+            .map(|p| p.pid)
+            .collect()
+    }
+
     pub fn expand_tree_node(&mut self) {
         if let Some(proc) = self.filtered_processes.get(self.selected) {
-            self.collapsed_pids.remove(&proc.pid);
+            self.collapsed_pids.remove(&proc.tid);
             self.update_filtered_processes();
         }
     }
 
+    /// Whether a row has anything under it in tree view: child processes,
+    /// or threads while they're shown.
+    fn has_tree_children(&self, proc: &ProcessInfo) -> bool {
+        !proc.children.is_empty()
+            || (self.show_threads
+                && !proc.is_thread
+                && self.thread_parents.contains(&proc.pid))
+    }
+
     pub fn collapse_tree_node(&mut self) {
         if let Some(proc) = self.filtered_processes.get(self.selected) {
-            if !proc.children.is_empty() {
-                self.collapsed_pids.insert(proc.pid);
+            if self.has_tree_children(proc) {
+                self.collapsed_pids.insert(proc.tid);
                 self.update_filtered_processes();
             }
         }
@@ -636,8 +1706,8 @@ impl App {
 
     pub fn toggle_tree_node(&mut self) {
         if let Some(proc) = self.filtered_processes.get(self.selected) {
-            let pid = proc.pid;
-            let has_children = !proc.children.is_empty();
+            let pid = proc.tid;
+            let has_children = self.has_tree_children(proc);
             if self.collapsed_pids.contains(&pid) {
                 self.collapsed_pids.remove(&pid);
                 self.update_filtered_processes();
@@ -662,8 +1732,8 @@ impl App {
     pub fn collapse_tree_recursive(&mut self) {
         if let Some(proc) = self.filtered_processes.get(self.selected) {
             let pid = proc.pid;
-            if !proc.children.is_empty() {
-                self.collapsed_pids.insert(pid);
+            if self.has_tree_children(proc) {
+                self.collapsed_pids.insert(proc.tid);
             }
             for anc in self.ancestor_pids(pid) {
                 self.collapsed_pids.insert(anc);
@@ -678,10 +1748,10 @@ impl App {
     }
 
     pub fn collapse_all_tree(&mut self) {
-        let parent_pids: Vec<u32> = self
+        let parent_pids: HashSet<u32> = self
             .all_processes
             .iter()
-            .filter(|p| !p.children.is_empty())
+            .filter(|p| !p.children.is_empty() || (self.show_threads && p.is_thread))
             .map(|p| p.pid)
             .collect();
         for pid in parent_pids {
@@ -694,6 +1764,7 @@ impl App {
         let child_map: HashMap<u32, &Vec<u32>> = self
             .all_processes
             .iter()
+            .filter(|p| !p.is_thread)
             .map(|p| (p.pid, &p.children))
             .collect();
         let mut result = Vec::new();
@@ -713,6 +1784,7 @@ impl App {
         let ppid_map: HashMap<u32, u32> = self
             .all_processes
             .iter()
+            .filter(|p| !p.is_thread)
             .map(|p| (p.pid, p.ppid))
             .collect();
         let mut result = Vec::new();
@@ -730,12 +1802,12 @@ impl App {
     // --- Flash message ---
 
     pub fn flash(&mut self, msg: String) {
-        self.flash_message = Some((msg, Instant::now()));
+        self.flash_message = Some((msg, self.clock.now()));
     }
 
     pub fn active_flash(&self) -> Option<&str> {
         if let Some((ref msg, when)) = self.flash_message {
-            if when.elapsed() < Duration::from_secs(2) {
+            if self.clock.now().duration_since(when) < Duration::from_secs(2) {
                 return Some(msg.as_str());
             }
         }
@@ -764,7 +1836,7 @@ impl App {
         }
 
         let joined = texts.join("\n");
-        if crate::clipboard::yank(&joined).is_err() {
+        if crate::clipboard::yank(&joined, self.config.general.clipboard).is_err() {
             return Some("Clipboard write failed".to_string());
         }
 
@@ -832,74 +1904,1104 @@ impl App {
                     format!("Yanked {} GMEM", texts.len())
                 }
             }
+            _ => {
+                if texts.len() == 1 {
+                    format!("Yanked {} {}", field.label(), texts[0])
+                } else {
+                    format!("Yanked {} {} values", texts.len(), field.label())
+                }
+            }
         };
         Some(desc)
     }
 
-    #[allow(dead_code)]
-    pub fn cycle_user_filter(&mut self) {
-        if self.user_filter.is_some() {
-            self.user_filter = None;
-        } else if let Some(proc) = self.filtered_processes.get(self.selected) {
-            self.user_filter = Some(proc.user.clone());
-        }
-        self.update_filtered_processes();
+    // --- Setup screen ---
+
+    /// Open the setup screen on its first pane. Runtime toggles (tree view,
+    /// thread visibility) are copied into the config so they show there.
+    pub fn open_setup(&mut self) {
+        self.config.general.tree_view = self.tree_view;
+        self.config.general.show_threads = self.show_threads;
+        self.config.general.show_kernel_threads = self.show_kernel_threads;
+        self.config.general.compact = self.compact;
+        self.config.columns.command = self.command_display;
+        self.setup_original = Some(Box::new(self.config.clone()));
+
+        let shown = &self.config.columns.show;
+        self.setup_columns = shown.iter().map(|&c| (c, true)).collect();
+        self.setup_columns.extend(
+            SortColumn::all()
+                .iter()
+                .filter(|c| !shown.contains(c))
+                .map(|&c| (c, false)),
+        );
+        self.setup_pane = SetupPane::default();
+        self.setup_selected = 0;
+        self.mode = AppMode::Setup;
     }
 
-    pub fn send_signal(&self, signal: i32) {
-        let pids: Vec<u32> = if self.filtered_processes.iter().any(|p| p.tagged) {
-            self.filtered_processes
-                .iter()
-                .filter(|p| p.tagged)
-                .map(|p| p.pid)
-                .collect()
-        } else if let Some(proc) = self.filtered_processes.get(self.selected) {
-            vec![proc.pid]
-        } else {
-            return;
-        };
+    /// Number of selectable rows on the open pane.
+    pub fn setup_len(&self) -> usize {
+        match self.setup_pane {
+            SetupPane::Columns => self.setup_columns.len(),
+            SetupPane::Theme => Theme::PRESETS.len(),
+            pane => pane.settings().len(),
+        }
+    }
 
-        let sig = match nix::sys::signal::Signal::try_from(signal) {
-            Ok(s) => s,
-            Err(_) => return,
-        };
+    /// Switch to the next (+1) or previous (-1) pane.
+    pub fn setup_switch_pane(&mut self, delta: isize) {
+        self.setup_pane = self.setup_pane.cycle(delta);
+        self.setup_selected = 0;
+    }
 
-        for pid in pids {
-            let _ = nix::sys::signal::kill(
-                nix::unistd::Pid::from_raw(pid as i32),
-                sig,
-            );
+    /// Space/Enter: show/hide a column, pick a preset, or flip a setting.
+    pub fn setup_activate(&mut self) {
+        match self.setup_pane {
+            SetupPane::Columns => {
+                if let Some(entry) = self.setup_columns.get_mut(self.setup_selected) {
+                    entry.1 = !entry.1;
+                }
+            }
+            SetupPane::Theme => {
+                if let Some(name) = Theme::PRESETS.get(self.setup_selected) {
+                    self.config.theme.preset = name.to_string();
+                }
+            }
+            _ => return self.setup_adjust(1),
         }
+        self.apply_setup();
     }
 
-    // --- Freeze/thaw ---
+    /// Step the selected setting up (+1) or down (-1).
+    pub fn setup_adjust(&mut self, delta: i32) {
+        if let Some(setting) = self.setup_pane.settings().get(self.setup_selected) {
+            setting.adjust(&mut self.config, delta);
+            self.apply_setup();
+        }
+    }
 
-    /// Gather selected/tagged processes, group by cgroup, populate freeze_targets, enter Freeze mode.
-    pub fn prepare_freeze(&mut self) {
-        let procs = self.target_processes();
-        if procs.is_empty() {
+    /// Move the selected column up (-1) or down (+1) in the order.
+    pub fn setup_move(&mut self, delta: isize) {
+        if self.setup_pane != SetupPane::Columns {
             return;
         }
-
-        let targets = self.build_freeze_targets(&procs);
-        if targets.is_empty() {
-            self.flash("No freezable cgroups found".to_string());
+        let from = self.setup_selected;
+        let Some(to) = from.checked_add_signed(delta) else {
             return;
+        };
+        if to < self.setup_columns.len() {
+            self.setup_columns.swap(from, to);
+            self.setup_selected = to;
+            self.apply_setup();
         }
-
-        self.freeze_targets = targets;
-        self.freeze_is_thaw = false;
-        self.freeze_scroll = 0;
-        self.pre_freeze_mode = self.mode;
-        self.mode = AppMode::Freeze;
     }
 
-    /// Gather selected/tagged processes, group by cgroup (only frozen ones), enter Freeze mode for thaw.
-    pub fn prepare_thaw(&mut self) {
-        let procs = self.target_processes();
-        if procs.is_empty() {
-            return;
-        }
+    /// Push the edited config into the running app, so changes show behind
+    /// the setup screen as they are made.
+    fn apply_setup(&mut self) {
+        self.config.columns.show = self
+            .setup_columns
+            .iter()
+            .filter(|(_, on)| *on)
+            .map(|(c, _)| *c)
+            .collect();
+        self.apply_config();
+    }
+
+    /// Re-derive runtime state from `self.config`.
+    fn apply_config(&mut self) {
+        let general = &self.config.general;
+        self.refresh_ms
+            .store(general.refresh_rate_ms, Ordering::Relaxed);
+        self.tree_view = general.tree_view;
+        self.show_threads = general.show_threads;
+        self.show_kernel_threads = general.show_kernel_threads;
+        self.compact = general.compact;
+        self.command_display = self.config.columns.command;
+        self.sync_probes();
+        self.theme = Theme::from_config(&self.config.theme.preset, &self.config.theme.overrides);
+        self.update_filtered_processes();
+    }
+
+    /// Close the setup screen, writing the config file.
+    pub fn save_setup(&mut self) {
+        self.mode = AppMode::Normal;
+        self.setup_original = None;
+        match self.config.save() {
+            Ok(()) => self.flash("Settings saved to config".to_string()),
+            Err(e) => self.flash(format!("Saving config failed: {e:#}")),
+        }
+    }
+
+    /// Close the setup screen and undo everything changed in it.
+    pub fn cancel_setup(&mut self) {
+        if let Some(original) = self.setup_original.take() {
+            self.config = *original;
+            self.apply_config();
+        }
+        self.mode = AppMode::Normal;
+    }
+
+    /// Enter copy mode. The screen is captured on the next draw.
+    pub fn enter_copy_mode(&mut self) {
+        self.copy_buffer = CopyBuffer::default();
+        self.mode = AppMode::Copy;
+    }
+
+    pub fn exit_copy_mode(&mut self) {
+        self.copy_buffer = CopyBuffer::default();
+        self.mode = AppMode::Normal;
+    }
+
+    /// Yank the copy-mode selection (or the cursor line) and leave copy mode.
+    pub fn yank_copy_selection(&mut self) {
+        let text = self.copy_buffer.selected_text();
+        let lines = text.lines().count().max(1);
+        let msg = if crate::clipboard::yank(&text, self.config.general.clipboard).is_err() {
+            "Clipboard write failed".to_string()
+        } else if lines == 1 {
+            format!("Yanked {} chars", text.chars().count())
+        } else {
+            format!("Yanked {lines} lines")
+        };
+        self.flash(msg);
+        self.exit_copy_mode();
+    }
+
+    #[allow(dead_code)]
+    pub fn cycle_user_filter(&mut self) {
+        if self.user_filter.is_some() {
+            self.user_filter = None;
+        } else if let Some(proc) = self.filtered_processes.get(self.selected) {
+            self.user_filter = Some(proc.user.clone());
+        }
+        self.update_filtered_processes();
+    }
+
+    /// PIDs the kill dialog will signal: tagged processes or the selected
+    /// one, or their distinct process group IDs when `kill_group` is set.
+    pub fn signal_targets(&self) -> Vec<u32> {
+        let procs: Vec<&ProcessInfo> = if self.filtered_processes.iter().any(|p| p.tagged) {
+            self.filtered_processes.iter().filter(|p| p.tagged).collect()
+        } else {
+            self.filtered_processes.get(self.selected).into_iter().collect()
+        };
+        if !self.kill_group {
+            return procs.iter().map(|p| p.pid).collect();
+        }
+        let mut pgids: Vec<u32> = procs.iter().map(|p| p.pgid).filter(|&g| g != 0).collect();
+        pgids.sort_unstable();
+        pgids.dedup();
+        pgids
+    }
+
+    /// Supervisor of the selected process, unless several are tagged.
+    pub fn selected_supervisor(&self) -> Option<Supervisor> {
+        if self.filtered_processes.iter().any(|p| p.tagged) {
+            return None;
+        }
+        let proc = self.filtered_processes.get(self.selected)?;
+        Supervisor::detect(proc, &self.all_processes)
+    }
+
+    /// Restart the kill dialog's target through its supervisor.
+    pub fn restart_supervised(&mut self) {
+        let Some(sup) = self.kill_supervisor.take() else {
+            return;
+        };
+        self.flash(format!("Restarting via {}", sup.label()));
+        self.restarts.push(sup.restart());
+    }
+
+    /// Report supervisor restarts that have finished.
+    fn poll_restarts(&mut self) {
+        let mut done = Vec::new();
+        self.restarts.retain(|restart| match restart.poll() {
+            None => true,
+            Some(Ok(())) => {
+                done.push(format!("Restarted via {}", restart.label));
+                false
+            }
+            Some(Err(e)) => {
+                done.push(format!("Restart via {} failed: {e}", restart.label));
+                false
+            }
+        });
+        for msg in done {
+            self.flash(msg);
+        }
+    }
+
+    /// Pin the kill dialog's processes with pidfds as it opens, so
+    /// confirming can't signal whatever reuses one of their PIDs while the
+    /// dialog is up.
+    pub fn pin_kill_targets(&mut self) {
+        let procs: Vec<&ProcessInfo> = if self.filtered_processes.iter().any(|p| p.tagged) {
+            self.filtered_processes.iter().filter(|p| p.tagged).collect()
+        } else {
+            self.filtered_processes.get(self.selected).into_iter().collect()
+        };
+        self.kill_pinned.clear();
+        self.kill_gone.clear();
+        let mut pinned = HashSet::new();
+        for p in procs {
+            let Some((pid, start_time_ns)) = kill_target(p, &self.all_processes) else {
+                self.kill_gone.push(p.pid);
+                continue;
+            };
+            // Tagged threads of one process pin it once
+            if !pinned.insert(pid) {
+                continue;
+            }
+            match PidFd::open(pid, start_time_ns) {
+                Ok(pidfd) => self.kill_pinned.push(pidfd),
+                Err(_) => self.kill_gone.push(pid),
+            }
+        }
+    }
+
+    pub fn send_signal(&mut self, signal: i32) {
+        let mut targets = self.signal_targets();
+
+        let sig = match nix::sys::signal::Signal::try_from(signal) {
+            Ok(s) => s,
+            Err(_) => return,
+        };
+
+        if !self.kill_group {
+            for pidfd in self.kill_pinned.drain(..) {
+                let _ = pidfd.send_signal(signal);
+            }
+            match std::mem::take(&mut self.kill_gone).as_slice() {
+                [] => {}
+                [pid] => self.flash(format!("PID {pid} exited or was reused; not signalled")),
+                gone => self.flash(format!("{} PIDs exited or were reused; not signalled", gone.len())),
+            }
+            return;
+        }
+
+        // Signalling our own group would take bpftop (and often the shell
+        // that launched it) down with the target
+        let own = nix::unistd::getpgrp().as_raw() as u32;
+        let skipped_own = targets.contains(&own);
+        targets.retain(|&g| g != own);
+
+        // Negative PID = every process in the group
+        let failed = targets
+            .iter()
+            .filter(|&&pgid| {
+                nix::sys::signal::killpg(nix::unistd::Pid::from_raw(pgid as i32), sig).is_err()
+            })
+            .count();
+        let mut msg = format!("Sent {sig} to {} process group(s)", targets.len() - failed);
+        if failed > 0 {
+            msg.push_str(&format!(", {failed} failed"));
+        }
+        if skipped_own {
+            msg.push_str(&format!(" (skipped bpftop's own group {own})"));
+        }
+        self.flash(msg);
+    }
+
+    /// Distinct cgroups of the kill dialog's targets that `c` would
+    /// cgroup.kill: never the root, nor one bpftop runs in or under.
+    pub fn kill_cgroup_targets(&self) -> Vec<String> {
+        let mut cgroups: Vec<String> = self
+            .target_processes()
+            .into_iter()
+            .map(|(_, cgroup)| cgroup)
+            .filter(|cgroup| !cgroup.is_empty() && !is_root_cgroup(cgroup))
+            .filter(|cgroup| {
+                self.own_cgroup.as_deref().is_none_or(|own| {
+                    own != cgroup && !own.starts_with(&format!("{cgroup}/"))
+                })
+            })
+            .collect();
+        cgroups.sort_unstable();
+        cgroups.dedup();
+        cgroups
+    }
+
+    /// SIGKILL everything in the targets' cgroups at once via cgroup.kill,
+    /// so a service or container can't respawn children mid-kill.
+    pub fn kill_cgroups(&mut self) {
+        let cgroups = self.kill_cgroup_targets();
+        if cgroups.is_empty() {
+            self.flash("No cgroup to kill (root cgroup, or bpftop's own)".to_string());
+            return;
+        }
+        let mut killed = 0;
+        let mut error = None;
+        for cgroup in &cgroups {
+            match cgroup_control::kill_cgroup(cgroup) {
+                Ok(()) => killed += 1,
+                Err(e) => error = Some(format!("{cgroup}: {e}")),
+            }
+        }
+        let msg = match (killed, error) {
+            (_, None) if cgroups.len() == 1 => format!("Killed cgroup {}", cgroups[0]),
+            (n, None) => format!("Killed {n} cgroups"),
+            (0, Some(e)) => format!("cgroup.kill failed: {e}"),
+            (n, Some(e)) => format!("Killed {n} cgroups, failed {e}"),
+        };
+        self.flash(msg);
+    }
+
+    // --- Renice ---
+
+    /// Renice tagged processes (or the selected one) by `delta`.
+    pub fn renice(&mut self, delta: i32) {
+        let pids: Vec<u32> = self.target_processes().into_iter().map(|(pid, _)| pid).collect();
+        self.renice_pids(&pids, delta);
+    }
+
+    /// Renice every process in the visual range by `delta`.
+    pub fn renice_visual_range(&mut self, delta: i32) {
+        let pids = self.visual_pids();
+        self.renice_pids(&pids, delta);
+    }
+
+    /// Shift each PID's nice value by `delta`, clamped to -20..=19.
+    /// Lowering nice needs CAP_SYS_NICE; failures are counted and flashed.
+    fn renice_pids(&mut self, pids: &[u32], delta: i32) {
+        let mut reniced = 0;
+        let mut last_error = None;
+        for &pid in pids {
+            let Some(nice) = self.all_processes.iter().find(|p| p.tid == pid).map(|p| p.nice) else {
+                continue;
+            };
+            let new_nice = (nice + delta).clamp(-20, 19);
+            let rc = unsafe { libc::setpriority(libc::PRIO_PROCESS, pid as libc::id_t, new_nice) };
+            if rc != 0 {
+                last_error = Some(io::Error::last_os_error());
+                continue;
+            }
+            reniced += 1;
+            // Reflect immediately; the next snapshot confirms it
+            for p in self
+                .all_processes
+                .iter_mut()
+                .chain(self.filtered_processes.iter_mut())
+                .filter(|p| p.tid == pid)
+            {
+                p.nice = new_nice;
+            }
+        }
+
+        let direction = if delta > 0 { "+" } else { "" };
+        match last_error {
+            None => self.flash(format!("Reniced {reniced} process(es) by {direction}{delta}")),
+            Some(e) => self.flash(format!(
+                "Reniced {reniced}/{} process(es): {e}",
+                pids.len()
+            )),
+        }
+    }
+
+    // --- Per-process inspection panels ---
+
+    /// Open an inspection panel (`Files`, `Connections` or `Detail`) for the
+    /// selected process and ask the collector to start gathering its details.
+    pub fn open_inspection(&mut self, mode: AppMode) {
+        let Some(proc) = self.filtered_processes.get(self.selected) else {
+            return;
+        };
+        self.inspect_target = proc.pid;
+        self.files_scroll.home();
+        self.files_filter.clear();
+        self.files_filter_editing = false;
+        self.conn_selected = 0;
+        self.conn_all = false;
+        self.conn_netns = None;
+        self.conn_sort_rtt = false;
+        self.detail_scroll.home();
+        self.inspect_pid.store(proc.pid, Ordering::Relaxed);
+        self.detail_wanted
+            .store(mode == AppMode::Detail, Ordering::Relaxed);
+        self.mode = mode;
+    }
+
+    /// Open the eBPF overhead panel; the collector enables kernel run-time
+    /// stats until it closes.
+    pub fn open_bpf_stats(&mut self) {
+        self.bpf_stats_wanted.store(true, Ordering::Relaxed);
+        self.mode = AppMode::BpfStats;
+    }
+
+    pub fn close_bpf_stats(&mut self) {
+        self.bpf_stats_wanted.store(false, Ordering::Relaxed);
+        self.bpf_stats = None;
+        self.mode = AppMode::Normal;
+    }
+
+    /// Open the system-wide BPF programs and maps panel; like the overhead
+    /// panel, run-time stats are enabled while it is open.
+    pub fn open_bpf_objects(&mut self) {
+        self.bpf_objects_wanted.store(true, Ordering::Relaxed);
+        self.bpf_objects_scroll.home();
+        self.mode = AppMode::BpfObjects;
+    }
+
+    pub fn close_bpf_objects(&mut self) {
+        self.bpf_objects_wanted.store(false, Ordering::Relaxed);
+        self.bpf_objects = None;
+        self.mode = AppMode::Normal;
+    }
+
+    /// Open the sessions panel on the selected process's session.
+    pub fn open_sessions(&mut self) {
+        let sid = self.filtered_processes.get(self.selected).map(|p| p.sid);
+        self.sessions_selected = self
+            .session_list()
+            .iter()
+            .position(|s| Some(s.sid) == sid)
+            .unwrap_or(0);
+        self.mode = AppMode::Sessions;
+    }
+
+    pub fn session_list(&self) -> Vec<SessionSummary> {
+        session::summarize(&self.all_processes, self.sessions_all)
+    }
+
+    pub fn move_session_selection(&mut self, delta: i32) {
+        let len = self.session_list().len();
+        if len == 0 {
+            self.sessions_selected = 0;
+            return;
+        }
+        let new = self.sessions_selected as i64 + delta as i64;
+        self.sessions_selected = new.clamp(0, len as i64 - 1) as usize;
+    }
+
+    pub fn toggle_sessions_all(&mut self) {
+        let sid = self.selected_session();
+        self.sessions_all = !self.sessions_all;
+        self.sessions_selected = self
+            .session_list()
+            .iter()
+            .position(|s| Some(s.sid) == sid)
+            .unwrap_or(0);
+    }
+
+    fn selected_session(&self) -> Option<u32> {
+        let sessions = self.session_list();
+        let idx = self.sessions_selected.min(sessions.len().saturating_sub(1));
+        sessions.get(idx).map(|s| s.sid)
+    }
+
+    /// Narrow the table to the selected session's processes.
+    pub fn show_session(&mut self) {
+        let Some(sid) = self.selected_session() else {
+            return;
+        };
+        self.push_jump_mark();
+        self.filter_query = format!("sid:{sid}");
+        self.active_filter = self.filter_query.clone();
+        self.mode = AppMode::Normal;
+        self.update_filtered_processes();
+    }
+
+    /// Open the cgroup view on the selected process's cgroup.
+    pub fn open_cgroups(&mut self) {
+        let path = self
+            .filtered_processes
+            .get(self.selected)
+            .map(|p| if p.cgroup_path.is_empty() { "/".to_string() } else { p.cgroup_path.clone() });
+        // Unfold its ancestors so the row is there to select
+        if let Some(ref path) = path {
+            self.cgroups_collapsed.retain(|c| !path.starts_with(&format!("{}/", c.trim_end_matches('/'))));
+        }
+        self.cgroups_selected = self
+            .cgroup_rows()
+            .iter()
+            .position(|r| Some(&r.path) == path.as_ref())
+            .unwrap_or(0);
+        self.mode = AppMode::Cgroups;
+    }
+
+    pub fn cgroup_rows(&self) -> Vec<CgroupRow> {
+        cgroup_tree::summarize(&self.all_processes, &self.cgroups_collapsed)
+    }
+
+    pub fn move_cgroup_selection(&mut self, delta: i32) {
+        let len = self.cgroup_rows().len();
+        let new = self.cgroups_selected as i64 + delta as i64;
+        self.cgroups_selected = new.clamp(0, len.saturating_sub(1) as i64) as usize;
+    }
+
+    fn selected_cgroup(&self) -> Option<CgroupRow> {
+        let rows = self.cgroup_rows();
+        let idx = self.cgroups_selected.min(rows.len().saturating_sub(1));
+        rows.into_iter().nth(idx)
+    }
+
+    /// Fold or unfold the selected cgroup; `Some(true)` folds, `None`
+    /// toggles.
+    pub fn fold_cgroup(&mut self, fold: Option<bool>) {
+        let Some(row) = self.selected_cgroup() else {
+            return;
+        };
+        if !row.has_children {
+            return;
+        }
+        if fold.unwrap_or(!row.collapsed) {
+            self.cgroups_collapsed.insert(row.path);
+        } else {
+            self.cgroups_collapsed.remove(&row.path);
+        }
+    }
+
+    /// Narrow the table to the selected cgroup and everything below it.
+    pub fn show_cgroup(&mut self) {
+        let Some(row) = self.selected_cgroup() else {
+            return;
+        };
+        self.push_jump_mark();
+        self.filter_query = if row.path == "/" { String::new() } else { format!("cgroup:{}", row.path) };
+        self.active_filter = self.filter_query.clone();
+        self.mode = AppMode::Normal;
+        self.update_filtered_processes();
+    }
+
+    /// Open the groups panel on the selected process's group, grouped the
+    /// way it was last time.
+    pub fn open_groups(&mut self) {
+        let pid = self.filtered_processes.get(self.selected).map(|p| p.pid);
+        let groups = self.group_list();
+        let group = groups
+            .iter()
+            .position(|g| g.members.iter().any(|m| Some(m.pid) == pid));
+        self.groups_selected = groups::rows(&groups, &self.groups_expanded)
+            .iter()
+            .position(|&r| Some(r) == group.map(GroupRow::Group))
+            .unwrap_or(0);
+        self.mode = AppMode::Groups;
+    }
+
+    pub fn group_list(&self) -> Vec<Group> {
+        groups::summarize(&self.all_processes, self.group_by)
+    }
+
+    pub fn move_group_selection(&mut self, delta: i32) {
+        let len = groups::rows(&self.group_list(), &self.groups_expanded).len();
+        let new = self.groups_selected as i64 + delta as i64;
+        self.groups_selected = new.clamp(0, len.saturating_sub(1) as i64) as usize;
+    }
+
+    /// Switch between grouping by service and by container, staying on
+    /// the selected process's group if it has one.
+    pub fn cycle_group_by(&mut self) {
+        let pid = self.selected_group().and_then(|(_, pid)| pid);
+        self.group_by = self.group_by.next();
+        let groups = self.group_list();
+        let group = groups
+            .iter()
+            .position(|g| g.members.iter().any(|m| Some(m.pid) == pid));
+        self.groups_selected = groups::rows(&groups, &self.groups_expanded)
+            .iter()
+            .position(|&r| Some(r) == group.map(GroupRow::Group))
+            .unwrap_or(0);
+    }
+
+    /// The selected row's group, and the PID if a process row is selected.
+    fn selected_group(&self) -> Option<(String, Option<u32>)> {
+        let groups = self.group_list();
+        let rows = groups::rows(&groups, &self.groups_expanded);
+        let row = *rows.get(self.groups_selected.min(rows.len().saturating_sub(1)))?;
+        Some(match row {
+            GroupRow::Group(g) => (groups[g].name.clone(), None),
+            GroupRow::Member(g, m) => (groups[g].name.clone(), Some(groups[g].members[m].pid)),
+        })
+    }
+
+    /// List or hide the selected group's processes. On a process row,
+    /// folds its group back up onto the group row.
+    pub fn toggle_group_expanded(&mut self) {
+        let Some((name, pid)) = self.selected_group() else {
+            return;
+        };
+        if !self.groups_expanded.remove(&name) {
+            self.groups_expanded.insert(name);
+        } else if pid.is_some() {
+            let groups = self.group_list();
+            let g = groups.iter().position(|g| g.name == name);
+            self.groups_selected = groups::rows(&groups, &self.groups_expanded)
+                .iter()
+                .position(|&r| Some(r) == g.map(GroupRow::Group))
+                .unwrap_or(0);
+        }
+    }
+
+    /// Narrow the table to the selected group, on the selected process if
+    /// a process row is selected.
+    pub fn show_group(&mut self) {
+        let Some((name, pid)) = self.selected_group() else {
+            return;
+        };
+        self.push_jump_mark();
+        self.filter_query = self.group_by.filter(&name);
+        self.active_filter = self.filter_query.clone();
+        self.mode = AppMode::Normal;
+        self.update_filtered_processes();
+        if let Some(pos) = pid.and_then(|pid| self.filtered_processes.iter().position(|p| p.tid == pid)) {
+            self.follow_pid = None;
+            self.selected = pos;
+            self.adjust_scroll();
+        }
+    }
+
+    /// Show the selected session's processes, tagged, in the kill dialog,
+    /// so the whole session goes in one signal. bpftop's own session is
+    /// refused: it would take the terminal bpftop runs in with it.
+    pub fn kill_session(&mut self) {
+        let Some(sid) = self.selected_session() else {
+            return;
+        };
+        if sid == self.own_sid {
+            self.flash(format!("Session {sid} is bpftop's own; not killing it"));
+            return;
+        }
+        self.show_session();
+        self.untag_all();
+        let pids: HashSet<u32> = self
+            .filtered_processes
+            .iter()
+            .filter(|p| !p.is_thread && p.exited.is_none())
+            .map(|p| p.pid)
+            .collect();
+        if pids.is_empty() {
+            return;
+        }
+        for p in self.filtered_processes.iter_mut().chain(self.all_processes.iter_mut()) {
+            p.tagged = !p.is_thread && pids.contains(&p.pid);
+        }
+        self.kill_signal_idx = 0;
+        self.kill_pid_scroll = 0;
+        self.kill_group = false;
+        self.kill_supervisor = None;
+        self.pin_kill_targets();
+        self.pre_kill_mode = AppMode::Normal;
+        self.mode = AppMode::Kill;
+    }
+
+    /// Fetch a thread dump of the selected JVM or Python process with
+    /// jcmd / py-spy, shown in a popup once the tool finishes.
+    pub fn open_runtime_probe(&mut self) {
+        let Some(proc) = self.filtered_processes.get(self.selected) else {
+            return;
+        };
+        let Some(runtime) = Runtime::detect(proc) else {
+            self.flash(format!("{} is not a JVM or Python process", proc.comm));
+            return;
+        };
+        self.runtime_probe = Some(RuntimeProbe::start(proc, runtime));
+        self.runtime_scroll.home();
+        self.mode = AppMode::Runtime;
+    }
+
+    /// Take a fresh dump of the process in the runtime popup.
+    pub fn rerun_runtime_probe(&mut self) {
+        let Some(ref probe) = self.runtime_probe else {
+            return;
+        };
+        let (pid, runtime) = (probe.pid, probe.runtime);
+        match self.all_processes.iter().find(|p| p.tid == pid) {
+            Some(proc) => {
+                self.runtime_probe = Some(RuntimeProbe::start(proc, runtime));
+                self.runtime_scroll.home();
+            }
+            None => self.flash(format!("Process {pid} has exited")),
+        }
+    }
+
+    pub fn close_runtime_probe(&mut self) {
+        self.runtime_probe = None;
+        self.mode = AppMode::Normal;
+    }
+
+    /// Show the selected process's environment (`e`).
+    pub fn open_environ(&mut self) {
+        let Some(proc) = self.filtered_processes.get(self.selected) else {
+            return;
+        };
+        self.env_pid = proc.pid;
+        self.env_comm = proc.comm.clone();
+        self.env_scroll.home();
+        self.env_filter.clear();
+        self.env_filter_editing = false;
+        self.reload_environ();
+        self.mode = AppMode::Environ;
+    }
+
+    /// Read the environment panel's process's variables again.
+    pub fn reload_environ(&mut self) {
+        let pid = self.env_pid;
+        self.env_vars = read_environ(pid).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => format!("Process {pid} has exited"),
+            io::ErrorKind::PermissionDenied => {
+                format!("Permission denied reading /proc/{pid}/environ (run as root)")
+            }
+            _ => format!("Reading /proc/{pid}/environ failed: {e}"),
+        });
+    }
+
+    /// Switch the history popup between the in-memory graphs and the
+    /// samples recorded under `[trends]`, read once on switching.
+    pub fn toggle_history_long_term(&mut self) {
+        if self.history_long_term.take().is_some() {
+            return;
+        }
+        let Some(p) = self.all_processes.iter().find(|p| p.tid == self.history_pid) else {
+            return;
+        };
+        self.history_long_term = Some(self.load_long_term(p.pid, p.start_time_ns));
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn load_long_term(&self, pid: u32, start_time_ns: u64) -> Result<(ProcessSeries, u64), String> {
+        crate::trends::load_series(&self.config.trends, pid, start_time_ns)
+            .map(|t| (t.series, t.span_secs))
+            .map_err(|e| format!("{e:#}"))
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    fn load_long_term(&self, _pid: u32, _start_time_ns: u64) -> Result<(ProcessSeries, u64), String> {
+        Err("Long-term history needs a build with the sqlite feature".to_string())
+    }
+
+    pub fn close_environ(&mut self) {
+        self.env_vars = Ok(Vec::new());
+        self.mode = AppMode::Normal;
+    }
+
+    /// Run the command typed after `:`.
+    pub fn run_command(&mut self) {
+        let input = std::mem::take(&mut self.command_input);
+        self.mode = AppMode::Normal;
+        let input = input.trim();
+        let (name, args) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+        match name {
+            "" => {}
+            "spawn" => self.spawn_command(args),
+            _ => self.flash(format!("Unknown command: {name} (try :spawn)")),
+        }
+    }
+
+    /// `:spawn`: start a command in its own scope with optional CPU and
+    /// memory limits, and keep the selection on it.
+    fn spawn_command(&mut self, args: &str) {
+        let req = match SpawnRequest::parse(args) {
+            Ok(req) => req,
+            Err(e) => return self.flash(e),
+        };
+        match spawn::spawn(&req, self.init_system == InitSystem::Systemd) {
+            Ok(spawned) => {
+                let limits = req.describe_limits();
+                self.flash(if limits.is_empty() {
+                    format!("Started PID {} in {}", spawned.pid, spawned.scope)
+                } else {
+                    format!("Started PID {} in {} ({limits})", spawned.pid, spawned.scope)
+                });
+                self.push_jump_mark();
+                self.follow_pid = Some(spawned.pid);
+                self.spawned.push(spawned);
+            }
+            Err(e) => self.flash(format!("spawn: {e}")),
+        }
+    }
+
+    /// Report spawned commands that have exited.
+    fn poll_spawned(&mut self) {
+        let mut done = Vec::new();
+        self.spawned.retain(|s| match s.exited.try_recv() {
+            Ok(msg) => {
+                done.push(msg);
+                false
+            }
+            Err(mpsc::TryRecvError::Empty) => true,
+            Err(mpsc::TryRecvError::Disconnected) => false,
+        });
+        for msg in done {
+            self.flash(msg);
+        }
+    }
+
+    /// Switch the detail view's off-CPU breakdown on or off. While on, the
+    /// collector attaches sched_switch programs counting only this process.
+    pub fn toggle_offcpu(&mut self) {
+        self.offcpu_wanted.fetch_xor(true, Ordering::Relaxed);
+    }
+
+    /// Keep the file I/O kprobes attached only while a DISK column is on
+    /// screen, and the run-queue tracepoints only while LAT is.
+    pub fn sync_probes(&self) {
+        let columns = &self.config.columns.show;
+        let disk_shown = self.show_disk
+            && columns
+                .iter()
+                .any(|c| matches!(c, SortColumn::DiskRead | SortColumn::DiskWrite));
+        self.file_io_wanted.store(disk_shown, Ordering::Relaxed);
+        let runq_shown = columns.contains(&SortColumn::RunqLat);
+        self.runq_wanted.store(runq_shown, Ordering::Relaxed);
+    }
+
+    pub fn offcpu_wanted(&self) -> bool {
+        self.offcpu_wanted.load(Ordering::Relaxed)
+    }
+
+    /// Switch hardware counters (IPC, cache misses) for the inspected
+    /// process on or off.
+    pub fn toggle_perf(&mut self) {
+        self.perf_wanted.fetch_xor(true, Ordering::Relaxed);
+    }
+
+    pub fn close_inspection(&mut self) {
+        self.inspect_pid.store(0, Ordering::Relaxed);
+        self.detail_wanted.store(false, Ordering::Relaxed);
+        self.offcpu_wanted.store(false, Ordering::Relaxed);
+        self.perf_wanted.store(false, Ordering::Relaxed);
+        self.inspection = None;
+        self.mode = AppMode::Normal;
+    }
+
+    fn inspected_comm(&self) -> &str {
+        self.all_processes
+            .iter()
+            .find(|p| p.tid == self.inspect_target)
+            .map(|p| p.comm.as_str())
+            .unwrap_or("")
+    }
+
+    /// FDs of the inspected process, or `None` until the collector has
+    /// produced data for it.
+    fn inspected_files(&self) -> Option<&[OpenFile]> {
+        self.inspection
+            .as_ref()
+            .filter(|i| i.pid == self.inspect_target)
+            .map(|i| i.files.as_slice())
+    }
+
+    /// Threads of the inspected process.
+    fn inspected_threads(&self) -> Option<&[ThreadInfo]> {
+        self.inspection
+            .as_ref()
+            .filter(|i| i.pid == self.inspect_target)
+            .map(|i| i.threads.as_slice())
+    }
+
+    fn inspected_peers(&self) -> Option<&[Peer]> {
+        self.inspection
+            .as_ref()
+            .filter(|i| i.pid == self.inspect_target)
+            .map(|i| i.peers.as_slice())
+    }
+
+    fn inspected_dns_queries(&self) -> Option<&[DnsQuery]> {
+        self.inspection
+            .as_ref()
+            .filter(|i| i.pid == self.inspect_target)
+            .map(|i| i.dns_queries.as_slice())
+    }
+
+    fn inspected_mount_writes(&self) -> Option<&[MountWrite]> {
+        self.inspection
+            .as_ref()
+            .filter(|i| i.pid == self.inspect_target)
+            .map(|i| i.mount_writes.as_slice())
+    }
+
+    fn inspected_cgroup_writeback(&self) -> Option<&CgroupWriteback> {
+        self.inspection
+            .as_ref()
+            .filter(|i| i.pid == self.inspect_target)
+            .and_then(|i| i.cgroup_writeback.as_ref())
+    }
+
+    fn inspected_off_cpu(&self) -> Option<OffCpuBreakdown> {
+        self.inspection
+            .as_ref()
+            .filter(|i| i.pid == self.inspect_target)
+            .and_then(|i| i.off_cpu)
+    }
+
+    fn inspected_perf_unavailable(&self) -> Option<&str> {
+        self.inspection
+            .as_ref()
+            .filter(|i| i.pid == self.inspect_target)
+            .and_then(|i| i.perf_unavailable.as_deref())
+    }
+
+    fn inspected_io_latency(&self) -> Option<IoLatency> {
+        self.inspection
+            .as_ref()
+            .filter(|i| i.pid == self.inspect_target)
+            .and_then(|i| i.io_latency)
+    }
+
+    /// TCP/UDP sockets shown in the connections panel: the inspected
+    /// process's, or every process's grouped by netns, narrowed to one
+    /// netns if `conn_netns` is set. `conn_sort_rtt` puts the slowest
+    /// round trips first.
+    fn inspected_sockets(&self) -> Option<Vec<&OpenFile>> {
+        let mut socks: Vec<&OpenFile> = if self.conn_all {
+            let insp = self.inspection.as_ref().filter(|i| i.pid == self.inspect_target)?;
+            let mut socks: Vec<&OpenFile> = insp.net_sockets.iter().collect();
+            socks.sort_by_key(|f| (f.netns, f.pid, f.fd));
+            socks
+        } else {
+            let insp = self.inspection.as_ref().filter(|i| i.pid == self.inspect_target)?;
+            insp.files
+                .iter()
+                .filter(|f| f.socket.is_some())
+                .chain(insp.net_sockets.iter().filter(|f| f.pid == insp.pid && f.fd == NO_FD))
+                .collect()
+        };
+        if let Some(netns) = self.conn_netns {
+            socks.retain(|f| f.netns == netns);
+        }
+        if self.conn_sort_rtt {
+            socks.sort_by_key(|f| Reverse(f.socket.as_ref().map_or(0, |s| s.srtt_us)));
+        }
+        Some(socks)
+    }
+
+    /// Name a network namespace: "host", or the container (else command)
+    /// of the lowest PID with a socket in it.
+    fn netns_labels(&self) -> HashMap<u32, String> {
+        let mut owners: HashMap<u32, u32> = HashMap::new();
+        if let Some(insp) = &self.inspection {
+            for f in &insp.net_sockets {
+                let owner = owners.entry(f.netns).or_insert(f.pid);
+                *owner = (*owner).min(f.pid);
+            }
+        }
+        owners
+            .into_iter()
+            .map(|(netns, pid)| {
+                let label = if netns == 0 {
+                    "?".to_string()
+                } else if Some(netns) == self.host_netns {
+                    "host".to_string()
+                } else {
+                    match self.all_processes.iter().find(|p| p.tid == pid) {
+                        Some(p) => p.container.clone().unwrap_or_else(|| format!("{}[{pid}]", p.comm)),
+                        None => netns.to_string(),
+                    }
+                };
+                (netns, label)
+            })
+            .collect()
+    }
+
+    /// Toggle between the inspected process's sockets and everyone's.
+    pub fn toggle_conn_all(&mut self) {
+        self.conn_all = !self.conn_all;
+        self.conn_selected = 0;
+    }
+
+    pub fn toggle_conn_sort_rtt(&mut self) {
+        self.conn_sort_rtt = !self.conn_sort_rtt;
+        self.conn_selected = 0;
+    }
+
+    /// Narrow the connections panel to the selected socket's network
+    /// namespace, or widen it again.
+    pub fn toggle_conn_netns(&mut self) {
+        if self.conn_netns.take().is_none() {
+            self.conn_netns = self.inspected_sockets().and_then(|socks| {
+                let idx = self.conn_selected.min(socks.len().saturating_sub(1));
+                socks.get(idx).map(|f| f.netns)
+            });
+        }
+        self.conn_selected = 0;
+    }
+
+    pub fn move_conn_selection(&mut self, delta: i32) {
+        let len = self.inspected_sockets().map_or(0, |s| s.len());
+        if len == 0 {
+            self.conn_selected = 0;
+            return;
+        }
+        let new = self.conn_selected as i64 + delta as i64;
+        self.conn_selected = new.clamp(0, len as i64 - 1) as usize;
+    }
+
+    /// Copy the remote address of the selected connection to the clipboard.
+    pub fn yank_connection_remote(&mut self) {
+        let remote = self.inspected_sockets().and_then(|socks| {
+            let idx = self.conn_selected.min(socks.len().saturating_sub(1));
+            socks.get(idx).and_then(|f| f.socket.as_ref()).map(|s| s.remote)
+        });
+        let Some(remote) = remote else {
+            return;
+        };
+        if remote.port() == 0 {
+            self.flash("Socket has no remote address".to_string());
+            return;
+        }
+        let text = remote.to_string();
+        if crate::clipboard::yank(&text, self.config.general.clipboard).is_err() {
+            self.flash("Clipboard write failed".to_string());
+        } else {
+            self.flash(format!("Yanked remote {text}"));
+        }
+    }
+
+    // --- Process tree diff ---
+
+    /// First press records the current process list as T1. Later presses
+    /// diff T1 against the current list (T2) and open the diff view.
+    pub fn snapshot_or_diff(&mut self) {
+        match self.diff_base {
+            None => self.record_diff_base(),
+            Some((taken, ref base)) => {
+                self.diff_entries = diff_processes(base, &self.all_processes);
+                self.diff_span = self.clock.now().duration_since(taken);
+                self.diff_scroll.home();
+                self.mode = AppMode::Diff;
+            }
+        }
+    }
+
+    /// Record the current process list as the new T1 baseline.
+    pub fn record_diff_base(&mut self) {
+        self.diff_base = Some((self.clock.now(), self.all_processes.clone()));
+        self.flash(format!(
+            "Snapshot recorded ({} processes), press D again to diff",
+            self.all_processes.iter().filter(|p| !p.is_thread).count()
+        ));
+    }
+
+    // --- Freeze/thaw ---
+
+    /// Gather selected/tagged processes, group by cgroup, populate freeze_targets, enter Freeze mode.
+    pub fn prepare_freeze(&mut self) {
+        let procs = self.target_processes();
+        if procs.is_empty() {
+            return;
+        }
+
+        let targets = self.build_freeze_targets(&procs);
+        if targets.is_empty() {
+            self.flash("No freezable cgroups found".to_string());
+            return;
+        }
+
+        self.freeze_targets = targets;
+        self.freeze_is_thaw = false;
+        self.freeze_scroll = 0;
+        self.freeze_thaw_secs = 0;
+        self.pre_freeze_mode = self.mode;
+        self.mode = AppMode::Freeze;
+    }
+
+    /// Gather selected/tagged processes, group by cgroup (only frozen ones), enter Freeze mode for thaw.
+    pub fn prepare_thaw(&mut self) {
+        let procs = self.target_processes();
+        if procs.is_empty() {
+            return;
+        }
 
         let targets: Vec<FreezeTarget> = self
             .build_freeze_targets(&procs)
@@ -942,7 +3044,51 @@ impl App {
             }
         }
         let count: usize = self.freeze_targets.iter().map(|t| t.pids.len()).sum();
-        self.flash(format!("Frozen {count} process(es)"));
+        if self.freeze_thaw_secs == 0 {
+            self.flash(format!("Frozen {count} process(es)"));
+            return;
+        }
+        let deadline = self.clock.now() + Duration::from_secs(self.freeze_thaw_secs);
+        let mut schedule = self.thaw_schedule.lock().unwrap();
+        for target in &self.freeze_targets {
+            schedule.insert(target.cgroup_path.clone(), deadline);
+        }
+        drop(schedule);
+        self.flash(format!(
+            "Frozen {count} process(es), thawing in {}",
+            format_time_short(self.freeze_thaw_secs as f64)
+        ));
+    }
+
+    /// Step the freeze dialog's auto-thaw timer through `THAW_AFTER_SECS`.
+    pub fn cycle_thaw_after(&mut self) {
+        let idx = THAW_AFTER_SECS
+            .iter()
+            .position(|&s| s == self.freeze_thaw_secs)
+            .unwrap_or(0);
+        self.freeze_thaw_secs = THAW_AFTER_SECS[(idx + 1) % THAW_AFTER_SECS.len()];
+    }
+
+    /// Drop the auto-thaw timers of cgroups thawed by hand.
+    fn cancel_thaw_timers<'a>(&self, paths: impl Iterator<Item = &'a str>) {
+        let mut schedule = self.thaw_schedule.lock().unwrap();
+        for path in paths {
+            schedule.remove(path);
+        }
+    }
+
+    /// Thaw every cgroup still waiting on its auto-thaw timer. Returns how
+    /// many were thawed.
+    fn thaw_scheduled(&self) -> usize {
+        let schedule = std::mem::take(&mut *self.thaw_schedule.lock().unwrap());
+        let mut thawed = 0;
+        for path in schedule.keys().filter(|path| cgroup_control::is_frozen(path)) {
+            match cgroup_control::thaw_cgroup(path) {
+                Ok(()) => thawed += 1,
+                Err(e) => log::warn!("thawing {path} on exit failed: {e}"),
+            }
+        }
+        thawed
     }
 
     /// Execute thaw on all targets (called on Enter in thaw dialog).
@@ -953,6 +3099,7 @@ impl App {
                 return;
             }
         }
+        self.cancel_thaw_timers(self.freeze_targets.iter().map(|t| t.cgroup_path.as_str()));
         let count: usize = self.freeze_targets.iter().map(|t| t.pids.len()).sum();
         self.flash(format!("Thawed {count} process(es)"));
     }
@@ -975,6 +3122,7 @@ impl App {
                 thawed += target.pids.len();
             }
         }
+        self.cancel_thaw_timers(targets.iter().map(|t| t.cgroup_path.as_str()));
         if thawed > 0 {
             self.flash(format!("Thawed {thawed} process(es)"));
         }
@@ -1040,3 +3188,37 @@ impl App {
 fn is_root_cgroup(path: &str) -> bool {
     path == "/"
 }
+
+/// Process a kill dialog row signals, and the start time to pin it by.
+/// kill(2) on a thread row's PID reaches the whole process, so threads pin
+/// their leader; `None` if it is no longer listed.
+fn kill_target(p: &ProcessInfo, all: &[ProcessInfo]) -> Option<(u32, u64)> {
+    if !p.is_thread {
+        return Some((p.pid, p.start_time_ns));
+    }
+    all.iter()
+        .find(|leader| !leader.is_thread && leader.tid == p.pid)
+        .map(|leader| (leader.pid, leader.start_time_ns))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui_bench::synthetic_process;
+
+    #[test]
+    fn thread_rows_pin_their_leader() {
+        let leader = synthetic_process(100, 1, "java");
+        let thread = ProcessInfo {
+            tid: 105,
+            is_thread: true,
+            start_time_ns: leader.start_time_ns + 5_000,
+            ..leader.clone()
+        };
+        let all = vec![leader.clone(), thread.clone()];
+        assert_eq!(kill_target(&leader, &all), Some((100, leader.start_time_ns)));
+        assert_eq!(kill_target(&thread, &all), Some((100, leader.start_time_ns)));
+        // A thread whose process has left the list pins nothing
+        assert_eq!(kill_target(&thread, &all[1..]), None);
+    }
+}
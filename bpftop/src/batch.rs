@@ -0,0 +1,172 @@
+use std::io::{self, Write};
+use std::sync::atomic::Ordering;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+
+use crate::config::Config;
+use crate::data::collector::Collector;
+use crate::dump::{snapshot_json, SCHEMA_VERSION};
+use crate::data::process::{compare_processes, format_bytes, format_time, ProcessInfo, SortColumn};
+use crate::data::system::{format_uptime, SystemInfo};
+use crate::ebpf::loader::EbpfLoader;
+use crate::redact::Redact;
+use crate::ui::process_table::truncate_cols;
+
+/// Output format for `--batch`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum BatchFormat {
+    /// top -b style summary and table.
+    #[default]
+    Text,
+    /// One JSON object per iteration, in the snapshot dump format.
+    Json,
+    /// One row per process per iteration, header printed once.
+    Csv,
+}
+
+const CSV_HEADER: &str =
+    "timestamp,pid,ppid,user,state,pri,ni,virt_bytes,res_bytes,shr_bytes,cpu_percent,mem_percent,cpu_time_secs,comm,cmdline,schema_version";
+
+/// Run `iterations` collection cycles without the TUI and print each one to
+/// stdout. Zero iterations means run until killed.
+pub fn run(config: &Config, iterations: u64, format: BatchFormat, user: Option<&str>) -> Result<()> {
+    let ebpf = EbpfLoader::load(&config.ebpf.map_sizes)
+        .context("eBPF failed to load (batch mode needs root, e.g. sudo bpftop --batch)")?;
+    let mut collector = Collector::new(ebpf);
+    // JSON carries the per-process file I/O and run-queue counters; text
+    // and CSV don't
+    let json = format == BatchFormat::Json;
+    collector.file_io_handle().store(json, Ordering::Relaxed);
+    collector.runq_handle().store(json, Ordering::Relaxed);
+    let delay = Duration::from_millis(config.general.refresh_rate_ms);
+    let redact = Redact::new(&config.privacy.redact);
+
+    // Per-process CPU% is a delta, so the first sample only primes it
+    collector.collect()?;
+
+    let mut out = io::stdout().lock();
+    if format == BatchFormat::Csv {
+        writeln!(out, "{CSV_HEADER}")?;
+    }
+
+    let mut n = 0;
+    while iterations == 0 || n < iterations {
+        std::thread::sleep(delay);
+        let snapshot = collector.collect()?;
+
+        let mut procs: Vec<&ProcessInfo> = snapshot
+            .processes
+            .iter()
+            .filter(|p| config.general.show_threads || !p.is_thread)
+            .filter(|p| config.general.show_kernel_threads || !p.is_kernel_thread)
+            .filter(|p| user.is_none_or(|u| p.user == u))
+            .collect();
+        procs.sort_by(|a, b| compare_processes(a, b, SortColumn::CpuPercent, false));
+
+        let result = match format {
+            BatchFormat::Text => write_text(&mut out, &snapshot.sys, &procs, &redact),
+            BatchFormat::Json => {
+                let owned: Vec<ProcessInfo> = procs.into_iter().cloned().collect();
+                writeln!(out, "{}", snapshot_json(&snapshot.sys, &owned, &redact)?)
+            }
+            BatchFormat::Csv => write_csv(&mut out, &procs, &redact),
+        };
+        // A closed pipe (e.g. `| head`) is a normal way to stop
+        match result.and_then(|_| out.flush()) {
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+            r => r?,
+        }
+        n += 1;
+    }
+    Ok(())
+}
+
+fn write_text(out: &mut impl Write, sys: &SystemInfo, procs: &[&ProcessInfo], redact: &Redact) -> io::Result<()> {
+    writeln!(
+        out,
+        "bpftop - up {}, load average: {:.2}, {:.2}, {:.2}",
+        format_uptime(sys.uptime_secs),
+        sys.load_avg[0],
+        sys.load_avg[1],
+        sys.load_avg[2]
+    )?;
+    writeln!(
+        out,
+        "Tasks: {} total, {} running   CPU: {:.1}%",
+        sys.total_tasks, sys.running_tasks, sys.cpu_total.total_pct
+    )?;
+    writeln!(
+        out,
+        "Mem: {} total, {} used   Swap: {} total, {} used",
+        format_bytes(sys.memory.total),
+        format_bytes(sys.memory.used),
+        format_bytes(sys.swap.total),
+        format_bytes(sys.swap.used)
+    )?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{:>7} {:<10} {:>3} {:>3} {:>6} {:>6} {:>6} S {:>5} {:>5} {:>10}  COMMAND",
+        "PID", "USER", "PRI", "NI", "VIRT", "RES", "SHR", "CPU%", "MEM%", "TIME+"
+    )?;
+    for p in procs {
+        writeln!(
+            out,
+            "{:>7} {:<10} {:>3} {:>3} {:>6} {:>6} {:>6} {} {:>5.1} {:>5.1} {:>10}  {}",
+            p.pid,
+            truncate_cols(&redact.user(&p.user), 10),
+            p.priority,
+            p.nice,
+            format_bytes(p.virt_bytes),
+            format_bytes(p.res_bytes),
+            format_bytes(p.shr_bytes),
+            p.state.as_char(),
+            p.cpu_percent,
+            p.mem_percent,
+            format_time(p.cpu_time_secs),
+            redact.cmdline(&p.cmdline)
+        )?;
+    }
+    writeln!(out)
+}
+
+fn write_csv(out: &mut impl Write, procs: &[&ProcessInfo], redact: &Redact) -> io::Result<()> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    for p in procs {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{:.1},{:.1},{:.2},{},{},{}",
+            timestamp,
+            p.pid,
+            p.ppid,
+            csv_field(&redact.user(&p.user)),
+            p.state.as_char(),
+            p.priority,
+            p.nice,
+            p.virt_bytes,
+            p.res_bytes,
+            p.shr_bytes,
+            p.cpu_percent,
+            p.mem_percent,
+            p.cpu_time_secs,
+            csv_field(&p.comm),
+            csv_field(&redact.cmdline(&p.cmdline)),
+            SCHEMA_VERSION
+        )?;
+    }
+    Ok(())
+}
+
+/// Quote a CSV field if it contains a separator, quote or newline (RFC 4180).
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
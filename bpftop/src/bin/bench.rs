@@ -2,11 +2,13 @@
 //!
 //! Follows the diagnose.rs pattern: self-contained, loads BPF directly.
 //! Runs the BPF task iterator + /proc system stats in a loop and outputs
-//! timing data as JSON.
+//! timing data as JSON. Each run times two passes: a baseline that reads
+//! the iterator into a fresh buffer with `read_to_end`, and a pooled pass
+//! that reuses one buffer the way the app does.
 //!
 //! Run: sudo ./target/release/bench --iterations 50 --json
 
-use std::io::Read;
+use std::io::{self, Read};
 use std::time::Instant;
 
 use anyhow::{Context, Result};
@@ -41,11 +43,22 @@ struct BenchResult {
     iterations: usize,
     warmup: usize,
     process_count: usize,
+    /// Pooled-buffer timings, as the app collects.
     timings_us: Vec<f64>,
     stats: Stats,
+    /// Fresh buffer + `read_to_end` each cycle.
+    baseline: Stats,
+    /// Mean read(2) calls on the iterator file per cycle.
+    read_calls: ReadCalls,
     system: SystemMeta,
 }
 
+#[derive(Serialize)]
+struct ReadCalls {
+    baseline: f64,
+    pooled: f64,
+}
+
 #[derive(Serialize)]
 struct Stats {
     min_us: f64,
@@ -87,33 +100,24 @@ fn main() -> Result<()> {
 
     eprintln!("BPF loaded. Running {} warmup iterations...", cli.warmup);
 
-    // Warmup
+    // Warmup (also grows the pooled buffer to its working size)
+    let mut pool = Vec::new();
     let mut last_count = 0;
     for _ in 0..cli.warmup {
-        let tasks = collect_once(&mut bpf)?;
+        let (tasks, _) = collect_once(&mut bpf, Some(&mut pool))?;
         last_count = tasks.len();
     }
     eprintln!("Warmup done. {} tasks seen. Running {} timed iterations...", last_count, cli.iterations);
 
     // Timed iterations
-    let mut timings_us = Vec::with_capacity(cli.iterations);
-    let mut process_count = 0;
-
-    for i in 0..cli.iterations {
-        let start = Instant::now();
-        let tasks = collect_once(&mut bpf)?;
-        let elapsed = start.elapsed();
-
-        process_count = tasks.len();
-        timings_us.push(elapsed.as_secs_f64() * 1_000_000.0);
-
-        if !cli.json && (i + 1) % 10 == 0 {
-            eprintln!("  [{}/{}] {:.0} µs, {} tasks",
-                i + 1, cli.iterations, timings_us.last().unwrap(), process_count);
-        }
-    }
+    eprintln!("Baseline (fresh buffer):");
+    let (baseline_us, baseline_reads, _) = run_timed(&mut bpf, None, cli.iterations, cli.json)?;
+    eprintln!("Pooled buffer:");
+    let (timings_us, pooled_reads, process_count) =
+        run_timed(&mut bpf, Some(&mut pool), cli.iterations, cli.json)?;
 
     let stats = compute_stats(&timings_us);
+    let baseline = compute_stats(&baseline_us);
     let system = get_system_meta();
 
     let result = BenchResult {
@@ -122,6 +126,11 @@ fn main() -> Result<()> {
         process_count,
         timings_us,
         stats,
+        baseline,
+        read_calls: ReadCalls {
+            baseline: baseline_reads,
+            pooled: pooled_reads,
+        },
         system,
     };
 
@@ -138,14 +147,67 @@ fn main() -> Result<()> {
         eprintln!("P95:    {:.1} µs", result.stats.p95_us);
         eprintln!("P99:    {:.1} µs", result.stats.p99_us);
         eprintln!("Stddev: {:.1} µs", result.stats.stddev_us);
+        eprintln!(
+            "Baseline mean: {:.1} µs ({:+.1}% pooled)",
+            result.baseline.mean_us,
+            (result.stats.mean_us / result.baseline.mean_us - 1.0) * 100.0
+        );
+        eprintln!(
+            "read(2) calls/cycle: {:.1} baseline, {:.1} pooled",
+            result.read_calls.baseline, result.read_calls.pooled
+        );
     }
 
     Ok(())
 }
 
+/// Time `iterations` collection cycles, returning per-cycle timings, mean
+/// read calls per cycle and the last task count.
+fn run_timed(
+    bpf: &mut Ebpf,
+    mut pool: Option<&mut Vec<u8>>,
+    iterations: usize,
+    quiet: bool,
+) -> Result<(Vec<f64>, f64, usize)> {
+    let mut timings_us = Vec::with_capacity(iterations);
+    let mut reads = 0;
+    let mut process_count = 0;
+
+    for i in 0..iterations {
+        let start = Instant::now();
+        let (tasks, n) = collect_once(bpf, pool.as_deref_mut())?;
+        let elapsed = start.elapsed();
+
+        reads += n;
+        process_count = tasks.len();
+        timings_us.push(elapsed.as_secs_f64() * 1_000_000.0);
+
+        if !quiet && (i + 1) % 10 == 0 {
+            eprintln!("  [{}/{}] {:.0} µs, {} tasks",
+                i + 1, iterations, timings_us.last().unwrap(), process_count);
+        }
+    }
+    Ok((timings_us, reads as f64 / iterations.max(1) as f64, process_count))
+}
+
+/// Counts read(2) calls made through it.
+struct CountingReader<R> {
+    inner: R,
+    reads: usize,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.reads += 1;
+        self.inner.read(buf)
+    }
+}
+
 /// Run one full collection cycle: BPF iterator walk + parse tasks.
-/// This is the core operation being benchmarked.
-fn collect_once(bpf: &mut Ebpf) -> Result<Vec<TaskInfo>> {
+/// This is the core operation being benchmarked. With `pool`, the iterator
+/// is read into that reused buffer (as the app does); otherwise into a
+/// fresh one via `read_to_end`. Also returns the number of read calls.
+fn collect_once(bpf: &mut Ebpf, pool: Option<&mut Vec<u8>>) -> Result<(Vec<TaskInfo>, usize)> {
     // Run the BPF task iterator
     let prog: &mut Iter = bpf
         .program_mut("dump_task")
@@ -155,13 +217,24 @@ fn collect_once(bpf: &mut Ebpf) -> Result<Vec<TaskInfo>> {
 
     let link_id = prog.attach().context("attach")?;
     let link: IterLink = prog.take_link(link_id).context("take_link")?;
-    let mut file = link.into_file().context("into_file")?;
-
-    let mut buf = Vec::with_capacity(64 * 1024);
-    file.read_to_end(&mut buf).context("read iterator")?;
+    let file = link.into_file().context("into_file")?;
+    let mut file = CountingReader { inner: file, reads: 0 };
+
+    let mut fresh = Vec::new();
+    let data = match pool {
+        Some(buf) => {
+            let len = read_all(&mut file, buf).context("read iterator")?;
+            &buf[..len]
+        }
+        None => {
+            fresh.reserve(64 * 1024);
+            file.read_to_end(&mut fresh).context("read iterator")?;
+            &fresh[..]
+        }
+    };
 
     let task_size = std::mem::size_of::<TaskInfo>();
-    let tasks: Vec<TaskInfo> = buf
+    let tasks: Vec<TaskInfo> = data
         .chunks_exact(task_size)
         .map(|chunk| unsafe { std::ptr::read_unaligned(chunk.as_ptr() as *const TaskInfo) })
         .collect();
@@ -172,7 +245,24 @@ fn collect_once(bpf: &mut Ebpf) -> Result<Vec<TaskInfo>> {
     let _ = std::fs::read_to_string("/proc/loadavg");
     let _ = std::fs::read_to_string("/proc/uptime");
 
-    Ok(tasks)
+    Ok((tasks, file.reads))
+}
+
+/// Same as the loader's `read_all`: fill `buf` from its start, growing it
+/// only when full, and return the number of bytes read.
+fn read_all(file: &mut impl Read, buf: &mut Vec<u8>) -> io::Result<usize> {
+    let mut len = 0;
+    loop {
+        if len == buf.len() {
+            buf.resize((buf.len() * 2).max(64 * 1024), 0);
+        }
+        match file.read(&mut buf[len..]) {
+            Ok(0) => return Ok(len),
+            Ok(n) => len += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
 }
 
 fn load_tracepoint(bpf: &mut Ebpf, name: &str, category: &str, tp: &str) -> Result<()> {
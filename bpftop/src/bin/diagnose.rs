@@ -11,7 +11,7 @@ use aya::maps::HashMap as BpfHashMap;
 use aya::programs::iter::{Iter, IterLink};
 use aya::programs::TracePoint;
 use aya::{Btf, Ebpf};
-use bpftop_common::{CmdlineEvent, TaskInfo};
+use bpftop_common::{CmdlineEvent, CmdlineKey, TaskInfo};
 
 /// Path relative to this file (src/bin/diagnose.rs):
 ///   ../../../../bpftop-ebpf/target/bpfel-unknown-none/release/bpftop-ebpf
@@ -344,17 +344,17 @@ fn run_iterator(bpf: &mut Ebpf) -> Result<Vec<u8>, String> {
 fn check_cmdline_map(bpf: &Ebpf) {
     match bpf.map("CMDLINE_MAP") {
         Some(map) => {
-            match BpfHashMap::<_, u32, CmdlineEvent>::try_from(map) {
+            match BpfHashMap::<_, CmdlineKey, CmdlineEvent>::try_from(map) {
                 Ok(hash) => {
                     let mut count = 0;
                     let mut shown = 0;
-                    for (pid, event) in hash.iter().flatten() {
+                    for (key, event) in hash.iter().flatten() {
                         count += 1;
                         if shown < 5 {
                             let len = (event.len as usize).min(event.cmdline.len());
                             let cmdline = String::from_utf8_lossy(&event.cmdline[..len])
                                 .replace('\0', " ");
-                            println!("  PID={pid:<6} cmdline={}", cmdline.trim());
+                            println!("  PID={:<6} cmdline={}", key.pid, cmdline.trim());
                             shown += 1;
                         }
                     }
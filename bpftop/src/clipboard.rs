@@ -1,14 +1,83 @@
 use std::fs::OpenOptions;
 use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
 
 use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// Bytes per write(2) when emitting OSC-52, so terminals with small input
+/// buffers don't truncate the sequence.
+const OSC52_WRITE_CHUNK: usize = 4096;
+/// GNU screen drops DCS strings longer than this, so its passthrough is split.
+const SCREEN_DCS_CHUNK: usize = 76;
+
+/// How yanked text reaches the clipboard (`[general] clipboard = ...`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClipboardBackend {
+    /// Local clipboard tool when one is usable, otherwise OSC-52.
+    #[default]
+    Auto,
+    Osc52,
+    WlCopy,
+    Xclip,
+    Xsel,
+}
+
+impl ClipboardBackend {
+    /// Pick a concrete backend. Over SSH the local tools would write to the
+    /// remote machine's clipboard, so OSC-52 wins there.
+    fn resolve(self) -> Self {
+        if self != Self::Auto {
+            return self;
+        }
+        let env = |name| std::env::var_os(name).is_some_and(|v| !v.is_empty());
+        if env("SSH_CONNECTION") || env("SSH_TTY") {
+            return Self::Osc52;
+        }
+        if env("WAYLAND_DISPLAY") && in_path("wl-copy") {
+            return Self::WlCopy;
+        }
+        if env("DISPLAY") {
+            if in_path("xclip") {
+                return Self::Xclip;
+            }
+            if in_path("xsel") {
+                return Self::Xsel;
+            }
+        }
+        Self::Osc52
+    }
+}
+
+/// Copy text to the clipboard using `backend` (resolving `Auto` first).
+/// With `Auto`, a local tool that fails to run falls back to OSC-52; an
+/// explicitly configured tool reports its error instead.
+pub fn yank(text: &str, backend: ClipboardBackend) -> io::Result<()> {
+    let resolved = backend.resolve();
+    let result = match resolved {
+        ClipboardBackend::WlCopy => pipe_to("wl-copy", &[], text),
+        ClipboardBackend::Xclip => pipe_to("xclip", &["-selection", "clipboard"], text),
+        ClipboardBackend::Xsel => pipe_to("xsel", &["--clipboard", "--input"], text),
+        ClipboardBackend::Osc52 | ClipboardBackend::Auto => return osc52(text),
+    };
+    match result {
+        Err(e) if backend == ClipboardBackend::Auto => {
+            log::info!("{resolved:?} failed ({e}); copying with OSC-52");
+            osc52(text)
+        }
+        r => r,
+    }
+}
 
 /// Copy text to the system clipboard via OSC-52 escape sequence.
 ///
 /// Writes directly to `/dev/tty` so it bypasses ratatui's stdout ownership.
 /// If `$TMUX` is set, wraps in a DCS passthrough so tmux forwards it to the
-/// outer terminal.
-pub fn yank(text: &str) -> io::Result<()> {
+/// outer terminal; under GNU screen (`$STY`) the passthrough is split into
+/// short DCS strings.
+fn osc52(text: &str) -> io::Result<()> {
     let encoded = base64::engine::general_purpose::STANDARD.encode(text);
     let osc = format!("\x1b]52;c;{encoded}\x07");
 
@@ -16,11 +85,47 @@ pub fn yank(text: &str) -> io::Result<()> {
         // Tmux DCS passthrough: escape every \x1b inside the payload
         let escaped = osc.replace('\x1b', "\x1b\x1b");
         format!("\x1bPtmux;{escaped}\x1b\\")
+    } else if std::env::var("STY").is_ok() {
+        // OSC is pure ASCII, so byte chunks are valid str slices
+        osc.as_bytes()
+            .chunks(SCREEN_DCS_CHUNK)
+            .map(|c| format!("\x1bP{}\x1b\\", String::from_utf8_lossy(c)))
+            .collect()
     } else {
         osc
     };
 
     let mut tty = OpenOptions::new().write(true).open("/dev/tty")?;
-    tty.write_all(payload.as_bytes())?;
-    tty.flush()
+    for chunk in payload.as_bytes().chunks(OSC52_WRITE_CHUNK) {
+        tty.write_all(chunk)?;
+        tty.flush()?;
+    }
+    Ok(())
+}
+
+/// Feed `text` to a clipboard tool's stdin. Output is discarded: wl-copy and
+/// xclip fork a process that keeps serving the selection, and an inherited
+/// stdout would corrupt the TUI.
+fn pipe_to(program: &str, args: &[&str], text: &str) -> io::Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("{program} exited with {status}")))
+    }
+}
+
+fn in_path(program: &str) -> bool {
+    std::env::var_os("PATH").is_some_and(|path| {
+        std::env::split_paths(&path).any(|dir| Path::new(&dir).join(program).is_file())
+    })
 }
@@ -1,11 +1,30 @@
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use toml::{Table, Value};
 
+use crate::alerts::ForwardTarget;
+use crate::clipboard::ClipboardBackend;
+use crate::data::collector::ROUTINE_SETUID;
+use crate::data::process::SortColumn;
+use crate::data::system::NetInterface;
+use crate::metrics::PushFormat;
+use crate::redact::RedactField;
 use crate::theme::ThemeOverrides;
 
+/// Schema version written to config.toml as `version`. Bump it when keys
+/// are renamed or reshaped, and add a step to [`MIGRATIONS`] that upgrades
+/// files of the previous version.
+pub const CONFIG_VERSION: i64 = 1;
+
+/// Upgrade steps: entry `i` turns a version `i + 1` file into version
+/// `i + 2`, returning what it changed. Files without a `version` key
+/// predate versioning and count as version 1.
+const MIGRATIONS: &[fn(&mut Table) -> Vec<String>] = &[];
+
 /// Application configuration loaded from ~/.config/bpftop/config.toml.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Config {
@@ -13,6 +32,26 @@ pub struct Config {
     pub general: GeneralConfig,
     #[serde(default)]
     pub theme: ThemeConfig,
+    #[serde(default)]
+    pub snapshot: SnapshotConfig,
+    #[serde(default)]
+    pub trends: TrendsConfig,
+    #[serde(default)]
+    pub alerts: AlertsConfig,
+    #[serde(default)]
+    pub push: PushConfig,
+    #[serde(default)]
+    pub columns: ColumnsConfig,
+    #[serde(default)]
+    pub meters: MetersConfig,
+    #[serde(default)]
+    pub ebpf: EbpfConfig,
+    #[serde(default)]
+    pub badges: BadgesConfig,
+    #[serde(default)]
+    pub perf: PerfConfig,
+    #[serde(default)]
+    pub privacy: PrivacyConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +68,36 @@ pub struct GeneralConfig {
     /// Show kernel threads.
     #[serde(default)]
     pub show_kernel_threads: bool,
+    /// Clipboard backend: "auto", "osc52", "wl-copy", "xclip" or "xsel".
+    #[serde(default)]
+    pub clipboard: ClipboardBackend,
+    /// Dim rows with no CPU, disk or network activity for this many
+    /// consecutive refreshes (0 = never dim).
+    #[serde(default = "default_idle_dim_refreshes")]
+    pub idle_dim_refreshes: u32,
+    /// Upper bound on redraws per second (0 = no fixed cap). Frames are
+    /// also spaced by how long the last one took to reach the terminal.
+    #[serde(default = "default_max_fps")]
+    pub max_fps: u32,
+    /// Start in compact mode: one-line header and tighter table columns.
+    #[serde(default)]
+    pub compact: bool,
+    /// Screen-reader friendly output: no box drawing or bar graphs, fixed
+    /// columns, and the selected row spelled out on the status line.
+    #[serde(default)]
+    pub screen_reader: bool,
+    /// Show only the header meters and the top few processes, for small
+    /// panes and status displays.
+    #[serde(default)]
+    pub summary: bool,
+    /// In tree view, list processes whose parent exited at the top level
+    /// after the main tree, instead of under whoever adopted them.
+    #[serde(default)]
+    pub group_orphans: bool,
+    /// Keep `m` marks in the state directory, so they survive restarting
+    /// bpftop (while the marked processes keep running).
+    #[serde(default)]
+    pub persist_marks: bool,
 }
 
 impl Default for GeneralConfig {
@@ -38,6 +107,14 @@ impl Default for GeneralConfig {
             tree_view: false,
             show_threads: false,
             show_kernel_threads: false,
+            clipboard: ClipboardBackend::default(),
+            idle_dim_refreshes: default_idle_dim_refreshes(),
+            max_fps: default_max_fps(),
+            compact: false,
+            screen_reader: false,
+            summary: false,
+            group_orphans: false,
+            persist_marks: false,
         }
     }
 }
@@ -46,6 +123,14 @@ fn default_refresh_rate() -> u64 {
     1000
 }
 
+fn default_idle_dim_refreshes() -> u32 {
+    5
+}
+
+fn default_max_fps() -> u32 {
+    30
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThemeConfig {
     /// Theme preset name: "gruvbox-dark" or "gruvbox-light".
@@ -69,40 +154,578 @@ fn default_preset() -> String {
     "gruvbox-dark".to_string()
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotConfig {
+    /// Write a JSON snapshot of the process table every N minutes (0 = disabled).
+    #[serde(default)]
+    pub interval_minutes: u64,
+    /// Directory for snapshot files. Defaults to ~/.local/state/bpftop/snapshots.
+    #[serde(default)]
+    pub dir: Option<PathBuf>,
+    /// Number of most recent snapshot files to keep; older ones are deleted.
+    #[serde(default = "default_snapshot_keep")]
+    pub keep: usize,
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            interval_minutes: 0,
+            dir: None,
+            keep: default_snapshot_keep(),
+        }
+    }
+}
+
+fn default_snapshot_keep() -> usize {
+    48
+}
+
+impl SnapshotConfig {
+    /// Resolved snapshot directory.
+    pub fn dir(&self) -> Result<PathBuf> {
+        match &self.dir {
+            Some(dir) => Ok(dir.clone()),
+            None => Ok(state_dir()?.join("snapshots")),
+        }
+    }
+}
+
+/// Long-term per-process samples in sqlite, for `bpftop history`.
+/// Only takes effect in builds with the `sqlite` feature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "sqlite"), allow(dead_code))]
+pub struct TrendsConfig {
+    /// Record samples while bpftop runs.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Database file. Defaults to ~/.local/state/bpftop/trends.sqlite.
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    /// Seconds averaged into each stored row.
+    #[serde(default = "default_trends_interval")]
+    pub interval_secs: u64,
+    /// Rows older than this many days are deleted.
+    #[serde(default = "default_trends_retain_days")]
+    pub retain_days: u64,
+}
+
+impl Default for TrendsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: None,
+            interval_secs: default_trends_interval(),
+            retain_days: default_trends_retain_days(),
+        }
+    }
+}
+
+fn default_trends_interval() -> u64 {
+    60
+}
+
+fn default_trends_retain_days() -> u64 {
+    7
+}
+
+#[cfg_attr(not(feature = "sqlite"), allow(dead_code))]
+impl TrendsConfig {
+    /// Resolved database path.
+    pub fn path(&self) -> Result<PathBuf> {
+        match &self.path {
+            Some(path) => Ok(path.clone()),
+            None => Ok(state_dir()?.join("trends.sqlite")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertsConfig {
+    /// Forward alerts to "journald", "syslog", or "none".
+    #[serde(default)]
+    pub forward: ForwardTarget,
+    /// Alert when a process's listening/UDP sockets drop more packets per
+    /// second than this (0 = never).
+    #[serde(default = "default_drops_per_sec")]
+    pub drops_per_sec: f64,
+    /// Alert when a process's resident memory grew without ever shrinking
+    /// for this many minutes, by at least 16 MiB (0 = never). Also the
+    /// window the GROW column averages over.
+    #[serde(default = "default_leak_minutes")]
+    pub leak_minutes: u64,
+    /// Alert when CPUs keep being thermally throttled for this many
+    /// seconds (0 = never).
+    #[serde(default = "default_throttle_secs")]
+    pub throttle_secs: u64,
+    /// Commands (as in comm) that become root routinely, like sudo and
+    /// passwd, so their uid change doesn't raise an escalation alert.
+    #[serde(default = "default_setuid_allowlist")]
+    pub setuid_allowlist: Vec<String>,
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        Self {
+            forward: ForwardTarget::default(),
+            drops_per_sec: default_drops_per_sec(),
+            leak_minutes: default_leak_minutes(),
+            throttle_secs: default_throttle_secs(),
+            setuid_allowlist: default_setuid_allowlist(),
+        }
+    }
+}
+
+fn default_drops_per_sec() -> f64 {
+    1.0
+}
+
+fn default_leak_minutes() -> u64 {
+    10
+}
+
+fn default_throttle_secs() -> u64 {
+    10
+}
+
+fn default_setuid_allowlist() -> Vec<String> {
+    ROUTINE_SETUID.iter().map(|s| s.to_string()).collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushConfig {
+    /// http:// endpoint to push metrics to (empty = disabled), e.g.
+    /// "http://pushgw:9091/metrics/job/bpftop", "http://prom:9090/api/v1/write"
+    /// or "http://otel:4318/v1/metrics".
+    #[serde(default)]
+    pub url: String,
+    /// "openmetrics" (Prometheus text format), "remote_write" (Prometheus
+    /// remote-write protobuf) or "otlp" (OTLP/HTTP JSON).
+    #[serde(default)]
+    pub format: PushFormat,
+    /// Seconds between pushes.
+    #[serde(default = "default_push_interval")]
+    pub interval_secs: u64,
+    /// Per-process series are limited to the top N processes by CPU%.
+    #[serde(default = "default_push_max_processes")]
+    pub max_processes: usize,
+}
+
+impl Default for PushConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            format: PushFormat::default(),
+            interval_secs: default_push_interval(),
+            max_processes: default_push_max_processes(),
+        }
+    }
+}
+
+fn default_push_interval() -> u64 {
+    15
+}
+
+fn default_push_max_processes() -> usize {
+    50
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnsConfig {
+    /// Process table columns, in display order (e.g. ["pid", "user",
+    /// "cpu-percent", "command"]). Unlisted columns are hidden; GPU, network,
+    /// disk and PGRP/SID columns still need their toggle on to appear.
+    #[serde(default = "default_columns")]
+    pub show: Vec<SortColumn>,
+    /// What the Command column shows: "full", "basename" or "comm".
+    #[serde(default)]
+    pub command: CommandDisplay,
+}
+
+impl Default for ColumnsConfig {
+    fn default() -> Self {
+        Self {
+            show: default_columns(),
+            command: CommandDisplay::default(),
+        }
+    }
+}
+
+fn default_columns() -> Vec<SortColumn> {
+    SortColumn::all().to_vec()
+}
+
+/// What the Command column shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CommandDisplay {
+    /// The command line as the process set it.
+    #[default]
+    Full,
+    /// The command line with the directory stripped from the program.
+    Basename,
+    /// Only the kernel's 15-character task name.
+    Comm,
+}
+
+impl CommandDisplay {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CommandDisplay::Full => "full",
+            CommandDisplay::Basename => "basename",
+            CommandDisplay::Comm => "comm",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            CommandDisplay::Full => CommandDisplay::Basename,
+            CommandDisplay::Basename => CommandDisplay::Comm,
+            CommandDisplay::Comm => CommandDisplay::Full,
+        }
+    }
+}
+
+/// How the header shows CPU usage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CpuMeter {
+    /// One bar per CPU, in a grid.
+    #[default]
+    PerCpu,
+    /// A single bar for all CPUs.
+    Total,
+    Off,
+}
+
+impl CpuMeter {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CpuMeter::PerCpu => "per-cpu",
+            CpuMeter::Total => "total",
+            CpuMeter::Off => "off",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            CpuMeter::PerCpu => CpuMeter::Total,
+            CpuMeter::Total => CpuMeter::Off,
+            CpuMeter::Off => CpuMeter::PerCpu,
+        }
+    }
+}
+
+/// How the header shows disk throughput.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiskMeter {
+    /// A single bar summing all disks.
+    #[default]
+    Total,
+    /// One bar per disk.
+    PerDevice,
+    Off,
+}
+
+impl DiskMeter {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DiskMeter::Total => "total",
+            DiskMeter::PerDevice => "per-device",
+            DiskMeter::Off => "off",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            DiskMeter::Total => DiskMeter::PerDevice,
+            DiskMeter::PerDevice => DiskMeter::Off,
+            DiskMeter::Off => DiskMeter::Total,
+        }
+    }
+}
+
+/// How the header shows network throughput.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NetMeter {
+    /// A single bar summing the shown interfaces.
+    #[default]
+    Total,
+    /// One bar per interface.
+    PerInterface,
+    Off,
+}
+
+impl NetMeter {
+    pub fn label(&self) -> &'static str {
+        match self {
+            NetMeter::Total => "total",
+            NetMeter::PerInterface => "per-interface",
+            NetMeter::Off => "off",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            NetMeter::Total => NetMeter::PerInterface,
+            NetMeter::PerInterface => NetMeter::Off,
+            NetMeter::Off => NetMeter::Total,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetersConfig {
+    /// CPU meters: "per-cpu", "total" or "off".
+    #[serde(default)]
+    pub cpu: CpuMeter,
+    /// Show the memory bar.
+    #[serde(default = "default_true")]
+    pub memory: bool,
+    /// Show the swap bar.
+    #[serde(default = "default_true")]
+    pub swap: bool,
+    /// Disk throughput bars: "total", "per-device" or "off".
+    #[serde(default)]
+    pub disk: DiskMeter,
+    /// Network throughput bars: "total", "per-interface" or "off".
+    #[serde(default)]
+    pub net: NetMeter,
+    /// Interfaces the network bars cover, e.g. ["eth0", "wg0"]. Empty means
+    /// every interface backed by a device, leaving out bridges, veths and
+    /// tunnels, whose traffic also crosses a physical interface.
+    #[serde(default)]
+    pub net_interfaces: Vec<String>,
+    /// Show GPU utilization and memory bars (when a GPU is present).
+    #[serde(default = "default_true")]
+    pub gpu: bool,
+    /// Show CPU/memory/IO pressure stall (PSI) bars (when the kernel has PSI).
+    #[serde(default = "default_true")]
+    pub pressure: bool,
+}
+
+impl Default for MetersConfig {
+    fn default() -> Self {
+        Self {
+            cpu: CpuMeter::default(),
+            memory: true,
+            swap: true,
+            disk: DiskMeter::default(),
+            net: NetMeter::default(),
+            net_interfaces: Vec::new(),
+            gpu: true,
+            pressure: true,
+        }
+    }
+}
+
+impl MetersConfig {
+    /// Whether the network bars include `iface`.
+    pub fn shows_interface(&self, iface: &NetInterface) -> bool {
+        if self.net_interfaces.is_empty() {
+            iface.physical
+        } else {
+            self.net_interfaces.contains(&iface.name)
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BadgesConfig {
+    /// Tag databases, JVMs, interpreters, compilers, browsers and
+    /// container runtimes in the Command column.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Extra workload rules, tried in order before the built-in ones.
+    #[serde(default)]
+    pub rules: Vec<BadgeRule>,
+}
+
+impl Default for BadgesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// One `[[badges.rules]]` entry, e.g. `{ label = "ml", cmdline = "torchrun" }`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BadgeRule {
+    /// Tag text; an empty label keeps matching processes untagged.
+    pub label: String,
+    /// Regex on the process name (comm, at most 15 characters).
+    #[serde(default)]
+    pub comm: Option<String>,
+    /// Regex on the full command line.
+    #[serde(default)]
+    pub cmdline: Option<String>,
+    /// "#rrggbb"; defaults to yellow.
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EbpfConfig {
+    /// Override the max_entries of BPF hash maps at load time, by map name
+    /// (e.g. { CMDLINE_MAP = 8192 }). Smaller maps use less kernel memory;
+    /// larger ones fill up later on hosts with many processes.
+    #[serde(default)]
+    pub map_sizes: BTreeMap<String, u32>,
+}
+
+/// Hardware performance counters (cycles, instructions, cache misses).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PerfConfig {
+    /// Keep counters open for the N busiest processes, so the detail view
+    /// shows their IPC right away (0 = only the inspected process, after
+    /// `i` in the detail view).
+    #[serde(default)]
+    pub top: usize,
+}
+
+/// Masking for screenshots and shared output.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrivacyConfig {
+    /// What to mask in the UI, snapshot dumps and `--batch` output:
+    /// any of "users", "args" and "ips". `--redact` masks all three.
+    #[serde(default)]
+    pub redact: Vec<RedactField>,
+}
 
 impl Config {
     /// Load config from the default path, or return defaults if not found.
-    pub fn load() -> Result<Self> {
+    /// A file from an older bpftop is upgraded and rewritten, with the
+    /// original kept next to it; the returned notice says so.
+    pub fn load() -> Result<(Self, Option<String>)> {
         let path = config_path();
-        if path.exists() {
-            let content = fs::read_to_string(&path)
-                .with_context(|| format!("reading config from {}", path.display()))?;
-            let config: Config = toml::from_str(&content)
-                .with_context(|| format!("parsing config from {}", path.display()))?;
-            Ok(config)
-        } else {
-            Ok(Config::default())
+        if !path.exists() {
+            return Ok((Config::default(), None));
         }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("reading config from {}", path.display()))?;
+        let mut table: Table = toml::from_str(&content)
+            .with_context(|| format!("parsing config from {}", path.display()))?;
+        let version = file_version(&table);
+        let changes = migrate(&mut table, version);
+        let config: Config = table
+            .clone()
+            .try_into()
+            .with_context(|| format!("parsing config from {}", path.display()))?;
+
+        let notice = if version > CONFIG_VERSION {
+            Some(format!(
+                "{} is from a newer bpftop (version {version}); settings this one doesn't know are ignored",
+                path.display()
+            ))
+        } else if version < CONFIG_VERSION {
+            Some(match rewrite_migrated(&path, version, &table) {
+                Ok(backup) if changes.is_empty() => format!(
+                    "Config upgraded to version {CONFIG_VERSION}; old file kept as {}",
+                    backup.display()
+                ),
+                Ok(backup) => format!(
+                    "Config upgraded to version {CONFIG_VERSION} ({}); old file kept as {}",
+                    changes.join(", "),
+                    backup.display()
+                ),
+                Err(e) => format!("Config upgraded for this run only: {e:#}"),
+            })
+        } else {
+            None
+        };
+        Ok((config, notice))
     }
 
     /// Save config to the default path.
-    #[allow(dead_code)]
     pub fn save(&self) -> Result<()> {
         let path = config_path();
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)
                 .with_context(|| format!("creating config directory {}", parent.display()))?;
         }
-        let content = toml::to_string_pretty(self).context("serializing config")?;
+        let mut table = Table::try_from(self).context("serializing config")?;
+        table.insert("version".to_string(), Value::Integer(CONFIG_VERSION));
+        let content = toml::to_string_pretty(&table).context("serializing config")?;
         fs::write(&path, content)
             .with_context(|| format!("writing config to {}", path.display()))?;
         Ok(())
     }
 }
 
+fn file_version(table: &Table) -> i64 {
+    table.get("version").and_then(Value::as_integer).unwrap_or(1)
+}
+
+/// Run every step from `version` up to [`CONFIG_VERSION`] and stamp the
+/// result; returns the changes made. Newer files are left alone.
+fn migrate(table: &mut Table, version: i64) -> Vec<String> {
+    if version >= CONFIG_VERSION {
+        return Vec::new();
+    }
+    let mut changes = Vec::new();
+    for step in MIGRATIONS.iter().skip((version.max(1) - 1) as usize) {
+        changes.extend(step(table));
+    }
+    table.insert("version".to_string(), Value::Integer(CONFIG_VERSION));
+    changes
+}
+
+/// Copy the old file to `config.toml.v<version>.bak`, then write the
+/// upgraded one in its place. Only keys the file already had are written,
+/// so defaults keep following bpftop's.
+fn rewrite_migrated(path: &Path, version: i64, table: &Table) -> Result<PathBuf> {
+    let backup = path.with_extension(format!("toml.v{version}.bak"));
+    fs::copy(path, &backup).with_context(|| format!("backing up config to {}", backup.display()))?;
+    let content = toml::to_string_pretty(table).context("serializing config")?;
+    fs::write(path, content).with_context(|| format!("writing config to {}", path.display()))?;
+    Ok(backup)
+}
+
+/// bpftop's directory under `$XDG_STATE_HOME`, or `$HOME/.local/state`
+/// without it. Fails when there is no home directory either, rather than
+/// writing under a literal `~` in the working directory.
+pub fn state_dir() -> Result<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join("bpftop"))
+        .context("no state directory: neither $XDG_STATE_HOME nor $HOME is set")
+}
+
 fn config_path() -> PathBuf {
     dirs::config_dir()
         .unwrap_or_else(|| PathBuf::from("~/.config"))
         .join("bpftop")
         .join("config.toml")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unversioned_config_is_current() {
+        let mut table: Table = toml::from_str(
+            r#"
+            [general]
+            idle_dim_refreshes = 7
+            tree_view = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(file_version(&table), CONFIG_VERSION);
+        assert!(migrate(&mut table, CONFIG_VERSION).is_empty());
+        assert!(!table.contains_key("version"));
+
+        let config: Config = table.try_into().unwrap();
+        assert_eq!(config.general.idle_dim_refreshes, 7);
+        assert!(config.general.tree_view);
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::{BTreeMap, HashSet};
+
+use super::process::ProcessInfo;
+
+/// One cgroup in the cgroup view, with everything below it summed in.
+#[derive(Debug, Clone)]
+pub struct CgroupRow {
+    /// Path below the cgroup2 mount, "/" for the root.
+    pub path: String,
+    /// Last path component, e.g. "nginx.service".
+    pub name: String,
+    /// Tree drawing in front of the name, like the process tree's.
+    pub prefix: String,
+    pub has_children: bool,
+    pub collapsed: bool,
+    pub processes: usize,
+    /// Processes plus their threads.
+    pub tasks: usize,
+    pub cpu_percent: f64,
+    pub res_bytes: u64,
+}
+
+#[derive(Default)]
+struct Totals {
+    processes: usize,
+    tasks: usize,
+    cpu_percent: f64,
+    res_bytes: u64,
+}
+
+/// The cgroup hierarchy holding `processes`, flattened depth-first with
+/// siblings by name, so rows stay put between refreshes. Cgroups without
+/// tasks of their own appear only as ancestors of ones that have some;
+/// children of `collapsed` paths are left out but still counted.
+pub fn summarize(processes: &[ProcessInfo], collapsed: &HashSet<String>) -> Vec<CgroupRow> {
+    // BTreeMap so every parent sorts before its children
+    let mut totals: BTreeMap<String, Totals> = BTreeMap::new();
+    totals.insert("/".to_string(), Totals::default());
+    for proc in processes {
+        if proc.exited.is_some() {
+            continue;
+        }
+        let path = if proc.cgroup_path.is_empty() { "/" } else { proc.cgroup_path.as_str() };
+        // Credit the cgroup and every ancestor up to the root
+        let mut at = Some(path);
+        while let Some(p) = at {
+            let t = totals.entry(p.to_string()).or_default();
+            t.tasks += 1;
+            if !proc.is_thread {
+                t.processes += 1;
+                t.cpu_percent += proc.cpu_percent;
+                t.res_bytes += proc.res_bytes;
+            }
+            at = parent(p);
+        }
+    }
+
+    let mut children: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for path in totals.keys() {
+        if let Some(p) = parent(path) {
+            children.entry(p).or_default().push(path);
+        }
+    }
+
+    let mut rows = Vec::new();
+    push_subtree("/", 0, true, String::new(), &totals, &children, collapsed, &mut rows);
+    rows
+}
+
+/// Parent of a cgroup path, `None` for the root.
+fn parent(path: &str) -> Option<&str> {
+    if path == "/" {
+        return None;
+    }
+    match path.trim_end_matches('/').rfind('/') {
+        Some(0) | None => Some("/"),
+        Some(i) => Some(&path[..i]),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn push_subtree(
+    path: &str,
+    depth: u16,
+    is_last: bool,
+    indent_prefix: String,
+    totals: &BTreeMap<String, Totals>,
+    children: &BTreeMap<&str, Vec<&str>>,
+    collapsed: &HashSet<String>,
+    rows: &mut Vec<CgroupRow>,
+) {
+    let prefix = if depth == 0 {
+        String::new()
+    } else if is_last {
+        format!("{indent_prefix}\u{2514}\u{2500}") // └─
+    } else {
+        format!("{indent_prefix}\u{251c}\u{2500}") // ├─
+    };
+    let kids = children.get(path).map_or(&[][..], Vec::as_slice);
+    let is_collapsed = collapsed.contains(path);
+    let t = &totals[path];
+    rows.push(CgroupRow {
+        path: path.to_string(),
+        name: path.rsplit('/').next().filter(|n| !n.is_empty()).unwrap_or("/").to_string(),
+        prefix,
+        has_children: !kids.is_empty(),
+        collapsed: is_collapsed,
+        processes: t.processes,
+        tasks: t.tasks,
+        cpu_percent: t.cpu_percent,
+        res_bytes: t.res_bytes,
+    });
+    if is_collapsed {
+        return;
+    }
+    for (i, &child) in kids.iter().enumerate() {
+        let child_indent = if depth == 0 {
+            String::new()
+        } else if is_last {
+            format!("{indent_prefix}  ")
+        } else {
+            format!("{indent_prefix}\u{2502} ") // │
+        };
+        push_subtree(child, depth + 1, i == kids.len() - 1, child_indent, totals, children, collapsed, rows);
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::{HashMap, HashSet};
+
+use super::process::ProcessInfo;
+
+/// CPU% change (absolute, in percentage points) that counts as significant.
+const CPU_DELTA_THRESHOLD: f64 = 10.0;
+/// Relative RES change that counts as significant...
+const RES_DELTA_RATIO: f64 = 0.25;
+/// ...provided the absolute change is also at least this large.
+const RES_DELTA_MIN_BYTES: u64 = 16 * 1024 * 1024;
+
+/// How a process differs between the two snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    Appeared,
+    Exited,
+    Changed,
+}
+
+impl DiffKind {
+    /// Single-character marker shown in the diff view.
+    pub fn marker(&self) -> char {
+        match self {
+            Self::Appeared => '+',
+            Self::Exited => '-',
+            Self::Changed => '~',
+        }
+    }
+}
+
+/// One row of a process tree diff.
+#[derive(Debug, Clone)]
+pub struct DiffEntry {
+    pub pid: u32,
+    pub kind: DiffKind,
+    pub comm: String,
+    /// Number of ancestors that are also part of the diff (for indentation).
+    pub depth: usize,
+    /// CPU% at T1 / T2 (0 when the process did not exist at that time).
+    pub cpu_before: f64,
+    pub cpu_after: f64,
+    /// Resident memory at T1 / T2.
+    pub res_before: u64,
+    pub res_after: u64,
+}
+
+/// Compare two process lists and return the processes that appeared,
+/// exited, or changed CPU/memory usage significantly, in tree order.
+///
+/// Processes are matched by (pid, start time) so a recycled PID shows up
+/// as one exit plus one new process. Threads are ignored.
+pub fn diff_processes(before: &[ProcessInfo], after: &[ProcessInfo]) -> Vec<DiffEntry> {
+    let key = |p: &ProcessInfo| (p.pid, p.start_time_ns);
+    let old: HashMap<(u32, u64), &ProcessInfo> = before
+        .iter()
+        .filter(|p| !p.is_thread)
+        .map(|p| (key(p), p))
+        .collect();
+    let new: HashMap<(u32, u64), &ProcessInfo> = after
+        .iter()
+        .filter(|p| !p.is_thread)
+        .map(|p| (key(p), p))
+        .collect();
+
+    let mut entries = Vec::new();
+    for (k, p) in &new {
+        match old.get(k) {
+            None => entries.push(entry(p, DiffKind::Appeared, None, Some(p))),
+            Some(o) if changed_significantly(o, p) => {
+                entries.push(entry(p, DiffKind::Changed, Some(o), Some(p)));
+            }
+            Some(_) => {}
+        }
+    }
+    for (k, o) in &old {
+        if !new.contains_key(k) {
+            entries.push(entry(o, DiffKind::Exited, Some(o), None));
+        }
+    }
+
+    // Parent links from both snapshots (T2 wins) so exited subtrees still
+    // sort under their former parent.
+    let ppid_of: HashMap<u32, u32> = before
+        .iter()
+        .chain(after.iter())
+        .filter(|p| !p.is_thread)
+        .map(|p| (p.pid, p.ppid))
+        .collect();
+    let in_diff: HashSet<u32> = entries.iter().map(|e| e.pid).collect();
+
+    // Root-to-self PID path: sorting by it yields depth-first tree order
+    let path_of = |pid: u32| -> Vec<u32> {
+        let mut path = vec![pid];
+        let mut current = pid;
+        while let Some(&parent) = ppid_of.get(&current) {
+            if parent == 0 || parent == current || path.contains(&parent) {
+                break;
+            }
+            path.push(parent);
+            current = parent;
+        }
+        path.reverse();
+        path
+    };
+
+    let mut keyed: Vec<(Vec<u32>, DiffEntry)> = entries
+        .into_iter()
+        .map(|mut e| {
+            let path = path_of(e.pid);
+            e.depth = path[..path.len() - 1]
+                .iter()
+                .filter(|pid| in_diff.contains(pid))
+                .count();
+            (path, e)
+        })
+        .collect();
+    // Exited before appeared so a recycled PID reads in chronological order
+    keyed.sort_by(|(pa, a), (pb, b)| {
+        pa.cmp(pb)
+            .then((b.kind == DiffKind::Exited).cmp(&(a.kind == DiffKind::Exited)))
+    });
+    keyed.into_iter().map(|(_, e)| e).collect()
+}
+
+fn changed_significantly(old: &ProcessInfo, new: &ProcessInfo) -> bool {
+    if (new.cpu_percent - old.cpu_percent).abs() >= CPU_DELTA_THRESHOLD {
+        return true;
+    }
+    let delta = new.res_bytes.abs_diff(old.res_bytes);
+    let base = old.res_bytes.max(1) as f64;
+    delta >= RES_DELTA_MIN_BYTES && delta as f64 / base >= RES_DELTA_RATIO
+}
+
+fn entry(
+    p: &ProcessInfo,
+    kind: DiffKind,
+    before: Option<&ProcessInfo>,
+    after: Option<&ProcessInfo>,
+) -> DiffEntry {
+    DiffEntry {
+        pid: p.pid,
+        kind,
+        comm: p.comm.clone(),
+        depth: 0,
+        cpu_before: before.map_or(0.0, |b| b.cpu_percent),
+        cpu_after: after.map_or(0.0, |a| a.cpu_percent),
+        res_before: before.map_or(0, |b| b.res_bytes),
+        res_after: after.map_or(0, |a| a.res_bytes),
+    }
+}
@@ -0,0 +1,48 @@
+use std::fs;
+use std::io;
+
+/// One `NAME=value` entry of a process's environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvVar {
+    pub name: String,
+    pub value: String,
+}
+
+/// The environment `pid` was started with, in the order exec received it.
+/// /proc/<pid>/environ is the block handed to exec, so variables the
+/// process set or unset afterwards don't show. Reading another user's
+/// process needs the same rights as ptrace.
+pub fn read_environ(pid: u32) -> io::Result<Vec<EnvVar>> {
+    fs::read(format!("/proc/{pid}/environ")).map(|raw| parse_environ(&raw))
+}
+
+/// Split a NUL-separated environment block. Entries without `=` are kept
+/// with an empty value; nothing stops a process from passing them.
+fn parse_environ(raw: &[u8]) -> Vec<EnvVar> {
+    raw.split(|&b| b == 0)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let entry = String::from_utf8_lossy(entry);
+            let (name, value) = entry.split_once('=').unwrap_or((&entry, ""));
+            EnvVar {
+                name: name.to_string(),
+                value: value.to_string(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nul_separated_entries() {
+        let vars = parse_environ(b"PATH=/usr/bin:/bin\0EMPTY=\0OPTS=a=b\0BARE\0\0");
+        let pairs: Vec<(&str, &str)> = vars.iter().map(|v| (v.name.as_str(), v.value.as_str())).collect();
+        assert_eq!(
+            pairs,
+            [("PATH", "/usr/bin:/bin"), ("EMPTY", ""), ("OPTS", "a=b"), ("BARE", "")]
+        );
+    }
+}
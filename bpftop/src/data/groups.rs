@@ -0,0 +1,134 @@
+use std::collections::{BTreeMap, HashSet};
+
+use super::container::{self, ContainerRuntime};
+use super::process::ProcessInfo;
+
+/// What the groups panel groups processes by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    /// The SERVICE column: systemd unit (as `S`/`A` show it) or
+    /// runit/s6/OpenRC service.
+    Service,
+    /// The CONTAINER column, from the cgroup the process runs in.
+    Container,
+}
+
+impl GroupBy {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Service => "Services",
+            Self::Container => "Containers",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Service => Self::Container,
+            Self::Container => Self::Service,
+        }
+    }
+
+    /// Filter term selecting one group's processes in the table.
+    pub fn filter(&self, name: &str) -> String {
+        match self {
+            Self::Service => format!("unit:{name}"),
+            Self::Container => format!("container:{name}"),
+        }
+    }
+
+    fn key(&self, proc: &ProcessInfo) -> Option<String> {
+        match self {
+            Self::Service => proc.service.clone(),
+            Self::Container => proc.container.clone(),
+        }
+    }
+}
+
+/// One group with its processes and what they add up to.
+#[derive(Debug, Clone)]
+pub struct Group {
+    pub name: String,
+    /// Container runtime, for containers.
+    pub runtime: Option<ContainerRuntime>,
+    pub cpu_percent: f64,
+    pub mem_percent: f64,
+    pub res_bytes: u64,
+    /// Member processes, busiest first.
+    pub members: Vec<GroupMember>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GroupMember {
+    pub pid: u32,
+    pub user: String,
+    pub comm: String,
+    pub cpu_percent: f64,
+    pub mem_percent: f64,
+    pub res_bytes: u64,
+}
+
+/// A row of the groups panel: a group, or (when expanded) one of its
+/// processes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupRow {
+    Group(usize),
+    Member(usize, usize),
+}
+
+/// Groups of `processes` by name, so rows stay put between refreshes.
+/// Threads are left out (their process already counts them), as are
+/// processes outside any group, like kernel threads.
+pub fn summarize(processes: &[ProcessInfo], by: GroupBy) -> Vec<Group> {
+    let mut groups: BTreeMap<String, Group> = BTreeMap::new();
+    for proc in processes {
+        if proc.is_thread || proc.exited.is_some() {
+            continue;
+        }
+        let Some(name) = by.key(proc) else {
+            continue;
+        };
+        let g = groups.entry(name.clone()).or_insert_with(|| Group {
+            name,
+            runtime: match by {
+                GroupBy::Service => None,
+                GroupBy::Container => {
+                    container::parse_container_from_cgroup(&proc.cgroup_path).map(|c| c.runtime)
+                }
+            },
+            cpu_percent: 0.0,
+            mem_percent: 0.0,
+            res_bytes: 0,
+            members: Vec::new(),
+        });
+        g.cpu_percent += proc.cpu_percent;
+        g.mem_percent += proc.mem_percent;
+        g.res_bytes += proc.res_bytes;
+        g.members.push(GroupMember {
+            pid: proc.pid,
+            user: proc.user.clone(),
+            comm: proc.comm.clone(),
+            cpu_percent: proc.cpu_percent,
+            mem_percent: proc.mem_percent,
+            res_bytes: proc.res_bytes,
+        });
+    }
+    let mut groups: Vec<Group> = groups.into_values().collect();
+    for g in &mut groups {
+        g.members
+            .sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent).then(a.pid.cmp(&b.pid)));
+    }
+    groups
+}
+
+/// Panel rows: every group, followed by its members if it is in
+/// `expanded`.
+pub fn rows(groups: &[Group], expanded: &HashSet<String>) -> Vec<GroupRow> {
+    let mut rows = Vec::new();
+    for (i, g) in groups.iter().enumerate() {
+        rows.push(GroupRow::Group(i));
+        if expanded.contains(&g.name) {
+            rows.extend((0..g.members.len()).map(|j| GroupRow::Member(i, j)));
+        }
+    }
+    rows
+}
@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use super::container::{resolve_service_from_path, ServiceDisplayMode};
+use super::process::ProcessInfo;
+use super::supervisor::RUNSV_MAX_DEPTH;
+
+/// PID 1 names of the minimal inits container runtimes inject
+/// (`docker run --init` is tini under another name).
+const CONTAINER_INITS: &[&str] = &["tini", "docker-init", "dumb-init", "catatonit", "podman-init"];
+
+/// What runs as PID 1, which decides where a process's service name
+/// comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InitSystem {
+    /// Services are systemd units, read off the cgroup path.
+    Systemd,
+    /// Services run in `openrc.<name>` cgroups.
+    OpenRc,
+    /// Services are supervised by a `runsv <name>` ancestor.
+    Runit,
+    /// We're inside a container; holds PID 1's name.
+    Container(String),
+    /// Anything else (sysvinit, busybox, s6...); holds PID 1's name.
+    Other(String),
+}
+
+impl InitSystem {
+    /// Look at PID 1 of our PID namespace and the markers each init or
+    /// container runtime leaves behind.
+    pub fn detect() -> Self {
+        let comm = fs::read_to_string("/proc/1/comm").unwrap_or_default();
+        let in_container = Path::new("/.dockerenv").exists()
+            || Path::new("/run/.containerenv").exists();
+        Self::classify(comm.trim(), Path::new("/run/openrc").is_dir(), in_container)
+    }
+
+    fn classify(comm: &str, openrc_running: bool, in_container: bool) -> Self {
+        match comm {
+            // A systemd container still has units to show
+            "systemd" => Self::Systemd,
+            "runit" | "runit-init" => Self::Runit,
+            "openrc-init" => Self::OpenRc,
+            // OpenRC usually runs under sysvinit or busybox init
+            _ if openrc_running => Self::OpenRc,
+            _ if in_container || CONTAINER_INITS.contains(&comm) => Self::Container(comm.to_string()),
+            _ => Self::Other(comm.to_string()),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::Systemd => "systemd".to_string(),
+            Self::OpenRc => "openrc".to_string(),
+            Self::Runit => "runit".to_string(),
+            Self::Container(name) => format!("container ({name})"),
+            Self::Other(name) if name.is_empty() => "unknown".to_string(),
+            Self::Other(name) => name.clone(),
+        }
+    }
+
+    /// PID -> runit service for every process below a `runsv`, so the
+    /// parent chain is walked once per refresh rather than per lookup.
+    /// Empty unless runit is PID 1.
+    pub fn supervised(&self, processes: &[&ProcessInfo]) -> HashMap<u32, String> {
+        let mut services = HashMap::new();
+        if *self != Self::Runit {
+            return services;
+        }
+        let by_pid: HashMap<u32, &ProcessInfo> = processes
+            .iter()
+            .filter(|p| !p.is_thread)
+            .map(|p| (p.pid, *p))
+            .collect();
+        for proc in by_pid.values() {
+            let mut ppid = proc.ppid;
+            for _ in 0..RUNSV_MAX_DEPTH {
+                let Some(parent) = by_pid.get(&ppid) else {
+                    break;
+                };
+                if parent.comm == "runsv" {
+                    // runsvdir starts `runsv <service dir>` from the scan directory
+                    if let Some(dir) = parent.cmdline.split_whitespace().nth(1) {
+                        let name = dir.trim_end_matches('/').rsplit('/').next().unwrap_or(dir);
+                        services.insert(proc.pid, name.to_string());
+                    }
+                    break;
+                }
+                ppid = parent.ppid;
+            }
+        }
+        services
+    }
+
+    /// Service name for the SERVICE column. Falls back to systemd units
+    /// in the cgroup path, since the eBPF side also sees the host's
+    /// processes when we run in a container.
+    pub fn service(
+        &self,
+        proc: &ProcessInfo,
+        supervised: &HashMap<u32, String>,
+        mode: ServiceDisplayMode,
+    ) -> Option<String> {
+        let own = match self {
+            Self::OpenRc if mode != ServiceDisplayMode::FullSlice => proc
+                .cgroup_path
+                .rsplit('/')
+                .find_map(|seg| seg.strip_prefix("openrc."))
+                .map(str::to_string),
+            Self::Runit => supervised.get(&proc.pid).cloned(),
+            _ => None,
+        };
+        own.or_else(|| resolve_service_from_path(&proc.cgroup_path, mode))
+    }
+}
@@ -1,6 +1,14 @@
-pub mod cgroup_control;
-pub mod collector;
-pub mod container;
-pub mod gpu;
-pub mod process;
-pub mod system;
+//! Process data the TUI works with beyond what bpftop-core collects: its
+//! modules are re-exported here next to the UI-only ones.
+
+pub use bpftop_core::data::*;
+
+pub mod cgroup_tree;
+pub mod diff;
+pub mod environ;
+pub mod groups;
+pub mod init_system;
+pub mod pidfd;
+pub mod runtime_probe;
+pub mod spawn;
+pub mod supervisor;
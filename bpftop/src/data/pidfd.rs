@@ -0,0 +1,90 @@
+use std::fs;
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+use super::system::read_suspended_ns;
+
+/// A process pinned by a pidfd. Signals sent through it reach the process
+/// it was opened for or nothing, even if its PID has been reused since.
+pub struct PidFd {
+    pid: u32,
+    /// `None` on kernels without pidfd_open (before 5.3), where signals
+    /// go through kill() after all.
+    fd: Option<OwnedFd>,
+}
+
+impl PidFd {
+    /// Pin `pid`, checking it is still the process that started at
+    /// `start_time_ns` (CLOCK_MONOTONIC, as the task iterator reports it).
+    /// Fails with `NotFound` if it exited or its PID now belongs to
+    /// another process.
+    pub fn open(pid: u32, start_time_ns: u64) -> io::Result<Self> {
+        // SAFETY: pidfd_open takes a PID and flags and returns a new FD
+        let ret = unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) };
+        let fd = if ret >= 0 {
+            // SAFETY: a fresh FD nothing else owns
+            Some(unsafe { OwnedFd::from_raw_fd(ret as i32) })
+        } else {
+            match io::Error::last_os_error() {
+                e if e.raw_os_error() == Some(libc::ENOSYS) => None,
+                e if e.raw_os_error() == Some(libc::ESRCH) => return Err(gone(pid)),
+                e => return Err(e),
+            }
+        };
+        // Checked after opening: if /proc still shows the same process,
+        // the pidfd refers to it
+        if !started_at(pid, start_time_ns) {
+            return Err(gone(pid));
+        }
+        Ok(Self { pid, fd })
+    }
+
+    /// Send `signal` to the pinned process.
+    pub fn send_signal(&self, signal: i32) -> io::Result<()> {
+        let ret = match &self.fd {
+            // SAFETY: a pidfd we own; no siginfo, no flags
+            Some(fd) => unsafe {
+                libc::syscall(
+                    libc::SYS_pidfd_send_signal,
+                    fd.as_raw_fd(),
+                    signal,
+                    std::ptr::null::<libc::siginfo_t>(),
+                    0,
+                )
+            },
+            // SAFETY: plain kill(2)
+            None => unsafe { libc::kill(self.pid as libc::pid_t, signal) }.into(),
+        };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+fn gone(pid: u32) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, format!("PID {pid} exited or was reused"))
+}
+
+/// Whether /proc/<pid> is the process that started at `start_time_ns`.
+/// /proc counts start time in clock ticks on CLOCK_BOOTTIME, which runs
+/// ahead of CLOCK_MONOTONIC by the time spent suspended before the process
+/// started: anything from none to all of it so far.
+fn started_at(pid: u32, start_time_ns: u64) -> bool {
+    let Ok(stat) = fs::read_to_string(format!("/proc/{pid}/stat")) else {
+        return false;
+    };
+    // comm may contain spaces and parentheses; fields resume after the last ')'
+    let Some(ticks) = stat
+        .rsplit_once(')')
+        .and_then(|(_, rest)| rest.split_whitespace().nth(19))
+        .and_then(|s| s.parse::<u64>().ok())
+    else {
+        return false;
+    };
+    // SAFETY: sysconf has no preconditions
+    let hz = unsafe { libc::sysconf(libc::_SC_CLK_TCK) }.max(1) as u64;
+    let tick_ns = 1_000_000_000 / hz;
+    let boot_ns = ticks * tick_ns;
+    boot_ns + tick_ns >= start_time_ns && boot_ns <= start_time_ns + read_suspended_ns() + tick_ns
+}
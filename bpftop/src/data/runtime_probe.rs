@@ -0,0 +1,142 @@
+use std::io;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
+use super::process::ProcessInfo;
+
+/// A language runtime whose own tooling can show what a process is doing
+/// beyond what the kernel sees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Runtime {
+    Jvm,
+    Python,
+}
+
+impl Runtime {
+    /// Runtime of `proc`, from its comm or the program in its cmdline
+    /// (launchers often rename the main thread).
+    pub fn detect(proc: &ProcessInfo) -> Option<Self> {
+        if proc.is_kernel_thread {
+            return None;
+        }
+        let program = proc.cmdline.split_whitespace().next().unwrap_or("");
+        let program = program.rsplit('/').next().unwrap_or(program);
+        let is_python = |name: &str| {
+            name.strip_prefix("python")
+                .is_some_and(|v| v.chars().all(|c| c.is_ascii_digit() || c == '.'))
+        };
+        if proc.comm == "java" || program == "java" {
+            Some(Self::Jvm)
+        } else if is_python(&proc.comm) || is_python(program) {
+            Some(Self::Python)
+        } else {
+            None
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Jvm => "JVM",
+            Self::Python => "Python",
+        }
+    }
+
+    /// The external tool and what it is asked for.
+    fn command(self, pid: u32) -> Command {
+        let pid = pid.to_string();
+        match self {
+            Self::Jvm => {
+                let mut c = Command::new("jcmd");
+                c.args([pid.as_str(), "Thread.print"]);
+                c
+            }
+            Self::Python => {
+                let mut c = Command::new("py-spy");
+                c.args(["dump", "--pid", pid.as_str()]);
+                c
+            }
+        }
+    }
+
+    fn tool(self) -> &'static str {
+        match self {
+            Self::Jvm => "jcmd",
+            Self::Python => "py-spy",
+        }
+    }
+}
+
+/// A thread dump being fetched in the background. The tools can take a
+/// few seconds (jcmd waits for a safepoint), so the UI polls for it.
+pub struct RuntimeProbe {
+    pub pid: u32,
+    pub comm: String,
+    pub runtime: Runtime,
+    /// Output lines, or why there are none; `None` while still running.
+    pub result: Option<Result<Vec<String>, String>>,
+    rx: Receiver<Result<Vec<String>, String>>,
+}
+
+impl RuntimeProbe {
+    pub fn start(proc: &ProcessInfo, runtime: Runtime) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let pid = proc.pid;
+        std::thread::spawn(move || {
+            let _ = tx.send(run(runtime, pid));
+        });
+        Self {
+            pid,
+            comm: proc.comm.clone(),
+            runtime,
+            result: None,
+            rx,
+        }
+    }
+
+    /// Pick up the result if it has arrived; true when it just did.
+    pub fn poll(&mut self) -> bool {
+        if self.result.is_some() {
+            return false;
+        }
+        match self.rx.try_recv() {
+            Ok(result) => self.result = Some(result),
+            Err(TryRecvError::Empty) => return false,
+            Err(TryRecvError::Disconnected) => {
+                self.result = Some(Err(format!("{} did not finish", self.runtime.tool())))
+            }
+        }
+        true
+    }
+
+    /// Short description of what is being run, for the popup title.
+    pub fn describe(&self) -> String {
+        let what = match self.runtime {
+            Runtime::Jvm => "jcmd Thread.print",
+            Runtime::Python => "py-spy dump",
+        };
+        format!("{} {} ({}): {what}", self.runtime.label(), self.pid, self.comm)
+    }
+}
+
+fn run(runtime: Runtime, pid: u32) -> Result<Vec<String>, String> {
+    let output = match runtime.command(pid).stdin(Stdio::null()).output() {
+        Ok(output) => output,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Err(format!("{} is not installed (not found in PATH)", runtime.tool()));
+        }
+        Err(e) => return Err(format!("{}: {e}", runtime.tool())),
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        // jcmd reports attach failures on stdout, py-spy on stderr
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr
+            .lines()
+            .chain(stdout.lines())
+            .find(|l| !l.trim().is_empty())
+            .map(|l| l.trim().to_string())
+            .unwrap_or_else(|| output.status.to_string());
+        return Err(format!("{}: {reason}", runtime.tool()));
+    }
+    Ok(stdout.lines().map(|l| l.replace('\t', "    ")).collect())
+}
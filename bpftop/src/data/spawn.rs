@@ -0,0 +1,250 @@
+use std::ffi::CString;
+use std::io::{self, BufRead, BufReader};
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+use super::cgroup_control;
+use super::process::{parse_quantity, Unit};
+
+/// Numbers the scopes/cgroups of one bpftop instance.
+static SPAWN_SEQ: AtomicU32 = AtomicU32::new(1);
+
+/// How often the reaper checks whether a spawned command has exited.
+const REAP_POLL: Duration = Duration::from_millis(200);
+
+/// A command to run from `:spawn`, with optional resource limits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpawnRequest {
+    /// Program and arguments.
+    pub argv: Vec<String>,
+    /// CPU limit in percent of one CPU (200 = two CPUs).
+    pub cpu_percent: Option<u32>,
+    /// Memory limit in bytes.
+    pub memory_max: Option<u64>,
+}
+
+impl SpawnRequest {
+    /// Parse `[--cpu 50%] [--mem 512M] [--] command args...`. Arguments are
+    /// split on whitespace; there is no shell quoting.
+    pub fn parse(args: &str) -> Result<Self, String> {
+        let mut words = args.split_whitespace().peekable();
+        let mut req = Self {
+            argv: Vec::new(),
+            cpu_percent: None,
+            memory_max: None,
+        };
+        while let Some(&word) = words.peek() {
+            let (flag, inline) = match word.split_once('=') {
+                Some((flag, value)) => (flag, Some(value)),
+                None => (word, None),
+            };
+            if !matches!(flag, "--cpu" | "--mem" | "--") {
+                break;
+            }
+            words.next();
+            if flag == "--" {
+                break;
+            }
+            let value = inline
+                .or_else(|| words.next())
+                .ok_or_else(|| format!("{flag} needs a value"))?;
+            if flag == "--cpu" {
+                let pct = parse_quantity(value, Unit::Percent)
+                    .filter(|p| *p >= 1.0)
+                    .ok_or_else(|| format!("bad CPU limit '{value}' (e.g. 50% or 200%)"))?;
+                req.cpu_percent = Some(pct.round() as u32);
+            } else {
+                let bytes = parse_quantity(value, Unit::Bytes)
+                    .filter(|b| *b >= 1.0)
+                    .ok_or_else(|| format!("bad memory limit '{value}' (e.g. 512M or 2G)"))?;
+                req.memory_max = Some(bytes as u64);
+            }
+        }
+        req.argv = words.map(str::to_string).collect();
+        if req.argv.is_empty() {
+            return Err("usage: spawn [--cpu 50%] [--mem 512M] command args...".to_string());
+        }
+        Ok(req)
+    }
+
+    /// The limits as systemd properties, e.g. "CPUQuota=50% MemoryMax=512M".
+    pub fn describe_limits(&self) -> String {
+        let mut limits = Vec::new();
+        if let Some(pct) = self.cpu_percent {
+            limits.push(format!("CPUQuota={pct}%"));
+        }
+        if let Some(bytes) = self.memory_max {
+            limits.push(format!("MemoryMax={bytes}"));
+        }
+        limits.join(" ")
+    }
+}
+
+/// A process launched by `:spawn`. A reaper thread waits for it and sends
+/// one line describing how it ended.
+pub struct Spawned {
+    pub pid: u32,
+    /// Transient unit name, or cgroup path when systemd isn't used.
+    pub scope: String,
+    pub exited: Receiver<String>,
+}
+
+/// Launch `req` in its own scope: a transient systemd scope when systemd
+/// is PID 1, otherwise a cgroup under /bpftop.spawn. The command's output
+/// is discarded since the terminal belongs to the TUI.
+pub fn spawn(req: &SpawnRequest, systemd: bool) -> Result<Spawned, String> {
+    let name = format!(
+        "bpftop-spawn-{}-{}",
+        std::process::id(),
+        SPAWN_SEQ.fetch_add(1, Ordering::Relaxed)
+    );
+    let (mut cmd, scope, cgroup) = if systemd {
+        let unit = format!("{name}.scope");
+        let mut c = Command::new("systemd-run");
+        if !nix::unistd::geteuid().is_root() {
+            c.arg("--user");
+        }
+        // --scope execs the command in place, so its PID is the child's
+        c.args(["--scope", "--quiet", "--collect"]).arg(format!("--unit={unit}"));
+        if let Some(pct) = req.cpu_percent {
+            c.arg("-p").arg(format!("CPUQuota={pct}%"));
+        }
+        if let Some(bytes) = req.memory_max {
+            c.arg("-p").arg(format!("MemoryMax={bytes}"));
+        }
+        c.arg("--").args(&req.argv);
+        (c, unit, None)
+    } else {
+        let path = cgroup_control::create_spawn_cgroup(&name, req.cpu_percent, req.memory_max)
+            .map_err(|e| format!("cannot create cgroup: {e}"))?;
+        let mut c = Command::new(&req.argv[0]);
+        c.args(&req.argv[1..]);
+        join_cgroup_before_exec(&mut c, &path);
+        (c, path.clone(), Some(path))
+    };
+
+    let program = req.argv[0].clone();
+    let tool = if systemd { "systemd-run" } else { program.as_str() };
+    let mut child = match cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            if let Some(path) = &cgroup {
+                let _ = cgroup_control::remove_cgroup(path);
+            }
+            return Err(if e.kind() == io::ErrorKind::NotFound {
+                format!("{tool}: not found in PATH")
+            } else {
+                format!("{tool}: {e}")
+            });
+        }
+    };
+
+    let pid = child.id();
+    // Keep draining stderr so the command never blocks on a full pipe,
+    // passing on only the first line (systemd-run's error, if any). A
+    // daemon the command leaves behind can hold the pipe open long after
+    // it exits, so the reaper below doesn't wait for EOF
+    let (line_tx, first_line) = mpsc::channel();
+    if let Some(stderr) = child.stderr.take() {
+        std::thread::spawn(move || {
+            let mut lines = BufReader::new(stderr).lines().map_while(Result::ok);
+            if let Some(line) = lines.by_ref().find(|l| !l.trim().is_empty()) {
+                let _ = line_tx.send(line.trim().to_string());
+            }
+            lines.for_each(drop);
+        });
+    }
+
+    let (tx, exited) = mpsc::channel();
+    std::thread::spawn(move || {
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break Ok(status),
+                Ok(None) => std::thread::sleep(REAP_POLL),
+                Err(e) => break Err(e),
+            }
+        };
+        let msg = match status {
+            Ok(status) if status.success() => format!("{program} (PID {pid}) finished"),
+            Ok(status) => {
+                // Written before the exit, but the drain thread may not
+                // have read it yet
+                let reason = first_line
+                    .recv_timeout(REAP_POLL)
+                    .unwrap_or_else(|_| status.to_string());
+                format!("{program} (PID {pid}) exited: {reason}")
+            }
+            Err(e) => format!("{program} (PID {pid}): {e}"),
+        };
+        if let Some(path) = cgroup {
+            let _ = cgroup_control::remove_cgroup(&path);
+        }
+        let _ = tx.send(msg);
+    });
+
+    Ok(Spawned { pid, scope, exited })
+}
+
+/// Have the child move itself into `cgroup_path` between fork and exec, so
+/// nothing it starts escapes the limits.
+fn join_cgroup_before_exec(cmd: &mut Command, cgroup_path: &str) {
+    let procs = CString::new(cgroup_control::procs_file(cgroup_path))
+        .expect("cgroup path contains no NUL");
+    // SAFETY: only async-signal-safe calls (open/write/close) run in the
+    // forked child; the path was allocated before the fork.
+    unsafe {
+        cmd.pre_exec(move || {
+            // Writing "0" to cgroup.procs moves the writing process
+            let fd = libc::open(procs.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let written = libc::write(fd, b"0".as_ptr().cast(), 1);
+            let err = io::Error::last_os_error();
+            libc::close(fd);
+            if written == 1 {
+                Ok(())
+            } else {
+                Err(err)
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_limits_and_command() {
+        let req = SpawnRequest::parse("--cpu 50% --mem=512M make -j8").unwrap();
+        assert_eq!(req.argv, ["make", "-j8"]);
+        assert_eq!(req.cpu_percent, Some(50));
+        assert_eq!(req.memory_max, Some(512 * 1024 * 1024));
+        assert_eq!(req.describe_limits(), "CPUQuota=50% MemoryMax=536870912");
+
+        // Flags stop at the first other word or at --
+        let req = SpawnRequest::parse("sleep --cpu 10").unwrap();
+        assert_eq!(req.argv, ["sleep", "--cpu", "10"]);
+        assert_eq!(req.cpu_percent, None);
+        let req = SpawnRequest::parse("--cpu=200 -- --mem 1G").unwrap();
+        assert_eq!(req.argv, ["--mem", "1G"]);
+        assert_eq!(req.cpu_percent, Some(200));
+        assert_eq!(req.memory_max, None);
+    }
+
+    #[test]
+    fn rejects_bad_requests() {
+        for args in ["", "--cpu 50%", "--mem", "--cpu 0 ls", "--cpu lots ls", "--mem 1X ls", "--"] {
+            assert!(SpawnRequest::parse(args).is_err(), "{args:?}");
+        }
+    }
+}
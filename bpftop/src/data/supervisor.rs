@@ -0,0 +1,156 @@
+use std::fs;
+use std::io;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
+use super::container::extract_scope_id;
+use super::process::ProcessInfo;
+
+/// How far up the parent chain to look for a runit `runsv`.
+pub(super) const RUNSV_MAX_DEPTH: usize = 8;
+
+/// Something that will respawn a process if it is killed, and can restart
+/// it properly instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Supervisor {
+    /// A systemd unit; `user` is set for units of a per-user manager.
+    Systemd { unit: String, user: Option<String> },
+    Docker(String),
+    Podman(String),
+    /// A runit service directory (runsv's working directory).
+    Runit(String),
+}
+
+impl Supervisor {
+    /// Find the supervisor of `proc`, from its cgroup path or, for runit,
+    /// its parent chain.
+    pub fn detect(proc: &ProcessInfo, all: &[ProcessInfo]) -> Option<Self> {
+        let path = &proc.cgroup_path;
+        if let Some(id) = extract_scope_id(path, "docker-") {
+            return Some(Self::Docker(id));
+        }
+        if let Some(id) = extract_scope_id(path, "libpod-") {
+            return Some(Self::Podman(id));
+        }
+        if let Some(dir) = runsv_dir(proc, all) {
+            return Some(Self::Runit(dir));
+        }
+
+        let unit = path.rsplit('/').find(|seg| seg.ends_with(".service"))?;
+        // Restarting a user manager would end the whole login session
+        if unit.starts_with("user@") {
+            return None;
+        }
+        let user = path.contains("/user@").then(|| proc.user.clone());
+        Some(Self::Systemd {
+            unit: unit.to_string(),
+            user,
+        })
+    }
+
+    /// Short description for the kill dialog, e.g. "systemd: sshd.service".
+    pub fn label(&self) -> String {
+        match self {
+            Self::Systemd { unit, user: None } => format!("systemd: {unit}"),
+            Self::Systemd { unit, user: Some(u) } => format!("systemd --user ({u}): {unit}"),
+            Self::Docker(id) => format!("docker: {}", &id[..id.len().min(12)]),
+            Self::Podman(id) => format!("podman: {}", &id[..id.len().min(12)]),
+            Self::Runit(dir) => format!("runit: {dir}"),
+        }
+    }
+
+    /// Ask the supervisor to restart the service, in the background.
+    pub fn restart(&self) -> PendingRestart {
+        let (tx, rx) = mpsc::channel();
+        let sup = self.clone();
+        std::thread::spawn(move || {
+            let _ = tx.send(sup.run_restart());
+        });
+        PendingRestart {
+            label: self.label(),
+            rx,
+        }
+    }
+
+    /// systemd restarts are queued (`--no-block`); the others wait for the
+    /// tool to finish.
+    fn run_restart(&self) -> io::Result<()> {
+        let mut cmd = match self {
+            Self::Systemd { unit, user } => {
+                let mut c = Command::new("systemctl");
+                if let Some(user) = user {
+                    c.arg("--user").arg(format!("--machine={user}@"));
+                }
+                c.args(["--no-block", "restart", unit]);
+                c
+            }
+            Self::Docker(id) => {
+                let mut c = Command::new("docker");
+                c.args(["restart", id]);
+                c
+            }
+            Self::Podman(id) => {
+                let mut c = Command::new("podman");
+                c.args(["restart", id]);
+                c
+            }
+            Self::Runit(dir) => {
+                let mut c = Command::new("sv");
+                c.args(["restart", dir]);
+                c
+            }
+        };
+        let output = cmd.stdin(Stdio::null()).output()?;
+        if output.status.success() {
+            return Ok(());
+        }
+        // Surface the tool's own complaint (e.g. "Unit foo.service not found")
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr
+            .lines()
+            .find(|l| !l.trim().is_empty())
+            .map(|l| l.trim().to_string())
+            .unwrap_or_else(|| output.status.to_string());
+        Err(io::Error::other(reason))
+    }
+}
+
+/// A supervisor restart still running. `docker restart` waits out the
+/// container's stop timeout, so the UI polls for the outcome.
+pub struct PendingRestart {
+    /// `Supervisor::label` of the supervisor asked.
+    pub label: String,
+    rx: Receiver<io::Result<()>>,
+}
+
+impl PendingRestart {
+    /// The outcome once the tool has finished, `None` while it runs.
+    pub fn poll(&self) -> Option<io::Result<()>> {
+        match self.rx.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(io::Error::other("did not finish"))),
+        }
+    }
+}
+
+/// Service directory of the nearest `runsv` ancestor, if any.
+fn runsv_dir(proc: &ProcessInfo, all: &[ProcessInfo]) -> Option<String> {
+    let mut ppid = proc.ppid;
+    for _ in 0..RUNSV_MAX_DEPTH {
+        let parent = all.iter().find(|p| p.tid == ppid)?;
+        if parent.comm == "runsv" {
+            // runsv chdirs into the service directory; argv[1] is relative
+            // to wherever runsvdir was started, so prefer the cwd
+            return fs::read_link(format!("/proc/{}/cwd", parent.pid))
+                .ok()
+                .map(|p| p.display().to_string())
+                .or_else(|| parent.cmdline.split_whitespace().nth(1).map(str::to_string));
+        }
+        if parent.ppid == 0 || parent.ppid == parent.pid {
+            return None;
+        }
+        ppid = parent.ppid;
+    }
+    None
+}
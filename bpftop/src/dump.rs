@@ -0,0 +1,250 @@
+use std::borrow::Cow;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::config::SnapshotConfig;
+use crate::data::process::ProcessInfo;
+use crate::data::system::SystemInfo;
+use crate::redact::Redact;
+
+const FILE_PREFIX: &str = "bpftop-";
+const FILE_SUFFIX: &str = ".json";
+
+/// Version of bpftop's machine-readable output: snapshot dumps, `--batch`
+/// JSON and CSV, and the trends database. Within a version, fields and
+/// columns are only ever added (CSV columns at the end); renaming,
+/// removing or changing the meaning of one bumps it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Periodically writes the full process table to JSON files so spikes that
+/// happen while nobody is watching can be inspected afterwards.
+pub struct SnapshotDumper {
+    dir: PathBuf,
+    interval: Duration,
+    keep: usize,
+    last: Option<Instant>,
+    redact: Redact,
+}
+
+#[derive(Serialize)]
+struct DumpFile<'a> {
+    schema_version: u32,
+    timestamp: u64,
+    hostname: String,
+    system: SystemRecord,
+    processes: Vec<ProcessRecord<'a>>,
+}
+
+#[derive(Serialize)]
+struct SystemRecord {
+    cpu_percent: f64,
+    mem_total_bytes: u64,
+    mem_used_bytes: u64,
+    swap_total_bytes: u64,
+    swap_used_bytes: u64,
+    load_avg: [f64; 3],
+    uptime_secs: f64,
+    total_tasks: u32,
+    running_tasks: u32,
+}
+
+#[derive(Serialize)]
+struct ProcessRecord<'a> {
+    pid: u32,
+    ppid: u32,
+    pgid: u32,
+    sid: u32,
+    tty: Option<&'a str>,
+    user: Cow<'a, str>,
+    state: char,
+    priority: i32,
+    nice: i32,
+    virt_bytes: u64,
+    res_bytes: u64,
+    shr_bytes: u64,
+    cpu_percent: f64,
+    mem_percent: f64,
+    gpu_percent: f64,
+    gpu_mem_bytes: u64,
+    net_rx_bytes: u64,
+    net_tx_bytes: u64,
+    net_rx_rate: f64,
+    net_tx_rate: f64,
+    disk_read_bytes: u64,
+    disk_write_bytes: u64,
+    fd_count: u32,
+    nvcsw: u64,
+    nivcsw: u64,
+    runq_wait_ns: u64,
+    cpu_time_secs: f64,
+    comm: &'a str,
+    cmdline: Cow<'a, str>,
+    container: Option<&'a str>,
+    cgroup: &'a str,
+    is_thread: bool,
+    is_kernel_thread: bool,
+}
+
+impl SnapshotDumper {
+    /// Returns `None` when scheduled snapshots are disabled.
+    pub fn from_config(config: &SnapshotConfig, redact: Redact) -> Option<Self> {
+        if config.interval_minutes == 0 {
+            return None;
+        }
+        let dir = match config.dir() {
+            Ok(dir) => dir,
+            Err(e) => {
+                log::warn!("Snapshots disabled: {e:#}");
+                return None;
+            }
+        };
+        Some(Self {
+            dir,
+            interval: Duration::from_secs(config.interval_minutes * 60),
+            keep: config.keep.max(1),
+            last: None,
+            redact,
+        })
+    }
+
+    /// Write a snapshot if the interval has elapsed since the last one.
+    /// The first call always writes.
+    pub fn maybe_dump(&mut self, sys: &SystemInfo, processes: &[ProcessInfo]) {
+        if self.last.is_some_and(|t| t.elapsed() < self.interval) {
+            return;
+        }
+        self.last = Some(Instant::now());
+        match self.dump(sys, processes) {
+            Ok(path) => log::info!("Wrote snapshot {}", path.display()),
+            Err(e) => log::warn!("Snapshot dump failed: {e:#}"),
+        }
+        if let Err(e) = self.prune() {
+            log::warn!("Snapshot retention failed: {e:#}");
+        }
+    }
+
+    fn dump(&self, sys: &SystemInfo, processes: &[ProcessInfo]) -> Result<PathBuf> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("creating snapshot directory {}", self.dir.display()))?;
+
+        let file = DumpFile::new(sys, processes, &self.redact);
+        let path = self.dir.join(format!("{FILE_PREFIX}{}{FILE_SUFFIX}", file.timestamp));
+        let content = serde_json::to_string(&file).context("serializing snapshot")?;
+        fs::write(&path, content).with_context(|| format!("writing {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Delete all but the `keep` newest snapshot files.
+    fn prune(&self) -> Result<()> {
+        let mut files: Vec<(u64, PathBuf)> = fs::read_dir(&self.dir)
+            .with_context(|| format!("reading {}", self.dir.display()))?
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let name = path.file_name()?.to_str()?;
+                let ts = name
+                    .strip_prefix(FILE_PREFIX)?
+                    .strip_suffix(FILE_SUFFIX)?
+                    .parse()
+                    .ok()?;
+                Some((ts, path))
+            })
+            .collect();
+        if files.len() <= self.keep {
+            return Ok(());
+        }
+        files.sort_unstable_by_key(|(ts, _)| *ts);
+        let excess = files.len() - self.keep;
+        for (_, path) in files.into_iter().take(excess) {
+            fs::remove_file(&path).with_context(|| format!("removing {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Serialize one snapshot in the dump file format, as a single line.
+pub fn snapshot_json(sys: &SystemInfo, processes: &[ProcessInfo], redact: &Redact) -> Result<String> {
+    serde_json::to_string(&DumpFile::new(sys, processes, redact)).context("serializing snapshot")
+}
+
+impl<'a> DumpFile<'a> {
+    fn new(sys: &SystemInfo, processes: &'a [ProcessInfo], redact: &Redact) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        Self {
+            schema_version: SCHEMA_VERSION,
+            timestamp,
+            hostname: fs::read_to_string("/proc/sys/kernel/hostname")
+                .map(|h| h.trim().to_string())
+                .unwrap_or_default(),
+            system: SystemRecord {
+                cpu_percent: sys.cpu_total.total_pct,
+                mem_total_bytes: sys.memory.total,
+                mem_used_bytes: sys.memory.used,
+                swap_total_bytes: sys.swap.total,
+                swap_used_bytes: sys.swap.used,
+                load_avg: sys.load_avg,
+                uptime_secs: sys.uptime_secs,
+                total_tasks: sys.total_tasks,
+                running_tasks: sys.running_tasks,
+            },
+            processes: processes.iter().map(|p| ProcessRecord::new(p, redact)).collect(),
+        }
+    }
+}
+
+impl<'a> ProcessRecord<'a> {
+    fn new(p: &'a ProcessInfo, redact: &Redact) -> Self {
+        Self {
+            pid: p.pid,
+            ppid: p.ppid,
+            pgid: p.pgid,
+            sid: p.sid,
+            tty: p.tty.as_deref(),
+            user: redact.user(&p.user),
+            state: p.state.as_char(),
+            priority: p.priority,
+            nice: p.nice,
+            virt_bytes: p.virt_bytes,
+            res_bytes: p.res_bytes,
+            shr_bytes: p.shr_bytes,
+            cpu_percent: p.cpu_percent,
+            mem_percent: p.mem_percent,
+            gpu_percent: p.gpu_percent,
+            gpu_mem_bytes: p.gpu_mem_bytes,
+            net_rx_bytes: p.net_rx_bytes,
+            net_tx_bytes: p.net_tx_bytes,
+            net_rx_rate: p.net_rx_rate,
+            net_tx_rate: p.net_tx_rate,
+            disk_read_bytes: p.disk_read_bytes,
+            disk_write_bytes: p.disk_write_bytes,
+            fd_count: p.fd_count,
+            nvcsw: p.nvcsw,
+            nivcsw: p.nivcsw,
+            runq_wait_ns: p.runq_wait_ns,
+            cpu_time_secs: p.cpu_time_secs,
+            comm: &p.comm,
+            cmdline: redact.cmdline(&p.cmdline),
+            container: p.container.as_deref(),
+            cgroup: &p.cgroup_path,
+            is_thread: p.is_thread,
+            is_kernel_thread: p.is_kernel_thread,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_json_leads_with_schema_version() {
+        let json = snapshot_json(&SystemInfo::default(), &[], &Redact::default()).unwrap();
+        assert!(json.starts_with(&format!("{{\"schema_version\":{SCHEMA_VERSION},")));
+    }
+}
@@ -3,6 +3,7 @@ use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, Mouse
 use crate::app::{App, AppMode};
 use crate::data::container::ServiceDisplayMode;
 use crate::data::process::{SortColumn, YankField};
+use crate::ui::copy_mode::SelectionKind;
 use crate::ui::dialogs::signal_list;
 use crate::ui::filter_bar::FilterMode;
 use crate::ui::process_table;
@@ -34,6 +35,23 @@ fn handle_key(app: &mut App, key: KeyEvent) -> bool {
         AppMode::SortSelect => handle_sort_key(app, key),
         AppMode::Visual => handle_visual_key(app, key),
         AppMode::Freeze => handle_freeze_key(app, key),
+        AppMode::Files => handle_files_key(app, key),
+        AppMode::Diff => handle_diff_key(app, key),
+        AppMode::Connections => handle_connections_key(app, key),
+        AppMode::Detail => handle_detail_key(app, key),
+        AppMode::Execs => handle_execs_key(app, key),
+        AppMode::Ooms => handle_ooms_key(app, key),
+        AppMode::History => handle_history_key(app, key),
+        AppMode::BpfStats => handle_bpf_stats_key(app, key),
+        AppMode::BpfObjects => handle_bpf_objects_key(app, key),
+        AppMode::Runtime => handle_runtime_key(app, key),
+        AppMode::Environ => handle_environ_key(app, key),
+        AppMode::Sessions => handle_sessions_key(app, key),
+        AppMode::Cgroups => handle_cgroups_key(app, key),
+        AppMode::Groups => handle_groups_key(app, key),
+        AppMode::Command => handle_command_key(app, key),
+        AppMode::Copy => handle_copy_key(app, key),
+        AppMode::Setup => handle_setup_key(app, key),
     }
 }
 
@@ -70,6 +88,14 @@ fn handle_normal_key(app: &mut App, key: KeyEvent) -> bool {
                 }
                 return false;
             }
+            ('m', KeyCode::Char(c)) if c.is_ascii_lowercase() => {
+                app.set_mark(c);
+                return false;
+            }
+            ('\'', KeyCode::Char(c)) if c.is_ascii_lowercase() => {
+                app.jump_to_mark(c);
+                return false;
+            }
             ('z', KeyCode::Char(c)) if app.tree_view => {
                 match c {
                     'o' => app.expand_tree_node(),
@@ -112,6 +138,9 @@ fn handle_normal_key(app: &mut App, key: KeyEvent) -> bool {
         // Help
         KeyCode::F(1) | KeyCode::Char('?') => app.mode = AppMode::Help,
 
+        // Setup screen
+        KeyCode::F(2) => app.open_setup(),
+
         // Search
         KeyCode::F(3) | KeyCode::Char('/') => {
             app.mode = AppMode::Search;
@@ -124,6 +153,12 @@ fn handle_normal_key(app: &mut App, key: KeyEvent) -> bool {
             app.filter_query.clear();
         }
 
+        // Command line
+        KeyCode::Char(':') => {
+            app.mode = AppMode::Command;
+            app.command_input.clear();
+        }
+
         // Tree view
         KeyCode::F(5) | KeyCode::Char('t') => {
             app.tree_view = !app.tree_view;
@@ -133,6 +168,7 @@ fn handle_normal_key(app: &mut App, key: KeyEvent) -> bool {
 
         // Sort column select
         KeyCode::F(6) | KeyCode::Char('>') | KeyCode::Char('<') => {
+            app.sort_select_then = false;
             app.mode = AppMode::SortSelect;
         }
 
@@ -141,6 +177,9 @@ fn handle_normal_key(app: &mut App, key: KeyEvent) -> bool {
             if !app.filtered_processes.is_empty() {
                 app.kill_signal_idx = 0;
                 app.kill_pid_scroll = 0;
+                app.kill_group = false;
+                app.kill_supervisor = app.selected_supervisor();
+                app.pin_kill_targets();
                 app.pre_kill_mode = AppMode::Normal;
                 app.mode = AppMode::Kill;
             }
@@ -160,6 +199,9 @@ fn handle_normal_key(app: &mut App, key: KeyEvent) -> bool {
         KeyCode::Char('y') => app.pending_key = Some('y'),
         // Vim fold (zo, zc, za, zO, zC, zM, zR)
         KeyCode::Char('z') => app.pending_key = Some('z'),
+        // Marks (ma sets, 'a jumps back)
+        KeyCode::Char('m') => app.pending_key = Some('m'),
+        KeyCode::Char('\'') => app.pending_key = Some('\''),
         KeyCode::Char('G') => { app.push_jump_mark(); app.select_last(); }
 
         // Jump forward (Tab = Ctrl+I in terminals)
@@ -178,9 +220,71 @@ fn handle_normal_key(app: &mut App, key: KeyEvent) -> bool {
             }
         }
 
+        // Process detail view
+        KeyCode::Enter => app.open_inspection(AppMode::Detail),
+
+        // Open files panel
+        KeyCode::Char('l') => app.open_inspection(AppMode::Files),
+
+        // Environment variables
+        KeyCode::Char('e') => app.open_environ(),
+
+        // Network connections panel
+        KeyCode::Char('c') => app.open_inspection(AppMode::Connections),
+
+        // CPU/RSS/net history graphs
+        KeyCode::Char('h') => {
+            if let Some(proc) = app.filtered_processes.get(app.selected) {
+                app.history_pid = proc.pid;
+                app.history_long_term = None;
+                app.mode = AppMode::History;
+            }
+        }
+
+        // Recent execs panel
+        KeyCode::Char('E') => {
+            app.execs_scroll.home();
+            app.mode = AppMode::Execs;
+        }
+        KeyCode::Char('X') => {
+            app.oom_scroll.home();
+            app.oom_unseen = 0;
+            app.mode = AppMode::Ooms;
+        }
+
+        // bpftop's own eBPF program overhead
+        KeyCode::Char('B') => app.open_bpf_stats(),
+        // Every BPF program and map on the system
+        KeyCode::Char('b') => app.open_bpf_objects(),
+
+        // Login sessions (SSH, terminals)
+        KeyCode::Char('s') => app.open_sessions(),
+
+        // CPU, memory and tasks per cgroup
+        KeyCode::Char('a') => app.open_cgroups(),
+
+        // CPU and memory per service or container
+        KeyCode::Char('w') => app.open_groups(),
+
+        // JVM / Python thread dump (jcmd, py-spy)
+        KeyCode::Char('i') => app.open_runtime_probe(),
+
+        // Process tree diff (record snapshot, then diff against it)
+        KeyCode::Char('D') => app.snapshot_or_diff(),
+
+        // Follow the selected process as the sort order changes
+        KeyCode::Char('F') => app.toggle_follow(),
+
+        // Copy mode (screen text selection)
+        KeyCode::Char('v') => app.enter_copy_mode(),
+
         // Tag process
         KeyCode::Char(' ') => app.toggle_tag(),
 
+        // Renice tagged/selected (htop F7/F8)
+        KeyCode::F(7) | KeyCode::Char('[') => app.renice(-1),
+        KeyCode::F(8) | KeyCode::Char(']') => app.renice(1),
+
         // Thaw cgroup (with dialog)
         KeyCode::Char('u') => {
             if !app.filtered_processes.is_empty() {
@@ -253,6 +357,14 @@ fn handle_normal_key(app: &mut App, key: KeyEvent) -> bool {
             } else {
                 match app.sort_column {
                     SortColumn::NetRate => {
+                        app.sort_column = SortColumn::NetTx;
+                        app.sort_ascending = false;
+                    }
+                    SortColumn::NetTx => {
+                        app.sort_column = SortColumn::NetRx;
+                        app.sort_ascending = false;
+                    }
+                    SortColumn::NetRx => {
                         app.sort_column = SortColumn::NetTotal;
                         app.sort_ascending = false;
                     }
@@ -261,6 +373,10 @@ fn handle_normal_key(app: &mut App, key: KeyEvent) -> bool {
                         app.sort_ascending = true;
                     }
                     SortColumn::NetIf => {
+                        app.sort_column = SortColumn::Drops;
+                        app.sort_ascending = false;
+                    }
+                    SortColumn::Drops => {
                         app.show_net = false;
                         app.sort_column = SortColumn::CpuPercent;
                         app.sort_ascending = false;
@@ -273,8 +389,40 @@ fn handle_normal_key(app: &mut App, key: KeyEvent) -> bool {
             }
             app.update_filtered_processes();
         }
+        KeyCode::Char('C') => app.compact = !app.compact,
+        KeyCode::Char('p') => app.cycle_command_display(),
+        KeyCode::Char('R') => app.header_graphs = !app.header_graphs,
+        KeyCode::Char('J') => {
+            app.show_job = !app.show_job;
+            if !app.show_job && matches!(app.sort_column, SortColumn::Pgrp | SortColumn::Sid | SortColumn::Tty) {
+                app.sort_column = SortColumn::Pid;
+                app.sort_ascending = true;
+            }
+            app.update_filtered_processes();
+        }
+        KeyCode::Char('O') => {
+            if !app.show_disk {
+                app.show_disk = true;
+                app.sort_column = SortColumn::DiskRead;
+            } else if app.sort_column == SortColumn::DiskRead {
+                app.sort_column = SortColumn::DiskWrite;
+            } else if app.sort_column == SortColumn::DiskWrite {
+                app.show_disk = false;
+                app.sort_column = SortColumn::CpuPercent;
+            } else {
+                app.sort_column = SortColumn::DiskRead;
+            }
+            app.sort_ascending = false;
+            app.sync_probes();
+            app.update_filtered_processes();
+        }
+        // GPU memory, then GPU utilisation
         KeyCode::Char('W') => {
-            app.sort_column = SortColumn::GpuMem;
+            app.sort_column = if app.sort_column == SortColumn::GpuMem {
+                SortColumn::GpuPercent
+            } else {
+                SortColumn::GpuMem
+            };
             app.sort_ascending = false;
 
             app.update_filtered_processes();
@@ -333,6 +481,17 @@ fn handle_visual_key(app: &mut App, key: KeyEvent) -> bool {
                 }
                 return false;
             }
+            // Y{key}: one column for the whole range, newline-separated
+            ('Y', KeyCode::Char(c)) => {
+                if let Some(field) = YankField::from_column_key(c) {
+                    if let Some(desc) = app.yank(field) {
+                        app.flash(desc);
+                    }
+                    app.visual_anchor = None;
+                    app.mode = AppMode::Normal;
+                }
+                return false;
+            }
             _ => { /* cancel pending, fall through */ }
         }
     }
@@ -351,6 +510,7 @@ fn handle_visual_key(app: &mut App, key: KeyEvent) -> bool {
 
         // y alone: yank full rows and exit visual mode
         KeyCode::Char('y') => app.pending_key = Some('y'),
+        KeyCode::Char('Y') => app.pending_key = Some('Y'),
 
         // Space: tag the visual range and exit visual mode
         KeyCode::Char(' ') => {
@@ -363,6 +523,9 @@ fn handle_visual_key(app: &mut App, key: KeyEvent) -> bool {
         KeyCode::F(9) | KeyCode::Char('x') => {
             app.tag_visual_range();
             app.kill_signal_idx = 0;
+            app.kill_group = false;
+            app.kill_supervisor = None;
+            app.pin_kill_targets();
             app.pre_kill_mode = AppMode::Visual;
             app.mode = AppMode::Kill;
         }
@@ -379,6 +542,17 @@ fn handle_visual_key(app: &mut App, key: KeyEvent) -> bool {
             app.prepare_thaw();
         }
 
+        // [ / ]: renice the range -1 / +1 (stays in visual mode so it can be repeated)
+        KeyCode::F(7) | KeyCode::Char('[') => app.renice_visual_range(-1),
+        KeyCode::F(8) | KeyCode::Char(']') => app.renice_visual_range(1),
+
+        // Backspace: untag the visual range and exit visual mode
+        KeyCode::Backspace => {
+            app.untag_visual_range();
+            app.visual_anchor = None;
+            app.mode = AppMode::Normal;
+        }
+
         // U: tag the visual range, instant thaw
         KeyCode::Char('U') => {
             app.tag_visual_range();
@@ -426,6 +600,24 @@ fn handle_filter_key(app: &mut App, key: KeyEvent, _mode: FilterMode) -> bool {
     false
 }
 
+fn handle_command_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc => {
+            app.command_input.clear();
+            app.mode = AppMode::Normal;
+        }
+        KeyCode::Enter => app.run_command(),
+        // Backspace on an empty line leaves, like vim
+        KeyCode::Backspace if app.command_input.is_empty() => app.mode = AppMode::Normal,
+        KeyCode::Backspace => {
+            app.command_input.pop();
+        }
+        KeyCode::Char(c) => app.command_input.push(c),
+        _ => {}
+    }
+    false
+}
+
 fn handle_help_key(app: &mut App, key: KeyEvent) -> bool {
     match key.code {
         KeyCode::Esc | KeyCode::F(1) | KeyCode::Char('q') | KeyCode::Char('?') => {
@@ -438,9 +630,12 @@ fn handle_help_key(app: &mut App, key: KeyEvent) -> bool {
 
 fn handle_kill_key(app: &mut App, key: KeyEvent) -> bool {
     let signals = signal_list();
+    // Entry 0 is "restart via supervisor" when one was detected
+    let offset = usize::from(app.kill_supervisor.is_some());
     match key.code {
         KeyCode::Esc => {
             app.untag_all();
+            app.kill_pinned.clear();
             app.mode = app.pre_kill_mode;
         }
         KeyCode::Up | KeyCode::Char('k') => {
@@ -449,7 +644,7 @@ fn handle_kill_key(app: &mut App, key: KeyEvent) -> bool {
             }
         }
         KeyCode::Down | KeyCode::Char('j') => {
-            if app.kill_signal_idx < signals.len() - 1 {
+            if app.kill_signal_idx < signals.len() - 1 + offset {
                 app.kill_signal_idx += 1;
             }
         }
@@ -463,8 +658,26 @@ fn handle_kill_key(app: &mut App, key: KeyEvent) -> bool {
             // Clamped during render; just increment here
             app.kill_pid_scroll = app.kill_pid_scroll.saturating_add(1);
         }
+        // Toggle signalling whole process groups
+        KeyCode::Char('g') => {
+            app.kill_group = !app.kill_group;
+            app.kill_pid_scroll = 0;
+        }
+        KeyCode::Char('R') if offset == 1 => {
+            app.restart_supervised();
+            app.mode = AppMode::Normal;
+        }
+        // Kill the targets' whole cgroups atomically
+        KeyCode::Char('c') => {
+            app.kill_cgroups();
+            app.untag_all();
+            app.visual_anchor = None;
+            app.mode = AppMode::Normal;
+        }
         KeyCode::Enter => {
-            if let Some((sig_num, _)) = signals.get(app.kill_signal_idx) {
+            if offset == 1 && app.kill_signal_idx == 0 {
+                app.restart_supervised();
+            } else if let Some((sig_num, _)) = signals.get(app.kill_signal_idx - offset) {
                 app.send_signal(*sig_num);
             }
             app.untag_all();
@@ -500,61 +713,344 @@ fn handle_freeze_key(app: &mut App, key: KeyEvent) -> bool {
         KeyCode::Up | KeyCode::Char('k') => {
             app.freeze_scroll = app.freeze_scroll.saturating_sub(1);
         }
+        // Auto-thaw timer (freeze only)
+        KeyCode::Char('t') if !app.freeze_is_thaw => app.cycle_thaw_after(),
         _ => {}
     }
     false
 }
 
-fn handle_sort_key(app: &mut App, key: KeyEvent) -> bool {
+fn handle_files_key(app: &mut App, key: KeyEvent) -> bool {
+    if app.files_filter_editing {
+        match key.code {
+            KeyCode::Esc => {
+                app.files_filter_editing = false;
+                app.files_filter.clear();
+            }
+            KeyCode::Enter => app.files_filter_editing = false,
+            KeyCode::Backspace => {
+                app.files_filter.pop();
+            }
+            KeyCode::Char(c) => {
+                app.files_filter.push(c);
+                app.files_scroll.home();
+            }
+            _ => {}
+        }
+        return false;
+    }
+
     match key.code {
-        KeyCode::Esc => app.mode = AppMode::Normal,
-        KeyCode::Up | KeyCode::Char('k') => {
-            let cols = SortColumn::all();
-            if let Some(pos) = cols.iter().position(|c| *c == app.sort_column) {
-                if pos > 0 {
-                    app.sort_column = cols[pos - 1];
-        
-                    app.update_filtered_processes();
-                }
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('l') => app.close_inspection(),
+        KeyCode::Char('/') => app.files_filter_editing = true,
+        code => {
+            app.files_scroll.handle_key(code);
+        }
+    }
+    false
+}
+
+fn handle_connections_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('c') => app.close_inspection(),
+        KeyCode::Down | KeyCode::Char('j') => app.move_conn_selection(1),
+        KeyCode::Up | KeyCode::Char('k') => app.move_conn_selection(-1),
+        KeyCode::Home | KeyCode::Char('g') => app.conn_selected = 0,
+        KeyCode::End | KeyCode::Char('G') => app.move_conn_selection(i32::MAX),
+        KeyCode::Char('y') => app.yank_connection_remote(),
+        KeyCode::Char('a') => app.toggle_conn_all(),
+        KeyCode::Char('n') => app.toggle_conn_netns(),
+        KeyCode::Char('r') => app.toggle_conn_sort_rtt(),
+        _ => {}
+    }
+    false
+}
+
+fn handle_detail_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => app.close_inspection(),
+        KeyCode::Char('o') => app.toggle_offcpu(),
+        KeyCode::Char('i') => app.toggle_perf(),
+        code => {
+            app.detail_scroll.handle_key(code);
+        }
+    }
+    false
+}
+
+fn handle_execs_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('E') => app.mode = AppMode::Normal,
+        KeyCode::Char('C') => {
+            app.exec_log.clear();
+            app.execs_scroll.home();
+        }
+        code => {
+            app.execs_scroll.handle_key(code);
+        }
+    }
+    false
+}
+
+fn handle_ooms_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('X') => app.mode = AppMode::Normal,
+        KeyCode::Char('C') => {
+            app.oom_log.clear();
+            app.oom_scroll.home();
+        }
+        code => {
+            app.oom_scroll.handle_key(code);
+        }
+    }
+    false
+}
+
+fn handle_sessions_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('s') => app.mode = AppMode::Normal,
+        KeyCode::Down | KeyCode::Char('j') => app.move_session_selection(1),
+        KeyCode::Up | KeyCode::Char('k') => app.move_session_selection(-1),
+        KeyCode::Home | KeyCode::Char('g') => app.sessions_selected = 0,
+        KeyCode::End | KeyCode::Char('G') => app.move_session_selection(i32::MAX),
+        KeyCode::Enter => app.show_session(),
+        KeyCode::Char('x') => app.kill_session(),
+        KeyCode::Char('a') => app.toggle_sessions_all(),
+        _ => {}
+    }
+    false
+}
+
+fn handle_cgroups_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('a') => app.mode = AppMode::Normal,
+        KeyCode::Down | KeyCode::Char('j') => app.move_cgroup_selection(1),
+        KeyCode::Up | KeyCode::Char('k') => app.move_cgroup_selection(-1),
+        KeyCode::PageDown => app.move_cgroup_selection(app.visible_rows as i32),
+        KeyCode::PageUp => app.move_cgroup_selection(-(app.visible_rows as i32)),
+        KeyCode::Home | KeyCode::Char('g') => app.cgroups_selected = 0,
+        KeyCode::End | KeyCode::Char('G') => app.move_cgroup_selection(i32::MAX),
+        KeyCode::Char(' ') => app.fold_cgroup(None),
+        KeyCode::Char('-') | KeyCode::Left | KeyCode::Char('h') => app.fold_cgroup(Some(true)),
+        KeyCode::Char('+') | KeyCode::Right | KeyCode::Char('l') => app.fold_cgroup(Some(false)),
+        KeyCode::Enter => app.show_cgroup(),
+        _ => {}
+    }
+    false
+}
+
+fn handle_groups_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('w') => app.mode = AppMode::Normal,
+        KeyCode::Down | KeyCode::Char('j') => app.move_group_selection(1),
+        KeyCode::Up | KeyCode::Char('k') => app.move_group_selection(-1),
+        KeyCode::PageDown => app.move_group_selection(app.visible_rows as i32),
+        KeyCode::PageUp => app.move_group_selection(-(app.visible_rows as i32)),
+        KeyCode::Home | KeyCode::Char('g') => app.groups_selected = 0,
+        KeyCode::End | KeyCode::Char('G') => app.move_group_selection(i32::MAX),
+        KeyCode::Char(' ') => app.toggle_group_expanded(),
+        KeyCode::Tab => app.cycle_group_by(),
+        KeyCode::Enter => app.show_group(),
+        _ => {}
+    }
+    false
+}
+
+fn handle_runtime_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('i') => app.close_runtime_probe(),
+        KeyCode::Char('r') => app.rerun_runtime_probe(),
+        code => {
+            app.runtime_scroll.handle_key(code);
+        }
+    }
+    false
+}
+
+fn handle_environ_key(app: &mut App, key: KeyEvent) -> bool {
+    if app.env_filter_editing {
+        match key.code {
+            KeyCode::Esc => {
+                app.env_filter_editing = false;
+                app.env_filter.clear();
+            }
+            KeyCode::Enter => app.env_filter_editing = false,
+            KeyCode::Backspace => {
+                app.env_filter.pop();
+            }
+            KeyCode::Char(c) => {
+                app.env_filter.push(c);
+                app.env_scroll.home();
             }
+            _ => {}
         }
-        KeyCode::Down | KeyCode::Char('j') => {
-            let cols = SortColumn::all();
-            if let Some(pos) = cols.iter().position(|c| *c == app.sort_column) {
-                if pos < cols.len() - 1 {
-                    app.sort_column = cols[pos + 1];
-        
-                    app.update_filtered_processes();
-                }
+        return false;
+    }
+
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('e') => app.close_environ(),
+        KeyCode::Char('/') => app.env_filter_editing = true,
+        KeyCode::Char('r') => app.reload_environ(),
+        code => {
+            app.env_scroll.handle_key(code);
+        }
+    }
+    false
+}
+
+fn handle_bpf_stats_key(app: &mut App, key: KeyEvent) -> bool {
+    if matches!(key.code, KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('B')) {
+        app.close_bpf_stats();
+    }
+    false
+}
+
+fn handle_bpf_objects_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('b') => app.close_bpf_objects(),
+        KeyCode::Tab => {
+            app.bpf_objects_maps = !app.bpf_objects_maps;
+            app.bpf_objects_scroll.home();
+        }
+        code => {
+            app.bpf_objects_scroll.handle_key(code);
+        }
+    }
+    false
+}
+
+fn handle_history_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('h') => app.mode = AppMode::Normal,
+        KeyCode::Char('t') => app.toggle_history_long_term(),
+        _ => {}
+    }
+    false
+}
+
+fn handle_setup_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.cancel_setup(),
+        KeyCode::Char('w') | KeyCode::F(2) => app.save_setup(),
+        KeyCode::Tab => app.setup_switch_pane(1),
+        KeyCode::BackTab => app.setup_switch_pane(-1),
+        KeyCode::Down | KeyCode::Char('j') if app.setup_selected + 1 < app.setup_len() => {
+            app.setup_selected += 1;
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            app.setup_selected = app.setup_selected.saturating_sub(1);
+        }
+        KeyCode::Char(' ') | KeyCode::Enter => app.setup_activate(),
+        KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('+') => app.setup_adjust(1),
+        KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('-') => app.setup_adjust(-1),
+        KeyCode::Char('J') => app.setup_move(1),
+        KeyCode::Char('K') => app.setup_move(-1),
+        _ => {}
+    }
+    false
+}
+
+fn handle_copy_key(app: &mut App, key: KeyEvent) -> bool {
+    // Nothing to move over until the first frame has been captured
+    if app.copy_buffer.is_empty() {
+        if matches!(key.code, KeyCode::Esc | KeyCode::Char('q')) {
+            app.exit_copy_mode();
+        }
+        return false;
+    }
+    let buf = &mut app.copy_buffer;
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            if buf.anchor.is_some() {
+                buf.anchor = None;
+            } else {
+                app.exit_copy_mode();
             }
         }
-        KeyCode::Enter => app.mode = AppMode::Normal,
+        KeyCode::Left | KeyCode::Char('h') => buf.move_cursor(0, -1),
+        KeyCode::Right | KeyCode::Char('l') => buf.move_cursor(0, 1),
+        KeyCode::Down | KeyCode::Char('j') => buf.move_cursor(1, 0),
+        KeyCode::Up | KeyCode::Char('k') => buf.move_cursor(-1, 0),
+        KeyCode::PageDown => buf.move_cursor(app.visible_rows as i64, 0),
+        KeyCode::PageUp => buf.move_cursor(-(app.visible_rows as i64), 0),
+        KeyCode::Home | KeyCode::Char('0') => buf.line_start(),
+        KeyCode::End | KeyCode::Char('$') => buf.line_end(),
+        KeyCode::Char('w') => buf.word_forward(),
+        KeyCode::Char('b') => buf.word_backward(),
+        KeyCode::Char('g') => buf.move_cursor(i64::MIN / 2, 0),
+        KeyCode::Char('G') => buf.move_cursor(i64::MAX / 2, 0),
+        KeyCode::Char('v') => buf.toggle_selection(SelectionKind::Char),
+        KeyCode::Char('V') => buf.toggle_selection(SelectionKind::Line),
+        KeyCode::Char('y') | KeyCode::Enter => app.yank_copy_selection(),
+        _ => {}
+    }
+    false
+}
+
+fn handle_diff_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('D') => app.mode = AppMode::Normal,
+        KeyCode::Char('r') => {
+            app.record_diff_base();
+            app.mode = AppMode::Normal;
+        }
+        code => {
+            app.diff_scroll.handle_key(code);
+        }
+    }
+    false
+}
+
+fn handle_sort_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc | KeyCode::Enter => app.mode = AppMode::Normal,
+        KeyCode::Up | KeyCode::Char('k') => app.step_sort_key(-1),
+        KeyCode::Down | KeyCode::Char('j') => app.step_sort_key(1),
+        // Switch between the primary and the secondary ("then") key
+        KeyCode::Tab => app.sort_select_then = !app.sort_select_then,
+        KeyCode::Char('r') => app.reverse_sort_key(),
+        KeyCode::Char('d') | KeyCode::Backspace => app.clear_sort_then(),
         _ => {}
     }
     false
 }
 
 fn handle_mouse(app: &mut App, mouse: MouseEvent) {
+    if app.mode == AppMode::Copy {
+        handle_copy_mouse(app, mouse);
+        return;
+    }
     match mouse.kind {
         MouseEventKind::ScrollUp => app.move_selection(-3),
         MouseEventKind::ScrollDown => app.move_selection(3),
         MouseEventKind::Down(_) => {
             let col_header_row = app.header_height;
             let data_start = app.header_height + 1;
+            let in_rows = mouse.row >= data_start && mouse.row < data_start + app.visible_rows as u16;
+            let on_scrollbar = in_rows
+                && mouse.column + 1 == app.table_width
+                && app.filtered_processes.len() > app.visible_rows;
 
-            if mouse.row == col_header_row {
+            if on_scrollbar {
+                app.grab_scrollbar((mouse.row - data_start) as usize);
+            } else if mouse.row == col_header_row {
                 // Click on column header → toggle sort
-                let has_container = app.filtered_processes.iter().any(|p| p.container.is_some());
-                let has_service = app.filtered_processes.iter().any(|p| p.service.is_some());
+                let (has_container, has_pod, has_service) = app.optional_columns();
                 let has_gpu = app.show_gpu && app.sys_info.gpus.len() > 0;
                 let layout = process_table::column_layout(
                     app.table_width,
+                    &app.config.columns.show,
                     &app.filtered_processes,
                     has_container,
+                    has_pod,
                     has_service,
                     app.service_display_mode,
                     has_gpu,
+                    app.sys_info.package_watts().is_some(),
                     app.show_net,
+                    app.show_disk,
+                    app.show_job,
+                    app.compact,
                 );
 
                 let mut x: u16 = 0;
@@ -572,14 +1068,45 @@ fn handle_mouse(app: &mut App, mouse: MouseEvent) {
                     }
                     x = next_x;
                 }
-            } else if mouse.row >= data_start {
+            } else if in_rows {
                 // Click to select a process row
                 let row = (mouse.row - data_start) as usize + app.scroll_offset;
                 if row < app.filtered_processes.len() {
+                    app.follow_pid = None;
                     app.selected = row;
                 }
             }
         }
+        MouseEventKind::Drag(_) if app.scrollbar_drag.is_some() => {
+            let row = mouse.row.saturating_sub(app.header_height + 1);
+            app.scroll_to_track_row(row as usize);
+        }
+        MouseEventKind::Up(_) => app.scrollbar_drag = None,
+        _ => {}
+    }
+}
+
+/// Click sets the cursor, drag extends a selection, release yanks it.
+fn handle_copy_mouse(app: &mut App, mouse: MouseEvent) {
+    if app.copy_buffer.is_empty() {
+        return;
+    }
+    let (row, col) = (mouse.row as usize, mouse.column as usize);
+    let buf = &mut app.copy_buffer;
+    match mouse.kind {
+        MouseEventKind::Down(_) => {
+            buf.anchor = None;
+            buf.set_cursor(row, col);
+        }
+        MouseEventKind::Drag(_) => {
+            if buf.anchor.is_none() {
+                buf.anchor = Some((buf.cursor, SelectionKind::Char));
+            }
+            buf.set_cursor(row, col);
+        }
+        MouseEventKind::Up(_) if buf.anchor.is_some() => app.yank_copy_selection(),
+        MouseEventKind::ScrollUp => buf.move_cursor(-3, 0),
+        MouseEventKind::ScrollDown => buf.move_cursor(3, 0),
         _ => {}
     }
 }
@@ -1,17 +1,27 @@
 mod app;
+mod batch;
 mod clipboard;
 mod config;
 mod data;
-mod ebpf;
+mod dump;
 mod input;
+mod marks;
+mod metrics;
+mod mirror;
+mod redact;
 mod theme;
+#[cfg(feature = "sqlite")]
+mod trends;
 mod ui;
+mod ui_bench;
+
+use bpftop_core::{alerts, ebpf};
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{CommandFactory, Parser, Subcommand};
 
 #[derive(Parser)]
-#[command(name = "bpftop", about = "eBPF-powered interactive process monitor")]
+#[command(name = "bpftop", version, about = "eBPF-powered interactive process monitor")]
 struct Cli {
     /// Refresh rate in milliseconds
     #[arg(short = 'd', long, default_value_t = 1000)]
@@ -24,13 +34,135 @@ struct Cli {
     /// Filter processes by user
     #[arg(short = 'u', long)]
     user: Option<String>,
+
+    /// Plain output for screen readers (no box drawing, selection read out)
+    #[arg(long)]
+    screen_reader: bool,
+
+    /// Show only the header meters and the top 5 processes, for small
+    /// tmux panes and status displays (keys work as usual)
+    #[arg(long)]
+    summary: bool,
+
+    /// Print N refreshes to stdout without the TUI (0 = until killed)
+    #[arg(
+        short = 'b',
+        long,
+        value_name = "N",
+        num_args = 0..=1,
+        default_missing_value = "1"
+    )]
+    batch: Option<u64>,
+
+    /// Output format for --batch
+    #[arg(long, value_enum, default_value_t = batch::BatchFormat::Text, requires = "batch")]
+    format: batch::BatchFormat,
+
+    /// Mask user names, command line arguments and remote IPs on screen
+    /// and in exports, for sharing screenshots (or only the listed ones)
+    #[arg(long, value_enum, value_name = "FIELDS", num_args = 0..=1, require_equals = true, value_delimiter = ',')]
+    redact: Option<Vec<redact::RedactField>>,
+
+    /// Mirror the screen, read-only, to viewers connecting to this Unix
+    /// socket path or TCP host:port (watch with socat or nc)
+    #[arg(long, value_name = "ADDR")]
+    share: Option<String>,
+
+    /// Let --share bind a TCP address other hosts can reach
+    #[arg(long, requires = "share")]
+    share_remote: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print samples recorded under [trends] for a PID or command name
+    /// (needs a build with the sqlite feature)
+    History {
+        /// PID, or process name as shown in comm
+        target: String,
+        /// How far back to look, e.g. 90m, 24h, 7d
+        #[arg(long, default_value = "24h", value_parser = parse_age)]
+        since: u64,
+    },
+    /// Time merge + filter + render of the TUI on synthetic processes,
+    /// headless (no eBPF or terminal needed)
+    BenchUi {
+        /// Process counts to measure, comma separated
+        #[arg(long, value_delimiter = ',', default_value = "1000,5000,10000,50000")]
+        tasks: Vec<usize>,
+        /// Number of timed frames per count
+        #[arg(short, long, default_value_t = 20)]
+        iterations: usize,
+        /// Number of warmup frames (not timed)
+        #[arg(short, long, default_value_t = 3)]
+        warmup: usize,
+        /// Terminal size to render into
+        #[arg(long, default_value_t = 200)]
+        width: u16,
+        #[arg(long, default_value_t = 60)]
+        height: u16,
+        /// Filter expression to apply, as typed after `\\`
+        #[arg(long)]
+        filter: Option<String>,
+        /// Output results as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print a shell completion script, e.g.
+    /// `bpftop completions bash > /etc/bash_completion.d/bpftop`
+    Completions {
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// Print the man page (roff) to stdout, e.g. `bpftop man | man -l -`
+    Man,
+}
+
+/// Parse "30s", "90m", "24h" or "7d" (bare numbers are seconds) into seconds.
+fn parse_age(s: &str) -> Result<u64, String> {
+    let (num, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len()));
+    let n: u64 = num.parse().map_err(|_| format!("invalid duration: {s}"))?;
+    let scale = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return Err(format!("invalid duration unit in {s} (use s, m, h or d)")),
+    };
+    Ok(n * scale)
+}
+
+#[cfg(feature = "sqlite")]
+fn history(config: &config::Config, target: &str, since: u64) -> Result<()> {
+    trends::print_history(&config.trends, target, since)
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn history(_config: &config::Config, _target: &str, _since: u64) -> Result<()> {
+    anyhow::bail!("bpftop was built without the sqlite feature (cargo build --features sqlite)")
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    // Generated from the CLI definition; no config or privileges needed
+    match cli.command {
+        Some(Command::Completions { shell }) => {
+            clap_complete::generate(shell, &mut Cli::command(), "bpftop", &mut std::io::stdout());
+            return Ok(());
+        }
+        Some(Command::Man) => {
+            clap_mangen::Man::new(Cli::command()).render(&mut std::io::stdout())?;
+            return Ok(());
+        }
+        _ => {}
+    }
+
     // Load config
-    let mut config = config::Config::load().unwrap_or_default();
+    let (mut config, config_notice) = config::Config::load().unwrap_or_default();
 
     // CLI overrides
     if cli.delay != 1000 {
@@ -39,13 +171,71 @@ fn main() -> Result<()> {
     if cli.tree {
         config.general.tree_view = true;
     }
+    if cli.screen_reader {
+        config.general.screen_reader = true;
+    }
+    if cli.summary {
+        config.general.summary = true;
+    }
+    match cli.redact {
+        Some(fields) if fields.is_empty() => config.privacy.redact = redact::RedactField::ALL.to_vec(),
+        Some(fields) => config.privacy.redact = fields,
+        // Sharing masks everything unless [privacy] chose the fields
+        None if cli.share.is_some() && config.privacy.redact.is_empty() => {
+            config.privacy.redact = redact::RedactField::ALL.to_vec()
+        }
+        None => {}
+    }
+
+    match cli.command {
+        Some(Command::History { target, since }) => return history(&config, &target, since),
+        Some(Command::BenchUi {
+            tasks,
+            iterations,
+            warmup,
+            width,
+            height,
+            filter,
+            json,
+        }) => {
+            let opts = ui_bench::BenchOptions {
+                tasks,
+                iterations,
+                warmup,
+                width,
+                height,
+                tree: config.general.tree_view,
+                filter,
+                json,
+            };
+            return ui_bench::run(config, &opts);
+        }
+        Some(Command::Completions { .. } | Command::Man) | None => {}
+    }
+
+    if let Some(iterations) = cli.batch {
+        if let Some(notice) = config_notice {
+            eprintln!("{notice}");
+        }
+        return batch::run(&config, iterations, cli.format, cli.user.as_deref());
+    }
 
     // Create and run app
     let mut app = app::App::new(config);
+    if let Some(notice) = config_notice {
+        app.flash(notice);
+    }
 
     if let Some(user) = cli.user {
         app.user_filter = Some(user);
     }
+    if let Some(addr) = cli.share {
+        let mirror = mirror::Mirror::bind(&addr, cli.share_remote)?;
+        if mirror.is_tcp() {
+            eprintln!("warning: --share {addr} is unencrypted and unauthenticated; anyone who can connect sees the screen");
+        }
+        app.mirror = Some(mirror);
+    }
 
     app.run()
 }
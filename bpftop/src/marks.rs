@@ -0,0 +1,120 @@
+//! Vim-style process marks: `m{a-z}` sets one on the selected process,
+//! `'{a-z}` jumps back to it. With `persist_marks` they are saved in the
+//! state directory and outlive a restart of bpftop, as long as the marked
+//! processes do.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+
+/// A marked process. The start time tells a recycled PID apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mark {
+    pub pid: u32,
+    pub start_time_ns: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MarksFile {
+    /// Start times count from boot, so marks from another boot are void.
+    boot_id: String,
+    marks: BTreeMap<char, Mark>,
+}
+
+#[derive(Debug, Default)]
+pub struct Marks {
+    marks: BTreeMap<char, Mark>,
+    /// Where to save them; `None` unless `persist_marks` is set.
+    path: Option<PathBuf>,
+}
+
+impl Marks {
+    /// Marks saved by a previous run when `persist` is set, else none.
+    pub fn load(persist: bool) -> Self {
+        if !persist {
+            return Self::default();
+        }
+        let path = match config::state_dir() {
+            Ok(dir) => dir.join("marks.json"),
+            Err(e) => {
+                log::warn!("Not saving marks: {e:#}");
+                return Self::default();
+            }
+        };
+        let marks = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| parse(&content, &boot_id()))
+            .unwrap_or_default();
+        Self {
+            marks,
+            path: Some(path),
+        }
+    }
+
+    pub fn get(&self, name: char) -> Option<Mark> {
+        self.marks.get(&name).copied()
+    }
+
+    pub fn set(&mut self, name: char, mark: Mark) {
+        self.marks.insert(name, mark);
+        self.save();
+    }
+
+    pub fn remove(&mut self, name: char) {
+        if self.marks.remove(&name).is_some() {
+            self.save();
+        }
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Err(e) = self.write(path) {
+            log::warn!("Saving marks failed: {e:#}");
+        }
+    }
+
+    fn write(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+        }
+        let file = MarksFile {
+            boot_id: boot_id(),
+            marks: self.marks.clone(),
+        };
+        let content = serde_json::to_string(&file).context("serializing marks")?;
+        fs::write(path, content).with_context(|| format!("writing {}", path.display()))
+    }
+}
+
+fn boot_id() -> String {
+    fs::read_to_string("/proc/sys/kernel/random/boot_id")
+        .map(|id| id.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// The marks in a saved file, if it was written during this boot.
+fn parse(content: &str, boot_id: &str) -> Option<BTreeMap<char, Mark>> {
+    let file: MarksFile = serde_json::from_str(content).ok()?;
+    (file.boot_id == boot_id).then_some(file.marks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_marks_only_load_in_the_same_boot() {
+        let content = r#"{"boot_id":"b1","marks":{"a":{"pid":42,"start_time_ns":7}}}"#;
+        let marks = parse(content, "b1").unwrap();
+        assert_eq!(marks.get(&'a'), Some(&Mark { pid: 42, start_time_ns: 7 }));
+        assert!(parse(content, "b2").is_none());
+        assert!(parse("not json", "b1").is_none());
+    }
+}
@@ -0,0 +1,439 @@
+//! Periodic metrics push for hosts that can't be scraped (e.g. behind NAT).
+//!
+//! Supports the Prometheus text/OpenMetrics format (for a Pushgateway or any
+//! endpoint accepting the exposition format), Prometheus remote-write 1.0
+//! (snappy-compressed protobuf, for Prometheus, Mimir, VictoriaMetrics...)
+//! and OTLP/HTTP JSON (for an OpenTelemetry collector). Only plain `http://`
+//! endpoints are supported; put a local collector or TLS-terminating proxy
+//! in front for anything else.
+
+use std::fmt::Write as _;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::config::PushConfig;
+use crate::data::process::{compare_processes, ProcessInfo, SortColumn};
+use crate::data::system::SystemInfo;
+use crate::redact::Redact;
+
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Wire format for pushed metrics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PushFormat {
+    /// Prometheus text exposition format (Pushgateway compatible).
+    #[default]
+    OpenMetrics,
+    /// Prometheus remote-write 1.0 (`/api/v1/write`).
+    #[serde(rename = "remote_write")]
+    RemoteWrite,
+    /// OTLP/HTTP with JSON encoding (`/v1/metrics`).
+    Otlp,
+}
+
+impl PushFormat {
+    /// Request headers describing the body.
+    fn headers(&self) -> &'static str {
+        match self {
+            Self::OpenMetrics => "Content-Type: text/plain; version=0.0.4\r\n",
+            Self::RemoteWrite => {
+                "Content-Type: application/x-protobuf\r\nContent-Encoding: snappy\r\n\
+                 X-Prometheus-Remote-Write-Version: 0.1.0\r\n"
+            }
+            Self::Otlp => "Content-Type: application/json\r\n",
+        }
+    }
+}
+
+/// Renders metrics on the collector thread and hands them to a sender
+/// thread, so a slow endpoint never stalls data collection.
+pub struct MetricsPusher {
+    format: PushFormat,
+    interval: Duration,
+    max_processes: usize,
+    last: Option<Instant>,
+    tx: SyncSender<Vec<u8>>,
+    redact: Redact,
+}
+
+impl MetricsPusher {
+    /// Returns `None` when pushing is disabled or the URL is invalid.
+    pub fn from_config(config: &PushConfig, redact: Redact) -> Option<Self> {
+        if config.url.is_empty() {
+            return None;
+        }
+        let endpoint = match Endpoint::parse(&config.url) {
+            Ok(e) => e,
+            Err(e) => {
+                log::warn!("Metrics push disabled: {e:#}");
+                return None;
+            }
+        };
+
+        // Capacity 1: if a push is still in flight, the next one is dropped
+        let (tx, rx) = mpsc::sync_channel::<Vec<u8>>(1);
+        let headers = config.format.headers();
+        std::thread::spawn(move || {
+            while let Ok(body) = rx.recv() {
+                if let Err(e) = endpoint.post(headers, &body) {
+                    log::warn!("Metrics push failed: {e:#}");
+                }
+            }
+        });
+
+        Some(Self {
+            format: config.format,
+            interval: Duration::from_secs(config.interval_secs.max(1)),
+            max_processes: config.max_processes,
+            last: None,
+            tx,
+            redact,
+        })
+    }
+
+    /// Push a snapshot if the interval has elapsed since the last one.
+    pub fn maybe_push(&mut self, sys: &SystemInfo, processes: &[ProcessInfo]) {
+        if self.last.is_some_and(|t| t.elapsed() < self.interval) {
+            return;
+        }
+        self.last = Some(Instant::now());
+
+        let top = top_processes(processes, self.max_processes);
+        let body = match self.format {
+            PushFormat::OpenMetrics => render_openmetrics(sys, &top, &self.redact).into_bytes(),
+            PushFormat::RemoteWrite => match render_remote_write(sys, &top, &self.redact) {
+                Ok(body) => body,
+                Err(e) => {
+                    log::warn!("Compressing metrics failed: {e}");
+                    return;
+                }
+            },
+            PushFormat::Otlp => render_otlp(sys, &top, &self.redact).into_bytes(),
+        };
+        if let Err(TrySendError::Full(_)) = self.tx.try_send(body) {
+            log::debug!("Metrics push still in flight, skipping interval");
+        }
+    }
+}
+
+/// The `limit` busiest non-thread processes by CPU%.
+fn top_processes(processes: &[ProcessInfo], limit: usize) -> Vec<&ProcessInfo> {
+    let mut procs: Vec<&ProcessInfo> = processes.iter().filter(|p| !p.is_thread).collect();
+    procs.sort_by(|a, b| compare_processes(a, b, SortColumn::CpuPercent, false));
+    procs.truncate(limit);
+    procs
+}
+
+/// System-wide gauges as (name, help, value).
+fn system_gauges(sys: &SystemInfo) -> [(&'static str, &'static str, f64); 7] {
+    [
+        ("bpftop_cpu_percent", "Total CPU utilisation in percent.", sys.cpu_total.total_pct),
+        ("bpftop_memory_total_bytes", "Total physical memory.", sys.memory.total as f64),
+        ("bpftop_memory_used_bytes", "Used physical memory.", sys.memory.used as f64),
+        ("bpftop_swap_used_bytes", "Used swap.", sys.swap.used as f64),
+        ("bpftop_load1", "1-minute load average.", sys.load_avg[0]),
+        ("bpftop_tasks", "Number of tasks.", sys.total_tasks as f64),
+        ("bpftop_tasks_running", "Number of running tasks.", sys.running_tasks as f64),
+    ]
+}
+
+/// Per-process gauges as (name, help, getter).
+type ProcessGauge = (&'static str, &'static str, fn(&ProcessInfo) -> f64);
+
+const PROCESS_GAUGES: [ProcessGauge; 7] = [
+    ("bpftop_process_cpu_percent", "Process CPU utilisation in percent.", |p| p.cpu_percent),
+    ("bpftop_process_resident_bytes", "Process resident memory.", |p| p.res_bytes as f64),
+    ("bpftop_process_net_rx_bytes_per_second", "Process network receive rate.", |p| p.net_rx_rate),
+    ("bpftop_process_net_tx_bytes_per_second", "Process network transmit rate.", |p| p.net_tx_rate),
+    ("bpftop_process_disk_read_bytes_per_second", "Process file read rate.", |p| p.disk_read_rate),
+    ("bpftop_process_disk_write_bytes_per_second", "Process file write rate.", |p| p.disk_write_rate),
+    ("bpftop_process_open_fds", "Process open file descriptors.", |p| p.fd_count as f64),
+];
+
+fn render_openmetrics(sys: &SystemInfo, processes: &[&ProcessInfo], redact: &Redact) -> String {
+    let mut out = String::new();
+    for (name, help, value) in system_gauges(sys) {
+        let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} gauge\n{name} {value}");
+    }
+    for (name, help, get) in PROCESS_GAUGES {
+        let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} gauge");
+        for p in processes {
+            let _ = writeln!(
+                out,
+                "{name}{{pid=\"{}\",comm=\"{}\",user=\"{}\"}} {}",
+                p.pid,
+                escape_label(&p.comm),
+                escape_label(&redact.user(&p.user)),
+                get(p)
+            );
+        }
+    }
+    out
+}
+
+fn escape_label(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// A remote-write `WriteRequest`, protobuf-encoded and snappy-compressed.
+/// The message is small enough to encode by hand:
+///
+/// ```text
+/// WriteRequest { repeated TimeSeries timeseries = 1; }
+/// TimeSeries   { repeated Label labels = 1; repeated Sample samples = 2; }
+/// Label        { string name = 1; string value = 2; }
+/// Sample       { double value = 1; int64 timestamp = 2; }
+/// ```
+fn render_remote_write(
+    sys: &SystemInfo,
+    processes: &[&ProcessInfo],
+    redact: &Redact,
+) -> Result<Vec<u8>, snap::Error> {
+    let now_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64;
+
+    let mut request = Vec::new();
+    for (name, _, value) in system_gauges(sys) {
+        encode_series(&mut request, &[("__name__", name)], value, now_ms);
+    }
+    for (name, _, get) in PROCESS_GAUGES {
+        for p in processes {
+            let pid = p.pid.to_string();
+            let user = redact.user(&p.user);
+            // Label names sorted, as the protocol requires
+            let labels = [("__name__", name), ("comm", &p.comm), ("pid", &pid), ("user", &user)];
+            encode_series(&mut request, &labels, get(p), now_ms);
+        }
+    }
+    snap::raw::Encoder::new().compress_vec(&request)
+}
+
+/// Append one `TimeSeries` with a single sample to a `WriteRequest`.
+fn encode_series(out: &mut Vec<u8>, labels: &[(&str, &str)], value: f64, timestamp_ms: i64) {
+    let mut series = Vec::new();
+    for (name, label_value) in labels {
+        let mut label = Vec::new();
+        encode_bytes(&mut label, 1, name.as_bytes());
+        encode_bytes(&mut label, 2, label_value.as_bytes());
+        encode_bytes(&mut series, 1, &label);
+    }
+    let mut sample = Vec::new();
+    // Field 1, wire type 1 (64-bit)
+    sample.push(1 << 3 | 1);
+    sample.extend_from_slice(&value.to_le_bytes());
+    // Field 2, wire type 0 (varint)
+    sample.push(2 << 3);
+    encode_varint(&mut sample, timestamp_ms as u64);
+    encode_bytes(&mut series, 2, &sample);
+    encode_bytes(out, 1, &series);
+}
+
+/// A length-delimited field (wire type 2).
+fn encode_bytes(out: &mut Vec<u8>, field: u8, bytes: &[u8]) {
+    out.push(field << 3 | 2);
+    encode_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+fn encode_varint(out: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        out.push(v as u8 | 0x80);
+        v >>= 7;
+    }
+    out.push(v as u8);
+}
+
+fn render_otlp(sys: &SystemInfo, processes: &[&ProcessInfo], redact: &Redact) -> String {
+    let now_ns = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string();
+    let attr = |key: &str, value: String| json!({ "key": key, "value": { "stringValue": value } });
+
+    let mut metrics: Vec<serde_json::Value> = system_gauges(sys)
+        .iter()
+        .map(|(name, help, value)| {
+            json!({
+                "name": name,
+                "description": help,
+                "gauge": { "dataPoints": [{ "timeUnixNano": now_ns, "asDouble": value }] },
+            })
+        })
+        .collect();
+    for (name, help, get) in PROCESS_GAUGES {
+        let points: Vec<serde_json::Value> = processes
+            .iter()
+            .map(|p| {
+                json!({
+                    "timeUnixNano": now_ns,
+                    "asDouble": get(p),
+                    "attributes": [
+                        attr("pid", p.pid.to_string()),
+                        attr("comm", p.comm.clone()),
+                        attr("user", redact.user(&p.user).into_owned()),
+                    ],
+                })
+            })
+            .collect();
+        metrics.push(json!({
+            "name": name,
+            "description": help,
+            "gauge": { "dataPoints": points },
+        }));
+    }
+
+    let hostname = std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .map(|h| h.trim().to_string())
+        .unwrap_or_default();
+    json!({
+        "resourceMetrics": [{
+            "resource": { "attributes": [
+                attr("service.name", "bpftop".to_string()),
+                attr("host.name", hostname),
+            ]},
+            "scopeMetrics": [{
+                "scope": { "name": "bpftop", "version": env!("CARGO_PKG_VERSION") },
+                "metrics": metrics,
+            }],
+        }],
+    })
+    .to_string()
+}
+
+/// A parsed `http://host[:port]/path` URL.
+struct Endpoint {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl Endpoint {
+    fn parse(url: &str) -> Result<Self> {
+        let Some(rest) = url.strip_prefix("http://") else {
+            bail!("only http:// push URLs are supported: {url}");
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        // "[v6addr]:port" keeps its brackets in `host`; they're stripped when resolving
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((h, p)) if !authority.starts_with('[') || h.ends_with(']') => {
+                (h, p.parse().with_context(|| format!("invalid port in {url}"))?)
+            }
+            _ => (authority, 80),
+        };
+        if host.is_empty() {
+            bail!("missing host in {url}");
+        }
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+
+    fn post(&self, headers: &str, body: &[u8]) -> Result<()> {
+        let addr = (self.host.trim_matches(['[', ']']), self.port)
+            .to_socket_addrs()
+            .with_context(|| format!("resolving {}", self.host))?
+            .next()
+            .with_context(|| format!("no address for {}", self.host))?;
+        let mut stream = TcpStream::connect_timeout(&addr, IO_TIMEOUT)
+            .with_context(|| format!("connecting to {addr}"))?;
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+        let request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}:{}\r\nUser-Agent: bpftop/{}\r\n\
+             {headers}Content-Length: {}\r\nConnection: close\r\n\r\n",
+            self.path,
+            self.host,
+            self.port,
+            env!("CARGO_PKG_VERSION"),
+            body.len()
+        );
+        stream.write_all(request.as_bytes())?;
+        stream.write_all(body)?;
+
+        // Only the status line matters
+        let mut buf = [0u8; 64];
+        let n = stream.read(&mut buf).context("reading response")?;
+        let status_line = String::from_utf8_lossy(&buf[..n]);
+        let status: u16 = status_line
+            .split_whitespace()
+            .nth(1)
+            .and_then(|s| s.parse().ok())
+            .with_context(|| format!("malformed HTTP response: {status_line:?}"))?;
+        if !(200..300).contains(&status) {
+            bail!("endpoint returned HTTP {status}");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redact::RedactField;
+    use crate::ui_bench::synthetic_process;
+
+    #[test]
+    fn encodes_remote_write_series() {
+        let mut request = Vec::new();
+        encode_series(&mut request, &[("__name__", "up"), ("pid", "7")], 1.5, 300);
+
+        let mut expected = vec![
+            0x0a, 40, // TimeSeries, 40 bytes
+            0x0a, 14, 0x0a, 8, // Label, name
+        ];
+        expected.extend_from_slice(b"__name__");
+        expected.extend_from_slice(&[0x12, 2]);
+        expected.extend_from_slice(b"up");
+        expected.extend_from_slice(&[0x0a, 8, 0x0a, 3]);
+        expected.extend_from_slice(b"pid");
+        expected.extend_from_slice(&[0x12, 1, b'7']);
+        // Sample: double 1.5, then timestamp 300 as a two-byte varint
+        expected.extend_from_slice(&[0x12, 12, 0x09]);
+        expected.extend_from_slice(&1.5f64.to_le_bytes());
+        expected.extend_from_slice(&[0x10, 0xac, 0x02]);
+        assert_eq!(request, expected);
+    }
+
+    #[test]
+    fn remote_write_body_is_snappy_compressed() {
+        let sys = SystemInfo::default();
+        let body = render_remote_write(&sys, &[], &Redact::default()).unwrap();
+        let request = snap::raw::Decoder::new().decompress_vec(&body).unwrap();
+        assert_eq!(request[0], 0x0a);
+        assert!(request.windows(b"bpftop_load1".len()).any(|w| w == b"bpftop_load1"));
+    }
+
+    #[test]
+    fn every_format_redacts_users() {
+        let sys = SystemInfo::default();
+        let alice = ProcessInfo {
+            user: "alice".into(),
+            ..synthetic_process(7, 1, "worker")
+        };
+        let redact = Redact::new(&[RedactField::Users]);
+        let contains_alice = |body: &[u8]| body.windows(5).any(|w| w == b"alice");
+
+        assert!(!contains_alice(render_openmetrics(&sys, &[&alice], &redact).as_bytes()));
+        assert!(!contains_alice(render_otlp(&sys, &[&alice], &redact).as_bytes()));
+        let body = render_remote_write(&sys, &[&alice], &redact).unwrap();
+        let request = snap::raw::Decoder::new().decompress_vec(&body).unwrap();
+        assert!(!contains_alice(&request));
+        assert!(contains_alice(render_openmetrics(&sys, &[&alice], &Redact::default()).as_bytes()));
+    }
+}
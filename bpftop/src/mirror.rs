@@ -0,0 +1,283 @@
+//! Read-only mirror of the TUI (`--share`). Every drawn frame goes out as
+//! plain terminal output to whoever connects, so a teammate can watch with
+//! `socat -u UNIX-CONNECT:<path> STDOUT` or `nc <host> <port>` without any
+//! way to act on the host: nothing is ever read from viewers.
+//!
+//! TCP binds only to loopback unless `--share-remote` is given, and sharing
+//! turns on `--redact` unless the fields to mask were chosen explicitly.
+
+use std::fs;
+use std::io::{self, Write};
+use std::net::TcpListener;
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use ratatui::backend::{Backend, CrosstermBackend};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Size;
+use unicode_width::UnicodeWidthStr;
+
+/// A viewer that stops reading is dropped after this long.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Hide the cursor and clear the screen.
+const CLEAR: &[u8] = b"\x1b[?25l\x1b[2J";
+
+enum Listener {
+    Unix(UnixListener, PathBuf),
+    Tcp(TcpListener),
+}
+
+/// One sender thread per viewer, so a slow one never stalls the UI.
+struct Viewer {
+    tx: SyncSender<Arc<Vec<u8>>>,
+    /// The size changed since this viewer last took a frame, so the next
+    /// one it gets must clear the screen first.
+    needs_clear: bool,
+}
+
+pub struct Mirror {
+    listener: Listener,
+    viewers: Vec<Viewer>,
+    /// Size of the last frame sent; viewers are cleared when it changes.
+    size: Size,
+}
+
+impl Mirror {
+    /// Listen on a Unix socket (anything with a `/`) or a TCP `host:port`,
+    /// which must be a loopback address unless `remote` is set.
+    pub fn bind(addr: &str, remote: bool) -> Result<Self> {
+        let listener = if addr.contains('/') {
+            let path = PathBuf::from(addr);
+            if fs::symlink_metadata(&path).is_ok_and(|m| m.file_type().is_socket()) {
+                match UnixStream::connect(&path) {
+                    Ok(_) => bail!("{addr} is in use, is another bpftop sharing on it?"),
+                    // Left behind by a run that didn't exit cleanly
+                    Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => {
+                        fs::remove_file(&path).with_context(|| format!("removing stale {addr}"))?;
+                    }
+                    Err(e) => bail!("{addr} exists and can't be checked: {e}"),
+                }
+            }
+            let listener = UnixListener::bind(&path).with_context(|| format!("binding {addr}"))?;
+            listener.set_nonblocking(true)?;
+            Listener::Unix(listener, path)
+        } else {
+            let listener = TcpListener::bind(addr).with_context(|| format!("binding {addr}"))?;
+            let local = listener.local_addr()?;
+            if !remote && !local.ip().is_loopback() {
+                bail!("--share {addr} is reachable from other hosts; add --share-remote to allow it");
+            }
+            listener.set_nonblocking(true)?;
+            Listener::Tcp(listener)
+        };
+        Ok(Self {
+            listener,
+            viewers: Vec::new(),
+            size: Size::default(),
+        })
+    }
+
+    pub fn viewers(&self) -> usize {
+        self.viewers.len()
+    }
+
+    /// Whether viewers connect over TCP, which is unencrypted.
+    pub fn is_tcp(&self) -> bool {
+        matches!(self.listener, Listener::Tcp(_))
+    }
+
+    /// Accept new viewers and send them all `buffer`. A viewer still busy
+    /// with the previous frame skips this one; each frame is complete.
+    pub fn send(&mut self, buffer: &Buffer) {
+        self.accept();
+        if self.viewers.is_empty() {
+            return;
+        }
+        let size = Size::new(buffer.area.width, buffer.area.height);
+        if size != self.size {
+            self.size = size;
+            for viewer in &mut self.viewers {
+                viewer.needs_clear = true;
+            }
+        }
+        let mut frame = Vec::new();
+        if let Err(e) = encode(buffer, &mut frame) {
+            log::warn!("Encoding mirror frame failed: {e}");
+            return;
+        }
+        let frame = Arc::new(frame);
+        let mut cleared = None;
+        self.viewers.retain_mut(|viewer| {
+            let out = if viewer.needs_clear {
+                Arc::clone(cleared.get_or_insert_with(|| Arc::new([CLEAR, frame.as_slice()].concat())))
+            } else {
+                Arc::clone(&frame)
+            };
+            // A viewer still busy keeps `needs_clear` for the frame it does get
+            match viewer.tx.try_send(out) {
+                Ok(()) => {
+                    viewer.needs_clear = false;
+                    true
+                }
+                Err(TrySendError::Full(_)) => true,
+                Err(TrySendError::Disconnected(_)) => false,
+            }
+        });
+    }
+
+    fn accept(&mut self) {
+        loop {
+            let stream: io::Result<Box<dyn Write + Send>> = match &self.listener {
+                Listener::Unix(l, path) => l.accept().and_then(|(s, _)| {
+                    log::info!("Mirror viewer connected on {}", path.display());
+                    s.set_nonblocking(false)?;
+                    s.set_write_timeout(Some(WRITE_TIMEOUT))?;
+                    Ok(Box::new(s) as Box<dyn Write + Send>)
+                }),
+                Listener::Tcp(l) => l.accept().and_then(|(s, peer)| {
+                    log::info!("Mirror viewer connected from {peer}");
+                    s.set_nonblocking(false)?;
+                    s.set_write_timeout(Some(WRITE_TIMEOUT))?;
+                    Ok(Box::new(s) as Box<dyn Write + Send>)
+                }),
+            };
+            match stream {
+                Ok(stream) => self.viewers.push(Viewer {
+                    tx: spawn_viewer(stream),
+                    needs_clear: false,
+                }),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return,
+                Err(e) => {
+                    log::warn!("Accepting mirror viewer failed: {e}");
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Mirror {
+    fn drop(&mut self) {
+        if let Listener::Unix(_, path) = &self.listener {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+fn spawn_viewer(mut stream: Box<dyn Write + Send>) -> SyncSender<Arc<Vec<u8>>> {
+    let (tx, rx) = mpsc::sync_channel::<Arc<Vec<u8>>>(1);
+    std::thread::spawn(move || {
+        if stream.write_all(CLEAR).is_err() {
+            return;
+        }
+        for frame in rx {
+            if stream.write_all(&frame).and_then(|_| stream.flush()).is_err() {
+                return;
+            }
+        }
+    });
+    tx
+}
+
+/// The whole of `buffer` as terminal output, drawn from the top left.
+fn encode(buffer: &Buffer, out: &mut Vec<u8>) -> io::Result<()> {
+    let mut backend = CrosstermBackend::new(out);
+    let mut covered = 0;
+    let cells = buffer
+        .content
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| {
+            // The cells after a wide character are drawn by it
+            if covered > 0 {
+                covered -= 1;
+                return false;
+            }
+            covered = c.symbol().width().saturating_sub(1);
+            true
+        })
+        .map(|(i, c)| {
+            let (x, y) = buffer.pos_of(i);
+            (x, y, c)
+        });
+    backend.draw(cells)?;
+    Backend::flush(&mut backend)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+    use std::os::unix::net::UnixStream;
+
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+
+    use super::*;
+
+    #[test]
+    fn viewers_get_the_frame_without_cells_under_wide_chars() {
+        let path = std::env::temp_dir().join(format!("bpftop-mirror-{}.sock", std::process::id()));
+        let mut mirror = Mirror::bind(path.to_str().unwrap(), false).unwrap();
+        let mut viewer = UnixStream::connect(&path).unwrap();
+
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 6, 1));
+        buffer.set_string(0, 0, "a漢b", Style::default());
+        mirror.send(&buffer);
+        assert_eq!(mirror.viewers(), 1);
+        drop(mirror);
+        assert!(!path.exists());
+
+        let mut out = String::new();
+        viewer.read_to_string(&mut out).unwrap();
+        assert!(out.starts_with("\x1b[?25l\x1b[2J"));
+        assert!(out.contains("a漢"));
+        assert!(!out.contains("漢 "));
+        assert!(out.contains('b'));
+    }
+
+    #[test]
+    fn bind_replaces_only_a_dead_socket() {
+        let path = std::env::temp_dir().join(format!("bpftop-mirror-bind-{}.sock", std::process::id()));
+        let addr = path.to_str().unwrap();
+        let _ = fs::remove_file(&path);
+
+        let live = Mirror::bind(addr, false).unwrap();
+        assert!(Mirror::bind(addr, false).is_err());
+        drop(live);
+
+        // Nobody listening: left over from a crash
+        drop(UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+        let mirror = Mirror::bind(addr, false).unwrap();
+        drop(mirror);
+
+        assert!(Mirror::bind("0.0.0.0:0", false).is_err());
+        let mirror = Mirror::bind("127.0.0.1:0", false).unwrap();
+        assert!(mirror.is_tcp());
+    }
+
+    #[test]
+    fn a_busy_viewer_is_cleared_with_the_next_frame_it_takes() {
+        let path = std::env::temp_dir().join(format!("bpftop-mirror-clear-{}.sock", std::process::id()));
+        let mut mirror = Mirror::bind(path.to_str().unwrap(), false).unwrap();
+        let (tx, rx) = mpsc::sync_channel(1);
+        mirror.viewers.push(Viewer { tx, needs_clear: false });
+
+        mirror.send(&Buffer::empty(Rect::new(0, 0, 4, 1)));
+        assert!(rx.try_recv().unwrap().starts_with(CLEAR));
+        mirror.send(&Buffer::empty(Rect::new(0, 0, 4, 1)));
+        // Resized while the viewer still holds the last frame
+        mirror.send(&Buffer::empty(Rect::new(0, 0, 8, 2)));
+        assert!(!rx.try_recv().unwrap().starts_with(CLEAR));
+        mirror.send(&Buffer::empty(Rect::new(0, 0, 8, 2)));
+        assert!(rx.try_recv().unwrap().starts_with(CLEAR));
+        mirror.send(&Buffer::empty(Rect::new(0, 0, 8, 2)));
+        assert!(!rx.try_recv().unwrap().starts_with(CLEAR));
+    }
+}
@@ -0,0 +1,142 @@
+//! Masking of identifying columns for screenshots and shared exports
+//! (`--redact`, `[privacy] redact = [...]`).
+
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::hash::{BuildHasher, Hash, RandomState};
+use std::net::{IpAddr, SocketAddr};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// A kind of data `--redact` can mask.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum RedactField {
+    /// User names, except root.
+    Users,
+    /// Command line arguments and environment values; the program and
+    /// variable names stay.
+    Args,
+    /// Remote IP addresses, except loopback.
+    Ips,
+}
+
+impl RedactField {
+    pub const ALL: [RedactField; 3] = [RedactField::Users, RedactField::Args, RedactField::Ips];
+}
+
+/// Rewrites values for display and export. Masked users and IPs become
+/// short pseudonyms, so rows stay distinguishable. They are salted per run,
+/// so a pseudonym can't be reversed by hashing likely names.
+#[derive(Debug, Clone, Default)]
+pub struct Redact {
+    users: bool,
+    args: bool,
+    ips: bool,
+    salt: RandomState,
+}
+
+impl Redact {
+    pub fn new(fields: &[RedactField]) -> Self {
+        Self {
+            users: fields.contains(&RedactField::Users),
+            args: fields.contains(&RedactField::Args),
+            ips: fields.contains(&RedactField::Ips),
+            salt: RandomState::new(),
+        }
+    }
+
+    pub fn user<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        if !self.users || name.is_empty() || name == "root" {
+            return Cow::Borrowed(name);
+        }
+        Cow::Owned(format!("user-{:04x}", self.tag(name)))
+    }
+
+    /// Keep the program (argv[0]) and replace its arguments with "…". The
+    /// user name in a `/home/<name>` path is masked like a user.
+    pub fn cmdline<'a>(&self, cmdline: &'a str) -> Cow<'a, str> {
+        let cmdline = match cmdline.split_once(' ') {
+            Some((program, _)) if self.args => Cow::Owned(format!("{program} …")),
+            _ => Cow::Borrowed(cmdline),
+        };
+        if !(self.users || self.args) || !cmdline.contains("/home/") {
+            return cmdline;
+        }
+        let mut out = String::with_capacity(cmdline.len());
+        let mut rest = cmdline.as_ref();
+        while let Some(at) = rest.find("/home/") {
+            let (before, after) = rest.split_at(at + "/home/".len());
+            out.push_str(before);
+            let end = after.find(['/', ' ']).unwrap_or(after.len());
+            let name = &after[..end];
+            if !name.is_empty() {
+                let _ = write!(out, "user-{:04x}", self.tag(name));
+            }
+            rest = &after[end..];
+        }
+        out.push_str(rest);
+        Cow::Owned(out)
+    }
+
+    /// An environment variable's value, masked along with arguments.
+    pub fn env_value<'a>(&self, value: &'a str) -> &'a str {
+        if self.args && !value.is_empty() {
+            "…"
+        } else {
+            value
+        }
+    }
+
+    pub fn ip(&self, ip: IpAddr) -> String {
+        if !self.ips || ip.is_loopback() || ip.is_unspecified() {
+            return ip.to_string();
+        }
+        format!("ip-{:04x}", self.tag(&ip))
+    }
+
+    /// An address with its port, which stays.
+    pub fn socket_addr(&self, addr: SocketAddr) -> String {
+        if !self.ips || addr.ip().is_loopback() || addr.ip().is_unspecified() {
+            return addr.to_string();
+        }
+        format!("{}:{}", self.ip(addr.ip()), addr.port())
+    }
+
+    fn tag<T: Hash + ?Sized>(&self, value: &T) -> u64 {
+        self.salt.hash_one(value) & 0xffff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_only_the_selected_fields() {
+        let r = Redact::new(&[RedactField::Users, RedactField::Args]);
+        assert_eq!(r.user("root"), "root");
+        assert!(r.user("alice").starts_with("user-"));
+        assert_eq!(r.user("alice"), r.user("alice"));
+        assert_eq!(r.cmdline("/usr/bin/ssh alice@host"), "/usr/bin/ssh …");
+        assert_eq!(r.cmdline("[kworker/0:1]"), "[kworker/0:1]");
+        let home = r.cmdline("/home/alice/bin/tool --verbose");
+        assert_eq!(home, format!("/home/{}/bin/tool …", r.user("alice")));
+        assert_eq!(r.cmdline("/home/alice"), format!("/home/{}", r.user("alice")));
+        let ip: IpAddr = "203.0.113.7".parse().unwrap();
+        assert_eq!(r.ip(ip), "203.0.113.7");
+
+        let r = Redact::new(&[RedactField::Ips]);
+        assert!(r.ip(ip).starts_with("ip-"));
+        assert_eq!(r.ip("127.0.0.1".parse().unwrap()), "127.0.0.1");
+        assert!(r.socket_addr(SocketAddr::new(ip, 443)).ends_with(":443"));
+        assert_eq!(r.user("alice"), "alice");
+        assert_eq!(r.cmdline("/home/alice/bin/tool"), "/home/alice/bin/tool");
+
+        // Masking users alone still hides home directories in arguments
+        let r = Redact::new(&[RedactField::Users]);
+        let masked = r.cmdline("vim /home/alice/notes.txt");
+        assert_eq!(masked, format!("vim /home/{}/notes.txt", r.user("alice")));
+    }
+}
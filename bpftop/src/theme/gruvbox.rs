@@ -23,6 +23,14 @@ pub fn dark() -> Theme {
         swap_used: BR_ORANGE,
         gpu_util: BR_PURPLE,
         gpu_mem: BR_AQUA,
+        net_rate: BR_BLUE,
+        disk_read: BR_GREEN,
+        disk_write: BR_RED,
+        net_rx: BR_BLUE,
+        net_tx: BR_AQUA,
+        psi_cpu: BR_YELLOW,
+        psi_memory: BR_ORANGE,
+        psi_io: BR_PURPLE,
 
         // Process states
         proc_running: BR_GREEN,
@@ -30,6 +38,7 @@ pub fn dark() -> Theme {
         proc_zombie: BR_RED,
         proc_stopped: BR_YELLOW,
         proc_frozen: BR_BLUE,
+        command_basename: BR_AQUA,
 
         // Status bar
         status_bg: DARK_BG2,
@@ -67,6 +76,14 @@ pub fn light() -> Theme {
         swap_used: ORANGE,
         gpu_util: PURPLE,
         gpu_mem: AQUA,
+        net_rate: BLUE,
+        disk_read: GREEN,
+        disk_write: RED,
+        net_rx: BLUE,
+        net_tx: AQUA,
+        psi_cpu: YELLOW,
+        psi_memory: ORANGE,
+        psi_io: PURPLE,
 
         // Process states
         proc_running: GREEN,
@@ -74,6 +91,7 @@ pub fn light() -> Theme {
         proc_zombie: RED,
         proc_stopped: YELLOW,
         proc_frozen: BLUE,
+        command_basename: AQUA,
 
         // Status bar
         status_bg: LIGHT_BG2,
@@ -28,6 +28,14 @@ pub struct Theme {
     pub swap_used: Color,
     pub gpu_util: Color,
     pub gpu_mem: Color,
+    pub net_rate: Color,
+    pub disk_read: Color,
+    pub disk_write: Color,
+    pub net_rx: Color,
+    pub net_tx: Color,
+    pub psi_cpu: Color,
+    pub psi_memory: Color,
+    pub psi_io: Color,
 
     // Process states
     pub proc_running: Color,
@@ -36,6 +44,8 @@ pub struct Theme {
     pub proc_zombie: Color,
     pub proc_stopped: Color,
     pub proc_frozen: Color,
+    /// The program's name within the command line.
+    pub command_basename: Color,
 
     // Status bar
     pub status_bg: Color,
@@ -57,6 +67,9 @@ impl Default for Theme {
 }
 
 impl Theme {
+    /// Preset names accepted by `[theme] preset`.
+    pub const PRESETS: &'static [&'static str] = &["gruvbox-dark", "gruvbox-light"];
+
     /// Create a theme from a preset name, with optional color overrides.
     pub fn from_config(preset: &str, overrides: &ThemeOverrides) -> Self {
         let mut theme = match preset {
@@ -84,6 +97,15 @@ impl Theme {
         if let Some(c) = ov.swap_used.as_deref().and_then(parse_hex_color) { self.swap_used = c; }
         if let Some(c) = ov.gpu_util.as_deref().and_then(parse_hex_color) { self.gpu_util = c; }
         if let Some(c) = ov.gpu_mem.as_deref().and_then(parse_hex_color) { self.gpu_mem = c; }
+        if let Some(c) = ov.net_rate.as_deref().and_then(parse_hex_color) { self.net_rate = c; }
+        if let Some(c) = ov.disk_read.as_deref().and_then(parse_hex_color) { self.disk_read = c; }
+        if let Some(c) = ov.disk_write.as_deref().and_then(parse_hex_color) { self.disk_write = c; }
+        if let Some(c) = ov.net_rx.as_deref().and_then(parse_hex_color) { self.net_rx = c; }
+        if let Some(c) = ov.net_tx.as_deref().and_then(parse_hex_color) { self.net_tx = c; }
+        if let Some(c) = ov.psi_cpu.as_deref().and_then(parse_hex_color) { self.psi_cpu = c; }
+        if let Some(c) = ov.psi_memory.as_deref().and_then(parse_hex_color) { self.psi_memory = c; }
+        if let Some(c) = ov.psi_io.as_deref().and_then(parse_hex_color) { self.psi_io = c; }
+        if let Some(c) = ov.command_basename.as_deref().and_then(parse_hex_color) { self.command_basename = c; }
         if let Some(c) = ov.status_key.as_deref().and_then(parse_hex_color) { self.status_key = c; }
         if let Some(c) = ov.visual_bg.as_deref().and_then(parse_hex_color) { self.visual_bg = c; }
     }
@@ -108,12 +130,21 @@ pub struct ThemeOverrides {
     pub swap_used: Option<String>,
     pub gpu_util: Option<String>,
     pub gpu_mem: Option<String>,
+    pub net_rate: Option<String>,
+    pub disk_read: Option<String>,
+    pub disk_write: Option<String>,
+    pub net_rx: Option<String>,
+    pub net_tx: Option<String>,
+    pub psi_cpu: Option<String>,
+    pub psi_memory: Option<String>,
+    pub psi_io: Option<String>,
+    pub command_basename: Option<String>,
     pub status_key: Option<String>,
     pub visual_bg: Option<String>,
 }
 
 /// Parse a hex color string like "#fb4934" into a ratatui Color.
-fn parse_hex_color(s: &str) -> Option<Color> {
+pub(crate) fn parse_hex_color(s: &str) -> Option<Color> {
     let s = s.strip_prefix('#')?;
     if s.len() != 6 {
         return None;
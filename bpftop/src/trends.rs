@@ -0,0 +1,443 @@
+//! Long-term per-process samples in sqlite (`[trends]` in the config).
+//!
+//! The collector thread averages each process over `interval_secs` and
+//! writes one row per process per interval; `bpftop history` and the
+//! history popup's long-term view (`t`) read them back.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+
+use crate::config::TrendsConfig;
+use crate::data::history::{ProcessSeries, HISTORY_SAMPLES};
+use crate::data::process::{format_bytes, format_rate, ProcessInfo};
+use crate::dump::SCHEMA_VERSION;
+use crate::redact::Redact;
+use crate::ui::process_table::truncate_cols;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS samples (
+    ts INTEGER NOT NULL,
+    pid INTEGER NOT NULL,
+    start_time INTEGER NOT NULL,
+    comm TEXT NOT NULL,
+    cmdline TEXT NOT NULL,
+    cpu_percent REAL NOT NULL,
+    res_bytes INTEGER NOT NULL,
+    net_rate REAL NOT NULL,
+    disk_read_rate REAL NOT NULL,
+    disk_write_rate REAL NOT NULL
+);
+CREATE INDEX IF NOT EXISTS samples_pid ON samples (pid, ts);
+CREATE INDEX IF NOT EXISTS samples_comm ON samples (comm, ts);
+CREATE INDEX IF NOT EXISTS samples_ts ON samples (ts);
+";
+
+/// One process's samples within the current interval.
+#[derive(Default)]
+struct Accum {
+    comm: String,
+    cmdline: String,
+    samples: u32,
+    cpu_percent: f64,
+    /// Peak rather than mean, so a short spike survives downsampling.
+    res_bytes: u64,
+    net_rate: f64,
+    disk_read_rate: f64,
+    disk_write_rate: f64,
+}
+
+/// Averages process samples and writes them to the trends database.
+pub struct TrendRecorder {
+    conn: Connection,
+    interval: Duration,
+    retain: Duration,
+    window_start: Instant,
+    /// Keyed by (pid, start time) so a recycled PID isn't averaged in.
+    pending: HashMap<(u32, u64), Accum>,
+    /// Applied before anything is written, so `--redact` holds on disk too.
+    redact: Redact,
+}
+
+impl TrendRecorder {
+    /// Returns `None` when recording is disabled or the database can't be
+    /// opened (logged, so the UI still starts).
+    pub fn from_config(config: &TrendsConfig, redact: Redact) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        match config.path().and_then(|path| Self::open(&path, config, redact)) {
+            Ok(recorder) => Some(recorder),
+            Err(e) => {
+                log::warn!("Trend recording disabled: {e:#}");
+                None
+            }
+        }
+    }
+
+    fn open(path: &Path, config: &TrendsConfig, redact: Redact) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("creating {}", dir.display()))?;
+        }
+        let conn = Connection::open(path).with_context(|| format!("opening {}", path.display()))?;
+        Self::with_connection(conn, config, redact)
+    }
+
+    fn with_connection(conn: Connection, config: &TrendsConfig, redact: Redact) -> Result<Self> {
+        // Before anything is written, so a newer bpftop's database is left
+        // as it is. Older ones (0 predates versioning) need no migration yet
+        check_schema_version(&conn)?;
+        conn.execute_batch(SCHEMA).context("creating trends schema")?;
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)
+            .context("recording trends schema version")?;
+        Ok(Self {
+            conn,
+            interval: Duration::from_secs(config.interval_secs.max(1)),
+            retain: Duration::from_secs(config.retain_days * 86400),
+            window_start: Instant::now(),
+            pending: HashMap::new(),
+            redact,
+        })
+    }
+
+    /// Fold in one collect cycle, writing a row per process once the
+    /// interval has elapsed.
+    pub fn maybe_record(&mut self, processes: &[ProcessInfo]) {
+        for p in processes.iter().filter(|p| !p.is_thread && !p.is_kernel_thread) {
+            let acc = self.pending.entry((p.pid, p.start_time_ns)).or_default();
+            if acc.samples == 0 {
+                acc.comm = p.comm.clone();
+                acc.cmdline = self.redact.cmdline(&p.cmdline).into_owned();
+            }
+            acc.samples += 1;
+            acc.cpu_percent += p.cpu_percent;
+            acc.res_bytes = acc.res_bytes.max(p.res_bytes);
+            acc.net_rate += p.net_rate;
+            acc.disk_read_rate += p.disk_read_rate;
+            acc.disk_write_rate += p.disk_write_rate;
+        }
+        if self.window_start.elapsed() < self.interval {
+            return;
+        }
+        self.window_start = Instant::now();
+        if let Err(e) = self.flush() {
+            log::warn!("Writing trend samples failed: {e:#}");
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let pending = std::mem::take(&mut self.pending);
+        let tx = self.conn.transaction().context("starting transaction")?;
+        {
+            let mut insert = tx.prepare_cached(
+                "INSERT INTO samples (ts, pid, start_time, comm, cmdline, cpu_percent, res_bytes,
+                     net_rate, disk_read_rate, disk_write_rate)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            )?;
+            for ((pid, start_time), acc) in &pending {
+                let n = acc.samples as f64;
+                insert.execute(params![
+                    now,
+                    pid,
+                    start_time,
+                    acc.comm,
+                    acc.cmdline,
+                    acc.cpu_percent / n,
+                    acc.res_bytes,
+                    acc.net_rate / n,
+                    acc.disk_read_rate / n,
+                    acc.disk_write_rate / n,
+                ])?;
+            }
+        }
+        if !self.retain.is_zero() {
+            let cutoff = now.saturating_sub(self.retain.as_secs());
+            tx.execute("DELETE FROM samples WHERE ts < ?1", params![cutoff])
+                .context("pruning old samples")?;
+        }
+        tx.commit().context("committing samples")?;
+        Ok(())
+    }
+}
+
+/// A stored row as read back by `bpftop history`.
+struct TrendRow {
+    time: String,
+    pid: u32,
+    comm: String,
+    cpu_percent: f64,
+    res_bytes: u64,
+    net_rate: f64,
+    disk_read_rate: f64,
+    disk_write_rate: f64,
+}
+
+/// Print the recorded samples for a PID or command name over the last
+/// `since_secs` seconds, oldest first.
+pub fn print_history(config: &TrendsConfig, target: &str, since_secs: u64) -> Result<()> {
+    let path = config.path()?;
+    if !path.exists() {
+        anyhow::bail!(
+            "no trends database at {} (set enabled = true under [trends] and let bpftop run)",
+            path.display()
+        );
+    }
+    let conn = Connection::open(&path).with_context(|| format!("opening {}", path.display()))?;
+    check_schema_version(&conn)?;
+    let cutoff = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        .saturating_sub(since_secs);
+
+    // A number is a PID; anything else matches the command name
+    let pid = target.parse::<u32>().ok();
+    let filter = if pid.is_some() { "pid" } else { "comm" };
+    let mut stmt = conn.prepare(&format!(
+        "SELECT datetime(ts, 'unixepoch', 'localtime'), pid, comm, cpu_percent, res_bytes,
+             net_rate, disk_read_rate, disk_write_rate
+         FROM samples WHERE {filter} = ?1 AND ts >= ?2 ORDER BY ts, pid"
+    ))?;
+    let rows = match pid {
+        Some(pid) => stmt.query_map(params![pid, cutoff], TrendRow::from_row)?,
+        None => stmt.query_map(params![target, cutoff], TrendRow::from_row)?,
+    }
+    .collect::<rusqlite::Result<Vec<_>>>()
+    .context("reading samples")?;
+
+    if rows.is_empty() {
+        anyhow::bail!("no samples for {target} in the requested window");
+    }
+
+    let mut out = io::stdout().lock();
+    // A closed pipe (e.g. `| head`) is a normal way to stop
+    match write_history(&mut out, &rows).and_then(|_| out.flush()) {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        r => Ok(r?),
+    }
+}
+
+/// One process's recorded samples, for replaying in the history popup.
+pub struct TrendSeries {
+    /// Downsampled to at most [`HISTORY_SAMPLES`] points, oldest first.
+    pub series: ProcessSeries,
+    /// Seconds from the first recorded row to the last.
+    pub span_secs: u64,
+}
+
+/// Read back everything recorded for the process with this PID and start
+/// time, averaged into buckets so the whole retained window fits the graphs.
+pub fn load_series(config: &TrendsConfig, pid: u32, start_time_ns: u64) -> Result<TrendSeries> {
+    let path = config.path()?;
+    if !path.exists() {
+        anyhow::bail!("No trends recorded yet (set enabled = true under [trends])");
+    }
+    let conn = Connection::open(&path).with_context(|| format!("opening {}", path.display()))?;
+    check_schema_version(&conn)?;
+    read_series(&conn, pid, start_time_ns)
+}
+
+/// Refuse a database written by a newer bpftop, whose schema this one
+/// can't know.
+fn check_schema_version(conn: &Connection) -> Result<()> {
+    let version: u32 = conn
+        .pragma_query_value(None, "user_version", |row| row.get(0))
+        .context("reading trends schema version")?;
+    if version > SCHEMA_VERSION {
+        anyhow::bail!(
+            "trends database has schema version {version}, newer than this bpftop's {SCHEMA_VERSION}; \
+             upgrade bpftop or point [trends] path elsewhere"
+        );
+    }
+    Ok(())
+}
+
+fn read_series(conn: &Connection, pid: u32, start_time_ns: u64) -> Result<TrendSeries> {
+    let mut stmt = conn.prepare(
+        "SELECT ts, cpu_percent, res_bytes, net_rate FROM samples
+         WHERE pid = ?1 AND start_time = ?2 ORDER BY ts",
+    )?;
+    let rows = stmt
+        .query_map(params![pid, start_time_ns], |row| {
+            Ok((
+                row.get::<_, u64>(0)?,
+                row.get::<_, f64>(1)?,
+                row.get::<_, u64>(2)?,
+                row.get::<_, f64>(3)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("reading samples")?;
+    let (Some(first), Some(last)) = (rows.first(), rows.last()) else {
+        anyhow::bail!("No samples recorded for this process yet");
+    };
+    let span_secs = last.0 - first.0;
+
+    let mut series = ProcessSeries::default();
+    for bucket in rows.chunks(rows.len().div_ceil(HISTORY_SAMPLES)) {
+        let n = bucket.len() as f64;
+        series.cpu.push_back(bucket.iter().map(|r| r.1).sum::<f64>() / n);
+        // Peak, as when recording
+        series.rss.push_back(bucket.iter().map(|r| r.2).max().unwrap_or(0) as f64);
+        series.net.push_back(bucket.iter().map(|r| r.3).sum::<f64>() / n);
+    }
+    Ok(TrendSeries { series, span_secs })
+}
+
+impl TrendRow {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            time: row.get(0)?,
+            pid: row.get(1)?,
+            comm: row.get(2)?,
+            cpu_percent: row.get(3)?,
+            res_bytes: row.get(4)?,
+            net_rate: row.get(5)?,
+            disk_read_rate: row.get(6)?,
+            disk_write_rate: row.get(7)?,
+        })
+    }
+}
+
+fn write_history(out: &mut impl Write, rows: &[TrendRow]) -> io::Result<()> {
+    writeln!(
+        out,
+        "{:<19} {:>7} {:>6} {:>6} {:>9} {:>9} {:>9}  COMMAND",
+        "TIME", "PID", "CPU%", "RES", "NET/s", "DISK R/s", "DISK W/s"
+    )?;
+    for r in rows {
+        writeln!(
+            out,
+            "{:<19} {:>7} {:>6.1} {:>6} {:>9} {:>9} {:>9}  {}",
+            r.time,
+            r.pid,
+            r.cpu_percent,
+            format_bytes(r.res_bytes),
+            format_rate(r.net_rate),
+            format_rate(r.disk_read_rate),
+            format_rate(r.disk_write_rate),
+            truncate_cols(&r.comm, 16)
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redact::RedactField;
+    use crate::ui_bench::synthetic_process;
+
+    fn process(pid: u32, cpu_percent: f64, res_bytes: u64) -> ProcessInfo {
+        ProcessInfo {
+            start_time_ns: 1000,
+            cpu_percent,
+            res_bytes,
+            ..synthetic_process(pid, 1, "worker")
+        }
+    }
+
+    fn recorder() -> TrendRecorder {
+        let conn = Connection::open_in_memory().unwrap();
+        TrendRecorder::with_connection(conn, &TrendsConfig::default(), Redact::default()).unwrap()
+    }
+
+    #[test]
+    fn refuses_a_newer_schema() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1).unwrap();
+        let err = TrendRecorder::with_connection(conn, &TrendsConfig::default(), Redact::default()).err().unwrap();
+        assert!(err.to_string().contains("newer than this bpftop"));
+
+        // Unversioned databases from before are stamped with the current one
+        let conn = Connection::open_in_memory().unwrap();
+        let recorder = TrendRecorder::with_connection(conn, &TrendsConfig::default(), Redact::default()).unwrap();
+        let version: u32 = recorder
+            .conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn averages_each_interval_into_one_row() {
+        let mut recorder = recorder();
+        let thread = ProcessInfo {
+            is_thread: true,
+            ..process(43, 90.0, 0)
+        };
+        recorder.maybe_record(&[process(42, 10.0, 300), thread.clone()]);
+        recorder.maybe_record(&[process(42, 30.0, 100), thread]);
+        recorder.flush().unwrap();
+        assert!(recorder.pending.is_empty());
+
+        let trend = read_series(&recorder.conn, 42, 1000).unwrap();
+        assert_eq!(trend.series.cpu, [20.0]);
+        // Memory keeps the peak so a spike survives
+        assert_eq!(trend.series.rss, [300.0]);
+        assert_eq!(trend.span_secs, 0);
+        // Threads are folded into their process, not stored
+        assert!(read_series(&recorder.conn, 43, 1000).is_err());
+        // A recycled PID is a different process
+        assert!(read_series(&recorder.conn, 42, 2000).is_err());
+    }
+
+    #[test]
+    fn stores_the_redacted_cmdline() {
+        let conn = Connection::open_in_memory().unwrap();
+        let redact = Redact::new(&[RedactField::Args]);
+        let mut recorder = TrendRecorder::with_connection(conn, &TrendsConfig::default(), redact).unwrap();
+        let p = ProcessInfo {
+            cmdline: "/home/alice/bin/sync --token=secret".into(),
+            ..process(42, 10.0, 0)
+        };
+        recorder.maybe_record(&[p]);
+        recorder.flush().unwrap();
+
+        let cmdline: String = recorder
+            .conn
+            .query_row("SELECT cmdline FROM samples WHERE pid = 42", [], |row| row.get(0))
+            .unwrap();
+        assert!(cmdline.ends_with("/bin/sync …"));
+        assert!(!cmdline.contains("alice") && !cmdline.contains("secret"));
+    }
+
+    #[test]
+    fn prunes_rows_past_retention_and_downsamples_long_windows() {
+        let mut recorder = recorder();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let old = now - 8 * 86400;
+        for i in 0..HISTORY_SAMPLES as u64 * 2 {
+            recorder
+                .conn
+                .execute(
+                    "INSERT INTO samples VALUES (?1, 42, 1000, 'worker', '', ?2, 0, 0, 0, 0)",
+                    params![now - 3600 + i, i as f64],
+                )
+                .unwrap();
+        }
+        recorder
+            .conn
+            .execute(
+                "INSERT INTO samples VALUES (?1, 42, 1000, 'worker', '', 0, 0, 0, 0, 0)",
+                params![old],
+            )
+            .unwrap();
+
+        recorder.flush().unwrap();
+        let trend = read_series(&recorder.conn, 42, 1000).unwrap();
+        assert_eq!(trend.series.cpu.len(), HISTORY_SAMPLES);
+        assert_eq!(trend.series.cpu[0], 0.5);
+        assert_eq!(trend.span_secs, HISTORY_SAMPLES as u64 * 2 - 1);
+    }
+}
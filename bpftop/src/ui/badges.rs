@@ -0,0 +1,148 @@
+use ratatui::style::Color;
+use regex::Regex;
+
+use crate::config::{BadgeRule, BadgesConfig};
+use crate::data::process::ProcessInfo;
+use crate::theme::palette::{AQUA, BLUE, ORANGE, PURPLE, RED, YELLOW};
+use crate::theme::parse_hex_color;
+
+/// Built-in workload profiles: label, comm regex, cmdline regex, color.
+/// comm is at most 15 characters, hence the truncated browser names.
+const BUILTIN: &[(&str, Option<&str>, Option<&str>, Color)] = &[
+    (
+        "db",
+        Some(r"^(postgres|postmaster|mysqld|mariadbd|mongod|mongos|redis-server|valkey-server|memcached|etcd|clickhouse.*|cockroach|influxd)$"),
+        None,
+        BLUE,
+    ),
+    ("jvm", Some(r"^java$"), Some(r"^\S*/java\s"), ORANGE),
+    (
+        "cc",
+        Some(r"^(cc1|cc1plus|cc1obj|rustc|gcc|g\+\+|clang|clang\+\+|ld|ld\.lld|ld\.gold|mold|javac|tsc|go|compile|cargo|make|ninja)$"),
+        None,
+        YELLOW,
+    ),
+    (
+        "web",
+        Some(r"^(firefox.*|Web Content|WebExtensions|Isolated Web Co|Privileged Cont|RDD Process|chrome|chromium.*|brave|msedge|WebKitWebProces)$"),
+        None,
+        PURPLE,
+    ),
+    (
+        "ctr",
+        Some(r"^(dockerd|containerd|containerd-shim|runc|crun|podman|conmon|kubelet|crio|buildkitd)$"),
+        None,
+        AQUA,
+    ),
+    (
+        "interp",
+        Some(r"^(python[0-9.]*|node|nodejs|deno|bun|ruby[0-9.]*|perl[0-9.]*|php[0-9.]*|php-fpm.*|lua[0-9.]*|luajit|Rscript|erl|beam\.smp)$"),
+        None,
+        RED,
+    ),
+];
+
+/// A workload tag drawn in front of the command line.
+#[derive(Debug, Clone)]
+pub struct Badge {
+    pub label: String,
+    pub color: Color,
+}
+
+struct Rule {
+    /// Index into `Badges::badges`; `None` for a rule with an empty label,
+    /// which suppresses the badge.
+    badge: Option<usize>,
+    comm: Option<Regex>,
+    cmdline: Option<Regex>,
+}
+
+/// Workload profile rules: `[badges] rules` first, then the built-in
+/// ones. The first rule whose comm or cmdline pattern matches wins.
+#[derive(Default)]
+pub struct Badges {
+    badges: Vec<Badge>,
+    rules: Vec<Rule>,
+}
+
+impl Badges {
+    pub fn from_config(config: &BadgesConfig) -> Self {
+        let mut badges = Self::default();
+        if !config.enabled {
+            return badges;
+        }
+        for rule in &config.rules {
+            badges.add_user_rule(rule);
+        }
+        for &(label, comm, cmdline, color) in BUILTIN {
+            let compile = |p: Option<&str>| p.map(|p| Regex::new(p).expect("built-in badge regex"));
+            let badge = Some(badges.badge_index(label, color));
+            badges.rules.push(Rule {
+                badge,
+                comm: compile(comm),
+                cmdline: compile(cmdline),
+            });
+        }
+        badges
+    }
+
+    fn add_user_rule(&mut self, rule: &BadgeRule) {
+        let compile = |p: &Option<String>| -> Result<Option<Regex>, ()> {
+            match p.as_deref().map(Regex::new).transpose() {
+                Ok(re) => Ok(re),
+                Err(e) => {
+                    log::warn!("badges: rule {:?}: {e}; ignoring", rule.label);
+                    Err(())
+                }
+            }
+        };
+        let (Ok(comm), Ok(cmdline)) = (compile(&rule.comm), compile(&rule.cmdline)) else {
+            return;
+        };
+        if comm.is_none() && cmdline.is_none() {
+            log::warn!("badges: rule {:?} has neither comm nor cmdline; ignoring", rule.label);
+            return;
+        }
+        let color = match rule.color.as_deref() {
+            None => YELLOW,
+            Some(hex) => parse_hex_color(hex).unwrap_or_else(|| {
+                log::warn!("badges: rule {:?}: bad color {hex:?}", rule.label);
+                YELLOW
+            }),
+        };
+        let badge = (!rule.label.is_empty()).then(|| self.badge_index(&rule.label, color));
+        self.rules.push(Rule { badge, comm, cmdline });
+    }
+
+    /// Index of the badge with this label, added if new. The first color
+    /// given for a label is kept.
+    fn badge_index(&mut self, label: &str, color: Color) -> usize {
+        match self.badges.iter().position(|b| b.label == label) {
+            Some(i) => i,
+            None => {
+                self.badges.push(Badge {
+                    label: label.to_string(),
+                    color,
+                });
+                self.badges.len() - 1
+            }
+        }
+    }
+
+    /// Badge index for `proc`, to store in `ProcessInfo::badge`. Threads
+    /// show their own name rather than the cmdline, so they get none.
+    pub fn classify(&self, proc: &ProcessInfo) -> Option<usize> {
+        if proc.is_thread || proc.is_kernel_thread {
+            return None;
+        }
+        let hit = |re: &Option<Regex>, text: &str| re.as_ref().is_some_and(|re| re.is_match(text));
+        self.rules
+            .iter()
+            .find(|r| hit(&r.comm, &proc.comm) || hit(&r.cmdline, &proc.cmdline))
+            .and_then(|r| r.badge)
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Badge> {
+        self.badges.get(index)
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::widgets::{Paragraph, StatefulWidget, Widget};
+
+use crate::data::bpf_objects::BpfObjects;
+use crate::data::process::{format_bytes, format_ns};
+use crate::theme::Theme;
+use crate::ui::overlay::{render_column_header, render_footer, render_frame, Scroll};
+use crate::ui::process_table::truncate_cols;
+
+/// Overlay listing every BPF program (costliest first) or map loaded on
+/// the system, with the processes holding them.
+pub struct BpfObjectsPanel<'a> {
+    /// `None` until the collector has sampled.
+    pub objects: Option<&'a BpfObjects>,
+    /// Maps instead of programs.
+    pub show_maps: bool,
+    /// PID -> command name, for the holders column.
+    pub comms: &'a HashMap<u32, String>,
+    pub theme: &'a Theme,
+}
+
+impl BpfObjectsPanel<'_> {
+    /// "bpftop(812), systemd(1)", or "-" when no process holds it.
+    fn holders(&self, pids: &[u32]) -> String {
+        if pids.is_empty() {
+            return "-".to_string();
+        }
+        pids.iter()
+            .map(|pid| match self.comms.get(pid) {
+                Some(comm) => format!("{comm}({pid})"),
+                None => pid.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl<'a> StatefulWidget for BpfObjectsPanel<'a> {
+    type State = Scroll;
+
+    fn render(self, area: Rect, buf: &mut Buffer, scroll: &mut Scroll) {
+        let default_objects = BpfObjects::default();
+        let objects = self.objects.unwrap_or(&default_objects);
+        let title = if self.show_maps {
+            format!(" BPF maps: {} ", objects.maps.len())
+        } else {
+            format!(" BPF programs: {} ", objects.programs.len())
+        };
+        let (inner, footer_area) = render_frame(title, (90, 80), area, buf, self.theme);
+
+        let text_style = Style::default().fg(self.theme.fg);
+        let dim_style = Style::default().fg(self.theme.border);
+
+        let header = if self.show_maps {
+            format!(
+                "{:>6} {:<14} {:<16} {:>9} {:>9} {:>8}  {}",
+                "ID", "TYPE", "NAME", "KEY/VAL", "MAX", "MEM", "HELD BY"
+            )
+        } else {
+            format!(
+                "{:>6} {:<14} {:<16} {:>12} {:>9} {:>8} {:>7} {:>8}  {}",
+                "ID", "TYPE", "NAME", "RUNS", "RUNS/s", "AVG", "CPU%", "MEM", "HELD BY"
+            )
+        };
+        let content_area = render_column_header(header, inner, buf, self.theme);
+
+        let total = if self.show_maps { objects.maps.len() } else { objects.programs.len() };
+        let rows = scroll.fit(total, content_area.height as usize);
+        let lines: Vec<Line> = if self.objects.is_none() {
+            vec![Line::styled("  Enabling run-time stats...", dim_style)]
+        } else if total == 0 {
+            vec![Line::styled("  Nothing loaded (or not allowed to list it)", dim_style)]
+        } else if self.show_maps {
+            objects.maps[rows]
+                .iter()
+                .map(|m| {
+                    let mem = m.memlock_bytes.map_or_else(|| "?".to_string(), format_bytes);
+                    Line::styled(
+                        format!(
+                            "{:>6} {:<14} {:<16} {:>9} {:>9} {:>8}  {}",
+                            m.id,
+                            truncate_cols(&m.kind, 14),
+                            truncate_cols(&m.name, 16),
+                            format!("{}/{}", m.key_size, m.value_size),
+                            m.max_entries,
+                            mem,
+                            self.holders(&m.pids)
+                        ),
+                        text_style,
+                    )
+                })
+                .collect()
+        } else {
+            objects.programs[rows]
+                .iter()
+                .map(|p| {
+                    let style = if p.runs_per_sec > 0.0 { text_style } else { dim_style };
+                    Line::styled(
+                        format!(
+                            "{:>6} {:<14} {:<16} {:>12} {:>9.0} {:>8} {:>7.2} {:>8}  {}",
+                            p.id,
+                            truncate_cols(&p.kind, 14),
+                            truncate_cols(&p.name, 16),
+                            p.total_runs,
+                            p.runs_per_sec,
+                            format_ns(p.avg_ns),
+                            p.cpu_percent,
+                            format_bytes(p.memlock_bytes),
+                            self.holders(&p.pids)
+                        ),
+                        style,
+                    )
+                })
+                .collect()
+        };
+        Paragraph::new(lines).render(content_area, buf);
+
+        let other = if self.show_maps { "Programs" } else { "Maps" };
+        render_footer(
+            &[("j/k", "Scroll"), ("Tab", other), ("Esc", "Close")],
+            Vec::new(),
+            scroll.position(total),
+            footer_area,
+            buf,
+            self.theme,
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, Widget};
+
+use crate::data::collector::{BpfStats, ProgramLoad, MAP_FULL_PERCENT};
+use crate::data::process::format_ns;
+use crate::theme::Theme;
+use crate::ui::overlay::{render_column_header, render_footer, render_frame};
+use crate::ui::process_table::truncate_cols;
+
+/// Overlay with the run count and CPU cost of each of bpftop's own eBPF
+/// programs, costliest first, and how full its hash maps are.
+pub struct BpfStatsPanel<'a> {
+    /// `None` until the collector has sampled with run-time stats enabled.
+    pub stats: Option<&'a BpfStats>,
+    pub theme: &'a Theme,
+}
+
+impl<'a> Widget for BpfStatsPanel<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (inner, footer_area) =
+            render_frame(" eBPF overhead ".to_string(), (70, 70), area, buf, self.theme);
+
+        let key_style = Style::default()
+            .fg(self.theme.status_key)
+            .add_modifier(Modifier::BOLD);
+        let header_style = Style::default()
+            .fg(self.theme.column_header_fg)
+            .bg(self.theme.column_header_bg)
+            .add_modifier(Modifier::BOLD);
+        let text_style = Style::default().fg(self.theme.fg);
+        let dim_style = Style::default().fg(self.theme.border);
+        let warn_style = Style::default()
+            .fg(self.theme.proc_zombie)
+            .add_modifier(Modifier::BOLD);
+
+        let maps_header = Line::styled(
+            format!("{:<24} {:>12} {:>10} {:>9}", "MAP", "ENTRIES", "MAX", "FULL"),
+            header_style,
+        );
+
+        let table_area = render_column_header(
+            format!(
+                "{:<24} {:>12} {:>10} {:>9} {:>7}",
+                "PROGRAM", "RUNS", "RUNS/s", "AVG", "CPU%"
+            ),
+            inner,
+            buf,
+            self.theme,
+        );
+        // The last row holds the total
+        let content_area = Rect {
+            height: table_area.height.saturating_sub(1),
+            ..table_area
+        };
+
+        let default_stats = BpfStats::default();
+        let stats = self.stats.unwrap_or(&default_stats);
+        let mut programs: Vec<&ProgramLoad> = stats.programs.iter().collect();
+        programs.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent));
+        let mut lines = Vec::new();
+        if self.stats.is_none() {
+            lines.push(Line::styled("  Enabling run-time stats...", dim_style));
+        } else if programs.is_empty() {
+            lines.push(Line::styled("  No programs loaded", dim_style));
+        }
+        for p in &programs {
+            let style = if p.runs_per_sec > 0.0 { text_style } else { dim_style };
+            lines.push(Line::styled(
+                format!(
+                    "{:<24} {:>12} {:>10.0} {:>9} {:>7.2}",
+                    truncate_cols(&p.name, 24),
+                    p.total_runs,
+                    p.runs_per_sec,
+                    format_ns(p.avg_ns),
+                    p.cpu_percent
+                ),
+                style,
+            ));
+        }
+
+        // Plain hash maps drop new entries once full
+        lines.push(Line::raw(""));
+        lines.push(maps_header);
+        for m in &stats.maps {
+            let percent = m.entries as f64 * 100.0 / m.max_entries.max(1) as f64;
+            let style = if percent >= MAP_FULL_PERCENT { warn_style } else { text_style };
+            lines.push(Line::styled(
+                format!(
+                    "{:<24} {:>12} {:>10} {:>8.0}%",
+                    m.name, m.entries, m.max_entries, percent
+                ),
+                style,
+            ));
+        }
+        Paragraph::new(lines).render(content_area, buf);
+
+        let total: f64 = programs.iter().map(|p| p.cpu_percent).sum();
+        let total_area = Rect {
+            y: content_area.y + content_area.height,
+            height: table_area.height.min(1),
+            ..table_area
+        };
+        Paragraph::new(Line::from(vec![
+            Span::styled("Total in kernel: ", text_style),
+            Span::styled(format!("{total:.2}% of one CPU"), key_style),
+        ]))
+        .render(total_area, buf);
+
+        render_footer(
+            &[("Esc", "Close")],
+            vec![Span::styled("  run-time stats are enabled while this is open", dim_style)],
+            None,
+            footer_area,
+            buf,
+            self.theme,
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, Widget};
+
+use crate::data::cgroup_tree::CgroupRow;
+use crate::data::process::format_bytes;
+use crate::theme::Theme;
+use crate::ui::overlay::{render_column_header, render_footer, render_frame};
+use crate::ui::process_table::truncate_cols;
+
+/// Width of the TASKS, PROCS, CPU% and RES columns together.
+const NUMBERS_WIDTH: usize = 6 + 1 + 6 + 1 + 7 + 1 + 8;
+
+/// Overlay showing CPU, memory and task counts per cgroup, each including
+/// the cgroups below it.
+pub struct CgroupsPanel<'a> {
+    pub rows: &'a [CgroupRow],
+    pub selected: usize,
+    pub theme: &'a Theme,
+}
+
+impl<'a> Widget for CgroupsPanel<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (inner, footer_area) =
+            render_frame(" Cgroups ".to_string(), (85, 80), area, buf, self.theme);
+
+        let text_style = Style::default().fg(self.theme.fg);
+        let dim_style = Style::default().fg(self.theme.border);
+
+        let name_width = (inner.width as usize).saturating_sub(NUMBERS_WIDTH + 1).max(16);
+        let content_area = render_column_header(
+            format!(
+                "{:<name_width$} {:>6} {:>6} {:>7} {:>8}",
+                "CGROUP", "TASKS", "PROCS", "CPU%", "RES"
+            ),
+            inner,
+            buf,
+            self.theme,
+        );
+        let content_height = content_area.height as usize;
+
+        let selected = self.selected.min(self.rows.len().saturating_sub(1));
+        // Keep the selected row in view
+        let scroll = (selected + 1).saturating_sub(content_height);
+        let lines: Vec<Line> = self
+            .rows
+            .iter()
+            .enumerate()
+            .skip(scroll)
+            .take(content_height)
+            .map(|(i, row)| {
+                let indicator = if !row.has_children {
+                    " "
+                } else if row.collapsed {
+                    "+"
+                } else {
+                    "-"
+                };
+                let label = format!("{}{} {}", row.prefix, indicator, row.name);
+                let text = format!(
+                    "{:<name_width$} {:>6} {:>6} {:>7.1} {:>8}",
+                    truncate_cols(&label, name_width),
+                    row.tasks,
+                    row.processes,
+                    row.cpu_percent,
+                    format_bytes(row.res_bytes)
+                );
+                let style = if i == selected {
+                    Style::default()
+                        .fg(self.theme.selection_fg)
+                        .bg(self.theme.selection_bg)
+                } else if row.tasks == 0 {
+                    dim_style
+                } else {
+                    text_style
+                };
+                Line::styled(text, style)
+            })
+            .collect();
+        Paragraph::new(lines).render(content_area, buf);
+
+        let path = self
+            .rows
+            .get(selected)
+            .map(|row| Span::styled(format!("  {}", row.path), dim_style));
+        render_footer(
+            &[
+                ("j/k", "Select"),
+                ("Space", "Fold"),
+                ("Enter", "Show processes"),
+                ("Esc", "Close"),
+            ],
+            path.into_iter().collect(),
+            None,
+            footer_area,
+            buf,
+            self.theme,
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::widgets::{Paragraph, Widget};
+
+use crate::data::files::OpenFile;
+use crate::redact::Redact;
+use crate::theme::Theme;
+use crate::ui::overlay::{render_column_header, render_footer, render_frame};
+use crate::ui::process_table::truncate_cols;
+
+/// Width of the NETNS column.
+const NETNS_WIDTH: usize = 16;
+
+/// One socket row with the names it is shown with.
+pub struct ConnRow<'a> {
+    pub file: &'a OpenFile,
+    /// Command of the owning process (shown in all-processes mode).
+    pub comm: &'a str,
+    /// Network namespace label, e.g. "host" or a container name.
+    pub netns: &'a str,
+}
+
+/// Overlay listing TCP/UDP sockets of one process, or of every process
+/// grouped by network namespace.
+pub struct ConnectionsPanel<'a> {
+    pub pid: u32,
+    pub comm: &'a str,
+    /// Inet sockets only; `None` until the collector has produced data for `pid`.
+    pub rows: Option<&'a [ConnRow<'a>]>,
+    /// Rows come from all processes (adds PID and COMMAND columns).
+    pub all: bool,
+    /// Label of the netns the rows are narrowed to, if any.
+    pub netns_filter: Option<&'a str>,
+    /// Rows are sorted by RTT, slowest first.
+    pub sort_rtt: bool,
+    pub selected: usize,
+    pub redact: &'a Redact,
+    pub theme: &'a Theme,
+}
+
+impl<'a> Widget for ConnectionsPanel<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let scope = if self.all {
+            "all processes".to_string()
+        } else {
+            format!("{} ({})", self.comm, self.pid)
+        };
+        let scope = match self.netns_filter {
+            Some(ns) => format!("{scope}, netns {ns}"),
+            None => scope,
+        };
+        let title = match self.rows {
+            Some(rows) => format!(" Connections: {scope} - {} sockets ", rows.len()),
+            None => format!(" Connections: {scope} "),
+        };
+        let (inner, footer_area) = render_frame(title, (80, 70), area, buf, self.theme);
+
+        let text_style = Style::default().fg(self.theme.fg);
+        let dim_style = Style::default().fg(self.theme.border);
+
+        let owner_header = if self.all {
+            format!("{:>7} {:<15} ", "PID", "COMMAND")
+        } else {
+            String::new()
+        };
+        let content_area = render_column_header(
+            format!(
+                "{owner_header}{:>5}  {:<5} {:<NETNS_WIDTH$} {:<46} {:<46} {:>8} {:>5} {}",
+                "FD",
+                "PROTO",
+                "NETNS",
+                "LOCAL",
+                "REMOTE",
+                if self.sort_rtt { "RTTv" } else { "RTT" },
+                "CWND",
+                "STATE"
+            ),
+            inner,
+            buf,
+            self.theme,
+        );
+        let content_height = content_area.height as usize;
+
+        let lines: Vec<Line> = match self.rows {
+            None => vec![Line::styled("  Waiting for next refresh...", dim_style)],
+            Some([]) => vec![Line::styled("  No TCP/UDP sockets", dim_style)],
+            Some(rows) => {
+                // Keep the selected row in view
+                let selected = self.selected.min(rows.len() - 1);
+                let scroll = (selected + 1).saturating_sub(content_height);
+                rows.iter()
+                    .enumerate()
+                    .skip(scroll)
+                    .take(content_height)
+                    .filter_map(|(i, row)| {
+                        let f = row.file;
+                        let sock = f.socket.as_ref()?;
+                        let remote = if sock.remote.port() == 0 {
+                            "*".to_string()
+                        } else {
+                            self.redact.socket_addr(sock.remote)
+                        };
+                        let owner = if self.all {
+                            format!("{:>7} {:<15} ", f.pid, truncate_cols(row.comm, 15))
+                        } else {
+                            String::new()
+                        };
+                        let text = format!(
+                            "{owner}{:>5}  {:<5} {:<NETNS_WIDTH$} {:<46} {:<46} {:>8} {:>5} {}",
+                            f.fd_label(),
+                            sock.protocol(),
+                            truncate_cols(row.netns, NETNS_WIDTH),
+                            sock.local,
+                            remote,
+                            sock.rtt_label(),
+                            sock.cwnd_label(),
+                            sock.state_name()
+                        );
+                        let style = if i == selected {
+                            Style::default()
+                                .fg(self.theme.selection_fg)
+                                .bg(self.theme.selection_bg)
+                        } else {
+                            text_style
+                        };
+                        Some(Line::styled(text, style))
+                    })
+                    .collect()
+            }
+        };
+        Paragraph::new(lines).render(content_area, buf);
+
+        render_footer(
+            &[
+                ("j/k", "Select"),
+                ("y", "Copy remote address"),
+                ("a", if self.all { "This process" } else { "All processes" }),
+                ("r", if self.sort_rtt { "Unsorted" } else { "Sort by RTT" }),
+                ("n", if self.netns_filter.is_some() { "All netns" } else { "Same netns" }),
+                ("Esc", "Close"),
+            ],
+            Vec::new(),
+            None,
+            footer_area,
+            buf,
+            self.theme,
+        );
+    }
+}
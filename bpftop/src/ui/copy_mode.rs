@@ -0,0 +1,211 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::Widget;
+
+use crate::theme::Theme;
+
+/// (row, col) in buffer coordinates.
+type Pos = (usize, usize);
+
+/// How the selection extends from the anchor to the cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionKind {
+    /// Reading order, like vim `v`.
+    Char,
+    /// Whole lines, like vim `V`.
+    Line,
+}
+
+/// A frozen text copy of the screen, navigable like a tmux copy-mode buffer.
+///
+/// Cells are kept one symbol per terminal column so cursor positions line up
+/// with mouse coordinates; the trailing cells of wide characters hold the
+/// blank ratatui resets them to.
+#[derive(Debug, Clone, Default)]
+pub struct CopyBuffer {
+    lines: Vec<Vec<String>>,
+    pub cursor: Pos,
+    /// Selection start and kind, if a selection is active.
+    pub anchor: Option<(Pos, SelectionKind)>,
+}
+
+impl CopyBuffer {
+    /// Capture `area` of a rendered frame. The cursor starts at `cursor_row`
+    /// (e.g. the selected table row), clamped to the area.
+    pub fn capture(buf: &Buffer, area: Rect, cursor_row: usize) -> Self {
+        let lines: Vec<Vec<String>> = (area.y..area.y + area.height)
+            .map(|y| {
+                (area.x..area.x + area.width)
+                    .map(|x| buf[(x, y)].symbol().to_string())
+                    .collect()
+            })
+            .collect();
+        let row = cursor_row.min(lines.len().saturating_sub(1));
+        Self {
+            lines,
+            cursor: (row, 0),
+            anchor: None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    fn width(&self) -> usize {
+        self.lines.first().map_or(0, |l| l.len())
+    }
+
+    /// Move the cursor by (rows, cols), clamped to the buffer.
+    pub fn move_cursor(&mut self, drow: i64, dcol: i64) {
+        let max_row = self.lines.len().saturating_sub(1) as i64;
+        let max_col = self.width().saturating_sub(1) as i64;
+        self.cursor.0 = (self.cursor.0 as i64 + drow).clamp(0, max_row) as usize;
+        self.cursor.1 = (self.cursor.1 as i64 + dcol).clamp(0, max_col) as usize;
+    }
+
+    /// Place the cursor at a screen position relative to the captured area.
+    pub fn set_cursor(&mut self, row: usize, col: usize) {
+        self.cursor = (
+            row.min(self.lines.len().saturating_sub(1)),
+            col.min(self.width().saturating_sub(1)),
+        );
+    }
+
+    pub fn line_start(&mut self) {
+        self.cursor.1 = 0;
+    }
+
+    /// Last non-blank column of the cursor line.
+    pub fn line_end(&mut self) {
+        let line = &self.lines[self.cursor.0];
+        self.cursor.1 = line.iter().rposition(|c| !is_blank(c)).unwrap_or(0);
+    }
+
+    /// Start of the next word on the cursor line (vim `w`, without wrapping).
+    pub fn word_forward(&mut self) {
+        let line = &self.lines[self.cursor.0];
+        let mut col = self.cursor.1;
+        while col < line.len() && !is_blank(&line[col]) {
+            col += 1;
+        }
+        while col < line.len() && is_blank(&line[col]) {
+            col += 1;
+        }
+        if col < line.len() {
+            self.cursor.1 = col;
+        }
+    }
+
+    /// Start of the current or previous word on the cursor line (vim `b`).
+    pub fn word_backward(&mut self) {
+        let line = &self.lines[self.cursor.0];
+        let mut col = self.cursor.1;
+        while col > 0 && is_blank(&line[col - 1]) {
+            col -= 1;
+        }
+        while col > 0 && !is_blank(&line[col - 1]) {
+            col -= 1;
+        }
+        self.cursor.1 = col;
+    }
+
+    /// Start a selection of `kind` at the cursor, or clear it if one of the
+    /// same kind is already active.
+    pub fn toggle_selection(&mut self, kind: SelectionKind) {
+        self.anchor = match self.anchor {
+            Some((_, k)) if k == kind => None,
+            Some((pos, _)) => Some((pos, kind)),
+            None => Some((self.cursor, kind)),
+        };
+    }
+
+    /// Selection bounds in reading order.
+    fn bounds(&self) -> Option<(Pos, Pos, SelectionKind)> {
+        let (anchor, kind) = self.anchor?;
+        let (start, end) = if anchor <= self.cursor {
+            (anchor, self.cursor)
+        } else {
+            (self.cursor, anchor)
+        };
+        Some((start, end, kind))
+    }
+
+    fn is_selected(&self, row: usize, col: usize) -> bool {
+        let Some((start, end, kind)) = self.bounds() else {
+            return false;
+        };
+        if row < start.0 || row > end.0 {
+            return false;
+        }
+        match kind {
+            SelectionKind::Line => true,
+            SelectionKind::Char => {
+                (row > start.0 || col >= start.1) && (row < end.0 || col <= end.1)
+            }
+        }
+    }
+
+    /// The selected text (or the cursor line when nothing is selected),
+    /// with trailing blanks trimmed from every line.
+    pub fn selected_text(&self) -> String {
+        let (start, end, kind) = self
+            .bounds()
+            .unwrap_or((self.cursor, self.cursor, SelectionKind::Line));
+        (start.0..=end.0)
+            .map(|row| {
+                let line = &self.lines[row];
+                let (from, to) = match kind {
+                    SelectionKind::Line => (0, line.len()),
+                    SelectionKind::Char => (
+                        if row == start.0 { start.1 } else { 0 },
+                        if row == end.0 { (end.1 + 1).min(line.len()) } else { line.len() },
+                    ),
+                };
+                line[from..to].concat().trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn is_blank(cell: &str) -> bool {
+    cell.trim().is_empty()
+}
+
+/// Renders the frozen screen text with the selection and cursor highlighted.
+pub struct CopyView<'a> {
+    pub buffer: &'a CopyBuffer,
+    pub theme: &'a Theme,
+}
+
+impl<'a> Widget for CopyView<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let text_style = Style::default().fg(self.theme.fg).bg(self.theme.bg);
+        let selected_style = Style::default()
+            .fg(self.theme.fg)
+            .bg(self.theme.visual_bg);
+        let cursor_style = Style::default()
+            .fg(self.theme.selection_fg)
+            .bg(self.theme.selection_bg)
+            .add_modifier(Modifier::BOLD);
+
+        for (row, line) in self.buffer.lines.iter().enumerate().take(area.height as usize) {
+            for (col, symbol) in line.iter().enumerate().take(area.width as usize) {
+                let style = if (row, col) == self.buffer.cursor {
+                    cursor_style
+                } else if self.buffer.is_selected(row, col) {
+                    selected_style
+                } else {
+                    text_style
+                };
+                let cell = &mut buf[(area.x + col as u16, area.y + row as u16)];
+                // A blank behind a wide glyph doesn't clobber it: ratatui's
+                // diff skips the cells a wide symbol covers
+                cell.set_symbol(if symbol.is_empty() { " " } else { symbol });
+                cell.set_style(style);
+            }
+        }
+    }
+}
@@ -0,0 +1,426 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, StatefulWidget, Widget};
+
+use crate::data::cgroup_control::CgroupWriteback;
+use crate::data::collector::{IoLatency, MountWrite, OffCpuBreakdown, Peer, ThreadInfo};
+use crate::data::dns::DnsQuery;
+use crate::data::init_system::InitSystem;
+use crate::data::process::{format_bytes, format_count, format_ns, format_rate, format_time, ProcessInfo};
+use crate::data::system::format_uptime;
+use crate::redact::Redact;
+use crate::theme::Theme;
+use crate::ui::overlay::{render_footer, render_frame, Scroll};
+
+/// Width of the label column in the key/value section.
+const LABEL_WIDTH: usize = 11;
+
+/// Full-screen overlay with everything known about one process.
+pub struct DetailView<'a> {
+    pub pid: u32,
+    /// `None` once the process has exited.
+    pub process: Option<&'a ProcessInfo>,
+    /// `None` until the collector has produced data for `pid`.
+    pub threads: Option<&'a [ThreadInfo]>,
+    /// Busiest remote addresses; `None` until the collector has produced data.
+    pub peers: Option<&'a [Peer]>,
+    /// Recent DNS queries, newest first.
+    pub dns_queries: Option<&'a [DnsQuery]>,
+    /// Block I/O latency percentiles, if the process has done any.
+    pub io_latency: Option<IoLatency>,
+    /// Mount points written to, busiest first.
+    pub mount_writes: Option<&'a [MountWrite]>,
+    /// Writeback state of the process's cgroup.
+    pub cgroup_writeback: Option<&'a CgroupWriteback>,
+    /// Running/runnable/sleeping split, once counting has started.
+    pub off_cpu: Option<OffCpuBreakdown>,
+    /// The off-CPU breakdown is switched on (`o`).
+    pub off_cpu_wanted: bool,
+    /// Hardware counters are switched on (`i`).
+    pub perf_wanted: bool,
+    /// Why hardware counters can't be read, if they can't.
+    pub perf_unavailable: Option<&'a str>,
+    /// PID 1's init system, shown next to the service name.
+    pub init_system: &'a InitSystem,
+    pub uptime_secs: f64,
+    /// `SystemInfo::monotonic_secs`, for the start time.
+    pub monotonic_secs: f64,
+    pub redact: &'a Redact,
+    pub theme: &'a Theme,
+}
+
+impl<'a> StatefulWidget for DetailView<'a> {
+    type State = Scroll;
+
+    fn render(self, area: Rect, buf: &mut Buffer, scroll: &mut Scroll) {
+        let title = match self.process {
+            Some(p) => format!(" {} ({}) ", p.comm, p.pid),
+            None => format!(" PID {} ", self.pid),
+        };
+        let (content_area, footer_area) = render_frame(title, (90, 90), area, buf, self.theme);
+
+        let key_style = Style::default()
+            .fg(self.theme.status_key)
+            .add_modifier(Modifier::BOLD);
+        let header_style = Style::default()
+            .fg(self.theme.column_header_fg)
+            .bg(self.theme.column_header_bg)
+            .add_modifier(Modifier::BOLD);
+        let text_style = Style::default().fg(self.theme.fg);
+        let dim_style = Style::default().fg(self.theme.border);
+
+        let lines = match self.process {
+            Some(p) => self.lines(p, content_area.width as usize, key_style, header_style, text_style, dim_style),
+            None => vec![Line::styled("  Process has exited", dim_style)],
+        };
+
+        let total = lines.len();
+        let rows = scroll.fit(total, content_area.height as usize);
+        let visible: Vec<Line> = lines.into_iter().skip(rows.start).take(rows.len()).collect();
+        Paragraph::new(visible).render(content_area, buf);
+
+        render_footer(
+            &[("j/k", "Scroll"), ("o", "Off-CPU"), ("Esc", "Close")],
+            Vec::new(),
+            scroll.position(total),
+            footer_area,
+            buf,
+            self.theme,
+        );
+    }
+}
+
+impl<'a> DetailView<'a> {
+    fn off_cpu_summary(&self) -> String {
+        match (self.off_cpu_wanted, self.off_cpu) {
+            (false, _) => "- (o to break down running/runnable/sleeping time)".to_string(),
+            (true, None) => "counting...".to_string(),
+            (true, Some(b)) => format!(
+                "running {:.0}%  runnable {:.0}%  sleeping {:.0}%  disk {:.0}%  (over {})",
+                b.percent(b.running_ns),
+                b.percent(b.runnable_ns),
+                b.percent(b.sleeping_ns),
+                b.percent(b.blocked_ns),
+                format_uptime(b.elapsed.as_secs_f64())
+            ),
+        }
+    }
+
+    fn counters_summary(&self, p: &ProcessInfo) -> String {
+        if let Some(reason) = self.perf_unavailable {
+            return format!("unavailable: {reason}");
+        }
+        let rates = match (p.perf, self.perf_wanted) {
+            (Some(rates), _) => rates,
+            (None, false) => return "- (i to count cycles, instructions and cache misses)".to_string(),
+            (None, true) => return "counting...".to_string(),
+        };
+        let Some(ipc) = rates.ipc() else {
+            return "not running".to_string();
+        };
+        let mut s = format!(
+            "IPC {ipc:.2}  cycles {}/s  instructions {}/s",
+            format_count(rates.cycles_per_sec),
+            format_count(rates.instructions_per_sec)
+        );
+        if let Some(miss) = rates.miss_percent() {
+            s.push_str(&format!("  cache misses {}/s ({miss:.1}%", format_count(rates.cache_misses_per_sec)));
+            if let Some(mpki) = rates.misses_per_kilo_instr() {
+                s.push_str(&format!(", {mpki:.1} per 1k instructions"));
+            }
+            s.push(')');
+        }
+        s
+    }
+
+    fn lines(
+        &self,
+        p: &ProcessInfo,
+        width: usize,
+        key_style: Style,
+        header_style: Style,
+        text_style: Style,
+        dim_style: Style,
+    ) -> Vec<Line<'static>> {
+        let field = |label: &str, value: String| {
+            Line::from(vec![
+                Span::styled(format!(" {label:<LABEL_WIDTH$} "), key_style),
+                Span::styled(value, text_style),
+            ])
+        };
+
+        let started_ago = self.monotonic_secs - p.start_time_ns as f64 / 1_000_000_000.0;
+        let mut lines = vec![
+            field(
+                "PID",
+                if p.reparented() {
+                    format!("{}  (parent {}, originally {})", p.pid, p.ppid, p.orig_ppid)
+                } else {
+                    format!("{}  (parent {})", p.pid, p.ppid)
+                },
+            ),
+            field("User", format!("{} (uid {})", self.redact.user(&p.user), p.uid)),
+            field("State", p.state.as_char().to_string()),
+            field("Priority", format!("{}  nice {}", p.priority, p.nice)),
+            field("Started", format!("{} ago", format_uptime(started_ago.max(0.0)))),
+            field(
+                "CPU",
+                format!("{:.1}%  time {}", p.cpu_percent, format_time(p.cpu_time_secs)),
+            ),
+            field(
+                "Switches",
+                format!(
+                    "voluntary {:.0}/s ({} total)  involuntary {:.0}/s ({} total)",
+                    p.csw_rate, p.nvcsw, p.icsw_rate, p.nivcsw
+                ),
+            ),
+            field(
+                "Run queue",
+                format!(
+                    "avg wait {}  ({} waited over {} wakeups)",
+                    format_ns(p.runq_latency_ns),
+                    format_time(p.runq_wait_ns as f64 / 1_000_000_000.0),
+                    p.runq_waits
+                ),
+            ),
+            field("Off-CPU", self.off_cpu_summary()),
+            field("Counters", self.counters_summary(p)),
+            field(
+                "Memory",
+                format!(
+                    "{:.1}%  virt {}  res {}  shr {}",
+                    p.mem_percent,
+                    format_bytes(p.virt_bytes),
+                    format_bytes(p.res_bytes),
+                    format_bytes(p.shr_bytes)
+                ),
+            ),
+            field("Open FDs", p.fd_count.to_string()),
+            field(
+                "Network",
+                format!(
+                    "tx {} ({} total)  rx {} ({} total)  if {}",
+                    format_rate(p.net_tx_rate),
+                    format_bytes(p.net_tx_bytes),
+                    format_rate(p.net_rx_rate),
+                    format_bytes(p.net_rx_bytes),
+                    if p.net_ifname.is_empty() { "-" } else { &p.net_ifname }
+                ),
+            ),
+            field(
+                "Disk",
+                format!(
+                    "read {} ({} total)  write {} ({} total)",
+                    format_rate(p.disk_read_rate),
+                    format_bytes(p.disk_read_bytes),
+                    format_rate(p.disk_write_rate),
+                    format_bytes(p.disk_write_bytes)
+                ),
+            ),
+            field(
+                "Disk lat",
+                match self.io_latency {
+                    Some(lat) => format!(
+                        "p50 {}  p99 {}  ({} requests)",
+                        format_latency(lat.p50_us),
+                        format_latency(lat.p99_us),
+                        lat.requests
+                    ),
+                    None => "-".to_string(),
+                },
+            ),
+            field(
+                "GPU",
+                format!("{:.1}%  mem {}", p.gpu_percent, format_bytes(p.gpu_mem_bytes)),
+            ),
+            field(
+                "Container",
+                match (&p.container, &p.container_image) {
+                    (Some(name), Some(image)) => format!("{name}  ({image})"),
+                    (Some(name), None) => name.clone(),
+                    (None, _) => "-".to_string(),
+                },
+            ),
+            field(
+                "Pod",
+                match (&p.pod, &p.pod_namespace) {
+                    (Some(pod), Some(ns)) => format!("{ns}/{pod}"),
+                    (Some(pod), None) => pod.clone(),
+                    (None, _) => "-".to_string(),
+                },
+            ),
+            field(
+                "Service",
+                format!(
+                    "{}  (init: {})",
+                    p.service.as_deref().unwrap_or("-"),
+                    self.init_system.label()
+                ),
+            ),
+            field("Cgroup", p.cgroup_path.clone()),
+            field(
+                "Writeback",
+                match self.cgroup_writeback {
+                    Some(wb) => {
+                        let stall = match &wb.io_pressure {
+                            Some(psi) => format!("  io stall {:.1}%/{:.1}% (10s/60s)", psi.avg10, psi.avg60),
+                            None => String::new(),
+                        };
+                        format!(
+                            "dirty {}  writeback {}{stall}",
+                            format_bytes(wb.dirty),
+                            format_bytes(wb.writeback)
+                        )
+                    }
+                    None => "-".to_string(),
+                },
+            ),
+        ];
+
+        // Wrap the command line under its label
+        let wrap = width.saturating_sub(LABEL_WIDTH + 2).max(1);
+        let chars: Vec<char> = self.redact.cmdline(&p.cmdline).chars().collect();
+        for (i, chunk) in chars.chunks(wrap).enumerate() {
+            let label = if i == 0 { "Command" } else { "" };
+            lines.push(field(label, chunk.iter().collect()));
+        }
+
+        if let Some(peers) = self.peers.filter(|peers| !peers.is_empty()) {
+            lines.push(Line::raw(""));
+            lines.push(Line::styled(
+                format!(" {:<39}  {:>10}  {:>10}  (top {})", "PEER", "SENT", "RECEIVED", peers.len()),
+                header_style,
+            ));
+            for peer in peers {
+                lines.push(Line::from(vec![
+                    Span::styled(format!(" {:<39}  ", self.redact.ip(peer.addr)), key_style),
+                    Span::styled(
+                        format!("{:>10}  {:>10}", format_bytes(peer.tx_bytes), format_bytes(peer.rx_bytes)),
+                        text_style,
+                    ),
+                ]));
+            }
+        }
+
+        if let Some(queries) = self.dns_queries.filter(|queries| !queries.is_empty()) {
+            lines.push(Line::raw(""));
+            lines.push(Line::styled(
+                format!(" {:>10}  {:<5}  {:<39}  {}", "AGO", "TYPE", "DNS QUERY", "SERVER"),
+                header_style,
+            ));
+            for q in queries {
+                let ago = format_uptime((self.uptime_secs - q.time_secs).max(0.0));
+                let server = q.server.map(|s| self.redact.ip(s)).unwrap_or_default();
+                lines.push(Line::from(vec![
+                    Span::styled(format!(" {ago:>10}  {:<5}  ", q.type_label()), text_style),
+                    Span::styled(format!("{:<39}  ", q.name), key_style),
+                    Span::styled(server, text_style),
+                ]));
+            }
+        }
+
+        if let Some(mounts) = self.mount_writes.filter(|mounts| !mounts.is_empty()) {
+            lines.push(Line::raw(""));
+            lines.push(Line::styled(
+                format!(" {:<39}  {:>10}  {:>10}", "WRITES TO", "RATE", "TOTAL"),
+                header_style,
+            ));
+            for m in mounts {
+                lines.push(Line::from(vec![
+                    Span::styled(format!(" {:<39}  ", m.mount), key_style),
+                    Span::styled(
+                        format!("{:>10}  {:>10}", format_rate(m.rate), format_bytes(m.bytes)),
+                        text_style,
+                    ),
+                ]));
+            }
+        }
+
+        if let Some(lat) = self.io_latency {
+            lines.push(Line::raw(""));
+            lines.extend(io_latency_histogram(&lat, width, key_style, header_style, text_style));
+        }
+
+        lines.push(Line::raw(""));
+        let Some(threads) = self.threads else {
+            lines.push(Line::styled("  Waiting for next refresh...", dim_style));
+            return lines;
+        };
+        lines.push(Line::styled(
+            format!(
+                " {:>7}  {:<16} {}  {:>5}  {:>10}  ({} threads)",
+                "TID",
+                "COMM",
+                "S",
+                "CPU%",
+                "TIME+",
+                threads.len()
+            ),
+            header_style,
+        ));
+        let mut sorted: Vec<&ThreadInfo> = threads.iter().collect();
+        sorted.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent).then(a.tid.cmp(&b.tid)));
+        for t in sorted {
+            lines.push(Line::from(vec![
+                Span::styled(format!(" {:>7}  ", t.tid), key_style),
+                Span::styled(
+                    format!(
+                        "{:<16} {}  {:>5.1}  {:>10}",
+                        t.comm,
+                        t.state.as_char(),
+                        t.cpu_percent,
+                        format_time(t.cpu_time_secs)
+                    ),
+                    text_style,
+                ),
+            ]));
+        }
+        lines
+    }
+}
+
+/// biolatency-style rows for the non-empty span of the latency histogram,
+/// bars scaled to the fullest bucket.
+fn io_latency_histogram(
+    lat: &IoLatency,
+    width: usize,
+    key_style: Style,
+    header_style: Style,
+    text_style: Style,
+) -> Vec<Line<'static>> {
+    let first = lat.buckets.iter().position(|&n| n > 0).unwrap_or(0);
+    let last = lat.buckets.iter().rposition(|&n| n > 0).unwrap_or(0);
+    let max = lat.buckets.iter().copied().max().unwrap_or(0).max(1);
+    // Whatever is left after the label and count columns
+    let bar_width = width.saturating_sub(LABEL_WIDTH + 12).clamp(1, 40);
+
+    let mut lines = vec![Line::styled(
+        format!(" {:<w$} {:>8}  DISTRIBUTION  ({} requests)", "DISK LAT", "COUNT", lat.requests, w = LABEL_WIDTH - 1),
+        header_style,
+    )];
+    for (i, &n) in lat.buckets.iter().enumerate().take(last + 1).skip(first) {
+        let label = if i == lat.buckets.len() - 1 {
+            // The last bucket also holds everything slower
+            format!("{}+", format_latency(1 << i).trim_start_matches('<'))
+        } else {
+            format_latency(1 << (i + 1))
+        };
+        let bar = (n as usize * bar_width).div_ceil(max as usize);
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {label:<w$}", w = LABEL_WIDTH - 1), key_style),
+            Span::styled(format!(" {n:>8}  {}", "*".repeat(bar)), text_style),
+        ]));
+    }
+    lines
+}
+
+/// Latency bound for display, e.g. "<512us" or "<4.1ms".
+fn format_latency(us: u64) -> String {
+    match us {
+        us if us < 1000 => format!("<{us}us"),
+        us if us < 1_000_000 => format!("<{:.1}ms", us as f64 / 1000.0),
+        us => format!("<{:.1}s", us as f64 / 1_000_000.0),
+    }
+}
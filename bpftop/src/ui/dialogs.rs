@@ -36,19 +36,40 @@ impl<'a> Widget for HelpDialog<'a> {
         let bindings = [
             ("q", "Quit"),
             ("?", "This help"),
+            ("F2", "Setup: refresh rate, columns, theme, meters"),
             ("/", "Incremental search"),
-            ("\\", "Filter processes"),
+            (":", "Command: spawn [--cpu 50%] [--mem 512M] cmd args (own scope, selected)"),
+            ("\\", "Filter: text, /regex/, user:/state:/unit:/pid:/sid:/tty:, cpu>50, mem>2G, !negate"),
             ("t", "Toggle tree view"),
-            ("> <", "Sort column select"),
-            ("x", "Kill process (send signal)"),
+            ("> <", "Sort column select (Tab: secondary key, r: reverse)"),
+            ("x", "Kill process (R restarts via systemd/docker/runit; g: process group; c: whole cgroup)"),
             ("f", "Freeze cgroup"),
             ("u / U", "Thaw (dialog / instant)"),
             ("j / k", "Navigate down / up"),
             ("gg", "Jump to top"),
             ("G", "Jump to bottom"),
+            ("m{a-z} / '{a-z}", "Mark the selected process / jump back to the mark"),
+            ("F", "Follow the selected process as the order changes (moving stops it)"),
             ("Ctrl+O", "Jump back"),
             ("Tab", "Jump forward"),
             ("V", "Visual mode"),
+            ("[ / ]", "Renice -1 / +1 (tagged, selected, or visual range)"),
+            ("Bksp", "Untag visual range"),
+            ("Enter", "Process details"),
+            ("l", "List open files"),
+            ("e", "Environment variables (/ searches, r reloads)"),
+            ("c", "Network connections with RTT (y copies remote, a all, n same netns, r sort by RTT)"),
+            ("D", "Snapshot / diff process tree"),
+            ("h", "CPU/RSS/network history graphs"),
+            ("E", "Recent execs (incl. short-lived)"),
+            ("X", "OOM kill log"),
+            ("B", "bpftop's own eBPF overhead and map fill"),
+            ("b", "Every BPF program and map on the system, with their holders (Tab switches)"),
+            ("i", "JVM / Python thread dump (jcmd, py-spy)"),
+            ("s", "Login sessions by terminal (Enter shows one, x kills it)"),
+            ("a", "Cgroup tree with summed CPU, memory and tasks (Space folds)"),
+            ("w", "CPU and memory per service or container (Tab switches, Space lists processes)"),
+            ("v", "Copy mode (select screen text, y to yank)"),
             ("yy", "Yank row to clipboard"),
             ("yp", "Yank PID"),
             ("yu", "Yank user"),
@@ -58,15 +79,21 @@ impl<'a> Widget for HelpDialog<'a> {
             ("yl", "Yank full cmdline"),
             ("yg", "Yank GPU%"),
             ("yv", "Yank VRAM usage"),
+            ("Y{key}", "Visual: yank one column (y keys, C cgroup, P/M/T, R/V, N, r/w)"),
             ("Space", "Tag process"),
             ("H", "Toggle user threads"),
             ("K", "Toggle kernel threads"),
+            ("C", "Compact mode (one-line header, tight columns)"),
+            ("p", "Command column: full cmdline / program path stripped / comm"),
+            ("R", "Header history graphs (CPU, memory, network)"),
             ("P", "Sort by CPU%"),
             ("M", "Sort by MEM%"),
             ("T", "Sort by TIME"),
-            ("N", "Toggle network columns"),
+            ("N", "Cycle network sort (NET/s, TX/s, RX/s, ...)"),
+            ("O", "Cycle disk I/O sort (R/s, W/s, off)"),
+            ("J", "Toggle PGRP/SID/TTY columns"),
             ("gG", "Toggle GPU columns"),
-            ("W", "Sort by GPU MEM"),
+            ("W", "Sort by GPU MEM / GPU% (toggles)"),
             ("I", "Invert sort order"),
             ("S", "Toggle full slice path"),
             ("A", "Toggle all unit types"),
@@ -94,7 +121,13 @@ impl<'a> Widget for HelpDialog<'a> {
 
 /// Kill signal picker dialog.
 pub struct KillDialog<'a> {
+    /// PIDs, or process group IDs when `group` is set.
     pub pids: &'a [u32],
+    pub group: bool,
+    /// Cgroups `c` kills whole; empty if none may be.
+    pub cgroups: &'a [String],
+    /// Supervisor label; when set, entry 0 is "restart" and signals follow.
+    pub restart: Option<&'a str>,
     pub pid_scroll: usize,
     pub selected_signal: usize,
     pub theme: &'a Theme,
@@ -103,13 +136,15 @@ pub struct KillDialog<'a> {
 impl<'a> Widget for KillDialog<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let multi = self.pids.len() > 1;
-        let dialog = centered_rect(if multi { 55 } else { 40 }, 60, area);
+        let wide = multi || self.restart.is_some();
+        let dialog = centered_rect(if wide { 55 } else { 40 }, 60, area);
         Clear.render(dialog, buf);
 
-        let title = if self.pids.len() == 1 {
-            format!(" Send signal to PID {} ", self.pids[0])
-        } else {
-            format!(" Send signal to {} PIDs ", self.pids.len())
+        let title = match (self.group, self.pids.len()) {
+            (false, 1) => format!(" Send signal to PID {} [g: group] ", self.pids[0]),
+            (false, n) => format!(" Send signal to {n} PIDs [g: group] "),
+            (true, 1) => format!(" Send signal to group {} [g: PID] ", self.pids[0]),
+            (true, n) => format!(" Send signal to {n} groups [g: PID] "),
         };
         let block = Block::default()
             .title(title)
@@ -122,21 +157,40 @@ impl<'a> Widget for KillDialog<'a> {
 
         let signals = signal_list();
 
-        // Build signal lines (shared by both paths)
-        let signal_lines: Vec<Line> = signals
-            .iter()
-            .enumerate()
-            .map(|(i, (num, name))| {
-                let style = if i == self.selected_signal {
-                    Style::default()
-                        .fg(self.theme.selection_fg)
-                        .bg(self.theme.selection_bg)
-                } else {
-                    Style::default().fg(self.theme.fg)
-                };
-                Line::styled(format!("{:>2}) {}", num, name), style)
-            })
-            .collect();
+        let entry_style = |i: usize| {
+            if i == self.selected_signal {
+                Style::default()
+                    .fg(self.theme.selection_fg)
+                    .bg(self.theme.selection_bg)
+            } else {
+                Style::default().fg(self.theme.fg)
+            }
+        };
+
+        // Build signal lines (shared by both paths), with the supervisor
+        // restart first so it is the default for managed services
+        let offset = usize::from(self.restart.is_some());
+        let mut signal_lines: Vec<Line> = Vec::new();
+        if let Some(label) = self.restart {
+            signal_lines.push(Line::styled(format!(" R) Restart via {label}"), entry_style(0)));
+            signal_lines.push(Line::styled(
+                "    (killing it would just respawn it)",
+                Style::default().fg(self.theme.border),
+            ));
+        }
+        signal_lines.extend(signals.iter().enumerate().map(|(i, (num, name))| {
+            Line::styled(format!("{:>2}) {}", num, name), entry_style(i + offset))
+        }));
+        if !self.cgroups.is_empty() {
+            let dim_style = Style::default().fg(self.theme.border);
+            signal_lines.push(Line::default());
+            signal_lines.push(Line::styled(" c) cgroup.kill", Style::default().fg(self.theme.fg)));
+            let which = match self.cgroups {
+                [cgroup] => cgroup.clone(),
+                cgroups => format!("{} cgroups", cgroups.len()),
+            };
+            signal_lines.push(Line::styled(format!("    {which}"), dim_style));
+        }
 
         if !multi {
             Paragraph::new(signal_lines).render(inner, buf);
@@ -202,6 +256,8 @@ pub struct FreezeDialog<'a> {
     pub targets: &'a [FreezeTarget],
     pub is_thaw: bool,
     pub scroll: usize,
+    /// Auto-thaw timer in seconds, 0 for none. Not offered when thawing.
+    pub thaw_after_secs: u64,
     pub theme: &'a Theme,
 }
 
@@ -261,8 +317,20 @@ impl<'a> Widget for FreezeDialog<'a> {
             }
         }
 
+        // The timer runs in bpftop, so say so on the first row while one is set
+        let warning = (!self.is_thaw && self.thaw_after_secs > 0).then(|| {
+            Line::styled(
+                "  bpftop must keep running to thaw on time; quitting thaws now",
+                Style::default().fg(self.theme.proc_frozen),
+            )
+        });
+        let top = u16::from(warning.is_some()).min(inner.height);
+        if let Some(warning) = warning {
+            Paragraph::new(warning).render(Rect { height: top, ..inner }, buf);
+        }
+
         // Reserve 2 rows for footer
-        let content_height = inner.height.saturating_sub(2) as usize;
+        let content_height = inner.height.saturating_sub(2 + top) as usize;
         let total_lines = all_lines.len();
         let scroll = self.scroll.min(total_lines.saturating_sub(content_height));
 
@@ -273,7 +341,8 @@ impl<'a> Widget for FreezeDialog<'a> {
             .collect();
 
         let content_area = Rect {
-            height: inner.height.saturating_sub(2),
+            y: inner.y + top,
+            height: content_height as u16,
             ..inner
         };
         Paragraph::new(visible_lines).render(content_area, buf);
@@ -293,13 +362,23 @@ impl<'a> Widget for FreezeDialog<'a> {
             Paragraph::new(vec![indicator]).render(indicator_area, buf);
         }
 
-        // Footer: [Enter] Confirm  [Esc] Cancel
-        let footer = Line::from(vec![
+        // Footer: [Enter] Confirm  [Esc] Cancel  [t] Thaw after: off
+        let mut footer = vec![
             Span::styled("[Enter]", pid_style),
             Span::styled(" Confirm  ", footer_style),
             Span::styled("[Esc]", pid_style),
             Span::styled(" Cancel", footer_style),
-        ]);
+        ];
+        if !self.is_thaw {
+            let after = match self.thaw_after_secs {
+                0 => "off".to_string(),
+                s if s < 60 => format!("{s}s"),
+                s => format!("{}m", s / 60),
+            };
+            footer.push(Span::styled("  [t]", pid_style));
+            footer.push(Span::styled(format!(" Thaw after: {after}"), footer_style));
+        }
+        let footer = Line::from(footer);
         let footer_area = Rect {
             y: inner.y + inner.height.saturating_sub(1),
             height: 1,
@@ -326,7 +405,7 @@ pub fn signal_list() -> Vec<(i32, &'static str)> {
 }
 
 /// Center a rectangle within an area by percentage.
-fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+pub fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
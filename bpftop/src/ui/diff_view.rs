@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, StatefulWidget, Widget};
+
+use crate::data::diff::{DiffEntry, DiffKind};
+use crate::data::process::format_bytes;
+use crate::theme::Theme;
+use crate::ui::overlay::{render_column_header, render_footer, render_frame, Scroll};
+
+/// Overlay listing processes that appeared, exited, or changed between
+/// the recorded snapshot (T1) and the moment the diff was taken (T2).
+pub struct DiffView<'a> {
+    pub entries: &'a [DiffEntry],
+    /// Time between T1 and T2.
+    pub span: Duration,
+    pub theme: &'a Theme,
+}
+
+impl<'a> StatefulWidget for DiffView<'a> {
+    type State = Scroll;
+
+    fn render(self, area: Rect, buf: &mut Buffer, scroll: &mut Scroll) {
+        let count = |kind| self.entries.iter().filter(|e| e.kind == kind).count();
+        let title = format!(
+            " Diff over {}s: +{} -{} ~{} ",
+            self.span.as_secs(),
+            count(DiffKind::Appeared),
+            count(DiffKind::Exited),
+            count(DiffKind::Changed),
+        );
+        let (inner, footer_area) = render_frame(title, (80, 80), area, buf, self.theme);
+
+        let key_style = Style::default()
+            .fg(self.theme.status_key)
+            .add_modifier(Modifier::BOLD);
+        let text_style = Style::default().fg(self.theme.fg);
+        let dim_style = Style::default().fg(self.theme.border);
+
+        let content_area = render_column_header(
+            format!("  {:>7}  {:>13}  {:>15}  {}", "PID", "CPU%", "RES", "Command"),
+            inner,
+            buf,
+            self.theme,
+        );
+
+        let total = self.entries.len();
+        let rows = scroll.fit(total, content_area.height as usize);
+
+        let lines: Vec<Line> = if self.entries.is_empty() {
+            vec![Line::styled("  No significant changes", dim_style)]
+        } else {
+            self.entries[rows]
+                .iter()
+                .map(|e| {
+                    let kind_style = match e.kind {
+                        DiffKind::Appeared => Style::default().fg(self.theme.proc_running),
+                        DiffKind::Exited => Style::default().fg(self.theme.proc_zombie),
+                        DiffKind::Changed => Style::default().fg(self.theme.proc_stopped),
+                    };
+                    let cpu = match e.kind {
+                        DiffKind::Appeared => format!("{:.1}", e.cpu_after),
+                        DiffKind::Exited => format!("{:.1}", e.cpu_before),
+                        DiffKind::Changed => format!("{:.1}->{:.1}", e.cpu_before, e.cpu_after),
+                    };
+                    let res = match e.kind {
+                        DiffKind::Appeared => format_bytes(e.res_after),
+                        DiffKind::Exited => format_bytes(e.res_before),
+                        DiffKind::Changed => format!(
+                            "{}->{}",
+                            format_bytes(e.res_before),
+                            format_bytes(e.res_after)
+                        ),
+                    };
+                    Line::from(vec![
+                        Span::styled(format!("{} ", e.kind.marker()), kind_style),
+                        Span::styled(format!("{:>7}  ", e.pid), key_style),
+                        Span::styled(format!("{:>13}  {:>15}  ", cpu, res), text_style),
+                        Span::styled(format!("{}{}", "  ".repeat(e.depth), e.comm), kind_style),
+                    ])
+                })
+                .collect()
+        };
+        Paragraph::new(lines).render(content_area, buf);
+
+        render_footer(
+            &[("j/k", "Scroll"), ("r", "Re-snapshot"), ("Esc", "Close")],
+            Vec::new(),
+            scroll.position(total),
+            footer_area,
+            buf,
+            self.theme,
+        );
+    }
+}
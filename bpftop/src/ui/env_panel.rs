@@ -0,0 +1,126 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, StatefulWidget, Widget};
+
+use crate::data::environ::EnvVar;
+use crate::redact::Redact;
+use crate::theme::Theme;
+use crate::ui::overlay::{self, render_frame, Scroll};
+
+/// Overlay listing the environment of one process, like htop's `e`.
+pub struct EnvPanel<'a> {
+    pub pid: u32,
+    pub comm: &'a str,
+    /// The variables, or why /proc/<pid>/environ could not be read.
+    pub vars: &'a Result<Vec<EnvVar>, String>,
+    pub filter: &'a str,
+    pub filter_editing: bool,
+    /// Masks values under `--redact args`.
+    pub redact: &'a Redact,
+    /// The screen is mirrored to `--share` viewers, so every value is masked.
+    pub shared: bool,
+    pub theme: &'a Theme,
+}
+
+impl EnvPanel<'_> {
+    fn value<'v>(&self, value: &'v str) -> &'v str {
+        if self.shared && !value.is_empty() {
+            "…"
+        } else {
+            self.redact.env_value(value)
+        }
+    }
+}
+
+impl<'a> StatefulWidget for EnvPanel<'a> {
+    type State = Scroll;
+
+    fn render(self, area: Rect, buf: &mut Buffer, scroll: &mut Scroll) {
+        let title = match self.vars {
+            Ok(vars) => format!(" Environment: {} ({}) - {} vars ", self.comm, self.pid, vars.len()),
+            Err(_) => format!(" Environment: {} ({}) ", self.comm, self.pid),
+        };
+        let (content_area, footer_area) = render_frame(title, (80, 80), area, buf, self.theme);
+
+        let key_style = Style::default()
+            .fg(self.theme.status_key)
+            .add_modifier(Modifier::BOLD);
+        let text_style = Style::default().fg(self.theme.fg);
+        let dim_style = Style::default().fg(self.theme.border);
+
+        let vars = match self.vars {
+            Ok(vars) => vars,
+            Err(e) => {
+                let err_style = Style::default().fg(self.theme.proc_zombie);
+                Paragraph::new(vec![Line::styled(format!("  {e}"), err_style)]).render(content_area, buf);
+                render_footer(&self, footer_area, buf, None);
+                return;
+            }
+        };
+
+        // Match what is on screen, so a masked value can't be searched for
+        let query = self.filter.to_lowercase();
+        let matching: Vec<(&str, &str)> = vars
+            .iter()
+            .map(|v| (v.name.as_str(), self.value(&v.value)))
+            .filter(|(name, value)| {
+                query.is_empty()
+                    || name.to_lowercase().contains(&query)
+                    || value.to_lowercase().contains(&query)
+            })
+            .collect();
+        let total = matching.len();
+        let rows = scroll.fit(total, content_area.height as usize);
+
+        let lines: Vec<Line> = matching[rows]
+            .iter()
+            .map(|(name, value)| {
+                Line::from(vec![
+                    Span::styled(name.to_string(), key_style),
+                    Span::styled("=", dim_style),
+                    Span::styled(value.to_string(), text_style),
+                ])
+            })
+            .collect();
+        Paragraph::new(lines).render(content_area, buf);
+
+        render_footer(&self, footer_area, buf, scroll.position(total));
+    }
+}
+
+/// Footer: filter input while editing, otherwise key hints plus the scroll position.
+fn render_footer(panel: &EnvPanel, area: Rect, buf: &mut Buffer, position: Option<String>) {
+    let key_style = Style::default()
+        .fg(panel.theme.status_key)
+        .add_modifier(Modifier::BOLD);
+    let text_style = Style::default().fg(panel.theme.fg);
+    if panel.filter_editing {
+        let footer = Line::from(vec![
+            Span::styled("/", key_style),
+            Span::styled(panel.filter.to_string(), text_style),
+            Span::styled("_", key_style),
+        ]);
+        Paragraph::new(vec![footer]).render(area, buf);
+        return;
+    }
+    let mut extra = Vec::new();
+    if !panel.filter.is_empty() {
+        extra.push(Span::styled(format!("  search: {}", panel.filter), text_style));
+    }
+    if panel.shared {
+        extra.push(Span::styled(
+            "  values hidden while sharing",
+            Style::default().fg(panel.theme.border),
+        ));
+    }
+    overlay::render_footer(
+        &[("j/k", "Scroll"), ("/", "Search"), ("r", "Reload"), ("Esc", "Close")],
+        extra,
+        position,
+        area,
+        buf,
+        panel.theme,
+    );
+}
@@ -0,0 +1,100 @@
+use std::collections::{HashSet, VecDeque};
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, StatefulWidget, Widget};
+
+use crate::data::execs::ExecRecord;
+use crate::data::system::format_uptime;
+use crate::redact::Redact;
+use crate::theme::Theme;
+use crate::ui::overlay::{render_column_header, render_footer, render_frame, Scroll};
+use crate::ui::process_table::truncate_cols;
+
+/// execsnoop-style overlay listing recent execs, newest first.
+pub struct ExecsPanel<'a> {
+    pub execs: &'a VecDeque<ExecRecord>,
+    /// PIDs in the current process list; anything else has already exited.
+    pub live_pids: &'a HashSet<u32>,
+    pub uptime_secs: f64,
+    pub redact: &'a Redact,
+    pub theme: &'a Theme,
+}
+
+impl<'a> StatefulWidget for ExecsPanel<'a> {
+    type State = Scroll;
+
+    fn render(self, area: Rect, buf: &mut Buffer, scroll: &mut Scroll) {
+        let exited = self
+            .execs
+            .iter()
+            .filter(|e| !self.live_pids.contains(&e.pid))
+            .count();
+        let title = format!(" Recent execs: {} ({} exited) ", self.execs.len(), exited);
+        let (inner, footer_area) = render_frame(title, (90, 80), area, buf, self.theme);
+
+        let key_style = Style::default()
+            .fg(self.theme.status_key)
+            .add_modifier(Modifier::BOLD);
+        let text_style = Style::default().fg(self.theme.fg);
+        let dim_style = Style::default().fg(self.theme.border);
+
+        let content_area = render_column_header(
+            format!(
+                "{:>10}  {:>7}  {:>7}  {:<10} {}",
+                "AGO", "PID", "PPID", "USER", "COMMAND"
+            ),
+            inner,
+            buf,
+            self.theme,
+        );
+
+        let total = self.execs.len();
+        let rows = scroll.fit(total, content_area.height as usize);
+
+        let lines: Vec<Line> = if self.execs.is_empty() {
+            vec![Line::styled("  No execs seen yet", dim_style)]
+        } else {
+            self.execs
+                .iter()
+                .rev()
+                .skip(rows.start)
+                .take(rows.len())
+                .map(|e| {
+                    let ago = format_uptime((self.uptime_secs - e.time_secs).max(0.0));
+                    // Exited processes are the ones the table never showed
+                    let style = if self.live_pids.contains(&e.pid) {
+                        text_style
+                    } else {
+                        dim_style
+                    };
+                    Line::from(vec![
+                        Span::styled(format!("{:>10}  ", ago), style),
+                        Span::styled(format!("{:>7}  ", e.pid), key_style),
+                        Span::styled(
+                            format!(
+                                "{:>7}  {:<10} {}",
+                                e.ppid,
+                                truncate_cols(&self.redact.user(&e.user), 10),
+                                self.redact.cmdline(&e.cmdline)
+                            ),
+                            style,
+                        ),
+                    ])
+                })
+                .collect()
+        };
+        Paragraph::new(lines).render(content_area, buf);
+
+        render_footer(
+            &[("j/k", "Scroll"), ("C", "Clear"), ("Esc", "Close")],
+            Vec::new(),
+            scroll.position(total),
+            footer_area,
+            buf,
+            self.theme,
+        );
+    }
+}
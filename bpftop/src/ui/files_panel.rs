@@ -0,0 +1,105 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, StatefulWidget, Widget};
+
+use crate::data::files::{FdKind, OpenFile};
+use crate::theme::Theme;
+use crate::ui::overlay::{self, render_column_header, render_frame, Scroll};
+
+/// lsof-style overlay listing the open file descriptors of one process.
+pub struct FilesPanel<'a> {
+    pub pid: u32,
+    pub comm: &'a str,
+    /// `None` until the collector has produced data for `pid`.
+    pub files: Option<&'a [OpenFile]>,
+    pub filter: &'a str,
+    pub filter_editing: bool,
+    pub theme: &'a Theme,
+}
+
+impl<'a> StatefulWidget for FilesPanel<'a> {
+    type State = Scroll;
+
+    fn render(self, area: Rect, buf: &mut Buffer, scroll: &mut Scroll) {
+        let title = match self.files {
+            Some(files) => format!(" Open files: {} ({}) - {} fds ", self.comm, self.pid, files.len()),
+            None => format!(" Open files: {} ({}) ", self.comm, self.pid),
+        };
+        let (inner, footer_area) = render_frame(title, (80, 80), area, buf, self.theme);
+
+        let key_style = Style::default()
+            .fg(self.theme.status_key)
+            .add_modifier(Modifier::BOLD);
+        let text_style = Style::default().fg(self.theme.fg);
+        let dim_style = Style::default().fg(self.theme.border);
+
+        let content_area = render_column_header(
+            format!("{:>5}  {:<6}  {}", "FD", "TYPE", "NAME"),
+            inner,
+            buf,
+            self.theme,
+        );
+
+        let Some(files) = self.files else {
+            Paragraph::new(vec![Line::styled("  Waiting for next refresh...", dim_style)])
+                .render(content_area, buf);
+            render_footer(&self, footer_area, buf, None);
+            return;
+        };
+
+        let matching: Vec<&OpenFile> = files.iter().filter(|f| f.matches(self.filter)).collect();
+        let total = matching.len();
+        let rows = scroll.fit(total, content_area.height as usize);
+
+        let lines: Vec<Line> = matching[rows]
+            .iter()
+            .map(|f| {
+                let type_style = match f.kind {
+                    FdKind::Socket => Style::default().fg(self.theme.cpu_system),
+                    FdKind::Pipe => Style::default().fg(self.theme.cpu_nice),
+                    FdKind::Regular => text_style,
+                    FdKind::Other => dim_style,
+                };
+                Line::from(vec![
+                    Span::styled(format!("{:>5}  ", f.fd), key_style),
+                    Span::styled(format!("{:<6}  ", f.type_label()), type_style),
+                    Span::styled(f.describe(), text_style),
+                ])
+            })
+            .collect();
+        Paragraph::new(lines).render(content_area, buf);
+
+        render_footer(&self, footer_area, buf, scroll.position(total));
+    }
+}
+
+/// Footer: filter input while editing, otherwise key hints plus the scroll position.
+fn render_footer(panel: &FilesPanel, area: Rect, buf: &mut Buffer, position: Option<String>) {
+    let key_style = Style::default()
+        .fg(panel.theme.status_key)
+        .add_modifier(Modifier::BOLD);
+    let text_style = Style::default().fg(panel.theme.fg);
+    if panel.filter_editing {
+        let footer = Line::from(vec![
+            Span::styled("/", key_style),
+            Span::styled(panel.filter.to_string(), text_style),
+            Span::styled("_", key_style),
+        ]);
+        Paragraph::new(vec![footer]).render(area, buf);
+        return;
+    }
+    let mut extra = Vec::new();
+    if !panel.filter.is_empty() {
+        extra.push(Span::styled(format!("  filter: {}", panel.filter), text_style));
+    }
+    overlay::render_footer(
+        &[("j/k", "Scroll"), ("/", "Filter"), ("Esc", "Close")],
+        extra,
+        position,
+        area,
+        buf,
+        panel.theme,
+    );
+}
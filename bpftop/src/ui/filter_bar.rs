@@ -10,6 +10,8 @@ use crate::theme::Theme;
 pub struct FilterBarWidget<'a> {
     pub query: &'a str,
     pub mode: FilterMode,
+    /// Parse error for the query, shown after it.
+    pub error: Option<&'a str>,
     pub theme: &'a Theme,
 }
 
@@ -19,6 +21,8 @@ pub enum FilterMode {
     Search,
     /// F4 filter - hides non-matching processes.
     Filter,
+    /// `:` command line, e.g. `:spawn`.
+    Command,
 }
 
 impl<'a> Widget for FilterBarWidget<'a> {
@@ -35,9 +39,10 @@ impl<'a> Widget for FilterBarWidget<'a> {
         let label = match self.mode {
             FilterMode::Search => "Search: ",
             FilterMode::Filter => "Filter: ",
+            FilterMode::Command => ":",
         };
 
-        let line = Line::from(vec![
+        let mut line = Line::from(vec![
             Span::styled(
                 label,
                 Style::default()
@@ -57,6 +62,14 @@ impl<'a> Widget for FilterBarWidget<'a> {
                     .bg(self.theme.status_bg),
             ),
         ]);
+        if let Some(err) = self.error {
+            line.push_span(Span::styled(
+                format!("  {err}"),
+                Style::default()
+                    .fg(self.theme.proc_zombie)
+                    .bg(self.theme.status_bg),
+            ));
+        }
 
         buf.set_line(area.x, area.y, &line, area.width);
     }
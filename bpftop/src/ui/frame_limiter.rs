@@ -0,0 +1,54 @@
+use std::time::{Duration, Instant};
+
+/// Caps how often frames are drawn (`[general] max_fps`).
+///
+/// Drawing blocks until the terminal has accepted the output, so on a slow
+/// SSH link the time a frame took is also a measure of the link. The next
+/// frame waits at least that long, which drops intermediate frames instead
+/// of queueing them behind input.
+pub struct FrameLimiter {
+    min_interval: Duration,
+    last_cost: Duration,
+    last_end: Option<Instant>,
+}
+
+impl FrameLimiter {
+    /// `max_fps == 0` removes the fixed cap but keeps the adaptive backoff.
+    pub fn new(max_fps: u32) -> Self {
+        Self {
+            min_interval: min_interval(max_fps),
+            last_cost: Duration::ZERO,
+            last_end: None,
+        }
+    }
+
+    /// Change the cap, e.g. after it was edited on the setup screen.
+    pub fn set_max_fps(&mut self, max_fps: u32) {
+        self.min_interval = min_interval(max_fps);
+    }
+
+    /// Time left before the next frame may be drawn (zero if it may now).
+    pub fn wait(&self) -> Duration {
+        let Some(end) = self.last_end else {
+            return Duration::ZERO;
+        };
+        self.min_interval
+            .max(self.last_cost)
+            .saturating_sub(end.elapsed())
+    }
+
+    /// Record a frame that started drawing at `started` and just finished.
+    pub fn record(&mut self, started: Instant) {
+        let now = Instant::now();
+        self.last_cost = now - started;
+        self.last_end = Some(now);
+    }
+}
+
+fn min_interval(max_fps: u32) -> Duration {
+    if max_fps == 0 {
+        Duration::ZERO
+    } else {
+        Duration::from_secs(1) / max_fps
+    }
+}
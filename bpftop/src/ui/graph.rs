@@ -0,0 +1,91 @@
+use std::collections::VecDeque;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::widgets::Widget;
+
+use crate::theme::Theme;
+use crate::ui::process_table::truncate_cols;
+
+/// Braille dot bits of a cell's left and right column, bottom dot first.
+pub const BRAILLE_LEFT: [u32; 4] = [0x40, 0x04, 0x02, 0x01];
+pub const BRAILLE_RIGHT: [u32; 4] = [0x80, 0x20, 0x10, 0x08];
+
+/// Scrolling area graph drawn in braille: two samples per cell and four
+/// levels per row, newest sample at the right edge. The first row holds
+/// the title.
+pub struct Graph<'a> {
+    /// Caption with the current value, e.g. "CPU 12.3%".
+    pub title: String,
+    pub samples: &'a VecDeque<f64>,
+    /// Value drawn at full height; larger samples are clipped.
+    pub max: f64,
+    pub color: Color,
+    pub theme: &'a Theme,
+}
+
+impl<'a> Widget for Graph<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+        buf.set_string(
+            area.x,
+            area.y,
+            truncate_cols(&self.title, area.width as usize),
+            Style::default().fg(self.theme.fg),
+        );
+
+        let plot = Rect {
+            y: area.y + 1,
+            height: area.height.saturating_sub(1),
+            ..area
+        };
+        if plot.is_empty() || self.max <= 0.0 {
+            return;
+        }
+
+        let height_dots = plot.height as usize * 4;
+        let slots = plot.width as usize * 2;
+        let shown = self.samples.len().min(slots);
+        // Slots left empty on the left until the history fills the width
+        let blank = slots - shown;
+        let levels: Vec<usize> = self
+            .samples
+            .iter()
+            .skip(self.samples.len() - shown)
+            .map(|&v| {
+                let dots = (v / self.max).clamp(0.0, 1.0) * height_dots as f64;
+                // Anything above zero shows at least one dot
+                if v > 0.0 {
+                    (dots.round() as usize).max(1)
+                } else {
+                    0
+                }
+            })
+            .collect();
+
+        let style = Style::default().fg(self.color);
+        for row in 0..plot.height as usize {
+            // Dots covered by the rows below this one
+            let floor = (plot.height as usize - 1 - row) * 4;
+            for cell in 0..plot.width as usize {
+                let mut bits = 0;
+                for (half, masks) in [BRAILLE_LEFT, BRAILLE_RIGHT].iter().enumerate() {
+                    let Some(slot) = (cell * 2 + half).checked_sub(blank) else {
+                        continue;
+                    };
+                    let lit = levels[slot].saturating_sub(floor).min(4);
+                    bits |= masks[..lit].iter().sum::<u32>();
+                }
+                if bits != 0 {
+                    let ch = char::from_u32(0x2800 | bits).unwrap_or(' ');
+                    buf[(plot.x + cell as u16, plot.y + row as u16)]
+                        .set_char(ch)
+                        .set_style(style);
+                }
+            }
+        }
+    }
+}
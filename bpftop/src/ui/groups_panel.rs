@@ -0,0 +1,146 @@
+use std::collections::HashSet;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Paragraph, Widget};
+
+use crate::data::groups::{Group, GroupBy, GroupRow};
+use crate::data::process::format_bytes;
+use crate::redact::Redact;
+use crate::theme::Theme;
+use crate::ui::overlay::{render_column_header, render_footer, render_frame};
+use crate::ui::process_table::truncate_cols;
+
+/// Width of the PROCS, CPU%, MEM% and RES columns together.
+const NUMBERS_WIDTH: usize = 6 + 1 + 7 + 1 + 6 + 1 + 8;
+/// Width of the RUNTIME column shown for containers.
+const RUNTIME_WIDTH: usize = 10;
+
+/// Overlay with one row per service (or container) and its CPU and memory
+/// totals, expandable into its processes.
+pub struct GroupsPanel<'a> {
+    pub by: GroupBy,
+    pub groups: &'a [Group],
+    pub rows: &'a [GroupRow],
+    pub expanded: &'a HashSet<String>,
+    pub selected: usize,
+    pub redact: &'a Redact,
+    pub theme: &'a Theme,
+}
+
+impl<'a> Widget for GroupsPanel<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (inner, footer_area) = render_frame(
+            format!(" {}: {} ", self.by.label(), self.groups.len()),
+            (80, 80),
+            area,
+            buf,
+            self.theme,
+        );
+
+        let text_style = Style::default().fg(self.theme.fg);
+        let group_style = text_style.add_modifier(Modifier::BOLD);
+        let dim_style = Style::default().fg(self.theme.border);
+
+        let runtime_width = if self.by == GroupBy::Container { RUNTIME_WIDTH + 1 } else { 0 };
+        let name_width = (inner.width as usize)
+            .saturating_sub(NUMBERS_WIDTH + runtime_width + 1)
+            .max(16);
+        let runtime_col = |runtime: &str| {
+            if runtime_width == 0 {
+                String::new()
+            } else {
+                format!("{runtime:<RUNTIME_WIDTH$} ")
+            }
+        };
+
+        let content_area = render_column_header(
+            format!(
+                "{:<name_width$} {}{:>6} {:>7} {:>6} {:>8}",
+                "NAME",
+                runtime_col("RUNTIME"),
+                "PROCS",
+                "CPU%",
+                "MEM%",
+                "RES"
+            ),
+            inner,
+            buf,
+            self.theme,
+        );
+        let content_height = content_area.height as usize;
+
+        let lines: Vec<Line> = if self.rows.is_empty() {
+            vec![Line::styled(format!("  No {} found", self.by.label().to_lowercase()), dim_style)]
+        } else {
+            // Keep the selected row in view
+            let selected = self.selected.min(self.rows.len() - 1);
+            let scroll = (selected + 1).saturating_sub(content_height);
+            self.rows
+                .iter()
+                .enumerate()
+                .skip(scroll)
+                .take(content_height)
+                .map(|(i, &row)| {
+                    let (text, style) = match row {
+                        GroupRow::Group(g) => {
+                            let g = &self.groups[g];
+                            let indicator = if self.expanded.contains(&g.name) { "-" } else { "+" };
+                            let label = format!("{indicator} {}", g.name);
+                            let text = format!(
+                                "{:<name_width$} {}{:>6} {:>7.1} {:>6.1} {:>8}",
+                                truncate_cols(&label, name_width),
+                                runtime_col(g.runtime.map_or("", |r| r.label())),
+                                g.members.len(),
+                                g.cpu_percent,
+                                g.mem_percent,
+                                format_bytes(g.res_bytes)
+                            );
+                            (text, group_style)
+                        }
+                        GroupRow::Member(g, m) => {
+                            let m = &self.groups[g].members[m];
+                            let label = format!("    {:>7} {:<10} {}", m.pid, truncate_cols(&self.redact.user(&m.user), 10), m.comm);
+                            let text = format!(
+                                "{:<name_width$} {}{:>6} {:>7.1} {:>6.1} {:>8}",
+                                truncate_cols(&label, name_width),
+                                runtime_col(""),
+                                "",
+                                m.cpu_percent,
+                                m.mem_percent,
+                                format_bytes(m.res_bytes)
+                            );
+                            (text, text_style)
+                        }
+                    };
+                    let style = if i == selected {
+                        Style::default()
+                            .fg(self.theme.selection_fg)
+                            .bg(self.theme.selection_bg)
+                    } else {
+                        style
+                    };
+                    Line::styled(text, style)
+                })
+                .collect()
+        };
+        Paragraph::new(lines).render(content_area, buf);
+
+        render_footer(
+            &[
+                ("j/k", "Select"),
+                ("Space", "Expand"),
+                ("Enter", "Show in table"),
+                ("Tab", self.by.next().label()),
+                ("Esc", "Close"),
+            ],
+            Vec::new(),
+            None,
+            footer_area,
+            buf,
+            self.theme,
+        );
+    }
+}
@@ -1,23 +1,51 @@
+use std::collections::VecDeque;
+
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
-use ratatui::style::Style;
+use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::Widget;
 
+use crate::config::{CpuMeter, DiskMeter, MetersConfig, NetMeter};
 use crate::data::gpu::GpuDeviceInfo;
-use crate::data::system::{format_uptime, CpuStats, MemoryInfo, SwapInfo, SystemInfo};
+use crate::data::process::{format_bytes, format_rate};
+use crate::data::history::SystemHistory;
+use crate::data::system::{
+    format_uptime, BlockDevice, CpuStats, NetInterface, MemoryInfo, PsiAverages, SwapInfo, SystemInfo,
+};
 use crate::theme::Theme;
-use crate::ui::layout::cpu_grid_dims;
+use crate::ui::graph::{Graph, BRAILLE_LEFT, BRAILLE_RIGHT};
+use crate::ui::layout::{cpu_grid_dims, cpu_meter_rows, disk_meter_rows, net_meter_rows};
+
+/// Rows of each history graph, title included.
+pub const GRAPH_ROWS: u16 = 5;
+/// Smallest network scale, so an idle link doesn't draw noise at full height.
+pub const MIN_NET_SCALE: f64 = 64.0 * 1024.0;
+
+/// What the header shows above the process table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HeaderMode {
+    /// Instantaneous meters, one bar per row.
+    #[default]
+    Meters,
+    /// All meters squeezed onto one line.
+    Compact,
+    /// Scrolling CPU, memory and network graphs.
+    Graphs,
+}
 
 /// Renders the header area with CPU grid, memory bar, swap, and info line.
 pub struct HeaderWidget<'a> {
     pub sys: &'a SystemInfo,
+    pub history: &'a SystemHistory,
     pub theme: &'a Theme,
+    pub meters: &'a MetersConfig,
+    pub mode: HeaderMode,
 }
 
 impl<'a> Widget for HeaderWidget<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        if area.height < 2 {
+        if area.is_empty() {
             return;
         }
 
@@ -25,119 +53,385 @@ impl<'a> Widget for HeaderWidget<'a> {
         if num_cpus == 0 {
             return;
         }
-        let (grid_cols, grid_rows) = cpu_grid_dims(num_cpus, area.width);
-
-        let num_gpus = self.sys.gpus.len();
-
-        // Vertical layout: cpu_rows + (gpu * 2) + mem + swap + info
-        let mut constraints: Vec<Constraint> = (0..grid_rows)
-            .map(|_| Constraint::Length(1))
-            .collect();
-        for _ in 0..num_gpus {
-            constraints.push(Constraint::Length(1)); // GPU util
-            constraints.push(Constraint::Length(1)); // GPU mem
+        match self.mode {
+            HeaderMode::Compact => {
+                self.render_compact(area, buf);
+                return;
+            }
+            HeaderMode::Graphs => {
+                self.render_graphs(area, buf);
+                return;
+            }
+            HeaderMode::Meters => {}
         }
-        constraints.push(Constraint::Length(1)); // mem
-        constraints.push(Constraint::Length(1)); // swap
-        constraints.push(Constraint::Length(1)); // info line
-
+        let (grid_cols, grid_rows) = cpu_grid_dims(num_cpus, area.width);
+        let cpu_rows = cpu_meter_rows(self.meters.cpu, num_cpus, area.width);
+
+        let num_gpus = if self.meters.gpu { self.sys.gpus.len() } else { 0 };
+
+        // Vertical layout: cpu_rows + (gpu * 2) + mem + swap + disk + net + pressure + info
+        let total_rows = cpu_rows
+            + num_gpus * 2
+            + self.meters.memory as usize
+            + self.meters.swap as usize
+            + disk_meter_rows(self.meters.disk, self.sys.disks.len())
+            + net_meter_rows(self.meters, self.sys)
+            + self.meters.pressure as usize
+            + 1;
         let rows = Layout::default()
             .direction(Direction::Vertical)
-            .constraints(constraints)
+            .constraints(vec![Constraint::Length(1); total_rows])
             .split(area);
 
-        // CPU grid: column-major fill like htop
-        for grid_row in 0..grid_rows {
-            let row_area = rows[grid_row];
-            // Split this row into grid_cols columns
-            let col_constraints: Vec<Constraint> = (0..grid_cols)
-                .map(|_| Constraint::Ratio(1, grid_cols as u32))
-                .collect();
-            let col_areas = Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints(col_constraints)
-                .split(row_area);
-
-            for grid_col in 0..grid_cols {
-                let cpu_idx = grid_col * grid_rows + grid_row;
-                if cpu_idx < num_cpus {
-                    let label = format!("{cpu_idx}");
-                    // Shrink area by 1 on the right to leave a gap between columns
-                    let mut bar_area = col_areas[grid_col];
-                    if grid_col + 1 < grid_cols && bar_area.width > 1 {
-                        bar_area.width -= 1;
+        match self.meters.cpu {
+            CpuMeter::PerCpu => {
+                // CPU grid: column-major fill like htop
+                for grid_row in 0..grid_rows {
+                    let row_area = rows[grid_row];
+                    // Split this row into grid_cols columns
+                    let col_constraints: Vec<Constraint> = (0..grid_cols)
+                        .map(|_| Constraint::Ratio(1, grid_cols as u32))
+                        .collect();
+                    let col_areas = Layout::default()
+                        .direction(Direction::Horizontal)
+                        .constraints(col_constraints)
+                        .split(row_area);
+
+                    for grid_col in 0..grid_cols {
+                        let cpu_idx = grid_col * grid_rows + grid_row;
+                        if cpu_idx < num_cpus {
+                            let label = format!("{cpu_idx}");
+                            // Shrink area by 1 on the right to leave a gap between columns
+                            let mut bar_area = col_areas[grid_col];
+                            if grid_col + 1 < grid_cols && bar_area.width > 1 {
+                                bar_area.width -= 1;
+                            }
+                            render_cpu_bar(buf, bar_area, &self.sys.cpus[cpu_idx], &label, self.theme);
+                        }
                     }
-                    render_cpu_bar(buf, bar_area, &self.sys.cpus[cpu_idx], &label, self.theme);
                 }
             }
+            CpuMeter::Total => {
+                render_cpu_bar(buf, rows[0], &self.sys.cpu_total, "CPU", self.theme);
+            }
+            CpuMeter::Off => {}
         }
+        let mut row = cpu_rows;
 
         // GPU bars (2 rows per GPU: utilization + memory)
-        for (gi, gpu) in self.sys.gpus.iter().enumerate() {
-            let util_row = grid_rows + gi * 2;
-            let mem_row = grid_rows + gi * 2 + 1;
-            if util_row < rows.len() {
-                render_gpu_util_bar(buf, rows[util_row], gpu, self.theme);
-            }
-            if mem_row < rows.len() {
-                render_gpu_mem_bar(buf, rows[mem_row], gpu, self.theme);
-            }
+        for gpu in self.sys.gpus.iter().take(num_gpus) {
+            render_gpu_util_bar(buf, rows[row], gpu, self.theme);
+            render_gpu_mem_bar(buf, rows[row + 1], gpu, self.theme);
+            row += 2;
         }
 
-        let gpu_offset = num_gpus * 2;
-
         // Mem bar (full width)
-        let mem_row = grid_rows + gpu_offset;
-        if mem_row < rows.len() {
-            render_mem_bar(buf, rows[mem_row], &self.sys.memory, self.theme);
+        if self.meters.memory {
+            render_mem_bar(buf, rows[row], &self.sys.memory, self.theme);
+            row += 1;
         }
 
         // Swap bar (full width)
-        let swap_row = grid_rows + gpu_offset + 1;
-        if swap_row < rows.len() {
-            render_swap_bar(buf, rows[swap_row], &self.sys.swap, self.theme);
+        if self.meters.swap {
+            render_swap_bar(buf, rows[row], &self.sys.swap, self.theme);
+            row += 1;
         }
 
-        // Info line (full width): Tasks: N, N running  Load: x.xx x.xx x.xx  Uptime: Xd HH:MM:SS
-        let info_row = grid_rows + gpu_offset + 2;
-        if info_row < rows.len() {
-            let info_area = rows[info_row];
-            let line = Line::from(vec![
-                Span::styled("Tasks: ", Style::default().fg(self.theme.fg)),
-                Span::styled(
-                    format!("{}", self.sys.total_tasks),
-                    Style::default().fg(self.theme.fg),
-                ),
-                Span::styled(
-                    format!(", {} thr, {} kthr", self.sys.user_threads, self.sys.kernel_threads),
-                    Style::default().fg(self.theme.fg),
-                ),
-                Span::styled("; ", Style::default().fg(self.theme.fg)),
-                Span::styled(
-                    format!("{} running", self.sys.running_tasks),
-                    Style::default().fg(self.theme.proc_running),
-                ),
-                Span::styled("  ", Style::default().fg(self.theme.fg)),
-                Span::styled("Load: ", Style::default().fg(self.theme.fg)),
-                Span::styled(
-                    format!(
-                        "{:.2} {:.2} {:.2}",
-                        self.sys.load_avg[0], self.sys.load_avg[1], self.sys.load_avg[2]
-                    ),
-                    Style::default().fg(self.theme.fg),
+        // Disk bars (full width): one summing all disks, or one per disk
+        match self.meters.disk {
+            DiskMeter::Total if !self.sys.disks.is_empty() => {
+                render_disk_bar(buf, rows[row], "Disk", &disk_total(&self.sys.disks), self.theme);
+                row += 1;
+            }
+            DiskMeter::PerDevice => {
+                for disk in &self.sys.disks {
+                    render_disk_bar(buf, rows[row], &disk.name, disk, self.theme);
+                    row += 1;
+                }
+            }
+            _ => {}
+        }
+
+        // Network bars (full width): one summing the shown interfaces, or one each
+        let ifaces = self.shown_interfaces();
+        match self.meters.net {
+            NetMeter::Total if !ifaces.is_empty() => {
+                render_net_bar(buf, rows[row], "Net", &net_total(&ifaces), self.theme);
+                row += 1;
+            }
+            NetMeter::PerInterface => {
+                for iface in ifaces {
+                    render_net_bar(buf, rows[row], &iface.name, iface, self.theme);
+                    row += 1;
+                }
+            }
+            _ => {}
+        }
+
+        // Pressure bars side by side: cpu | memory | io
+        if let Some(pressure) = self.sys.pressure.as_ref().filter(|_| self.meters.pressure) {
+            let cols = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Ratio(1, 3); 3])
+                .spacing(1)
+                .split(rows[row]);
+            let bars = [
+                ("PSIcpu", &pressure.cpu, self.theme.psi_cpu),
+                ("PSImem", &pressure.memory, self.theme.psi_memory),
+                ("PSIio", &pressure.io, self.theme.psi_io),
+            ];
+            for ((label, avgs, color), col) in bars.into_iter().zip(cols.iter()) {
+                render_psi_bar(buf, *col, label, avgs, color, self.theme);
+            }
+            row += 1;
+        }
+
+        self.render_info_line(rows[row], buf);
+    }
+}
+
+impl<'a> HeaderWidget<'a> {
+    /// Interfaces the network meters cover (`[meters] net_interfaces`).
+    fn shown_interfaces(&self) -> Vec<&'a NetInterface> {
+        let meters = self.meters;
+        self.sys.net_ifaces.iter().filter(|i| meters.shows_interface(i)).collect()
+    }
+
+    /// Info line (full width): Tasks: N, N running  Load: x.xx x.xx x.xx  [Freq: x.x/y.yGHz [THROTTLED]]  [Power: x.xW]  Uptime: Xd HH:MM:SS
+    fn render_info_line(&self, area: Rect, buf: &mut Buffer) {
+        let mut spans = vec![
+            Span::styled("Tasks: ", Style::default().fg(self.theme.fg)),
+            Span::styled(
+                format!("{}", self.sys.total_tasks),
+                Style::default().fg(self.theme.fg),
+            ),
+            Span::styled(
+                format!(", {} thr, {} kthr", self.sys.user_threads, self.sys.kernel_threads),
+                Style::default().fg(self.theme.fg),
+            ),
+            Span::styled("; ", Style::default().fg(self.theme.fg)),
+            Span::styled(
+                format!("{} running", self.sys.running_tasks),
+                Style::default().fg(self.theme.proc_running),
+            ),
+            Span::styled("  ", Style::default().fg(self.theme.fg)),
+            Span::styled("Load: ", Style::default().fg(self.theme.fg)),
+            Span::styled(
+                format!(
+                    "{:.2} {:.2} {:.2}",
+                    self.sys.load_avg[0], self.sys.load_avg[1], self.sys.load_avg[2]
                 ),
-                Span::styled("  ", Style::default().fg(self.theme.fg)),
-                Span::styled("Uptime: ", Style::default().fg(self.theme.fg)),
-                Span::styled(
-                    format_uptime(self.sys.uptime_secs),
-                    Style::default().fg(self.theme.fg),
+                Style::default().fg(self.theme.fg),
+            ),
+        ];
+        if let Some(thermal) = self.sys.thermal.as_ref().filter(|t| t.max_khz > 0) {
+            spans.push(Span::styled("  Freq: ", Style::default().fg(self.theme.fg)));
+            spans.push(Span::styled(
+                format!("{:.1}/{:.1}GHz", thermal.cur_khz as f64 / 1e6, thermal.max_khz as f64 / 1e6),
+                Style::default().fg(self.theme.fg),
+            ));
+        }
+        if self.sys.thermal.as_ref().is_some_and(|t| t.throttling()) {
+            spans.push(Span::styled(
+                " THROTTLED",
+                Style::default().fg(self.theme.proc_zombie).add_modifier(Modifier::BOLD),
+            ));
+        }
+        if let Some(watts) = self.sys.package_watts() {
+            spans.push(Span::styled("  Power: ", Style::default().fg(self.theme.fg)));
+            spans.push(Span::styled(format!("{watts:.1}W"), Style::default().fg(self.theme.fg)));
+        }
+        spans.extend([
+            Span::styled("  ", Style::default().fg(self.theme.fg)),
+            Span::styled("Uptime: ", Style::default().fg(self.theme.fg)),
+            Span::styled(
+                format_uptime(self.sys.uptime_secs),
+                Style::default().fg(self.theme.fg),
+            ),
+        ]);
+        buf.set_line(area.x, area.y, &Line::from(spans), area.width);
+    }
+
+    /// History graphs side by side, then the info line.
+    fn render_graphs(&self, area: Rect, buf: &mut Buffer) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(GRAPH_ROWS), Constraint::Length(1)])
+            .split(area);
+        let cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(1, 3); 3])
+            .spacing(2)
+            .split(rows[0]);
+
+        let history = self.history;
+        let latest = |series: &VecDeque<f64>| series.back().copied().unwrap_or(0.0);
+        let net_peak = history.net.iter().copied().fold(0.0, f64::max);
+        let graphs = [
+            Graph {
+                title: format!("CPU {:.1}%", latest(&history.cpu)),
+                samples: &history.cpu,
+                max: 100.0,
+                color: self.theme.cpu_user,
+                theme: self.theme,
+            },
+            Graph {
+                title: format!("Mem {:.1}%", latest(&history.mem)),
+                samples: &history.mem,
+                max: 100.0,
+                color: self.theme.mem_used,
+                theme: self.theme,
+            },
+            Graph {
+                title: format!(
+                    "Net {}/s (peak {}/s)",
+                    format_bytes(latest(&history.net) as u64),
+                    format_bytes(net_peak as u64)
                 ),
-            ]);
-            buf.set_line(info_area.x, info_area.y, &line, info_area.width);
+                samples: &history.net,
+                max: net_peak.max(MIN_NET_SCALE),
+                color: self.theme.net_rate,
+                theme: self.theme,
+            },
+        ];
+        for (graph, col) in graphs.into_iter().zip(cols.iter()) {
+            graph.render(*col, buf);
+        }
+
+        self.render_info_line(rows[1], buf);
+    }
+
+    /// One-line header for compact mode: a braille strip with two CPUs per
+    /// cell, then memory, swap, GPU and the info line as plain numbers.
+    fn render_compact(&self, area: Rect, buf: &mut Buffer) {
+        let text = Style::default().fg(self.theme.fg);
+        let mut spans = Vec::new();
+        match self.meters.cpu {
+            CpuMeter::PerCpu => {
+                spans.push(Span::styled("CPU ", text));
+                spans.push(Span::styled(
+                    braille_strip(&self.sys.cpus),
+                    Style::default().fg(self.theme.cpu_user),
+                ));
+                spans.push(Span::styled(format!(" {:.1}%  ", self.sys.cpu_total.total_pct), text));
+            }
+            CpuMeter::Total => {
+                spans.push(Span::styled(format!("CPU {:.1}%  ", self.sys.cpu_total.total_pct), text));
+            }
+            CpuMeter::Off => {}
         }
+        if self.meters.memory {
+            let mem = &self.sys.memory;
+            let total = mem.total.saturating_sub(mem.zram_mem_used);
+            spans.push(Span::styled("Mem ", text));
+            spans.push(Span::styled(
+                format!("{}/{}", format_bytes(mem.used), format_bytes(total)),
+                Style::default().fg(self.theme.mem_used),
+            ));
+            spans.push(Span::styled("  ", text));
+        }
+        if self.meters.swap && self.sys.swap.total > 0 {
+            let swap = &self.sys.swap;
+            spans.push(Span::styled("Swp ", text));
+            spans.push(Span::styled(
+                format!("{}/{}", format_bytes(swap.used), format_bytes(swap.total)),
+                Style::default().fg(self.theme.swap_used),
+            ));
+            spans.push(Span::styled("  ", text));
+        }
+        if self.meters.gpu {
+            for gpu in &self.sys.gpus {
+                spans.push(Span::styled(
+                    format!("GPU{} {}%  ", gpu.index, gpu.utilization_pct),
+                    Style::default().fg(self.theme.gpu_util),
+                ));
+            }
+        }
+        if self.meters.disk != DiskMeter::Off && !self.sys.disks.is_empty() {
+            let total = disk_total(&self.sys.disks);
+            spans.push(Span::styled("Disk ", text));
+            spans.push(Span::styled(
+                format!("R{} ", format_rate(total.read_rate)),
+                Style::default().fg(self.theme.disk_read),
+            ));
+            spans.push(Span::styled(
+                format!("W{}", format_rate(total.write_rate)),
+                Style::default().fg(self.theme.disk_write),
+            ));
+            spans.push(Span::styled("  ", text));
+        }
+        let ifaces = self.shown_interfaces();
+        if self.meters.net != NetMeter::Off && !ifaces.is_empty() {
+            let total = net_total(&ifaces);
+            spans.push(Span::styled("Net ", text));
+            spans.push(Span::styled(
+                format!("R{} ", format_rate(total.rx_rate)),
+                Style::default().fg(self.theme.net_rx),
+            ));
+            spans.push(Span::styled(
+                format!("T{}", format_rate(total.tx_rate)),
+                Style::default().fg(self.theme.net_tx),
+            ));
+            spans.push(Span::styled("  ", text));
+        }
+        if let Some(pressure) = self.sys.pressure.as_ref().filter(|_| self.meters.pressure) {
+            spans.push(Span::styled("PSI ", text));
+            for (label, avgs, color) in [
+                ("c", &pressure.cpu, self.theme.psi_cpu),
+                ("m", &pressure.memory, self.theme.psi_memory),
+                ("i", &pressure.io, self.theme.psi_io),
+            ] {
+                spans.push(Span::styled(
+                    format!("{label}{:.1} ", avgs.avg10),
+                    Style::default().fg(color),
+                ));
+            }
+            spans.push(Span::styled(" ", text));
+        }
+        if let Some(thermal) = self.sys.thermal.as_ref().filter(|t| t.max_khz > 0) {
+            spans.push(Span::styled(format!("{:.1}GHz  ", thermal.cur_khz as f64 / 1e6), text));
+        }
+        if self.sys.thermal.as_ref().is_some_and(|t| t.throttling()) {
+            spans.push(Span::styled(
+                "THROT  ",
+                Style::default().fg(self.theme.proc_zombie).add_modifier(Modifier::BOLD),
+            ));
+        }
+        if let Some(watts) = self.sys.package_watts() {
+            spans.push(Span::styled(format!("Pwr {watts:.0}W  "), text));
+        }
+        spans.push(Span::styled(
+            format!(
+                "Tasks {}, {} run  Load {:.2} {:.2} {:.2}  Up {}",
+                self.sys.total_tasks,
+                self.sys.running_tasks,
+                self.sys.load_avg[0],
+                self.sys.load_avg[1],
+                self.sys.load_avg[2],
+                format_uptime(self.sys.uptime_secs)
+            ),
+            text,
+        ));
+        buf.set_line(area.x, area.y, &Line::from(spans), area.width);
     }
 }
 
+/// Render per-CPU load as braille bars, two CPUs per character.
+fn braille_strip(cpus: &[CpuStats]) -> String {
+    // 0-4 dots lit, rounding up so any load shows
+    let level = |cpu: &CpuStats| ((cpu.total_pct / 25.0).ceil() as usize).min(4);
+    cpus.chunks(2)
+        .map(|pair| {
+            let mut bits = 0x2800;
+            bits |= BRAILLE_LEFT[..level(&pair[0])].iter().sum::<u32>();
+            if let Some(right) = pair.get(1) {
+                bits |= BRAILLE_RIGHT[..level(right)].iter().sum::<u32>();
+            }
+            char::from_u32(bits).unwrap_or(' ')
+        })
+        .collect()
+}
+
 fn render_cpu_bar(buf: &mut Buffer, area: Rect, cpu: &CpuStats, label: &str, theme: &Theme) {
     if area.width < 10 {
         return;
@@ -222,6 +516,114 @@ fn render_mem_bar(buf: &mut Buffer, area: Rect, mem: &MemoryInfo, theme: &Theme)
     buf.set_line(area.x, area.y, &line, area.width);
 }
 
+/// All disks as one: rates summed, busy time of the busiest disk (the
+/// one that limits throughput).
+fn disk_total(disks: &[BlockDevice]) -> BlockDevice {
+    BlockDevice {
+        read_rate: disks.iter().map(|d| d.read_rate).sum(),
+        write_rate: disks.iter().map(|d| d.write_rate).sum(),
+        busy_pct: disks.iter().map(|d| d.busy_pct).fold(0.0, f64::max),
+        ..Default::default()
+    }
+}
+
+/// Disk bar: fills with busy %, split between reads and writes by bytes;
+/// the text shows both rates.
+fn render_disk_bar(buf: &mut Buffer, area: Rect, label: &str, disk: &BlockDevice, theme: &Theme) {
+    let suffix = format!(
+        "R {} W {}]",
+        format_rate(disk.read_rate),
+        format_rate(disk.write_rate)
+    );
+    let parts = [(disk.read_rate, theme.disk_read), (disk.write_rate, theme.disk_write)];
+    render_labeled_bar(buf, area, label, &suffix, disk.busy_pct, &parts, theme);
+}
+
+/// The shown interfaces as one: rates summed, utilisation of the busiest.
+fn net_total(ifaces: &[&NetInterface]) -> NetInterface {
+    NetInterface {
+        rx_rate: ifaces.iter().map(|i| i.rx_rate).sum(),
+        tx_rate: ifaces.iter().map(|i| i.tx_rate).sum(),
+        util_pct: ifaces.iter().map(|i| i.util_pct).fold(0.0, f64::max),
+        ..Default::default()
+    }
+}
+
+/// Network bar: fills with link utilisation of the busier direction, split
+/// between receive and transmit by bytes; the text shows both rates.
+fn render_net_bar(buf: &mut Buffer, area: Rect, label: &str, iface: &NetInterface, theme: &Theme) {
+    let suffix = format!(
+        "R {} T {}]",
+        format_rate(iface.rx_rate),
+        format_rate(iface.tx_rate)
+    );
+    let parts = [(iface.rx_rate, theme.net_rx), (iface.tx_rate, theme.net_tx)];
+    render_labeled_bar(buf, area, label, &suffix, iface.util_pct, &parts, theme);
+}
+
+/// One PSI resource: the bar fills with avg10, the text shows avg10/avg60.
+fn render_psi_bar(
+    buf: &mut Buffer,
+    area: Rect,
+    label: &str,
+    avgs: &PsiAverages,
+    color: Color,
+    theme: &Theme,
+) {
+    let suffix = format!("{:.1}/{:.1}%]", avgs.avg10, avgs.avg60);
+    render_labeled_bar(buf, area, label, &suffix, avgs.avg10, &[(1.0, color)], theme);
+}
+
+/// `label[||||    suffix`: the bar fills `fill_pct` of the room left, split
+/// between `parts` by weight and drawn in their colors. With no weight at
+/// all the last part takes the whole fill.
+fn render_labeled_bar(
+    buf: &mut Buffer,
+    area: Rect,
+    label: &str,
+    suffix: &str,
+    fill_pct: f64,
+    parts: &[(f64, Color)],
+    theme: &Theme,
+) {
+    if area.width < 10 {
+        return;
+    }
+
+    let prefix = format!("{label}[");
+    let bar_width = (area.width as usize).saturating_sub(prefix.len() + suffix.len());
+    if bar_width == 0 {
+        return;
+    }
+
+    let fill_chars = ((fill_pct / 100.0) * bar_width as f64) as usize;
+    let total_weight: f64 = parts.iter().map(|&(weight, _)| weight).sum();
+    let mut left = fill_chars;
+
+    let mut spans = vec![Span::styled(prefix, Style::default().fg(theme.fg))];
+    for (i, &(weight, color)) in parts.iter().enumerate() {
+        let chars = if i + 1 == parts.len() {
+            left
+        } else if total_weight > 0.0 {
+            ((fill_chars as f64 * weight / total_weight).round() as usize).min(left)
+        } else {
+            0
+        };
+        left -= chars;
+        if chars > 0 {
+            spans.push(Span::styled("|".repeat(chars), Style::default().fg(color)));
+        }
+    }
+    let empty_chars = bar_width.saturating_sub(fill_chars);
+    if empty_chars > 0 {
+        spans.push(Span::styled(" ".repeat(empty_chars), Style::default().fg(theme.fg)));
+    }
+    spans.push(Span::styled(suffix.to_string(), Style::default().fg(theme.fg)));
+
+    let line = Line::from(spans);
+    buf.set_line(area.x, area.y, &line, area.width);
+}
+
 fn render_gpu_util_bar(buf: &mut Buffer, area: Rect, gpu: &GpuDeviceInfo, theme: &Theme) {
     if area.width < 10 {
         return;
@@ -0,0 +1,128 @@
+use std::collections::VecDeque;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, Widget};
+
+use crate::data::history::ProcessSeries;
+use crate::data::process::{format_bytes, format_rate, ProcessInfo};
+use crate::theme::Theme;
+use crate::ui::graph::Graph;
+use crate::ui::header::MIN_NET_SCALE;
+use crate::ui::overlay::{render_footer, render_frame};
+
+/// Popup plotting one process's CPU, RSS and network over the retained
+/// history, or over what `[trends]` recorded, one braille graph per series.
+pub struct HistoryView<'a> {
+    pub pid: u32,
+    /// `None` once the process has exited.
+    pub process: Option<&'a ProcessInfo>,
+    /// `None` until the first sample after the process appeared.
+    pub series: Option<&'a ProcessSeries>,
+    /// Seconds the series covers, for the footer.
+    pub span_secs: u64,
+    /// Showing the samples recorded under `[trends]` (`t`).
+    pub long_term: bool,
+    /// Why the recorded samples can't be shown.
+    pub unavailable: Option<&'a str>,
+    pub theme: &'a Theme,
+}
+
+impl<'a> Widget for HistoryView<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let kind = if self.long_term { "Long-term history" } else { "History" };
+        let title = match self.process {
+            Some(p) => format!(" {kind}: {} ({}) ", p.comm, p.pid),
+            None => format!(" {kind}: PID {} ", self.pid),
+        };
+        let (content_area, footer_area) = render_frame(title, (80, 80), area, buf, self.theme);
+
+        let dim_style = Style::default().fg(self.theme.border);
+
+        let mut extra = Vec::new();
+
+        match (self.process, self.unavailable, self.series) {
+            (Some(_), Some(reason), _) => {
+                let warn_style = Style::default().fg(self.theme.proc_zombie);
+                Paragraph::new(Line::styled(format!("  {reason}"), warn_style))
+                    .render(content_area, buf);
+            }
+            (Some(_), None, Some(series)) => {
+                self.render_graphs(series, content_area, buf);
+                extra.push(Span::styled(format!("  last {}", span_label(self.span_secs)), dim_style));
+            }
+            (Some(_), None, None) => {
+                Paragraph::new(Line::styled("  Waiting for samples", dim_style))
+                    .render(content_area, buf);
+            }
+            (None, _, _) => {
+                Paragraph::new(Line::styled("  Process has exited", dim_style))
+                    .render(content_area, buf);
+            }
+        }
+
+        let other = if self.long_term { "Recent" } else { "Long-term" };
+        render_footer(&[("t", other), ("Esc", "Close")], extra, None, footer_area, buf, self.theme);
+    }
+}
+
+/// "9m30s" for the ten-minute graphs, "26h05m" for recorded trends.
+fn span_label(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}h{:02}m", secs / 3600, secs % 3600 / 60)
+    } else {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    }
+}
+
+impl<'a> HistoryView<'a> {
+    fn render_graphs(&self, series: &ProcessSeries, area: Rect, buf: &mut Buffer) {
+        let latest = |samples: &VecDeque<f64>| samples.back().copied().unwrap_or(0.0);
+        let peak = |samples: &VecDeque<f64>| samples.iter().copied().fold(0.0, f64::max);
+        let (cpu_peak, rss_peak, net_peak) = (peak(&series.cpu), peak(&series.rss), peak(&series.net));
+
+        let graphs = [
+            Graph {
+                title: format!("CPU {:.1}% (peak {:.1}%)", latest(&series.cpu), cpu_peak),
+                samples: &series.cpu,
+                // One full core, or higher once threads push past it
+                max: cpu_peak.max(100.0),
+                color: self.theme.cpu_user,
+                theme: self.theme,
+            },
+            Graph {
+                title: format!(
+                    "RSS {} (peak {})",
+                    format_bytes(latest(&series.rss) as u64),
+                    format_bytes(rss_peak as u64)
+                ),
+                samples: &series.rss,
+                max: rss_peak,
+                color: self.theme.mem_used,
+                theme: self.theme,
+            },
+            Graph {
+                title: format!(
+                    "Net {} (peak {})",
+                    format_rate(latest(&series.net)),
+                    format_rate(net_peak)
+                ),
+                samples: &series.net,
+                max: net_peak.max(MIN_NET_SCALE),
+                color: self.theme.net_rate,
+                theme: self.theme,
+            },
+        ];
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Ratio(1, 3); 3])
+            .spacing(1)
+            .split(area);
+        for (graph, row) in graphs.into_iter().zip(rows.iter()) {
+            graph.render(*row, buf);
+        }
+    }
+}
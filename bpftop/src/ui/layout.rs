@@ -1,5 +1,9 @@
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 
+use crate::config::{CpuMeter, DiskMeter, MetersConfig, NetMeter};
+use crate::data::system::SystemInfo;
+use crate::ui::header::{HeaderMode, GRAPH_ROWS};
+
 /// Compute the number of columns and rows for the CPU grid.
 pub fn cpu_grid_dims(num_cpus: usize, terminal_width: u16) -> (usize, usize) {
     let min_col_width: u16 = 20;
@@ -9,12 +13,64 @@ pub fn cpu_grid_dims(num_cpus: usize, terminal_width: u16) -> (usize, usize) {
     (cols, rows)
 }
 
+/// Rows taken by the CPU meters.
+pub fn cpu_meter_rows(meter: CpuMeter, num_cpus: usize, terminal_width: u16) -> usize {
+    match meter {
+        CpuMeter::PerCpu => cpu_grid_dims(num_cpus, terminal_width).1,
+        CpuMeter::Total => 1,
+        CpuMeter::Off => 0,
+    }
+}
+
+/// Rows taken by the disk meters.
+pub fn disk_meter_rows(meter: DiskMeter, num_disks: usize) -> usize {
+    match meter {
+        DiskMeter::Total => (num_disks > 0) as usize,
+        DiskMeter::PerDevice => num_disks,
+        DiskMeter::Off => 0,
+    }
+}
+
+/// Rows taken by the network meters.
+pub fn net_meter_rows(meters: &MetersConfig, sys: &SystemInfo) -> usize {
+    let shown = sys.net_ifaces.iter().filter(|i| meters.shows_interface(i)).count();
+    match meters.net {
+        NetMeter::Total => (shown > 0) as usize,
+        NetMeter::PerInterface => shown,
+        NetMeter::Off => 0,
+    }
+}
+
+/// Process rows shown by `--summary`.
+pub const SUMMARY_ROWS: u16 = 5;
+
 /// Main screen layout: header | process_table | status_bar.
 /// Returns (header_area, table_area, status_area, filter_area).
-pub fn main_layout(area: Rect, filter_active: bool, num_cpus: usize, num_gpus: usize) -> (Rect, Rect, Rect, Option<Rect>) {
-    let (_, cpu_rows) = cpu_grid_dims(num_cpus, area.width);
-    let gpu_rows = num_gpus * 2; // utilization + memory per GPU
-    let header_height = (cpu_rows + gpu_rows + 3) as u16; // cpu grid + gpu bars + mem + swap + info line
+/// With `table_rows`, the table is cut to that many processes and the
+/// status bar follows right under it.
+pub fn main_layout(
+    area: Rect,
+    filter_active: bool,
+    sys: &SystemInfo,
+    meters: &MetersConfig,
+    header_mode: HeaderMode,
+    table_rows: Option<u16>,
+) -> (Rect, Rect, Rect, Option<Rect>) {
+    let cpu_rows = cpu_meter_rows(meters.cpu, sys.cpus.len().max(1), area.width);
+    let gpu_rows = if meters.gpu { sys.gpus.len() * 2 } else { 0 }; // utilization + memory per GPU
+    let header_height = match header_mode {
+        // cpu meters + gpu bars + mem + swap + disk + net + pressure + info line
+        HeaderMode::Meters => {
+            let bar_rows = meters.memory as usize
+                + meters.swap as usize
+                + disk_meter_rows(meters.disk, sys.disks.len())
+                + net_meter_rows(meters, sys)
+                + meters.pressure as usize;
+            (cpu_rows + gpu_rows + bar_rows + 1) as u16
+        }
+        HeaderMode::Compact => 1,
+        HeaderMode::Graphs => GRAPH_ROWS + 1,
+    };
     let status_height = 1;
     let filter_height = if filter_active { 1 } else { 0 };
 
@@ -22,9 +78,11 @@ pub fn main_layout(area: Rect, filter_active: bool, num_cpus: usize, num_gpus: u
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(header_height),
-            Constraint::Min(5),
+            // Plus the column header row
+            table_rows.map_or(Constraint::Min(5), |rows| Constraint::Length(rows + 1)),
             Constraint::Length(filter_height),
             Constraint::Length(status_height),
+            Constraint::Min(0),
         ])
         .split(area);
 
@@ -1,7 +1,28 @@
+pub mod badges;
+pub mod bpf_objects_panel;
+pub mod bpf_stats_panel;
+pub mod cgroups_panel;
+pub mod connections_panel;
+pub mod copy_mode;
+pub mod detail;
 pub mod dialogs;
+pub mod diff_view;
+pub mod env_panel;
+pub mod execs_panel;
+pub mod files_panel;
 pub mod filter_bar;
+pub mod frame_limiter;
+pub mod graph;
+pub mod groups_panel;
 pub mod header;
+pub mod history_view;
 pub mod layout;
+pub mod overlay;
+pub mod oom_panel;
 pub mod process_table;
+pub mod runtime_panel;
+pub mod screen_reader;
+pub mod sessions_panel;
+pub mod setup;
 pub mod status_bar;
 pub mod tree_view;
@@ -0,0 +1,101 @@
+use std::collections::VecDeque;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, StatefulWidget, Widget};
+
+use crate::data::oom::OomRecord;
+use crate::data::process::format_bytes;
+use crate::data::system::format_uptime;
+use crate::redact::Redact;
+use crate::theme::Theme;
+use crate::ui::overlay::{render_column_header, render_footer, render_frame, Scroll};
+use crate::ui::process_table::truncate_cols;
+
+/// Overlay listing recent OOM kills, newest first.
+pub struct OomPanel<'a> {
+    pub ooms: &'a VecDeque<OomRecord>,
+    pub uptime_secs: f64,
+    pub redact: &'a Redact,
+    pub theme: &'a Theme,
+}
+
+impl<'a> StatefulWidget for OomPanel<'a> {
+    type State = Scroll;
+
+    fn render(self, area: Rect, buf: &mut Buffer, scroll: &mut Scroll) {
+        let (inner, footer_area) = render_frame(
+            format!(" OOM kills: {} ", self.ooms.len()),
+            (90, 60),
+            area,
+            buf,
+            self.theme,
+        );
+
+        let key_style = Style::default()
+            .fg(self.theme.status_key)
+            .add_modifier(Modifier::BOLD);
+        let text_style = Style::default().fg(self.theme.fg);
+        let dim_style = Style::default().fg(self.theme.border);
+        let victim_style = Style::default()
+            .fg(self.theme.proc_zombie)
+            .add_modifier(Modifier::BOLD);
+
+        let content_area = render_column_header(
+            format!(
+                "{:>10}  {:>7}  {:<16} {:<10} {:>7}  {:<24} {}",
+                "AGO", "PID", "VICTIM", "USER", "RSS", "GROUP", "TRIGGERED BY"
+            ),
+            inner,
+            buf,
+            self.theme,
+        );
+
+        let total = self.ooms.len();
+        let rows = scroll.fit(total, content_area.height as usize);
+
+        let lines: Vec<Line> = if self.ooms.is_empty() {
+            vec![Line::styled("  No OOM kills since bpftop started", dim_style)]
+        } else {
+            self.ooms
+                .iter()
+                .rev()
+                .skip(rows.start)
+                .take(rows.len())
+                .map(|o| {
+                    let ago = format_uptime((self.uptime_secs - o.time_secs).max(0.0));
+                    let rss = if o.rss_bytes > 0 { format_bytes(o.rss_bytes) } else { "?".to_string() };
+                    let comm = if o.comm.is_empty() { "?" } else { o.comm.as_str() };
+                    Line::from(vec![
+                        Span::styled(format!("{:>10}  ", ago), text_style),
+                        Span::styled(format!("{:>7}  ", o.pid), key_style),
+                        Span::styled(format!("{:<16} ", truncate_cols(comm, 16)), victim_style),
+                        Span::styled(
+                            format!(
+                                "{:<10} {:>7}  {:<24} {} ({})",
+                                truncate_cols(&self.redact.user(o.user.as_deref().unwrap_or("?")), 10),
+                                rss,
+                                truncate_cols(o.group.as_deref().unwrap_or("-"), 24),
+                                o.trigger_comm,
+                                o.trigger_pid
+                            ),
+                            text_style,
+                        ),
+                    ])
+                })
+                .collect()
+        };
+        Paragraph::new(lines).render(content_area, buf);
+
+        render_footer(
+            &[("j/k", "Scroll"), ("C", "Clear"), ("Esc", "Close")],
+            Vec::new(),
+            scroll.position(total),
+            footer_area,
+            buf,
+            self.theme,
+        );
+    }
+}
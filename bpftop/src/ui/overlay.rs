@@ -0,0 +1,193 @@
+use std::ops::Range;
+
+use crossterm::event::KeyCode;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+
+use crate::theme::Theme;
+use crate::ui::dialogs::centered_rect;
+
+/// Scroll position of a list overlay. Each render records how far the list
+/// can scroll, so keys stop at either end instead of running past it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Scroll {
+    offset: usize,
+    /// Largest offset that still fills the page, as of the last render.
+    max: usize,
+    /// Rows shown by the last render, used for PgUp/PgDn.
+    page: usize,
+}
+
+impl Scroll {
+    pub fn by(&mut self, delta: isize) {
+        self.offset = self.offset.saturating_add_signed(delta).min(self.max);
+    }
+
+    /// Keep the same rows in view after `rows` more are inserted above them.
+    pub fn hold(&mut self, rows: usize) {
+        if self.offset > 0 {
+            self.offset += rows;
+            self.max += rows;
+        }
+    }
+
+    pub fn home(&mut self) {
+        self.offset = 0;
+    }
+
+    pub fn end(&mut self) {
+        self.offset = self.max;
+    }
+
+    /// Apply j/k, arrows, PgUp/PgDn, g/Home or G/End. Returns false for any
+    /// other key so the caller can handle it.
+    pub fn handle_key(&mut self, code: KeyCode) -> bool {
+        let page = self.page.max(1) as isize;
+        match code {
+            KeyCode::Down | KeyCode::Char('j') => self.by(1),
+            KeyCode::Up | KeyCode::Char('k') => self.by(-1),
+            KeyCode::PageDown => self.by(page),
+            KeyCode::PageUp => self.by(-page),
+            KeyCode::Home | KeyCode::Char('g') => self.home(),
+            KeyCode::End | KeyCode::Char('G') => self.end(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Fit the position to `total` rows shown `page` at a time and return
+    /// the range of rows to draw.
+    pub fn fit(&mut self, total: usize, page: usize) -> Range<usize> {
+        self.page = page;
+        self.max = total.saturating_sub(page);
+        self.offset = self.offset.min(self.max);
+        self.offset..(self.offset + page).min(total)
+    }
+
+    /// "11-20/57" once the list no longer fits on one page.
+    pub fn position(&self, total: usize) -> Option<String> {
+        (total > self.page).then(|| {
+            let end = (self.offset + self.page).min(total);
+            format!("{}-{}/{}", self.offset + 1, end, total)
+        })
+    }
+}
+
+/// Clear a centered `percent_x` by `percent_y` popup, box it with `title`,
+/// and split the inside into the content rows and the footer row.
+pub fn render_frame(
+    title: String,
+    (percent_x, percent_y): (u16, u16),
+    area: Rect,
+    buf: &mut Buffer,
+    theme: &Theme,
+) -> (Rect, Rect) {
+    let dialog = centered_rect(percent_x, percent_y, area);
+    Clear.render(dialog, buf);
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.bg));
+    let inner = block.inner(dialog);
+    block.render(dialog, buf);
+
+    let content = Rect {
+        height: inner.height.saturating_sub(1),
+        ..inner
+    };
+    let footer = Rect {
+        y: inner.y + inner.height.saturating_sub(1),
+        height: inner.height.min(1),
+        ..inner
+    };
+    (content, footer)
+}
+
+/// Draw `text` as a column header on the first row of `area` and return the
+/// rows below it.
+pub fn render_column_header(text: String, area: Rect, buf: &mut Buffer, theme: &Theme) -> Rect {
+    let header_style = Style::default()
+        .fg(theme.column_header_fg)
+        .bg(theme.column_header_bg)
+        .add_modifier(Modifier::BOLD);
+    Paragraph::new(Line::styled(text, header_style))
+        .style(header_style)
+        .render(Rect { height: area.height.min(1), ..area }, buf);
+    Rect {
+        y: area.y + area.height.min(1),
+        height: area.height.saturating_sub(1),
+        ..area
+    }
+}
+
+/// Draw a footer of `[key] label` hints, then `extra`, then the scroll
+/// `position` dimmed at the end.
+pub fn render_footer(
+    hints: &[(&str, &str)],
+    extra: Vec<Span<'_>>,
+    position: Option<String>,
+    area: Rect,
+    buf: &mut Buffer,
+    theme: &Theme,
+) {
+    let key_style = Style::default()
+        .fg(theme.status_key)
+        .add_modifier(Modifier::BOLD);
+    let text_style = Style::default().fg(theme.fg);
+
+    let mut spans = Vec::with_capacity(hints.len() * 2 + extra.len() + 1);
+    for (i, (key, label)) in hints.iter().enumerate() {
+        spans.push(Span::styled(format!("[{}]", key), key_style));
+        let sep = if i + 1 < hints.len() { "  " } else { "" };
+        spans.push(Span::styled(format!(" {}{}", label, sep), text_style));
+    }
+    spans.extend(extra);
+    if let Some(position) = position {
+        spans.push(Span::styled(
+            format!("  {}", position),
+            Style::default().fg(theme.border),
+        ));
+    }
+    Paragraph::new(Line::from(spans)).render(area, buf);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scroll_clamps_in_state() {
+        let mut scroll = Scroll::default();
+        assert_eq!(scroll.fit(25, 10), 0..10);
+
+        assert!(scroll.handle_key(KeyCode::Char('G')));
+        assert_eq!(scroll.fit(25, 10), 15..25);
+        // j past the end stays put, so one k moves back up right away
+        scroll.handle_key(KeyCode::Char('j'));
+        scroll.handle_key(KeyCode::Char('k'));
+        assert_eq!(scroll.fit(25, 10), 14..24);
+
+        scroll.handle_key(KeyCode::PageUp);
+        assert_eq!(scroll.fit(25, 10), 4..14);
+        scroll.handle_key(KeyCode::PageUp);
+        assert_eq!(scroll.fit(25, 10), 0..10);
+        assert_eq!(scroll.position(25).as_deref(), Some("1-10/25"));
+        assert!(!scroll.handle_key(KeyCode::Char('x')));
+
+        // New rows above a scrolled list keep the same rows in view
+        scroll.by(3);
+        scroll.hold(5);
+        assert_eq!(scroll.fit(30, 10), 8..18);
+
+        // The list shrinking pulls the offset back in
+        scroll.end();
+        assert_eq!(scroll.fit(12, 10), 2..12);
+        assert_eq!(scroll.fit(5, 10), 0..5);
+        assert_eq!(scroll.position(5), None);
+    }
+}
@@ -1,16 +1,22 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::{Modifier, Style};
 use ratatui::widgets::Widget;
 
-use unicode_width::UnicodeWidthChar;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
+use crate::config::CommandDisplay;
 use crate::data::container::ServiceDisplayMode;
-use crate::data::process::{format_bytes, format_rate, format_time, ProcessInfo, ProcessState, SortColumn};
+use crate::data::process::{format_bytes, format_ns, format_rate, format_time, format_time_short, ProcessInfo, ProcessState, SortColumn};
+use crate::redact::Redact;
 use crate::theme::Theme;
+use crate::ui::badges::{Badge, Badges};
 
 /// Truncate a string to at most `max_cols` display columns (Unicode-aware).
-fn truncate_cols(s: &str, max_cols: usize) -> &str {
+pub fn truncate_cols(s: &str, max_cols: usize) -> &str {
     let mut cols = 0;
     for (i, ch) in s.char_indices() {
         let w = ch.width().unwrap_or(0);
@@ -29,12 +35,29 @@ pub struct ProcessTableWidget<'a> {
     pub scroll_offset: usize,
     pub sort_column: SortColumn,
     pub sort_ascending: bool,
+    /// Secondary sort key; its header gets an arrow but no highlight.
+    pub sort_then: Option<(SortColumn, bool)>,
     pub theme: &'a Theme,
     pub show_container: bool,
+    /// NAMESPACE and POD.
+    pub show_pod: bool,
     pub show_service: bool,
     pub service_display_mode: ServiceDisplayMode,
     pub show_gpu: bool,
+    /// WATTS, on hosts with RAPL.
+    pub show_power: bool,
     pub show_net: bool,
+    pub show_disk: bool,
+    pub show_job: bool,
+    /// Columns to show, in order (`[columns] show`).
+    pub columns: &'a [SortColumn],
+    /// Workload badges referenced by `ProcessInfo::badge`.
+    pub badges: &'a Badges,
+    /// Masks USER and the command line's arguments under `--redact`.
+    pub redact: &'a Redact,
+    /// Tighter columns for fitting more on screen.
+    pub compact: bool,
+    pub command_display: CommandDisplay,
     pub visual_range: Option<(usize, usize)>,
     pub error_message: Option<&'a str>,
 }
@@ -98,6 +121,23 @@ impl<'a> Widget for ProcessTableWidget<'a> {
                 abs_index,
             );
         }
+
+        // Scrollbar over the last column when the list overflows
+        if let Some((thumb, len)) =
+            scrollbar_thumb(self.processes.len(), visible_rows, self.scroll_offset, visible_rows)
+        {
+            let x = rows_area.right().saturating_sub(1);
+            let track_style = Style::default().fg(self.theme.border);
+            let thumb_style = Style::default().fg(self.theme.fg);
+            for i in 0..visible_rows {
+                let (symbol, style) = if (thumb..thumb + len).contains(&i) {
+                    ("█", thumb_style)
+                } else {
+                    ("│", track_style)
+                };
+                buf.set_string(x, rows_area.y + i as u16, symbol, style);
+            }
+        }
     }
 }
 
@@ -124,11 +164,14 @@ impl<'a> ProcessTableWidget<'a> {
         for (col, width) in &columns {
             let is_sort = *col == self.sort_column;
             let label = col.label();
-            let styled = if is_sort {
-                let arrow = if self.sort_ascending { "^" } else { "v" };
-                format!("{label}{arrow}")
+            let direction = if is_sort {
+                Some(self.sort_ascending)
             } else {
-                label.to_string()
+                self.sort_then.filter(|(c, _)| c == col).map(|(_, ascending)| ascending)
+            };
+            let styled = match direction {
+                Some(ascending) => format!("{label}{}", if ascending { "^" } else { "v" }),
+                None => label.to_string(),
             };
             let display = if *width > 0 {
                 format!("{:<width$}", styled, width = *width as usize)
@@ -158,6 +201,8 @@ impl<'a> ProcessTableWidget<'a> {
 
         let fg = if selected {
             self.theme.selection_fg
+        } else if proc.exited.is_some() {
+            self.theme.border
         } else if proc.frozen {
             self.theme.proc_frozen
         } else {
@@ -169,7 +214,10 @@ impl<'a> ProcessTableWidget<'a> {
             }
         };
 
-        let style = Style::default().fg(fg).bg(bg);
+        let mut style = Style::default().fg(fg).bg(bg);
+        if proc.idle && !selected {
+            style = style.add_modifier(Modifier::DIM);
+        }
 
         // Fill background
         for x in area.x..area.x + area.width {
@@ -181,9 +229,14 @@ impl<'a> ProcessTableWidget<'a> {
         let columns = self.column_layout(area.width);
         let mut x = area.x;
         for (col, width) in &columns {
-            let text = self.format_column(proc, col, *width);
             let cell_style = if *col == self.sort_column { sort_style } else { style };
-            buf.set_string(x, area.y, &text, cell_style);
+            if *col == SortColumn::Command {
+                let cell = Rect::new(x, area.y, *width, 1);
+                self.render_command(proc, cell, cell_style, selected, buf);
+            } else {
+                let text = self.format_column(proc, col, *width);
+                buf.set_string(x, area.y, &text, cell_style);
+            }
             x += *width;
             if x < area.x + area.width {
                 buf.set_string(x, area.y, " ", style);
@@ -192,13 +245,25 @@ impl<'a> ProcessTableWidget<'a> {
         }
     }
 
+    /// Workload badge to draw for a live process row.
+    fn badge_of(&self, proc: &ProcessInfo) -> Option<&'a Badge> {
+        if proc.exited.is_some() {
+            return None;
+        }
+        proc.badge.and_then(|i| self.badges.get(i))
+    }
+
     fn format_column(&self, proc: &ProcessInfo, col: &SortColumn, width: u16) -> String {
         let w = width as usize;
         match col {
-            SortColumn::Pid => format!("{:>w$}", proc.pid),
+            // Threads show their own ID, like htop
+            SortColumn::Pid => format!("{:>w$}", proc.tid),
+            SortColumn::Pgrp => format!("{:>w$}", proc.pgid),
+            SortColumn::Sid => format!("{:>w$}", proc.sid),
+            SortColumn::Tty => format!("{:<w$}", truncate_cols(proc.tty.as_deref().unwrap_or("-"), w)),
             SortColumn::User => {
-                let u = &proc.user;
-                let t = truncate_cols(u, w);
+                let u = self.redact.user(&proc.user);
+                let t = truncate_cols(&u, w);
                 if t.len() < u.len() {
                     t.to_string()
                 } else {
@@ -213,8 +278,16 @@ impl<'a> ProcessTableWidget<'a> {
             SortColumn::State => format!("{:>w$}", proc.state.as_char()),
             SortColumn::CpuPercent => format!("{:>w$.1}", proc.cpu_percent),
             SortColumn::MemPercent => format!("{:>w$.1}", proc.mem_percent),
+            SortColumn::Grow => {
+                if proc.rss_growth <= 0.0 {
+                    format!("{:>w$}", "-")
+                } else {
+                    format!("{:>w$}", format_rate(proc.rss_growth))
+                }
+            }
             SortColumn::GpuPercent => format!("{:>w$.1}", proc.gpu_percent),
             SortColumn::GpuMem => format!("{:>w$}", format_bytes(proc.gpu_mem_bytes)),
+            SortColumn::Watts => format!("{:>w$.1}", proc.watts),
             SortColumn::NetRate => {
                 let total = proc.net_tx_bytes + proc.net_rx_bytes;
                 if total == 0 {
@@ -223,6 +296,20 @@ impl<'a> ProcessTableWidget<'a> {
                     format!("{:>w$}", format_rate(proc.net_rate))
                 }
             }
+            SortColumn::NetTx => {
+                if proc.net_tx_bytes == 0 {
+                    format!("{:>w$}", "-")
+                } else {
+                    format!("{:>w$}", format_rate(proc.net_tx_rate))
+                }
+            }
+            SortColumn::NetRx => {
+                if proc.net_rx_bytes == 0 {
+                    format!("{:>w$}", "-")
+                } else {
+                    format!("{:>w$}", format_rate(proc.net_rx_rate))
+                }
+            }
             SortColumn::NetTotal => {
                 let total = proc.net_tx_bytes + proc.net_rx_bytes;
                 if total == 0 {
@@ -231,6 +318,36 @@ impl<'a> ProcessTableWidget<'a> {
                     format!("{:>w$}", format_bytes(total))
                 }
             }
+            SortColumn::Drops => {
+                if proc.sock_drops == 0 {
+                    format!("{:>w$}", "-")
+                } else {
+                    format!("{:>w$.0}", proc.sock_drop_rate)
+                }
+            }
+            SortColumn::Csw => format!("{:>w$.0}", proc.csw_rate),
+            SortColumn::Icsw => format!("{:>w$.0}", proc.icsw_rate),
+            SortColumn::RunqLat => {
+                if proc.runq_latency_ns <= 0.0 {
+                    format!("{:>w$}", "-")
+                } else {
+                    format!("{:>w$}", format_ns(proc.runq_latency_ns))
+                }
+            }
+            SortColumn::DiskRead => {
+                if proc.disk_read_bytes == 0 {
+                    format!("{:>w$}", "-")
+                } else {
+                    format!("{:>w$}", format_rate(proc.disk_read_rate))
+                }
+            }
+            SortColumn::DiskWrite => {
+                if proc.disk_write_bytes == 0 {
+                    format!("{:>w$}", "-")
+                } else {
+                    format!("{:>w$}", format_rate(proc.disk_write_rate))
+                }
+            }
             SortColumn::NetIf => {
                 let total = proc.net_tx_bytes + proc.net_rx_bytes;
                 if total == 0 {
@@ -245,10 +362,24 @@ impl<'a> ProcessTableWidget<'a> {
                     }
                 }
             }
+            SortColumn::Time if self.compact => format!("{:>w$}", format_time_short(proc.cpu_time_secs)),
             SortColumn::Time => {
                 let t = format_time(proc.cpu_time_secs);
                 format!("{:>w$}", t)
             }
+            SortColumn::Namespace | SortColumn::Pod => {
+                let name = match col {
+                    SortColumn::Namespace => proc.pod_namespace.as_deref(),
+                    _ => proc.pod.as_deref(),
+                };
+                let name = name.unwrap_or("-");
+                let t = truncate_cols(name, w);
+                if t.len() < name.len() {
+                    t.to_string()
+                } else {
+                    format!("{:<w$}", name)
+                }
+            }
             SortColumn::Container => {
                 let name = proc.container.as_deref().unwrap_or("-");
                 let t = truncate_cols(name, w);
@@ -268,7 +399,12 @@ impl<'a> ProcessTableWidget<'a> {
                 }
             }
             SortColumn::Command => {
-                let display = format!("{}{}", proc.tree_prefix, proc.cmdline);
+                let display = format!(
+                    "{}{}{}",
+                    proc.tree_prefix,
+                    command_status(proc).unwrap_or_default(),
+                    self.command_text(proc)
+                );
                 if w > 0 {
                     let t = truncate_cols(&display, w);
                     if t.len() < display.len() {
@@ -280,38 +416,128 @@ impl<'a> ProcessTableWidget<'a> {
         }
     }
 
+    /// Command cell: tree prefix, then the workload badge in its own color
+    /// or an exit/thaw note, then the command with the program's name
+    /// picked out.
+    fn render_command(&self, proc: &ProcessInfo, cell: Rect, style: Style, selected: bool, buf: &mut Buffer) {
+        let (y, end) = (cell.y, cell.right());
+        let room = |x: u16| end.saturating_sub(x) as usize;
+        let (mut x, _) = buf.set_stringn(cell.x, y, &proc.tree_prefix, cell.width as usize, style);
+        if let Some(badge) = self.badge_of(proc) {
+            let badge_style = style.fg(badge.color).add_modifier(Modifier::BOLD);
+            (x, _) = buf.set_stringn(x, y, &badge.label, room(x), badge_style);
+            (x, _) = buf.set_stringn(x, y, " ", room(x), style);
+        } else if let Some(status) = command_status(proc) {
+            (x, _) = buf.set_stringn(x, y, status, room(x), style);
+        }
+        let cmd = self.command_text(proc);
+        buf.set_stringn(x, y, &cmd, room(x), style);
+
+        // Comm is the name already, and exited rows stay grayed out
+        let plain = self.command_display == CommandDisplay::Comm || proc.is_thread || proc.exited.is_some();
+        let span = program_span(&cmd);
+        if plain || span.is_empty() {
+            return;
+        }
+        let name_style = if selected {
+            style.add_modifier(Modifier::BOLD)
+        } else {
+            style.fg(self.theme.command_basename).add_modifier(Modifier::BOLD)
+        };
+        let name_x = x.saturating_add(cmd[..span.start].width() as u16);
+        if name_x < end {
+            buf.set_stringn(name_x, y, &cmd[span], room(name_x), name_style);
+        }
+    }
+
+    /// The command as `command_display` asks for it.
+    fn command_text<'p>(&self, proc: &'p ProcessInfo) -> Cow<'p, str> {
+        // The thread name says more than the process's cmdline again
+        if proc.is_thread || self.command_display == CommandDisplay::Comm {
+            return Cow::Borrowed(&proc.comm);
+        }
+        match (self.command_display, self.redact.cmdline(&proc.cmdline)) {
+            (CommandDisplay::Basename, Cow::Borrowed(cmd)) => Cow::Borrowed(strip_program_dir(cmd)),
+            (CommandDisplay::Basename, Cow::Owned(cmd)) => Cow::Owned(strip_program_dir(&cmd).to_string()),
+            (_, cmd) => cmd,
+        }
+    }
+
     fn column_layout(&self, total_width: u16) -> Vec<(SortColumn, u16)> {
         column_layout(
             total_width,
+            self.columns,
             self.processes,
             self.show_container,
+            self.show_pod,
             self.show_service,
             self.service_display_mode,
             self.show_gpu,
+            self.show_power,
             self.show_net,
+            self.show_disk,
+            self.show_job,
+            self.compact,
         )
     }
 }
 
+/// Note ahead of the command for a process that exited or will be thawed.
+fn command_status(proc: &ProcessInfo) -> Option<String> {
+    match (&proc.exited, proc.thaw_in_secs) {
+        (Some(exit), _) => Some(format!("[{}, ran {}] ", exit.status_label(), format_time(exit.lifetime_secs))),
+        (None, Some(secs)) => Some(format!("[frozen, thaws in {}] ", format_time_short(secs as f64))),
+        (None, None) => None,
+    }
+}
+
+/// Byte range of the program's name in a command line: the first word,
+/// after its last `/`. Empty for a kernel thread's `[name]`, where a `/`
+/// separates the thread from its CPU or workqueue.
+fn program_span(cmd: &str) -> Range<usize> {
+    if cmd.starts_with('[') {
+        return 0..0;
+    }
+    let end = cmd.find(' ').unwrap_or(cmd.len());
+    let start = cmd[..end].rfind('/').map_or(0, |i| i + 1);
+    start..end
+}
+
+/// The command line with the directory in front of the program dropped.
+fn strip_program_dir(cmd: &str) -> &str {
+    &cmd[program_span(cmd).start..]
+}
+
 /// Compute the column layout: which columns are visible and their widths.
 ///
 /// This is extracted so that `input.rs` can resolve mouse clicks to columns.
+#[allow(clippy::too_many_arguments)]
 pub fn column_layout(
     total_width: u16,
+    columns: &[SortColumn],
     processes: &[ProcessInfo],
     show_container: bool,
+    show_pod: bool,
     show_service: bool,
     service_display_mode: ServiceDisplayMode,
     show_gpu: bool,
+    show_power: bool,
     show_net: bool,
+    show_disk: bool,
+    show_job: bool,
+    compact: bool,
 ) -> Vec<(SortColumn, u16)> {
-    let mut cols: Vec<(SortColumn, u16)> = SortColumn::all()
+    let mut cols: Vec<(SortColumn, u16)> = columns
         .iter()
         .filter(|c| **c != SortColumn::Container || show_container)
+        .filter(|c| !matches!(**c, SortColumn::Namespace | SortColumn::Pod) || show_pod)
         .filter(|c| **c != SortColumn::Service || show_service)
         .filter(|c| (**c != SortColumn::GpuPercent && **c != SortColumn::GpuMem) || show_gpu)
-        .filter(|c| !matches!(**c, SortColumn::NetRate | SortColumn::NetTotal | SortColumn::NetIf) || show_net)
-        .map(|c| (*c, c.width()))
+        .filter(|c| **c != SortColumn::Watts || show_power)
+        .filter(|c| !matches!(**c, SortColumn::NetRate | SortColumn::NetTx | SortColumn::NetRx | SortColumn::NetTotal | SortColumn::NetIf | SortColumn::Drops) || show_net)
+        .filter(|c| !matches!(**c, SortColumn::DiskRead | SortColumn::DiskWrite) || show_disk)
+        .filter(|c| !matches!(**c, SortColumn::Pgrp | SortColumn::Sid | SortColumn::Tty) || show_job)
+        .map(|c| (*c, if compact { c.compact_width() } else { c.width() }))
         .collect();
 
     // Size UNIT column to fit content, capped per display mode
@@ -326,6 +552,7 @@ pub fn column_layout(
             ServiceDisplayMode::AllUnits => 28,
             ServiceDisplayMode::FullSlice => 40,
         };
+        let cap = if compact { cap / 2 } else { cap };
         // At least wide enough for the header label, at most the cap
         entry.1 = max_len.clamp(5, cap);
     }
@@ -340,7 +567,76 @@ pub fn column_layout(
     cols
 }
 
+/// First row and length of the scrollbar thumb on a `track`-row track, or
+/// `None` when all `total` rows fit in `visible`.
+pub fn scrollbar_thumb(total: usize, visible: usize, offset: usize, track: usize) -> Option<(usize, usize)> {
+    if total <= visible || track == 0 {
+        return None;
+    }
+    let len = (visible * track / total).clamp(1, track);
+    let max_offset = total - visible;
+    let start = (offset.min(max_offset) * (track - len) + max_offset / 2) / max_offset;
+    Some((start, len))
+}
+
+/// Scroll offset that puts the top of the thumb at `row` of a `track`-row
+/// scrollbar, the inverse of [`scrollbar_thumb`] for dragging.
+pub fn scroll_offset_at(row: usize, total: usize, visible: usize, track: usize) -> usize {
+    let Some((_, len)) = scrollbar_thumb(total, visible, 0, track) else {
+        return 0;
+    };
+    let max_offset = total - visible;
+    let travel = track - len;
+    if travel == 0 {
+        return 0;
+    }
+    (row.min(travel) * max_offset + travel / 2) / travel
+}
+
 /// Calculate visible row count for the process table area.
 pub fn visible_rows(area: Rect) -> usize {
     area.height.saturating_sub(1) as usize // minus header row
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrollbar_thumb_and_drag_offset_agree() {
+        assert_eq!(scrollbar_thumb(10, 20, 0, 20), None);
+        assert_eq!(scrollbar_thumb(100, 20, 0, 0), None);
+        assert_eq!(scroll_offset_at(3, 10, 20, 20), 0);
+
+        // 100 rows, 20 on screen: a 4-row thumb travelling 16 rows
+        assert_eq!(scrollbar_thumb(100, 20, 0, 20), Some((0, 4)));
+        assert_eq!(scrollbar_thumb(100, 20, 40, 20), Some((8, 4)));
+        assert_eq!(scrollbar_thumb(100, 20, 80, 20), Some((16, 4)));
+        assert_eq!(scrollbar_thumb(100, 20, 500, 20), Some((16, 4)));
+        assert_eq!(scroll_offset_at(8, 100, 20, 20), 40);
+        assert_eq!(scroll_offset_at(16, 100, 20, 20), 80);
+        assert_eq!(scroll_offset_at(19, 100, 20, 20), 80);
+
+        // Dragging the thumb to where it is drawn doesn't move it
+        for offset in 0..=80 {
+            let (start, _) = scrollbar_thumb(100, 20, offset, 20).unwrap();
+            let moved = scroll_offset_at(start, 100, 20, 20);
+            assert_eq!(scrollbar_thumb(100, 20, moved, 20).unwrap().0, start);
+        }
+    }
+
+    #[test]
+    fn program_name_is_the_first_word_after_its_directory() {
+        assert_eq!(program_span("/usr/bin/python3 -m http.server"), 9..16);
+        assert_eq!(program_span("nginx: worker process"), 0..6);
+        assert_eq!(program_span("/opt/app/bin/server"), 13..19);
+        assert_eq!(program_span(""), 0..0);
+        assert_eq!(strip_program_dir("/usr/bin/python3 -m http.server"), "python3 -m http.server");
+        assert_eq!(strip_program_dir("python3 /srv/app.py"), "python3 /srv/app.py");
+
+        // Kernel threads keep their whole name
+        assert!(program_span("[kworker/0:1-events]").is_empty());
+        assert_eq!(strip_program_dir("[kworker/0:1-events]"), "[kworker/0:1-events]");
+        assert_eq!(strip_program_dir("[ksoftirqd/3]"), "[ksoftirqd/3]");
+    }
+}
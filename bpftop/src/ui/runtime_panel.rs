@@ -0,0 +1,62 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Paragraph, StatefulWidget, Widget};
+
+use crate::data::runtime_probe::RuntimeProbe;
+use crate::theme::Theme;
+use crate::ui::overlay::{render_footer, render_frame, Scroll};
+
+/// Overlay with a JVM or Python thread dump from jcmd / py-spy.
+pub struct RuntimePanel<'a> {
+    pub probe: &'a RuntimeProbe,
+    pub theme: &'a Theme,
+}
+
+impl<'a> StatefulWidget for RuntimePanel<'a> {
+    type State = Scroll;
+
+    fn render(self, area: Rect, buf: &mut Buffer, scroll: &mut Scroll) {
+        let (content_area, footer_area) = render_frame(
+            format!(" {} ", self.probe.describe()),
+            (90, 85),
+            area,
+            buf,
+            self.theme,
+        );
+
+        let text_style = Style::default().fg(self.theme.fg);
+        let dim_style = Style::default().fg(self.theme.border);
+        let warn_style = Style::default()
+            .fg(self.theme.proc_zombie)
+            .add_modifier(Modifier::BOLD);
+
+        let mut total = 0;
+        let lines: Vec<Line> = match &self.probe.result {
+            None => vec![Line::styled("  Running...", dim_style)],
+            Some(Err(reason)) => vec![Line::styled(format!("  {reason}"), warn_style)],
+            Some(Ok(output)) if output.is_empty() => {
+                vec![Line::styled("  No output", dim_style)]
+            }
+            Some(Ok(output)) => {
+                total = output.len();
+                let rows = scroll.fit(total, content_area.height as usize);
+                output[rows]
+                    .iter()
+                    .map(|l| Line::styled(l.as_str(), text_style))
+                    .collect()
+            }
+        };
+        Paragraph::new(lines).render(content_area, buf);
+
+        render_footer(
+            &[("j/k", "Scroll"), ("r", "Rerun"), ("Esc", "Close")],
+            Vec::new(),
+            scroll.position(total),
+            footer_area,
+            buf,
+            self.theme,
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use ratatui::buffer::Buffer;
+
+use crate::data::process::{format_bytes, ProcessInfo};
+use crate::redact::Redact;
+
+/// Blank out box-drawing, block and braille characters, which screen
+/// readers either skip or spell out one by one. Tree indentation and dialog
+/// layout keep their width, so columns stay where they were.
+pub fn strip_decoration(buf: &mut Buffer) {
+    for cell in buf.content.iter_mut() {
+        let decorative = cell
+            .symbol()
+            .chars()
+            .next()
+            .is_some_and(|c| matches!(c, '\u{2500}'..='\u{259F}' | '\u{2800}'..='\u{28FF}'));
+        if decorative {
+            cell.set_symbol(" ");
+        }
+    }
+}
+
+/// Sentence describing the selected row, shown on the status line (where
+/// the cursor sits) so it is read out whenever the selection moves.
+pub fn describe_selection(proc: &ProcessInfo, redact: &Redact, index: usize, total: usize) -> String {
+    let mut text = format!(
+        "{} of {}: {} PID {}, user {}, CPU {:.1}%, memory {} ({:.1}%), {}",
+        index + 1,
+        total,
+        proc.comm,
+        proc.pid,
+        redact.user(&proc.user),
+        proc.cpu_percent,
+        format_bytes(proc.res_bytes),
+        proc.mem_percent,
+        proc.state.description()
+    );
+    if proc.tagged {
+        text.push_str(", tagged");
+    }
+    if proc.frozen {
+        text.push_str(", frozen");
+    }
+    text
+}
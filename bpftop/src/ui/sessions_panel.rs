@@ -0,0 +1,103 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::widgets::{Paragraph, Widget};
+
+use crate::data::process::format_bytes;
+use crate::data::session::SessionSummary;
+use crate::redact::Redact;
+use crate::theme::Theme;
+use crate::ui::overlay::{render_column_header, render_footer, render_frame};
+use crate::ui::process_table::truncate_cols;
+
+/// Overlay listing login sessions (SSH connections, terminal windows,
+/// consoles) with what runs in each.
+pub struct SessionsPanel<'a> {
+    pub sessions: &'a [SessionSummary],
+    pub selected: usize,
+    /// Sessions without a terminal (daemons) are listed too.
+    pub all: bool,
+    /// bpftop's own session, marked so it isn't killed by mistake.
+    pub own_sid: u32,
+    pub redact: &'a Redact,
+    pub theme: &'a Theme,
+}
+
+impl<'a> Widget for SessionsPanel<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = if self.all {
+            format!(" Sessions: {} ", self.sessions.len())
+        } else {
+            format!(" Terminal sessions: {} ", self.sessions.len())
+        };
+        let (inner, footer_area) = render_frame(title, (80, 70), area, buf, self.theme);
+
+        let text_style = Style::default().fg(self.theme.fg);
+        let dim_style = Style::default().fg(self.theme.border);
+
+        let content_area = render_column_header(
+            format!(
+                "{:>7}  {:<8} {:<10} {:<16} {:<16} {:>5} {:>6} {:>7}",
+                "SID", "TTY", "USER", "LEADER", "FROM", "PROCS", "CPU%", "RES"
+            ),
+            inner,
+            buf,
+            self.theme,
+        );
+        let content_height = content_area.height as usize;
+
+        let lines: Vec<Line> = if self.sessions.is_empty() {
+            vec![Line::styled("  No terminal sessions ([a] shows all)", dim_style)]
+        } else {
+            // Keep the selected row in view
+            let selected = self.selected.min(self.sessions.len() - 1);
+            let scroll = (selected + 1).saturating_sub(content_height);
+            self.sessions
+                .iter()
+                .enumerate()
+                .skip(scroll)
+                .take(content_height)
+                .map(|(i, s)| {
+                    let own = if s.sid == self.own_sid { "  (bpftop)" } else { "" };
+                    let text = format!(
+                        "{:>7}  {:<8} {:<10} {:<16} {:<16} {:>5} {:>6.1} {:>7}{own}",
+                        s.sid,
+                        truncate_cols(s.tty.as_deref().unwrap_or("-"), 8),
+                        truncate_cols(&self.redact.user(&s.user), 10),
+                        truncate_cols(s.leader.as_deref().unwrap_or("(exited)"), 16),
+                        truncate_cols(s.origin.as_deref().unwrap_or("-"), 16),
+                        s.processes,
+                        s.cpu_percent,
+                        format_bytes(s.res_bytes)
+                    );
+                    let style = if i == selected {
+                        Style::default()
+                            .fg(self.theme.selection_fg)
+                            .bg(self.theme.selection_bg)
+                    } else {
+                        text_style
+                    };
+                    Line::styled(text, style)
+                })
+                .collect()
+        };
+        Paragraph::new(lines).render(content_area, buf);
+
+        let toggle = if self.all { "Terminals only" } else { "All sessions" };
+        render_footer(
+            &[
+                ("j/k", "Select"),
+                ("Enter", "Show processes"),
+                ("x", "Kill session"),
+                ("a", toggle),
+                ("Esc", "Close"),
+            ],
+            Vec::new(),
+            None,
+            footer_area,
+            buf,
+            self.theme,
+        );
+    }
+}
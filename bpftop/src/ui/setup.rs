@@ -0,0 +1,354 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+
+use crate::config::Config;
+use crate::data::process::SortColumn;
+use crate::theme::Theme;
+use crate::ui::dialogs::centered_rect;
+use crate::ui::process_table::truncate_cols;
+
+/// Lowest refresh rate the setup screen will step down to.
+const MIN_REFRESH_MS: u64 = 100;
+const REFRESH_STEP_MS: u64 = 100;
+const FPS_STEP: u32 = 5;
+
+/// Panes of the setup screen, in tab order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SetupPane {
+    #[default]
+    General,
+    Columns,
+    Theme,
+    Meters,
+}
+
+impl SetupPane {
+    pub const ALL: [SetupPane; 4] = [
+        SetupPane::General,
+        SetupPane::Columns,
+        SetupPane::Theme,
+        SetupPane::Meters,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SetupPane::General => "General",
+            SetupPane::Columns => "Columns",
+            SetupPane::Theme => "Theme",
+            SetupPane::Meters => "Meters",
+        }
+    }
+
+    /// The pane `delta` tabs away, wrapping around.
+    pub fn cycle(&self, delta: isize) -> Self {
+        let n = Self::ALL.len() as isize;
+        let idx = Self::ALL.iter().position(|p| p == self).unwrap_or(0) as isize;
+        Self::ALL[(idx + delta).rem_euclid(n) as usize]
+    }
+
+    /// Editable settings on this pane (empty for Columns and Theme, which
+    /// list columns and presets instead).
+    pub fn settings(&self) -> &'static [Setting] {
+        match self {
+            SetupPane::General => &[
+                Setting::RefreshRate,
+                Setting::MaxFps,
+                Setting::IdleDimRefreshes,
+                Setting::TreeView,
+                Setting::ShowThreads,
+                Setting::ShowKernelThreads,
+                Setting::Compact,
+                Setting::CommandDisplay,
+            ],
+            SetupPane::Meters => &[
+                Setting::CpuMeter,
+                Setting::MemoryMeter,
+                Setting::SwapMeter,
+                Setting::DiskMeter,
+                Setting::NetMeter,
+                Setting::GpuMeters,
+                Setting::PressureMeters,
+            ],
+            SetupPane::Columns | SetupPane::Theme => &[],
+        }
+    }
+}
+
+/// A single value on the General or Meters pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    RefreshRate,
+    MaxFps,
+    IdleDimRefreshes,
+    TreeView,
+    ShowThreads,
+    ShowKernelThreads,
+    Compact,
+    CommandDisplay,
+    CpuMeter,
+    MemoryMeter,
+    SwapMeter,
+    DiskMeter,
+    NetMeter,
+    GpuMeters,
+    PressureMeters,
+}
+
+impl Setting {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Setting::RefreshRate => "Refresh rate",
+            Setting::MaxFps => "Max redraws/s",
+            Setting::IdleDimRefreshes => "Dim idle after",
+            Setting::TreeView => "Tree view",
+            Setting::ShowThreads => "Show threads",
+            Setting::ShowKernelThreads => "Show kernel threads",
+            Setting::Compact => "Compact rows",
+            Setting::CommandDisplay => "Command column",
+            Setting::CpuMeter => "CPU meters",
+            Setting::MemoryMeter => "Memory bar",
+            Setting::SwapMeter => "Swap bar",
+            Setting::DiskMeter => "Disk bars",
+            Setting::NetMeter => "Network bars",
+            Setting::GpuMeters => "GPU bars",
+            Setting::PressureMeters => "Pressure (PSI)",
+        }
+    }
+
+    /// Current value as shown on the setup screen.
+    pub fn value(&self, config: &Config) -> String {
+        let general = &config.general;
+        let meters = &config.meters;
+        match self {
+            Setting::RefreshRate => format!("{} ms", general.refresh_rate_ms),
+            Setting::MaxFps if general.max_fps == 0 => "unlimited".to_string(),
+            Setting::MaxFps => general.max_fps.to_string(),
+            Setting::IdleDimRefreshes if general.idle_dim_refreshes == 0 => "never".to_string(),
+            Setting::IdleDimRefreshes => format!("{} refreshes", general.idle_dim_refreshes),
+            Setting::TreeView => on_off(general.tree_view),
+            Setting::ShowThreads => on_off(general.show_threads),
+            Setting::ShowKernelThreads => on_off(general.show_kernel_threads),
+            Setting::Compact => on_off(general.compact),
+            Setting::CommandDisplay => config.columns.command.label().to_string(),
+            Setting::CpuMeter => meters.cpu.label().to_string(),
+            Setting::MemoryMeter => on_off(meters.memory),
+            Setting::SwapMeter => on_off(meters.swap),
+            Setting::DiskMeter => meters.disk.label().to_string(),
+            Setting::NetMeter => meters.net.label().to_string(),
+            Setting::GpuMeters => on_off(meters.gpu),
+            Setting::PressureMeters => on_off(meters.pressure),
+        }
+    }
+
+    /// Step a number up (`delta > 0`) or down, or flip/cycle any other value.
+    pub fn adjust(&self, config: &mut Config, delta: i32) {
+        let general = &mut config.general;
+        let meters = &mut config.meters;
+        let up = delta > 0;
+        match self {
+            Setting::RefreshRate if up => general.refresh_rate_ms += REFRESH_STEP_MS,
+            Setting::RefreshRate => {
+                general.refresh_rate_ms = general
+                    .refresh_rate_ms
+                    .saturating_sub(REFRESH_STEP_MS)
+                    .max(MIN_REFRESH_MS);
+            }
+            Setting::MaxFps if up => general.max_fps += FPS_STEP,
+            Setting::MaxFps => general.max_fps = general.max_fps.saturating_sub(FPS_STEP),
+            Setting::IdleDimRefreshes if up => general.idle_dim_refreshes += 1,
+            Setting::IdleDimRefreshes => {
+                general.idle_dim_refreshes = general.idle_dim_refreshes.saturating_sub(1);
+            }
+            Setting::TreeView => general.tree_view = !general.tree_view,
+            Setting::ShowThreads => general.show_threads = !general.show_threads,
+            Setting::ShowKernelThreads => {
+                general.show_kernel_threads = !general.show_kernel_threads;
+            }
+            Setting::Compact => general.compact = !general.compact,
+            Setting::CommandDisplay => config.columns.command = config.columns.command.next(),
+            Setting::CpuMeter => meters.cpu = meters.cpu.next(),
+            Setting::MemoryMeter => meters.memory = !meters.memory,
+            Setting::SwapMeter => meters.swap = !meters.swap,
+            Setting::DiskMeter => meters.disk = meters.disk.next(),
+            Setting::NetMeter => meters.net = meters.net.next(),
+            Setting::GpuMeters => meters.gpu = !meters.gpu,
+            Setting::PressureMeters => meters.pressure = !meters.pressure,
+        }
+    }
+
+    /// Whether left/right step this value (as opposed to toggling it).
+    fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Setting::RefreshRate | Setting::MaxFps | Setting::IdleDimRefreshes
+        )
+    }
+}
+
+fn on_off(on: bool) -> String {
+    if on { "on" } else { "off" }.to_string()
+}
+
+/// Setup screen (F2): general settings, process table columns, theme
+/// preset and header meters, one pane at a time.
+pub struct SetupScreen<'a> {
+    pub pane: SetupPane,
+    /// Every column with whether it is shown; shown ones come first, in order.
+    pub columns: &'a [(SortColumn, bool)],
+    pub selected: usize,
+    pub config: &'a Config,
+    pub theme: &'a Theme,
+}
+
+impl<'a> Widget for SetupScreen<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let dialog = centered_rect(70, 80, area);
+        Clear.render(dialog, buf);
+
+        let block = Block::default()
+            .title(" Setup ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.border))
+            .style(Style::default().bg(self.theme.bg));
+
+        let inner = block.inner(dialog);
+        block.render(dialog, buf);
+
+        let key_style = Style::default()
+            .fg(self.theme.status_key)
+            .add_modifier(Modifier::BOLD);
+        let text_style = Style::default().fg(self.theme.fg);
+        let dim_style = Style::default().fg(self.theme.border);
+        let selected_style = Style::default()
+            .fg(self.theme.selection_fg)
+            .bg(self.theme.selection_bg);
+
+        // Tab bar on the first row
+        let mut tabs = Vec::new();
+        for pane in SetupPane::ALL {
+            let style = if pane == self.pane {
+                selected_style.add_modifier(Modifier::BOLD)
+            } else {
+                dim_style
+            };
+            tabs.push(Span::styled(format!(" {} ", pane.label()), style));
+            tabs.push(Span::raw(" "));
+        }
+        Paragraph::new(vec![Line::from(tabs)]).render(
+            Rect {
+                height: 1.min(inner.height),
+                ..inner
+            },
+            buf,
+        );
+
+        // Reserve the tab bar, a gap below it, and 1 row for the footer
+        let content_height = inner.height.saturating_sub(3) as usize;
+        let content_area = Rect {
+            y: inner.y + 2,
+            height: content_height as u16,
+            ..inner
+        };
+
+        let rows: Vec<(String, Style)> = match self.pane {
+            SetupPane::Columns => {
+                let desc_width = (inner.width as usize).saturating_sub(16);
+                self.columns
+                    .iter()
+                    .map(|(col, on)| {
+                        let check = if *on { "[x]" } else { "[ ]" };
+                        let text = format!(
+                            " {check} {:<9} {}",
+                            col.label(),
+                            truncate_cols(col.description(), desc_width)
+                        );
+                        (text, if *on { text_style } else { dim_style })
+                    })
+                    .collect()
+            }
+            SetupPane::Theme => Theme::PRESETS
+                .iter()
+                .map(|name| {
+                    let on = *name == self.config.theme.preset;
+                    let mark = if on { "(*)" } else { "( )" };
+                    (format!(" {mark} {name}"), if on { text_style } else { dim_style })
+                })
+                .collect(),
+            SetupPane::General | SetupPane::Meters => self
+                .pane
+                .settings()
+                .iter()
+                .map(|s| {
+                    let text = format!(" {:<20} {}", s.label(), s.value(self.config));
+                    (text, text_style)
+                })
+                .collect(),
+        };
+
+        // Keep the selection in view
+        let scroll = self.selected.saturating_sub(content_height.saturating_sub(1));
+        let lines: Vec<Line> = rows
+            .into_iter()
+            .enumerate()
+            .skip(scroll)
+            .take(content_height)
+            .map(|(i, (text, style))| {
+                let style = if i == self.selected { selected_style } else { style };
+                Line::styled(text, style)
+            })
+            .collect();
+        Paragraph::new(lines).render(content_area, buf);
+
+        let mut footer = vec![
+            Span::styled("[Tab]", key_style),
+            Span::styled(" Pane  ", text_style),
+            Span::styled("[j/k]", key_style),
+            Span::styled(" Move  ", text_style),
+        ];
+        match self.pane {
+            SetupPane::Columns => footer.extend([
+                Span::styled("[Space]", key_style),
+                Span::styled(" Show/hide  ", text_style),
+                Span::styled("[J/K]", key_style),
+                Span::styled(" Reorder  ", text_style),
+            ]),
+            SetupPane::Theme => footer.extend([
+                Span::styled("[Space]", key_style),
+                Span::styled(" Select  ", text_style),
+            ]),
+            SetupPane::General | SetupPane::Meters => {
+                let numeric = self
+                    .pane
+                    .settings()
+                    .get(self.selected)
+                    .is_some_and(Setting::is_numeric);
+                if numeric {
+                    footer.extend([
+                        Span::styled("[←/→]", key_style),
+                        Span::styled(" Adjust  ", text_style),
+                    ]);
+                } else {
+                    footer.extend([
+                        Span::styled("[Space]", key_style),
+                        Span::styled(" Change  ", text_style),
+                    ]);
+                }
+            }
+        }
+        footer.extend([
+            Span::styled("[w]", key_style),
+            Span::styled(" Save  ", text_style),
+            Span::styled("[Esc]", key_style),
+            Span::styled(" Cancel", text_style),
+        ]);
+        let footer_area = Rect {
+            y: inner.y + inner.height.saturating_sub(1),
+            height: 1,
+            ..inner
+        };
+        Paragraph::new(vec![Line::from(footer)]).render(footer_area, buf);
+    }
+}
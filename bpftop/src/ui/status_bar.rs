@@ -4,13 +4,26 @@ use ratatui::style::Style;
 use ratatui::text::{Line, Span};
 use ratatui::widgets::Widget;
 
+use crate::ebpf::loader::Subsystem;
 use crate::theme::Theme;
 
 /// Renders the bottom status bar with F-key hints.
 pub struct StatusBarWidget<'a> {
     pub theme: &'a Theme,
     pub ebpf_loaded: bool,
+    /// Subsystems that failed to attach; tagged on the eBPF indicator.
+    pub ebpf_unavailable: &'a [Subsystem],
     pub flash: Option<&'a str>,
+    /// Latest OOM kill, shown until the OOM log is opened.
+    pub oom: Option<String>,
+    /// Applied filter expression, if any.
+    pub filter: Option<&'a str>,
+    /// PID the selection is pinned to.
+    pub follow: Option<u32>,
+    /// Viewers watching through `--share`, when sharing.
+    pub viewers: Option<usize>,
+    /// Replaces the key hints while set (e.g. the sort explanation in sort-select mode).
+    pub hint: Option<String>,
 }
 
 impl<'a> Widget for StatusBarWidget<'a> {
@@ -40,20 +53,64 @@ impl<'a> Widget for StatusBarWidget<'a> {
         ];
 
         let mut spans = Vec::new();
-        for (key, label) in &keys {
-            spans.push(Span::styled(*key, key_style));
-            spans.push(Span::styled(*label, label_style));
-            spans.push(Span::styled(" ", label_style));
+        if let Some(hint) = self.hint {
+            spans.push(Span::styled(hint, key_style));
+        } else {
+            for (key, label) in &keys {
+                spans.push(Span::styled(*key, key_style));
+                spans.push(Span::styled(*label, label_style));
+                spans.push(Span::styled(" ", label_style));
+            }
         }
 
-        // Add eBPF indicator
-        if self.ebpf_loaded {
+        if let Some(filter) = self.filter {
+            spans.push(Span::styled(" Filter:", key_style));
+            spans.push(Span::styled(format!(" {filter} "), label_style));
+        }
+        if let Some(pid) = self.follow {
+            spans.push(Span::styled(" Follow:", key_style));
+            spans.push(Span::styled(format!(" {pid} "), label_style));
+        }
+
+        // Add eBPF indicator, listing any subsystems that failed to attach
+        if self.ebpf_loaded && self.ebpf_unavailable.is_empty() {
             spans.push(Span::styled(
                 " [eBPF]",
                 Style::default()
                     .fg(self.theme.proc_running)
                     .bg(self.theme.status_bg),
             ));
+        } else if self.ebpf_loaded {
+            let missing: Vec<String> = self
+                .ebpf_unavailable
+                .iter()
+                .map(|s| format!("-{}", s.label()))
+                .collect();
+            spans.push(Span::styled(
+                format!(" [eBPF {}]", missing.join(" ")),
+                Style::default()
+                    .fg(self.theme.proc_stopped)
+                    .bg(self.theme.status_bg),
+            ));
+        }
+
+        if let Some(viewers) = self.viewers {
+            spans.push(Span::styled(
+                format!(" [shared: {viewers} watching]"),
+                Style::default()
+                    .fg(self.theme.status_key)
+                    .bg(self.theme.status_bg),
+            ));
+        }
+
+        if let Some(oom) = self.oom {
+            spans.push(Span::styled(
+                format!(" {oom} "),
+                Style::default()
+                    .fg(self.theme.status_bg)
+                    .bg(self.theme.proc_zombie)
+                    .add_modifier(ratatui::style::Modifier::BOLD),
+            ));
         }
 
         // Flash message (transient yank feedback etc.)
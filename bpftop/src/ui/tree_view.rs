@@ -5,8 +5,8 @@ use crate::data::process::ProcessInfo;
 /// A flattened tree entry with indentation info for rendering.
 #[derive(Debug, Clone)]
 pub struct TreeEntry {
-    pub pid: u32,
-    #[allow(dead_code)]
+    /// TID of the row; the PID for processes.
+    pub tid: u32,
     pub depth: u16,
     pub prefix: String, // e.g., "├─" or "└─"
     #[allow(dead_code)]
@@ -17,31 +17,45 @@ pub struct TreeEntry {
 
 /// Build a flat list of tree entries from the process list.
 /// The tree is rooted at init (pid 1) or the earliest ancestor.
+/// Threads hang off their process, ahead of its child processes.
 /// Collapsed PIDs have their children omitted from the output.
-pub fn build_tree(processes: &[ProcessInfo], collapsed: &HashSet<u32>) -> Vec<TreeEntry> {
-    let proc_map: HashMap<u32, &ProcessInfo> = processes.iter().map(|p| (p.pid, p)).collect();
+/// With `group_orphans`, reparented processes become roots of their own,
+/// listed after the rest, so they don't silently move under init.
+pub fn build_tree(
+    processes: &[ProcessInfo],
+    collapsed: &HashSet<u32>,
+    group_orphans: bool,
+) -> Vec<TreeEntry> {
+    let proc_map: HashMap<u32, &ProcessInfo> = processes.iter().map(|p| (p.tid, p)).collect();
+    let parent_of = |p: &ProcessInfo| {
+        if group_orphans && p.reparented() {
+            None
+        } else {
+            tree_parent(p)
+        }
+    };
 
     // Find root processes (ppid == 0 or ppid not in our process list)
     let mut roots: Vec<u32> = processes
         .iter()
-        .filter(|p| p.ppid == 0 || !proc_map.contains_key(&p.ppid))
-        .map(|p| p.pid)
+        .filter(|p| parent_of(p).is_none_or(|parent| !proc_map.contains_key(&parent)))
+        .map(|p| p.tid)
         .collect();
-    roots.sort();
+    roots.sort_by_key(|tid| (group_orphans && proc_map[tid].reparented(), *tid));
 
     // Build children map
     let mut children_map: HashMap<u32, Vec<u32>> = HashMap::new();
     for proc in processes {
-        if proc.ppid != 0 && proc.ppid != proc.pid {
+        if let Some(parent) = parent_of(proc) {
             children_map
-                .entry(proc.ppid)
+                .entry(parent)
                 .or_default()
-                .push(proc.pid);
+                .push(proc.tid);
         }
     }
-    // Sort children by PID
+    // Threads first, then child processes, each by ID
     for children in children_map.values_mut() {
-        children.sort();
+        children.sort_by_key(|tid| (!proc_map[tid].is_thread, *tid));
     }
 
     let mut entries = Vec::new();
@@ -61,6 +75,16 @@ pub fn build_tree(processes: &[ProcessInfo], collapsed: &HashSet<u32>) -> Vec<Tr
     entries
 }
 
+/// Row a process or thread is drawn under: a thread's process, or a
+/// process's parent. `None` at the top of the tree.
+fn tree_parent(proc: &ProcessInfo) -> Option<u32> {
+    if proc.is_thread {
+        Some(proc.pid)
+    } else {
+        (proc.ppid != 0 && proc.ppid != proc.pid).then_some(proc.ppid)
+    }
+}
+
 fn build_subtree(
     pid: u32,
     depth: u16,
@@ -84,7 +108,7 @@ fn build_subtree(
     let is_collapsed = collapsed.contains(&pid);
 
     entries.push(TreeEntry {
-        pid,
+        tid: pid,
         depth,
         prefix,
         is_last,
@@ -117,10 +141,10 @@ pub fn tree_ordered_processes(
     processes: &[ProcessInfo],
     tree: &[TreeEntry],
 ) -> Vec<ProcessInfo> {
-    let proc_map: HashMap<u32, &ProcessInfo> = processes.iter().map(|p| (p.pid, p)).collect();
+    let proc_map: HashMap<u32, &ProcessInfo> = processes.iter().map(|p| (p.tid, p)).collect();
     tree.iter()
         .filter_map(|entry| {
-            proc_map.get(&entry.pid).map(|p| {
+            proc_map.get(&entry.tid).map(|p| {
                 let mut proc = (*p).clone();
                 let indicator = if entry.has_children {
                     if entry.collapsed { "+" } else { "-" }
@@ -128,6 +152,10 @@ pub fn tree_ordered_processes(
                     " "
                 };
                 proc.tree_prefix = format!("{}{} ", entry.prefix, indicator);
+                // A top-level process that was reparented: say whose it was
+                if entry.depth == 0 && proc.reparented() {
+                    proc.tree_prefix += &format!("(orphan of {}) ", proc.orig_ppid);
+                }
                 proc
             })
         })
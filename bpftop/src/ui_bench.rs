@@ -0,0 +1,323 @@
+//! `bpftop bench-ui`: end-to-end frame latency of the TUI. Feeds synthetic
+//! snapshots through the real App (merge, filter/sort, render) into a
+//! ratatui TestBackend, so it needs neither eBPF nor a terminal. The
+//! collection side is covered by the `bench` binary.
+
+use std::time::Instant;
+
+use anyhow::Result;
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use serde::Serialize;
+
+use crate::app::App;
+use crate::config::Config;
+use crate::data::collector::Snapshot;
+use crate::data::process::{ProcessInfo, ProcessState};
+use crate::data::system::{CpuStats, SystemInfo};
+
+/// Share of processes replaced by new PIDs each frame, so merges take the
+/// full-rebuild path the way fork/exit churn makes them.
+const CHURN: f64 = 0.01;
+/// One process in this many is followed by a few thread rows.
+const THREADED_EVERY: u32 = 8;
+const THREADS_PER_PROCESS: u32 = 4;
+
+pub struct BenchOptions {
+    pub tasks: Vec<usize>,
+    pub iterations: usize,
+    pub warmup: usize,
+    pub width: u16,
+    pub height: u16,
+    pub tree: bool,
+    pub filter: Option<String>,
+    pub json: bool,
+}
+
+#[derive(Serialize)]
+struct SizeResult {
+    tasks: usize,
+    merge: Stats,
+    filter: Stats,
+    render: Stats,
+    total: Stats,
+}
+
+#[derive(Serialize, Default)]
+struct Stats {
+    mean_us: f64,
+    median_us: f64,
+    p95_us: f64,
+    max_us: f64,
+}
+
+pub fn run(config: Config, opts: &BenchOptions) -> Result<()> {
+    let mut results = Vec::new();
+    for &tasks in &opts.tasks {
+        eprintln!("{tasks} tasks: {} warmup + {} timed frames...", opts.warmup, opts.iterations);
+        results.push(bench_size(config.clone(), opts, tasks)?);
+    }
+
+    if opts.json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+        return Ok(());
+    }
+    println!(
+        "{:>7}  {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "TASKS", "MERGE", "FILTER", "RENDER", "TOTAL", "TOTAL p95"
+    );
+    for r in &results {
+        println!(
+            "{:>7}  {:>8.0}µs {:>8.0}µs {:>8.0}µs {:>8.0}µs {:>8.0}µs",
+            r.tasks, r.merge.mean_us, r.filter.mean_us, r.render.mean_us, r.total.mean_us, r.total.p95_us
+        );
+    }
+    Ok(())
+}
+
+fn bench_size(config: Config, opts: &BenchOptions, tasks: usize) -> Result<SizeResult> {
+    let mut app = App::headless(config);
+    app.tree_view = opts.tree;
+    if let Some(ref filter) = opts.filter {
+        app.active_filter = filter.clone();
+    }
+    let mut terminal = Terminal::new(TestBackend::new(opts.width, opts.height))?;
+    let mut world = SyntheticTasks::new(tasks);
+
+    let (mut merge, mut filter, mut render) = (Vec::new(), Vec::new(), Vec::new());
+    for i in 0..opts.warmup + opts.iterations {
+        let snapshot = world.next_snapshot();
+
+        let t0 = Instant::now();
+        app.merge_data(snapshot);
+        let t1 = Instant::now();
+        // What every filter keystroke or sort change costs
+        app.update_filtered_processes();
+        let t2 = Instant::now();
+        terminal.draw(|frame| app.draw(frame))?;
+        let t3 = Instant::now();
+
+        if i >= opts.warmup {
+            merge.push(micros(t1 - t0));
+            filter.push(micros(t2 - t1));
+            render.push(micros(t3 - t2));
+        }
+    }
+    let total: Vec<f64> = (0..merge.len()).map(|i| merge[i] + filter[i] + render[i]).collect();
+    Ok(SizeResult {
+        tasks,
+        merge: stats(&merge),
+        filter: stats(&filter),
+        render: stats(&render),
+        total: stats(&total),
+    })
+}
+
+fn micros(d: std::time::Duration) -> f64 {
+    d.as_secs_f64() * 1_000_000.0
+}
+
+fn stats(samples: &[f64]) -> Stats {
+    if samples.is_empty() {
+        return Stats::default();
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let at = |p: f64| sorted[((p / 100.0 * (sorted.len() - 1) as f64).round() as usize).min(sorted.len() - 1)];
+    Stats {
+        mean_us: sorted.iter().sum::<f64>() / sorted.len() as f64,
+        median_us: at(50.0),
+        p95_us: at(95.0),
+        max_us: sorted[sorted.len() - 1],
+    }
+}
+
+/// A made-up process table that changes a little every frame: CPU and
+/// memory move around and a few processes are replaced by new ones.
+struct SyntheticTasks {
+    rows: Vec<ProcessInfo>,
+    next_pid: u32,
+    rng: u64,
+    frame: u64,
+}
+
+impl SyntheticTasks {
+    fn new(tasks: usize) -> Self {
+        let mut world = Self {
+            rows: Vec::with_capacity(tasks),
+            next_pid: 2,
+            rng: 0x9e37_79b9_7f4a_7c15,
+            frame: 0,
+        };
+        while world.rows.len() < tasks {
+            world.spawn_process(tasks);
+        }
+        world
+    }
+
+    /// xorshift64; deterministic so runs compare.
+    fn rand(&mut self) -> u64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng
+    }
+
+    /// Add a process (and maybe threads), parented to an earlier one so
+    /// the tree view has depth.
+    fn spawn_process(&mut self, cap: usize) {
+        let pid = self.next_pid;
+        self.next_pid += 1;
+        let ppid = match self.rows.len() {
+            0 => 1,
+            n => {
+                let i = self.rand() as usize % n;
+                self.rows[i].pid
+            }
+        };
+        let name = COMMS[self.rand() as usize % COMMS.len()];
+        let mut proc = synthetic_process(pid, ppid, name);
+        proc.user = USERS[pid as usize % USERS.len()].to_string();
+        self.rows.push(proc.clone());
+        if pid.is_multiple_of(THREADED_EVERY) {
+            for _ in 0..THREADS_PER_PROCESS {
+                if self.rows.len() >= cap {
+                    break;
+                }
+                let tid = self.next_pid;
+                self.next_pid += 1;
+                self.rows.push(ProcessInfo {
+                    tid,
+                    is_thread: true,
+                    ..proc.clone()
+                });
+            }
+        }
+    }
+
+    fn next_snapshot(&mut self) -> Snapshot {
+        self.frame += 1;
+        let target = self.rows.len();
+        // Replace a few processes (with their threads) by fresh ones
+        let churn = ((target as f64 * CHURN) as usize).max(1);
+        for _ in 0..churn {
+            let i = self.rand() as usize % self.rows.len();
+            let victim = self.rows[i].pid;
+            self.rows.retain(|p| p.pid != victim);
+        }
+        while self.rows.len() < target {
+            self.spawn_process(target);
+        }
+
+        for i in 0..self.rows.len() {
+            let r = self.rand();
+            let p = &mut self.rows[i];
+            // Most tasks idle, a few busy, like a real box
+            p.cpu_percent = if r.is_multiple_of(20) { (r >> 8) as f64 % 400.0 / 4.0 } else { 0.0 };
+            p.cpu_time_secs += p.cpu_percent / 100.0;
+            p.res_bytes = (p.res_bytes as i64 + ((r >> 16) % 8192) as i64 - 4096).max(4096) as u64;
+            p.mem_percent = p.res_bytes as f64 / SYNTHETIC_MEM as f64 * 100.0;
+            p.state = if p.cpu_percent > 0.0 { ProcessState::Running } else { ProcessState::Sleeping };
+        }
+
+        let cpu = CpuStats {
+            user_pct: 20.0,
+            system_pct: 5.0,
+            idle_pct: 75.0,
+            total_pct: 25.0,
+            ..CpuStats::default()
+        };
+        let mut sys = SystemInfo {
+            cpu_total: cpu.clone(),
+            cpus: vec![cpu; 16],
+            total_tasks: self.rows.len() as u32,
+            uptime_secs: self.frame as f64,
+            ..SystemInfo::default()
+        };
+        sys.memory.total = SYNTHETIC_MEM;
+        Snapshot {
+            sys,
+            processes: self.rows.clone(),
+            inspection: None,
+            alerts: Vec::new(),
+            execs: Vec::new(),
+            exits: Vec::new(),
+            ooms: Vec::new(),
+            bpf_stats: None,
+            bpf_objects: None,
+        }
+    }
+}
+
+const SYNTHETIC_MEM: u64 = 64 * 1024 * 1024 * 1024;
+const COMMS: &[&str] = &["bash", "postgres", "nginx", "java", "python3", "node", "sshd", "cc1", "rustc", "redis-server"];
+const USERS: &[&str] = &["root", "www-data", "postgres", "alice", "bob"];
+
+pub(crate) fn synthetic_process(pid: u32, ppid: u32, comm: &str) -> ProcessInfo {
+    ProcessInfo {
+        pid,
+        ppid,
+        orig_ppid: ppid,
+        pgid: pid,
+        sid: 1,
+        tty: None,
+        uid: 1000,
+        user: String::new(),
+        state: ProcessState::Sleeping,
+        priority: 20,
+        nice: 0,
+        virt_bytes: 256 * 1024 * 1024,
+        res_bytes: 16 * 1024 * 1024,
+        shr_bytes: 4 * 1024 * 1024,
+        cpu_percent: 0.0,
+        mem_percent: 0.0,
+        rss_growth: 0.0,
+        gpu_percent: 0.0,
+        watts: 0.0,
+        gpu_mem_bytes: 0,
+        net_rx_bytes: 0,
+        net_tx_bytes: 0,
+        net_rate: 0.0,
+        net_tx_rate: 0.0,
+        net_rx_rate: 0.0,
+        net_ifname: String::new(),
+        disk_read_bytes: 0,
+        disk_write_bytes: 0,
+        disk_read_rate: 0.0,
+        disk_write_rate: 0.0,
+        sock_drops: 0,
+        sock_drop_rate: 0.0,
+        fd_count: 16,
+        nvcsw: 0,
+        nivcsw: 0,
+        csw_rate: 0.0,
+        icsw_rate: 0.0,
+        runq_wait_ns: 0,
+        runq_waits: 0,
+        runq_latency_ns: 0.0,
+        cpu_time_secs: 0.0,
+        start_time_ns: u64::from(pid) * 1_000_000,
+        comm: comm.to_string(),
+        cmdline: format!("/usr/bin/{comm} --worker {pid}"),
+        container: None,
+        container_id: None,
+        container_image: None,
+        perf: None,
+        pod: None,
+        pod_namespace: None,
+        service: None,
+        badge: None,
+        cgroup_path: format!("/system.slice/{comm}.service"),
+        children: Vec::new(),
+        prev_cpu_ns: 0,
+        is_kernel_thread: false,
+        is_thread: false,
+        tid: pid,
+        tagged: false,
+        frozen: false,
+        thaw_in_secs: None,
+        tree_prefix: String::new(),
+        exited: None,
+        idle: false,
+    }
+}
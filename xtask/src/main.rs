@@ -1,4 +1,6 @@
-use std::path::PathBuf;
+mod offsets;
+
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use anyhow::{bail, Context, Result};
@@ -30,8 +32,32 @@ enum Cli {
         #[clap(last = true)]
         run_args: Vec<String>,
     },
+    /// Regenerate bpftop-ebpf's struct offsets for the running kernel from BTF
+    GenOffsets {
+        /// Kernel version to write offsets for (default: the running kernel, e.g. "6_18")
+        #[clap(long)]
+        kernel: Option<String>,
+        /// Raw BTF to read struct layouts from
+        #[clap(long, default_value = "/sys/kernel/btf/vmlinux")]
+        btf: PathBuf,
+    },
+    /// Build a release binary plus shell completions and the man page
+    /// into target/dist
+    Dist {
+        /// Kernel version for struct offsets (e.g. "6_12", "6_18")
+        #[clap(long, default_value = "6_18")]
+        kernel: String,
+    },
 }
 
+/// Completion scripts written by `dist`, under the names each shell's
+/// completion directory expects.
+const COMPLETIONS: &[(&str, &str)] = &[
+    ("bash", "bpftop.bash"),
+    ("zsh", "_bpftop"),
+    ("fish", "bpftop.fish"),
+];
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
@@ -41,6 +67,25 @@ fn main() -> Result<()> {
             build_ebpf("bpfel-unknown-none", release, &kernel)?;
             run(release, &run_args)
         }
+        Cli::GenOffsets { kernel, btf } => {
+            let kernel = match kernel {
+                Some(k) => k,
+                None => offsets::running_kernel()?,
+            };
+            offsets::gen_offsets(&workspace_root(), &btf, arch()?, &kernel)
+        }
+        Cli::Dist { kernel } => {
+            build_ebpf("bpfel-unknown-none", true, &kernel)?;
+            dist()
+        }
+    }
+}
+
+/// Host architecture as spelled in bpftop-ebpf's `arch-*` features.
+fn arch() -> Result<&'static str> {
+    match std::env::consts::ARCH {
+        arch @ ("x86_64" | "aarch64") => Ok(arch),
+        other => bail!("unsupported architecture: {other}"),
     }
 }
 
@@ -48,14 +93,7 @@ fn build_ebpf(target: &str, _release: bool, kernel: &str) -> Result<()> {
     let workspace_root = workspace_root();
     let ebpf_dir = workspace_root.join("bpftop-ebpf");
 
-    let arch_feature = match std::env::consts::ARCH {
-        "x86_64" => "arch-x86_64",
-        "aarch64" => "arch-aarch64",
-        other => bail!("unsupported architecture: {other}"),
-    };
-
-    let kernel_feature = format!("kernel-{kernel}");
-    let features = format!("{arch_feature},{kernel_feature}");
+    let features = format!("arch-{},kernel-{kernel}", arch()?);
 
     // eBPF programs MUST be built in release mode because debug builds
     // include core::fmt code that exceeds BPF's function argument limit.
@@ -89,11 +127,9 @@ fn build_ebpf(target: &str, _release: bool, kernel: &str) -> Result<()> {
     Ok(())
 }
 
-fn run(release: bool, run_args: &[String]) -> Result<()> {
-    let workspace_root = workspace_root();
-
+fn build_userspace(workspace_root: &Path, release: bool) -> Result<()> {
     let mut cmd = Command::new("cargo");
-    cmd.current_dir(&workspace_root)
+    cmd.current_dir(workspace_root)
         .args(["build", "--package", "bpftop"]);
 
     if release {
@@ -104,6 +140,12 @@ fn run(release: bool, run_args: &[String]) -> Result<()> {
     if !status.success() {
         bail!("userspace build failed with status: {}", status);
     }
+    Ok(())
+}
+
+fn run(release: bool, run_args: &[String]) -> Result<()> {
+    let workspace_root = workspace_root();
+    build_userspace(&workspace_root, release)?;
 
     let profile = if release { "release" } else { "debug" };
     let bin = workspace_root
@@ -123,6 +165,39 @@ fn run(release: bool, run_args: &[String]) -> Result<()> {
     Ok(())
 }
 
+fn dist() -> Result<()> {
+    let workspace_root = workspace_root();
+    build_userspace(&workspace_root, true)?;
+
+    let bin = workspace_root.join("target/release/bpftop");
+    let out = workspace_root.join("target/dist");
+    std::fs::create_dir_all(out.join("completions"))?;
+    std::fs::create_dir_all(out.join("man"))?;
+    std::fs::copy(&bin, out.join("bpftop")).context("copying bpftop binary")?;
+
+    for (shell, name) in COMPLETIONS {
+        let script = generate(&bin, &["completions", shell])?;
+        std::fs::write(out.join("completions").join(name), script)?;
+    }
+    let man = generate(&bin, &["man"])?;
+    std::fs::write(out.join("man/bpftop.1"), man)?;
+
+    println!("wrote {}", out.display());
+    Ok(())
+}
+
+/// Run the built binary (unprivileged) and return its stdout.
+fn generate(bin: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let output = Command::new(bin)
+        .args(args)
+        .output()
+        .with_context(|| format!("failed to run bpftop {}", args.join(" ")))?;
+    if !output.status.success() {
+        bail!("bpftop {} failed with status: {}", args.join(" "), output.status);
+    }
+    Ok(output.stdout)
+}
+
 fn workspace_root() -> PathBuf {
     let output = Command::new("cargo")
         .args(["metadata", "--format-version=1", "--no-deps"])
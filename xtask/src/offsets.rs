@@ -0,0 +1,160 @@
+//! `cargo xtask gen-offsets`: regenerate the per-kernel `mod offsets` block
+//! in bpftop-ebpf from the running kernel's BTF.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use bpftop_common::btf::Btf;
+
+/// Constant name, struct and member path for every kernel-dependent offset
+/// in `mod offsets`, in emission order.
+const OFFSETS: &[(&str, &str, &str)] = &[
+    ("TASK_MM", "task_struct", "mm"),
+    ("TASK_PID", "task_struct", "pid"),
+    ("TASK_TGID", "task_struct", "tgid"),
+    ("TASK_REAL_PARENT", "task_struct", "real_parent"),
+    ("TASK_UTIME", "task_struct", "utime"),
+    ("TASK_STIME", "task_struct", "stime"),
+    ("TASK_NVCSW", "task_struct", "nvcsw"),
+    ("TASK_NIVCSW", "task_struct", "nivcsw"),
+    ("TASK_START_TIME", "task_struct", "start_time"),
+    ("TASK_CRED", "task_struct", "cred"),
+    ("TASK_COMM", "task_struct", "comm"),
+    ("TASK_CGROUPS", "task_struct", "cgroups"),
+    ("TASK_SIGNAL", "task_struct", "signal"),
+    ("MM_TOTAL_VM", "mm_struct", "total_vm"),
+    ("MM_ARG_START", "mm_struct", "arg_start"),
+    ("MM_ARG_END", "mm_struct", "arg_end"),
+    ("MM_RSS_FILE_COUNT", "mm_struct", "rss_stat[0].count"),
+    ("MM_RSS_ANON_COUNT", "mm_struct", "rss_stat[1].count"),
+    ("MM_RSS_SHMEM_COUNT", "mm_struct", "rss_stat[3].count"),
+    ("CSS_SET_DFL_CGRP", "css_set", "dfl_cgrp"),
+    ("SOCK_DST_CACHE", "sock", "sk_dst_cache"),
+    ("SIGNAL_PIDS", "signal_struct", "pids"),
+    ("PID_NUMBERS_NR", "pid", "numbers[0].nr"),
+    ("NET_NS_INUM", "net", "ns.inum"),
+    ("TCP_SND_CWND", "tcp_sock", "snd_cwnd"),
+    ("TCP_SRTT_US", "tcp_sock", "srtt_us"),
+];
+
+/// Relative to the workspace root.
+const EBPF_MAIN: &str = "bpftop-ebpf/src/main.rs";
+const EBPF_MANIFEST: &str = "bpftop-ebpf/Cargo.toml";
+
+/// `6.18.4-arch1` -> `6_18`.
+pub fn running_kernel() -> Result<String> {
+    let release = fs::read_to_string("/proc/sys/kernel/osrelease")
+        .context("reading /proc/sys/kernel/osrelease")?;
+    let mut parts = release.trim().split(['.', '-']);
+    match (parts.next(), parts.next()) {
+        (Some(major), Some(minor)) if !major.is_empty() && !minor.is_empty() => {
+            Ok(format!("{major}_{minor}"))
+        }
+        _ => bail!("unrecognised kernel release {release:?}"),
+    }
+}
+
+pub fn gen_offsets(workspace_root: &Path, btf_path: &Path, arch: &str, kernel: &str) -> Result<()> {
+    let data = fs::read(btf_path).with_context(|| format!("reading {}", btf_path.display()))?;
+    let btf = Btf::parse(&data).with_context(|| format!("parsing {}", btf_path.display()))?;
+    let block = render_block(&btf, arch, kernel)?;
+
+    let main_path = workspace_root.join(EBPF_MAIN);
+    let source = fs::read_to_string(&main_path)
+        .with_context(|| format!("reading {}", main_path.display()))?;
+    let cfg = format!("#[cfg(all(feature = \"arch-{arch}\", feature = \"kernel-{kernel}\"))]");
+    let known_kernel = source.contains(&format!("feature = \"kernel-{kernel}\""));
+    let mut updated = replace_block(&source, &cfg, &block)?;
+    if !known_kernel {
+        updated = add_kernel_to_guard(&updated, kernel)?;
+        add_kernel_feature(&workspace_root.join(EBPF_MANIFEST), kernel)?;
+        println!("added feature kernel-{kernel} to {EBPF_MANIFEST}");
+    }
+
+    if updated == source {
+        println!("{EBPF_MAIN}: arch-{arch}/kernel-{kernel} offsets already up to date");
+    } else {
+        fs::write(&main_path, updated)
+            .with_context(|| format!("writing {}", main_path.display()))?;
+        println!("{EBPF_MAIN}: regenerated arch-{arch}/kernel-{kernel} offsets");
+    }
+    Ok(())
+}
+
+fn render_block(btf: &Btf, arch: &str, kernel: &str) -> Result<String> {
+    let version = kernel.replace('_', ".");
+    let mut out = format!(
+        "#[cfg(all(feature = \"arch-{arch}\", feature = \"kernel-{kernel}\"))]\nmod offsets {{\n"
+    );
+    let mut current = "";
+    for &(name, strukt, path) in OFFSETS {
+        if strukt != current {
+            current = strukt;
+            match strukt {
+                "task_struct" => out += &format!("    // task_struct ({arch}, Linux {version})\n"),
+                "tcp_sock" => out += "    // tcp_sock embeds struct sock, so these are relative to the sock pointer\n",
+                _ => out += &format!("    // {strukt}\n"),
+            }
+        }
+        let id = btf
+            .struct_id(strukt)
+            .with_context(|| format!("struct {strukt} not found in BTF"))?;
+        let offset = btf
+            .offset_of(id, path)
+            .with_context(|| format!("resolving {strukt}.{path}"))?;
+        let line = format!("    pub const {name}: usize = {offset};");
+        if path.contains(['.', '[']) {
+            out += &format!("{line:<44} // {strukt}.{path}\n");
+        } else {
+            out += &format!("{line}\n");
+        }
+    }
+    out += "}\n";
+    Ok(out)
+}
+
+/// Swap the existing block under `cfg` for `block`, or insert it ahead of
+/// `use offsets::*;` when this arch/kernel pair is new.
+fn replace_block(source: &str, cfg: &str, block: &str) -> Result<String> {
+    if let Some(start) = source.find(cfg) {
+        let end = source[start..]
+            .find("\n}\n")
+            .map(|i| start + i + "\n}\n".len())
+            .context("unterminated mod offsets block")?;
+        return Ok(format!("{}{block}{}", &source[..start], &source[end..]));
+    }
+    let anchor = source
+        .find("use offsets::*;")
+        .context("`use offsets::*;` not found")?;
+    Ok(format!("{}{block}\n{}", &source[..anchor], &source[anchor..]))
+}
+
+/// Add `kernel-{kernel}` to the "no kernel feature enabled" compile_error.
+fn add_kernel_to_guard(source: &str, kernel: &str) -> Result<String> {
+    let guard = "#[cfg(not(any(feature = \"kernel-";
+    let start = source.find(guard).context("kernel feature guard not found")?;
+    let cfg_end = start + source[start..].find(")))]").context("malformed kernel guard")?;
+    let msg_end = cfg_end + source[cfg_end..].find("\");").context("malformed kernel guard")?;
+    Ok(format!(
+        "{}, feature = \"kernel-{kernel}\"{}, kernel-{kernel}{}",
+        &source[..cfg_end],
+        &source[cfg_end..msg_end],
+        &source[msg_end..]
+    ))
+}
+
+fn add_kernel_feature(manifest: &Path, kernel: &str) -> Result<()> {
+    let text = fs::read_to_string(manifest)
+        .with_context(|| format!("reading {}", manifest.display()))?;
+    let mut lines: Vec<&str> = text.lines().collect();
+    let last = lines
+        .iter()
+        .rposition(|l| l.starts_with("kernel-"))
+        .context("no kernel-* features in bpftop-ebpf/Cargo.toml")?;
+    let feature = format!("kernel-{kernel} = []");
+    lines.insert(last + 1, &feature);
+    fs::write(manifest, lines.join("\n") + "\n")
+        .with_context(|| format!("writing {}", manifest.display()))
+}